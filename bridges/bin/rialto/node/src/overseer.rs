@@ -201,7 +201,7 @@ where
 		))
 		.candidate_backing(CandidateBackingSubsystem::new(
 			spawner.clone(),
-			keystore.clone(),
+			(keystore.clone(), Default::default()),
 			Metrics::register(registry)?,
 		))
 		.candidate_validation(CandidateValidationSubsystem::with_config(
@@ -109,12 +109,79 @@ pub struct RunCmd {
 	#[structopt(long)]
 	pub beefy: bool,
 
+	/// Limit the outbound notifications on the validation peer set to the given bytes per second.
+	///
+	/// Once saturated, approval distribution messages and view updates are prioritized and other
+	/// notifications are queued. Must not be zero, unlimited by default.
+	#[structopt(long, parse(try_from_str = parse_rate_limit))]
+	pub validation_rate_limit: Option<u64>,
+
+	/// Limit the outbound notifications on the collation peer set to the given bytes per second.
+	///
+	/// Once saturated, declarations, `CollationSeconded` messages and view updates are
+	/// prioritized and advertisements are queued. Must not be zero, unlimited by default.
+	#[structopt(long, parse(try_from_str = parse_rate_limit))]
+	pub collation_rate_limit: Option<u64>,
+
+	/// Arrange the gossip topology so that row neighbors are likely in the same network.
+	///
+	/// Experimental and hidden. Our own network is derived from the `--public-addr` addresses, and
+	/// other authorities may arrive at a different topology than us, which breaks the symmetry of
+	/// the grid. Not meant to be used on production networks.
+	#[structopt(long = "experimental-latency-aware-gossip-topology", hidden = true)]
+	pub latency_aware_gossip_topology: bool,
+
 	/// Add the destination address to the jaeger agent.
 	///
 	/// Must be valid socket address, of format `IP:Port`
 	/// commonly `127.0.0.1:6831`.
 	#[structopt(long)]
 	pub jaeger_agent: Option<std::net::SocketAddr>,
+
+	/// Send the jaeger spans to an OpenTelemetry collector instead,
+	/// via OTLP over HTTP with JSON encoding.
+	///
+	/// Must be valid socket address, of format `IP:Port`
+	/// commonly `127.0.0.1:4318`.
+	#[structopt(long, conflicts_with_all = &["jaeger-agent", "jaeger-spans-file"])]
+	pub jaeger_otlp_collector: Option<std::net::SocketAddr>,
+
+	/// Append the jaeger spans to the given file instead, in the OTLP JSON encoding.
+	#[structopt(long, parse(from_os_str), conflicts_with = "jaeger-agent")]
+	pub jaeger_spans_file: Option<std::path::PathBuf>,
+
+	/// Only send the spans of the given fraction of candidates at a stage
+	/// of the inclusion pipeline, e.g. `approval-checking=0.1`.
+	///
+	/// May be given once per stage, not supported with `--jaeger-agent`.
+	#[structopt(long, parse(try_from_str = parse_stage_sampling))]
+	pub jaeger_sample_stage: Vec<(String, f64)>,
+
+	/// Record all messages and signals passed between subsystems to the given file.
+	///
+	/// Meant for debugging only, the recording grows quickly and may be replayed
+	/// into a single subsystem with the subsystem test helpers.
+	#[structopt(long, parse(from_os_str))]
+	pub overseer_recording: Option<std::path::PathBuf>,
+}
+
+/// Parse `stage=ratio`, the stage is checked once the jaeger configuration is built.
+fn parse_stage_sampling(s: &str) -> Result<(String, f64), String> {
+	let (stage, ratio) =
+		s.split_once('=').ok_or_else(|| format!("Expected `stage=ratio`, got `{}`", s))?;
+	let ratio = ratio.parse::<f64>().map_err(|e| format!("Invalid ratio `{}`: {}", ratio, e))?;
+	if !(0.0..=1.0).contains(&ratio) {
+		return Err(format!("Ratio `{}` is not between 0 and 1", ratio))
+	}
+	Ok((stage.to_owned(), ratio))
+}
+
+/// Parse a rate limit in bytes per second, a zero rate would never let a message through.
+fn parse_rate_limit(s: &str) -> Result<u64, String> {
+	match s.parse::<u64>().map_err(|e| format!("Invalid rate limit `{}`: {}", s, e))? {
+		0 => Err("The rate limit must not be zero".to_owned()),
+		rate => Ok(rate),
+	}
 }
 
 #[allow(missing_docs)]
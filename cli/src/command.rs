@@ -237,6 +237,25 @@ pub fn run_node(run: Cli, overseer_gen: impl service::OverseerGen) -> Result<()>
 	run_node_inner(run, overseer_gen, |_logger_builder, _config| {})
 }
 
+/// The jaeger configuration given on the command line, if any exporter was given.
+fn jaeger_config(run: &crate::cli::RunCmd) -> Result<Option<service::jaeger::JaegerConfigBuilder>> {
+	use service::jaeger::{JaegerConfig, JaegerExporter, Stage};
+
+	let exporter = match (run.jaeger_agent, run.jaeger_otlp_collector, &run.jaeger_spans_file) {
+		(Some(agent), _, _) => JaegerExporter::Agent(agent),
+		(None, Some(collector), _) => JaegerExporter::Otlp(collector),
+		(None, None, Some(path)) => JaegerExporter::File(path.clone()),
+		(None, None, None) => return Ok(None),
+	};
+
+	run.jaeger_sample_stage
+		.iter()
+		.try_fold(JaegerConfig::builder().exporter(exporter), |builder, (stage, ratio)| {
+			Ok::<_, Error>(builder.sample_stage(stage.parse::<Stage>()?, *ratio))
+		})
+		.map(Some)
+}
+
 fn run_node_inner<F>(
 	cli: Cli,
 	overseer_gen: impl service::OverseerGen,
@@ -266,7 +285,7 @@ where
 		info!("----------------------------");
 	}
 
-	let jaeger_agent = cli.run.jaeger_agent;
+	let jaeger_config = jaeger_config(&cli.run)?;
 
 	runner.run_node_until_exit(move |config| async move {
 		let role = config.role.clone();
@@ -278,7 +297,12 @@ where
 				service::IsCollator::No,
 				grandpa_pause,
 				cli.run.beefy,
-				jaeger_agent,
+				service::NetworkBridgeConfig {
+					validation_rate_limit: cli.run.validation_rate_limit,
+					collation_rate_limit: cli.run.collation_rate_limit,
+				},
+				cli.run.latency_aware_gossip_topology,
+				jaeger_config,
 				None,
 				overseer_gen,
 			)
@@ -293,7 +317,15 @@ pub fn run() -> Result<()> {
 	let cli: Cli = Cli::from_args();
 
 	match &cli.subcommand {
-		None => run_node_inner(cli, service::RealOverseerGen, polkadot_node_metrics::logger_hook()),
+		None => match cli.run.overseer_recording.clone() {
+			Some(path) => run_node_inner(
+				cli,
+				service::RecordingOverseerGen(path),
+				polkadot_node_metrics::logger_hook(),
+			),
+			None =>
+				run_node_inner(cli, service::RealOverseerGen, polkadot_node_metrics::logger_hook()),
+		},
 		Some(Subcommand::BuildSpec(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			Ok(runner.sync_run(|config| cmd.run(config.chain_spec, config.network))?)
@@ -22,7 +22,7 @@ use futures::{channel::mpsc, future::FutureExt, join, select, sink::SinkExt, str
 use parity_scale_codec::Encode;
 use polkadot_node_primitives::{AvailableData, CollationGenerationConfig, PoV};
 use polkadot_node_subsystem::{
-	messages::{AllMessages, CollationGenerationMessage, CollatorProtocolMessage},
+	messages::{CollationGenerationMessage, CollatorProtocolMessage, RuntimeApiMessage},
 	overseer, ActiveLeavesUpdate, FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext,
 	SubsystemError, SubsystemResult, SubsystemSender,
 };
@@ -37,7 +37,7 @@ use polkadot_primitives::v1::{
 	ValidationCodeHash,
 };
 use sp_core::crypto::Pair;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 mod error;
 
@@ -48,14 +48,15 @@ const LOG_TARGET: &'static str = "parachain::collation-generation";
 
 /// Collation Generation Subsystem
 pub struct CollationGenerationSubsystem {
-	config: Option<Arc<CollationGenerationConfig>>,
+	/// The configurations of the paras we collate for, one per `Initialize` message.
+	configs: HashMap<ParaId, Arc<CollationGenerationConfig>>,
 	metrics: Metrics,
 }
 
 impl CollationGenerationSubsystem {
 	/// Create a new instance of the `CollationGenerationSubsystem`.
 	pub fn new(metrics: Metrics) -> Self {
-		Self { config: None, metrics }
+		Self { configs: HashMap::new(), metrics }
 	}
 
 	/// Run this subsystem
@@ -106,7 +107,7 @@ impl CollationGenerationSubsystem {
 		&mut self,
 		incoming: SubsystemResult<FromOverseer<<Context as SubsystemContext>::Message>>,
 		ctx: &mut Context,
-		sender: &mpsc::Sender<AllMessages>,
+		sender: &mpsc::Sender<CollatorProtocolMessage>,
 	) -> bool
 	where
 		Context: SubsystemContext<Message = CollationGenerationMessage>,
@@ -118,10 +119,10 @@ impl CollationGenerationSubsystem {
 				..
 			}))) => {
 				// follow the procedure from the guide
-				if let Some(config) = &self.config {
+				if !self.configs.is_empty() {
 					let metrics = self.metrics.clone();
 					if let Err(err) = handle_new_activations(
-						config.clone(),
+						&self.configs,
 						activated.into_iter().map(|v| v.hash),
 						ctx,
 						metrics,
@@ -139,10 +140,14 @@ impl CollationGenerationSubsystem {
 			Ok(FromOverseer::Communication {
 				msg: CollationGenerationMessage::Initialize(config),
 			}) => {
-				if self.config.is_some() {
-					tracing::error!(target: LOG_TARGET, "double initialization");
+				if self.configs.contains_key(&config.para_id) {
+					tracing::error!(
+						target: LOG_TARGET,
+						para_id = %config.para_id,
+						"double initialization",
+					);
 				} else {
-					self.config = Some(Arc::new(config));
+					self.configs.insert(config.para_id, Arc::new(config));
 				}
 				false
 			},
@@ -176,13 +181,17 @@ where
 	}
 }
 
-async fn handle_new_activations<Context: SubsystemContext>(
-	config: Arc<CollationGenerationConfig>,
+async fn handle_new_activations<Context>(
+	configs: &HashMap<ParaId, Arc<CollationGenerationConfig>>,
 	activated: impl IntoIterator<Item = Hash>,
 	ctx: &mut Context,
 	metrics: Metrics,
-	sender: &mpsc::Sender<AllMessages>,
-) -> crate::error::Result<()> {
+	sender: &mpsc::Sender<CollatorProtocolMessage>,
+) -> crate::error::Result<()>
+where
+	Context: SubsystemContext<Message = CollationGenerationMessage>,
+	Context: overseer::SubsystemContext<Message = CollationGenerationMessage>,
+{
 	// follow the procedure from the guide:
 	// https://w3f.github.io/parachain-implementers-guide/node/collators/collation-generation.html
 
@@ -225,17 +234,19 @@ async fn handle_new_activations<Context: SubsystemContext>(
 				},
 			};
 
-			if scheduled_core.para_id != config.para_id {
-				tracing::trace!(
-					target: LOG_TARGET,
-					core_idx = %core_idx,
-					relay_parent = ?relay_parent,
-					our_para = %config.para_id,
-					their_para = %scheduled_core.para_id,
-					"core is not assigned to our para. Keep going.",
-				);
-				continue
-			}
+			let config = match configs.get(&scheduled_core.para_id) {
+				Some(config) => config,
+				None => {
+					tracing::trace!(
+						target: LOG_TARGET,
+						core_idx = %core_idx,
+						relay_parent = ?relay_parent,
+						their_para = %scheduled_core.para_id,
+						"core is not assigned to any of our paras. Keep going.",
+					);
+					continue
+				},
+			};
 
 			// we get validation data and validation code synchronously for each core instead of
 			// within the subtask loop, because we have only a single mutable handle to the
@@ -393,9 +404,7 @@ async fn handle_new_activations<Context: SubsystemContext>(
 					metrics.on_collation_generated();
 
 					if let Err(err) = task_sender
-						.send(AllMessages::CollatorProtocol(
-							CollatorProtocolMessage::DistributeCollation(ccr, pov, result_sender),
-						))
+						.send(CollatorProtocolMessage::DistributeCollation(ccr, pov, result_sender))
 						.await
 					{
 						tracing::warn!(
@@ -413,11 +422,11 @@ async fn handle_new_activations<Context: SubsystemContext>(
 	Ok(())
 }
 
-async fn obtain_current_validation_code_hash(
+async fn obtain_current_validation_code_hash<Outgoing: From<RuntimeApiMessage>>(
 	relay_parent: Hash,
 	para_id: ParaId,
 	assumption: OccupiedCoreAssumption,
-	sender: &mut impl SubsystemSender,
+	sender: &mut impl SubsystemSender<Outgoing>,
 ) -> Result<Option<ValidationCodeHash>, crate::error::Error> {
 	use polkadot_node_subsystem::RuntimeApiError;
 
@@ -89,6 +89,12 @@ mod handle_new_activations {
 		})
 	}
 
+	fn test_configs(
+		configs: impl IntoIterator<Item = Arc<CollationGenerationConfig>>,
+	) -> HashMap<ParaId, Arc<CollationGenerationConfig>> {
+		configs.into_iter().map(|config| (config.para_id, config)).collect()
+	}
+
 	fn scheduled_core_for<Id: Into<ParaId>>(para_id: Id) -> ScheduledCore {
 		ScheduledCore { para_id: para_id.into(), collator: None }
 	}
@@ -122,7 +128,7 @@ mod handle_new_activations {
 		let subsystem_activated_hashes = activated_hashes.clone();
 		subsystem_test_harness(overseer, |mut ctx| async move {
 			handle_new_activations(
-				test_config(123u32),
+				&test_configs(vec![test_config(123u32)]),
 				subsystem_activated_hashes,
 				&mut ctx,
 				Metrics(None),
@@ -199,9 +205,15 @@ mod handle_new_activations {
 		let (tx, _rx) = mpsc::channel(0);
 
 		subsystem_test_harness(overseer, |mut ctx| async move {
-			handle_new_activations(test_config(16), activated_hashes, &mut ctx, Metrics(None), &tx)
-				.await
-				.unwrap();
+			handle_new_activations(
+				&test_configs(vec![test_config(16)]),
+				activated_hashes,
+				&mut ctx,
+				Metrics(None),
+				&tx,
+			)
+			.await
+			.unwrap();
 		});
 
 		let requested_validation_data = Arc::try_unwrap(requested_validation_data)
@@ -287,7 +299,7 @@ mod handle_new_activations {
 		let subsystem_sent_messages = sent_messages.clone();
 		subsystem_test_harness(overseer, |mut ctx| async move {
 			handle_new_activations(
-				subsystem_config,
+				&test_configs(vec![subsystem_config]),
 				activated_hashes,
 				&mut ctx,
 				Metrics(None),
@@ -334,11 +346,11 @@ mod handle_new_activations {
 
 		assert_eq!(sent_messages.len(), 1);
 		match &sent_messages[0] {
-			AllMessages::CollatorProtocol(CollatorProtocolMessage::DistributeCollation(
+			CollatorProtocolMessage::DistributeCollation(
 				CandidateReceipt { descriptor, .. },
 				_pov,
 				..,
-			)) => {
+			) => {
 				// signature generation is non-deterministic, so we can't just assert that the
 				// expected descriptor is correct. What we can do is validate that the produced
 				// descriptor has a valid signature, then just copy in the generated signature
@@ -367,6 +379,96 @@ mod handle_new_activations {
 		}
 	}
 
+	#[test]
+	fn collates_for_all_configured_paras() {
+		let overseer = |mut handle: TestSubsystemContextHandle<CollationGenerationMessage>| async move {
+			loop {
+				match handle.try_recv().await {
+					None => break,
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::AvailabilityCores(tx),
+					))) => {
+						tx.send(Ok(vec![
+							CoreState::Scheduled(scheduled_core_for(16)),
+							CoreState::Free,
+							CoreState::Scheduled(scheduled_core_for(20)),
+							CoreState::Scheduled(scheduled_core_for(24)),
+						]))
+						.unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::PersistedValidationData(
+							_para_id,
+							_occupied_core_assumption,
+							tx,
+						),
+					))) => {
+						tx.send(Ok(Some(test_validation_data()))).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::Validators(tx),
+					))) => {
+						tx.send(Ok(vec![dummy_validator(); 3])).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::ValidationCodeHash(
+							_para_id,
+							OccupiedCoreAssumption::Free,
+							tx,
+						),
+					))) => {
+						tx.send(Ok(Some(ValidationCode(vec![1, 2, 3]).hash()))).unwrap();
+					},
+					Some(msg) => {
+						panic!("didn't expect any other overseer requests; got {:?}", msg)
+					},
+				}
+			}
+		};
+
+		let (tx, rx) = mpsc::channel(0);
+
+		let sent_messages = Arc::new(Mutex::new(Vec::new()));
+		let subsystem_sent_messages = sent_messages.clone();
+		subsystem_test_harness(overseer, |mut ctx| async move {
+			handle_new_activations(
+				&test_configs(vec![test_config(16), test_config(24)]),
+				vec![Hash::repeat_byte(4)],
+				&mut ctx,
+				Metrics(None),
+				&tx,
+			)
+			.await
+			.unwrap();
+
+			std::mem::drop(tx);
+
+			*subsystem_sent_messages.lock().await = rx.collect().await;
+		});
+
+		let sent_messages = Arc::try_unwrap(sent_messages)
+			.expect("subsystem should have shut down by now")
+			.into_inner();
+
+		// One collation for each of our paras, none for the para we are not collating for.
+		let mut para_ids: Vec<ParaId> = sent_messages
+			.iter()
+			.map(|msg| match msg {
+				CollatorProtocolMessage::DistributeCollation(
+					CandidateReceipt { descriptor, .. },
+					..,
+				) => descriptor.para_id,
+				_ => panic!("received wrong message type"),
+			})
+			.collect();
+		para_ids.sort();
+		assert_eq!(para_ids, vec![16.into(), 24.into()]);
+	}
+
 	#[test]
 	fn fallback_when_no_validation_code_hash_api() {
 		// This is a variant of the above test, but with the validation code hash API disabled.
@@ -453,7 +555,7 @@ mod handle_new_activations {
 		let subsystem_sent_messages = sent_messages.clone();
 		subsystem_test_harness(overseer, |mut ctx| async move {
 			handle_new_activations(
-				subsystem_config,
+				&test_configs(vec![subsystem_config]),
 				activated_hashes,
 				&mut ctx,
 				Metrics(None),
@@ -475,11 +577,11 @@ mod handle_new_activations {
 
 		assert_eq!(sent_messages.len(), 1);
 		match &sent_messages[0] {
-			AllMessages::CollatorProtocol(CollatorProtocolMessage::DistributeCollation(
+			CollatorProtocolMessage::DistributeCollation(
 				CandidateReceipt { descriptor, .. },
 				_pov,
 				..,
-			)) => {
+			) => {
 				assert_eq!(expect_validation_code_hash, descriptor.validation_code_hash);
 			},
 			_ => panic!("received wrong message type"),
@@ -17,7 +17,9 @@
 //! Utilities for checking whether a candidate has been approved under a given block.
 
 use bitvec::{order::Lsb0 as BitOrderLsb0, slice::BitSlice};
-use polkadot_node_primitives::approval::DelayTranche;
+use polkadot_node_primitives::approval::{
+	DelayTranche, RequiredTranchesStatus, TrancheApprovalStatus,
+};
 use polkadot_primitives::v1::ValidatorIndex;
 
 use crate::{
@@ -63,6 +65,81 @@ pub enum RequiredTranches {
 	},
 }
 
+impl From<&RequiredTranches> for RequiredTranchesStatus {
+	fn from(required: &RequiredTranches) -> Self {
+		match *required {
+			RequiredTranches::All => RequiredTranchesStatus::All,
+			RequiredTranches::Pending { considered, maximum_broadcast, .. } =>
+				RequiredTranchesStatus::Pending { considered, maximum_broadcast },
+			RequiredTranches::Exact { needed, tolerated_missing, .. } =>
+				RequiredTranchesStatus::Exact { needed, tolerated_missing: tolerated_missing as _ },
+		}
+	}
+}
+
+impl RequiredTranches {
+	/// A rough estimate of the tick at which the candidate could be approved, assuming all
+	/// outstanding assignments and approval votes arrive in time.
+	///
+	/// Returns `None` if all validators are required.
+	pub fn estimated_approval_tick(&self, block_tick: Tick, tick_now: Tick) -> Option<Tick> {
+		match *self {
+			RequiredTranches::All => None,
+			// All needed assignments are in, we are only waiting for approval votes which
+			// are expected before the next no-show.
+			RequiredTranches::Exact { next_no_show, .. } =>
+				Some(next_no_show.unwrap_or(tick_now).max(tick_now)),
+			// Assignments up to `maximum_broadcast` are still needed, which are only
+			// broadcast once the (drifted) tranche is reached.
+			RequiredTranches::Pending { maximum_broadcast, clock_drift, next_no_show, .. } => {
+				let broadcast_tick = block_tick
+					.saturating_add(maximum_broadcast as Tick)
+					.saturating_add(clock_drift);
+				Some(broadcast_tick.max(next_no_show.unwrap_or(tick_now)).max(tick_now))
+			},
+		}
+	}
+}
+
+/// Break down the assignments of an approval entry by tranche, along with the approvals and
+/// no-shows among them.
+///
+/// An assignment is considered a no-show if no approval vote was received within
+/// `no_show_duration` ticks after it was received, ignoring any clock drift.
+pub fn tranche_statuses(
+	approval_entry: &ApprovalEntry,
+	approvals: &BitSlice<BitOrderLsb0, u8>,
+	block_tick: Tick,
+	no_show_duration: Tick,
+	tick_now: Tick,
+) -> Vec<TrancheApprovalStatus> {
+	approval_entry
+		.tranches()
+		.iter()
+		.map(|tranche_entry| {
+			let mut status = TrancheApprovalStatus {
+				tranche: tranche_entry.tranche(),
+				assignments: Vec::new(),
+				approvals: Vec::new(),
+				no_shows: Vec::new(),
+			};
+
+			for &(v_index, tick) in tranche_entry.assignments() {
+				status.assignments.push(v_index);
+
+				let has_approved = approvals.get(v_index.0 as usize).map_or(false, |a| *a);
+				if has_approved {
+					status.approvals.push(v_index);
+				} else if tick.max(block_tick) + no_show_duration <= tick_now {
+					status.no_shows.push(v_index);
+				}
+			}
+
+			status
+		})
+		.collect()
+}
+
 /// The result of a check.
 #[derive(Debug, Clone, Copy)]
 pub enum Check {
@@ -18,7 +18,8 @@
 
 use parity_scale_codec::{Decode, Encode};
 use polkadot_node_primitives::approval::{
-	self as approval_types, AssignmentCert, AssignmentCertKind, DelayTranche, RelayVRFStory,
+	self as approval_types, AssignmentCert, AssignmentCertKind, CoreBitfield, DelayTranche,
+	RelayVRFStory,
 };
 use polkadot_primitives::{
 	v1::{AssignmentId, AssignmentPair, CandidateHash, CoreIndex, GroupIndex, ValidatorIndex},
@@ -30,7 +31,10 @@ use sp_application_crypto::ByteArray;
 use merlin::Transcript;
 use schnorrkel::vrf::VRFInOut;
 
-use std::collections::{hash_map::Entry, HashMap};
+use std::{
+	collections::{hash_map::Entry, BTreeSet, HashMap},
+	convert::TryFrom,
+};
 
 use super::LOG_TARGET;
 
@@ -105,6 +109,34 @@ fn relay_vrf_modulo_core(vrf_in_out: &VRFInOut, n_cores: u32) -> CoreIndex {
 	CoreIndex(random_core)
 }
 
+fn relay_vrf_modulo_compact_transcript(relay_vrf_story: RelayVRFStory) -> Transcript {
+	// combine the relay VRF story with the compact context. All samples are derived from
+	// the single VRF output.
+	let mut t = Transcript::new(approval_types::RELAY_VRF_MODULO_COMPACT_CONTEXT);
+	t.append_message(b"RC-VRF", &relay_vrf_story.0);
+
+	t
+}
+
+fn relay_vrf_modulo_cores(
+	vrf_in_out: &VRFInOut,
+	n_samples: u32,
+	n_cores: u32,
+) -> BTreeSet<CoreIndex> {
+	(0..n_samples)
+		.map(|sample| {
+			// Each sample draws from its own context so samples are independent.
+			let mut context = approval_types::CORE_RANDOMNESS_CONTEXT.to_vec();
+			sample.using_encoded(|s| context.extend_from_slice(s));
+
+			let bytes: [u8; 4] = vrf_in_out.make_bytes(&context);
+
+			// interpret as little-endian u32.
+			CoreIndex(u32::from_le_bytes(bytes) % n_cores)
+		})
+		.collect()
+}
+
 fn relay_vrf_delay_transcript(relay_vrf_story: RelayVRFStory, core_index: CoreIndex) -> Transcript {
 	let mut t = Transcript::new(approval_types::RELAY_VRF_DELAY_CONTEXT);
 	t.append_message(b"RC-VRF", &relay_vrf_story.0);
@@ -133,6 +165,12 @@ fn assigned_core_transcript(core_index: CoreIndex) -> Transcript {
 	t
 }
 
+fn assigned_cores_transcript(core_bitfield: &CoreBitfield) -> Transcript {
+	let mut t = Transcript::new(approval_types::ASSIGNED_CORES_CONTEXT);
+	core_bitfield.using_encoded(|s| t.append_message(b"cores", s));
+	t
+}
+
 /// Information about the world assignments are being produced in.
 #[derive(Clone)]
 pub(crate) struct Config {
@@ -175,16 +213,20 @@ pub(crate) trait AssignmentCriteria {
 
 	fn check_assignment_cert(
 		&self,
-		claimed_core_index: CoreIndex,
+		claimed_core_indices: CoreBitfield,
 		validator_index: ValidatorIndex,
 		config: &Config,
 		relay_vrf_story: RelayVRFStory,
 		assignment: &AssignmentCert,
-		backing_group: GroupIndex,
+		backing_groups: Vec<GroupIndex>,
 	) -> Result<DelayTranche, InvalidAssignment>;
 }
 
-pub(crate) struct RealAssignmentCriteria;
+pub(crate) struct RealAssignmentCriteria {
+	/// Whether to produce `RelayVRFModuloCompact` assignments instead of one
+	/// `RelayVRFModulo` assignment per sample.
+	pub(crate) enable_compact_assignments: bool,
+}
 
 impl AssignmentCriteria for RealAssignmentCriteria {
 	fn compute_assignments(
@@ -194,25 +236,31 @@ impl AssignmentCriteria for RealAssignmentCriteria {
 		config: &Config,
 		leaving_cores: Vec<(CandidateHash, CoreIndex, GroupIndex)>,
 	) -> HashMap<CoreIndex, OurAssignment> {
-		compute_assignments(keystore, relay_vrf_story, config, leaving_cores)
+		compute_assignments(
+			keystore,
+			relay_vrf_story,
+			config,
+			leaving_cores,
+			self.enable_compact_assignments,
+		)
 	}
 
 	fn check_assignment_cert(
 		&self,
-		claimed_core_index: CoreIndex,
+		claimed_core_indices: CoreBitfield,
 		validator_index: ValidatorIndex,
 		config: &Config,
 		relay_vrf_story: RelayVRFStory,
 		assignment: &AssignmentCert,
-		backing_group: GroupIndex,
+		backing_groups: Vec<GroupIndex>,
 	) -> Result<DelayTranche, InvalidAssignment> {
 		check_assignment_cert(
-			claimed_core_index,
+			claimed_core_indices,
 			validator_index,
 			config,
 			relay_vrf_story,
 			assignment,
-			backing_group,
+			backing_groups,
 		)
 	}
 }
@@ -227,11 +275,15 @@ impl AssignmentCriteria for RealAssignmentCriteria {
 /// The idea is that most assignments are never triggered and fall by the wayside.
 ///
 /// This will not assign to anything the local validator was part of the backing group for.
+///
+/// With `enable_compact_assignments`, the tranche zero assignments are produced as a single
+/// `RelayVRFModuloCompact` cert claiming all sampled cores, instead of one cert per sample.
 pub(crate) fn compute_assignments(
 	keystore: &LocalKeystore,
 	relay_vrf_story: RelayVRFStory,
 	config: &Config,
 	leaving_cores: impl IntoIterator<Item = (CandidateHash, CoreIndex, GroupIndex)> + Clone,
+	enable_compact_assignments: bool,
 ) -> HashMap<CoreIndex, OurAssignment> {
 	if config.n_cores == 0 ||
 		config.assignment_keys.is_empty() ||
@@ -289,15 +341,26 @@ pub(crate) fn compute_assignments(
 
 	let mut assignments = HashMap::new();
 
-	// First run `RelayVRFModulo` for each sample.
-	compute_relay_vrf_modulo_assignments(
-		&assignments_key,
-		index,
-		config,
-		relay_vrf_story.clone(),
-		leaving_cores.iter().cloned(),
-		&mut assignments,
-	);
+	// First run `RelayVRFModulo` for each sample, or `RelayVRFModuloCompact` once for all of them.
+	if enable_compact_assignments {
+		compute_relay_vrf_modulo_compact_assignment(
+			&assignments_key,
+			index,
+			config,
+			relay_vrf_story.clone(),
+			leaving_cores.iter().cloned(),
+			&mut assignments,
+		);
+	} else {
+		compute_relay_vrf_modulo_assignments(
+			&assignments_key,
+			index,
+			config,
+			relay_vrf_story.clone(),
+			leaving_cores.iter().cloned(),
+			&mut assignments,
+		);
+	}
 
 	// Then run `RelayVRFDelay` once for the whole block.
 	compute_relay_vrf_delay_assignments(
@@ -373,6 +436,80 @@ fn compute_relay_vrf_modulo_assignments(
 	}
 }
 
+fn compute_relay_vrf_modulo_compact_assignment(
+	assignments_key: &schnorrkel::Keypair,
+	validator_index: ValidatorIndex,
+	config: &Config,
+	relay_vrf_story: RelayVRFStory,
+	leaving_cores: impl IntoIterator<Item = (CandidateHash, CoreIndex)> + Clone,
+	assignments: &mut HashMap<CoreIndex, OurAssignment>,
+) {
+	let mut assigned_cores = None;
+
+	let maybe_assignment = {
+		// Extra scope to ensure borrowing instead of moving `assigned_cores`
+		// into closure.
+		let assigned_cores = &mut assigned_cores;
+		assignments_key.vrf_sign_extra_after_check(
+			relay_vrf_modulo_compact_transcript(relay_vrf_story),
+			|vrf_in_out| {
+				let sampled_cores = relay_vrf_modulo_cores(
+					&vrf_in_out,
+					config.relay_vrf_modulo_samples,
+					config.n_cores,
+				);
+
+				let cores = leaving_cores
+					.clone()
+					.into_iter()
+					.filter(|(_, c)| sampled_cores.contains(c))
+					.map(|(candidate_hash, core)| {
+						tracing::trace!(
+							target: LOG_TARGET,
+							?candidate_hash,
+							?core,
+							?validator_index,
+							tranche = 0,
+							"RelayVRFModuloCompact Assignment."
+						);
+
+						core
+					})
+					.collect::<Vec<_>>();
+
+				let core_bitfield = CoreBitfield::try_from(cores).ok()?;
+				let transcript = assigned_cores_transcript(&core_bitfield);
+				*assigned_cores = Some(core_bitfield);
+
+				Some(transcript)
+			},
+		)
+	};
+
+	if let (Some((vrf_in_out, vrf_proof, _)), Some(core_bitfield)) =
+		(maybe_assignment, assigned_cores)
+	{
+		let kind = AssignmentCertKind::RelayVRFModuloCompact { core_bitfield: core_bitfield.clone() };
+		let cert = AssignmentCert {
+			kind,
+			vrf: (
+				approval_types::VRFOutput(vrf_in_out.to_output()),
+				approval_types::VRFProof(vrf_proof),
+			),
+		};
+
+		// The same cert is shared by all claimed cores, all of them in tranche 0.
+		for core in core_bitfield.iter_ones() {
+			assignments.entry(core).or_insert(OurAssignment {
+				cert: cert.clone(),
+				tranche: 0,
+				validator_index,
+				triggered: false,
+			});
+		}
+	}
+}
+
 fn compute_relay_vrf_delay_assignments(
 	assignments_key: &schnorrkel::Keypair,
 	validator_index: ValidatorIndex,
@@ -443,21 +580,25 @@ impl std::error::Error for InvalidAssignment {}
 /// Checks the crypto of an assignment cert. Failure conditions:
 ///   * Validator index out of bounds
 ///   * VRF signature check fails
-///   * VRF output doesn't match assigned core
-///   * Core is not covered by extra data in signature
+///   * VRF output doesn't match assigned cores
+///   * Cores are not covered by extra data in signature
 ///   * Core index out of bounds
 ///   * Sample is out of bounds
-///   * Validator is present in backing group.
+///   * Validator is present in any backing group of the claimed cores.
+///   * More than one core claimed by a cert which can only claim a single core.
+///
+/// `claimed_core_indices` and `backing_groups` are expected to be in the same order, one
+/// backing group per claimed core.
 ///
 /// This function does not check whether the core is actually a valid assignment or not. That should be done
 /// outside the scope of this function.
 pub(crate) fn check_assignment_cert(
-	claimed_core_index: CoreIndex,
+	claimed_core_indices: CoreBitfield,
 	validator_index: ValidatorIndex,
 	config: &Config,
 	relay_vrf_story: RelayVRFStory,
 	assignment: &AssignmentCert,
-	backing_group: GroupIndex,
+	backing_groups: Vec<GroupIndex>,
 ) -> Result<DelayTranche, InvalidAssignment> {
 	let validator_public = config
 		.assignment_keys
@@ -467,22 +608,37 @@ pub(crate) fn check_assignment_cert(
 	let public = schnorrkel::PublicKey::from_bytes(validator_public.as_slice())
 		.map_err(|_| InvalidAssignment)?;
 
-	if claimed_core_index.0 >= config.n_cores {
+	if claimed_core_indices.count_ones() == 0 ||
+		claimed_core_indices.count_ones() != backing_groups.len()
+	{
+		return Err(InvalidAssignment)
+	}
+
+	if claimed_core_indices.iter_ones().any(|core| core.0 >= config.n_cores) {
 		return Err(InvalidAssignment)
 	}
 
 	// Check that the validator was not part of the backing group
 	// and not already assigned.
-	let is_in_backing =
-		is_in_backing_group(&config.validator_groups, validator_index, backing_group);
+	let is_in_backing = backing_groups
+		.iter()
+		.any(|group| is_in_backing_group(&config.validator_groups, validator_index, *group));
 
 	if is_in_backing {
 		return Err(InvalidAssignment)
 	}
 
+	// Certs other than `RelayVRFModuloCompact` claim exactly one core.
+	let single_claimed_core = || match claimed_core_indices.count_ones() {
+		1 => claimed_core_indices.first_one().ok_or(InvalidAssignment),
+		_ => Err(InvalidAssignment),
+	};
+
 	let &(ref vrf_output, ref vrf_proof) = &assignment.vrf;
 	match assignment.kind {
 		AssignmentCertKind::RelayVRFModulo { sample } => {
+			let claimed_core_index = single_claimed_core()?;
+
 			if sample >= config.relay_vrf_modulo_samples {
 				return Err(InvalidAssignment)
 			}
@@ -504,7 +660,7 @@ pub(crate) fn check_assignment_cert(
 			}
 		},
 		AssignmentCertKind::RelayVRFDelay { core_index } => {
-			if core_index != claimed_core_index {
+			if core_index != single_claimed_core()? {
 				return Err(InvalidAssignment)
 			}
 
@@ -522,6 +678,34 @@ pub(crate) fn check_assignment_cert(
 				config.zeroth_delay_tranche_width,
 			))
 		},
+		AssignmentCertKind::RelayVRFModuloCompact { ref core_bitfield } => {
+			// The claimed cores must be exactly the ones signed for.
+			if !core_bitfield.iter_ones().eq(claimed_core_indices.iter_ones()) {
+				return Err(InvalidAssignment)
+			}
+
+			let (vrf_in_out, _) = public
+				.vrf_verify_extra(
+					relay_vrf_modulo_compact_transcript(relay_vrf_story),
+					&vrf_output.0,
+					&vrf_proof.0,
+					assigned_cores_transcript(core_bitfield),
+				)
+				.map_err(|_| InvalidAssignment)?;
+
+			// ensure that the `vrf_in_out` actually samples all of the claimed cores.
+			let sampled_cores = relay_vrf_modulo_cores(
+				&vrf_in_out,
+				config.relay_vrf_modulo_samples,
+				config.n_cores,
+			);
+
+			if claimed_core_indices.iter_ones().all(|core| sampled_cores.contains(&core)) {
+				Ok(0)
+			} else {
+				Err(InvalidAssignment)
+			}
+		},
 	}
 }
 
@@ -626,6 +810,7 @@ mod tests {
 				n_delay_tranches: 40,
 			},
 			vec![(c_a, CoreIndex(0), GroupIndex(1)), (c_b, CoreIndex(1), GroupIndex(0))],
+			false,
 		);
 
 		// Note that alice is in group 0, which was the backing group for core 1.
@@ -661,6 +846,7 @@ mod tests {
 				n_delay_tranches: 40,
 			},
 			vec![(c_a, CoreIndex(0), GroupIndex(0)), (c_b, CoreIndex(1), GroupIndex(1))],
+			false,
 		);
 
 		assert_eq!(assignments.len(), 1);
@@ -688,15 +874,16 @@ mod tests {
 				n_delay_tranches: 40,
 			},
 			vec![],
+			false,
 		);
 
 		assert!(assignments.is_empty());
 	}
 
 	struct MutatedAssignment {
-		core: CoreIndex,
+		cores: CoreBitfield,
 		cert: AssignmentCert,
-		group: GroupIndex,
+		groups: Vec<GroupIndex>,
 		own_group: GroupIndex,
 		val_index: ValidatorIndex,
 		config: Config,
@@ -708,6 +895,26 @@ mod tests {
 		n_cores: usize,
 		rotation_offset: usize,
 		f: impl Fn(&mut MutatedAssignment) -> Option<bool>, // None = skip
+	) {
+		check_mutated_assignments_inner(n_validators, n_cores, rotation_offset, false, f)
+	}
+
+	// Like `check_mutated_assignments`, but producing `RelayVRFModuloCompact` assignments.
+	fn check_mutated_compact_assignments(
+		n_validators: usize,
+		n_cores: usize,
+		rotation_offset: usize,
+		f: impl Fn(&mut MutatedAssignment) -> Option<bool>, // None = skip
+	) {
+		check_mutated_assignments_inner(n_validators, n_cores, rotation_offset, true, f)
+	}
+
+	fn check_mutated_assignments_inner(
+		n_validators: usize,
+		n_cores: usize,
+		rotation_offset: usize,
+		enable_compact_assignments: bool,
+		f: impl Fn(&mut MutatedAssignment) -> Option<bool>, // None = skip
 	) {
 		let keystore = futures::executor::block_on(make_keystore(&[Sr25519Keyring::Alice]));
 
@@ -739,13 +946,26 @@ mod tests {
 					)
 				})
 				.collect::<Vec<_>>(),
+			enable_compact_assignments,
 		);
 
 		let mut counted = 0;
 		for (core, assignment) in assignments {
+			let cores = match assignment.cert.kind {
+				AssignmentCertKind::RelayVRFModuloCompact { ref core_bitfield } => {
+					// A compact cert is shared by all cores it claims, check it only once.
+					if core_bitfield.first_one() != Some(core) {
+						continue
+					}
+
+					core_bitfield.clone()
+				},
+				_ => core.into(),
+			};
+
 			let mut mutated = MutatedAssignment {
-				core,
-				group: group_for_core(core.0 as _),
+				groups: cores.iter_ones().map(|c| group_for_core(c.0 as _)).collect(),
+				cores,
 				cert: assignment.cert,
 				own_group: GroupIndex(0),
 				val_index: ValidatorIndex(0),
@@ -760,12 +980,12 @@ mod tests {
 			counted += 1;
 
 			let is_good = check_assignment_cert(
-				mutated.core,
+				mutated.cores,
 				mutated.val_index,
 				&mutated.config,
 				relay_vrf_story.clone(),
 				&mutated.cert,
-				mutated.group,
+				mutated.groups,
 			)
 			.is_ok();
 
@@ -783,7 +1003,7 @@ mod tests {
 	#[test]
 	fn check_rejects_claimed_core_out_of_bounds() {
 		check_mutated_assignments(200, 100, 25, |m| {
-			m.core.0 += 100;
+			m.cores = CoreIndex(m.cores.first_one().unwrap().0 + 100).into();
 			Some(false)
 		});
 	}
@@ -791,7 +1011,7 @@ mod tests {
 	#[test]
 	fn check_rejects_in_backing_group() {
 		check_mutated_assignments(200, 100, 25, |m| {
-			m.group = m.own_group;
+			m.groups[0] = m.own_group;
 			Some(false)
 		});
 	}
@@ -848,7 +1068,7 @@ mod tests {
 		check_mutated_assignments(200, 100, 25, |m| {
 			match m.cert.kind.clone() {
 				AssignmentCertKind::RelayVRFDelay { .. } => {
-					m.core = CoreIndex((m.core.0 + 1) % 100);
+					m.cores = CoreIndex((m.cores.first_one().unwrap().0 + 1) % 100).into();
 					Some(false)
 				},
 				_ => None, // skip everything else.
@@ -861,11 +1081,121 @@ mod tests {
 		check_mutated_assignments(200, 100, 25, |m| {
 			match m.cert.kind.clone() {
 				AssignmentCertKind::RelayVRFModulo { .. } => {
-					m.core = CoreIndex((m.core.0 + 1) % 100);
+					m.cores = CoreIndex((m.cores.first_one().unwrap().0 + 1) % 100).into();
+					Some(false)
+				},
+				_ => None, // skip everything else.
+			}
+		});
+	}
+
+	#[test]
+	fn compact_assignment_shares_one_cert_for_all_claimed_cores() {
+		let keystore = futures::executor::block_on(make_keystore(&[Sr25519Keyring::Alice]));
+
+		let n_cores = 10;
+		let assignments = compute_assignments(
+			&keystore,
+			RelayVRFStory([42u8; 32]),
+			&Config {
+				assignment_keys: assignment_keys_plus_random(&[Sr25519Keyring::Alice], 9),
+				validator_groups: basic_groups(10, n_cores),
+				n_cores: n_cores as u32,
+				zeroth_delay_tranche_width: 10,
+				relay_vrf_modulo_samples: 6,
+				n_delay_tranches: 40,
+			},
+			// Alice is in group 0, which doesn't back any core.
+			(1..n_cores)
+				.map(|i| {
+					let candidate_hash = CandidateHash(Hash::repeat_byte(i as u8));
+					(candidate_hash, CoreIndex(i as u32), GroupIndex(i as _))
+				})
+				.collect::<Vec<_>>(),
+			true,
+		);
+
+		let compact = assignments
+			.iter()
+			.filter_map(|(core, a)| match a.cert.kind {
+				AssignmentCertKind::RelayVRFModuloCompact { ref core_bitfield } =>
+					Some((*core, a.cert.clone(), core_bitfield.clone())),
+				AssignmentCertKind::RelayVRFModulo { .. } =>
+					panic!("no per-sample certs are produced in compact mode"),
+				AssignmentCertKind::RelayVRFDelay { .. } => None,
+			})
+			.collect::<Vec<_>>();
+
+		assert!(!compact.is_empty());
+
+		let (_, cert, core_bitfield) = compact[0].clone();
+		assert_eq!(core_bitfield.count_ones(), compact.len());
+		for (core, c, _) in compact {
+			assert_eq!(c, cert);
+			assert!(core_bitfield.bit_at(core));
+			assert_eq!(assignments[&core].tranche(), 0);
+		}
+	}
+
+	#[test]
+	fn computed_compact_assignments_pass_checks() {
+		check_mutated_compact_assignments(200, 100, 25, |_| Some(true));
+	}
+
+	#[test]
+	fn check_rejects_compact_bad_vrf() {
+		check_mutated_compact_assignments(200, 100, 25, |m| {
+			match m.cert.kind.clone() {
+				AssignmentCertKind::RelayVRFModuloCompact { .. } => {
+					m.cert.vrf = garbage_vrf();
 					Some(false)
 				},
 				_ => None, // skip everything else.
 			}
 		});
 	}
+
+	#[test]
+	fn check_rejects_compact_claiming_unsigned_core() {
+		check_mutated_compact_assignments(200, 100, 25, |m| {
+			match m.cert.kind.clone() {
+				AssignmentCertKind::RelayVRFModuloCompact { .. } => {
+					let extra = (0..100).map(CoreIndex).find(|c| !m.cores.bit_at(*c)).unwrap();
+					let mut cores = m.cores.iter_ones().collect::<Vec<_>>();
+					cores.push(extra);
+
+					m.cores = CoreBitfield::try_from(cores).unwrap();
+					m.groups.push(m.groups[0]);
+					Some(false)
+				},
+				_ => None, // skip everything else.
+			}
+		});
+	}
+
+	#[test]
+	fn check_rejects_compact_in_any_backing_group() {
+		check_mutated_compact_assignments(200, 100, 25, |m| {
+			match m.cert.kind.clone() {
+				AssignmentCertKind::RelayVRFModuloCompact { .. } => {
+					let last = m.groups.len() - 1;
+					m.groups[last] = m.own_group;
+					Some(false)
+				},
+				_ => None, // skip everything else.
+			}
+		});
+	}
+
+	#[test]
+	fn check_rejects_multiple_cores_for_single_core_certs() {
+		check_mutated_assignments(200, 100, 25, |m| {
+			let core = m.cores.first_one().unwrap();
+			let other = CoreIndex((core.0 + 1) % 100);
+
+			m.cores = CoreBitfield::try_from(vec![core, other]).unwrap();
+			m.groups.push(m.groups[0]);
+			Some(false)
+		});
+	}
 }
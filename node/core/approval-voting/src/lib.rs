@@ -25,7 +25,9 @@ use kvdb::KeyValueDB;
 use polkadot_node_jaeger as jaeger;
 use polkadot_node_primitives::{
 	approval::{
-		BlockApprovalMeta, DelayTranche, IndirectAssignmentCert, IndirectSignedApprovalVote,
+		AssignmentCert, AssignmentCertKind, BlockApprovalMeta, BlockApprovalStatus,
+		CandidateApprovalStatus, CandidateBitfield, CoreBitfield, DelayTranche,
+		IndirectAssignmentCert, IndirectSignedApprovalVote,
 	},
 	SignedDisputeStatement, ValidationResult, APPROVAL_EXECUTION_TIMEOUT,
 };
@@ -38,7 +40,7 @@ use polkadot_node_subsystem::{
 		ChainSelectionMessage, DisputeCoordinatorMessage, HighestApprovedAncestorBlock,
 		ImportStatementsResult, RuntimeApiMessage, RuntimeApiRequest,
 	},
-	overseer::{self, SubsystemSender as _},
+	overseer::{self, ApprovalVotingOutgoingMessages, SubsystemSender as _},
 	FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext, SubsystemError,
 	SubsystemResult, SubsystemSender,
 };
@@ -74,6 +76,7 @@ use std::{
 	collections::{
 		btree_map::Entry as BTMEntry, hash_map::Entry as HMEntry, BTreeMap, HashMap, HashSet,
 	},
+	convert::TryFrom,
 	sync::Arc,
 	time::Duration,
 };
@@ -81,7 +84,7 @@ use std::{
 use approval_checking::RequiredTranches;
 use criteria::{AssignmentCriteria, RealAssignmentCriteria};
 use persisted_entries::{ApprovalEntry, BlockEntry, CandidateEntry};
-use time::{slot_number_to_tick, Clock, ClockExt, SystemClock, Tick};
+use time::{slot_number_to_tick, Clock, ClockExt, SystemClock, Tick, TICK_DURATION_MILLIS};
 
 mod approval_checking;
 mod approval_db;
@@ -116,6 +119,10 @@ pub struct Config {
 	/// The slot duration of the consensus algorithm, in milliseconds. Should be evenly
 	/// divisible by 500.
 	pub slot_duration_millis: u64,
+	/// Whether to produce compact assignment certificates, claiming all cores sampled in
+	/// tranche zero with a single VRF. These can only be gossiped to peers speaking version 2
+	/// of the validation protocol, so this should only be enabled once the network has upgraded.
+	pub enable_compact_assignments: bool,
 }
 
 // The mode of the approval voting subsystem. It should start in a `Syncing` mode when it first
@@ -140,6 +147,7 @@ pub struct ApprovalVotingSubsystem {
 	keystore: Arc<LocalKeystore>,
 	db_config: DatabaseConfig,
 	slot_duration_millis: u64,
+	enable_compact_assignments: bool,
 	db: Arc<dyn KeyValueDB>,
 	mode: Mode,
 	metrics: Metrics,
@@ -337,6 +345,7 @@ impl ApprovalVotingSubsystem {
 		ApprovalVotingSubsystem {
 			keystore,
 			slot_duration_millis: config.slot_duration_millis,
+			enable_compact_assignments: config.enable_compact_assignments,
 			db,
 			db_config: DatabaseConfig { col_data: config.col_data },
 			mode: Mode::Syncing(sync_oracle),
@@ -352,11 +361,13 @@ where
 {
 	fn start(self, ctx: Context) -> SpawnedSubsystem {
 		let backend = DbBackend::new(self.db.clone(), self.db_config);
+		let assignment_criteria =
+			RealAssignmentCriteria { enable_compact_assignments: self.enable_compact_assignments };
 		let future = run::<DbBackend, Context>(
 			ctx,
 			self,
 			Box::new(SystemClock),
-			Box::new(RealAssignmentCriteria),
+			Box::new(assignment_criteria),
 			backend,
 		)
 		.map_err(|e| SubsystemError::with_origin("approval-voting", e))
@@ -595,7 +606,8 @@ impl State {
 	/// Bring `session_window` up to date.
 	pub async fn cache_session_info_for_head(
 		&mut self,
-		ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+		ctx: &mut (impl SubsystemContext<Message = ApprovalVotingMessage>
+		          + overseer::SubsystemContext<Message = ApprovalVotingMessage>),
 		head: Hash,
 	) -> Result<Option<SessionWindowUpdate>, SessionsUnavailable> {
 		let session_window = self.session_window.take();
@@ -673,7 +685,7 @@ enum Action {
 		indirect_cert: IndirectAssignmentCert,
 		assignment_tranche: DelayTranche,
 		relay_block_hash: Hash,
-		candidate_index: CandidateIndex,
+		claimed_candidate_indices: CandidateBitfield,
 		session: SessionIndex,
 		candidate: CandidateReceipt,
 		backing_group: GroupIndex,
@@ -891,7 +903,7 @@ async fn handle_actions(
 				indirect_cert,
 				assignment_tranche,
 				relay_block_hash,
-				candidate_index,
+				claimed_candidate_indices,
 				session,
 				candidate,
 				backing_group,
@@ -907,7 +919,7 @@ async fn handle_actions(
 
 				ctx.send_unbounded_message(ApprovalDistributionMessage::DistributeAssignment(
 					indirect_cert,
-					candidate_index,
+					claimed_candidate_indices,
 				));
 
 				match approvals_cache.get(&candidate_hash) {
@@ -1020,6 +1032,10 @@ fn distribution_messages_for_activation(
 			slot: block_entry.slot(),
 		});
 
+		// Candidates already claimed by an assignment emitted for this block. A compact cert
+		// claims several candidates and is emitted only once for all of them.
+		let mut claimed_so_far = HashSet::new();
+
 		for (i, (_, candidate_hash)) in block_entry.candidates().iter().enumerate() {
 			let candidate_entry = match db.load_candidate_entry(&candidate_hash)? {
 				Some(c) => c,
@@ -1039,25 +1055,26 @@ fn distribution_messages_for_activation(
 				Some(approval_entry) => {
 					match approval_entry.local_statements() {
 						(None, None) | (None, Some(_)) => {}, // second is impossible case.
-						(Some(assignment), None) => {
-							messages.push(ApprovalDistributionMessage::DistributeAssignment(
-								IndirectAssignmentCert {
-									block_hash,
-									validator: assignment.validator_index(),
-									cert: assignment.cert().clone(),
-								},
-								i as _,
-							));
-						},
-						(Some(assignment), Some(approval_sig)) => {
-							messages.push(ApprovalDistributionMessage::DistributeAssignment(
-								IndirectAssignmentCert {
-									block_hash,
-									validator: assignment.validator_index(),
-									cert: assignment.cert().clone(),
-								},
-								i as _,
-							));
+						(Some(assignment), maybe_approval_sig) => {
+							if !claimed_so_far.contains(&(i as CandidateIndex)) {
+								let claimed =
+									claimed_candidate_indices(&block_entry, assignment.cert(), i as _);
+								claimed_so_far.extend(claimed.iter_ones());
+
+								messages.push(ApprovalDistributionMessage::DistributeAssignment(
+									IndirectAssignmentCert {
+										block_hash,
+										validator: assignment.validator_index(),
+										cert: assignment.cert().clone(),
+									},
+									claimed,
+								));
+							}
+
+							let approval_sig = match maybe_approval_sig {
+								Some(sig) => sig,
+								None => continue,
+							};
 
 							messages.push(ApprovalDistributionMessage::DistributeApproval(
 								IndirectSignedApprovalVote {
@@ -1190,6 +1207,11 @@ async fn handle_from_overseer(
 					},
 				}
 
+				Vec::new()
+			},
+			ApprovalVotingMessage::ApprovalStatus(block_hash, res) => {
+				let _ = res.send(handle_approval_status(state, db, block_hash)?);
+
 				Vec::new()
 			},
 		},
@@ -1198,8 +1220,92 @@ async fn handle_from_overseer(
 	Ok(actions)
 }
 
+// Collect the approval progress of all candidates included by the given block.
+fn handle_approval_status(
+	state: &State,
+	db: &OverlayedBackend<'_, impl Backend>,
+	block_hash: Hash,
+) -> SubsystemResult<Option<BlockApprovalStatus>> {
+	let block_entry = match db.load_block_entry(&block_hash)? {
+		Some(b) => b,
+		None => return Ok(None),
+	};
+
+	let session_info = match state.session_info(block_entry.session()) {
+		Some(s) => s,
+		None => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?block_hash,
+				session = block_entry.session(),
+				"Unknown session info for approval status request",
+			);
+			return Ok(None)
+		},
+	};
+
+	let block_tick = slot_number_to_tick(state.slot_duration_millis, block_entry.slot());
+	let tick_now = state.clock.tick_now();
+	let no_show_duration = slot_number_to_tick(
+		state.slot_duration_millis,
+		Slot::from(u64::from(session_info.no_show_slots)),
+	);
+
+	let mut candidates = Vec::with_capacity(block_entry.candidates().len());
+	for (core_index, candidate_hash) in block_entry.candidates() {
+		let candidate_entry = match db.load_candidate_entry(candidate_hash)? {
+			Some(c) => c,
+			None => continue,
+		};
+
+		let (approval_entry, status) = match state.approval_status(&block_entry, &candidate_entry)
+		{
+			Some(s) => s,
+			None => continue,
+		};
+
+		let approved = block_entry.is_candidate_approved(candidate_hash);
+		let (required_tranches, estimated_approval_millis) = if approved {
+			(None, Some(0))
+		} else {
+			let estimate = status
+				.required_tranches
+				.estimated_approval_tick(block_tick, tick_now)
+				.map(|tick| tick.saturating_sub(tick_now) * TICK_DURATION_MILLIS);
+
+			(Some((&status.required_tranches).into()), estimate)
+		};
+
+		candidates.push(CandidateApprovalStatus {
+			candidate_hash: candidate_hash.0,
+			core_index: core_index.0,
+			approved,
+			needed_approvals: session_info.needed_approvals,
+			required_tranches,
+			tranches: approval_checking::tranche_statuses(
+				approval_entry,
+				candidate_entry.approvals(),
+				block_tick,
+				no_show_duration,
+				tick_now,
+			),
+			estimated_approval_millis,
+		});
+	}
+
+	Ok(Some(BlockApprovalStatus {
+		block_hash,
+		block_number: block_entry.block_number(),
+		session: block_entry.session(),
+		tranche_now: state.clock.tranche_now(state.slot_duration_millis, block_entry.slot()),
+		approved: block_entry.is_fully_approved(),
+		candidates,
+	}))
+}
+
 async fn handle_approved_ancestor(
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	ctx: &mut (impl SubsystemContext<Message = ApprovalVotingMessage>
+	          + overseer::SubsystemContext<Message = ApprovalVotingMessage>),
 	db: &OverlayedBackend<'_, impl Backend>,
 	target: Hash,
 	lower_bound: BlockNumber,
@@ -1535,7 +1641,7 @@ fn check_and_import_assignment(
 	state: &State,
 	db: &mut OverlayedBackend<'_, impl Backend>,
 	assignment: IndirectAssignmentCert,
-	candidate_index: CandidateIndex,
+	candidate_indices: CandidateBitfield,
 ) -> SubsystemResult<(AssignmentCheckResult, Vec<Action>)> {
 	let tick_now = state.clock.tick_now();
 
@@ -1561,34 +1667,38 @@ fn check_and_import_assignment(
 			)),
 	};
 
-	let (claimed_core_index, assigned_candidate_hash) =
-		match block_entry.candidate(candidate_index as usize) {
-			Some((c, h)) => (*c, *h),
+	// Load all claimed candidates along with the cores and groups they were backed on.
+	let mut claimed_core_indices = Vec::new();
+	let mut backing_groups = Vec::new();
+	let mut candidate_entries = Vec::new();
+
+	for candidate_index in candidate_indices.iter_ones() {
+		let (claimed_core_index, assigned_candidate_hash) =
+			match block_entry.candidate(candidate_index as usize) {
+				Some((c, h)) => (*c, *h),
+				None =>
+					return Ok((
+						AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCandidateIndex(
+							candidate_index,
+						)),
+						Vec::new(),
+					)), // no candidate at core.
+			};
+
+		let candidate_entry = match db.load_candidate_entry(&assigned_candidate_hash)? {
+			Some(c) => c,
 			None =>
 				return Ok((
-					AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCandidateIndex(
+					AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCandidate(
 						candidate_index,
+						assigned_candidate_hash,
 					)),
 					Vec::new(),
-				)), // no candidate at core.
-		};
-
-	let mut candidate_entry = match db.load_candidate_entry(&assigned_candidate_hash)? {
-		Some(c) => c,
-		None =>
-			return Ok((
-				AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCandidate(
-					candidate_index,
-					assigned_candidate_hash,
 				)),
-				Vec::new(),
-			)),
-	};
+		};
 
-	let res = {
-		// import the assignment.
-		let approval_entry = match candidate_entry.approval_entry_mut(&assignment.block_hash) {
-			Some(a) => a,
+		let backing_group = match candidate_entry.approval_entry(&assignment.block_hash) {
+			Some(a) => a.backing_group(),
 			None =>
 				return Ok((
 					AssignmentCheckResult::Bad(AssignmentCheckError::Internal(
@@ -1599,43 +1709,61 @@ fn check_and_import_assignment(
 				)),
 		};
 
-		let res = state.assignment_criteria.check_assignment_cert(
-			claimed_core_index,
-			assignment.validator,
-			&criteria::Config::from(session_info),
-			block_entry.relay_vrf_story(),
-			&assignment.cert,
-			approval_entry.backing_group(),
-		);
+		claimed_core_indices.push(claimed_core_index);
+		backing_groups.push(backing_group);
+		candidate_entries.push((assigned_candidate_hash, candidate_entry));
+	}
 
-		let tranche = match res {
-			Err(crate::criteria::InvalidAssignment) =>
-				return Ok((
-					AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCert(
-						assignment.validator,
-					)),
-					Vec::new(),
-				)),
-			Ok(tranche) => {
-				let current_tranche =
-					state.clock.tranche_now(state.slot_duration_millis, block_entry.slot());
+	let claimed_core_indices = match CoreBitfield::try_from(claimed_core_indices) {
+		Ok(b) => b,
+		Err(_) =>
+			return Ok((AssignmentCheckResult::Bad(AssignmentCheckError::NullAssignment), Vec::new())),
+	};
 
-				let too_far_in_future = current_tranche + TICK_TOO_FAR_IN_FUTURE as DelayTranche;
+	let res = state.assignment_criteria.check_assignment_cert(
+		claimed_core_indices,
+		assignment.validator,
+		&criteria::Config::from(session_info),
+		block_entry.relay_vrf_story(),
+		&assignment.cert,
+		backing_groups,
+	);
 
-				if tranche >= too_far_in_future {
-					return Ok((AssignmentCheckResult::TooFarInFuture, Vec::new()))
-				}
+	let tranche = match res {
+		Err(crate::criteria::InvalidAssignment) =>
+			return Ok((
+				AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCert(assignment.validator)),
+				Vec::new(),
+			)),
+		Ok(tranche) => {
+			let current_tranche =
+				state.clock.tranche_now(state.slot_duration_millis, block_entry.slot());
 
-				tranche
-			},
-		};
+			let too_far_in_future = current_tranche + TICK_TOO_FAR_IN_FUTURE as DelayTranche;
+
+			if tranche >= too_far_in_future {
+				return Ok((AssignmentCheckResult::TooFarInFuture, Vec::new()))
+			}
 
-		let is_duplicate = approval_entry.is_assigned(assignment.validator);
+			tranche
+		},
+	};
+
+	let mut is_duplicate = true;
+	let mut actions = Vec::new();
+
+	// Import the assignment for every claimed candidate.
+	for (assigned_candidate_hash, mut candidate_entry) in candidate_entries {
+		let approval_entry = candidate_entry
+			.approval_entry_mut(&assignment.block_hash)
+			.expect("checked above that the approval entry exists; qed");
+
+		let is_new = !approval_entry.is_assigned(assignment.validator);
 		approval_entry.import_assignment(tranche, assignment.validator, tick_now);
 
-		if is_duplicate {
-			AssignmentCheckResult::AcceptedDuplicate
-		} else {
+		if is_new {
+			is_duplicate = false;
+
 			tracing::trace!(
 				target: LOG_TARGET,
 				validator = assignment.validator.0,
@@ -1643,28 +1771,33 @@ fn check_and_import_assignment(
 				para_id = ?candidate_entry.candidate_receipt().descriptor.para_id,
 				"Imported assignment.",
 			);
-
-			AssignmentCheckResult::Accepted
 		}
-	};
 
-	let mut actions = Vec::new();
+		// We've imported a new assignment, so we need to schedule a wake-up for when that might
+		// no-show.
+		if let Some((approval_entry, status)) =
+			state.approval_status(&block_entry, &candidate_entry)
+		{
+			actions.extend(schedule_wakeup_action(
+				approval_entry,
+				block_entry.block_hash(),
+				block_entry.block_number(),
+				assigned_candidate_hash,
+				status.block_tick,
+				tick_now,
+				status.required_tranches,
+			));
+		}
 
-	// We've imported a new approval, so we need to schedule a wake-up for when that might no-show.
-	if let Some((approval_entry, status)) = state.approval_status(&block_entry, &candidate_entry) {
-		actions.extend(schedule_wakeup_action(
-			approval_entry,
-			block_entry.block_hash(),
-			block_entry.block_number(),
-			assigned_candidate_hash,
-			status.block_tick,
-			tick_now,
-			status.required_tranches,
-		));
+		// We also write the candidate entry as it now contains the new candidate.
+		db.write_candidate_entry(candidate_entry.into());
 	}
 
-	// We also write the candidate entry as it now contains the new candidate.
-	db.write_candidate_entry(candidate_entry.into());
+	let res = if is_duplicate {
+		AssignmentCheckResult::AcceptedDuplicate
+	} else {
+		AssignmentCheckResult::Accepted
+	};
 
 	Ok((res, actions))
 }
@@ -1996,6 +2129,31 @@ fn should_trigger_assignment(
 	}
 }
 
+// The candidates of a block claimed by one of our assignment certs, given the candidate the
+// assignment was triggered for. A compact cert claims all candidates included on the cores it
+// covers, so it is distributed once for all of them.
+fn claimed_candidate_indices(
+	block_entry: &BlockEntry,
+	cert: &AssignmentCert,
+	candidate_index: CandidateIndex,
+) -> CandidateBitfield {
+	match cert.kind {
+		AssignmentCertKind::RelayVRFModuloCompact { ref core_bitfield } => {
+			let claimed = block_entry
+				.candidates()
+				.iter()
+				.enumerate()
+				.filter(|(_, (core, _))| core_bitfield.bit_at(*core))
+				.map(|(i, _)| i as CandidateIndex)
+				.collect::<Vec<_>>();
+
+			CandidateBitfield::try_from(claimed).unwrap_or_else(|_| candidate_index.into())
+		},
+		AssignmentCertKind::RelayVRFModulo { .. } | AssignmentCertKind::RelayVRFDelay { .. } =>
+			candidate_index.into(),
+	}
+}
+
 fn process_wakeup(
 	state: &State,
 	db: &mut OverlayedBackend<'_, impl Backend>,
@@ -2096,13 +2254,14 @@ fn process_wakeup(
 	};
 
 	if let Some((cert, val_index, tranche)) = maybe_cert {
-		let indirect_cert =
-			IndirectAssignmentCert { block_hash: relay_block, validator: val_index, cert };
-
 		let index_in_candidate =
 			block_entry.candidates().iter().position(|(_, h)| &candidate_hash == h);
 
 		if let Some(i) = index_in_candidate {
+			let claimed_candidate_indices = claimed_candidate_indices(&block_entry, &cert, i as _);
+			let indirect_cert =
+				IndirectAssignmentCert { block_hash: relay_block, validator: val_index, cert };
+
 			tracing::trace!(
 				target: LOG_TARGET,
 				?candidate_hash,
@@ -2117,7 +2276,7 @@ fn process_wakeup(
 				indirect_cert,
 				assignment_tranche: tranche,
 				relay_block_hash: relay_block,
-				candidate_index: i as _,
+				claimed_candidate_indices,
 				session: block_entry.session(),
 				candidate: candidate_receipt,
 				backing_group,
@@ -2369,7 +2528,7 @@ async fn launch_approval(
 // Issue and import a local approval vote. Should only be invoked after approval checks
 // have been done.
 async fn issue_approval(
-	ctx: &mut impl SubsystemSender,
+	ctx: &mut impl SubsystemSender<ApprovalVotingOutgoingMessages>,
 	state: &mut State,
 	db: &mut OverlayedBackend<'_, impl Backend>,
 	metrics: &Metrics,
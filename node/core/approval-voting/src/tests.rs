@@ -17,7 +17,7 @@
 use super::*;
 use polkadot_node_primitives::{
 	approval::{
-		AssignmentCert, AssignmentCertKind, DelayTranche, VRFOutput, VRFProof,
+		AssignmentCert, AssignmentCertKind, CoreBitfield, DelayTranche, VRFOutput, VRFProof,
 		RELAY_VRF_MODULO_CONTEXT,
 	},
 	AvailableData, BlockData, PoV,
@@ -239,12 +239,12 @@ where
 
 	fn check_assignment_cert(
 		&self,
-		_claimed_core_index: polkadot_primitives::v1::CoreIndex,
+		_claimed_core_indices: polkadot_node_primitives::approval::CoreBitfield,
 		validator_index: ValidatorIndex,
 		_config: &criteria::Config,
 		_relay_vrf_story: polkadot_node_primitives::approval::RelayVRFStory,
 		_assignment: &polkadot_node_primitives::approval::AssignmentCert,
-		_backing_group: polkadot_primitives::v1::GroupIndex,
+		_backing_groups: Vec<polkadot_primitives::v1::GroupIndex>,
 	) -> Result<polkadot_node_primitives::approval::DelayTranche, criteria::InvalidAssignment> {
 		self.1(validator_index)
 	}
@@ -481,6 +481,7 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 			Config {
 				col_data: test_constants::TEST_CONFIG.col_data,
 				slot_duration_millis: SLOT_DURATION_MILLIS,
+				enable_compact_assignments: false,
 			},
 			Arc::new(kvdb_memorydb::create(test_constants::NUM_COLUMNS)),
 			Arc::new(keystore),
@@ -628,7 +629,7 @@ async fn check_and_import_assignment(
 					validator,
 					cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo { sample: 0 }),
 				},
-				candidate_index,
+				candidate_index.into(),
 				tx,
 			),
 		},
@@ -1076,7 +1077,7 @@ fn blank_subsystem_act_on_bad_block() {
 							sample: 0,
 						}),
 					},
-					0u32,
+					0u32.into(),
 					tx,
 				),
 			},
@@ -1486,6 +1487,108 @@ fn subsystem_accepts_and_imports_approval_after_assignment() {
 	});
 }
 
+async fn approval_status(
+	overseer: &mut VirtualOverseer,
+	block_hash: Hash,
+) -> Option<BlockApprovalStatus> {
+	let (tx, rx) = oneshot::channel();
+	overseer_send(
+		overseer,
+		FromOverseer::Communication { msg: ApprovalVotingMessage::ApprovalStatus(block_hash, tx) },
+	)
+	.await;
+	rx.await.unwrap()
+}
+
+#[test]
+fn subsystem_reports_approval_status_per_tranche() {
+	test_harness(HarnessConfig::default(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, sync_oracle_handle: _sync_oracle_handle, .. } =
+			test_harness;
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(rx)) => {
+				rx.send(Ok(0)).unwrap();
+			}
+		);
+
+		let block_hash = Hash::repeat_byte(0x01);
+
+		let candidate_hash = {
+			let mut candidate_receipt =
+				dummy_candidate_receipt_bad_sig(block_hash, Some(Default::default()));
+			candidate_receipt.descriptor.para_id = 0.into();
+			candidate_receipt.descriptor.relay_parent = block_hash;
+			candidate_receipt.hash()
+		};
+
+		let candidate_index = 0;
+		let validator = ValidatorIndex(0);
+		let session_index = 1;
+
+		// Unknown blocks have no status.
+		assert!(approval_status(&mut virtual_overseer, block_hash).await.is_none());
+
+		ChainBuilder::new()
+			.add_block(
+				block_hash,
+				ChainBuilder::GENESIS_HASH,
+				1,
+				BlockConfig { slot: Slot::from(1), candidates: None, session_info: None },
+			)
+			.build(&mut virtual_overseer)
+			.await;
+
+		let status = approval_status(&mut virtual_overseer, block_hash).await.unwrap();
+		assert_eq!(status.block_hash, block_hash);
+		assert_eq!(status.block_number, 1);
+		assert!(!status.approved);
+		assert_eq!(status.candidates.len(), 1);
+		assert_eq!(status.candidates[0].candidate_hash, candidate_hash.0);
+		assert!(status.candidates[0].required_tranches.is_some());
+		assert!(status.candidates[0].tranches.is_empty());
+
+		let rx = check_and_import_assignment(
+			&mut virtual_overseer,
+			block_hash,
+			candidate_index,
+			validator,
+		)
+		.await;
+		assert_eq!(rx.await, Ok(AssignmentCheckResult::Accepted));
+
+		let status = approval_status(&mut virtual_overseer, block_hash).await.unwrap();
+		let tranches = &status.candidates[0].tranches;
+		assert_eq!(tranches.len(), 1);
+		assert_eq!(tranches[0].assignments, vec![validator]);
+		assert!(tranches[0].approvals.is_empty());
+
+		let rx = check_and_import_approval(
+			&mut virtual_overseer,
+			block_hash,
+			candidate_index,
+			validator,
+			candidate_hash,
+			session_index,
+			true,
+			true,
+			None,
+		)
+		.await;
+		assert_eq!(rx.await, Ok(ApprovalCheckResult::Accepted));
+
+		let status = approval_status(&mut virtual_overseer, block_hash).await.unwrap();
+		assert!(status.approved);
+		assert!(status.candidates[0].required_tranches.is_none());
+		assert_eq!(status.candidates[0].estimated_approval_millis, Some(0));
+		let tranches = &status.candidates[0].tranches;
+		assert_eq!(tranches[0].approvals, vec![validator]);
+		assert!(tranches[0].no_shows.is_empty());
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn subsystem_second_approval_import_only_schedules_wakeups() {
 	test_harness(HarnessConfig::default(), |test_harness| async move {
@@ -1750,7 +1853,7 @@ fn linear_import_act_on_leaf() {
 							sample: 0,
 						}),
 					},
-					0u32,
+					0u32.into(),
 					tx,
 				),
 			},
@@ -1820,7 +1923,7 @@ fn forkful_import_at_same_height_act_on_leaf() {
 								sample: 0,
 							}),
 						},
-						0u32,
+						0u32.into(),
 						tx,
 					),
 				},
@@ -2116,6 +2219,187 @@ fn subsystem_import_checked_approval_sets_one_block_bit_at_a_time() {
 	});
 }
 
+#[test]
+fn subsystem_imports_compact_assignment_for_all_claimed_candidates() {
+	test_harness(HarnessConfig::default(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, sync_oracle_handle: _sync_oracle_handle, .. } =
+			test_harness;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(rx)) => {
+				rx.send(Ok(0)).unwrap();
+			}
+		);
+
+		let block_hash = Hash::repeat_byte(0x01);
+
+		let candidate_receipt1 = {
+			let mut receipt = dummy_candidate_receipt(block_hash);
+			receipt.descriptor.para_id = 1.into();
+			receipt
+		};
+		let candidate_receipt2 = {
+			let mut receipt = dummy_candidate_receipt(block_hash);
+			receipt.descriptor.para_id = 2.into();
+			receipt
+		};
+		let candidate_hash2 = candidate_receipt2.hash();
+		let validator = ValidatorIndex(0);
+		let session_index = 1;
+
+		ChainBuilder::new()
+			.add_block(
+				block_hash,
+				ChainBuilder::GENESIS_HASH,
+				1,
+				BlockConfig {
+					slot: Slot::from(0),
+					candidates: Some(vec![
+						(candidate_receipt1, CoreIndex(0), GroupIndex(1)),
+						(candidate_receipt2, CoreIndex(1), GroupIndex(1)),
+					]),
+					session_info: None,
+				},
+			)
+			.build(&mut virtual_overseer)
+			.await;
+
+		let cert = garbage_assignment_cert(AssignmentCertKind::RelayVRFModuloCompact {
+			core_bitfield: CoreBitfield::try_from(vec![CoreIndex(0), CoreIndex(1)]).unwrap(),
+		});
+
+		let (tx, rx) = oneshot::channel();
+		overseer_send(
+			&mut virtual_overseer,
+			FromOverseer::Communication {
+				msg: ApprovalVotingMessage::CheckAndImportAssignment(
+					IndirectAssignmentCert { block_hash, validator, cert },
+					CandidateBitfield::try_from(vec![0, 1]).unwrap(),
+					tx,
+				),
+			},
+		)
+		.await;
+
+		assert_eq!(rx.await, Ok(AssignmentCheckResult::Accepted));
+
+		// The assignment is known for the second candidate already.
+		let rx = check_and_import_assignment(&mut virtual_overseer, block_hash, 1, validator).await;
+
+		assert_eq!(rx.await, Ok(AssignmentCheckResult::AcceptedDuplicate));
+
+		// And approvals for the second candidate are accepted without a separate assignment.
+		let rx = check_and_import_approval(
+			&mut virtual_overseer,
+			block_hash,
+			1,
+			validator,
+			candidate_hash2,
+			session_index,
+			false,
+			true,
+			None,
+		)
+		.await;
+
+		assert_eq!(rx.await, Ok(ApprovalCheckResult::Accepted));
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn activation_distributes_compact_assignment_once() {
+	use bitvec::{bitvec, order::Lsb0 as BitOrderLsb0};
+
+	let mut store = TestStore::default();
+
+	let block_hash = Hash::repeat_byte(0x01);
+	let session = 1;
+	let validator_index = ValidatorIndex(0);
+	let candidates: Vec<_> = (0..3u32)
+		.map(|i| {
+			let mut receipt = dummy_candidate_receipt(block_hash);
+			receipt.descriptor.para_id = i.into();
+			receipt
+		})
+		.collect();
+
+	let compact_cert = garbage_assignment_cert(AssignmentCertKind::RelayVRFModuloCompact {
+		core_bitfield: CoreBitfield::try_from(vec![CoreIndex(0), CoreIndex(1)]).unwrap(),
+	});
+	let delay_cert =
+		garbage_assignment_cert(AssignmentCertKind::RelayVRFDelay { core_index: CoreIndex(2) });
+
+	let mut write_ops = Vec::new();
+	for (i, receipt) in candidates.iter().enumerate() {
+		// The first two candidates are claimed by the compact cert.
+		let (cert, tranche) =
+			if i < 2 { (compact_cert.clone(), 0) } else { (delay_cert.clone(), 3) };
+		let approval_entry = approval_db::v1::ApprovalEntry {
+			tranches: Vec::new(),
+			backing_group: GroupIndex(1),
+			our_assignment: Some(approval_db::v1::OurAssignment {
+				cert,
+				tranche,
+				validator_index,
+				triggered: true,
+			}),
+			our_approval_sig: Some(sign_approval(Sr25519Keyring::Alice, receipt.hash(), session)),
+			assignments: bitvec![BitOrderLsb0, u8; 0; 3],
+			approved: false,
+		};
+		let candidate_entry = approval_db::v1::CandidateEntry {
+			candidate: receipt.clone(),
+			session,
+			block_assignments: vec![(block_hash, approval_entry)].into_iter().collect(),
+			approvals: bitvec![BitOrderLsb0, u8; 0; 3],
+		};
+		write_ops.push(BackendWriteOp::WriteCandidateEntry(candidate_entry.into()));
+	}
+
+	let block_entry = approval_db::v1::BlockEntry {
+		block_hash,
+		block_number: 1,
+		parent_hash: ChainBuilder::GENESIS_HASH,
+		session,
+		slot: Slot::from(1),
+		relay_vrf_story: [0u8; 32],
+		candidates: candidates
+			.iter()
+			.enumerate()
+			.map(|(i, receipt)| (CoreIndex(i as _), receipt.hash()))
+			.collect(),
+		approved_bitfield: bitvec![BitOrderLsb0, u8; 0; 3],
+		children: Vec::new(),
+	};
+	write_ops.push(BackendWriteOp::WriteBlockEntry(block_entry.into()));
+	store.write(write_ops).unwrap();
+
+	let overlay_db = OverlayedBackend::new(&store);
+	let messages = distribution_messages_for_activation(&overlay_db).unwrap();
+
+	let assignments: Vec<_> = messages
+		.iter()
+		.filter_map(|m| match m {
+			ApprovalDistributionMessage::DistributeAssignment(assignment, claimed) =>
+				Some((assignment.cert.kind.clone(), claimed.iter_ones().collect::<Vec<_>>())),
+			_ => None,
+		})
+		.collect();
+	assert_eq!(assignments, vec![(compact_cert.kind, vec![0, 1]), (delay_cert.kind, vec![2])]);
+
+	let approved: Vec<_> = messages
+		.iter()
+		.filter_map(|m| match m {
+			ApprovalDistributionMessage::DistributeApproval(vote) => Some(vote.candidate_index),
+			_ => None,
+		})
+		.collect();
+	assert_eq!(approved, vec![0, 1, 2]);
+}
+
 fn approved_ancestor_test(
 	skip_approval: impl Fn(BlockNumber) -> bool,
 	approved_height: BlockNumber,
@@ -2379,9 +2663,9 @@ pub async fn handle_double_assignment_import(
 		overseer_recv(virtual_overseer).await,
 		AllMessages::ApprovalDistribution(ApprovalDistributionMessage::DistributeAssignment(
 			_,
-			c_index,
+			c_indices,
 		)) => {
-			assert_eq!(candidate_index, c_index);
+			assert_eq!(CandidateBitfield::from(candidate_index), c_indices);
 		}
 	);
 
@@ -2394,9 +2678,9 @@ pub async fn handle_double_assignment_import(
 	for msg in vec![first_message, second_message].into_iter() {
 		match msg {
 			AllMessages::ApprovalDistribution(
-				ApprovalDistributionMessage::DistributeAssignment(_, c_index),
+				ApprovalDistributionMessage::DistributeAssignment(_, c_indices),
 			) => {
-				assert_eq!(candidate_index, c_index);
+				assert_eq!(CandidateBitfield::from(candidate_index), c_indices);
 			},
 			AllMessages::CandidateValidation(
 				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, tx),
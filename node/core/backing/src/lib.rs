@@ -22,6 +22,7 @@ use std::{
 	collections::{HashMap, HashSet},
 	pin::Pin,
 	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
 };
 
 use bitvec::vec::BitVec;
@@ -29,8 +30,11 @@ use futures::{
 	channel::{mpsc, oneshot},
 	Future, FutureExt, SinkExt, StreamExt,
 };
+use lru::LruCache;
+use parking_lot::Mutex;
 
 use polkadot_node_primitives::{
+	backing::{BackingEvent, BackingStatementKind, BackingTraceEntry},
 	AvailableData, PoV, SignedDisputeStatement, SignedFullStatement, Statement, ValidationResult,
 	BACKING_EXECUTION_TIMEOUT,
 };
@@ -40,27 +44,32 @@ use polkadot_node_subsystem_util::{
 	request_from_runtime, request_session_index_for_child, request_validator_groups,
 	request_validators, FromJobCommand, JobSender, Validator,
 };
-use polkadot_primitives::v1::{
-	BackedCandidate, CandidateCommitments, CandidateDescriptor, CandidateHash, CandidateReceipt,
-	CollatorId, CommittedCandidateReceipt, CoreIndex, CoreState, Hash, Id as ParaId, SessionIndex,
-	SigningContext, ValidatorId, ValidatorIndex, ValidatorSignature, ValidityAttestation,
+use polkadot_primitives::{
+	v1::{
+		BackedCandidate, CandidateCommitments, CandidateDescriptor, CandidateHash,
+		CandidateReceipt, CollatorId, CommittedCandidateReceipt, CoreIndex, CoreState, Hash,
+		Id as ParaId, SessionIndex, SigningContext, ValidatorId, ValidatorIndex,
+		ValidatorSignature, ValidityAttestation,
+	},
+	v2::slashing::{BackingMisbehavior, BackingMisbehaviorProof, BackingTimeSlot},
 };
 use polkadot_subsystem::{
 	jaeger,
 	messages::{
-		AllMessages, AvailabilityDistributionMessage, AvailabilityStoreMessage,
+		AvailabilityDistributionMessage, AvailabilityStoreMessage,
 		CandidateBackingMessage, CandidateValidationMessage, CollatorProtocolMessage,
 		DisputeCoordinatorMessage, ImportStatementsResult, ProvisionableData, ProvisionerMessage,
 		RuntimeApiRequest, StatementDistributionMessage, ValidationFailed,
 	},
-	overseer, ActivatedLeaf, PerLeafSpan, Stage, SubsystemSender,
+	overseer::{self, CandidateBackingOutgoingMessages, OutgoingMessagesOf},
+	ActivatedLeaf, PerLeafSpan, Stage, SubsystemSender,
 };
 use sp_keystore::SyncCryptoStorePtr;
 use statement_table::{
-	generic::AttestedCandidate as TableAttestedCandidate,
+	generic::{AttestedCandidate as TableAttestedCandidate, MultipleCandidates},
 	v1::{
-		SignedStatement as TableSignedStatement, Statement as TableStatement,
-		Summary as TableSummary,
+		Misbehavior as TableMisbehavior, SignedStatement as TableSignedStatement,
+		Statement as TableStatement, Summary as TableSummary,
 	},
 	Context as TableContextTrait, Table,
 };
@@ -71,6 +80,12 @@ mod tests;
 
 const LOG_TARGET: &str = "parachain::candidate-backing";
 
+/// The maximum number of lifecycle events kept per candidate.
+const MAX_TRACE_EVENTS: usize = 512;
+
+/// The maximum number of candidates whose lifecycle events are kept.
+const MAX_TRACED_CANDIDATES: usize = 1024;
+
 /// Errors that can occur in candidate backing.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -134,9 +149,9 @@ impl ValidatedCandidateCommand {
 	fn candidate_hash(&self) -> CandidateHash {
 		match *self {
 			ValidatedCandidateCommand::Second(Ok((ref candidate, _, _))) => candidate.hash(),
-			ValidatedCandidateCommand::Second(Err(ref candidate)) => candidate.hash(),
+			ValidatedCandidateCommand::Second(Err((ref candidate, _))) => candidate.hash(),
 			ValidatedCandidateCommand::Attest(Ok((ref candidate, _, _))) => candidate.hash(),
-			ValidatedCandidateCommand::Attest(Err(ref candidate)) => candidate.hash(),
+			ValidatedCandidateCommand::Attest(Err((ref candidate, _))) => candidate.hash(),
 			ValidatedCandidateCommand::AttestNoPoV(candidate_hash) => candidate_hash,
 		}
 	}
@@ -165,6 +180,8 @@ pub struct CandidateBackingJob {
 	/// The candidates that are includable, by hash. Each entry here indicates
 	/// that we've sent the provisioner the backed candidate.
 	backed: HashSet<CandidateHash>,
+	/// The lifecycle events of candidates, shared by all jobs.
+	traces: CandidateTraces,
 	keystore: SyncCryptoStorePtr,
 	table: Table<TableContext>,
 	table_context: TableContext,
@@ -252,6 +269,105 @@ fn primitive_statement_to_table(s: &SignedFullStatement) -> TableSignedStatement
 	}
 }
 
+/// The kind of the given statement, as recorded in the candidate trace.
+fn statement_kind(s: &Statement) -> BackingStatementKind {
+	match s {
+		Statement::Seconded(_) => BackingStatementKind::Seconded,
+		Statement::Valid(_) => BackingStatementKind::Valid,
+	}
+}
+
+/// Convert a misbehavior found by the statement table into evidence which can be submitted
+/// on-chain, if the misbehavior is provable by the signatures alone.
+fn misbehavior_evidence(
+	time_slot: BackingTimeSlot,
+	validator_index: ValidatorIndex,
+	misbehavior: &TableMisbehavior,
+) -> Option<BackingMisbehaviorProof> {
+	let misbehavior = match misbehavior {
+		TableMisbehavior::MultipleCandidates(MultipleCandidates { first, second }) =>
+			BackingMisbehavior::MultipleCandidates {
+				first: (first.0.hash(), first.1.clone()),
+				second: (second.0.hash(), second.1.clone()),
+			},
+		// Unauthorized statements can't be proven without the group assignments. Signing the same
+		// statement twice or seconding a candidate and also attesting its validity is harmless.
+		TableMisbehavior::ValidityDoubleVote(_) |
+		TableMisbehavior::UnauthorizedStatement(_) |
+		TableMisbehavior::DoubleSign(_) => return None,
+	};
+
+	Some(BackingMisbehaviorProof { time_slot, validator_index, misbehavior })
+}
+
+/// Submit evidence of a validator misbehaving in backing on-chain, along with a proof of the key
+/// ownership of the validator.
+///
+/// The runtime only accepts key ownership proofs of the session the statements were signed in,
+/// so the proof is obtained at the relay parent of the statements once we checked that its
+/// state is in that session.
+async fn report_misbehavior(
+	mut sender: JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
+	validator_id: ValidatorId,
+	misbehavior_proof: BackingMisbehaviorProof,
+) {
+	let BackingTimeSlot { session_index, relay_parent } = misbehavior_proof.time_slot.clone();
+	let validator_index = misbehavior_proof.validator_index;
+
+	match request_session_index_for_child(relay_parent, &mut sender).await.await {
+		Ok(Ok(session)) if session == session_index => {},
+		res => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?relay_parent,
+				?session_index,
+				?validator_index,
+				?res,
+				"Relay parent of backing misbehavior is not in the session of the statements",
+			);
+			return
+		},
+	}
+
+	let key_ownership_proof = match request_from_runtime(relay_parent, &mut sender, |tx| {
+		RuntimeApiRequest::KeyOwnershipProof(validator_id, tx)
+	})
+	.await
+	.await
+	{
+		Ok(Ok(Some(proof))) => proof,
+		res => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?relay_parent,
+				?validator_index,
+				?res,
+				"Could not obtain key ownership proof of misbehaving validator",
+			);
+			return
+		},
+	};
+
+	let submitted = request_from_runtime(relay_parent, &mut sender, |tx| {
+		RuntimeApiRequest::SubmitReportBackingMisbehavior(
+			misbehavior_proof,
+			key_ownership_proof,
+			tx,
+		)
+	})
+	.await
+	.await;
+
+	if !matches!(submitted, Ok(Ok(Some(())))) {
+		tracing::debug!(
+			target: LOG_TARGET,
+			?relay_parent,
+			?validator_index,
+			"Submitting backing misbehavior report failed",
+		);
+	}
+}
+
 fn table_attested_to_backed(
 	attested: TableAttestedCandidate<
 		ParaId,
@@ -302,7 +418,7 @@ fn table_attested_to_backed(
 }
 
 async fn store_available_data(
-	sender: &mut JobSender<impl SubsystemSender>,
+	sender: &mut JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
 	n_validators: u32,
 	candidate_hash: CandidateHash,
 	available_data: AvailableData,
@@ -327,7 +443,7 @@ async fn store_available_data(
 // This will compute the erasure root internally and compare it to the expected erasure root.
 // This returns `Err()` iff there is an internal error. Otherwise, it returns either `Ok(Ok(()))` or `Ok(Err(_))`.
 async fn make_pov_available(
-	sender: &mut JobSender<impl SubsystemSender>,
+	sender: &mut JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
 	n_validators: usize,
 	pov: Arc<PoV>,
 	candidate_hash: CandidateHash,
@@ -360,7 +476,7 @@ async fn make_pov_available(
 }
 
 async fn request_pov(
-	sender: &mut JobSender<impl SubsystemSender>,
+	sender: &mut JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
 	relay_parent: Hash,
 	from_validator: ValidatorIndex,
 	candidate_hash: CandidateHash,
@@ -382,7 +498,7 @@ async fn request_pov(
 }
 
 async fn request_candidate_validation(
-	sender: &mut JobSender<impl SubsystemSender>,
+	sender: &mut JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
 	candidate: CandidateDescriptor,
 	pov: Arc<PoV>,
 ) -> Result<ValidationResult, Error> {
@@ -404,10 +520,12 @@ async fn request_candidate_validation(
 	}
 }
 
+/// The result of background validation, either the validated candidate or the invalid candidate
+/// along with the reason for its invalidity.
 type BackgroundValidationResult =
-	Result<(CandidateReceipt, CandidateCommitments, Arc<PoV>), CandidateReceipt>;
+	Result<(CandidateReceipt, CandidateCommitments, Arc<PoV>), (CandidateReceipt, String)>;
 
-struct BackgroundValidationParams<S: overseer::SubsystemSender<AllMessages>, F> {
+struct BackgroundValidationParams<S: SubsystemSender<CandidateBackingOutgoingMessages>, F> {
 	sender: JobSender<S>,
 	tx_command: mpsc::Sender<ValidatedCandidateCommand>,
 	candidate: CandidateReceipt,
@@ -420,7 +538,7 @@ struct BackgroundValidationParams<S: overseer::SubsystemSender<AllMessages>, F>
 
 async fn validate_and_make_available(
 	params: BackgroundValidationParams<
-		impl SubsystemSender,
+		impl SubsystemSender<CandidateBackingOutgoingMessages>,
 		impl Fn(BackgroundValidationResult) -> ValidatedCandidateCommand + Sync,
 	>,
 ) -> Result<(), Error> {
@@ -482,7 +600,7 @@ async fn validate_and_make_available(
 					actual_commitments = ?commitments,
 					"Commitments obtained with validation don't match the announced by the candidate receipt",
 				);
-				Err(candidate)
+				Err((candidate, "commitments mismatch".into()))
 			} else {
 				let erasure_valid = make_pov_available(
 					&mut sender,
@@ -504,7 +622,7 @@ async fn validate_and_make_available(
 							actual_commitments = ?commitments,
 							"Erasure root doesn't match the announced by the candidate receipt",
 						);
-						Err(candidate)
+						Err((candidate, "erasure root mismatch".into()))
 					},
 				}
 			}
@@ -516,7 +634,7 @@ async fn validate_and_make_available(
 				reason = ?reason,
 				"Validation yielded an invalid candidate",
 			);
-			Err(candidate)
+			Err((candidate, format!("{:?}", reason)))
 		},
 	};
 
@@ -529,7 +647,7 @@ impl CandidateBackingJob {
 	/// Run asynchronously.
 	async fn run_loop(
 		mut self,
-		mut sender: JobSender<impl SubsystemSender>,
+		mut sender: JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
 		mut rx_to: mpsc::Receiver<CandidateBackingMessage>,
 		span: PerLeafSpan,
 	) -> Result<(), Error> {
@@ -561,7 +679,7 @@ impl CandidateBackingJob {
 	async fn handle_validated_candidate_command(
 		&mut self,
 		root_span: &jaeger::Span,
-		sender: &mut JobSender<impl SubsystemSender>,
+		sender: &mut JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
 		command: ValidatedCandidateCommand,
 	) -> Result<(), Error> {
 		let candidate_hash = command.candidate_hash();
@@ -571,6 +689,7 @@ impl CandidateBackingJob {
 			ValidatedCandidateCommand::Second(res) => {
 				match res {
 					Ok((candidate, commitments, _)) => {
+						self.trace(candidate_hash, BackingEvent::Valid);
 						// sanity check.
 						if self.seconded.is_none() &&
 							!self.issued_statements.contains(&candidate_hash)
@@ -596,7 +715,8 @@ impl CandidateBackingJob {
 							}
 						}
 					},
-					Err(candidate) => {
+					Err((candidate, reason)) => {
+						self.trace(candidate_hash, BackingEvent::Invalid { reason });
 						sender
 							.send_message(CollatorProtocolMessage::Invalid(self.parent, candidate))
 							.await;
@@ -606,6 +726,11 @@ impl CandidateBackingJob {
 			ValidatedCandidateCommand::Attest(res) => {
 				// We are done - avoid new validation spawns:
 				self.fallbacks.remove(&candidate_hash);
+				let event = match res {
+					Ok(_) => BackingEvent::Valid,
+					Err((_, ref reason)) => BackingEvent::Invalid { reason: reason.clone() },
+				};
+				self.trace(candidate_hash, event);
 				// sanity check.
 				if !self.issued_statements.contains(&candidate_hash) {
 					if res.is_ok() {
@@ -617,6 +742,10 @@ impl CandidateBackingJob {
 				}
 			},
 			ValidatedCandidateCommand::AttestNoPoV(candidate_hash) => {
+				if let Some((attesting, _)) = self.fallbacks.get(&candidate_hash) {
+					let from_validator = attesting.from_validator;
+					self.trace(candidate_hash, BackingEvent::PoVUnavailable { from_validator });
+				}
 				if let Some((attesting, span)) = self.fallbacks.get_mut(&candidate_hash) {
 					if let Some(index) = attesting.backing.pop() {
 						attesting.from_validator = index;
@@ -640,9 +769,9 @@ impl CandidateBackingJob {
 
 	async fn background_validate_and_make_available(
 		&mut self,
-		sender: &mut JobSender<impl SubsystemSender>,
+		sender: &mut JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
 		params: BackgroundValidationParams<
-			impl SubsystemSender,
+			impl SubsystemSender<CandidateBackingOutgoingMessages>,
 			impl Fn(BackgroundValidationResult) -> ValidatedCandidateCommand + Send + 'static + Sync,
 		>,
 	) -> Result<(), Error> {
@@ -679,7 +808,7 @@ impl CandidateBackingJob {
 		&mut self,
 		parent_span: &jaeger::Span,
 		root_span: &jaeger::Span,
-		sender: &mut JobSender<impl SubsystemSender>,
+		sender: &mut JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
 		candidate: &CandidateReceipt,
 		pov: Arc<PoV>,
 	) -> Result<(), Error> {
@@ -689,6 +818,7 @@ impl CandidateBackingJob {
 			.as_ref()
 			.map_or(false, |c| c != &candidate.descriptor().collator)
 		{
+			self.trace(candidate.hash(), BackingEvent::WrongCollator);
 			sender
 				.send_message(CollatorProtocolMessage::Invalid(self.parent, candidate.clone()))
 				.await;
@@ -696,6 +826,7 @@ impl CandidateBackingJob {
 		}
 
 		let candidate_hash = candidate.hash();
+		self.trace(candidate_hash, BackingEvent::ValidationStarted { from_validator: None });
 		let mut span = self.get_unbacked_validation_child(
 			root_span,
 			candidate_hash,
@@ -732,7 +863,7 @@ impl CandidateBackingJob {
 
 	async fn sign_import_and_distribute_statement(
 		&mut self,
-		sender: &mut JobSender<impl SubsystemSender>,
+		sender: &mut JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
 		statement: Statement,
 		root_span: &jaeger::Span,
 	) -> Result<Option<SignedFullStatement>, Error> {
@@ -748,23 +879,53 @@ impl CandidateBackingJob {
 	}
 
 	/// Check if there have happened any new misbehaviors and issue necessary messages.
-	async fn issue_new_misbehaviors(&mut self, sender: &mut JobSender<impl SubsystemSender>) {
+	///
+	/// Misbehaviors are found when importing statements, the given candidate is the one the
+	/// imported statement is about.
+	async fn issue_new_misbehaviors(
+		&mut self,
+		sender: &mut JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
+		candidate_hash: CandidateHash,
+	) {
 		// collect the misbehaviors to avoid double mutable self borrow issues
 		let misbehaviors: Vec<_> = self.table.drain_misbehaviors().collect();
 		for (validator_id, report) in misbehaviors {
+			self.metrics.on_misbehavior_reported();
+			self.trace(candidate_hash, BackingEvent::Misbehavior { validator: validator_id });
+
+			let time_slot =
+				BackingTimeSlot { session_index: self.session_index, relay_parent: self.parent };
+			let evidence = misbehavior_evidence(time_slot, validator_id, &report);
+
 			sender
 				.send_message(ProvisionerMessage::ProvisionableData(
 					self.parent,
 					ProvisionableData::MisbehaviorReport(self.parent, validator_id, report),
 				))
 				.await;
+
+			let validator_key = self.table_context.validators.get(validator_id.0 as usize);
+			if let (Some(evidence), Some(validator_key)) = (evidence, validator_key) {
+				let report =
+					report_misbehavior(sender.clone(), validator_key.clone(), evidence).boxed();
+				if let Err(error) = sender
+					.send_command(FromJobCommand::Spawn("backing-misbehavior-report", report))
+					.await
+				{
+					tracing::debug!(
+						target: LOG_TARGET,
+						?error,
+						"Failed to spawn backing misbehavior report",
+					);
+				}
+			}
 		}
 	}
 
 	/// Import a statement into the statement table and return the summary of the import.
 	async fn import_statement(
 		&mut self,
-		sender: &mut JobSender<impl SubsystemSender>,
+		sender: &mut JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
 		statement: &SignedFullStatement,
 		root_span: &jaeger::Span,
 	) -> Result<Option<TableSummary>, Error> {
@@ -776,6 +937,8 @@ impl CandidateBackingJob {
 		);
 
 		let candidate_hash = statement.payload().candidate_hash();
+		let validator = statement.validator_index();
+		let kind = statement_kind(statement.payload());
 		let import_statement_span = {
 			// create a span only for candidates we're already aware of.
 			self.get_unbacked_statement_child(
@@ -796,6 +959,7 @@ impl CandidateBackingJob {
 				validator_index = statement.validator_index().0,
 				"Supposedly 'Signed' statement has validator index out of bounds."
 			);
+			self.trace(candidate_hash, BackingEvent::StatementIgnored { validator, kind });
 
 			return Ok(None)
 		}
@@ -804,6 +968,21 @@ impl CandidateBackingJob {
 
 		let summary = self.table.import_statement(&self.table_context, stmt);
 
+		match summary {
+			Some(ref summary) => {
+				let requisite_votes = self.table_context.requisite_votes(&summary.group_id);
+				self.trace(candidate_hash, BackingEvent::Statement { validator, kind });
+				self.trace(
+					candidate_hash,
+					BackingEvent::Attestation {
+						validity_votes: summary.validity_votes as u32,
+						requisite_votes: requisite_votes as u32,
+					},
+				);
+			},
+			None => self.trace(candidate_hash, BackingEvent::StatementIgnored { validator, kind }),
+		}
+
 		let unbacked_span = if let Some(attested) = summary
 			.as_ref()
 			.and_then(|s| self.table.attested_candidate(&s.candidate, &self.table_context))
@@ -811,6 +990,7 @@ impl CandidateBackingJob {
 			let candidate_hash = attested.candidate.hash();
 			// `HashSet::insert` returns true if the thing wasn't in there already.
 			if self.backed.insert(candidate_hash) {
+				self.trace(candidate_hash, BackingEvent::Backed);
 				let span = self.remove_unbacked_span(&candidate_hash);
 
 				if let Some(backed) = table_attested_to_backed(attested, &self.table_context) {
@@ -840,7 +1020,7 @@ impl CandidateBackingJob {
 			None
 		};
 
-		self.issue_new_misbehaviors(sender).await;
+		self.issue_new_misbehaviors(sender, candidate_hash).await;
 
 		// It is important that the child span is dropped before its parent span (`unbacked_span`)
 		drop(import_statement_span);
@@ -863,7 +1043,7 @@ impl CandidateBackingJob {
 	/// is meant to check the signature and provenance of all statements before submission.
 	async fn dispatch_new_statement_to_dispute_coordinator(
 		&self,
-		sender: &mut JobSender<impl SubsystemSender>,
+		sender: &mut JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
 		candidate_hash: CandidateHash,
 		statement: &SignedFullStatement,
 	) -> Result<(), ValidatorIndexOutOfBounds> {
@@ -924,7 +1104,7 @@ impl CandidateBackingJob {
 	async fn process_msg(
 		&mut self,
 		root_span: &jaeger::Span,
-		sender: &mut JobSender<impl SubsystemSender>,
+		sender: &mut JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
 		msg: CandidateBackingMessage,
 	) -> Result<(), Error> {
 		match msg {
@@ -938,14 +1118,18 @@ impl CandidateBackingJob {
 					.with_candidate(candidate.hash())
 					.with_relay_parent(relay_parent);
 
+				let para_id = candidate.descriptor().para_id;
+				self.trace(candidate.hash(), BackingEvent::Received { para_id });
+
 				// Sanity check that candidate is from our assignment.
-				if Some(candidate.descriptor().para_id) != self.assignment {
+				if Some(para_id) != self.assignment {
 					tracing::debug!(
 						target: LOG_TARGET,
 						our_assignment = ?self.assignment,
 						collation = ?candidate.descriptor().para_id,
 						"Subsystem asked to second for para outside of our assignment",
 					);
+					self.trace(candidate.hash(), BackingEvent::NotOurAssignment { para_id });
 
 					return Ok(())
 				}
@@ -953,15 +1137,21 @@ impl CandidateBackingJob {
 				// If the message is a `CandidateBackingMessage::Second`, sign and dispatch a
 				// Seconded statement only if we have not seconded any other candidate and
 				// have not signed a Valid statement for the requested candidate.
-				if self.seconded.is_none() {
-					// This job has not seconded a candidate yet.
-					let candidate_hash = candidate.hash();
-
-					if !self.issued_statements.contains(&candidate_hash) {
-						let pov = Arc::new(pov);
-						self.validate_and_second(&span, &root_span, sender, &candidate, pov)
-							.await?;
-					}
+				match self.seconded {
+					None => {
+						// This job has not seconded a candidate yet.
+						let candidate_hash = candidate.hash();
+
+						if !self.issued_statements.contains(&candidate_hash) {
+							let pov = Arc::new(pov);
+							self.validate_and_second(&span, &root_span, sender, &candidate, pov)
+								.await?;
+						}
+					},
+					Some(seconded) => self.trace(
+						candidate.hash(),
+						BackingEvent::AlreadySeconded { seconded: seconded.0 },
+					),
 				}
 			},
 			CandidateBackingMessage::Statement(_relay_parent, statement) => {
@@ -992,6 +1182,9 @@ impl CandidateBackingJob {
 
 				tx.send(backed).map_err(|data| Error::Send(data))?;
 			},
+			CandidateBackingMessage::GetCandidateTrace(relay_parent, candidate_hash, tx) => {
+				let _ = tx.send(self.traces.get(relay_parent, candidate_hash));
+			},
 		}
 
 		Ok(())
@@ -1000,7 +1193,7 @@ impl CandidateBackingJob {
 	/// Kick off validation work and distribute the result as a signed statement.
 	async fn kick_off_validation_work(
 		&mut self,
-		sender: &mut JobSender<impl SubsystemSender>,
+		sender: &mut JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
 		attesting: AttestingData,
 		span: Option<jaeger::Span>,
 	) -> Result<(), Error> {
@@ -1025,9 +1218,13 @@ impl CandidateBackingJob {
 			//
 			// Act as though we've issued a statement.
 			self.issued_statements.insert(candidate_hash);
+			self.trace(candidate_hash, BackingEvent::WrongCollator);
 			return Ok(())
 		}
 
+		let from_validator = Some(attesting.from_validator);
+		self.trace(candidate_hash, BackingEvent::ValidationStarted { from_validator });
+
 		let bg_sender = sender.clone();
 		let pov = PoVData::FetchFromValidator {
 			from_validator: attesting.from_validator,
@@ -1054,7 +1251,7 @@ impl CandidateBackingJob {
 	async fn maybe_validate_and_import(
 		&mut self,
 		root_span: &jaeger::Span,
-		sender: &mut JobSender<impl SubsystemSender>,
+		sender: &mut JobSender<impl SubsystemSender<CandidateBackingOutgoingMessages>>,
 		statement: SignedFullStatement,
 	) -> Result<(), Error> {
 		if let Some(summary) = self.import_statement(sender, &statement, root_span).await? {
@@ -1177,19 +1374,88 @@ impl CandidateBackingJob {
 	fn remove_unbacked_span(&mut self, hash: &CandidateHash) -> Option<jaeger::Span> {
 		self.unbacked_candidates.remove(hash)
 	}
+
+	/// Record an event in the lifecycle of a candidate.
+	fn trace(&mut self, candidate_hash: CandidateHash, event: BackingEvent) {
+		self.traces.record(self.parent, candidate_hash, event);
+	}
+}
+
+/// The lifecycle events of the most recently traced candidates, bounded by
+/// `MAX_TRACED_CANDIDATES`.
+///
+/// The traces are shared by all backing jobs, so they can still be requested after the job of
+/// their relay parent finished.
+#[derive(Clone)]
+pub struct CandidateTraces(Arc<Mutex<LruCache<CandidateHash, (Hash, Vec<BackingTraceEntry>)>>>);
+
+impl Default for CandidateTraces {
+	fn default() -> Self {
+		CandidateTraces(Arc::new(Mutex::new(LruCache::new(MAX_TRACED_CANDIDATES))))
+	}
+}
+
+impl CandidateTraces {
+	/// Record an event in the lifecycle of a candidate at the given relay parent, ignoring it if
+	/// the trace of the candidate is full.
+	fn record(&self, relay_parent: Hash, candidate_hash: CandidateHash, event: BackingEvent) {
+		let timestamp = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(0, |elapsed| elapsed.as_millis() as u64);
+		let entry = BackingTraceEntry { timestamp, candidate_hash: candidate_hash.0, event };
+
+		let mut traces = self.0.lock();
+		match traces.get_mut(&candidate_hash) {
+			Some((_, trace)) if trace.len() >= MAX_TRACE_EVENTS => {},
+			Some((_, trace)) => trace.push(entry),
+			None => {
+				traces.put(candidate_hash, (relay_parent, vec![entry]));
+			},
+		}
+	}
+
+	/// The events of the given candidate, or of all candidates at the given relay parent if
+	/// none is given, oldest first.
+	fn get(
+		&self,
+		relay_parent: Hash,
+		candidate_hash: Option<CandidateHash>,
+	) -> Vec<BackingTraceEntry> {
+		let traces = self.0.lock();
+		let mut entries: Vec<_> = match candidate_hash {
+			Some(candidate_hash) => traces
+				.peek(&candidate_hash)
+				.filter(|(parent, _)| *parent == relay_parent)
+				.map_or_else(Vec::new, |(_, trace)| trace.clone()),
+			None => traces
+				.iter()
+				.filter(|(_, (parent, _))| *parent == relay_parent)
+				.flat_map(|(_, (_, trace))| trace.iter().cloned())
+				.collect(),
+		};
+
+		entries.sort_by_key(|entry| entry.timestamp);
+		entries
+	}
 }
 
 impl util::JobTrait for CandidateBackingJob {
 	type ToJob = CandidateBackingMessage;
 	type Error = Error;
-	type RunArgs = SyncCryptoStorePtr;
+	type RunArgs = (SyncCryptoStorePtr, CandidateTraces);
 	type Metrics = Metrics;
 
 	const NAME: &'static str = "candidate-backing-job";
 
-	fn run<S: SubsystemSender>(
+	fn handle_orphaned((_, traces): &Self::RunArgs, msg: Self::ToJob) {
+		if let CandidateBackingMessage::GetCandidateTrace(relay_parent, candidate_hash, tx) = msg {
+			let _ = tx.send(traces.get(relay_parent, candidate_hash));
+		}
+	}
+
+	fn run<S: SubsystemSender<OutgoingMessagesOf<Self::ToJob>>>(
 		leaf: ActivatedLeaf,
-		keystore: SyncCryptoStorePtr,
+		(keystore, traces): (SyncCryptoStorePtr, CandidateTraces),
 		metrics: Metrics,
 		rx_to: mpsc::Receiver<Self::ToJob>,
 		mut sender: JobSender<S>,
@@ -1308,6 +1574,7 @@ impl util::JobTrait for CandidateBackingJob {
 				seconded: None,
 				unbacked_candidates: HashMap::new(),
 				backed: HashSet::new(),
+				traces,
 				keystore,
 				table: Table::default(),
 				table_context,
@@ -1327,6 +1594,7 @@ impl util::JobTrait for CandidateBackingJob {
 struct MetricsInner {
 	signed_statements_total: prometheus::Counter<prometheus::U64>,
 	candidates_seconded_total: prometheus::Counter<prometheus::U64>,
+	misbehaviors_total: prometheus::Counter<prometheus::U64>,
 	process_second: prometheus::Histogram,
 	process_statement: prometheus::Histogram,
 	get_backed_candidates: prometheus::Histogram,
@@ -1349,6 +1617,12 @@ impl Metrics {
 		}
 	}
 
+	fn on_misbehavior_reported(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.misbehaviors_total.inc();
+		}
+	}
+
 	/// Provide a timer for handling `CandidateBackingMessage:Second` which observes on drop.
 	fn time_process_second(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.process_second.start_timer())
@@ -1384,6 +1658,13 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			misbehaviors_total: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_candidate_backing_misbehaviors_total",
+					"Number of misbehaviors by other validators found in the statement table.",
+				)?,
+				registry,
+			)?,
 			process_second: prometheus::register(
 				prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
 					"polkadot_parachain_candidate_backing_process_second",
@@ -23,11 +23,12 @@ use assert_matches::assert_matches;
 use futures::{future, Future};
 use polkadot_node_primitives::{BlockData, InvalidCandidate};
 use polkadot_node_subsystem_test_helpers as test_helpers;
-use polkadot_primitives::v1::{
-	CollatorId, GroupRotationInfo, HeadData, PersistedValidationData, ScheduledCore,
+use polkadot_primitives::{
+	v1::{CollatorId, GroupRotationInfo, HeadData, PersistedValidationData, ScheduledCore},
+	v2::slashing::OpaqueKeyOwnershipProof,
 };
 use polkadot_subsystem::{
-	messages::{CollatorProtocolMessage, RuntimeApiMessage, RuntimeApiRequest},
+	messages::{AllMessages, CollatorProtocolMessage, RuntimeApiMessage, RuntimeApiRequest},
 	ActivatedLeaf, ActiveLeavesUpdate, FromOverseer, LeafStatus, OverseerSignal,
 };
 use sp_application_crypto::AppKey;
@@ -153,7 +154,8 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 	let (context, virtual_overseer) = test_helpers::make_subsystem_context(pool.clone());
 
 	let subsystem =
-		CandidateBackingSubsystem::new(pool.clone(), keystore, Metrics(None)).run(context);
+		CandidateBackingSubsystem::new(pool.clone(), (keystore, Default::default()), Metrics(None))
+			.run(context);
 
 	let test_fut = test(virtual_overseer);
 
@@ -945,6 +947,103 @@ fn backing_misbehavior_works() {
 				).expect("signature must be valid");
 			}
 		);
+
+		// Seconding and validating the same candidate is harmless, so it is not reported on-chain,
+		// unlike seconding another candidate.
+		let candidate_b = TestCandidateBuilder {
+			para_id: test_state.chain_ids[0],
+			relay_parent: test_state.relay_parent,
+			pov_hash: Hash::repeat_byte(4),
+			head_data: expected_head_data.clone(),
+			..Default::default()
+		}
+		.build();
+		let candidate_b_hash = candidate_b.hash();
+
+		let seconded_2_b = SignedFullStatement::sign(
+			&test_state.keystore,
+			Statement::Seconded(candidate_b),
+			&test_state.signing_context,
+			ValidatorIndex(2),
+			&public2.into(),
+		)
+		.await
+		.ok()
+		.flatten()
+		.expect("should be signed");
+
+		let statement = CandidateBackingMessage::Statement(test_state.relay_parent, seconded_2_b);
+
+		virtual_overseer.send(FromOverseer::Communication { msg: statement }).await;
+
+		test_dispute_coordinator_notifications(
+			&mut virtual_overseer,
+			candidate_b_hash,
+			test_state.session(),
+			vec![ValidatorIndex(2)],
+		)
+		.await;
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::Provisioner(
+				ProvisionerMessage::ProvisionableData(
+					_,
+					ProvisionableData::MisbehaviorReport(
+						relay_parent,
+						ValidatorIndex(2),
+						Misbehavior::MultipleCandidates(_),
+					)
+				)
+			) if relay_parent == test_state.relay_parent
+		);
+
+		// The key ownership proof is obtained at the relay parent, as it is in the session of the
+		// statements.
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(
+					relay_parent,
+					RuntimeApiRequest::SessionIndexForChild(tx),
+				)
+			) if relay_parent == test_state.relay_parent => {
+				tx.send(Ok(test_state.session())).unwrap();
+			}
+		);
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(
+					relay_parent,
+					RuntimeApiRequest::KeyOwnershipProof(_, tx),
+				)
+			) if relay_parent == test_state.relay_parent => {
+				tx.send(Ok(Some(OpaqueKeyOwnershipProof::new(vec![1])))).unwrap();
+			}
+		);
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(
+					relay_parent,
+					RuntimeApiRequest::SubmitReportBackingMisbehavior(proof, _, tx),
+				)
+			) if relay_parent == test_state.relay_parent => {
+				assert_eq!(proof.validator_index, ValidatorIndex(2));
+				assert_eq!(proof.time_slot.session_index, test_state.session());
+				assert_matches!(
+					proof.misbehavior,
+					BackingMisbehavior::MultipleCandidates { first, second } => {
+						assert_eq!((first.0, second.0), (candidate_a_hash, candidate_b_hash));
+					}
+				);
+				assert!(proof.check(&test_state.validator_public[2]));
+				tx.send(Ok(Some(()))).unwrap();
+			}
+		);
 		virtual_overseer
 	});
 }
@@ -1369,6 +1468,94 @@ fn backing_doesnt_second_wrong_collator() {
 	});
 }
 
+#[test]
+fn candidate_trace_explains_why_not_backed() {
+	let mut test_state = TestState::default();
+	test_state.availability_cores[0] = CoreState::Scheduled(ScheduledCore {
+		para_id: ParaId::from(1),
+		collator: Some(Sr25519Keyring::Bob.public().into()),
+	});
+
+	test_harness(test_state.keystore.clone(), |mut virtual_overseer| async move {
+		test_startup(&mut virtual_overseer, &test_state).await;
+
+		let pov = PoV { block_data: BlockData(vec![42, 43, 44]) };
+
+		let expected_head_data = test_state.head_data.get(&test_state.chain_ids[0]).unwrap();
+
+		let pov_hash = pov.hash();
+		let candidate = TestCandidateBuilder {
+			para_id: test_state.chain_ids[0],
+			relay_parent: test_state.relay_parent,
+			pov_hash,
+			head_data: expected_head_data.clone(),
+			erasure_root: make_erasure_root(&test_state, pov.clone()),
+			..Default::default()
+		}
+		.build();
+
+		let second = CandidateBackingMessage::Second(
+			test_state.relay_parent,
+			candidate.to_plain(),
+			pov.clone(),
+		);
+
+		virtual_overseer.send(FromOverseer::Communication { msg: second }).await;
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::CollatorProtocol(CollatorProtocolMessage::Invalid(..))
+		);
+
+		let (tx, rx) = oneshot::channel();
+		let msg = CandidateBackingMessage::GetCandidateTrace(
+			test_state.relay_parent,
+			Some(candidate.hash()),
+			tx,
+		);
+		virtual_overseer.send(FromOverseer::Communication { msg }).await;
+
+		let events: Vec<_> = rx.await.unwrap().into_iter().map(|entry| entry.event).collect();
+		assert_eq!(
+			events,
+			vec![
+				BackingEvent::Received { para_id: test_state.chain_ids[0] },
+				BackingEvent::WrongCollator,
+			],
+		);
+
+		let (tx, rx) = oneshot::channel();
+		let msg = CandidateBackingMessage::GetCandidateTrace(
+			test_state.relay_parent,
+			Some(CandidateHash(dummy_hash())),
+			tx,
+		);
+		virtual_overseer.send(FromOverseer::Communication { msg }).await;
+		assert!(rx.await.unwrap().is_empty());
+
+		virtual_overseer
+			.send(FromOverseer::Signal(OverseerSignal::ActiveLeaves(
+				ActiveLeavesUpdate::stop_work(test_state.relay_parent),
+			)))
+			.await;
+
+		// The trace is kept after the job finished.
+		let (tx, rx) = oneshot::channel();
+		let msg = CandidateBackingMessage::GetCandidateTrace(test_state.relay_parent, None, tx);
+		virtual_overseer.send(FromOverseer::Communication { msg }).await;
+
+		let events: Vec<_> = rx.await.unwrap().into_iter().map(|entry| entry.event).collect();
+		assert_eq!(
+			events,
+			vec![
+				BackingEvent::Received { para_id: test_state.chain_ids[0] },
+				BackingEvent::WrongCollator,
+			],
+		);
+		virtual_overseer
+	});
+}
+
 #[test]
 fn validation_work_ignores_wrong_collator() {
 	let mut test_state = TestState::default();
@@ -34,6 +34,7 @@ use polkadot_node_subsystem::{
 		AvailabilityStoreMessage, BitfieldDistributionMessage, BitfieldSigningMessage,
 		RuntimeApiMessage, RuntimeApiRequest,
 	},
+	overseer::OutgoingMessagesOf,
 	ActivatedLeaf, LeafStatus, PerLeafSpan, SubsystemSender,
 };
 use polkadot_node_subsystem_util::{
@@ -81,10 +82,10 @@ pub enum Error {
 
 /// If there is a candidate pending availability, query the Availability Store
 /// for whether we have the availability chunk for our validator index.
-async fn get_core_availability(
+async fn get_core_availability<Outgoing: From<AvailabilityStoreMessage>>(
 	core: &CoreState,
 	validator_idx: ValidatorIndex,
-	sender: &Mutex<&mut impl SubsystemSender>,
+	sender: &Mutex<&mut impl SubsystemSender<Outgoing>>,
 	span: &jaeger::Span,
 ) -> Result<bool, Error> {
 	if let &CoreState::Occupied(ref core) = core {
@@ -121,9 +122,9 @@ async fn get_core_availability(
 }
 
 /// delegates to the v1 runtime API
-async fn get_availability_cores(
+async fn get_availability_cores<Outgoing: From<RuntimeApiMessage>>(
 	relay_parent: Hash,
-	sender: &mut impl SubsystemSender,
+	sender: &mut impl SubsystemSender<Outgoing>,
 ) -> Result<Vec<CoreState>, Error> {
 	let (tx, rx) = oneshot::channel();
 	sender
@@ -143,12 +144,15 @@ async fn get_availability_cores(
 /// - for each core, concurrently determine chunk availability (see `get_core_availability`)
 /// - return the bitfield if there were no errors at any point in this process
 ///   (otherwise, it's prone to false negatives)
-async fn construct_availability_bitfield(
+async fn construct_availability_bitfield<Outgoing>(
 	relay_parent: Hash,
 	span: &jaeger::Span,
 	validator_idx: ValidatorIndex,
-	sender: &mut impl SubsystemSender,
-) -> Result<AvailabilityBitfield, Error> {
+	sender: &mut impl SubsystemSender<Outgoing>,
+) -> Result<AvailabilityBitfield, Error>
+where
+	Outgoing: From<RuntimeApiMessage> + From<AvailabilityStoreMessage>,
+{
 	// get the set of availability cores from the runtime
 	let availability_cores = {
 		let _span = span.child("get-availability-cores");
@@ -236,7 +240,7 @@ impl JobTrait for BitfieldSigningJob {
 	const NAME: &'static str = "bitfield-signing-job";
 
 	/// Run a job for the parent block indicated
-	fn run<S: SubsystemSender>(
+	fn run<S: SubsystemSender<OutgoingMessagesOf<Self::ToJob>>>(
 		leaf: ActivatedLeaf,
 		keystore: Self::RunArgs,
 		metrics: Self::Metrics,
@@ -226,14 +226,15 @@ where
 
 struct RuntimeRequestFailed;
 
-async fn runtime_api_request<T, Sender>(
+async fn runtime_api_request<T, Sender, Outgoing>(
 	sender: &mut Sender,
 	relay_parent: Hash,
 	request: RuntimeApiRequest,
 	receiver: oneshot::Receiver<Result<T, RuntimeApiError>>,
 ) -> Result<T, RuntimeRequestFailed>
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<RuntimeApiMessage>,
 {
 	sender
 		.send_message(RuntimeApiMessage::Request(relay_parent, request).into())
@@ -260,13 +261,14 @@ where
 		})
 }
 
-async fn request_validation_code_by_hash<Sender>(
+async fn request_validation_code_by_hash<Sender, Outgoing>(
 	sender: &mut Sender,
 	relay_parent: Hash,
 	validation_code_hash: ValidationCodeHash,
 ) -> Result<Option<ValidationCode>, RuntimeRequestFailed>
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<RuntimeApiMessage>,
 {
 	let (tx, rx) = oneshot::channel();
 	runtime_api_request(
@@ -278,14 +280,15 @@ where
 	.await
 }
 
-async fn precheck_pvf<Sender>(
+async fn precheck_pvf<Sender, Outgoing>(
 	sender: &mut Sender,
 	mut validation_backend: impl ValidationBackend,
 	relay_parent: Hash,
 	validation_code_hash: ValidationCodeHash,
 ) -> PreCheckOutcome
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<RuntimeApiMessage>,
 {
 	let validation_code =
 		match request_validation_code_by_hash(sender, relay_parent, validation_code_hash).await {
@@ -334,13 +337,14 @@ enum AssumptionCheckOutcome {
 	BadRequest,
 }
 
-async fn check_assumption_validation_data<Sender>(
+async fn check_assumption_validation_data<Sender, Outgoing>(
 	sender: &mut Sender,
 	descriptor: &CandidateDescriptor,
 	assumption: OccupiedCoreAssumption,
 ) -> AssumptionCheckOutcome
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<RuntimeApiMessage>,
 {
 	let validation_data = {
 		let (tx, rx) = oneshot::channel();
@@ -379,12 +383,13 @@ where
 	}
 }
 
-async fn find_assumed_validation_data<Sender>(
+async fn find_assumed_validation_data<Sender, Outgoing>(
 	sender: &mut Sender,
 	descriptor: &CandidateDescriptor,
 ) -> AssumptionCheckOutcome
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<RuntimeApiMessage>,
 {
 	// The candidate descriptor has a `persisted_validation_data_hash` which corresponds to
 	// one of up to two possible values that we can derive from the state of the
@@ -413,7 +418,7 @@ where
 	AssumptionCheckOutcome::DoesNotMatch
 }
 
-async fn validate_from_chain_state<Sender>(
+async fn validate_from_chain_state<Sender, Outgoing>(
 	sender: &mut Sender,
 	validation_host: ValidationHost,
 	descriptor: CandidateDescriptor,
@@ -422,7 +427,8 @@ async fn validate_from_chain_state<Sender>(
 	metrics: &Metrics,
 ) -> Result<ValidationResult, ValidationFailed>
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<RuntimeApiMessage>,
 {
 	let (validation_data, validation_code) =
 		match find_assumed_validation_data(sender, &descriptor).await {
@@ -48,7 +48,7 @@ fn correctly_checks_included_assumption() {
 
 	let pool = TaskExecutor::new();
 	let (mut ctx, mut ctx_handle) =
-		test_helpers::make_subsystem_context::<AllMessages, _>(pool.clone());
+		test_helpers::make_subsystem_context::<CandidateValidationMessage, _>(pool.clone());
 
 	let (check_fut, check_result) = check_assumption_validation_data(
 		ctx.sender(),
@@ -120,7 +120,7 @@ fn correctly_checks_timed_out_assumption() {
 
 	let pool = TaskExecutor::new();
 	let (mut ctx, mut ctx_handle) =
-		test_helpers::make_subsystem_context::<AllMessages, _>(pool.clone());
+		test_helpers::make_subsystem_context::<CandidateValidationMessage, _>(pool.clone());
 
 	let (check_fut, check_result) = check_assumption_validation_data(
 		ctx.sender(),
@@ -190,7 +190,7 @@ fn check_is_bad_request_if_no_validation_data() {
 
 	let pool = TaskExecutor::new();
 	let (mut ctx, mut ctx_handle) =
-		test_helpers::make_subsystem_context::<AllMessages, _>(pool.clone());
+		test_helpers::make_subsystem_context::<CandidateValidationMessage, _>(pool.clone());
 
 	let (check_fut, check_result) = check_assumption_validation_data(
 		ctx.sender(),
@@ -244,7 +244,7 @@ fn check_is_bad_request_if_no_validation_code() {
 
 	let pool = TaskExecutor::new();
 	let (mut ctx, mut ctx_handle) =
-		test_helpers::make_subsystem_context::<AllMessages, _>(pool.clone());
+		test_helpers::make_subsystem_context::<CandidateValidationMessage, _>(pool.clone());
 
 	let (check_fut, check_result) = check_assumption_validation_data(
 		ctx.sender(),
@@ -310,7 +310,7 @@ fn check_does_not_match() {
 
 	let pool = TaskExecutor::new();
 	let (mut ctx, mut ctx_handle) =
-		test_helpers::make_subsystem_context::<AllMessages, _>(pool.clone());
+		test_helpers::make_subsystem_context::<CandidateValidationMessage, _>(pool.clone());
 
 	let (check_fut, check_result) = check_assumption_validation_data(
 		ctx.sender(),
@@ -721,7 +721,7 @@ fn precheck_works() {
 
 	let pool = TaskExecutor::new();
 	let (mut ctx, mut ctx_handle) =
-		test_helpers::make_subsystem_context::<AllMessages, _>(pool.clone());
+		test_helpers::make_subsystem_context::<CandidateValidationMessage, _>(pool.clone());
 
 	let (check_fut, check_result) = precheck_pvf(
 		ctx.sender(),
@@ -767,7 +767,7 @@ fn precheck_invalid_pvf_blob_compression() {
 
 	let pool = TaskExecutor::new();
 	let (mut ctx, mut ctx_handle) =
-		test_helpers::make_subsystem_context::<AllMessages, _>(pool.clone());
+		test_helpers::make_subsystem_context::<CandidateValidationMessage, _>(pool.clone());
 
 	let (check_fut, check_result) = precheck_pvf(
 		ctx.sender(),
@@ -809,7 +809,7 @@ fn precheck_properly_classifies_outcomes() {
 
 		let pool = TaskExecutor::new();
 		let (mut ctx, mut ctx_handle) =
-			test_helpers::make_subsystem_context::<AllMessages, _>(pool.clone());
+			test_helpers::make_subsystem_context::<CandidateValidationMessage, _>(pool.clone());
 
 		let (check_fut, check_result) = precheck_pvf(
 			ctx.sender(),
@@ -23,8 +23,12 @@
 //! ("CS_block_height", BigEndianBlockNumber) -> Vec<Hash>;
 //! ("CS_stagnant_at", BigEndianTimestamp) -> Vec<Hash>;
 //! ("CS_leaves") -> LeafEntrySet;
+//! ("CS_blacklisted", Hash) -> ();
 //! ```
 //!
+//! Manual blacklisting is stored separately from the `BlockEntry` so that entries written
+//! by earlier versions of the node can still be decoded.
+//!
 //! The big-endian encoding is used for creating iterators over the key-value DB which are
 //! accessible by prefix, to find the earliest block number stored as well as the all stagnant
 //! blocks.
@@ -49,6 +53,7 @@ const BLOCK_ENTRY_PREFIX: &[u8; 14] = b"CS_block_entry";
 const BLOCK_HEIGHT_PREFIX: &[u8; 15] = b"CS_block_height";
 const STAGNANT_AT_PREFIX: &[u8; 14] = b"CS_stagnant_at";
 const LEAVES_KEY: &[u8; 9] = b"CS_leaves";
+const BLACKLISTED_PREFIX: &[u8; 14] = b"CS_blacklisted";
 
 type Timestamp = u64;
 
@@ -103,6 +108,8 @@ impl From<ViabilityCriteria> for crate::ViabilityCriteria {
 	fn from(x: ViabilityCriteria) -> crate::ViabilityCriteria {
 		crate::ViabilityCriteria {
 			explicitly_reverted: x.explicitly_reverted,
+			// Stored under a separate key, see the module docs.
+			blacklisted: false,
 			approval: x.approval.into(),
 			earliest_unviable_ancestor: x.earliest_unviable_ancestor,
 		}
@@ -208,8 +215,19 @@ impl DbBackend {
 
 impl Backend for DbBackend {
 	fn load_block_entry(&self, hash: &Hash) -> Result<Option<crate::BlockEntry>, Error> {
-		load_decode::<BlockEntry>(&*self.inner, self.config.col_data, &block_entry_key(hash))
-			.map(|o| o.map(Into::into))
+		let entry =
+			load_decode::<BlockEntry>(&*self.inner, self.config.col_data, &block_entry_key(hash))?;
+
+		match entry {
+			None => Ok(None),
+			Some(entry) => {
+				let mut entry: crate::BlockEntry = entry.into();
+				entry.viability.blacklisted =
+					self.inner.get(self.config.col_data, &blacklisted_key(hash))?.is_some();
+
+				Ok(Some(entry))
+			},
+		}
 	}
 
 	fn load_leaves(&self) -> Result<crate::LeafEntrySet, Error> {
@@ -271,6 +289,16 @@ impl Backend for DbBackend {
 		for op in ops {
 			match op {
 				BackendWriteOp::WriteBlockEntry(block_entry) => {
+					if block_entry.viability.blacklisted {
+						tx.put_vec(
+							self.config.col_data,
+							&blacklisted_key(&block_entry.block_hash),
+							().encode(),
+						);
+					} else {
+						tx.delete(self.config.col_data, &blacklisted_key(&block_entry.block_hash));
+					}
+
 					let block_entry: BlockEntry = block_entry.into();
 					tx.put_vec(
 						self.config.col_data,
@@ -313,6 +341,7 @@ impl Backend for DbBackend {
 				},
 				BackendWriteOp::DeleteBlockEntry(hash) => {
 					tx.delete(self.config.col_data, &block_entry_key(&hash));
+					tx.delete(self.config.col_data, &blacklisted_key(&hash));
 				},
 				BackendWriteOp::DeleteStagnantAt(timestamp) => {
 					let timestamp: Timestamp = timestamp.into();
@@ -357,6 +386,13 @@ fn stagnant_at_key(timestamp: Timestamp) -> [u8; 14 + 8] {
 	key
 }
 
+fn blacklisted_key(hash: &Hash) -> [u8; 14 + 32] {
+	let mut key = [0; 14 + 32];
+	key[..14].copy_from_slice(BLACKLISTED_PREFIX);
+	hash.using_encoded(|s| key[14..].copy_from_slice(s));
+	key
+}
+
 fn decode_block_height_key(key: &[u8]) -> Option<BlockNumber> {
 	if key.len() != 15 + 4 {
 		return None
@@ -484,6 +520,43 @@ mod tests {
 		assert!(backend.load_block_entry(&block_entry.block_hash).unwrap().is_none());
 	}
 
+	#[test]
+	fn write_read_blacklisted_block_entry() {
+		let db = Arc::new(kvdb_memorydb::create(1));
+		let config = Config { col_data: 0 };
+
+		let mut backend = DbBackend::new(db, config);
+
+		let block_entry = BlockEntry {
+			block_hash: Hash::repeat_byte(1),
+			block_number: 1,
+			parent_hash: Hash::repeat_byte(0),
+			children: vec![],
+			viability: ViabilityCriteria {
+				earliest_unviable_ancestor: None,
+				explicitly_reverted: false,
+				approval: Approval::Unapproved,
+			},
+			weight: 100,
+		};
+
+		let mut blacklisted_entry: crate::BlockEntry = block_entry.clone().into();
+		blacklisted_entry.viability.blacklisted = true;
+
+		backend.write(vec![BackendWriteOp::WriteBlockEntry(blacklisted_entry)]).unwrap();
+
+		let loaded = backend.load_block_entry(&block_entry.block_hash).unwrap().unwrap();
+		assert!(loaded.viability.blacklisted);
+		assert_eq!(BlockEntry::from(loaded), block_entry);
+
+		backend
+			.write(vec![BackendWriteOp::WriteBlockEntry(block_entry.clone().into())])
+			.unwrap();
+
+		let loaded = backend.load_block_entry(&block_entry.block_hash).unwrap().unwrap();
+		assert!(!loaded.viability.blacklisted);
+	}
+
 	#[test]
 	fn earliest_block_number() {
 		let db = Arc::new(kvdb_memorydb::create(1));
@@ -70,10 +70,12 @@ impl Approval {
 struct ViabilityCriteria {
 	// Whether this block has been explicitly reverted by one of its descendants.
 	explicitly_reverted: bool,
+	// Whether this block has been manually blacklisted by the node operator.
+	blacklisted: bool,
 	// The approval state of this block specifically.
 	approval: Approval,
 	// The earliest unviable ancestor - the hash of the earliest unfinalized
-	// block in the ancestry which is explicitly reverted or stagnant.
+	// block in the ancestry which is explicitly reverted, blacklisted or stagnant.
 	earliest_unviable_ancestor: Option<Hash>,
 }
 
@@ -83,9 +85,9 @@ impl ViabilityCriteria {
 	}
 
 	// Whether the current block is explicitly viable.
-	// That is, whether the current block is neither reverted nor stagnant.
+	// That is, whether the current block is neither reverted, blacklisted nor stagnant.
 	fn is_explicitly_viable(&self) -> bool {
-		!self.explicitly_reverted && !self.approval.is_stagnant()
+		!self.explicitly_reverted && !self.blacklisted && !self.approval.is_stagnant()
 	}
 
 	// Whether the parent is viable. This assumes that the parent
@@ -424,6 +426,14 @@ where
 
 							let _ = tx.send(best_containing);
 						}
+						ChainSelectionMessage::Blacklist(hash, tx) => {
+							let known = handle_blacklist(backend, hash, true)?;
+							let _ = tx.send(known);
+						}
+						ChainSelectionMessage::ClearBlacklist(hash, tx) => {
+							let known = handle_blacklist(backend, hash, false)?;
+							let _ = tx.send(known);
+						}
 					}
 				}
 			}
@@ -435,7 +445,8 @@ where
 }
 
 async fn fetch_finalized(
-	ctx: &mut impl SubsystemContext,
+	ctx: &mut (impl SubsystemContext<Message = ChainSelectionMessage>
+	          + overseer::SubsystemContext<Message = ChainSelectionMessage>),
 ) -> Result<Option<(Hash, BlockNumber)>, Error> {
 	let (number_tx, number_rx) = oneshot::channel();
 
@@ -472,7 +483,8 @@ async fn fetch_finalized(
 }
 
 async fn fetch_header(
-	ctx: &mut impl SubsystemContext,
+	ctx: &mut (impl SubsystemContext<Message = ChainSelectionMessage>
+	          + overseer::SubsystemContext<Message = ChainSelectionMessage>),
 	hash: Hash,
 ) -> Result<Option<Header>, Error> {
 	let (tx, rx) = oneshot::channel();
@@ -485,7 +497,8 @@ async fn fetch_header(
 }
 
 async fn fetch_block_weight(
-	ctx: &mut impl SubsystemContext,
+	ctx: &mut (impl SubsystemContext<Message = ChainSelectionMessage>
+	          + overseer::SubsystemContext<Message = ChainSelectionMessage>),
 	hash: Hash,
 ) -> Result<Option<BlockWeight>, Error> {
 	let (tx, rx) = oneshot::channel();
@@ -501,7 +514,8 @@ async fn fetch_block_weight(
 
 // Handle a new active leaf.
 async fn handle_active_leaf(
-	ctx: &mut impl SubsystemContext,
+	ctx: &mut (impl SubsystemContext<Message = ChainSelectionMessage>
+	          + overseer::SubsystemContext<Message = ChainSelectionMessage>),
 	backend: &impl Backend,
 	stagnant_at: Timestamp,
 	hash: Hash,
@@ -636,6 +650,27 @@ fn handle_approved_block(backend: &mut impl Backend, approved_block: Hash) -> Re
 	backend.write(ops)
 }
 
+// Handle a request to blacklist a block or to clear its blacklisting.
+//
+// Returns `false` if the block is unknown.
+fn handle_blacklist(
+	backend: &mut impl Backend,
+	block_hash: Hash,
+	blacklisted: bool,
+) -> Result<bool, Error> {
+	let (known, ops) = {
+		let mut overlay = OverlayedBackend::new(&*backend);
+
+		let known = crate::tree::set_blacklisted(&mut overlay, block_hash, blacklisted)?;
+
+		(known, overlay.into_write_ops())
+	};
+
+	backend.write(ops)?;
+
+	Ok(known)
+}
+
 fn detect_stagnant(backend: &mut impl Backend, now: Timestamp) -> Result<(), Error> {
 	let ops = {
 		let overlay = crate::tree::detect_stagnant(&*backend, now)?;
@@ -649,7 +684,8 @@ fn detect_stagnant(backend: &mut impl Backend, now: Timestamp) -> Result<(), Err
 // Load the leaves from the backend. If there are no leaves, then return
 // the finalized block.
 async fn load_leaves(
-	ctx: &mut impl SubsystemContext,
+	ctx: &mut (impl SubsystemContext<Message = ChainSelectionMessage>
+	          + overseer::SubsystemContext<Message = ChainSelectionMessage>),
 	backend: &impl Backend,
 ) -> Result<Vec<Hash>, Error> {
 	let leaves: Vec<_> = backend.load_leaves()?.into_hashes_descending().collect();
@@ -633,6 +633,23 @@ async fn approve_block(
 	write_rx.await.unwrap()
 }
 
+async fn set_blacklisted(
+	virtual_overseer: &mut VirtualOverseer,
+	block_hash: Hash,
+	blacklisted: bool,
+) -> bool {
+	let (tx, rx) = oneshot::channel();
+	let msg = if blacklisted {
+		ChainSelectionMessage::Blacklist(block_hash, tx)
+	} else {
+		ChainSelectionMessage::ClearBlacklist(block_hash, tx)
+	};
+
+	virtual_overseer.send(FromOverseer::Communication { msg }).await;
+
+	rx.await.unwrap()
+}
+
 #[test]
 fn no_op_subsystem_run() {
 	test_harness(|_, _, virtual_overseer| async move { virtual_overseer });
@@ -2008,3 +2025,117 @@ fn stagnant_makes_childless_parent_leaf() {
 		virtual_overseer
 	})
 }
+
+#[test]
+fn blacklist_removes_viability_and_clearing_restores_it() {
+	test_harness(|backend, _, mut virtual_overseer| async move {
+		let finalized_number = 0;
+		let finalized_hash = Hash::repeat_byte(0);
+
+		// F <- A1 <- A2 <- A3
+		//      A1 <- B2
+
+		let (a3_hash, chain_a) =
+			construct_chain_on_base(vec![1, 2, 3], finalized_number, finalized_hash, |h| {
+				salt_header(h, b"a");
+			});
+
+		let (_, a1_hash, _) = extract_info_from_chain(0, &chain_a);
+		let (_, a2_hash, _) = extract_info_from_chain(1, &chain_a);
+
+		let (b2_hash, chain_b) =
+			construct_chain_on_base(vec![1], 1, a1_hash, |h| salt_header(h, b"b"));
+
+		import_chains_into_empty(
+			&mut virtual_overseer,
+			&backend,
+			finalized_number,
+			finalized_hash,
+			vec![chain_a.clone(), chain_b.clone()],
+		)
+		.await;
+
+		assert_leaves(&backend, vec![a3_hash, b2_hash]);
+
+		assert!(set_blacklisted(&mut virtual_overseer, a2_hash, true).await);
+
+		assert!(backend.load_block_entry(&a2_hash).unwrap().unwrap().viability.blacklisted);
+		let a3_entry = backend.load_block_entry(&a3_hash).unwrap().unwrap();
+		assert_eq!(a3_entry.viability.earliest_unviable_ancestor, Some(a2_hash));
+		assert_leaves(&backend, vec![b2_hash]);
+		assert_leaves_query(&mut virtual_overseer, vec![b2_hash]).await;
+
+		assert!(set_blacklisted(&mut virtual_overseer, a2_hash, false).await);
+
+		assert!(!backend.load_block_entry(&a2_hash).unwrap().unwrap().viability.blacklisted);
+		assert_leaves(&backend, vec![a3_hash, b2_hash]);
+		assert_leaves_query(&mut virtual_overseer, vec![a3_hash, b2_hash]).await;
+
+		virtual_overseer
+	})
+}
+
+#[test]
+fn blacklist_is_inherited_by_new_descendants() {
+	test_harness(|backend, _, mut virtual_overseer| async move {
+		let finalized_number = 0;
+		let finalized_hash = Hash::repeat_byte(0);
+
+		// F <- A1 <- A2
+		//
+		// A1 is blacklisted before A2 is imported.
+
+		let (a1_hash, chain_a) =
+			construct_chain_on_base(vec![1], finalized_number, finalized_hash, |_| {});
+
+		let (a2_hash, chain_a_ext) = construct_chain_on_base(vec![1], 1, a1_hash, |_| {});
+
+		import_chains_into_empty(
+			&mut virtual_overseer,
+			&backend,
+			finalized_number,
+			finalized_hash,
+			vec![chain_a.clone()],
+		)
+		.await;
+
+		assert!(set_blacklisted(&mut virtual_overseer, a1_hash, true).await);
+		assert_leaves(&backend, vec![]);
+
+		import_blocks_into(&mut virtual_overseer, &backend, None, chain_a_ext.clone()).await;
+
+		let a2_entry = backend.load_block_entry(&a2_hash).unwrap().unwrap();
+		assert_eq!(a2_entry.viability.earliest_unviable_ancestor, Some(a1_hash));
+		assert_leaves(&backend, vec![]);
+		assert_finalized_leaves_query(&mut virtual_overseer, finalized_number, finalized_hash)
+			.await;
+
+		virtual_overseer
+	})
+}
+
+#[test]
+fn blacklist_unknown_block_is_rejected() {
+	test_harness(|backend, _, mut virtual_overseer| async move {
+		let finalized_number = 0;
+		let finalized_hash = Hash::repeat_byte(0);
+
+		let (a1_hash, chain_a) =
+			construct_chain_on_base(vec![1], finalized_number, finalized_hash, |_| {});
+
+		import_chains_into_empty(
+			&mut virtual_overseer,
+			&backend,
+			finalized_number,
+			finalized_hash,
+			vec![chain_a.clone()],
+		)
+		.await;
+
+		assert!(!set_blacklisted(&mut virtual_overseer, Hash::repeat_byte(1), true).await);
+		assert!(!set_blacklisted(&mut virtual_overseer, finalized_hash, true).await);
+		assert_leaves(&backend, vec![a1_hash]);
+
+		virtual_overseer
+	})
+}
@@ -315,6 +315,7 @@ fn add_block(
 		viability: ViabilityCriteria {
 			earliest_unviable_ancestor: inherited_viability,
 			explicitly_reverted: false,
+			blacklisted: false,
 			approval: Approval::Unapproved,
 		},
 		weight,
@@ -525,6 +526,46 @@ pub(super) fn approve_block(
 	Ok(())
 }
 
+/// Manually blacklist a block, or clear its blacklisting, and update the viability
+/// of itself and its descendants accordingly.
+///
+/// Returns `false` if there is no entry for the block, e.g. because it is already finalized.
+pub(super) fn set_blacklisted(
+	backend: &mut OverlayedBackend<impl Backend>,
+	block_hash: Hash,
+	blacklisted: bool,
+) -> Result<bool, Error> {
+	let mut entry = match backend.load_block_entry(&block_hash)? {
+		None => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?block_hash,
+				blacklisted,
+				"Missing entry for block to (un)blacklist. Ignoring"
+			);
+
+			return Ok(false)
+		},
+		Some(entry) => entry,
+	};
+
+	tracing::info!(target: LOG_TARGET, ?block_hash, blacklisted, "Manually (un)blacklisting block");
+
+	let was_viable = entry.viability.is_viable();
+	entry.viability.blacklisted = blacklisted;
+	let is_viable = entry.viability.is_viable();
+
+	// Blacklisting can change the viability in either direction, depending
+	// on whether the mark is set or cleared.
+	if was_viable != is_viable {
+		propagate_viability_update(backend, entry)?;
+	} else {
+		backend.write_block_entry(entry);
+	}
+
+	Ok(true)
+}
+
 /// Check whether any blocks up to the given timestamp are stagnant and update
 /// accordingly.
 ///
@@ -26,7 +26,7 @@ use polkadot_primitives::v1::{CandidateHash, SessionIndex};
 
 use std::collections::HashMap;
 
-use super::db::v1::{CandidateVotes, RecentDisputes};
+use super::db::v1::{CandidateVotes, ParticipationQueue, RecentDisputes};
 use crate::error::FatalResult;
 
 #[derive(Debug)]
@@ -35,6 +35,7 @@ pub enum BackendWriteOp {
 	WriteRecentDisputes(RecentDisputes),
 	WriteCandidateVotes(SessionIndex, CandidateHash, CandidateVotes),
 	DeleteCandidateVotes(SessionIndex, CandidateHash),
+	WriteParticipationQueue(ParticipationQueue),
 }
 
 /// An abstraction over backend storage for the logic of this subsystem.
@@ -52,6 +53,9 @@ pub trait Backend {
 		candidate_hash: &CandidateHash,
 	) -> SubsystemResult<Option<CandidateVotes>>;
 
+	/// Load the persisted participation queue, if any.
+	fn load_participation_queue(&self) -> SubsystemResult<Option<ParticipationQueue>>;
+
 	/// Atomically writes the list of operations, with later operations taking precedence over
 	/// prior.
 	fn write<I>(&mut self, ops: I) -> FatalResult<()>
@@ -73,6 +77,8 @@ pub struct OverlayedBackend<'a, B: 'a> {
 	recent_disputes: Option<RecentDisputes>,
 	// `None` means deleted, missing means query inner.
 	candidate_votes: HashMap<(SessionIndex, CandidateHash), Option<CandidateVotes>>,
+	// `None` means unchanged.
+	participation_queue: Option<ParticipationQueue>,
 }
 
 impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
@@ -82,6 +88,7 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 			earliest_session: None,
 			recent_disputes: None,
 			candidate_votes: HashMap::new(),
+			participation_queue: None,
 		}
 	}
 
@@ -89,7 +96,8 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 	pub fn is_empty(&self) -> bool {
 		self.earliest_session.is_none() &&
 			self.recent_disputes.is_none() &&
-			self.candidate_votes.is_empty()
+			self.candidate_votes.is_empty() &&
+			self.participation_queue.is_none()
 	}
 
 	/// Load the earliest session, if any.
@@ -123,6 +131,15 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 		self.inner.load_candidate_votes(session, candidate_hash)
 	}
 
+	/// Load the persisted participation queue, if any.
+	pub fn load_participation_queue(&self) -> SubsystemResult<Option<ParticipationQueue>> {
+		if let Some(val) = &self.participation_queue {
+			return Ok(Some(val.clone()))
+		}
+
+		self.inner.load_participation_queue()
+	}
+
 	/// Prepare a write to the "earliest session" field of the DB.
 	///
 	/// Later calls to this function will override earlier ones.
@@ -156,6 +173,13 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 		self.candidate_votes.insert((session, candidate_hash), None);
 	}
 
+	/// Prepare a write of the participation queue.
+	///
+	/// Later calls to this function will override earlier ones.
+	pub fn write_participation_queue(&mut self, queue: ParticipationQueue) {
+		self.participation_queue = Some(queue);
+	}
+
 	/// Transform this backend into a set of write-ops to be written to the inner backend.
 	pub fn into_write_ops(self) -> impl Iterator<Item = BackendWriteOp> {
 		let earliest_session_ops = self
@@ -174,6 +198,14 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 					None => BackendWriteOp::DeleteCandidateVotes(session, candidate),
 				});
 
-		earliest_session_ops.chain(recent_dispute_ops).chain(candidate_vote_ops)
+		let participation_queue_ops = self
+			.participation_queue
+			.map(|q| BackendWriteOp::WriteParticipationQueue(q))
+			.into_iter();
+
+		earliest_session_ops
+			.chain(recent_dispute_ops)
+			.chain(candidate_vote_ops)
+			.chain(participation_queue_ops)
 	}
 }
@@ -18,7 +18,7 @@
 
 use polkadot_node_subsystem::{SubsystemError, SubsystemResult};
 use polkadot_primitives::v1::{
-	CandidateHash, CandidateReceipt, Hash, InvalidDisputeStatementKind, SessionIndex,
+	BlockNumber, CandidateHash, CandidateReceipt, Hash, InvalidDisputeStatementKind, SessionIndex,
 	ValidDisputeStatementKind, ValidatorIndex, ValidatorSignature,
 };
 
@@ -40,6 +40,7 @@ use crate::real::{
 const RECENT_DISPUTES_KEY: &[u8; 15] = b"recent-disputes";
 const EARLIEST_SESSION_KEY: &[u8; 16] = b"earliest-session";
 const CANDIDATE_VOTES_SUBKEY: &[u8; 15] = b"candidate-votes";
+const PARTICIPATION_QUEUE_KEY: &[u8; 19] = b"participation-queue";
 
 pub struct DbBackend {
 	inner: Arc<dyn KeyValueDB>,
@@ -72,6 +73,11 @@ impl Backend for DbBackend {
 		load_candidate_votes(&*self.inner, &self.config, session, candidate_hash)
 	}
 
+	/// Load the persisted participation queue, if any.
+	fn load_participation_queue(&self) -> SubsystemResult<Option<ParticipationQueue>> {
+		load_participation_queue(&*self.inner, &self.config)
+	}
+
 	/// Atomically writes the list of operations, with later operations taking precedence over
 	/// prior.
 	fn write<I>(&mut self, ops: I) -> FatalResult<()>
@@ -97,6 +103,9 @@ impl Backend for DbBackend {
 				BackendWriteOp::DeleteCandidateVotes(session, candidate_hash) => {
 					tx.delete(self.config.col_data, &candidate_votes_key(session, &candidate_hash));
 				},
+				BackendWriteOp::WriteParticipationQueue(queue) => {
+					tx.put_vec(self.config.col_data, PARTICIPATION_QUEUE_KEY, queue.encode());
+				},
 			}
 		}
 
@@ -156,6 +165,31 @@ impl From<polkadot_node_primitives::CandidateVotes> for CandidateVotes {
 /// The mapping for recent disputes; any which have not yet been pruned for being ancient.
 pub type RecentDisputes = std::collections::BTreeMap<(SessionIndex, CandidateHash), DisputeStatus>;
 
+/// A dispute participation which was queued or running, but had not yet finished.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct QueuedParticipation {
+	/// The receipt of the disputed candidate.
+	pub candidate_receipt: CandidateReceipt,
+	/// Session the candidate appeared in.
+	pub session: SessionIndex,
+	/// Number of validators in that session.
+	pub n_validators: u32,
+	/// Number of votes in the dispute at the time the participation got queued.
+	pub dispute_size: u32,
+	/// The validator who raised the dispute, if known.
+	pub initiator: Option<ValidatorIndex>,
+	/// Block number of the candidate's relay parent, if the participation was prioritized.
+	pub priority: Option<BlockNumber>,
+	/// How often the participation was requested, while it was queued best effort.
+	pub added_count: u32,
+}
+
+/// Participations we still have to carry out, persisted so they survive a restart.
+///
+/// Running participations come first, followed by the queued ones in the order they would have
+/// been carried out.
+pub type ParticipationQueue = Vec<QueuedParticipation>;
+
 /// Errors while accessing things from the DB.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -213,6 +247,15 @@ pub(crate) fn load_recent_disputes(
 		.map_err(|e| SubsystemError::with_origin("dispute-coordinator", e))
 }
 
+/// Load the persisted participation queue, if any.
+pub(crate) fn load_participation_queue(
+	db: &dyn KeyValueDB,
+	config: &ColumnConfiguration,
+) -> SubsystemResult<Option<ParticipationQueue>> {
+	load_decode(db, config.col_data, PARTICIPATION_QUEUE_KEY)
+		.map_err(|e| SubsystemError::with_origin("dispute-coordinator", e))
+}
+
 /// Maybe prune data in the DB based on the provided session index.
 ///
 /// This is intended to be called on every block, and as such will be used to populate the DB on
@@ -252,6 +295,15 @@ pub(crate) fn note_current_session(
 					}
 				}
 			}
+
+			// Participating in ancient disputes is pointless.
+			if let Some(mut queue) = overlay_db.load_participation_queue()? {
+				let prev_len = queue.len();
+				queue.retain(|queued| queued.session >= new_earliest);
+				if queue.len() != prev_len {
+					overlay_db.write_participation_queue(queue);
+				}
+			}
 		},
 		Some(_) => {
 			// nothing to do.
@@ -492,4 +544,44 @@ mod tests {
 			.is_some());
 		assert!(overlay_db.load_candidate_votes(very_recent, &hash_d).unwrap().is_some());
 	}
+
+	#[test]
+	fn participation_queue_is_persisted_and_pruned() {
+		let mut backend = make_db();
+
+		let queued = |session| QueuedParticipation {
+			candidate_receipt: dummy_candidate_receipt(dummy_hash()),
+			session,
+			n_validators: 10,
+			dispute_size: 2,
+			initiator: Some(ValidatorIndex(3)),
+			priority: Some(1),
+			added_count: 1,
+		};
+
+		let prev_earliest_session = 0;
+		let new_earliest_session = 5;
+		let current_session = 5 + DISPUTE_WINDOW.get();
+
+		let mut overlay_db = OverlayedBackend::new(&backend);
+		overlay_db.write_earliest_session(prev_earliest_session);
+		overlay_db.write_participation_queue(vec![queued(3), queued(new_earliest_session)]);
+		let write_ops = overlay_db.into_write_ops();
+		backend.write(write_ops).unwrap();
+
+		assert_eq!(
+			backend.load_participation_queue().unwrap().unwrap(),
+			vec![queued(3), queued(new_earliest_session)],
+		);
+
+		let mut overlay_db = OverlayedBackend::new(&backend);
+		note_current_session(&mut overlay_db, current_session).unwrap();
+		let write_ops = overlay_db.into_write_ops();
+		backend.write(write_ops).unwrap();
+
+		assert_eq!(
+			backend.load_participation_queue().unwrap().unwrap(),
+			vec![queued(new_earliest_session)],
+		);
+	}
 }
@@ -16,7 +16,10 @@
 
 //! Dispute coordinator subsystem in initialized state (after first active leaf is received).
 
-use std::{collections::HashSet, sync::Arc};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
 
 use futures::{
 	channel::{mpsc, oneshot},
@@ -32,7 +35,7 @@ use polkadot_node_primitives::{
 use polkadot_node_subsystem::{
 	messages::{
 		BlockDescription, DisputeCoordinatorMessage, DisputeDistributionMessage,
-		ImportStatementsResult, RuntimeApiMessage, RuntimeApiRequest,
+		ImportStatementsResult, RuntimeApiMessage, RuntimeApiRequest, RuntimeApiSender,
 	},
 	overseer, ActivatedLeaf, ActiveLeavesUpdate, FromOverseer, OverseerSignal, SubsystemContext,
 };
@@ -45,7 +48,10 @@ use polkadot_primitives::{
 		DisputeStatement, DisputeStatementSet, Hash, ScrapedOnChainVotes, SessionIndex,
 		ValidDisputeStatementKind, ValidatorId, ValidatorIndex, ValidatorPair, ValidatorSignature,
 	},
-	v2::SessionInfo,
+	v2::{
+		slashing::{DisputeProof, DisputesTimeSlot},
+		SessionInfo,
+	},
 };
 
 use crate::{metrics::Metrics, real::DisputeCoordinatorSubsystem, LOG_TARGET};
@@ -55,10 +61,17 @@ use crate::{
 	status::{get_active_with_status, Clock, DisputeStatus, Timestamp},
 };
 
+/// Number of blocks after which a slashing report, which was submitted but not yet applied, is
+/// submitted again.
+///
+/// Reports are only included by the node which submitted them, so they are likely to get
+/// dropped from the transaction pool before this node gets to author a block.
+pub(crate) const SLASHING_REPORT_RESUBMISSION_BLOCKS: BlockNumber = 600;
+
 use super::{
 	backend::Backend,
-	db,
-	ordering::{CandidateComparator, OrderingProvider},
+	db::{self, v1::QueuedParticipation},
+	ordering::OrderingProvider,
 	participation::{
 		self, Participation, ParticipationRequest, ParticipationStatement, WorkerMessageReceiver,
 	},
@@ -79,6 +92,12 @@ pub struct Initialized {
 	participation: Participation,
 	ordering_provider: OrderingProvider,
 	participation_receiver: WorkerMessageReceiver,
+	/// Slashing reports we submitted, with the number of the block they were submitted at.
+	submitted_slashing_reports: HashMap<(SessionIndex, CandidateHash, ValidatorIndex), BlockNumber>,
+	/// Slashing reports of past sessions we could not obtain a key ownership proof for.
+	///
+	/// Those will never become provable again, so they are not retried.
+	unprovable_slashing_reports: HashSet<(SessionIndex, CandidateHash, ValidatorIndex)>,
 	metrics: Metrics,
 }
 
@@ -104,6 +123,8 @@ impl Initialized {
 			ordering_provider,
 			participation,
 			participation_receiver,
+			submitted_slashing_reports: HashMap::new(),
+			unprovable_slashing_reports: HashSet::new(),
 			metrics,
 		}
 	}
@@ -115,7 +136,7 @@ impl Initialized {
 		mut self,
 		mut ctx: Context,
 		mut backend: B,
-		mut participations: Vec<(Option<CandidateComparator>, ParticipationRequest)>,
+		mut participations: Vec<QueuedParticipation>,
 		mut first_leaf: Option<ActivatedLeaf>,
 		clock: Box<dyn Clock>,
 	) -> FatalResult<()>
@@ -151,7 +172,7 @@ impl Initialized {
 		&mut self,
 		ctx: &mut Context,
 		backend: &mut B,
-		participations: &mut Vec<(Option<CandidateComparator>, ParticipationRequest)>,
+		participations: &mut Vec<QueuedParticipation>,
 		first_leaf: &mut Option<ActivatedLeaf>,
 		clock: &dyn Clock,
 	) -> Result<()>
@@ -160,21 +181,24 @@ impl Initialized {
 		Context: SubsystemContext<Message = DisputeCoordinatorMessage>,
 		B: Backend,
 	{
-		for (comparator, request) in participations.drain(..) {
-			self.participation.queue_participation(ctx, comparator, request).await?;
+		for queued in participations.drain(..) {
+			self.participation.restore_participation(ctx, queued).await?;
 		}
 		if let Some(first_leaf) = first_leaf.take() {
 			let mut overlay_db = OverlayedBackend::new(backend);
 			self.scrape_on_chain_votes(ctx, &mut overlay_db, first_leaf.hash, clock.now())
 				.await?;
-			if !overlay_db.is_empty() {
-				let ops = overlay_db.into_write_ops();
-				backend.write(ops)?;
-			}
 			// Also provide first leaf to participation for good measure.
 			self.participation
 				.process_active_leaves_update(ctx, &ActiveLeavesUpdate::start_work(first_leaf))
 				.await?;
+			if let Some(queue) = self.participation.take_queue_update() {
+				overlay_db.write_participation_queue(queue);
+			}
+			if !overlay_db.is_empty() {
+				let ops = overlay_db.into_write_ops();
+				backend.write(ops)?;
+			}
 		}
 
 		loop {
@@ -224,6 +248,10 @@ impl Initialized {
 					},
 				};
 
+			if let Some(queue) = self.participation.take_queue_update() {
+				overlay_db.write_participation_queue(queue);
+			}
+
 			if !overlay_db.is_empty() {
 				let ops = overlay_db.into_write_ops();
 				backend.write(ops)?;
@@ -245,8 +273,8 @@ impl Initialized {
 			.await?;
 		self.participation.process_active_leaves_update(ctx, &update).await?;
 
-		let new_activations = update.activated.into_iter().map(|a| a.hash);
-		for new_leaf in new_activations {
+		let new_activations = update.activated.into_iter().map(|a| (a.hash, a.number));
+		for (new_leaf, new_leaf_number) in new_activations {
 			match self.rolling_session_window.cache_session_info_for_head(ctx, new_leaf).await {
 				Err(e) => {
 					tracing::warn!(
@@ -278,11 +306,161 @@ impl Initialized {
 				Ok(SessionWindowUpdate::Unchanged) => {},
 			};
 			self.scrape_on_chain_votes(ctx, overlay_db, new_leaf, now).await?;
+			self.process_unapplied_slashes(ctx, overlay_db, new_leaf, new_leaf_number).await;
 		}
 
 		Ok(())
 	}
 
+	/// For each pending slash of a lost dispute, obtain a key ownership proof of the losing
+	/// validators and submit a slashing report, so the offence can get reported on-chain.
+	///
+	/// Key ownership proofs only prove that a key belonged to a validator in the session of the
+	/// block they were generated at, so they are obtained at a block of the dispute's session, see
+	/// `key_ownership_proof_block`.
+	///
+	/// Reports are only submitted again if they were not applied within
+	/// `SLASHING_REPORT_RESUBMISSION_BLOCKS`.
+	async fn process_unapplied_slashes(
+		&mut self,
+		ctx: &mut (impl SubsystemContext<Message = DisputeCoordinatorMessage>
+		          + overseer::SubsystemContext<Message = DisputeCoordinatorMessage>),
+		overlay_db: &mut OverlayedBackend<'_, impl Backend>,
+		relay_parent: Hash,
+		relay_parent_number: BlockNumber,
+	) {
+		let pending = match request_runtime(ctx, relay_parent, RuntimeApiRequest::UnappliedSlashes)
+			.await
+		{
+			Some(pending) => pending,
+			None => return,
+		};
+
+		// Forget about reports which got applied or pruned.
+		let still_pending: HashSet<_> = pending
+			.iter()
+			.flat_map(|(session_index, candidate_hash, pending)| {
+				pending.keys.keys().map(move |index| (*session_index, *candidate_hash, *index))
+			})
+			.collect();
+		self.submitted_slashing_reports.retain(|key, _| still_pending.contains(key));
+		self.unprovable_slashing_reports.retain(|key| still_pending.contains(key));
+
+		for (session_index, candidate_hash, pending) in pending {
+			tracing::info!(
+				target: LOG_TARGET,
+				?session_index,
+				?candidate_hash,
+				n_slashes = pending.keys.len(),
+				"Processing unapplied dispute slashes",
+			);
+
+			let is_current_session = session_index == self.highest_session;
+			let proof_block = self.key_ownership_proof_block(
+				overlay_db,
+				session_index,
+				&candidate_hash,
+				relay_parent,
+			);
+
+			for (validator_index, validator_id) in pending.keys {
+				let key = (session_index, candidate_hash, validator_index);
+				if self.unprovable_slashing_reports.contains(&key) {
+					continue
+				}
+				if let Some(submitted_at) = self.submitted_slashing_reports.get(&key) {
+					if relay_parent_number < submitted_at + SLASHING_REPORT_RESUBMISSION_BLOCKS {
+						continue
+					}
+				}
+
+				let key_ownership_proof = match proof_block {
+					Some(proof_block) => request_runtime(ctx, proof_block, |tx| {
+						RuntimeApiRequest::KeyOwnershipProof(validator_id.clone(), tx)
+					})
+					.await
+					.flatten(),
+					None => None,
+				};
+				let key_ownership_proof = match key_ownership_proof {
+					Some(proof) => proof,
+					None => {
+						tracing::debug!(
+							target: LOG_TARGET,
+							?session_index,
+							?candidate_hash,
+							?validator_id,
+							"Could not obtain key ownership proof for dispute loser",
+						);
+						// The state of past sessions only ever gets pruned, so there is no point
+						// in trying again:
+						if !is_current_session {
+							self.unprovable_slashing_reports.insert(key);
+						}
+						continue
+					},
+				};
+
+				let dispute_proof = DisputeProof {
+					time_slot: DisputesTimeSlot::new(session_index, candidate_hash),
+					kind: pending.kind,
+					validator_index,
+					validator_id,
+				};
+
+				let submitted = request_runtime(ctx, relay_parent, |tx| {
+					RuntimeApiRequest::SubmitReportDisputeLost(
+						dispute_proof,
+						key_ownership_proof,
+						tx,
+					)
+				})
+				.await;
+
+				if submitted.flatten().is_some() {
+					self.submitted_slashing_reports.insert(key, relay_parent_number);
+				} else {
+					tracing::debug!(
+						target: LOG_TARGET,
+						?session_index,
+						?candidate_hash,
+						?validator_index,
+						"Submitting dispute slashing report failed",
+					);
+				}
+			}
+		}
+	}
+
+	/// The block to obtain key ownership proofs for a dispute of the given session at.
+	///
+	/// That is the leaf, if it is in the dispute's session, or otherwise the relay parent of the
+	/// disputed candidate, as the session of a candidate is the session of its relay parent.
+	fn key_ownership_proof_block(
+		&self,
+		overlay_db: &OverlayedBackend<'_, impl Backend>,
+		session: SessionIndex,
+		candidate_hash: &CandidateHash,
+		leaf: Hash,
+	) -> Option<Hash> {
+		if session == self.highest_session {
+			return Some(leaf)
+		}
+		match overlay_db.load_candidate_votes(session, candidate_hash) {
+			Ok(votes) => votes.map(|votes| votes.candidate_receipt.descriptor.relay_parent),
+			Err(err) => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					?session,
+					?candidate_hash,
+					?err,
+					"Could not load votes of disputed candidate",
+				);
+				None
+			},
+		}
+	}
+
 	/// Scrapes on-chain votes (backing votes and concluded disputes) for a active leaf of the
 	/// relay chain.
 	async fn scrape_on_chain_votes(
@@ -495,7 +673,8 @@ impl Initialized {
 
 	async fn handle_incoming(
 		&mut self,
-		ctx: &mut impl SubsystemContext,
+		ctx: &mut (impl SubsystemContext<Message = DisputeCoordinatorMessage>
+		          + overseer::SubsystemContext<Message = DisputeCoordinatorMessage>),
 		overlay_db: &mut OverlayedBackend<'_, impl Backend>,
 		message: DisputeCoordinatorMessage,
 		now: Timestamp,
@@ -597,7 +776,8 @@ impl Initialized {
 
 	async fn handle_import_statements(
 		&mut self,
-		ctx: &mut impl SubsystemContext,
+		ctx: &mut (impl SubsystemContext<Message = DisputeCoordinatorMessage>
+		          + overseer::SubsystemContext<Message = DisputeCoordinatorMessage>),
 		overlay_db: &mut OverlayedBackend<'_, impl Backend>,
 		candidate_hash: CandidateHash,
 		candidate_receipt: MaybeCandidateReceipt,
@@ -778,7 +958,13 @@ impl Initialized {
 				.queue_participation(
 					ctx,
 					comparator,
-					ParticipationRequest::new(candidate_receipt, session, n_validators),
+					ParticipationRequest::new(
+						candidate_receipt,
+						session,
+						n_validators,
+						votes.voted_indices().len() as u32,
+						dispute_initiator(&votes),
+					),
 				)
 				.await;
 			log_error(r)?;
@@ -841,7 +1027,8 @@ impl Initialized {
 
 	async fn issue_local_statement(
 		&mut self,
-		ctx: &mut impl SubsystemContext,
+		ctx: &mut (impl SubsystemContext<Message = DisputeCoordinatorMessage>
+		          + overseer::SubsystemContext<Message = DisputeCoordinatorMessage>),
 		overlay_db: &mut OverlayedBackend<'_, impl Backend>,
 		candidate_hash: CandidateHash,
 		candidate_receipt: CandidateReceipt,
@@ -1025,6 +1212,41 @@ enum DisputeMessageCreationError {
 	InvalidStatementCombination(DisputeMessageCheckError),
 }
 
+/// Issue a runtime API request at the given relay parent.
+///
+/// Errors are logged and result in `None`.
+async fn request_runtime<T>(
+	ctx: &mut (impl SubsystemContext<Message = DisputeCoordinatorMessage>
+	          + overseer::SubsystemContext<Message = DisputeCoordinatorMessage>),
+	relay_parent: Hash,
+	request: impl FnOnce(RuntimeApiSender<T>) -> RuntimeApiRequest,
+) -> Option<T> {
+	let (tx, rx) = oneshot::channel();
+	ctx.send_message(RuntimeApiMessage::Request(relay_parent, request(tx))).await;
+
+	match rx.await {
+		Ok(Ok(res)) => Some(res),
+		Ok(Err(e)) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?relay_parent,
+				error = ?e,
+				"Runtime API request failed",
+			);
+			None
+		},
+		Err(e) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?relay_parent,
+				error = ?e,
+				"Runtime API request was canceled",
+			);
+			None
+		},
+	}
+}
+
 fn make_dispute_message(
 	info: &SessionInfo,
 	votes: &CandidateVotes,
@@ -1117,6 +1339,14 @@ fn determine_undisputed_chain(
 	Ok(last)
 }
 
+/// The validator we consider the initiator of a dispute.
+///
+/// Disputes are raised by casting an invalid vote, so this is the first validator (by index)
+/// who voted against the candidate. Used for rate accounting of participations.
+pub(crate) fn dispute_initiator(votes: &CandidateVotes) -> Option<ValidatorIndex> {
+	votes.invalid.iter().map(|(_, index, _)| *index).min()
+}
+
 fn find_controlled_validator_indices(
 	keystore: &LocalKeystore,
 	validators: &[ValidatorId],
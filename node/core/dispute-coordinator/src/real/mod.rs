@@ -24,7 +24,10 @@
 //! validation results as well as a sink for votes received by other subsystems. When importing a dispute vote from
 //! another node, this will trigger dispute participation to recover and validate the block.
 
-use std::{collections::HashSet, sync::Arc};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
 
 use futures::FutureExt;
 use kvdb::KeyValueDB;
@@ -46,13 +49,9 @@ use crate::{
 };
 
 use backend::{Backend, OverlayedBackend};
-use db::v1::DbBackend;
+use db::v1::{DbBackend, QueuedParticipation};
 
-use self::{
-	ordering::CandidateComparator,
-	participation::ParticipationRequest,
-	spam_slots::{SpamSlots, UnconfirmedDisputes},
-};
+use self::spam_slots::{SpamSlots, UnconfirmedDisputes};
 
 pub(crate) mod backend;
 pub(crate) mod db;
@@ -178,7 +177,7 @@ impl DisputeCoordinatorSubsystem {
 		clock: &(dyn Clock),
 	) -> FatalResult<
 		Option<(
-			Vec<(Option<CandidateComparator>, ParticipationRequest)>,
+			Vec<QueuedParticipation>,
 			ActivatedLeaf,
 			Initialized,
 			B,
@@ -248,7 +247,7 @@ impl DisputeCoordinatorSubsystem {
 		overlay_db: &mut OverlayedBackend<'_, impl Backend>,
 		clock: &dyn Clock,
 	) -> Result<(
-		Vec<(Option<CandidateComparator>, ParticipationRequest)>,
+		Vec<QueuedParticipation>,
 		SpamSlots,
 		OrderingProvider,
 	)>
@@ -273,6 +272,27 @@ impl DisputeCoordinatorSubsystem {
 			},
 		};
 
+		// Participations which were still queued or running on shutdown, they are restored in
+		// their persisted order:
+		let mut restored_participations: Vec<QueuedParticipation> =
+			match overlay_db.load_participation_queue() {
+				Ok(queue) => queue.unwrap_or_default(),
+				Err(e) => {
+					tracing::error!(
+						target: LOG_TARGET,
+						"Failed initial load of participation queue: {:?}",
+						e
+					);
+					Vec::new()
+				},
+			};
+		let restored_positions: HashMap<_, _> = restored_participations
+			.iter()
+			.enumerate()
+			.map(|(pos, queued)| ((queued.session, queued.candidate_receipt.hash()), pos))
+			.collect();
+		let mut still_needed = HashSet::new();
+
 		let mut participation_requests = Vec::new();
 		let mut unconfirmed_disputes: UnconfirmedDisputes = UnconfirmedDisputes::new();
 		let mut ordering_provider = OrderingProvider::new(ctx.sender(), initial_head).await?;
@@ -305,6 +325,7 @@ impl DisputeCoordinatorSubsystem {
 
 			let n_validators = validators.len();
 			let voted_indices: HashSet<_> = votes.voted_indices().into_iter().collect();
+			let dispute_size = voted_indices.len() as u32;
 
 			// Determine if there are any missing local statements for this dispute. Validators are
 			// filtered if:
@@ -335,17 +356,37 @@ impl DisputeCoordinatorSubsystem {
 			// Participate for all non-concluded disputes which do not have a
 			// recorded local statement.
 			if missing_local_statement {
-				participation_requests.push((
-					candidate_comparator,
-					ParticipationRequest::new(
-						votes.candidate_receipt.clone(),
+				let priority = candidate_comparator.map(|c| c.relay_parent_block_number());
+				match restored_positions.get(&(session, *candidate_hash)) {
+					Some(pos) => {
+						let queued = &mut restored_participations[*pos];
+						// The dispute might have grown since the participation got persisted.
+						queued.n_validators = n_validators as u32;
+						queued.dispute_size = queued.dispute_size.max(dispute_size);
+						// The including block might not be known anymore, keep the priority then:
+						queued.priority = priority.or(queued.priority);
+					},
+					None => participation_requests.push(QueuedParticipation {
+						candidate_receipt: votes.candidate_receipt.clone(),
 						session,
-						n_validators,
-					),
-				));
+						n_validators: n_validators as u32,
+						dispute_size,
+						initiator: initialized::dispute_initiator(&votes),
+						priority,
+						added_count: 1,
+					}),
+				}
+				still_needed.insert((session, *candidate_hash));
 			}
 		}
 
+		// Only restore participations which are still needed, ahead of newly found ones:
+		let participation_requests = restored_participations
+			.into_iter()
+			.filter(|q| still_needed.contains(&(q.session, q.candidate_receipt.hash())))
+			.chain(participation_requests)
+			.collect();
+
 		Ok((
 			participation_requests,
 			SpamSlots::recover_from_state(unconfirmed_disputes),
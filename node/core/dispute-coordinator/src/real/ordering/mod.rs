@@ -23,7 +23,8 @@ use futures::channel::oneshot;
 use lru::LruCache;
 
 use polkadot_node_subsystem::{
-	messages::ChainApiMessage, ActivatedLeaf, ActiveLeavesUpdate, ChainApiError, SubsystemSender,
+	messages::ChainApiMessage, overseer::DisputeCoordinatorOutgoingMessages, ActivatedLeaf,
+	ActiveLeavesUpdate, ChainApiError, SubsystemSender,
 };
 use polkadot_node_subsystem_util::runtime::get_candidate_events;
 use polkadot_primitives::v1::{BlockNumber, CandidateEvent, CandidateHash, CandidateReceipt, Hash};
@@ -119,6 +120,10 @@ impl CandidateComparator {
 	pub fn new_dummy(block_number: BlockNumber, candidate_hash: CandidateHash) -> Self {
 		Self { relay_parent_block_number: block_number, candidate_hash }
 	}
+	/// Block number of the relay parent of the candidate.
+	pub fn relay_parent_block_number(&self) -> BlockNumber {
+		self.relay_parent_block_number
+	}
 	/// Check whether the given candidate hash belongs to this comparator.
 	pub fn matches_candidate(&self, candidate_hash: &CandidateHash) -> bool {
 		&self.candidate_hash == candidate_hash
@@ -132,7 +137,7 @@ impl OrderingProvider {
 	pub(crate) const ANCESTRY_SIZE_LIMIT: usize = 1000;
 
 	/// Create a properly initialized `OrderingProvider`.
-	pub async fn new<Sender: SubsystemSender>(
+	pub async fn new<Sender: SubsystemSender<DisputeCoordinatorOutgoingMessages>>(
 		sender: &mut Sender,
 		initial_head: ActivatedLeaf,
 	) -> Result<Self> {
@@ -153,7 +158,7 @@ impl OrderingProvider {
 	/// should use spam slots for such disputes.
 	pub async fn candidate_comparator<'a>(
 		&mut self,
-		sender: &mut impl SubsystemSender,
+		sender: &mut impl SubsystemSender<DisputeCoordinatorOutgoingMessages>,
 		candidate: &CandidateReceipt,
 	) -> FatalResult<Option<CandidateComparator>> {
 		let candidate_hash = candidate.hash();
@@ -178,11 +183,14 @@ impl OrderingProvider {
 	/// Query active leaves for any candidate `CandidateEvent::CandidateIncluded` events.
 	///
 	/// and updates current heads, so we can query candidates for all non finalized blocks.
-	pub async fn process_active_leaves_update<Sender: SubsystemSender>(
+	pub async fn process_active_leaves_update<Sender>(
 		&mut self,
 		sender: &mut Sender,
 		update: &ActiveLeavesUpdate,
-	) -> Result<()> {
+	) -> Result<()>
+	where
+		Sender: SubsystemSender<DisputeCoordinatorOutgoingMessages>,
+	{
 		if let Some(activated) = update.activated.as_ref() {
 			// Fetch ancestors of the activated leaf.
 			let ancestors = self
@@ -248,7 +256,7 @@ impl OrderingProvider {
 	/// doesn't rely on block numbers.
 	///
 	/// Both `head` and last are **not** included in the result.
-	async fn get_block_ancestors<Sender: SubsystemSender>(
+	async fn get_block_ancestors<Sender: SubsystemSender<DisputeCoordinatorOutgoingMessages>>(
 		&mut self,
 		sender: &mut Sender,
 		mut head: Hash,
@@ -327,7 +335,7 @@ async fn send_message_fatal<Sender, Response>(
 	receiver: oneshot::Receiver<std::result::Result<Response, ChainApiError>>,
 ) -> FatalResult<Response>
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<DisputeCoordinatorOutgoingMessages>,
 {
 	sender.send_message(message.into()).await;
 
@@ -338,14 +346,16 @@ where
 }
 
 async fn get_block_number(
-	sender: &mut impl SubsystemSender,
+	sender: &mut impl SubsystemSender<DisputeCoordinatorOutgoingMessages>,
 	relay_parent: Hash,
 ) -> FatalResult<Option<BlockNumber>> {
 	let (tx, rx) = oneshot::channel();
 	send_message_fatal(sender, ChainApiMessage::BlockNumber(relay_parent, tx), rx).await
 }
 
-async fn get_finalized_block_number(sender: &mut impl SubsystemSender) -> FatalResult<BlockNumber> {
+async fn get_finalized_block_number(
+	sender: &mut impl SubsystemSender<DisputeCoordinatorOutgoingMessages>,
+) -> FatalResult<BlockNumber> {
 	let (number_tx, number_rx) = oneshot::channel();
 	send_message_fatal(sender, ChainApiMessage::FinalizedBlockNumber(number_tx), number_rx).await
 }
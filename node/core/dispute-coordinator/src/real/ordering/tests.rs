@@ -98,7 +98,7 @@ fn next_leaf(chain: &mut Vec<Hash>) -> ActivatedLeaf {
 }
 
 async fn process_active_leaves_update(
-	sender: &mut TestSubsystemSender,
+	sender: &mut TestSubsystemSender<DisputeCoordinatorMessage>,
 	ordering: &mut OrderingProvider,
 	update: ActivatedLeaf,
 ) {
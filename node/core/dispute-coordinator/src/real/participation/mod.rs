@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::HashSet;
+use std::collections::{hash_map::Entry, HashMap};
 
 use futures::{
 	channel::{mpsc, oneshot},
@@ -23,7 +23,11 @@ use futures::{
 
 use polkadot_node_primitives::{ValidationResult, APPROVAL_EXECUTION_TIMEOUT};
 use polkadot_node_subsystem::{
-	messages::{AvailabilityRecoveryMessage, AvailabilityStoreMessage, CandidateValidationMessage},
+	messages::{
+		AvailabilityRecoveryMessage, AvailabilityStoreMessage, CandidateValidationMessage,
+		DisputeCoordinatorMessage,
+	},
+	overseer::{self, DisputeCoordinatorOutgoingMessages},
 	ActiveLeavesUpdate, RecoveryError, SubsystemContext, SubsystemSender,
 };
 use polkadot_node_subsystem_util::runtime::get_validation_code_by_hash;
@@ -34,7 +38,10 @@ use crate::{
 	LOG_TARGET,
 };
 
-use super::ordering::CandidateComparator;
+use super::{
+	db::v1::{ParticipationQueue, QueuedParticipation},
+	ordering::CandidateComparator,
+};
 
 #[cfg(test)]
 mod tests;
@@ -56,11 +63,16 @@ const MAX_PARALLEL_PARTICIPATIONS: usize = 3;
 ///
 /// - Prioritize and queue participations
 /// - Dequeue participation requests in order and launch participation worker.
+/// - Keep track of changes, so the queue can be persisted.
 pub struct Participation {
-	/// Participations currently being processed.
-	running_participations: HashSet<CandidateHash>,
+	/// Participations currently being processed, along with the block number of the candidate's
+	/// relay parent for prioritized ones.
+	running_participations: HashMap<CandidateHash, (Option<BlockNumber>, ParticipationRequest)>,
 	/// Priority and best effort queues.
 	queue: Queues,
+	/// Whether queued or running participations changed since the last call to
+	/// `take_queue_update`.
+	queue_changed: bool,
 	/// Sender to be passed to worker tasks.
 	worker_sender: WorkerMessageSender,
 	/// Some recent block for retrieving validation code from chain.
@@ -130,8 +142,9 @@ impl Participation {
 	/// received messages.
 	pub fn new(sender: WorkerMessageSender) -> Self {
 		Self {
-			running_participations: HashSet::new(),
+			running_participations: HashMap::new(),
 			queue: Queues::new(),
+			queue_changed: false,
 			worker_sender: sender,
 			recent_block: None,
 		}
@@ -143,25 +156,75 @@ impl Participation {
 	/// `on_active_leaves_update`, the participation will be launched right away.
 	///
 	/// Returns: false, if queues are already full.
-	pub async fn queue_participation<Context: SubsystemContext>(
+	pub async fn queue_participation<Context>(
 		&mut self,
 		ctx: &mut Context,
 		comparator: Option<CandidateComparator>,
 		req: ParticipationRequest,
-	) -> Result<()> {
+	) -> Result<()>
+	where
+		Context: SubsystemContext<Message = DisputeCoordinatorMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeCoordinatorMessage>,
+	{
 		// Participation already running - we can ignore that request:
-		if self.running_participations.contains(req.candidate_hash()) {
+		if self.running_participations.contains_key(req.candidate_hash()) {
 			return Ok(())
 		}
 		// Available capacity - participate right away (if we already have a recent block):
 		if let Some((_, h)) = self.recent_block {
 			if self.running_participations.len() < MAX_PARALLEL_PARTICIPATIONS {
-				self.fork_participation(ctx, req, h)?;
+				let priority = comparator.map(|c| c.relay_parent_block_number());
+				self.fork_participation(ctx, priority, req, h)?;
 				return Ok(())
 			}
 		}
 		// Out of capacity/no recent block yet - queue:
-		Ok(self.queue.queue(comparator, req).map_err(NonFatal::QueueError)?)
+		self.queue.queue(comparator, req).map_err(NonFatal::QueueError)?;
+		self.queue_changed = true;
+		Ok(())
+	}
+
+	/// Queue a participation from the persisted participation queue again.
+	///
+	/// The participation keeps the priority and best effort count it had when it got persisted.
+	pub async fn restore_participation<Context>(
+		&mut self,
+		ctx: &mut Context,
+		queued: QueuedParticipation,
+	) -> Result<()>
+	where
+		Context: SubsystemContext<Message = DisputeCoordinatorMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeCoordinatorMessage>,
+	{
+		if self.running_participations.contains_key(&queued.candidate_receipt.hash()) {
+			return Ok(())
+		}
+		if let Some((_, h)) = self.recent_block {
+			if self.running_participations.len() < MAX_PARALLEL_PARTICIPATIONS {
+				self.fork_participation(ctx, queued.priority, queued.into(), h)?;
+				return Ok(())
+			}
+		}
+		self.queue.restore(queued).map_err(NonFatal::QueueError)?;
+		self.queue_changed = true;
+		Ok(())
+	}
+
+	/// Get all queued and running participations, if they changed since the last call.
+	///
+	/// The returned queue should be written to the database, so participations can be resumed
+	/// after a restart.
+	pub fn take_queue_update(&mut self) -> Option<ParticipationQueue> {
+		if !std::mem::take(&mut self.queue_changed) {
+			return None
+		}
+		Some(
+			self.running_participations
+				.values()
+				.map(|(priority, req)| req.to_queued(*priority, 1))
+				.chain(self.queue.persisted())
+				.collect(),
+		)
 	}
 
 	/// Message from a worker task was received - get the outcome.
@@ -173,13 +236,18 @@ impl Participation {
 	///
 	/// Returns: The received `ParticipationStatement` or a fatal error, in case
 	/// something went wrong when dequeuing more requests (tasks could not be spawned).
-	pub async fn get_participation_result<Context: SubsystemContext>(
+	pub async fn get_participation_result<Context>(
 		&mut self,
 		ctx: &mut Context,
 		msg: WorkerMessage,
-	) -> FatalResult<ParticipationStatement> {
+	) -> FatalResult<ParticipationStatement>
+	where
+		Context: SubsystemContext<Message = DisputeCoordinatorMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeCoordinatorMessage>,
+	{
 		let WorkerMessage(statement) = msg;
 		self.running_participations.remove(&statement.candidate_hash);
+		self.queue_changed = true;
 		let recent_block = self.recent_block.expect("We never ever reset recent_block to `None` and we already received a result, so it must have been set before. qed.");
 		self.dequeue_until_capacity(ctx, recent_block.1).await?;
 		Ok(statement)
@@ -189,11 +257,15 @@ impl Participation {
 	///
 	/// Make sure we to dequeue participations if that became possible and update most recent
 	/// block.
-	pub async fn process_active_leaves_update<Context: SubsystemContext>(
+	pub async fn process_active_leaves_update<Context>(
 		&mut self,
 		ctx: &mut Context,
 		update: &ActiveLeavesUpdate,
-	) -> FatalResult<()> {
+	) -> FatalResult<()>
+	where
+		Context: SubsystemContext<Message = DisputeCoordinatorMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeCoordinatorMessage>,
+	{
 		if let Some(activated) = &update.activated {
 			match self.recent_block {
 				None => {
@@ -211,14 +283,19 @@ impl Participation {
 	}
 
 	/// Dequeue until `MAX_PARALLEL_PARTICIPATIONS` is reached.
-	async fn dequeue_until_capacity<Context: SubsystemContext>(
+	async fn dequeue_until_capacity<Context>(
 		&mut self,
 		ctx: &mut Context,
 		recent_head: Hash,
-	) -> FatalResult<()> {
+	) -> FatalResult<()>
+	where
+		Context: SubsystemContext<Message = DisputeCoordinatorMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeCoordinatorMessage>,
+	{
 		while self.running_participations.len() < MAX_PARALLEL_PARTICIPATIONS {
-			if let Some(req) = self.queue.dequeue() {
-				self.fork_participation(ctx, req, recent_head)?;
+			if let Some((priority, req)) = self.queue.dequeue() {
+				self.queue_changed = true;
+				self.fork_participation(ctx, priority, req, recent_head)?;
 			} else {
 				break
 			}
@@ -227,13 +304,20 @@ impl Participation {
 	}
 
 	/// Fork a participation task in the background.
-	fn fork_participation<Context: SubsystemContext>(
+	fn fork_participation<Context>(
 		&mut self,
 		ctx: &mut Context,
+		priority: Option<BlockNumber>,
 		req: ParticipationRequest,
 		recent_head: Hash,
-	) -> FatalResult<()> {
-		if self.running_participations.insert(req.candidate_hash().clone()) {
+	) -> FatalResult<()>
+	where
+		Context: SubsystemContext<Message = DisputeCoordinatorMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeCoordinatorMessage>,
+	{
+		if let Entry::Vacant(entry) = self.running_participations.entry(*req.candidate_hash()) {
+			entry.insert((priority, req.clone()));
+			self.queue_changed = true;
 			let sender = ctx.sender().clone();
 			ctx.spawn(
 				"participation-worker",
@@ -247,7 +331,7 @@ impl Participation {
 
 async fn participate(
 	mut result_sender: WorkerMessageSender,
-	mut sender: impl SubsystemSender,
+	mut sender: impl SubsystemSender<DisputeCoordinatorOutgoingMessages>,
 	block_hash: Hash,
 	req: ParticipationRequest,
 ) {
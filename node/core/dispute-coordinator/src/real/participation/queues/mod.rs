@@ -14,13 +14,18 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::{BTreeMap, HashMap};
+use std::{
+	cmp::Reverse,
+	collections::{BTreeMap, HashMap},
+};
 
 use thiserror::Error;
 
-use polkadot_primitives::v1::{CandidateHash, CandidateReceipt, SessionIndex};
+use polkadot_primitives::v1::{
+	BlockNumber, CandidateHash, CandidateReceipt, SessionIndex, ValidatorIndex,
+};
 
-use crate::real::ordering::CandidateComparator;
+use crate::real::{db::v1::QueuedParticipation, ordering::CandidateComparator};
 
 #[cfg(test)]
 mod tests;
@@ -31,6 +36,15 @@ const BEST_EFFORT_QUEUE_SIZE: usize = 100;
 #[cfg(test)]
 const BEST_EFFORT_QUEUE_SIZE: usize = 3;
 
+/// How many best effort participations a single dispute initiator can have queued at any time.
+///
+/// Best effort disputes are not known to be legitimate yet, so a single (potentially malicious)
+/// validator raising lots of them should not be able to crowd out disputes raised by others.
+#[cfg(not(test))]
+const MAX_BEST_EFFORT_PER_INITIATOR: u32 = 10;
+#[cfg(test)]
+const MAX_BEST_EFFORT_PER_INITIATOR: u32 = 2;
+
 /// How many priority disputes can be queued.
 ///
 /// Once the queue exceeds that size, we will start to drop the newest participation requests in
@@ -52,18 +66,27 @@ pub struct Queues {
 	/// Set of best effort participation requests.
 	///
 	/// Note that as size is limited to `BEST_EFFORT_QUEUE_SIZE` we simply do a linear search for
-	/// the entry with the highest `added_count` to determine what dispute to participate next in.
+	/// the entry with the biggest `dispute_size` (and then highest `added_count`) to determine
+	/// what dispute to participate next in.
 	///
 	/// This mechanism leads to an amplifying effect - the more validators already participated,
 	/// the more likely it becomes that more validators will participate soon, which should lead to
 	/// a quick resolution of disputes, even in the best effort queue.
 	best_effort: HashMap<CandidateHash, BestEffortEntry>,
 
+	/// Number of best effort entries queued per dispute initiator.
+	initiator_load: HashMap<(SessionIndex, ValidatorIndex), u32>,
+
 	/// Priority queue.
 	///
 	/// In the priority queue, we have a strict ordering of candidates and participation will
 	/// happen in that order.
-	priority: BTreeMap<CandidateComparator, ParticipationRequest>,
+	priority: BTreeMap<PriorityKey, ParticipationRequest>,
+
+	/// Lookup of the current `PriorityKey` of a candidate in `priority`.
+	///
+	/// Needed, as the key changes with the dispute size.
+	priority_keys: HashMap<CandidateHash, PriorityKey>,
 }
 
 /// A dispute participation request that can be queued.
@@ -73,6 +96,10 @@ pub struct ParticipationRequest {
 	candidate_receipt: CandidateReceipt,
 	session: SessionIndex,
 	n_validators: usize,
+	/// Number of validators which already voted in the dispute, when the request was created.
+	dispute_size: u32,
+	/// The validator who raised the dispute, if known.
+	initiator: Option<ValidatorIndex>,
 }
 
 /// Entry for the best effort queue.
@@ -82,6 +109,18 @@ struct BestEffortEntry {
 	added_count: BestEffortCount,
 }
 
+/// Ordering of the priority queue.
+///
+/// Disputes for candidates with older relay parents come first, of equally old ones we prefer
+/// bigger disputes (more votes already) as those are closer to conclusion. The candidate hash
+/// ensures uniqueness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct PriorityKey {
+	relay_parent_block_number: BlockNumber,
+	dispute_size: Reverse<u32>,
+	candidate_hash: CandidateHash,
+}
+
 /// What can go wrong when queuing a request.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -89,6 +128,8 @@ pub enum Error {
 	BestEffortFull,
 	#[error("Request could not be queued, because priority queue was already full.")]
 	PriorityFull,
+	#[error("Request could not be queued, because initiator {0:?} already has too many best effort requests queued.")]
+	InitiatorLimitReached(ValidatorIndex),
 }
 
 impl ParticipationRequest {
@@ -97,8 +138,17 @@ impl ParticipationRequest {
 		candidate_receipt: CandidateReceipt,
 		session: SessionIndex,
 		n_validators: usize,
+		dispute_size: u32,
+		initiator: Option<ValidatorIndex>,
 	) -> Self {
-		Self { candidate_hash: candidate_receipt.hash(), candidate_receipt, session, n_validators }
+		Self {
+			candidate_hash: candidate_receipt.hash(),
+			candidate_receipt,
+			session,
+			n_validators,
+			dispute_size,
+			initiator,
+		}
 	}
 
 	pub fn candidate_receipt(&'_ self) -> &'_ CandidateReceipt {
@@ -113,16 +163,55 @@ impl ParticipationRequest {
 	pub fn n_validators(&self) -> usize {
 		self.n_validators
 	}
+	pub fn dispute_size(&self) -> u32 {
+		self.dispute_size
+	}
+	pub fn initiator(&self) -> Option<ValidatorIndex> {
+		self.initiator
+	}
 	pub fn into_candidate_info(self) -> (CandidateHash, CandidateReceipt) {
 		let Self { candidate_hash, candidate_receipt, .. } = self;
 		(candidate_hash, candidate_receipt)
 	}
+	/// Get the representation of the request in the persisted participation queue.
+	pub fn to_queued(
+		&self,
+		priority: Option<BlockNumber>,
+		added_count: BestEffortCount,
+	) -> QueuedParticipation {
+		QueuedParticipation {
+			candidate_receipt: self.candidate_receipt.clone(),
+			session: self.session,
+			n_validators: self.n_validators as u32,
+			dispute_size: self.dispute_size,
+			initiator: self.initiator,
+			priority,
+			added_count,
+		}
+	}
+}
+
+impl From<QueuedParticipation> for ParticipationRequest {
+	fn from(queued: QueuedParticipation) -> Self {
+		ParticipationRequest::new(
+			queued.candidate_receipt,
+			queued.session,
+			queued.n_validators as usize,
+			queued.dispute_size,
+			queued.initiator,
+		)
+	}
 }
 
 impl Queues {
 	/// Create new `Queues`.
 	pub fn new() -> Self {
-		Self { best_effort: HashMap::new(), priority: BTreeMap::new() }
+		Self {
+			best_effort: HashMap::new(),
+			initiator_load: HashMap::new(),
+			priority: BTreeMap::new(),
+			priority_keys: HashMap::new(),
+		}
 	}
 
 	/// Will put message in queue, either priority or best effort depending on whether a
@@ -130,9 +219,11 @@ impl Queues {
 	///
 	/// If the message was already previously present on best effort, it will be moved to priority
 	/// if a `CandidateComparator` has been passed now, otherwise the `added_count` on the best
-	/// effort queue will be bumped.
+	/// effort queue will be bumped. In both cases the queued request gets replaced, if the new one
+	/// reports a bigger dispute.
 	///
-	/// Returns error in case a queue was found full already.
+	/// Returns error in case a queue was found full already or the initiator of a best effort
+	/// request has used up its share of the best effort queue.
 	pub fn queue(
 		&mut self,
 		comparator: Option<CandidateComparator>,
@@ -142,23 +233,68 @@ impl Queues {
 			.map(|c| c.matches_candidate(req.candidate_hash()))
 			.unwrap_or(true));
 
-		if let Some(comparator) = comparator {
-			if self.priority.len() >= PRIORITY_QUEUE_SIZE {
-				return Err(Error::PriorityFull)
+		self.queue_with_priority(comparator.map(|c| c.relay_parent_block_number()), req, 1)
+	}
+
+	/// Put a participation from the persisted participation queue back into the queue it was in.
+	///
+	/// Priority participations keep their position, best effort participations keep how often
+	/// they have been requested.
+	pub fn restore(&mut self, queued: QueuedParticipation) -> Result<(), Error> {
+		let (priority, added_count) = (queued.priority, queued.added_count.max(1));
+		self.queue_with_priority(priority, queued.into(), added_count)
+	}
+
+	fn queue_with_priority(
+		&mut self,
+		priority: Option<BlockNumber>,
+		req: ParticipationRequest,
+		added_count: BestEffortCount,
+	) -> Result<(), Error> {
+		if let Some(relay_parent_block_number) = priority {
+			let key = PriorityKey {
+				relay_parent_block_number,
+				dispute_size: Reverse(req.dispute_size),
+				candidate_hash: req.candidate_hash,
+			};
+			match self.priority_keys.get(&req.candidate_hash).copied() {
+				Some(old_key) if old_key <= key => {
+					// Already queued with at least that priority.
+					return Ok(())
+				},
+				Some(old_key) => {
+					self.priority.remove(&old_key);
+				},
+				None =>
+					if self.priority.len() >= PRIORITY_QUEUE_SIZE {
+						return Err(Error::PriorityFull)
+					},
 			}
 			// Remove any best effort entry:
-			self.best_effort.remove(&req.candidate_hash);
-			self.priority.insert(comparator, req);
+			self.remove_best_effort(&req.candidate_hash);
+			self.priority_keys.insert(req.candidate_hash, key);
+			self.priority.insert(key, req);
 		} else {
+			if let Some(entry) = self.best_effort.get_mut(&req.candidate_hash) {
+				entry.added_count = entry.added_count.saturating_add(added_count);
+				if req.dispute_size > entry.req.dispute_size {
+					entry.req.dispute_size = req.dispute_size;
+				}
+				return Ok(())
+			}
 			if self.best_effort.len() >= BEST_EFFORT_QUEUE_SIZE {
 				return Err(Error::BestEffortFull)
 			}
+			if let Some(initiator) = req.initiator {
+				let load = self.initiator_load.entry((req.session, initiator)).or_default();
+				if *load >= MAX_BEST_EFFORT_PER_INITIATOR {
+					return Err(Error::InitiatorLimitReached(initiator))
+				}
+				*load += 1;
+			}
 			// Note: The request might have been added to priority in a previous call already, we
 			// take care of that case in `dequeue` (more efficient).
-			self.best_effort
-				.entry(req.candidate_hash)
-				.or_insert(BestEffortEntry { req, added_count: 0 })
-				.added_count += 1;
+			self.best_effort.insert(req.candidate_hash, BestEffortEntry { req, added_count });
 		}
 		Ok(())
 	}
@@ -166,43 +302,78 @@ impl Queues {
 	/// Get the next best request for dispute participation
 	///
 	/// if any.  Priority queue is always considered first, then the best effort queue based on
-	/// `added_count`.
-	pub fn dequeue(&mut self) -> Option<ParticipationRequest> {
-		if let Some(req) = self.pop_priority() {
+	/// `dispute_size` and `added_count`.
+	///
+	/// Along with the request, the block number of the candidate's relay parent is returned for
+	/// priority requests.
+	pub fn dequeue(&mut self) -> Option<(Option<BlockNumber>, ParticipationRequest)> {
+		if let Some((priority, req)) = self.pop_priority() {
 			// In case a candidate became best effort over time, we might have it also queued in
 			// the best effort queue - get rid of any such entry:
-			self.best_effort.remove(req.candidate_hash());
-			return Some(req)
+			self.remove_best_effort(req.candidate_hash());
+			return Some((Some(priority), req))
 		}
-		self.pop_best_effort()
+		self.pop_best_effort().map(|req| (None, req))
+	}
+
+	/// All queued requests, in the order they would be dequeued.
+	///
+	/// Used for persisting the queue.
+	pub fn persisted(&self) -> impl Iterator<Item = QueuedParticipation> + '_ {
+		let mut best_effort: Vec<_> = self
+			.best_effort
+			.values()
+			.filter(|entry| !self.priority_keys.contains_key(entry.req.candidate_hash()))
+			.collect();
+		best_effort.sort_by_key(|entry| Reverse((entry.req.dispute_size, entry.added_count)));
+
+		let priority = self
+			.priority
+			.iter()
+			.map(|(key, req)| req.to_queued(Some(key.relay_parent_block_number), 1));
+		let best_effort =
+			best_effort.into_iter().map(|entry| entry.req.to_queued(None, entry.added_count));
+		priority.chain(best_effort)
 	}
 
 	/// Get the next best from the best effort queue.
 	///
 	/// If there are multiple best - just pick one.
 	fn pop_best_effort(&mut self) -> Option<ParticipationRequest> {
-		let best = self.best_effort.iter().reduce(|(hash1, entry1), (hash2, entry2)| {
-			if entry1.added_count > entry2.added_count {
-				(hash1, entry1)
-			} else {
-				(hash2, entry2)
+		let best = self
+			.best_effort
+			.iter()
+			.max_by_key(|(_, entry)| (entry.req.dispute_size, entry.added_count))
+			.map(|(hash, _)| *hash);
+		best.and_then(|best_hash| self.remove_best_effort(&best_hash))
+	}
+
+	/// Remove an entry from the best effort queue and update initiator accounting.
+	fn remove_best_effort(
+		&mut self,
+		candidate_hash: &CandidateHash,
+	) -> Option<ParticipationRequest> {
+		let req = self.best_effort.remove(candidate_hash)?.req;
+		if let Some(initiator) = req.initiator {
+			let key = (req.session, initiator);
+			if let Some(load) = self.initiator_load.get_mut(&key) {
+				*load = load.saturating_sub(1);
+				if *load == 0 {
+					self.initiator_load.remove(&key);
+				}
 			}
-		});
-		if let Some((best_hash, _)) = best {
-			let best_hash = best_hash.clone();
-			self.best_effort.remove(&best_hash).map(|e| e.req)
-		} else {
-			None
 		}
+		Some(req)
 	}
 
-	/// Get best priority queue entry.
-	fn pop_priority(&mut self) -> Option<ParticipationRequest> {
+	/// Get best priority queue entry, along with the block number of its relay parent.
+	fn pop_priority(&mut self) -> Option<(BlockNumber, ParticipationRequest)> {
 		// Once https://github.com/rust-lang/rust/issues/62924 is there, we can use a simple:
 		// priority.pop_first().
-		if let Some((comparator, _)) = self.priority.iter().next() {
-			let comparator = comparator.clone();
-			self.priority.remove(&comparator)
+		if let Some((key, _)) = self.priority.iter().next() {
+			let key = *key;
+			self.priority_keys.remove(&key.candidate_hash);
+			self.priority.remove(&key).map(|req| (key.relay_parent_block_number, req))
 		} else {
 			None
 		}
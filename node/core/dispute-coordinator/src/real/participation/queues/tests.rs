@@ -16,7 +16,7 @@
 
 use ::test_helpers::{dummy_candidate_receipt, dummy_hash};
 use assert_matches::assert_matches;
-use polkadot_primitives::v1::{BlockNumber, Hash};
+use polkadot_primitives::v1::{BlockNumber, Hash, ValidatorIndex};
 
 use crate::real::ordering::CandidateComparator;
 
@@ -24,10 +24,19 @@ use super::{Error, ParticipationRequest, Queues};
 
 /// Make a `ParticipationRequest` based on the given commitments hash.
 fn make_participation_request(hash: Hash) -> ParticipationRequest {
+	make_sized_participation_request(hash, 2, None)
+}
+
+/// Make a `ParticipationRequest` with the given dispute size and initiator.
+fn make_sized_participation_request(
+	hash: Hash,
+	dispute_size: u32,
+	initiator: Option<ValidatorIndex>,
+) -> ParticipationRequest {
 	let mut receipt = dummy_candidate_receipt(dummy_hash());
 	// make it differ:
 	receipt.commitments_hash = hash;
-	ParticipationRequest::new(receipt, 1, 100)
+	ParticipationRequest::new(receipt, 1, 100, dispute_size, initiator)
 }
 
 /// Make dummy comparator for request, based on the given block number.
@@ -38,6 +47,11 @@ fn make_dummy_comparator(
 	CandidateComparator::new_dummy(relay_parent, *req.candidate_hash())
 }
 
+/// Dequeue the next request, without the block number of its relay parent.
+fn dequeue(queue: &mut Queues) -> Option<ParticipationRequest> {
+	queue.dequeue().map(|(_, req)| req)
+}
+
 /// Check that dequeuing acknowledges order.
 ///
 /// Any priority item will be dequeued before any best effort items, priority items will be
@@ -68,18 +82,18 @@ fn ordering_works_as_expected() {
 	);
 	assert_matches!(queue.queue(None, req_full), Err(Error::BestEffortFull));
 
-	assert_eq!(queue.dequeue(), Some(req_prio));
-	assert_eq!(queue.dequeue(), Some(req_prio_2));
-	assert_eq!(queue.dequeue(), Some(req3));
+	assert_eq!(dequeue(&mut queue), Some(req_prio));
+	assert_eq!(dequeue(&mut queue), Some(req_prio_2));
+	assert_eq!(dequeue(&mut queue), Some(req3));
 	assert_matches!(
-		queue.dequeue(),
+		dequeue(&mut queue),
 		Some(r) => { assert!(r == req1 || r == req5) }
 	);
 	assert_matches!(
-		queue.dequeue(),
+		dequeue(&mut queue),
 		Some(r) => { assert!(r == req1 || r == req5) }
 	);
-	assert_matches!(queue.dequeue(), None);
+	assert_matches!(dequeue(&mut queue), None);
 }
 
 /// No matter how often a candidate gets queued, it should only ever get dequeued once.
@@ -113,7 +127,7 @@ fn candidate_is_only_dequeued_once() {
 		.unwrap();
 
 	// Make space in prio:
-	assert_eq!(queue.dequeue(), Some(req_prio));
+	assert_eq!(dequeue(&mut queue), Some(req_prio));
 
 	// Insert first as prio:
 	queue
@@ -125,8 +139,106 @@ fn candidate_is_only_dequeued_once() {
 	// Then as best effort:
 	queue.queue(None, req_prio_then_best_effort.clone()).unwrap();
 
-	assert_eq!(queue.dequeue(), Some(req_best_effort_then_prio));
-	assert_eq!(queue.dequeue(), Some(req_prio_then_best_effort));
-	assert_eq!(queue.dequeue(), Some(req1));
-	assert_eq!(queue.dequeue(), None);
+	assert_eq!(dequeue(&mut queue), Some(req_best_effort_then_prio));
+	assert_eq!(dequeue(&mut queue), Some(req_prio_then_best_effort));
+	assert_eq!(dequeue(&mut queue), Some(req1));
+	assert_eq!(dequeue(&mut queue), None);
+}
+
+/// Equally old priority disputes are ordered by size, best effort disputes by size first.
+#[test]
+fn bigger_disputes_are_preferred() {
+	let mut queue = Queues::new();
+	let req_prio_small = make_sized_participation_request(Hash::repeat_byte(0x01), 2, None);
+	let req_prio_big = make_sized_participation_request(Hash::repeat_byte(0x02), 10, None);
+	let req_small = make_sized_participation_request(Hash::repeat_byte(0x03), 2, None);
+	let req_big = make_sized_participation_request(Hash::repeat_byte(0x04), 10, None);
+
+	queue
+		.queue(Some(make_dummy_comparator(&req_prio_small, 1)), req_prio_small.clone())
+		.unwrap();
+	queue
+		.queue(Some(make_dummy_comparator(&req_prio_big, 1)), req_prio_big.clone())
+		.unwrap();
+	queue.queue(None, req_small.clone()).unwrap();
+	// Added more often, but smaller:
+	queue.queue(None, req_small.clone()).unwrap();
+	queue.queue(None, req_big.clone()).unwrap();
+
+	assert_eq!(dequeue(&mut queue), Some(req_prio_big));
+	assert_eq!(dequeue(&mut queue), Some(req_prio_small));
+	assert_eq!(dequeue(&mut queue), Some(req_big));
+	assert_eq!(dequeue(&mut queue), Some(req_small));
+	assert_eq!(dequeue(&mut queue), None);
+}
+
+/// A single initiator can only occupy a limited amount of the best effort queue.
+#[test]
+fn best_effort_is_limited_per_initiator() {
+	let mut queue = Queues::new();
+	let initiator = Some(ValidatorIndex(1));
+	let req1 = make_sized_participation_request(Hash::repeat_byte(0x01), 2, initiator);
+	let req2 = make_sized_participation_request(Hash::repeat_byte(0x02), 2, initiator);
+	let req3 = make_sized_participation_request(Hash::repeat_byte(0x03), 2, initiator);
+	let req_other =
+		make_sized_participation_request(Hash::repeat_byte(0x04), 2, Some(ValidatorIndex(2)));
+
+	queue.queue(None, req1.clone()).unwrap();
+	queue.queue(None, req2.clone()).unwrap();
+	// Re-adding is fine:
+	queue.queue(None, req1.clone()).unwrap();
+	assert_matches!(
+		queue.queue(None, req3.clone()),
+		Err(Error::InitiatorLimitReached(ValidatorIndex(1)))
+	);
+	queue.queue(None, req_other.clone()).unwrap();
+
+	// Priority requests are not limited:
+	queue
+		.queue(Some(make_dummy_comparator(&req3, 1)), req3.clone())
+		.unwrap();
+
+	// Once dequeued, the initiator can queue again:
+	assert_eq!(dequeue(&mut queue), Some(req3));
+	assert_eq!(dequeue(&mut queue), Some(req1));
+	let req4 = make_sized_participation_request(Hash::repeat_byte(0x05), 2, initiator);
+	queue.queue(None, req4.clone()).unwrap();
+}
+
+/// A queue restored from its persisted entries keeps priority and order.
+#[test]
+fn restored_queue_keeps_priority_and_order() {
+	let mut queue = Queues::new();
+	let req_prio_late = make_participation_request(Hash::repeat_byte(0x01));
+	let req_prio_early = make_participation_request(Hash::repeat_byte(0x02));
+	let req_small = make_sized_participation_request(Hash::repeat_byte(0x03), 2, None);
+	let req_often = make_sized_participation_request(Hash::repeat_byte(0x04), 2, None);
+
+	queue
+		.queue(Some(make_dummy_comparator(&req_prio_late, 2)), req_prio_late.clone())
+		.unwrap();
+	queue
+		.queue(Some(make_dummy_comparator(&req_prio_early, 1)), req_prio_early.clone())
+		.unwrap();
+	queue.queue(None, req_small.clone()).unwrap();
+	queue.queue(None, req_often.clone()).unwrap();
+	queue.queue(None, req_often.clone()).unwrap();
+
+	let persisted: Vec<_> = queue.persisted().collect();
+	assert_eq!(
+		persisted.iter().map(|q| (q.priority, q.added_count)).collect::<Vec<_>>(),
+		vec![(Some(1), 1), (Some(2), 1), (None, 2), (None, 1)],
+	);
+
+	let mut restored = Queues::new();
+	// Restoring does not depend on the order of the persisted entries:
+	for queued in persisted.into_iter().rev() {
+		restored.restore(queued).unwrap();
+	}
+
+	assert_eq!(restored.dequeue(), Some((Some(1), req_prio_early)));
+	assert_eq!(restored.dequeue(), Some((Some(2), req_prio_late)));
+	assert_eq!(restored.dequeue(), Some((None, req_often)));
+	assert_eq!(restored.dequeue(), Some((None, req_small)));
+	assert_eq!(restored.dequeue(), None);
 }
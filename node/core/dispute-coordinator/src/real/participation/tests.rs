@@ -54,7 +54,8 @@ pub fn make_our_subsystem_context<S>(
 }
 
 async fn participate(
-	ctx: &mut impl SubsystemContext,
+	ctx: &mut (impl SubsystemContext<Message = DisputeCoordinatorMessage>
+	          + overseer::SubsystemContext<Message = DisputeCoordinatorMessage>),
 	participation: &mut Participation,
 ) -> Result<()> {
 	let commitments = CandidateCommitments::default();
@@ -62,7 +63,8 @@ async fn participate(
 }
 
 async fn participate_with_commitments_hash(
-	ctx: &mut impl SubsystemContext,
+	ctx: &mut (impl SubsystemContext<Message = DisputeCoordinatorMessage>
+	          + overseer::SubsystemContext<Message = DisputeCoordinatorMessage>),
 	participation: &mut Participation,
 	commitments_hash: Hash,
 ) -> Result<()> {
@@ -74,13 +76,14 @@ async fn participate_with_commitments_hash(
 	let session = 1;
 	let n_validators = 10;
 
-	let req = ParticipationRequest::new(candidate_receipt, session, n_validators);
+	let req = ParticipationRequest::new(candidate_receipt, session, n_validators, 2, None);
 
 	participation.queue_participation(ctx, None, req).await
 }
 
 async fn activate_leaf(
-	ctx: &mut impl SubsystemContext,
+	ctx: &mut (impl SubsystemContext<Message = DisputeCoordinatorMessage>
+	          + overseer::SubsystemContext<Message = DisputeCoordinatorMessage>),
 	participation: &mut Participation,
 	block_number: BlockNumber,
 ) -> FatalResult<()> {
@@ -35,8 +35,8 @@ use parity_scale_codec::Encode;
 use polkadot_node_primitives::SignedDisputeStatement;
 use polkadot_node_subsystem::{
 	messages::{
-		ChainApiMessage, DisputeCoordinatorMessage, DisputeDistributionMessage,
-		ImportStatementsResult,
+		AvailabilityRecoveryMessage, ChainApiMessage, DisputeCoordinatorMessage,
+		DisputeDistributionMessage, ImportStatementsResult,
 	},
 	overseer::FromOverseer,
 	ChainApiError, OverseerSignal,
@@ -56,24 +56,25 @@ use polkadot_node_subsystem::{
 use polkadot_node_subsystem_test_helpers::{make_subsystem_context, TestSubsystemContextHandle};
 use polkadot_primitives::{
 	v1::{
-		BlakeTwo256, BlockNumber, CandidateCommitments, CandidateHash, CandidateReceipt, Hash,
-		HashT, Header, MultiDisputeStatementSet, ScrapedOnChainVotes, SessionIndex, ValidatorId,
-		ValidatorIndex,
+		BlakeTwo256, BlockNumber, CandidateCommitments, CandidateEvent, CandidateHash,
+		CandidateReceipt, CoreIndex, GroupIndex, Hash, HashT, Header, MultiDisputeStatementSet,
+		ScrapedOnChainVotes, SessionIndex, ValidatorId, ValidatorIndex,
 	},
-	v2::SessionInfo,
+	v2::{slashing, SessionInfo},
 };
 
 use crate::{
 	metrics::Metrics,
 	real::{
-		backend::Backend,
+		backend::{Backend, OverlayedBackend},
+		initialized::SLASHING_REPORT_RESUBMISSION_BLOCKS,
 		participation::{participation_full_happy_path, participation_missing_availability},
 		Config, DisputeCoordinatorSubsystem,
 	},
 	status::{Clock, Timestamp, ACTIVE_DURATION_SECS},
 };
 
-use super::db::v1::DbBackend;
+use super::db::v1::{CandidateVotes, DbBackend};
 
 const TEST_TIMEOUT: Duration = Duration::from_secs(2);
 
@@ -129,6 +130,13 @@ struct TestState {
 	config: Config,
 	clock: MockClock,
 	headers: HashMap<Hash, Header>,
+	/// Candidate events of every activated leaf.
+	candidate_events: Vec<CandidateEvent>,
+	unapplied_slashes: Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>,
+	/// Whether reports for the `unapplied_slashes` are expected to be submitted.
+	expect_slashing_reports: bool,
+	/// The block key ownership proofs are expected to be requested at, if not the activated leaf.
+	key_ownership_proof_block: Option<Hash>,
 }
 
 impl Default for TestState {
@@ -167,6 +175,10 @@ impl Default for TestState {
 			config,
 			clock: MockClock::default(),
 			headers: HashMap::new(),
+			candidate_events: Vec::new(),
+			unapplied_slashes: Vec::new(),
+			expect_slashing_reports: true,
+			key_ownership_proof_block: None,
 		}
 	}
 }
@@ -258,7 +270,7 @@ impl TestState {
 				_new_leaf,
 				RuntimeApiRequest::CandidateEvents(tx),
 			)) => {
-				tx.send(Ok(Vec::new())).unwrap();
+				tx.send(Ok(self.candidate_events.clone())).unwrap();
 			}
 		);
 
@@ -275,7 +287,64 @@ impl TestState {
 					disputes: MultiDisputeStatementSet::default(),
 				}))).unwrap();
 			}
-		)
+		);
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_new_leaf,
+				RuntimeApiRequest::UnappliedSlashes(tx),
+			)) => {
+				tx.send(Ok(self.unapplied_slashes.clone())).unwrap();
+			}
+		);
+
+		let expected_reports =
+			if self.expect_slashing_reports { &self.unapplied_slashes[..] } else { &[] };
+		for (session_index, candidate_hash, pending) in expected_reports {
+			for (validator_index, validator_id) in &pending.keys {
+				assert_matches!(
+					virtual_overseer.recv().await,
+					AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						proof_block,
+						RuntimeApiRequest::KeyOwnershipProof(id, tx),
+					)) => {
+						assert_eq!(
+							proof_block,
+							self.key_ownership_proof_block.unwrap_or(block_hash),
+						);
+						assert_eq!(&id, validator_id);
+						tx.send(Ok(Some(slashing::OpaqueKeyOwnershipProof::new(id.encode()))))
+							.unwrap();
+					}
+				);
+
+				assert_matches!(
+					virtual_overseer.recv().await,
+					AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						leaf,
+						RuntimeApiRequest::SubmitReportDisputeLost(
+							dispute_proof,
+							key_ownership_proof,
+							tx,
+						),
+					)) => {
+						assert_eq!(leaf, block_hash);
+						assert_eq!(
+							dispute_proof.time_slot,
+							slashing::DisputesTimeSlot::new(*session_index, *candidate_hash),
+						);
+						assert_eq!(dispute_proof.kind, pending.kind);
+						assert_eq!(&dispute_proof.validator_index, validator_index);
+						assert_eq!(
+							key_ownership_proof.decode::<ValidatorId>().as_ref(),
+							Some(validator_id),
+						);
+						tx.send(Ok(Some(()))).unwrap();
+					}
+				);
+			}
+		}
 	}
 
 	async fn handle_resume_sync(
@@ -1197,6 +1266,126 @@ fn resume_dispute_without_local_statement() {
 	});
 }
 
+#[test]
+fn running_participation_is_resumed_after_restart() {
+	let session = 1;
+	let candidate_receipt = make_valid_candidate_receipt();
+	let candidate_hash = candidate_receipt.hash();
+
+	test_harness(|mut test_state, mut virtual_overseer| {
+		let candidate_receipt = candidate_receipt.clone();
+		Box::pin(async move {
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			// The candidate is included in the leaf, so participation is prioritized.
+			test_state.candidate_events = vec![CandidateEvent::CandidateIncluded(
+				candidate_receipt.clone(),
+				Default::default(),
+				CoreIndex(0),
+				GroupIndex(0),
+			)];
+			test_state.activate_leaf_at_session(&mut virtual_overseer, session, 1).await;
+
+			let valid_vote =
+				test_state.issue_statement_with_index(1, candidate_hash, session, true).await;
+
+			let invalid_vote =
+				test_state.issue_statement_with_index(2, candidate_hash, session, false).await;
+
+			let (pending_confirmation, confirmation_rx) = oneshot::channel();
+			virtual_overseer
+				.send(FromOverseer::Communication {
+					msg: DisputeCoordinatorMessage::ImportStatements {
+						candidate_hash,
+						candidate_receipt: candidate_receipt.clone(),
+						session,
+						statements: vec![
+							(valid_vote, ValidatorIndex(1)),
+							(invalid_vote, ValidatorIndex(2)),
+						],
+						pending_confirmation,
+					},
+				})
+				.await;
+
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::ChainApi(ChainApiMessage::BlockNumber(relay_parent, tx)) => {
+					assert_eq!(relay_parent, candidate_receipt.descriptor.relay_parent);
+					tx.send(Ok(Some(0))).unwrap();
+				}
+			);
+
+			// Participation starts, but does not finish before shutdown.
+			let recovery_tx = assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::AvailabilityRecovery(
+					AvailabilityRecoveryMessage::RecoverAvailableData(receipt, _, _, tx)
+				) => {
+					assert_eq!(receipt, candidate_receipt);
+					tx
+				}
+			);
+
+			// The import is confirmed once the participation queue got written as well.
+			assert_eq!(confirmation_rx.await, Ok(ImportStatementsResult::ValidImport));
+
+			let backend = DbBackend::new(test_state.db.clone(), test_state.config.column_config());
+			let queue = backend.load_participation_queue().unwrap().unwrap();
+			assert_eq!(queue.len(), 1);
+			assert_eq!(queue[0].candidate_receipt, candidate_receipt);
+			assert_eq!(queue[0].dispute_size, 2);
+			assert_eq!(queue[0].initiator, Some(ValidatorIndex(2)));
+			assert_eq!(queue[0].priority, Some(0));
+
+			virtual_overseer.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+			// Keep the participation running, so it does not conclude before the shutdown.
+			std::mem::forget(recovery_tx);
+
+			test_state
+		})
+	})
+	// The persisted participation is carried out after the restart and keeps its priority, even
+	// though the including block is not known anymore.
+	.resume(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			test_state.candidate_events.clear();
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			let recovery_tx = assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::AvailabilityRecovery(
+					AvailabilityRecoveryMessage::RecoverAvailableData(receipt, _, _, tx)
+				) => {
+					assert_eq!(receipt.hash(), candidate_hash);
+					tx
+				}
+			);
+
+			// Make sure the initial participation queue update got written.
+			let (tx, rx) = oneshot::channel();
+			virtual_overseer
+				.send(FromOverseer::Communication {
+					msg: DisputeCoordinatorMessage::ActiveDisputes(tx),
+				})
+				.await;
+			assert_eq!(rx.await.unwrap(), vec![(session, candidate_hash)]);
+
+			let backend = DbBackend::new(test_state.db.clone(), test_state.config.column_config());
+			let queue = backend.load_participation_queue().unwrap().unwrap();
+			assert_eq!(queue.len(), 1);
+			assert_eq!(queue[0].candidate_receipt.hash(), candidate_hash);
+			assert_eq!(queue[0].initiator, Some(ValidatorIndex(2)));
+			assert_eq!(queue[0].priority, Some(0));
+
+			virtual_overseer.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+			std::mem::forget(recovery_tx);
+
+			test_state
+		})
+	});
+}
+
 #[test]
 fn resume_dispute_with_local_statement() {
 	let session = 1;
@@ -1548,3 +1737,92 @@ fn negative_issue_local_statement_only_triggers_import() {
 		})
 	});
 }
+
+#[test]
+fn unapplied_slashes_are_reported() {
+	test_harness(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			let session = 1;
+
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			let candidate_receipt = make_invalid_candidate_receipt();
+			let candidate_hash = candidate_receipt.hash();
+			test_state.unapplied_slashes = vec![(
+				session,
+				candidate_hash,
+				slashing::PendingSlashes {
+					keys: vec![
+						(ValidatorIndex(1), test_state.validator_public[1].clone()),
+						(ValidatorIndex(3), test_state.validator_public[3].clone()),
+					]
+					.into_iter()
+					.collect(),
+					kind: slashing::SlashingOffenceKind::ForInvalid,
+				},
+			)];
+
+			// Key ownership proofs are requested at the leaf and reports submitted for all pending
+			// slashes of the current session.
+			test_state.activate_leaf_at_session(&mut virtual_overseer, session, 1).await;
+
+			// Reports are not submitted again right away.
+			test_state.expect_slashing_reports = false;
+			test_state.activate_leaf_at_session(&mut virtual_overseer, session, 2).await;
+
+			// Unless they were not applied for a while.
+			test_state.unapplied_slashes[0].2.keys.remove(&ValidatorIndex(1));
+			test_state.expect_slashing_reports = true;
+			test_state
+				.activate_leaf_at_session(
+					&mut virtual_overseer,
+					session,
+					1 + SLASHING_REPORT_RESUBMISSION_BLOCKS,
+				)
+				.await;
+
+			// In later sessions, key ownership proofs are requested at the relay parent of the
+			// disputed candidate, which is in the session of the dispute.
+			let mut backend =
+				DbBackend::new(test_state.db.clone(), test_state.config.column_config());
+			let mut overlay_db = OverlayedBackend::new(&backend);
+			overlay_db.write_candidate_votes(
+				session,
+				candidate_hash,
+				CandidateVotes {
+					candidate_receipt: candidate_receipt.clone(),
+					valid: Vec::new(),
+					invalid: Vec::new(),
+				},
+			);
+			let write_ops = overlay_db.into_write_ops();
+			backend.write(write_ops).unwrap();
+
+			test_state.key_ownership_proof_block = Some(candidate_receipt.descriptor.relay_parent);
+			test_state
+				.activate_leaf_at_session(
+					&mut virtual_overseer,
+					session + 1,
+					1 + 2 * SLASHING_REPORT_RESUBMISSION_BLOCKS,
+				)
+				.await;
+
+			// Reports of past sessions, for which no block of the dispute's session is known, are
+			// not even attempted, as they could never be proven.
+			test_state.unapplied_slashes[0].1 = make_valid_candidate_receipt().hash();
+			test_state.expect_slashing_reports = false;
+			test_state
+				.activate_leaf_at_session(
+					&mut virtual_overseer,
+					session + 1,
+					1 + 3 * SLASHING_REPORT_RESUBMISSION_BLOCKS,
+				)
+				.await;
+
+			virtual_overseer.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+			assert!(virtual_overseer.try_recv().await.is_none());
+
+			test_state
+		})
+	});
+}
@@ -31,13 +31,14 @@ use polkadot_node_subsystem::{
 	jaeger,
 	messages::{
 		CandidateBackingMessage, ChainApiMessage, DisputeCoordinatorMessage, ProvisionableData,
-		ProvisionerInherentData, ProvisionerMessage,
+		ProvisionerInherentData, ProvisionerMessage, RuntimeApiMessage,
 	},
+	overseer::{OutgoingMessagesOf, ProvisionerOutgoingMessages},
 	ActivatedLeaf, LeafStatus, PerLeafSpan, SubsystemSender,
 };
 use polkadot_node_subsystem_util::{
-	self as util, request_availability_cores, request_persisted_validation_data, JobSender,
-	JobSubsystem, JobTrait,
+	self as util, request_availability_cores, request_inherent_weight_info,
+	request_persisted_validation_data, JobSender, JobSubsystem, JobTrait,
 };
 use polkadot_primitives::v1::{
 	BackedCandidate, BlockNumber, CandidateHash, CandidateReceipt, CoreState, DisputeStatement,
@@ -51,8 +52,10 @@ use std::{
 use thiserror::Error;
 
 mod metrics;
+mod weight_limit;
 
 pub use self::metrics::*;
+use self::weight_limit::WeightBudget;
 
 #[cfg(test)]
 mod tests;
@@ -127,6 +130,9 @@ pub enum Error {
 	#[error("failed to get votes on dispute")]
 	CanceledCandidateVotes(#[source] oneshot::Canceled),
 
+	#[error("failed to get inherent weight info")]
+	CanceledInherentWeightInfo(#[source] oneshot::Canceled),
+
 	#[error(transparent)]
 	ChainApi(#[from] ChainApiError),
 
@@ -168,7 +174,7 @@ impl JobTrait for ProvisionerJob {
 	/// Run a job for the parent block indicated
 	//
 	// this function is in charge of creating and executing the job's main loop
-	fn run<S: SubsystemSender>(
+	fn run<S: SubsystemSender<OutgoingMessagesOf<Self::ToJob>>>(
 		leaf: ActivatedLeaf,
 		run_args: Self::RunArgs,
 		metrics: Self::Metrics,
@@ -209,7 +215,7 @@ impl ProvisionerJob {
 
 	async fn run_loop(
 		mut self,
-		sender: &mut impl SubsystemSender,
+		sender: &mut impl SubsystemSender<ProvisionerOutgoingMessages>,
 		disputes_enabled: bool,
 		span: PerLeafSpan,
 	) -> Result<(), Error> {
@@ -250,7 +256,7 @@ impl ProvisionerJob {
 
 	async fn send_inherent_data(
 		&mut self,
-		sender: &mut impl SubsystemSender,
+		sender: &mut impl SubsystemSender<ProvisionerOutgoingMessages>,
 		return_senders: Vec<oneshot::Sender<ProvisionerInherentData>>,
 		disputes_enabled: bool,
 	) {
@@ -310,12 +316,16 @@ type CoreAvailability = BitVec<bitvec::order::Lsb0, u8>;
 /// When we're choosing bitfields to include, the rule should be simple:
 /// maximize availability. So basically, include all bitfields. And then
 /// choose a coherent set of candidates along with that.
+///
+/// If the runtime provides the weights of the inherent's components, everything is packed into
+/// the block weight by priority: disputes, then bitfields, then backed candidates. Bitfields are
+/// limited before selecting candidates, as the selection depends on the included bitfields.
 async fn send_inherent_data(
 	leaf: &ActivatedLeaf,
 	bitfields: &[SignedAvailabilityBitfield],
 	candidates: &[CandidateReceipt],
 	return_senders: Vec<oneshot::Sender<ProvisionerInherentData>>,
-	from_job: &mut impl SubsystemSender,
+	from_job: &mut impl SubsystemSender<ProvisionerOutgoingMessages>,
 	disputes_enabled: bool,
 	metrics: &Metrics,
 ) -> Result<(), Error> {
@@ -324,17 +334,58 @@ async fn send_inherent_data(
 		.await
 		.map_err(|err| Error::CanceledAvailabilityCores(err))??;
 
-	let disputes =
-		if disputes_enabled { select_disputes(from_job, metrics).await? } else { vec![] };
+	let mut weight_budget = match request_inherent_weight_info(leaf.hash, from_job)
+		.await
+		.await
+		.map_err(|err| Error::CanceledInherentWeightInfo(err))?
+	{
+		Ok(weight_info) => Some(WeightBudget::new(weight_info)),
+		Err(err) => {
+			// The runtime enforces the block weight itself, so this is not fatal.
+			tracing::debug!(
+				target: LOG_TARGET,
+				?err,
+				"Inherent weight info unavailable, not limiting inherent data by weight",
+			);
+			None
+		},
+	};
+
+	let mut disputes = if disputes_enabled { select_disputes(from_job).await? } else { vec![] };
+	if let Some(budget) = weight_budget.as_mut() {
+		metrics.on_dropped_by_weight("disputes", budget.fit_disputes(&mut disputes));
+	}
+	for set in &disputes {
+		let valid = set
+			.statements
+			.iter()
+			.filter(|(statement, _, _)| matches!(statement, DisputeStatement::Valid(_)))
+			.count();
+		metrics.inc_valid_statements_by(valid);
+		metrics.inc_invalid_statements_by(set.statements.len() - valid);
+	}
+	metrics.inc_dispute_statement_sets_by(disputes.len());
 
 	// Only include bitfields on fresh leaves. On chain reversions, we want to make sure that
 	// there will be at least one block, which cannot get disputed, so the chain can make progress.
-	let bitfields = match leaf.status {
+	let mut bitfields = match leaf.status {
 		LeafStatus::Fresh => select_availability_bitfields(&availability_cores, bitfields),
 		LeafStatus::Stale => Vec::new(),
 	};
-	let candidates =
+	if let Some(budget) = weight_budget.as_mut() {
+		metrics.on_dropped_by_weight("bitfields", budget.fit_bitfields(&mut bitfields));
+	}
+
+	let mut candidates =
 		select_candidates(&availability_cores, &bitfields, candidates, leaf.hash, from_job).await?;
+	if let Some(budget) = weight_budget.as_mut() {
+		metrics.on_dropped_by_weight("candidates", budget.fit_candidates(&mut candidates));
+		tracing::trace!(
+			target: LOG_TARGET,
+			remaining_weight = budget.remaining(),
+			"Packed inherent data into the block weight",
+		);
+	}
 
 	let inherent_data =
 		ProvisionerInherentData { bitfields, backed_candidates: candidates, disputes };
@@ -391,13 +442,16 @@ fn select_availability_bitfields(
 }
 
 /// Determine which cores are free, and then to the degree possible, pick a candidate appropriate to each free core.
-async fn select_candidates(
+async fn select_candidates<Outgoing>(
 	availability_cores: &[CoreState],
 	bitfields: &[SignedAvailabilityBitfield],
 	candidates: &[CandidateReceipt],
 	relay_parent: Hash,
-	sender: &mut impl SubsystemSender,
-) -> Result<Vec<BackedCandidate>, Error> {
+	sender: &mut impl SubsystemSender<Outgoing>,
+) -> Result<Vec<BackedCandidate>, Error>
+where
+	Outgoing: From<CandidateBackingMessage> + From<ChainApiMessage> + From<RuntimeApiMessage>,
+{
 	let block_number = get_block_number_under_construction(relay_parent, sender).await?;
 
 	let mut selected_candidates =
@@ -521,9 +575,9 @@ async fn select_candidates(
 
 /// Produces a block number 1 higher than that of the relay parent
 /// in the event of an invalid `relay_parent`, returns `Ok(0)`
-async fn get_block_number_under_construction(
+async fn get_block_number_under_construction<Outgoing: From<ChainApiMessage>>(
 	relay_parent: Hash,
-	sender: &mut impl SubsystemSender,
+	sender: &mut impl SubsystemSender<Outgoing>,
 ) -> Result<BlockNumber, Error> {
 	let (tx, rx) = oneshot::channel();
 	sender.send_message(ChainApiMessage::BlockNumber(relay_parent, tx).into()).await;
@@ -584,7 +638,7 @@ enum RequestType {
 
 /// Request open disputes identified by `CandidateHash` and the `SessionIndex`.
 async fn request_disputes(
-	sender: &mut impl SubsystemSender,
+	sender: &mut impl SubsystemSender<ProvisionerOutgoingMessages>,
 	active_or_recent: RequestType,
 ) -> Vec<(SessionIndex, CandidateHash)> {
 	let (tx, rx) = oneshot::channel();
@@ -606,7 +660,7 @@ async fn request_disputes(
 
 /// Request the relevant dispute statements for a set of disputes identified by `CandidateHash` and the `SessionIndex`.
 async fn request_votes(
-	sender: &mut impl SubsystemSender,
+	sender: &mut impl SubsystemSender<ProvisionerOutgoingMessages>,
 	disputes_to_query: Vec<(SessionIndex, CandidateHash)>,
 ) -> Vec<(SessionIndex, CandidateHash, CandidateVotes)> {
 	let (tx, rx) = oneshot::channel();
@@ -652,9 +706,10 @@ fn extend_by_random_subset_without_repetition(
 }
 
 async fn select_disputes(
-	sender: &mut impl SubsystemSender,
-	metrics: &metrics::Metrics,
+	sender: &mut impl SubsystemSender<ProvisionerOutgoingMessages>,
 ) -> Result<MultiDisputeStatementSet, Error> {
+	// Bounds the number of disputes to load votes for from the coordinator. How many of them end
+	// up in the block is determined by their weight in `send_inherent_data`.
 	const MAX_DISPUTES_FORWARDED_TO_RUNTIME: usize = 10_000;
 
 	// We use `RecentDisputes` instead of `ActiveDisputes` because redundancy is fine.
@@ -709,10 +764,6 @@ async fn select_disputes(
 				.into_iter()
 				.map(|(s, i, sig)| (DisputeStatement::Invalid(s), i, sig));
 
-			metrics.inc_valid_statements_by(valid_statements.len());
-			metrics.inc_invalid_statements_by(invalid_statements.len());
-			metrics.inc_dispute_statement_sets_by(1);
-
 			DisputeStatementSet {
 				candidate_hash,
 				session: session_index,
@@ -28,6 +28,8 @@ struct MetricsInner {
 	/// 4 hours on Polkadot. The metrics are updated only when the node authors a block, so values vary across nodes.
 	inherent_data_dispute_statement_sets: prometheus::Counter<prometheus::U64>,
 	inherent_data_dispute_statements: prometheus::CounterVec<prometheus::U64>,
+
+	inherent_data_dropped_by_weight: prometheus::CounterVec<prometheus::U64>,
 }
 
 /// Provisioner metrics.
@@ -83,6 +85,16 @@ impl Metrics {
 				.inc_by(disputes.try_into().unwrap_or(0));
 		}
 	}
+
+	/// Note inherent data of the given kind which didn't fit into the block weight.
+	pub(crate) fn on_dropped_by_weight(&self, kind: &'static str, dropped: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics
+				.inherent_data_dropped_by_weight
+				.with_label_values(&[kind])
+				.inc_by(dropped.try_into().unwrap_or(0));
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -129,6 +141,16 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			inherent_data_dropped_by_weight: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_inherent_data_dropped_by_weight",
+						"Number of inherent data items not provided as they exceeded the block weight.",
+					),
+					&["kind"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
@@ -46,7 +46,7 @@ mod select_availability_bitfields {
 	use sp_keystore::{testing::KeyStore, CryptoStore, SyncCryptoStorePtr};
 	use std::sync::Arc;
 
-	async fn signed_bitfield(
+	pub(super) async fn signed_bitfield(
 		keystore: &SyncCryptoStorePtr,
 		field: CoreAvailability,
 		validator_idx: ValidatorIndex,
@@ -482,3 +482,119 @@ mod select_candidates {
 		)
 	}
 }
+
+mod weight_limit {
+	use super::{super::*, default_bitvec, select_availability_bitfields::signed_bitfield};
+	use crate::weight_limit::WeightBudget;
+	use ::test_helpers::{dummy_candidate_descriptor, dummy_hash};
+	use futures::executor::block_on;
+	use polkadot_primitives::{
+		v1::{
+			CandidateCommitments, CommittedCandidateReceipt, InvalidDisputeStatementKind,
+			ValidatorPair, ValidatorSignature, ValidityAttestation,
+		},
+		v2::InherentWeightInfo,
+	};
+	use sp_application_crypto::Pair;
+	use sp_keystore::{testing::KeyStore, SyncCryptoStorePtr};
+	use std::sync::Arc;
+
+	fn weight_info(max_block_weight: u64) -> InherentWeightInfo {
+		InherentWeightInfo {
+			max_block_weight,
+			bitfield: 10,
+			backed_candidate_base: 100,
+			backed_candidate_per_vote: 10,
+			backed_candidate_code_upgrade: 1_000,
+			dispute_statement_set_base: 100,
+			dispute_statement: 10,
+		}
+	}
+
+	fn signature() -> ValidatorSignature {
+		ValidatorPair::generate().0.sign(&[])
+	}
+
+	fn dispute_statement_set(statements: usize) -> DisputeStatementSet {
+		let signature = signature();
+		DisputeStatementSet {
+			candidate_hash: CandidateHash(dummy_hash()),
+			session: 1,
+			statements: (0..statements)
+				.map(|i| {
+					(
+						DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit),
+						ValidatorIndex(i as u32),
+						signature.clone(),
+					)
+				})
+				.collect(),
+		}
+	}
+
+	fn backed_candidate(para_id: u32, votes: usize, code_upgrade: bool) -> BackedCandidate {
+		let mut descriptor = dummy_candidate_descriptor(dummy_hash());
+		descriptor.para_id = para_id.into();
+		BackedCandidate {
+			candidate: CommittedCandidateReceipt {
+				descriptor,
+				commitments: CandidateCommitments {
+					new_validation_code: code_upgrade.then(|| vec![].into()),
+					..Default::default()
+				},
+			},
+			validity_votes: vec![ValidityAttestation::Explicit(signature()); votes],
+			validator_indices: default_bitvec(votes),
+		}
+	}
+
+	#[test]
+	fn skips_dispute_sets_exceeding_the_budget() {
+		// Fits sets of 10 and 5 statements, but not a further one of 10.
+		let mut budget = WeightBudget::new(weight_info(360));
+		let mut disputes =
+			vec![dispute_statement_set(10), dispute_statement_set(10), dispute_statement_set(5)];
+
+		assert_eq!(budget.fit_disputes(&mut disputes), 1);
+		assert_eq!(disputes.iter().map(|d| d.statements.len()).collect::<Vec<_>>(), vec![10, 5]);
+		assert_eq!(budget.remaining(), 10);
+	}
+
+	#[test]
+	fn keeps_bitfields_with_most_bits_set() {
+		let keystore: SyncCryptoStorePtr = Arc::new(KeyStore::new());
+		let bitfields: Vec<_> = (0..4)
+			.map(|i| {
+				let mut bitvec = default_bitvec(4);
+				for core in 0..i {
+					bitvec.set(core, true);
+				}
+				block_on(signed_bitfield(&keystore, bitvec, ValidatorIndex(i as u32)))
+			})
+			.collect();
+
+		let mut budget = WeightBudget::new(weight_info(25));
+		let mut selected = bitfields.clone();
+
+		assert_eq!(budget.fit_bitfields(&mut selected), 2);
+		assert_eq!(selected, bitfields[2..].to_vec());
+		assert_eq!(budget.remaining(), 5);
+	}
+
+	#[test]
+	fn prefers_code_upgrades_and_keeps_candidate_order() {
+		let candidates = vec![
+			backed_candidate(0, 5, false),
+			backed_candidate(1, 2, true),
+			backed_candidate(2, 2, false),
+		];
+
+		// Fits the code upgrade and the lighter candidate only.
+		let mut budget = WeightBudget::new(weight_info(1_130));
+		let mut selected = candidates.clone();
+
+		assert_eq!(budget.fit_candidates(&mut selected), 1);
+		assert_eq!(selected, candidates[1..].to_vec());
+		assert_eq!(budget.remaining(), 10);
+	}
+}
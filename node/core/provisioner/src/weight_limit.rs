@@ -0,0 +1,125 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Packing of the inherent data into the weight available in a block.
+//!
+//! The runtime drops whatever exceeds the block weight itself, but it has to do so at random and
+//! only after the whole inherent has been decoded. Packing by priority in the provisioner keeps
+//! the most valuable data: disputes first, then availability bitfields and finally backed
+//! candidates, preferring code upgrades like the runtime does.
+
+use std::collections::HashSet;
+
+use polkadot_primitives::{
+	v1::{BackedCandidate, MultiDisputeStatementSet, SignedAvailabilityBitfield},
+	v2::InherentWeightInfo,
+};
+
+/// The weight left in a block while the inherent data is being packed into it.
+pub(crate) struct WeightBudget {
+	info: InherentWeightInfo,
+	remaining: u64,
+}
+
+impl WeightBudget {
+	/// Create a budget spanning the whole block.
+	pub(crate) fn new(info: InherentWeightInfo) -> Self {
+		let remaining = info.max_block_weight;
+		Self { info, remaining }
+	}
+
+	/// The weight not yet consumed.
+	pub(crate) fn remaining(&self) -> u64 {
+		self.remaining
+	}
+
+	/// Keep the dispute statement sets fitting into the budget, in their given order.
+	///
+	/// Sets which are too heavy are skipped, so lighter ones further back still get a chance.
+	/// Returns the number of dropped sets.
+	pub(crate) fn fit_disputes(&mut self, disputes: &mut MultiDisputeStatementSet) -> usize {
+		let before = disputes.len();
+		disputes.retain(|set| {
+			let weight = self.info.dispute_statement_set_weight(set.statements.len());
+			self.consume(weight)
+		});
+		before - disputes.len()
+	}
+
+	/// Keep as many bitfields as fit into the budget, preferring the ones with the most bits set.
+	///
+	/// The order of the kept bitfields is preserved. Returns the number of dropped bitfields.
+	pub(crate) fn fit_bitfields(
+		&mut self,
+		bitfields: &mut Vec<SignedAvailabilityBitfield>,
+	) -> usize {
+		let fitting = match self.remaining.checked_div(self.info.bitfield) {
+			Some(fitting) => fitting.min(bitfields.len() as u64) as usize,
+			// Bitfields don't cost anything.
+			None => return 0,
+		};
+
+		let mut by_bits_set: Vec<usize> = (0..bitfields.len()).collect();
+		by_bits_set.sort_by_key(|i| std::cmp::Reverse(bitfields[*i].payload().0.count_ones()));
+		let keep: HashSet<usize> = by_bits_set.into_iter().take(fitting).collect();
+
+		let before = bitfields.len();
+		let mut index = 0;
+		bitfields.retain(|_| {
+			index += 1;
+			keep.contains(&(index - 1))
+		});
+		self.remaining -= self.info.bitfield * fitting as u64;
+		before - bitfields.len()
+	}
+
+	/// Keep the backed candidates fitting into the budget, code upgrades first.
+	///
+	/// The order of the kept candidates is preserved. Returns the number of dropped candidates.
+	pub(crate) fn fit_candidates(&mut self, candidates: &mut Vec<BackedCandidate>) -> usize {
+		let is_upgrade =
+			|c: &BackedCandidate| c.candidate.commitments.new_validation_code.is_some();
+		let mut keep = HashSet::new();
+		let upgrades = candidates.iter().enumerate().filter(|(_, c)| is_upgrade(c));
+		let others = candidates.iter().enumerate().filter(|(_, c)| !is_upgrade(c));
+		for (index, candidate) in upgrades.chain(others) {
+			let weight = self
+				.info
+				.backed_candidate_weight(candidate.validity_votes.len(), is_upgrade(candidate));
+			if self.consume(weight) {
+				keep.insert(index);
+			}
+		}
+
+		let before = candidates.len();
+		let mut index = 0;
+		candidates.retain(|_| {
+			index += 1;
+			keep.contains(&(index - 1))
+		});
+		before - candidates.len()
+	}
+
+	/// Consume the given weight if it fits into the budget.
+	fn consume(&mut self, weight: u64) -> bool {
+		if weight <= self.remaining {
+			self.remaining -= weight;
+			true
+		} else {
+			false
+		}
+	}
+}
@@ -19,6 +19,7 @@ use futures::channel::oneshot;
 use polkadot_node_subsystem::{
 	errors::RuntimeApiError as RuntimeApiSubsystemError,
 	messages::{RuntimeApiMessage, RuntimeApiRequest},
+	overseer::PvfCheckerOutgoingMessages,
 	SubsystemSender,
 };
 use polkadot_primitives::{
@@ -27,7 +28,7 @@ use polkadot_primitives::{
 };
 
 pub(crate) async fn session_index_for_child(
-	sender: &mut impl SubsystemSender,
+	sender: &mut impl SubsystemSender<PvfCheckerOutgoingMessages>,
 	relay_parent: Hash,
 ) -> Result<SessionIndex, RuntimeRequestError> {
 	let (tx, rx) = oneshot::channel();
@@ -35,7 +36,7 @@ pub(crate) async fn session_index_for_child(
 }
 
 pub(crate) async fn validators(
-	sender: &mut impl SubsystemSender,
+	sender: &mut impl SubsystemSender<PvfCheckerOutgoingMessages>,
 	relay_parent: Hash,
 ) -> Result<Vec<ValidatorId>, RuntimeRequestError> {
 	let (tx, rx) = oneshot::channel();
@@ -43,7 +44,7 @@ pub(crate) async fn validators(
 }
 
 pub(crate) async fn submit_pvf_check_statement(
-	sender: &mut impl SubsystemSender,
+	sender: &mut impl SubsystemSender<PvfCheckerOutgoingMessages>,
 	relay_parent: Hash,
 	stmt: PvfCheckStatement,
 	signature: ValidatorSignature,
@@ -59,7 +60,7 @@ pub(crate) async fn submit_pvf_check_statement(
 }
 
 pub(crate) async fn pvfs_require_precheck(
-	sender: &mut impl SubsystemSender,
+	sender: &mut impl SubsystemSender<PvfCheckerOutgoingMessages>,
 	relay_parent: Hash,
 ) -> Result<Vec<ValidationCodeHash>, RuntimeRequestError> {
 	let (tx, rx) = oneshot::channel();
@@ -74,7 +75,7 @@ pub(crate) enum RuntimeRequestError {
 }
 
 pub(crate) async fn runtime_api_request<T>(
-	sender: &mut impl SubsystemSender,
+	sender: &mut impl SubsystemSender<PvfCheckerOutgoingMessages>,
 	relay_parent: Hash,
 	request: RuntimeApiRequest,
 	receiver: oneshot::Receiver<Result<T, RuntimeApiSubsystemError>>,
@@ -22,13 +22,13 @@ use sp_consensus_babe::Epoch;
 
 use polkadot_primitives::{
 	v1::{
-		AuthorityDiscoveryId, BlockNumber, CandidateCommitments, CandidateEvent,
+		AuthorityDiscoveryId, BlockNumber, CandidateCommitments, CandidateEvent, CandidateHash,
 		CommittedCandidateReceipt, CoreState, GroupRotationInfo, Hash, Id as ParaId,
 		InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption,
 		PersistedValidationData, ScrapedOnChainVotes, SessionIndex, ValidationCode,
 		ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 	},
-	v2::{PvfCheckStatement, SessionInfo},
+	v2::{slashing, InherentWeightInfo, PvfCheckStatement, SessionInfo},
 };
 
 const AUTHORITIES_CACHE_SIZE: usize = 128 * 1024;
@@ -399,6 +399,33 @@ impl RequestResultCache {
 	) {
 		self.validation_code_hash.insert(key, ResidentSizeOf(value));
 	}
+
+	/// The memory used by each of the caches, in bytes.
+	///
+	/// The names match the ones returned by [`crate::cached_request_name`].
+	pub(crate) fn resident_sizes(&self) -> [(&'static str, usize); 19] {
+		[
+			("authorities", self.authorities.current_size()),
+			("validators", self.validators.current_size()),
+			("validator_groups", self.validator_groups.current_size()),
+			("availability_cores", self.availability_cores.current_size()),
+			("persisted_validation_data", self.persisted_validation_data.current_size()),
+			("assumed_validation_data", self.assumed_validation_data.current_size()),
+			("check_validation_outputs", self.check_validation_outputs.current_size()),
+			("session_index_for_child", self.session_index_for_child.current_size()),
+			("validation_code", self.validation_code.current_size()),
+			("validation_code_by_hash", self.validation_code_by_hash.current_size()),
+			("candidate_pending_availability", self.candidate_pending_availability.current_size()),
+			("candidate_events", self.candidate_events.current_size()),
+			("session_info", self.session_info.current_size()),
+			("dmq_contents", self.dmq_contents.current_size()),
+			("inbound_hrmp_channels_contents", self.inbound_hrmp_channels_contents.current_size()),
+			("current_babe_epoch", self.current_babe_epoch.current_size()),
+			("on_chain_votes", self.on_chain_votes.current_size()),
+			("pvfs_require_precheck", self.pvfs_require_precheck.current_size()),
+			("validation_code_hash", self.validation_code_hash.current_size()),
+		]
+	}
 }
 
 pub(crate) enum RequestResult {
@@ -433,4 +460,20 @@ pub(crate) enum RequestResult {
 	// This is a request with side-effects and no result, hence ().
 	SubmitPvfCheckStatement(Hash, PvfCheckStatement, ValidatorSignature, ()),
 	ValidationCodeHash(Hash, ParaId, OccupiedCoreAssumption, Option<ValidationCodeHash>),
+	UnappliedSlashes(Hash, Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>),
+	KeyOwnershipProof(Hash, ValidatorId, Option<slashing::OpaqueKeyOwnershipProof>),
+	// This is a request with side-effects.
+	SubmitReportDisputeLost(
+		Hash,
+		slashing::DisputeProof,
+		slashing::OpaqueKeyOwnershipProof,
+		Option<()>,
+	),
+	InherentWeightInfo(Hash, InherentWeightInfo),
+	SubmitReportBackingMisbehavior(
+		Hash,
+		slashing::BackingMisbehaviorProof,
+		slashing::OpaqueKeyOwnershipProof,
+		Option<()>,
+	),
 }
@@ -24,14 +24,14 @@
 
 use polkadot_node_subsystem_util::metrics::{self, prometheus};
 use polkadot_primitives::{
-	v1::{Block, BlockId, Hash},
+	v1::{Block, BlockId, Hash, SessionIndex},
 	v2::ParachainHost,
 };
 use polkadot_subsystem::{
 	errors::RuntimeApiError,
 	messages::{RuntimeApiMessage, RuntimeApiRequest as Request},
-	overseer, FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext, SubsystemError,
-	SubsystemResult,
+	overseer, ActiveLeavesUpdate, FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext,
+	SubsystemError, SubsystemResult,
 };
 
 use sp_api::ProvideRuntimeApi;
@@ -40,8 +40,14 @@ use sp_consensus_babe::BabeApi;
 use sp_core::traits::SpawnNamed;
 
 use cache::{RequestResult, RequestResultCache};
-use futures::{channel::oneshot, prelude::*, select, stream::FuturesUnordered};
-use std::{collections::VecDeque, pin::Pin, sync::Arc};
+use futures::{
+	channel::oneshot, future::BoxFuture, prelude::*, select, stream::FuturesUnordered,
+};
+use std::{
+	collections::{HashMap, VecDeque},
+	pin::Pin,
+	sync::Arc,
+};
 
 mod cache;
 
@@ -56,20 +62,46 @@ const MAX_PARALLEL_REQUESTS: usize = 4;
 /// The name of the blocking task that executes a runtime API request.
 const API_REQUEST_TASK_NAME: &str = "polkadot-runtime-api-request";
 
+/// A request which may be prefetched, identified by the key of its cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PrefetchKey {
+	AvailabilityCores(Hash),
+	SessionIndexForChild(Hash),
+	ValidatorGroups(Hash),
+	SessionInfo(SessionIndex),
+}
+
+impl PrefetchKey {
+	fn of(relay_parent: Hash, request: &Request) -> Option<Self> {
+		Some(match request {
+			Request::AvailabilityCores(_) => PrefetchKey::AvailabilityCores(relay_parent),
+			Request::SessionIndexForChild(_) => PrefetchKey::SessionIndexForChild(relay_parent),
+			Request::ValidatorGroups(_) => PrefetchKey::ValidatorGroups(relay_parent),
+			Request::SessionInfo(index, _) => PrefetchKey::SessionInfo(*index),
+			_ => return None,
+		})
+	}
+}
+
+/// A runtime API request being executed, resolving to its result, if any, and the key of the
+/// prefetch it executes.
+type ActiveRequest = BoxFuture<'static, (Option<PrefetchKey>, Option<RequestResult>)>;
+
 /// The `RuntimeApiSubsystem`. See module docs for more details.
 pub struct RuntimeApiSubsystem<Client> {
 	client: Arc<Client>,
 	metrics: Metrics,
 	spawn_handle: Box<dyn SpawnNamed>,
 	/// If there are [`MAX_PARALLEL_REQUESTS`] requests being executed, we buffer them in here until they can be executed.
-	waiting_requests: VecDeque<(
-		Pin<Box<dyn Future<Output = ()> + Send>>,
-		oneshot::Receiver<Option<RequestResult>>,
-	)>,
+	waiting_requests: VecDeque<(Pin<Box<dyn Future<Output = ()> + Send>>, ActiveRequest)>,
 	/// All the active runtime API requests that are currently being executed.
-	active_requests: FuturesUnordered<oneshot::Receiver<Option<RequestResult>>>,
+	active_requests: FuturesUnordered<ActiveRequest>,
+	/// Prefetches not finished yet, along with the requests for the same data waiting for them.
+	in_flight_prefetches: HashMap<PrefetchKey, Vec<(Hash, Request)>>,
 	/// Requests results cache
 	requests_cache: RequestResultCache,
+	/// Whether to warm up the cache with the requests most subsystems make on new leaves.
+	prefetch: bool,
 }
 
 impl<Client> RuntimeApiSubsystem<Client> {
@@ -85,9 +117,18 @@ impl<Client> RuntimeApiSubsystem<Client> {
 			spawn_handle: Box::new(spawn_handle),
 			waiting_requests: Default::default(),
 			active_requests: Default::default(),
+			in_flight_prefetches: Default::default(),
 			requests_cache: RequestResultCache::default(),
+			prefetch: false,
 		}
 	}
+
+	/// Prefetch the availability cores, session index, validator groups and session info on
+	/// every new leaf, so the requests subsystems make on new leaves are served from the cache.
+	pub fn with_prefetch(mut self, prefetch: bool) -> Self {
+		self.prefetch = prefetch;
+		self
+	}
 }
 
 impl<Client, Context> overseer::Subsystem<Context, SubsystemError> for RuntimeApiSubsystem<Client>
@@ -165,6 +206,14 @@ where
 			ValidationCodeHash(relay_parent, para_id, assumption, hash) => self
 				.requests_cache
 				.cache_validation_code_hash((relay_parent, para_id, assumption), hash),
+			// Pending slashes change with every block and key ownership proofs are only
+			// requested right before submitting a report, so none of these are cached.
+			UnappliedSlashes(..) => {},
+			KeyOwnershipProof(..) => {},
+			SubmitReportDisputeLost(..) => {},
+			// Only requested by the provisioner once per authored block.
+			InherentWeightInfo(..) => {},
+			SubmitReportBackingMisbehavior(..) => {},
 		}
 	}
 
@@ -175,7 +224,6 @@ where
 				let sender = $sender;
 				if let Some(value) = self.requests_cache.$cache_api_name(&relay_parent) {
 					let _ = sender.send(Ok(value.clone()));
-					self.metrics.on_cached_request();
 					None
 				} else {
 					Some(sender)
@@ -185,7 +233,6 @@ where
 			($cache_api_name:ident ($($param:expr),+), $sender:expr) => {{
 				let sender = $sender;
 				if let Some(value) = self.requests_cache.$cache_api_name((relay_parent.clone(), $($param.clone()),+)) {
-					self.metrics.on_cached_request();
 					let _ = sender.send(Ok(value.clone()));
 					None
 				} else {
@@ -239,7 +286,6 @@ where
 				query!(candidate_events(), sender).map(|sender| Request::CandidateEvents(sender)),
 			Request::SessionInfo(index, sender) => {
 				if let Some(info) = self.requests_cache.session_info(index) {
-					self.metrics.on_cached_request();
 					let _ = sender.send(Ok(Some(info.clone())));
 					None
 				} else {
@@ -264,6 +310,17 @@ where
 			Request::ValidationCodeHash(para, assumption, sender) =>
 				query!(validation_code_hash(para, assumption), sender)
 					.map(|sender| Request::ValidationCodeHash(para, assumption, sender)),
+			request @ Request::UnappliedSlashes(_) |
+			request @ Request::KeyOwnershipProof(_, _) => Some(request),
+			request @ Request::SubmitReportDisputeLost(_, _, _) => {
+				// This request is side-effecting and thus cannot be cached.
+				Some(request)
+			},
+			request @ Request::InherentWeightInfo(_) => Some(request),
+			request @ Request::SubmitReportBackingMisbehavior(_, _, _) => {
+				// This request is side-effecting and thus cannot be cached.
+				Some(request)
+			},
 		}
 	}
 
@@ -271,20 +328,77 @@ where
 	///
 	/// If there are already [`MAX_PARALLEL_REQUESTS`] requests being executed, the request will be buffered.
 	fn spawn_request(&mut self, relay_parent: Hash, request: Request) {
-		let client = self.client.clone();
-		let metrics = self.metrics.clone();
-		let (sender, receiver) = oneshot::channel();
+		let cache_name = cached_request_name(&request);
 
 		let request = match self.query_cache(relay_parent.clone(), request) {
 			Some(request) => request,
-			None => return,
+			None => {
+				self.metrics.on_cached_request(cache_name);
+				return
+			},
 		};
 
+		// Wait for a prefetch of the same data instead of executing the request twice.
+		let prefetch_key = PrefetchKey::of(relay_parent, &request);
+		let waiting = prefetch_key.and_then(|key| self.in_flight_prefetches.get_mut(&key));
+		if let Some(waiting) = waiting {
+			waiting.push((relay_parent, request));
+			return
+		}
+
+		if let Some(cache_name) = cache_name {
+			self.metrics.on_cache_miss(cache_name);
+		}
+		self.execute_request(relay_parent, request, None);
+	}
+
+	/// Spawn the requests most subsystems make on a new leaf, unless they are cached already.
+	///
+	/// The results are only stored in the cache.
+	fn prefetch(&mut self, relay_parent: Hash) {
+		let requests = vec![
+			Request::AvailabilityCores(oneshot::channel().0),
+			Request::SessionIndexForChild(oneshot::channel().0),
+			Request::ValidatorGroups(oneshot::channel().0),
+		];
+		for request in requests {
+			self.prefetch_request(relay_parent, request);
+		}
+	}
+
+	fn prefetch_request(&mut self, relay_parent: Hash, request: Request) {
+		let prefetch_key = match PrefetchKey::of(relay_parent, &request) {
+			Some(key) if !self.in_flight_prefetches.contains_key(&key) => key,
+			_ => return,
+		};
+
+		let cache_name = cached_request_name(&request);
+		if let Some(request) = self.query_cache(relay_parent, request) {
+			if let Some(cache_name) = cache_name {
+				self.metrics.on_prefetch(cache_name);
+			}
+			self.in_flight_prefetches.insert(prefetch_key, Vec::new());
+			self.execute_request(relay_parent, request, Some(prefetch_key));
+		}
+	}
+
+	/// Execute a request which couldn't be served from the cache.
+	fn execute_request(
+		&mut self,
+		relay_parent: Hash,
+		request: Request,
+		prefetch_key: Option<PrefetchKey>,
+	) {
+		let client = self.client.clone();
+		let metrics = self.metrics.clone();
+		let (sender, receiver) = oneshot::channel();
+
 		let request = async move {
 			let result = make_runtime_api_request(client, metrics, relay_parent, request);
 			let _ = sender.send(result);
 		}
 		.boxed();
+		let receiver = receiver.map(move |result| (prefetch_key, result.ok().flatten())).boxed();
 
 		if self.active_requests.len() >= MAX_PARALLEL_REQUESTS {
 			self.waiting_requests.push_back((request, receiver));
@@ -303,6 +417,16 @@ where
 		}
 	}
 
+	fn on_active_leaves(&mut self, update: ActiveLeavesUpdate) {
+		self.metrics.on_cache_sizes(&self.requests_cache.resident_sizes());
+
+		if self.prefetch {
+			for leaf in update.activated {
+				self.prefetch(leaf.hash);
+			}
+		}
+	}
+
 	/// Poll the active runtime API requests.
 	async fn poll_requests(&mut self) {
 		// If there are no active requests, this future should be pending forever.
@@ -311,8 +435,29 @@ where
 		}
 
 		// If there are active requests, this will always resolve to `Some(_)` when a request is finished.
-		if let Some(Ok(Some(result))) = self.active_requests.next().await {
-			self.store_cache(result);
+		if let Some((prefetch_key, result)) = self.active_requests.next().await {
+			if let Some(result) = result {
+				// The session info can only be prefetched once the session index is known.
+				let prefetch_session_info = match result {
+					RequestResult::SessionIndexForChild(relay_parent, index) if self.prefetch =>
+						Some((relay_parent, index)),
+					_ => None,
+				};
+
+				self.store_cache(result);
+
+				if let Some((relay_parent, index)) = prefetch_session_info {
+					let request = Request::SessionInfo(index, oneshot::channel().0);
+					self.prefetch_request(relay_parent, request);
+				}
+			}
+
+			// Requests waiting for the prefetch are served from the cache now, or executed
+			// themselves if the prefetch failed.
+			let waiting = prefetch_key.and_then(|key| self.in_flight_prefetches.remove(&key));
+			for (relay_parent, request) in waiting.into_iter().flatten() {
+				self.spawn_request(relay_parent, request);
+			}
 		}
 
 		if let Some((req, recv)) = self.waiting_requests.pop_front() {
@@ -337,7 +482,9 @@ where
 		select! {
 			req = ctx.recv().fuse() => match req? {
 				FromOverseer::Signal(OverseerSignal::Conclude) => return Ok(()),
-				FromOverseer::Signal(OverseerSignal::ActiveLeaves(_)) => {},
+				FromOverseer::Signal(OverseerSignal::ActiveLeaves(update)) => {
+					subsystem.on_active_leaves(update);
+				},
 				FromOverseer::Signal(OverseerSignal::BlockFinalized(..)) => {},
 				FromOverseer::Communication { msg } => match msg {
 					RuntimeApiMessage::Request(relay_parent, request) => {
@@ -350,6 +497,37 @@ where
 	}
 }
 
+/// The name of the cache serving the given request, if its results are cached at all.
+fn cached_request_name(request: &Request) -> Option<&'static str> {
+	Some(match request {
+		Request::Authorities(_) => "authorities",
+		Request::Validators(_) => "validators",
+		Request::ValidatorGroups(_) => "validator_groups",
+		Request::AvailabilityCores(_) => "availability_cores",
+		Request::PersistedValidationData(..) => "persisted_validation_data",
+		Request::AssumedValidationData(..) => "assumed_validation_data",
+		Request::CheckValidationOutputs(..) => "check_validation_outputs",
+		Request::SessionIndexForChild(_) => "session_index_for_child",
+		Request::ValidationCode(..) => "validation_code",
+		Request::ValidationCodeByHash(..) => "validation_code_by_hash",
+		Request::CandidatePendingAvailability(..) => "candidate_pending_availability",
+		Request::CandidateEvents(_) => "candidate_events",
+		Request::SessionInfo(..) => "session_info",
+		Request::DmqContents(..) => "dmq_contents",
+		Request::InboundHrmpChannelsContents(..) => "inbound_hrmp_channels_contents",
+		Request::CurrentBabeEpoch(_) => "current_babe_epoch",
+		Request::FetchOnChainVotes(_) => "on_chain_votes",
+		Request::PvfsRequirePrecheck(_) => "pvfs_require_precheck",
+		Request::ValidationCodeHash(..) => "validation_code_hash",
+		Request::SubmitPvfCheckStatement(..) |
+		Request::UnappliedSlashes(_) |
+		Request::KeyOwnershipProof(..) |
+		Request::SubmitReportDisputeLost(..) |
+		Request::InherentWeightInfo(_) |
+		Request::SubmitReportBackingMisbehavior(..) => return None,
+	})
+}
+
 fn make_runtime_api_request<Client>(
 	client: Arc<Client>,
 	metrics: Metrics,
@@ -503,6 +681,25 @@ where
 		},
 		Request::ValidationCodeHash(para, assumption, sender) =>
 			query!(ValidationCodeHash, validation_code_hash(para, assumption), ver = 2, sender),
+		Request::UnappliedSlashes(sender) =>
+			query!(UnappliedSlashes, unapplied_slashes(), ver = 3, sender),
+		Request::KeyOwnershipProof(validator_id, sender) =>
+			query!(KeyOwnershipProof, key_ownership_proof(validator_id), ver = 3, sender),
+		Request::SubmitReportDisputeLost(dispute_proof, key_ownership_proof, sender) => query!(
+			SubmitReportDisputeLost,
+			submit_report_dispute_lost(dispute_proof, key_ownership_proof),
+			ver = 3,
+			sender
+		),
+		Request::InherentWeightInfo(sender) =>
+			query!(InherentWeightInfo, inherent_weight_info(), ver = 4, sender),
+		Request::SubmitReportBackingMisbehavior(misbehavior_proof, key_ownership_proof, sender) =>
+			query!(
+				SubmitReportBackingMisbehavior,
+				submit_report_backing_misbehavior(misbehavior_proof, key_ownership_proof),
+				ver = 5,
+				sender
+			),
 	}
 }
 
@@ -510,6 +707,9 @@ where
 struct MetricsInner {
 	chain_api_requests: prometheus::CounterVec<prometheus::U64>,
 	make_runtime_api_request: prometheus::Histogram,
+	cache_requests: prometheus::CounterVec<prometheus::U64>,
+	prefetched_requests: prometheus::CounterVec<prometheus::U64>,
+	cache_size: prometheus::GaugeVec<prometheus::U64>,
 }
 
 /// Runtime API metrics.
@@ -527,10 +727,33 @@ impl Metrics {
 		}
 	}
 
-	fn on_cached_request(&self) {
-		self.0
-			.as_ref()
-			.map(|metrics| metrics.chain_api_requests.with_label_values(&["cached"]).inc());
+	fn on_cached_request(&self, cache_name: Option<&'static str>) {
+		if let Some(metrics) = &self.0 {
+			metrics.chain_api_requests.with_label_values(&["cached"]).inc();
+			if let Some(cache_name) = cache_name {
+				metrics.cache_requests.with_label_values(&[cache_name, "hit"]).inc();
+			}
+		}
+	}
+
+	fn on_cache_miss(&self, cache_name: &'static str) {
+		if let Some(metrics) = &self.0 {
+			metrics.cache_requests.with_label_values(&[cache_name, "miss"]).inc();
+		}
+	}
+
+	fn on_prefetch(&self, cache_name: &'static str) {
+		if let Some(metrics) = &self.0 {
+			metrics.prefetched_requests.with_label_values(&[cache_name]).inc();
+		}
+	}
+
+	fn on_cache_sizes(&self, sizes: &[(&'static str, usize)]) {
+		if let Some(metrics) = &self.0 {
+			for (cache_name, size) in sizes {
+				metrics.cache_size.with_label_values(&[cache_name]).set(*size as u64);
+			}
+		}
 	}
 
 	/// Provide a timer for `make_runtime_api_request` which observes on drop.
@@ -561,6 +784,36 @@ impl metrics::Metrics for Metrics {
 				))?,
 				registry,
 			)?,
+			cache_requests: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_runtime_api_cache_requests_total",
+						"Number of cacheable Runtime API requests, by whether the cache was hit.",
+					),
+					&["request", "result"],
+				)?,
+				registry,
+			)?,
+			prefetched_requests: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_runtime_api_prefetched_requests_total",
+						"Number of Runtime API requests made to warm up the cache on new leaves.",
+					),
+					&["request"],
+				)?,
+				registry,
+			)?,
+			cache_size: prometheus::register(
+				prometheus::GaugeVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_runtime_api_cache_size_bytes",
+						"Memory used by the Runtime API caches, which evict by size.",
+					),
+					&["request"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
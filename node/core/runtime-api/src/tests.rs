@@ -20,14 +20,15 @@ use ::test_helpers::{dummy_committed_candidate_receipt, dummy_validation_code};
 use futures::channel::oneshot;
 use polkadot_node_primitives::{BabeAllowedSlots, BabeEpoch, BabeEpochConfiguration};
 use polkadot_node_subsystem_test_helpers::make_subsystem_context;
+use polkadot_subsystem::{jaeger, ActivatedLeaf, LeafStatus};
 use polkadot_primitives::{
 	v1::{
-		AuthorityDiscoveryId, CandidateEvent, CommittedCandidateReceipt, CoreState,
+		AuthorityDiscoveryId, CandidateEvent, CandidateHash, CommittedCandidateReceipt, CoreState,
 		GroupRotationInfo, Id as ParaId, InboundDownwardMessage, InboundHrmpMessage,
 		OccupiedCoreAssumption, PersistedValidationData, ScrapedOnChainVotes, SessionIndex,
 		ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 	},
-	v2::{PvfCheckStatement, SessionInfo},
+	v2::{slashing, InherentWeightInfo, PvfCheckStatement, SessionInfo},
 };
 use sp_core::testing::TaskExecutor;
 use std::{
@@ -57,6 +58,8 @@ struct MockRuntimeApi {
 	submitted_pvf_check_statement: Arc<Mutex<Vec<(PvfCheckStatement, ValidatorSignature)>>>,
 	pvfs_require_precheck: Vec<ValidationCodeHash>,
 	validation_code_hash: HashMap<ParaId, ValidationCodeHash>,
+	unapplied_slashes: Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>,
+	inherent_weight_info: InherentWeightInfo,
 }
 
 impl ProvideRuntimeApi<Block> for MockRuntimeApi {
@@ -192,6 +195,36 @@ sp_api::mock_impl_runtime_apis! {
 		) -> Option<ValidationCodeHash> {
 			self.validation_code_hash.get(&para).map(|c| c.clone())
 		}
+
+		fn unapplied_slashes(
+			&self,
+		) -> Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)> {
+			self.unapplied_slashes.clone()
+		}
+
+		fn key_ownership_proof(
+			_validator_id: ValidatorId,
+		) -> Option<slashing::OpaqueKeyOwnershipProof> {
+			None
+		}
+
+		fn submit_report_dispute_lost(
+			_dispute_proof: slashing::DisputeProof,
+			_key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			None
+		}
+
+		fn inherent_weight_info(&self) -> InherentWeightInfo {
+			self.inherent_weight_info.clone()
+		}
+
+		fn submit_report_backing_misbehavior(
+			_misbehavior_proof: slashing::BackingMisbehaviorProof,
+			_key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			None
+		}
 	}
 
 	impl BabeApi<Block> for MockRuntimeApi {
@@ -558,6 +591,76 @@ fn requests_session_info() {
 	futures::executor::block_on(future::join(subsystem_task, test_task));
 }
 
+#[test]
+fn prefetches_hot_requests_on_new_leaves() {
+	let mut runtime_api = MockRuntimeApi::default();
+	let session_index = 1;
+	runtime_api.session_index_for_child = session_index;
+	runtime_api.session_info.insert(session_index, dummy_session_info());
+	runtime_api.availability_cores = vec![CoreState::Free];
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	let relay_parent = [1; 32].into();
+	let mut subsystem = RuntimeApiSubsystem::new(Arc::new(runtime_api), Metrics(None), spawner)
+		.with_prefetch(true);
+
+	subsystem.on_active_leaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+		hash: relay_parent,
+		number: 1,
+		status: LeafStatus::Fresh,
+		span: Arc::new(jaeger::Span::Disabled),
+	}));
+
+	// Availability cores, session index and validator groups, then the session info.
+	futures::executor::block_on(async {
+		for _ in 0..4 {
+			subsystem.poll_requests().await;
+		}
+	});
+
+	let cache = &mut subsystem.requests_cache;
+	assert_eq!(cache.availability_cores(&relay_parent), Some(&vec![CoreState::Free]));
+	assert_eq!(cache.session_index_for_child(&relay_parent), Some(&session_index));
+	assert!(cache.validator_groups(&relay_parent).is_some());
+	assert_eq!(cache.session_info(session_index), Some(&dummy_session_info()));
+}
+
+#[test]
+fn requests_wait_for_prefetches_in_flight() {
+	let mut runtime_api = MockRuntimeApi::default();
+	runtime_api.availability_cores = vec![CoreState::Free];
+	let mutex = runtime_api.availability_cores_wait.clone();
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	let relay_parent = [1; 32].into();
+	let mut subsystem = RuntimeApiSubsystem::new(Arc::new(runtime_api), Metrics(None), spawner)
+		.with_prefetch(true);
+
+	// Keep the prefetch of the availability cores in flight.
+	let lock = mutex.lock().unwrap();
+	subsystem.on_active_leaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+		hash: relay_parent,
+		number: 1,
+		status: LeafStatus::Fresh,
+		span: Arc::new(jaeger::Span::Disabled),
+	}));
+	assert_eq!(subsystem.active_requests.len(), 3);
+
+	let (tx, rx) = oneshot::channel();
+	subsystem.spawn_request(relay_parent, Request::AvailabilityCores(tx));
+	assert_eq!(subsystem.active_requests.len(), 3, "the request must wait for the prefetch");
+	drop(lock);
+
+	// Availability cores, session index and validator groups, then the session info.
+	futures::executor::block_on(async {
+		for _ in 0..4 {
+			subsystem.poll_requests().await;
+		}
+		assert_eq!(rx.await.unwrap().unwrap(), vec![CoreState::Free]);
+	});
+	assert!(subsystem.in_flight_prefetches.is_empty());
+}
+
 #[test]
 fn requests_validation_code() {
 	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
@@ -997,6 +1100,89 @@ fn requests_pvfs_require_precheck() {
 	futures::executor::block_on(future::join(subsystem_task, test_task));
 }
 
+#[test]
+fn requests_unapplied_slashes() {
+	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	let slashes = vec![(
+		1,
+		CandidateHash([2; 32].into()),
+		slashing::PendingSlashes {
+			keys: vec![(ValidatorIndex(0), sp_keyring::Sr25519Keyring::Alice.public().into())]
+				.into_iter()
+				.collect(),
+			kind: slashing::SlashingOffenceKind::ForInvalid,
+		},
+	)];
+
+	let runtime_api = Arc::new({
+		let mut runtime_api = MockRuntimeApi::default();
+		runtime_api.unapplied_slashes = slashes.clone();
+		runtime_api
+	});
+
+	let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+
+	let relay_parent = [1; 32].into();
+	let test_task = async move {
+		let (tx, rx) = oneshot::channel();
+
+		ctx_handle
+			.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(relay_parent, Request::UnappliedSlashes(tx)),
+			})
+			.await;
+
+		assert_eq!(rx.await.unwrap().unwrap(), slashes);
+		ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
+
+#[test]
+fn requests_inherent_weight_info() {
+	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	let weight_info = InherentWeightInfo {
+		max_block_weight: 2_000_000_000_000,
+		bitfield: 1_000,
+		backed_candidate_base: 10_000,
+		backed_candidate_per_vote: 100,
+		backed_candidate_code_upgrade: 1_000_000,
+		dispute_statement_set_base: 20_000,
+		dispute_statement: 200,
+	};
+
+	let runtime_api = Arc::new({
+		let mut runtime_api = MockRuntimeApi::default();
+		runtime_api.inherent_weight_info = weight_info.clone();
+		runtime_api
+	});
+
+	let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+
+	let relay_parent = [1; 32].into();
+	let test_task = async move {
+		let (tx, rx) = oneshot::channel();
+
+		ctx_handle
+			.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(relay_parent, Request::InherentWeightInfo(tx)),
+			})
+			.await;
+
+		assert_eq!(rx.await.unwrap().unwrap(), weight_info);
+		ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
+
 #[test]
 fn requests_validation_code_hash() {
 	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
@@ -16,20 +16,41 @@
 
 //! Polkadot Jaeger configuration.
 
+use std::{collections::HashMap, net::SocketAddr, path::PathBuf};
+
+use crate::Stage;
+
+/// The destination of the collected spans.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JaegerExporter {
+	/// A jaeger agent, receiving thrift encoded spans via `UDP`.
+	Agent(SocketAddr),
+	/// An OpenTelemetry collector, receiving spans via OTLP over `HTTP`
+	/// with `JSON` encoding at `/v1/traces`.
+	Otlp(SocketAddr),
+	/// A file, spans are appended in the OTLP `JSON` encoding,
+	/// one batch per line.
+	File(PathBuf),
+}
+
 /// Configuration for the jaeger tracing.
 #[derive(Clone)]
 pub struct JaegerConfig {
 	pub(crate) node_name: String,
-	pub(crate) agent_addr: std::net::SocketAddr,
+	pub(crate) exporter: JaegerExporter,
+	pub(crate) stage_sampling: HashMap<Stage, f64>,
 }
 
 impl std::default::Default for JaegerConfig {
 	fn default() -> Self {
 		Self {
 			node_name: "unknown_".to_owned(),
-			agent_addr: "127.0.0.1:6831"
-				.parse()
-				.expect(r#"Static "127.0.0.1:6831" is a valid socket address string. qed"#),
+			exporter: JaegerExporter::Agent(
+				"127.0.0.1:6831"
+					.parse()
+					.expect(r#"Static "127.0.0.1:6831" is a valid socket address string. qed"#),
+			),
+			stage_sampling: HashMap::new(),
 		}
 	}
 }
@@ -62,7 +83,30 @@ impl JaegerConfigBuilder {
 	where
 		U: Into<std::net::SocketAddr>,
 	{
-		self.inner.agent_addr = addr.into();
+		self.exporter(JaegerExporter::Agent(addr.into()))
+	}
+
+	/// Set the OpenTelemetry collector address to send the collected spans to.
+	pub fn otlp<U>(self, addr: U) -> Self
+	where
+		U: Into<std::net::SocketAddr>,
+	{
+		self.exporter(JaegerExporter::Otlp(addr.into()))
+	}
+
+	/// Set the destination of the collected spans.
+	pub fn exporter(mut self, exporter: JaegerExporter) -> Self {
+		self.inner.exporter = exporter;
+		self
+	}
+
+	/// Only export the given fraction of candidates at `stage`, between `0.0` and `1.0`.
+	///
+	/// The decision is derived from the trace identifier, so every node samples
+	/// the same candidates. Stages without a sampling ratio are always exported.
+	/// Not supported by [`JaegerExporter::Agent`], which ignores it.
+	pub fn sample_stage(mut self, stage: Stage, ratio: f64) -> Self {
+		self.inner.stage_sampling.insert(stage, ratio.max(0.0).min(1.0));
 		self
 	}
 
@@ -25,4 +25,7 @@ pub enum JaegerError {
 
 	#[error("Missing jaeger configuration")]
 	MissingConfiguration,
+
+	#[error("Failed to open span file {0:?}")]
+	SpanFile(std::path::PathBuf, #[source] std::io::Error),
 }
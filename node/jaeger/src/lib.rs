@@ -43,15 +43,23 @@
 //!  -p 9411:9411 \
 //!  docker.io/jaegertracing/all-in-one:1.21
 //! ```
+//!
+//! # OpenTelemetry
+//!
+//! Instead of a jaeger agent, spans can be exported to an OpenTelemetry collector,
+//! via OTLP over `HTTP` with `JSON` encoding, or appended to a file in the same
+//! encoding for offline analysis, see [`JaegerExporter`]. With these, the fraction
+//! of exported candidates can be configured per [`Stage`].
 
 #![forbid(unused_imports)]
 
 mod config;
 mod errors;
+mod otlp;
 mod spans;
 
 pub use self::{
-	config::{JaegerConfig, JaegerConfigBuilder},
+	config::{JaegerConfig, JaegerConfigBuilder, JaegerExporter},
 	errors::JaegerError,
 	spans::{PerLeafSpan, Span, Stage},
 };
@@ -63,6 +71,14 @@ use sp_core::traits::SpawnNamed;
 use parking_lot::RwLock;
 use std::{result, sync::Arc};
 
+/// Interval at which recorded spans are sent to an OpenTelemetry collector or file.
+#[cfg(not(target_os = "unknown"))]
+const OTLP_EXPORT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Timeout for sending recorded spans to an OpenTelemetry collector and receiving its response.
+#[cfg(not(target_os = "unknown"))]
+const OTLP_EXPORT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
 lazy_static::lazy_static! {
 	static ref INSTANCE: RwLock<Jaeger> = RwLock::new(Jaeger::None);
 }
@@ -74,6 +90,11 @@ pub enum Jaeger {
 		/// [`mick_jaeger`] provided API to record spans to.
 		traces_in: Arc<mick_jaeger::TracesIn>,
 	},
+	/// Launched and operational state, exporting to an OpenTelemetry collector or file.
+	LaunchedOtlp {
+		/// Records spans until they are exported.
+		recorder: Arc<otlp::Recorder>,
+	},
 	/// Preparation state with the necessary config to launch the collector.
 	Prep(JaegerConfig),
 	/// Uninitialized, suggests wrong API usage if encountered.
@@ -92,22 +113,36 @@ impl Jaeger {
 		Ok(())
 	}
 
-	/// Spawn the background task in order to send the tracing information out via UDP
+	/// Spawn the background task in order to send the tracing information out via UDP,
+	/// to an OpenTelemetry collector or to a file.
 	#[cfg(not(target_os = "unknown"))]
 	pub fn launch<S: SpawnNamed>(self, spawner: S) -> result::Result<(), JaegerError> {
 		let cfg = match self {
 			Self::Prep(cfg) => Ok(cfg),
-			Self::Launched { .. } => return Err(JaegerError::AlreadyLaunched),
+			Self::Launched { .. } | Self::LaunchedOtlp { .. } =>
+				return Err(JaegerError::AlreadyLaunched),
 			Self::None => Err(JaegerError::MissingConfiguration),
 		}?;
 
-		let jaeger_agent = cfg.agent_addr;
+		let service_name = format!("polkadot-{}", cfg.node_name);
+
+		let jaeger_agent = match cfg.exporter {
+			JaegerExporter::Agent(addr) => addr,
+			exporter => {
+				let recorder = Arc::new(otlp::Recorder::new(service_name, cfg.stage_sampling));
+				spawn_otlp_exporter(&spawner, exporter, recorder.clone())?;
+				*INSTANCE.write() = Self::LaunchedOtlp { recorder };
+				return Ok(())
+			},
+		};
+
+		if !cfg.stage_sampling.is_empty() {
+			log::warn!(target: "jaeger", "Stage sampling is not supported with a jaeger agent");
+		}
 
 		log::info!("🐹 Collecting jaeger spans for {:?}", &jaeger_agent);
 
-		let (traces_in, mut traces_out) = mick_jaeger::init(mick_jaeger::Config {
-			service_name: format!("polkadot-{}", cfg.node_name),
-		});
+		let (traces_in, mut traces_out) = mick_jaeger::init(mick_jaeger::Config { service_name });
 
 		// Spawn a background task that pulls span information and sends them on the network.
 		spawner.spawn(
@@ -133,16 +168,102 @@ impl Jaeger {
 		Ok(())
 	}
 
-	pub(crate) fn span<F>(&self, lazy_hash: F, span_name: &'static str) -> Option<mick_jaeger::Span>
+	pub(crate) fn span<F>(&self, lazy_hash: F, span_name: &'static str) -> Span
 	where
 		F: Fn() -> TraceIdentifier,
 	{
-		if let Self::Launched { traces_in, .. } = self {
-			let ident = lazy_hash();
-			let trace_id = std::num::NonZeroU128::new(ident)?;
-			Some(traces_in.span(trace_id, span_name))
-		} else {
-			None
+		match self {
+			Self::Launched { traces_in, .. } => std::num::NonZeroU128::new(lazy_hash())
+				.map(|trace_id| traces_in.span(trace_id, span_name))
+				.into(),
+			Self::LaunchedOtlp { recorder } => match lazy_hash() {
+				0 => Span::Disabled,
+				trace_id => recorder.span(trace_id, span_name).into(),
+			},
+			_ => Span::Disabled,
 		}
 	}
 }
+
+/// Spawn the background task exporting the spans of `recorder` to a collector or file.
+#[cfg(not(target_os = "unknown"))]
+fn spawn_otlp_exporter<S: SpawnNamed>(
+	spawner: &S,
+	exporter: JaegerExporter,
+	recorder: Arc<otlp::Recorder>,
+) -> result::Result<(), JaegerError> {
+	use async_std::io::prelude::*;
+
+	match exporter {
+		JaegerExporter::Agent(_) => Ok(()),
+		JaegerExporter::Otlp(collector) => {
+			log::info!("🐹 Collecting spans for the OpenTelemetry collector at {:?}", &collector);
+
+			let uri: hyper::Uri = format!("http://{}/v1/traces", collector)
+				.parse()
+				.expect("A socket address is a valid URI authority; qed");
+			let client = hyper::Client::new();
+
+			spawner.spawn(
+				"jaeger-otlp-exporter",
+				Some("jaeger"),
+				Box::pin(async move {
+					loop {
+						async_std::task::sleep(OTLP_EXPORT_INTERVAL).await;
+						let body = match recorder.drain_encoded() {
+							Some(body) => body,
+							None => continue,
+						};
+
+						let request = hyper::Request::post(uri.clone())
+							.header(hyper::header::CONTENT_TYPE, "application/json")
+							.body(hyper::Body::from(body))
+							.expect("The URI and header of the request are valid; qed");
+
+						// Exporting is best effort, the spans of this interval are lost.
+						let res = client.request(request);
+						match async_std::future::timeout(OTLP_EXPORT_TIMEOUT, res).await {
+							Ok(Ok(response)) if response.status().is_success() => {},
+							Ok(Ok(response)) => log::debug!(
+								target: "jaeger",
+								"OTLP export rejected: {}",
+								response.status(),
+							),
+							Ok(Err(e)) => log::debug!(target: "jaeger", "OTLP export error: {}", e),
+							Err(_) => log::debug!(target: "jaeger", "OTLP export timed out"),
+						}
+					}
+				}),
+			);
+			Ok(())
+		},
+		JaegerExporter::File(path) => {
+			log::info!("🐹 Collecting spans in {}", path.display());
+
+			let file = std::fs::OpenOptions::new()
+				.create(true)
+				.append(true)
+				.open(&path)
+				.map_err(|e| JaegerError::SpanFile(path, e))?;
+			let mut file = async_std::fs::File::from(file);
+
+			spawner.spawn(
+				"jaeger-file-exporter",
+				Some("jaeger"),
+				Box::pin(async move {
+					loop {
+						async_std::task::sleep(OTLP_EXPORT_INTERVAL).await;
+						if let Some(mut batch) = recorder.drain_encoded() {
+							batch.push('\n');
+							if let Err(e) = file.write_all(batch.as_bytes()).await {
+								log::warn!(target: "jaeger", "Span file write error: {}", e);
+								return
+							}
+						}
+					}
+				}),
+			);
+			Ok(())
+		},
+	}
+}
@@ -0,0 +1,311 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Recording of spans for OpenTelemetry collectors and files.
+//!
+//! Unlike [`mick_jaeger`], which only produces thrift encoded batches for a jaeger
+//! agent, spans are recorded as plain data and encoded in the OTLP `JSON` encoding,
+//! which both an OpenTelemetry collector and offline tooling can ingest.
+
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+
+use crate::{spans::TraceIdentifier, Stage};
+
+/// Maximum number of finished spans waiting for export, additional ones are dropped.
+const MAX_PENDING_SPANS: usize = 16 * 1024;
+
+/// The value of a span attribute.
+#[derive(Debug, Clone, PartialEq)]
+enum AttributeValue {
+	String(String),
+	Int(i64),
+}
+
+/// A finished span, waiting to be exported.
+#[derive(Debug, Clone)]
+pub(crate) struct SpanData {
+	trace_id: TraceIdentifier,
+	span_id: u64,
+	parent_span_id: Option<u64>,
+	name: &'static str,
+	start: SystemTime,
+	end: SystemTime,
+	attributes: Vec<(&'static str, AttributeValue)>,
+	links: Vec<(TraceIdentifier, u64)>,
+}
+
+/// Collects finished spans until they are exported.
+pub struct Recorder {
+	service_name: String,
+	stage_sampling: HashMap<Stage, f64>,
+	next_span_id: AtomicU64,
+	pending: Mutex<Vec<SpanData>>,
+}
+
+impl Recorder {
+	pub(crate) fn new(service_name: String, stage_sampling: HashMap<Stage, f64>) -> Self {
+		// Avoid colliding span identifiers of consecutive runs of the same node.
+		let seed = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|d| d.as_nanos() as u64)
+			.unwrap_or_default();
+
+		Recorder {
+			service_name,
+			stage_sampling,
+			next_span_id: AtomicU64::new(seed | 1),
+			pending: Mutex::new(Vec::new()),
+		}
+	}
+
+	/// Start a new root span of the trace `trace_id`.
+	pub(crate) fn span(
+		self: &Arc<Self>,
+		trace_id: TraceIdentifier,
+		name: &'static str,
+	) -> OtlpSpan {
+		self.start(trace_id, None, name)
+	}
+
+	fn start(
+		self: &Arc<Self>,
+		trace_id: TraceIdentifier,
+		parent_span_id: Option<u64>,
+		name: &'static str,
+	) -> OtlpSpan {
+		let now = SystemTime::now();
+		let data = SpanData {
+			trace_id,
+			span_id: self.next_span_id.fetch_add(1, Ordering::Relaxed).max(1),
+			parent_span_id,
+			name,
+			start: now,
+			end: now,
+			attributes: Vec::new(),
+			links: Vec::new(),
+		};
+
+		OtlpSpan { data: Some(data), recorder: self.clone() }
+	}
+
+	/// Whether the trace `trace_id` is sampled at `stage`.
+	fn is_sampled(&self, stage: Stage, trace_id: TraceIdentifier) -> bool {
+		match self.stage_sampling.get(&stage) {
+			// Trace identifiers are derived from hashes, their upper half is uniformly distributed.
+			Some(ratio) => ((trace_id >> 64) as u64 as f64) < ratio * u64::MAX as f64,
+			None => true,
+		}
+	}
+
+	fn finish(&self, span: SpanData) {
+		let mut pending = self.pending.lock();
+		if pending.len() < MAX_PENDING_SPANS {
+			pending.push(span);
+		} else {
+			log::trace!(target: "jaeger", "Dropping span {}, too many pending spans", span.name);
+		}
+	}
+
+	/// Take all finished spans, encoded as an OTLP `ExportTraceServiceRequest`.
+	///
+	/// Returns `None` if there are none.
+	pub(crate) fn drain_encoded(&self) -> Option<String> {
+		let spans = std::mem::take(&mut *self.pending.lock());
+		if spans.is_empty() {
+			return None
+		}
+
+		Some(encode_json(&self.service_name, &spans).to_string())
+	}
+}
+
+/// A span recorded for an OpenTelemetry collector or file.
+///
+/// Recorded once dropped.
+pub struct OtlpSpan {
+	data: Option<SpanData>,
+	recorder: Arc<Recorder>,
+}
+
+impl OtlpSpan {
+	pub(crate) fn child(&self, name: &'static str) -> Self {
+		match self.data {
+			Some(ref data) => self.recorder.start(data.trace_id, Some(data.span_id), name),
+			None => OtlpSpan { data: None, recorder: self.recorder.clone() },
+		}
+	}
+
+	/// Returns `None` and discards the span, if its trace is not sampled at `stage`.
+	pub(crate) fn sample_stage(mut self, stage: Stage) -> Option<Self> {
+		match self.data {
+			Some(ref data) if self.recorder.is_sampled(stage, data.trace_id) => Some(self),
+			_ => {
+				self.data = None;
+				None
+			},
+		}
+	}
+
+	pub(crate) fn add_follows_from(&mut self, other: &Self) {
+		if let (Some(data), Some(other)) = (self.data.as_mut(), other.data.as_ref()) {
+			data.links.push((other.trace_id, other.span_id));
+		}
+	}
+
+	pub(crate) fn add_string_tag(&mut self, tag: &'static str, value: String) {
+		if let Some(ref mut data) = self.data {
+			data.attributes.push((tag, AttributeValue::String(value)));
+		}
+	}
+
+	pub(crate) fn add_int_tag(&mut self, tag: &'static str, value: i64) {
+		if let Some(ref mut data) = self.data {
+			data.attributes.push((tag, AttributeValue::Int(value)));
+		}
+	}
+}
+
+impl Drop for OtlpSpan {
+	fn drop(&mut self) {
+		if let Some(mut data) = self.data.take() {
+			data.end = SystemTime::now();
+			self.recorder.finish(data);
+		}
+	}
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+	time.duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default().to_string()
+}
+
+fn encode_attribute((key, value): &(&'static str, AttributeValue)) -> Value {
+	let value = match value {
+		AttributeValue::String(s) => json!({ "stringValue": s }),
+		// 64 bit integers are encoded as strings in the `JSON` mapping of protobuf.
+		AttributeValue::Int(i) => json!({ "intValue": i.to_string() }),
+	};
+	json!({ "key": key, "value": value })
+}
+
+fn encode_span(span: &SpanData) -> Value {
+	let mut encoded = json!({
+		"traceId": format!("{:032x}", span.trace_id),
+		"spanId": format!("{:016x}", span.span_id),
+		"name": span.name,
+		// `SPAN_KIND_INTERNAL`
+		"kind": 1,
+		"startTimeUnixNano": unix_nanos(span.start),
+		"endTimeUnixNano": unix_nanos(span.end),
+		"attributes": span.attributes.iter().map(encode_attribute).collect::<Vec<_>>(),
+		"links": span
+			.links
+			.iter()
+			.map(|(trace_id, span_id)| {
+				json!({
+					"traceId": format!("{:032x}", trace_id),
+					"spanId": format!("{:016x}", span_id),
+				})
+			})
+			.collect::<Vec<_>>(),
+	});
+	if let Some(parent) = span.parent_span_id {
+		encoded["parentSpanId"] = json!(format!("{:016x}", parent));
+	}
+	encoded
+}
+
+fn encode_json(service_name: &str, spans: &[SpanData]) -> Value {
+	json!({
+		"resourceSpans": [{
+			"resource": {
+				"attributes": [{
+					"key": "service.name",
+					"value": { "stringValue": service_name },
+				}],
+			},
+			"scopeSpans": [{
+				"scope": { "name": "polkadot-node-jaeger" },
+				"spans": spans.iter().map(encode_span).collect::<Vec<_>>(),
+			}],
+		}],
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn recorder(stage_sampling: HashMap<Stage, f64>) -> Arc<Recorder> {
+		Arc::new(Recorder::new("polkadot-test".to_owned(), stage_sampling))
+	}
+
+	#[test]
+	fn children_share_the_trace() {
+		let recorder = recorder(HashMap::new());
+		{
+			let mut root = recorder.span(0xabcd, "root");
+			root.add_int_tag("para-id", 100);
+			let mut child = root.child("child");
+			child.add_string_tag("candidate-stage", "2".to_owned());
+		}
+
+		let encoded: Value = serde_json::from_str(&recorder.drain_encoded().unwrap()).unwrap();
+		let spans = &encoded["resourceSpans"][0]["scopeSpans"][0]["spans"];
+		// the child is dropped first
+		let (child, root) = (&spans[0], &spans[1]);
+
+		assert_eq!(root["name"], "root");
+		assert_eq!(root["traceId"], format!("{:032x}", 0xabcd));
+		assert!(root.get("parentSpanId").is_none());
+		assert_eq!(root["attributes"][0]["value"]["intValue"], "100");
+
+		assert_eq!(child["traceId"], root["traceId"]);
+		assert_eq!(child["parentSpanId"], root["spanId"]);
+		assert_eq!(child["attributes"][0]["key"], "candidate-stage");
+
+		assert!(recorder.drain_encoded().is_none());
+	}
+
+	#[test]
+	fn sampling_is_per_stage_and_trace() {
+		let mut stage_sampling = HashMap::new();
+		stage_sampling.insert(Stage::CandidateBacking, 0.5);
+		let recorder = recorder(stage_sampling);
+
+		let low = 1_u128 << 64;
+		let high = u128::MAX;
+
+		assert!(recorder.is_sampled(Stage::CandidateBacking, low));
+		assert!(!recorder.is_sampled(Stage::CandidateBacking, high));
+		assert!(recorder.is_sampled(Stage::ApprovalChecking, high));
+
+		assert!(recorder.span(high, "discarded").sample_stage(Stage::CandidateBacking).is_none());
+		assert!(recorder.drain_encoded().is_none());
+
+		drop(recorder.span(low, "kept").sample_stage(Stage::CandidateBacking));
+		assert!(recorder.drain_encoded().is_some());
+	}
+}
@@ -92,7 +92,7 @@ use sc_network::PeerId;
 
 use std::{fmt, sync::Arc};
 
-use super::INSTANCE;
+use super::{otlp::OtlpSpan, INSTANCE};
 
 /// A special "per leaf span".
 ///
@@ -140,7 +140,7 @@ impl std::ops::Deref for PerLeafSpan {
 /// statistical metrics for which stage of the inclusion
 /// pipeline drops a significant amount of candidates,
 /// statistically speaking.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
 #[non_exhaustive]
 pub enum Stage {
@@ -158,12 +158,31 @@ pub enum Stage {
 	// see [issue](https://github.com/paritytech/polkadot/issues/2389)
 }
 
+impl std::str::FromStr for Stage {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"candidate-backing" => Stage::CandidateBacking,
+			"statement-distribution" => Stage::StatementDistribution,
+			"pov-distribution" => Stage::PoVDistribution,
+			"availability-distribution" => Stage::AvailabilityDistribution,
+			"availability-recovery" => Stage::AvailabilityRecovery,
+			"bitfield-distribution" => Stage::BitfieldDistribution,
+			"approval-checking" => Stage::ApprovalChecking,
+			_ => return Err(format!("Unknown stage `{}`", s)),
+		})
+	}
+}
+
 /// A wrapper type for a span.
 ///
 /// Handles running with and without jaeger.
 pub enum Span {
 	/// Running with jaeger being enabled.
 	Enabled(mick_jaeger::Span),
+	/// Running with an OpenTelemetry collector or file as destination.
+	Otlp(OtlpSpan),
 	/// Running with jaeger disabled.
 	Disabled,
 }
@@ -243,8 +262,7 @@ impl Span {
 	pub fn new<I: LazyIdent>(identifier: I, span_name: &'static str) -> Span {
 		let mut span = INSTANCE
 			.read_recursive()
-			.span(|| <I as LazyIdent>::eval(&identifier), span_name)
-			.into();
+			.span(|| <I as LazyIdent>::eval(&identifier), span_name);
 		<I as LazyIdent>::extra_tags(&identifier, &mut span);
 		span
 	}
@@ -261,13 +279,13 @@ impl Span {
 				},
 				span_name,
 			)
-			.into()
 	}
 
 	/// Derive a child span from `self`.
 	pub fn child(&self, name: &'static str) -> Self {
 		match self {
 			Self::Enabled(inner) => Self::Enabled(inner.child(name)),
+			Self::Otlp(inner) => Self::Otlp(inner.child(name)),
 			Self::Disabled => Self::Disabled,
 		}
 	}
@@ -297,9 +315,18 @@ impl Span {
 
 	/// Attach a candidate stage.
 	/// Should always come with a `CandidateHash`.
+	///
+	/// Disables the span if the trace is not sampled at `stage`.
 	#[inline(always)]
 	pub fn with_stage(self, stage: Stage) -> Self {
-		self.with_string_tag("candidate-stage", stage as u8)
+		let span = match self {
+			Self::Otlp(inner) => match inner.sample_stage(stage) {
+				Some(inner) => Self::Otlp(inner),
+				None => return Self::Disabled,
+			},
+			span => span,
+		};
+		span.with_string_tag("candidate-stage", stage as u8)
 	}
 
 	#[inline(always)]
@@ -355,6 +382,8 @@ impl Span {
 		match (self, other) {
 			(Self::Enabled(ref mut inner), Self::Enabled(ref other_inner)) =>
 				inner.add_follows_from(&other_inner),
+			(Self::Otlp(ref mut inner), Self::Otlp(ref other_inner)) =>
+				inner.add_follows_from(&other_inner),
 			_ => {},
 		}
 	}
@@ -377,6 +406,7 @@ impl Span {
 	pub fn add_string_tag<V: ToString>(&mut self, tag: &'static str, val: V) {
 		match self {
 			Self::Enabled(ref mut inner) => inner.add_string_tag(tag, val.to_string().as_str()),
+			Self::Otlp(ref mut inner) => inner.add_string_tag(tag, val.to_string()),
 			Self::Disabled => {},
 		}
 	}
@@ -386,6 +416,7 @@ impl Span {
 		match self {
 			Self::Enabled(ref mut inner) =>
 				inner.add_string_tag(tag, format!("{:?}", val).as_str()),
+			Self::Otlp(ref mut inner) => inner.add_string_tag(tag, format!("{:?}", val)),
 			Self::Disabled => {},
 		}
 	}
@@ -393,6 +424,7 @@ impl Span {
 	pub fn add_int_tag(&mut self, tag: &'static str, value: i64) {
 		match self {
 			Self::Enabled(ref mut inner) => inner.add_int_tag(tag, value),
+			Self::Otlp(ref mut inner) => inner.add_int_tag(tag, value),
 			Self::Disabled => {},
 		}
 	}
@@ -400,6 +432,7 @@ impl Span {
 	pub fn add_uint_tag(&mut self, tag: &'static str, value: u64) {
 		match self {
 			Self::Enabled(ref mut inner) => inner.add_int_tag(tag, value as i64),
+			Self::Otlp(ref mut inner) => inner.add_int_tag(tag, value as i64),
 			Self::Disabled => {},
 		}
 	}
@@ -408,8 +441,8 @@ impl Span {
 	/// in order to avoid computational overhead.
 	pub const fn is_enabled(&self) -> bool {
 		match self {
-			Span::Enabled(_) => true,
-			_ => false,
+			Span::Enabled(_) | Span::Otlp(_) => true,
+			Span::Disabled => false,
 		}
 	}
 }
@@ -435,3 +468,9 @@ impl From<mick_jaeger::Span> for Span {
 		Self::Enabled(src)
 	}
 }
+
+impl From<OtlpSpan> for Span {
+	fn from(src: OtlpSpan) -> Self {
+		Self::Otlp(src)
+	}
+}
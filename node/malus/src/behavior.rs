@@ -0,0 +1,336 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime configurable malicious behavior.
+//!
+//! All variants consult a [`Behavior`] before they misbehave on a candidate or message.
+//! Its [`BehaviorSpec`] is given on the command line and, optionally, replaced at runtime
+//! by values sent to the key [`BACKCHANNEL_KEY`] of the zombienet backchannel, such that
+//! a single network can test both the onset of and the recovery from misbehavior.
+//!
+//! A spec is written as `;` separated fields, all of which are optional:
+//!
+//! * `fraction=0.25`: the fraction of candidates or messages to misbehave on, `1` by default
+//! * `paras=100,200`: the para ids to misbehave on, all by default, rejected by variants
+//!   which can't tell the para of what they misbehave on
+//! * `windows=30-90,120-`: the time windows, in seconds since startup, to misbehave in,
+//!   always by default
+//!
+//! The empty spec `""` thus misbehaves on everything, all the time, while `fraction=0`
+//! behaves honestly.
+
+use std::{
+	fmt,
+	ops::Range,
+	str::FromStr,
+	sync::{Arc, RwLock},
+	time::{Duration, Instant},
+};
+
+use color_eyre::eyre;
+use polkadot_node_primitives::SpawnNamed;
+use polkadot_primitives::v1::{Hash, Id as ParaId};
+use structopt::StructOpt;
+use zombienet_backchannel::ZombienetBackchannel;
+
+use crate::shared::MALUS;
+
+/// The backchannel key updated behavior specs are sent with.
+pub const BACKCHANNEL_KEY: &str = "malus-behavior";
+
+/// What to misbehave on, and when.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BehaviorSpec {
+	/// Fraction of the candidates or messages to misbehave on.
+	pub fraction: f64,
+	/// Para ids to misbehave on, all if `None`.
+	pub paras: Option<Vec<ParaId>>,
+	/// Time windows since startup to misbehave in, always if empty.
+	pub windows: Vec<Range<Duration>>,
+}
+
+impl Default for BehaviorSpec {
+	fn default() -> Self {
+		BehaviorSpec { fraction: 1.0, paras: None, windows: Vec::new() }
+	}
+}
+
+impl BehaviorSpec {
+	fn is_active(&self, elapsed: Duration) -> bool {
+		self.windows.is_empty() || self.windows.iter().any(|window| window.contains(&elapsed))
+	}
+
+	fn targets_para(&self, para_id: Option<ParaId>) -> bool {
+		match (&self.paras, para_id) {
+			(Some(paras), Some(para_id)) => paras.contains(&para_id),
+			// Not all messages tell which para they are about.
+			_ => true,
+		}
+	}
+
+	fn samples(&self, subject: &Hash) -> bool {
+		// Hashes are uniformly distributed, such that the decision is stable per subject.
+		let mut prefix = [0u8; 8];
+		prefix.copy_from_slice(&subject.as_bytes()[..8]);
+		let sample = u64::from_le_bytes(prefix) as f64;
+		self.fraction >= 1.0 || sample < self.fraction * u64::MAX as f64
+	}
+}
+
+fn parse_window(s: &str) -> Result<Range<Duration>, String> {
+	let (start, end) =
+		s.split_once('-').ok_or_else(|| format!("Expected `start-end`, got `{}`", s))?;
+	let secs = |s: &str| {
+		s.parse::<u64>()
+			.map(Duration::from_secs)
+			.map_err(|e| format!("Invalid seconds `{}`: {}", s, e))
+	};
+	let start = secs(start)?;
+	let end = if end.is_empty() { Duration::MAX } else { secs(end)? };
+	if start >= end {
+		return Err(format!("Window `{}` is empty", s))
+	}
+	Ok(start..end)
+}
+
+impl FromStr for BehaviorSpec {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut spec = BehaviorSpec::default();
+		for field in s.split(';').map(str::trim).filter(|field| !field.is_empty()) {
+			let (key, value) = field
+				.split_once('=')
+				.ok_or_else(|| format!("Expected `key=value`, got `{}`", field))?;
+			match key.trim() {
+				"fraction" => {
+					let fraction = value
+						.parse::<f64>()
+						.map_err(|e| format!("Invalid fraction `{}`: {}", value, e))?;
+					if !(0.0..=1.0).contains(&fraction) {
+						return Err(format!("Fraction `{}` is not between 0 and 1", fraction))
+					}
+					spec.fraction = fraction;
+				},
+				"paras" =>
+					spec.paras = Some(
+						value
+							.split(',')
+							.map(|id| {
+								id.trim()
+									.parse::<u32>()
+									.map(ParaId::from)
+									.map_err(|e| format!("Invalid para id `{}`: {}", id, e))
+							})
+							.collect::<Result<_, _>>()?,
+					),
+				"windows" =>
+					spec.windows = value
+						.split(',')
+						.map(|window| parse_window(window.trim()))
+						.collect::<Result<_, _>>()?,
+				key => return Err(format!("Unknown behavior field `{}`", key)),
+			}
+		}
+		Ok(spec)
+	}
+}
+
+impl fmt::Display for BehaviorSpec {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "fraction={}", self.fraction)?;
+		if let Some(ref paras) = self.paras {
+			let paras = paras.iter().map(|id| u32::from(*id).to_string()).collect::<Vec<_>>();
+			write!(f, ";paras={}", paras.join(","))?;
+		}
+		if !self.windows.is_empty() {
+			let windows = self
+				.windows
+				.iter()
+				.map(|window| {
+					if window.end == Duration::MAX {
+						format!("{}-", window.start.as_secs())
+					} else {
+						format!("{}-{}", window.start.as_secs(), window.end.as_secs())
+					}
+				})
+				.collect::<Vec<_>>();
+			write!(f, ";windows={}", windows.join(","))?;
+		}
+		Ok(())
+	}
+}
+
+/// Command line options shared by all variants.
+#[derive(Debug, StructOpt)]
+pub struct BehaviorOptions {
+	/// The initial behavior spec, e.g. `fraction=0.5;paras=100;windows=30-90`.
+	#[structopt(long, default_value = "")]
+	pub behavior: BehaviorSpec,
+
+	/// Replace the behavior spec with values received from the zombienet backchannel.
+	#[structopt(long)]
+	pub behavior_backchannel: bool,
+}
+
+/// The current behavior spec, shared by all interceptors of a node.
+#[derive(Debug, Clone)]
+pub struct Behavior {
+	spec: Arc<RwLock<BehaviorSpec>>,
+	started: Instant,
+	backchannel: bool,
+	paras_known: bool,
+}
+
+impl Behavior {
+	/// Create a behavior from the command line options.
+	pub fn new(options: BehaviorOptions) -> Self {
+		Behavior {
+			spec: Arc::new(RwLock::new(options.behavior)),
+			started: Instant::now(),
+			backchannel: options.behavior_backchannel,
+			paras_known: true,
+		}
+	}
+
+	/// Create a behavior from the command line options, for a variant which can't tell the
+	/// para of what it misbehaves on. Specs targeting paras are rejected.
+	pub fn without_paras(options: BehaviorOptions) -> eyre::Result<Self> {
+		if options.behavior.paras.is_some() {
+			return Err(eyre::eyre!("This variant can't target paras, remove `paras` from the spec"))
+		}
+		Ok(Behavior { paras_known: false, ..Behavior::new(options) })
+	}
+
+	/// Whether to misbehave on `subject`, a candidate hash or relay parent,
+	/// of the para `para_id`, if known.
+	pub fn applies(&self, para_id: Option<ParaId>, subject: &Hash) -> bool {
+		let spec = self.spec.read().unwrap();
+		spec.is_active(self.started.elapsed()) &&
+			spec.targets_para(para_id) &&
+			spec.samples(subject)
+	}
+
+	/// Replace the current behavior spec.
+	pub fn update(&self, spec: BehaviorSpec) {
+		if spec.paras.is_some() && !self.paras_known {
+			tracing::warn!(target = MALUS, %spec, "This variant can't target paras, ignoring spec");
+			return
+		}
+		tracing::info!(target = MALUS, %spec, "Updating behavior");
+		*self.spec.write().unwrap() = spec;
+	}
+
+	/// Keep the spec up to date with the backchannel, if enabled.
+	pub fn spawn_backchannel_updates(&self, spawner: &impl SpawnNamed) {
+		if !self.backchannel {
+			return
+		}
+
+		let behavior = self.clone();
+		spawner.spawn(
+			"malus-behavior-updates",
+			Some("malus"),
+			Box::pin(async move {
+				// Fine if another task initialized it already.
+				let _ = ZombienetBackchannel::init().await;
+				let mut items = match ZombienetBackchannel::broadcaster()
+					.and_then(|broadcaster| broadcaster.subscribe())
+				{
+					Ok(items) => items,
+					Err(err) => {
+						tracing::warn!(target = MALUS, ?err, "Backchannel unavailable");
+						return
+					},
+				};
+
+				while let Ok(item) = items.recv().await {
+					if item.key() != BACKCHANNEL_KEY {
+						continue
+					}
+					match item.value().parse() {
+						Ok(spec) => behavior.update(spec),
+						Err(err) => tracing::warn!(target = MALUS, %err, "Invalid behavior spec"),
+					}
+				}
+			}),
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn spec_roundtrips() {
+		let spec: BehaviorSpec = "fraction=0.5; paras=100,200; windows=30-90,120-".parse().unwrap();
+		assert_eq!(
+			spec,
+			BehaviorSpec {
+				fraction: 0.5,
+				paras: Some(vec![100.into(), 200.into()]),
+				windows: vec![
+					Duration::from_secs(30)..Duration::from_secs(90),
+					Duration::from_secs(120)..Duration::MAX,
+				],
+			},
+		);
+		assert_eq!(spec.to_string().parse::<BehaviorSpec>().unwrap(), spec);
+		assert_eq!("".parse::<BehaviorSpec>().unwrap(), BehaviorSpec::default());
+
+		assert!("fraction=2".parse::<BehaviorSpec>().is_err());
+		assert!("windows=30".parse::<BehaviorSpec>().is_err());
+		assert!("windows=90-30".parse::<BehaviorSpec>().is_err());
+		assert!("windows=30-30".parse::<BehaviorSpec>().is_err());
+		assert!("corrupt=all".parse::<BehaviorSpec>().is_err());
+	}
+
+	#[test]
+	fn spec_selects_subjects() {
+		let spec: BehaviorSpec = "fraction=0.5;paras=100;windows=30-90".parse().unwrap();
+		let low = Hash::zero();
+		let high = Hash::repeat_byte(0xff);
+
+		assert!(spec.samples(&low));
+		assert!(!spec.samples(&high));
+
+		assert!(spec.targets_para(Some(100.into())));
+		assert!(!spec.targets_para(Some(200.into())));
+		assert!(spec.targets_para(None));
+
+		assert!(!spec.is_active(Duration::from_secs(10)));
+		assert!(spec.is_active(Duration::from_secs(30)));
+		assert!(!spec.is_active(Duration::from_secs(90)));
+	}
+
+	#[test]
+	fn paras_are_rejected_if_unknown() {
+		let options = |behavior: &str| BehaviorOptions {
+			behavior: behavior.parse().unwrap(),
+			behavior_backchannel: false,
+		};
+		assert!(Behavior::without_paras(options("paras=100")).is_err());
+
+		let behavior = Behavior::without_paras(options("fraction=0.5")).unwrap();
+		behavior.update("paras=100".parse().unwrap());
+		assert_eq!(behavior.spec.read().unwrap().paras, None);
+
+		let behavior = Behavior::new(options("fraction=0.5"));
+		behavior.update("paras=100".parse().unwrap());
+		assert_eq!(behavior.spec.read().unwrap().paras, Some(vec![100.into()]));
+	}
+}
@@ -21,16 +21,20 @@
 //! messages on the overseer level.
 
 use polkadot_node_subsystem::*;
-pub use polkadot_node_subsystem::{messages::AllMessages, overseer, FromOverseer};
+pub use polkadot_node_subsystem::{
+	messages::AllMessages,
+	overseer::{self, OutgoingMessagesOf},
+	FromOverseer,
+};
 use std::{future::Future, pin::Pin};
 
 /// Filter incoming and outgoing messages.
 pub trait MessageInterceptor<Sender>: Send + Sync + Clone + 'static
 where
-	Sender: overseer::SubsystemSender<Self::Message> + Clone + 'static,
+	Sender: overseer::SubsystemSender<OutgoingMessagesOf<Self::Message>> + Clone + 'static,
 {
 	/// The message type the original subsystem handles incoming.
-	type Message: Send + 'static;
+	type Message: overseer::AssociateOutgoing<AllMessages> + Send + 'static;
 
 	/// Filter messages that are to be received by
 	/// the subsystem.
@@ -46,7 +50,10 @@ where
 	}
 
 	/// Modify outgoing messages.
-	fn intercept_outgoing(&self, msg: AllMessages) -> Option<AllMessages> {
+	fn intercept_outgoing(
+		&self,
+		msg: OutgoingMessagesOf<Self::Message>,
+	) -> Option<OutgoingMessagesOf<Self::Message>> {
 		Some(msg)
 	}
 }
@@ -59,13 +66,19 @@ pub struct InterceptedSender<Sender, Fil> {
 }
 
 #[async_trait::async_trait]
-impl<Sender, Fil> overseer::SubsystemSender<AllMessages> for InterceptedSender<Sender, Fil>
+impl<Sender, Fil>
+	overseer::SubsystemSender<OutgoingMessagesOf<<Fil as MessageInterceptor<Sender>>::Message>>
+	for InterceptedSender<Sender, Fil>
 where
-	Sender: overseer::SubsystemSender<AllMessages>
-		+ overseer::SubsystemSender<<Fil as MessageInterceptor<Sender>>::Message>,
+	Sender: overseer::SubsystemSender<
+		OutgoingMessagesOf<<Fil as MessageInterceptor<Sender>>::Message>,
+	>,
 	Fil: MessageInterceptor<Sender>,
 {
-	async fn send_message(&mut self, msg: AllMessages) {
+	async fn send_message(
+		&mut self,
+		msg: OutgoingMessagesOf<<Fil as MessageInterceptor<Sender>>::Message>,
+	) {
 		if let Some(msg) = self.message_filter.intercept_outgoing(msg) {
 			self.inner.send_message(msg).await;
 		}
@@ -73,7 +86,8 @@ where
 
 	async fn send_messages<T>(&mut self, msgs: T)
 	where
-		T: IntoIterator<Item = AllMessages> + Send,
+		T: IntoIterator<Item = OutgoingMessagesOf<<Fil as MessageInterceptor<Sender>>::Message>>
+			+ Send,
 		T::IntoIter: Send,
 	{
 		for msg in msgs {
@@ -81,7 +95,10 @@ where
 		}
 	}
 
-	fn send_unbounded_message(&mut self, msg: AllMessages) {
+	fn send_unbounded_message(
+		&mut self,
+		msg: OutgoingMessagesOf<<Fil as MessageInterceptor<Sender>>::Message>,
+	) {
 		if let Some(msg) = self.message_filter.intercept_outgoing(msg) {
 			self.inner.send_unbounded_message(msg);
 		}
@@ -94,7 +111,9 @@ where
 	Context: overseer::SubsystemContext + SubsystemContext,
 	Fil: MessageInterceptor<<Context as overseer::SubsystemContext>::Sender>,
 	<Context as overseer::SubsystemContext>::Sender: overseer::SubsystemSender<
-		<Fil as MessageInterceptor<<Context as overseer::SubsystemContext>::Sender>>::Message,
+		OutgoingMessagesOf<
+			<Fil as MessageInterceptor<<Context as overseer::SubsystemContext>::Sender>>::Message,
+		>,
 	>,
 {
 	inner: Context,
@@ -110,7 +129,9 @@ where
 		Message = <Context as overseer::SubsystemContext>::Message,
 	>,
 	<Context as overseer::SubsystemContext>::Sender: overseer::SubsystemSender<
-		<Fil as MessageInterceptor<<Context as overseer::SubsystemContext>::Sender>>::Message,
+		OutgoingMessagesOf<
+			<Fil as MessageInterceptor<<Context as overseer::SubsystemContext>::Sender>>::Message,
+		>,
 	>,
 {
 	pub fn new(mut inner: Context, message_filter: Fil) -> Self {
@@ -133,7 +154,9 @@ where
 	<Context as overseer::SubsystemContext>::AllMessages:
 		From<<Context as overseer::SubsystemContext>::Message>,
 	<Context as overseer::SubsystemContext>::Sender: overseer::SubsystemSender<
-		<Fil as MessageInterceptor<<Context as overseer::SubsystemContext>::Sender>>::Message,
+		OutgoingMessagesOf<
+			<Fil as MessageInterceptor<<Context as overseer::SubsystemContext>::Sender>>::Message,
+		>,
 	>,
 {
 	type Message = <Context as overseer::SubsystemContext>::Message;
@@ -187,6 +210,7 @@ where
 }
 
 /// A subsystem to which incoming and outgoing filters are applied.
+#[derive(Clone)]
 pub struct InterceptedSubsystem<Sub, Interceptor> {
 	pub subsystem: Sub,
 	pub message_interceptor: Interceptor,
@@ -208,7 +232,11 @@ where
 		Message = <Context as overseer::SubsystemContext>::Message,
 	>,
 	<Context as overseer::SubsystemContext>::Sender: overseer::SubsystemSender<
-		<Interceptor as MessageInterceptor<<Context as overseer::SubsystemContext>::Sender>>::Message,
+		OutgoingMessagesOf<
+			<Interceptor as MessageInterceptor<
+				<Context as overseer::SubsystemContext>::Sender,
+			>>::Message,
+		>,
 	>,
 {
 	fn start(self, ctx: Context) -> SpawnedSubsystem {
@@ -20,24 +20,45 @@ use color_eyre::eyre;
 use polkadot_cli::{Cli, RunCmd};
 use structopt::StructOpt;
 
+pub(crate) mod behavior;
 pub(crate) mod interceptor;
 pub(crate) mod shared;
 
 mod variants;
 
+use behavior::{Behavior, BehaviorOptions};
 use variants::*;
 
+/// Options of a malus node.
+#[derive(Debug, StructOpt)]
+#[allow(missing_docs)]
+struct MalusCmd {
+	#[structopt(flatten)]
+	pub run: RunCmd,
+
+	#[structopt(flatten)]
+	pub behavior: BehaviorOptions,
+}
+
 /// Define the different variants of behavior.
 #[derive(Debug, StructOpt)]
 #[structopt(about = "Malus - the nemesis of polkadot.")]
 #[structopt(rename_all = "kebab-case")]
 enum NemesisVariant {
 	/// Suggest a candidate with an invalid proof of validity.
-	SuggestGarbageCandidate(RunCmd),
+	SuggestGarbageCandidate(MalusCmd),
 	/// Back a candidate with a specifically crafted proof of validity.
-	BackGarbageCandidate(RunCmd),
+	BackGarbageCandidate(MalusCmd),
 	/// Delayed disputing of ancestors that are perfectly fine.
-	DisputeAncestor(RunCmd),
+	DisputeAncestor(MalusCmd),
+	/// Back candidates, but never serve their chunks or proofs of validity.
+	WithholdAvailability(MalusCmd),
+	/// Second a conflicting candidate for every candidate seconded.
+	EquivocateSeconded(MalusCmd),
+	/// Send bitfields whose payload does not match their signature.
+	InvalidBitfields(MalusCmd),
+	/// Replace every approval sent by a burst of approvals with bad signatures.
+	SpamBadApprovals(MalusCmd),
 
 	#[allow(missing_docs)]
 	#[structopt(name = "prepare-worker", setting = structopt::clap::AppSettings::Hidden)]
@@ -63,12 +84,34 @@ impl MalusCli {
 	/// Launch a malus node.
 	fn launch(self) -> eyre::Result<()> {
 		match self.variant {
-			NemesisVariant::BackGarbageCandidate(cmd) =>
-				polkadot_cli::run_node(run_cmd(cmd), BackGarbageCandidate)?,
-			NemesisVariant::SuggestGarbageCandidate(cmd) =>
-				polkadot_cli::run_node(run_cmd(cmd), SuggestGarbageCandidate)?,
-			NemesisVariant::DisputeAncestor(cmd) =>
-				polkadot_cli::run_node(run_cmd(cmd), DisputeValidCandidates)?,
+			NemesisVariant::BackGarbageCandidate(cmd) => {
+				let behavior = Behavior::new(cmd.behavior);
+				polkadot_cli::run_node(run_cmd(cmd.run), BackGarbageCandidate { behavior })?
+			},
+			NemesisVariant::SuggestGarbageCandidate(cmd) => {
+				let behavior = Behavior::new(cmd.behavior);
+				polkadot_cli::run_node(run_cmd(cmd.run), SuggestGarbageCandidate { behavior })?
+			},
+			NemesisVariant::DisputeAncestor(cmd) => {
+				let behavior = Behavior::without_paras(cmd.behavior)?;
+				polkadot_cli::run_node(run_cmd(cmd.run), DisputeValidCandidates { behavior })?
+			},
+			NemesisVariant::WithholdAvailability(cmd) => {
+				let behavior = Behavior::without_paras(cmd.behavior)?;
+				polkadot_cli::run_node(run_cmd(cmd.run), WithholdAvailability { behavior })?
+			},
+			NemesisVariant::EquivocateSeconded(cmd) => {
+				let behavior = Behavior::new(cmd.behavior);
+				polkadot_cli::run_node(run_cmd(cmd.run), EquivocateSeconded { behavior })?
+			},
+			NemesisVariant::InvalidBitfields(cmd) => {
+				let behavior = Behavior::without_paras(cmd.behavior)?;
+				polkadot_cli::run_node(run_cmd(cmd.run), SendInvalidBitfields { behavior })?
+			},
+			NemesisVariant::SpamBadApprovals(cmd) => {
+				let behavior = Behavior::without_paras(cmd.behavior)?;
+				polkadot_cli::run_node(run_cmd(cmd.run), SpamBadApprovals { behavior })?
+			},
 			NemesisVariant::PvfPrepareWorker(cmd) => {
 				#[cfg(target_os = "android")]
 				{
@@ -117,10 +160,12 @@ mod tests {
 		]))
 		.unwrap();
 		assert_matches::assert_matches!(cli, MalusCli {
-			variant: NemesisVariant::DisputeAncestor(run),
+			variant: NemesisVariant::DisputeAncestor(cmd),
 			..
 		} => {
-			assert!(run.base.bob);
+			assert!(cmd.run.base.bob);
+			assert_eq!(cmd.behavior.behavior, Default::default());
+			assert!(!cmd.behavior.behavior_backchannel);
 		});
 	}
 }
@@ -29,8 +29,7 @@ struct BlackHoleInterceptor;
 
 impl<Sender> MessageInterceptor<Sender> for BlackHoleInterceptor
 where
-	Sender: overseer::SubsystemSender<AllMessages>
-		+ overseer::SubsystemSender<AvailabilityStoreMessage>
+	Sender: overseer::SubsystemSender<OutgoingMessagesOf<AvailabilityStoreMessage>>
 		+ Clone
 		+ 'static,
 {
@@ -53,8 +52,7 @@ struct PassInterceptor;
 
 impl<Sender> MessageInterceptor<Sender> for PassInterceptor
 where
-	Sender: overseer::SubsystemSender<AllMessages>
-		+ overseer::SubsystemSender<AvailabilityStoreMessage>
+	Sender: overseer::SubsystemSender<OutgoingMessagesOf<AvailabilityStoreMessage>>
 		+ Clone
 		+ 'static,
 {
@@ -68,7 +66,7 @@ async fn overseer_send<T: Into<AllMessages>>(overseer: &mut TestSubsystemContext
 fn launch_harness<F, M, Sub, G>(test_gen: G)
 where
 	F: Future<Output = TestSubsystemContextHandle<M>> + Send,
-	M: Into<AllMessages> + std::fmt::Debug + Send + 'static,
+	M: overseer::AssociateOutgoing<AllMessages> + std::fmt::Debug + Send + 'static,
 	AllMessages: From<M>,
 	Sub: Subsystem<TestSubsystemContext<M, sp_core::testing::TaskExecutor>, SubsystemError>,
 	G: Fn(TestSubsystemContextHandle<M>) -> (F, Sub),
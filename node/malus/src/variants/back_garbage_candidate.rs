@@ -31,13 +31,14 @@ use polkadot_cli::{
 // Import extra types relevant to the particular
 // subsystem.
 use polkadot_node_core_candidate_validation::CandidateValidationSubsystem;
-use polkadot_node_subsystem::messages::{
-	AvailabilityRecoveryMessage, CandidateValidationMessage, ValidationFailed,
+use polkadot_node_subsystem::{
+	messages::{AvailabilityRecoveryMessage, CandidateValidationMessage, ValidationFailed},
+	overseer::CandidateValidationOutgoingMessages,
 };
 use polkadot_node_subsystem_util as util;
 
 // Filter wrapping related types.
-use crate::{interceptor::*, shared::*};
+use crate::{behavior::Behavior, interceptor::*, shared::*};
 use polkadot_node_primitives::{PoV, ValidationResult};
 
 use polkadot_primitives::v1::{
@@ -57,9 +58,12 @@ struct BribedPassageInner<Spawner> {
 	cache: HashMap<CandidateDescriptor, CandidateReceipt>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 struct BribedPassage<Spawner> {
 	inner: Arc<Mutex<BribedPassageInner<Spawner>>>,
+	// Candidate validation does not send to availability recovery, so go around its sender.
+	handle: overseer::Handle,
+	behavior: Behavior,
 }
 
 impl<Spawner> BribedPassage<Spawner>
@@ -87,11 +91,7 @@ where
 
 impl<Sender, Spawner> MessageInterceptor<Sender> for BribedPassage<Spawner>
 where
-	Sender: overseer::SubsystemSender<CandidateValidationMessage>
-		+ overseer::SubsystemSender<AllMessages>
-		+ Clone
-		+ Send
-		+ 'static,
+	Sender: overseer::SubsystemSender<CandidateValidationOutgoingMessages> + Clone + Send + 'static,
 	Spawner: SpawnNamed + Send + Clone + 'static,
 {
 	type Message = CandidateValidationMessage;
@@ -101,6 +101,10 @@ where
 		sender: &mut Sender,
 		msg: FromOverseer<Self::Message>,
 	) -> Option<FromOverseer<Self::Message>> {
+		// Pass garbage of the same relay parents as `suggest-garbage-candidate` suggests.
+		let bribed = |descriptor: &CandidateDescriptor| {
+			self.behavior.applies(Some(descriptor.para_id), &descriptor.relay_parent)
+		};
 		match msg {
 			FromOverseer::Communication {
 				msg:
@@ -112,7 +116,7 @@ where
 						_duration,
 						response_sender,
 					),
-			} if pov.block_data.0.as_slice() == MALICIOUS_POV => {
+			} if pov.block_data.0.as_slice() == MALICIOUS_POV && bribed(&candidate_descriptor) => {
 				Self::let_pass(
 					persisted_validation_data,
 					Some(validation_code),
@@ -130,11 +134,12 @@ where
 						_duration,
 						response_sender,
 					),
-			} if pov.block_data.0.as_slice() == MALICIOUS_POV => {
+			} if pov.block_data.0.as_slice() == MALICIOUS_POV && bribed(&candidate_descriptor) => {
 				if let Some(candidate_receipt) =
 					self.inner.lock().unwrap().cache.get(&candidate_descriptor).cloned()
 				{
 					let mut subsystem_sender = sender.clone();
+					let mut handle = self.handle.clone();
 					let spawner = self.inner.lock().unwrap().spawner.clone();
 					spawner.spawn(
 						"malus-back-garbage-adhoc",
@@ -150,14 +155,12 @@ where
 
 							let (a_tx, a_rx) = oneshot::channel();
 
-							subsystem_sender
-								.send_message(AllMessages::from(
-									AvailabilityRecoveryMessage::RecoverAvailableData(
-										candidate_receipt,
-										session_index,
-										None,
-										a_tx,
-									),
+							handle
+								.send_msg_anon(AvailabilityRecoveryMessage::RecoverAvailableData(
+									candidate_receipt,
+									session_index,
+									None,
+									a_tx,
 								))
 								.await;
 
@@ -185,14 +188,12 @@ where
 			msg => Some(msg),
 		}
 	}
-
-	fn intercept_outgoing(&self, msg: AllMessages) -> Option<AllMessages> {
-		Some(msg)
-	}
 }
 
 /// Generates an overseer that exposes bad behavior.
-pub(crate) struct BackGarbageCandidate;
+pub(crate) struct BackGarbageCandidate {
+	pub(crate) behavior: Behavior,
+}
 
 impl OverseerGen for BackGarbageCandidate {
 	fn generate<'a, Spawner, RuntimeClient>(
@@ -206,7 +207,10 @@ impl OverseerGen for BackGarbageCandidate {
 		Spawner: 'static + SpawnNamed + Clone + Unpin,
 	{
 		let candidate_validation_config = args.candidate_validation_config.clone();
+		let handle = overseer::Handle::new(connector.handle());
 		let spawner = args.spawner.clone();
+		let behavior = self.behavior.clone();
+		behavior.spawn_backchannel_updates(&spawner);
 
 		prepared_overseer_builder(args)?
 			.replace_candidate_validation(|cv| {
@@ -221,6 +225,8 @@ impl OverseerGen for BackGarbageCandidate {
 							spawner,
 							cache: Default::default(),
 						})),
+						handle,
+						behavior,
 					},
 				)
 			})
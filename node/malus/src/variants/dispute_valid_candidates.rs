@@ -31,27 +31,28 @@ use polkadot_cli::{
 };
 
 // Filter wrapping related types.
-use crate::interceptor::*;
+use crate::{behavior::Behavior, interceptor::*};
 
 // Import extra types relevant to the particular
 // subsystem.
-use polkadot_node_core_backing::CandidateBackingSubsystem;
-use polkadot_node_subsystem::messages::{
-	ApprovalDistributionMessage, CandidateBackingMessage, DisputeCoordinatorMessage,
+use polkadot_node_subsystem::{
+	messages::{ApprovalDistributionMessage, ApprovalVotingMessage, DisputeCoordinatorMessage},
+	overseer::ApprovalVotingOutgoingMessages,
 };
-use sp_keystore::SyncCryptoStorePtr;
 
 use std::sync::Arc;
 
 /// Replace outgoing approval messages with disputes.
 #[derive(Clone, Debug)]
-struct ReplaceApprovalsWithDisputes;
+struct ReplaceApprovalsWithDisputes {
+	behavior: Behavior,
+}
 
 impl<Sender> MessageInterceptor<Sender> for ReplaceApprovalsWithDisputes
 where
-	Sender: overseer::SubsystemSender<CandidateBackingMessage> + Clone + Send + 'static,
+	Sender: overseer::SubsystemSender<ApprovalVotingOutgoingMessages> + Clone + Send + 'static,
 {
-	type Message = CandidateBackingMessage;
+	type Message = ApprovalVotingMessage;
 
 	fn intercept_incoming(
 		&self,
@@ -61,29 +62,38 @@ where
 		Some(msg)
 	}
 
-	fn intercept_outgoing(&self, msg: AllMessages) -> Option<AllMessages> {
+	fn intercept_outgoing(
+		&self,
+		msg: ApprovalVotingOutgoingMessages,
+	) -> Option<ApprovalVotingOutgoingMessages> {
 		match msg {
-			AllMessages::ApprovalDistribution(ApprovalDistributionMessage::DistributeApproval(
-				_,
-			)) => {
+			ApprovalVotingOutgoingMessages::ApprovalDistributionMessage(
+				ApprovalDistributionMessage::DistributeApproval(ref vote),
+			) if self.behavior.applies(None, &vote.block_hash) => {
 				// drop the message on the floor
 				None
 			},
-			AllMessages::DisputeCoordinator(DisputeCoordinatorMessage::ImportStatements {
-				candidate_hash,
-				candidate_receipt,
-				session,
-				..
-			}) => {
+			ApprovalVotingOutgoingMessages::DisputeCoordinatorMessage(
+				DisputeCoordinatorMessage::ImportStatements {
+					candidate_hash,
+					candidate_receipt,
+					session,
+					..
+				},
+			) if self
+				.behavior
+				.applies(Some(candidate_receipt.descriptor.para_id), &candidate_hash.0) =>
+			{
 				// this would also dispute candidates we were not assigned to approve
-				Some(AllMessages::DisputeCoordinator(
+				Some(
 					DisputeCoordinatorMessage::IssueLocalStatement(
 						session,
 						candidate_hash,
 						candidate_receipt,
 						false,
-					),
-				))
+					)
+					.into(),
+				)
 			},
 			msg => Some(msg),
 		}
@@ -91,7 +101,9 @@ where
 }
 
 /// Generates an overseer that disputes instead of approving valid candidates.
-pub(crate) struct DisputeValidCandidates;
+pub(crate) struct DisputeValidCandidates {
+	pub(crate) behavior: Behavior,
+}
 
 impl OverseerGen for DisputeValidCandidates {
 	fn generate<'a, Spawner, RuntimeClient>(
@@ -104,17 +116,11 @@ impl OverseerGen for DisputeValidCandidates {
 		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
 		Spawner: 'static + SpawnNamed + Clone + Unpin,
 	{
-		let spawner = args.spawner.clone();
-		let crypto_store_ptr = args.keystore.clone() as SyncCryptoStorePtr;
-		let filter = ReplaceApprovalsWithDisputes;
+		let filter = ReplaceApprovalsWithDisputes { behavior: self.behavior.clone() };
+		self.behavior.spawn_backchannel_updates(&args.spawner);
 
 		prepared_overseer_builder(args)?
-			.replace_candidate_backing(move |cb| {
-				InterceptedSubsystem::new(
-					CandidateBackingSubsystem::new(spawner, crypto_store_ptr, cb.params.metrics),
-					filter,
-				)
-			})
+			.replace_approval_voting(|av| InterceptedSubsystem::new(av, filter))
 			.build_with_connector(connector)
 			.map_err(|e| e.into())
 	}
@@ -0,0 +1,183 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A malicious node that, whenever it seconds a candidate, also
+//! seconds a conflicting candidate for the same relay parent.
+//!
+//! Attention: For usage with `zombienet` only!
+
+#![allow(missing_docs)]
+
+use polkadot_cli::{
+	prepared_overseer_builder,
+	service::{
+		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, HeaderBackend, Overseer,
+		OverseerConnector, OverseerGen, OverseerGenArgs, OverseerHandle, ParachainHost,
+		ProvideRuntimeApi, SpawnNamed,
+	},
+};
+
+// Filter wrapping related types.
+use crate::{behavior::Behavior, interceptor::*, shared::*};
+
+// Import extra types relevant to the particular
+// subsystem.
+use polkadot_node_primitives::{SignedFullStatement, Statement};
+use polkadot_node_subsystem::{
+	messages::{
+		CandidateBackingMessage, RuntimeApiMessage, RuntimeApiRequest, RuntimeApiSender,
+		StatementDistributionMessage,
+	},
+	overseer::CandidateBackingOutgoingMessages,
+};
+use polkadot_primitives::v1::{Hash, SigningContext};
+use sp_keystore::SyncCryptoStorePtr;
+
+use futures::channel::{mpsc, oneshot};
+use std::sync::Arc;
+
+/// Pass on all statements, queueing our `Seconded` ones to be equivocated on.
+#[derive(Clone, Debug)]
+struct QueueSeconded {
+	queue: mpsc::UnboundedSender<(Hash, SignedFullStatement)>,
+	behavior: Behavior,
+}
+
+impl<Sender> MessageInterceptor<Sender> for QueueSeconded
+where
+	Sender: overseer::SubsystemSender<CandidateBackingOutgoingMessages> + Clone + Send + 'static,
+{
+	type Message = CandidateBackingMessage;
+
+	fn intercept_outgoing(
+		&self,
+		msg: CandidateBackingOutgoingMessages,
+	) -> Option<CandidateBackingOutgoingMessages> {
+		if let CandidateBackingOutgoingMessages::StatementDistributionMessage(
+			StatementDistributionMessage::Share(relay_parent, ref statement),
+		) = msg
+		{
+			if let Statement::Seconded(candidate) = statement.payload() {
+				let para_id = candidate.descriptor.para_id;
+				if self.behavior.applies(Some(para_id), &candidate.hash().0) {
+					// the honest statement is passed on below, before the conflicting one is signed
+					let _ = self.queue.unbounded_send((relay_parent, statement.clone()));
+				}
+			}
+		}
+		Some(msg)
+	}
+}
+
+/// Query the runtime at `relay_parent`, `None` on any error.
+async fn request<T>(
+	handle: &mut overseer::Handle,
+	relay_parent: Hash,
+	request: impl FnOnce(RuntimeApiSender<T>) -> RuntimeApiRequest,
+) -> Option<T> {
+	let (tx, rx) = oneshot::channel();
+	handle.send_msg_anon(RuntimeApiMessage::Request(relay_parent, request(tx))).await;
+	rx.await.ok()?.ok()
+}
+
+/// Sign and share a `Seconded` statement for a candidate conflicting with the one in `statement`.
+async fn equivocate(
+	mut handle: overseer::Handle,
+	keystore: SyncCryptoStorePtr,
+	relay_parent: Hash,
+	statement: SignedFullStatement,
+) {
+	let mut conflicting = match statement.payload() {
+		Statement::Seconded(candidate) => candidate.clone(),
+		Statement::Valid(_) => return,
+	};
+	// any change to the commitments yields a different candidate hash
+	conflicting.commitments.head_data.0.extend_from_slice(MALICIOUS_POV);
+
+	let session_index =
+		request(&mut handle, relay_parent, RuntimeApiRequest::SessionIndexForChild).await;
+	let validators = request(&mut handle, relay_parent, RuntimeApiRequest::Validators).await;
+	let validator_index = statement.validator_index();
+	let (session_index, key) = match (session_index, validators) {
+		(Some(session_index), Some(validators)) =>
+			match validators.get(validator_index.0 as usize) {
+				Some(key) => (session_index, key.clone()),
+				None => return,
+			},
+		_ => {
+			tracing::info!(target = MALUS, "Could not query the runtime, can't equivocate");
+			return
+		},
+	};
+
+	let context = SigningContext { session_index, parent_hash: relay_parent };
+	let conflicting_hash = conflicting.hash();
+	match SignedFullStatement::sign(
+		&keystore,
+		Statement::Seconded(conflicting),
+		&context,
+		validator_index,
+		&key,
+	)
+	.await
+	{
+		Ok(Some(signed)) => {
+			tracing::info!(
+				target = MALUS,
+				?relay_parent,
+				candidate_hash = ?conflicting_hash,
+				"Seconding a conflicting candidate",
+			);
+			handle
+				.send_msg_anon(StatementDistributionMessage::Share(relay_parent, signed))
+				.await;
+		},
+		_ => tracing::info!(target = MALUS, "Could not sign, can't equivocate"),
+	}
+}
+
+/// Generates an overseer that seconds two conflicting candidates per relay parent.
+pub(crate) struct EquivocateSeconded {
+	pub(crate) behavior: Behavior,
+}
+
+impl OverseerGen for EquivocateSeconded {
+	fn generate<'a, Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'a, Spawner, RuntimeClient>,
+	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		let handle = overseer::Handle::new(connector.handle());
+		let keystore = args.keystore.clone() as SyncCryptoStorePtr;
+		let (queue, seconded) = mpsc::unbounded();
+		let filter = QueueSeconded { queue, behavior: self.behavior.clone() };
+		self.behavior.spawn_backchannel_updates(&args.spawner);
+
+		launch_processing_task(&args.spawner, seconded, move |(relay_parent, statement)| {
+			equivocate(handle.clone(), keystore.clone(), relay_parent, statement)
+		});
+
+		prepared_overseer_builder(args)?
+			.replace_candidate_backing(move |cb| InterceptedSubsystem::new(cb, filter))
+			.build_with_connector(connector)
+			.map_err(|e| e.into())
+	}
+}
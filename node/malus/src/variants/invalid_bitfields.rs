@@ -0,0 +1,128 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A malicious node that gossips inverted availability bitfields,
+//! while keeping the original signature.
+//!
+//! Attention: For usage with `zombienet` only!
+
+#![allow(missing_docs)]
+
+use polkadot_cli::{
+	prepared_overseer_builder,
+	service::{
+		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, HeaderBackend, Overseer,
+		OverseerConnector, OverseerGen, OverseerGenArgs, OverseerHandle, ParachainHost,
+		ProvideRuntimeApi, SpawnNamed,
+	},
+};
+
+// Filter wrapping related types.
+use crate::{behavior::Behavior, interceptor::*, shared::MALUS};
+
+// Import extra types relevant to the particular
+// subsystem.
+use polkadot_node_network_protocol::v1 as protocol_v1;
+use polkadot_node_subsystem::{
+	messages::{BitfieldDistributionMessage, NetworkBridgeMessage},
+	overseer::BitfieldDistributionOutgoingMessages,
+};
+use polkadot_primitives::v1::{UncheckedSigned, UncheckedSignedAvailabilityBitfield};
+
+use std::sync::Arc;
+
+/// Invert a bitfield, which invalidates its signature.
+fn tamper(bitfield: UncheckedSignedAvailabilityBitfield) -> UncheckedSignedAvailabilityBitfield {
+	let validator_index = bitfield.unchecked_validator_index();
+	let signature = bitfield.unchecked_signature().clone();
+	let mut payload = bitfield.unchecked_into_payload();
+	payload.0 = !payload.0;
+
+	UncheckedSigned::new(payload, validator_index, signature)
+}
+
+/// Tamper with all bitfields sent to peers, our own and those we relay.
+#[derive(Clone, Debug)]
+struct TamperBitfields {
+	behavior: Behavior,
+}
+
+impl<Sender> MessageInterceptor<Sender> for TamperBitfields
+where
+	Sender: overseer::SubsystemSender<BitfieldDistributionOutgoingMessages>
+		+ Clone
+		+ Send
+		+ 'static,
+{
+	type Message = BitfieldDistributionMessage;
+
+	fn intercept_outgoing(
+		&self,
+		msg: BitfieldDistributionOutgoingMessages,
+	) -> Option<BitfieldDistributionOutgoingMessages> {
+		match msg {
+			BitfieldDistributionOutgoingMessages::NetworkBridgeMessage(
+				NetworkBridgeMessage::SendValidationMessage(
+					peers,
+					protocol_v1::ValidationProtocol::BitfieldDistribution(
+						protocol_v1::BitfieldDistributionMessage::Bitfield(relay_parent, bitfield),
+					),
+				),
+			) if self.behavior.applies(None, &relay_parent) => {
+				tracing::info!(target = MALUS, ?relay_parent, "Sending an invalid bitfield");
+				Some(
+					NetworkBridgeMessage::SendValidationMessage(
+						peers,
+						protocol_v1::ValidationProtocol::BitfieldDistribution(
+							protocol_v1::BitfieldDistributionMessage::Bitfield(
+								relay_parent,
+								tamper(bitfield),
+							),
+						),
+					)
+					.into(),
+				)
+			},
+			msg => Some(msg),
+		}
+	}
+}
+
+/// Generates an overseer that sends invalid availability bitfields.
+pub(crate) struct SendInvalidBitfields {
+	pub(crate) behavior: Behavior,
+}
+
+impl OverseerGen for SendInvalidBitfields {
+	fn generate<'a, Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'a, Spawner, RuntimeClient>,
+	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		let filter = TamperBitfields { behavior: self.behavior.clone() };
+		self.behavior.spawn_backchannel_updates(&args.spawner);
+
+		prepared_overseer_builder(args)?
+			.replace_bitfield_distribution(|bd| InterceptedSubsystem::new(bd, filter))
+			.build_with_connector(connector)
+			.map_err(|e| e.into())
+	}
+}
@@ -18,9 +18,15 @@
 
 mod back_garbage_candidate;
 mod dispute_valid_candidates;
+mod equivocate_seconded;
+mod invalid_bitfields;
+mod spam_bad_approvals;
 mod suggest_garbage_candidate;
+mod withhold_availability;
 
 pub(crate) use self::{
 	back_garbage_candidate::BackGarbageCandidate, dispute_valid_candidates::DisputeValidCandidates,
-	suggest_garbage_candidate::SuggestGarbageCandidate,
+	equivocate_seconded::EquivocateSeconded, invalid_bitfields::SendInvalidBitfields,
+	spam_bad_approvals::SpamBadApprovals, suggest_garbage_candidate::SuggestGarbageCandidate,
+	withhold_availability::WithholdAvailability,
 };
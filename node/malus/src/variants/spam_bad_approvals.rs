@@ -0,0 +1,149 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A malicious node that replaces the approvals it sends to peers
+//! by a burst of approvals with bad signatures.
+//!
+//! Attention: For usage with `zombienet` only!
+
+#![allow(missing_docs)]
+
+use polkadot_cli::{
+	prepared_overseer_builder,
+	service::{
+		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, HeaderBackend, Overseer,
+		OverseerConnector, OverseerGen, OverseerGenArgs, OverseerHandle, ParachainHost,
+		ProvideRuntimeApi, SpawnNamed,
+	},
+};
+
+// Filter wrapping related types.
+use crate::{behavior::Behavior, interceptor::*, shared::MALUS};
+
+// Import extra types relevant to the particular
+// subsystem.
+use parity_scale_codec::{Decode, Encode};
+use polkadot_node_network_protocol::v1 as protocol_v1;
+use polkadot_node_primitives::approval::IndirectSignedApprovalVote;
+use polkadot_node_subsystem::{
+	messages::{ApprovalDistributionMessage, NetworkBridgeMessage},
+	overseer::ApprovalDistributionOutgoingMessages,
+};
+use polkadot_primitives::v1::ValidatorSignature;
+
+use std::sync::Arc;
+
+/// Number of bad approvals sent in place of a single one.
+const SPAM_FACTOR: u32 = 16;
+
+/// Flip a byte of `signature`.
+fn corrupt(signature: &ValidatorSignature) -> ValidatorSignature {
+	let mut encoded = signature.encode();
+	encoded[0] ^= 0xff;
+	ValidatorSignature::decode(&mut &encoded[..])
+		.expect("Signatures are fixed size byte arrays, any bytes decode. qed")
+}
+
+/// Replace an approval by one with a corrupted signature, followed by
+/// further ones for the subsequent candidates of the block.
+fn spam(vote: IndirectSignedApprovalVote) -> impl Iterator<Item = IndirectSignedApprovalVote> {
+	let signature = corrupt(&vote.signature);
+	(0..SPAM_FACTOR).map(move |offset| IndirectSignedApprovalVote {
+		candidate_index: vote.candidate_index.wrapping_add(offset),
+		signature: signature.clone(),
+		..vote.clone()
+	})
+}
+
+/// Spam all approvals sent to peers, our own and those we relay.
+#[derive(Clone, Debug)]
+struct SpamApprovals {
+	behavior: Behavior,
+}
+
+impl<Sender> MessageInterceptor<Sender> for SpamApprovals
+where
+	Sender: overseer::SubsystemSender<ApprovalDistributionOutgoingMessages>
+		+ Clone
+		+ Send
+		+ 'static,
+{
+	type Message = ApprovalDistributionMessage;
+
+	fn intercept_outgoing(
+		&self,
+		msg: ApprovalDistributionOutgoingMessages,
+	) -> Option<ApprovalDistributionOutgoingMessages> {
+		match msg {
+			ApprovalDistributionOutgoingMessages::NetworkBridgeMessage(
+				NetworkBridgeMessage::SendValidationMessage(
+					peers,
+					protocol_v1::ValidationProtocol::ApprovalDistribution(
+						protocol_v1::ApprovalDistributionMessage::Approvals(approvals),
+					),
+				),
+			) => {
+				let approvals = approvals
+					.into_iter()
+					.flat_map(|vote| -> Box<dyn Iterator<Item = _>> {
+						if self.behavior.applies(None, &vote.block_hash) {
+							Box::new(spam(vote))
+						} else {
+							Box::new(std::iter::once(vote))
+						}
+					})
+					.collect::<Vec<_>>();
+				tracing::info!(target = MALUS, n = approvals.len(), "Sending approvals");
+				Some(
+					NetworkBridgeMessage::SendValidationMessage(
+						peers,
+						protocol_v1::ValidationProtocol::ApprovalDistribution(
+							protocol_v1::ApprovalDistributionMessage::Approvals(approvals),
+						),
+					)
+					.into(),
+				)
+			},
+			msg => Some(msg),
+		}
+	}
+}
+
+/// Generates an overseer that spams approvals with bad signatures.
+pub(crate) struct SpamBadApprovals {
+	pub(crate) behavior: Behavior,
+}
+
+impl OverseerGen for SpamBadApprovals {
+	fn generate<'a, Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'a, Spawner, RuntimeClient>,
+	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		let filter = SpamApprovals { behavior: self.behavior.clone() };
+		self.behavior.spawn_backchannel_updates(&args.spawner);
+
+		prepared_overseer_builder(args)?
+			.replace_approval_distribution(|ad| InterceptedSubsystem::new(ad, filter))
+			.build_with_connector(connector)
+			.map_err(|e| e.into())
+	}
+}
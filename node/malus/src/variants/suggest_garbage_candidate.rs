@@ -37,11 +37,11 @@ use polkadot_node_core_backing::CandidateBackingSubsystem;
 use polkadot_node_primitives::Statement;
 use polkadot_node_subsystem::{
 	messages::{CandidateBackingMessage, StatementDistributionMessage},
-	overseer::{self, SubsystemSender},
+	overseer::{self, CandidateBackingOutgoingMessages, SubsystemSender},
 };
 use polkadot_node_subsystem_util as util;
 // Filter wrapping related types.
-use crate::interceptor::*;
+use crate::{behavior::Behavior, interceptor::*};
 use polkadot_primitives::v1::{
 	CandidateCommitments, CandidateReceipt, CommittedCandidateReceipt, CompactStatement, Hash,
 	Signed,
@@ -61,11 +61,12 @@ where
 	Sender: Send,
 {
 	queue: metered::UnboundedMeteredSender<(Sender, Hash, CandidateReceipt)>,
+	behavior: Behavior,
 }
 
 impl<Sender> MessageInterceptor<Sender> for ReplacePoVBytes<Sender>
 where
-	Sender: overseer::SubsystemSender<CandidateBackingMessage> + Clone + Send + 'static,
+	Sender: overseer::SubsystemSender<CandidateBackingOutgoingMessages> + Clone + Send + 'static,
 {
 	type Message = CandidateBackingMessage;
 
@@ -77,7 +78,7 @@ where
 		match msg {
 			FromOverseer::Communication {
 				msg: CandidateBackingMessage::Second(hash, candidate_receipt, _pov),
-			} => {
+			} if self.behavior.applies(Some(candidate_receipt.descriptor.para_id), &hash) => {
 				self.queue
 					.unbounded_send((sender.clone(), hash, candidate_receipt.clone()))
 					.unwrap();
@@ -87,14 +88,12 @@ where
 			other => Some(other),
 		}
 	}
-
-	fn intercept_outgoing(&self, msg: AllMessages) -> Option<AllMessages> {
-		Some(msg)
-	}
 }
 
 /// Generates an overseer that exposes bad behavior.
-pub(crate) struct SuggestGarbageCandidate;
+pub(crate) struct SuggestGarbageCandidate {
+	pub(crate) behavior: Behavior,
+}
 
 impl OverseerGen for SuggestGarbageCandidate {
 	fn generate<'a, Spawner, RuntimeClient>(
@@ -111,7 +110,8 @@ impl OverseerGen for SuggestGarbageCandidate {
 		let (sink, source) = metered::unbounded();
 		let keystore = args.keystore.clone() as SyncCryptoStorePtr;
 
-		let filter = ReplacePoVBytes { queue: sink };
+		let filter = ReplacePoVBytes { queue: sink, behavior: self.behavior.clone() };
+		self.behavior.spawn_backchannel_updates(&spawner);
 
 		let keystore2 = keystore.clone();
 		let spawner2 = spawner.clone();
@@ -119,7 +119,11 @@ impl OverseerGen for SuggestGarbageCandidate {
 		let result = prepared_overseer_builder(args)?
 			.replace_candidate_backing(move |cb| {
 				InterceptedSubsystem::new(
-					CandidateBackingSubsystem::new(spawner2, keystore2, cb.params.metrics),
+					CandidateBackingSubsystem::new(
+						spawner2,
+						(keystore2, Default::default()),
+						cb.params.metrics,
+					),
 					filter,
 				)
 			})
@@ -154,9 +158,8 @@ impl OverseerGen for SuggestGarbageCandidate {
 							.expect("Something must come out of this. qed");
 
 						subsystem_sender
-							.send_message(StatementDistributionMessage::Share(
-								hash,
-								signed_statement,
+							.send_message(CandidateBackingOutgoingMessages::from(
+								StatementDistributionMessage::Share(hash, signed_statement),
 							))
 							.await;
 					} else {
@@ -0,0 +1,157 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A malicious node that backs candidates as usual, but refuses
+//! to serve the chunks, PoVs and full data of any candidate to other validators.
+//!
+//! Attention: For usage with `zombienet` only!
+
+#![allow(missing_docs)]
+
+use polkadot_cli::{
+	prepared_overseer_builder,
+	service::{
+		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, HeaderBackend, Overseer,
+		OverseerConnector, OverseerGen, OverseerGenArgs, OverseerHandle, ParachainHost,
+		ProvideRuntimeApi, SpawnNamed,
+	},
+};
+
+// Filter wrapping related types.
+use crate::{behavior::Behavior, interceptor::*, shared::MALUS};
+
+// Import extra types relevant to the particular
+// subsystem.
+use polkadot_node_subsystem::{
+	messages::{
+		AvailabilityDistributionMessage, AvailabilityRecoveryMessage, AvailabilityStoreMessage,
+	},
+	overseer::{AvailabilityDistributionOutgoingMessages, AvailabilityRecoveryOutgoingMessages},
+};
+
+use std::sync::Arc;
+
+/// Answer a query for a chunk or the full data of a candidate as if we did not have it.
+///
+/// Requests do not tell the para of a candidate, so all paras are targeted.
+fn withhold(
+	behavior: &Behavior,
+	msg: AvailabilityStoreMessage,
+) -> Option<AvailabilityStoreMessage> {
+	match msg {
+		AvailabilityStoreMessage::QueryChunk(candidate_hash, _, tx)
+			if behavior.applies(None, &candidate_hash.0) =>
+		{
+			tracing::info!(target = MALUS, ?candidate_hash, "Withholding chunk");
+			let _ = tx.send(None);
+			None
+		},
+		AvailabilityStoreMessage::QueryAvailableData(candidate_hash, tx)
+			if behavior.applies(None, &candidate_hash.0) =>
+		{
+			tracing::info!(target = MALUS, ?candidate_hash, "Withholding available data");
+			let _ = tx.send(None);
+			None
+		},
+		msg => Some(msg),
+	}
+}
+
+/// Withhold the data queried to answer chunk and PoV requests.
+#[derive(Clone, Debug)]
+struct WithholdChunksAndPoVs {
+	behavior: Behavior,
+}
+
+impl<Sender> MessageInterceptor<Sender> for WithholdChunksAndPoVs
+where
+	Sender: overseer::SubsystemSender<AvailabilityDistributionOutgoingMessages>
+		+ Clone
+		+ Send
+		+ 'static,
+{
+	type Message = AvailabilityDistributionMessage;
+
+	fn intercept_outgoing(
+		&self,
+		msg: AvailabilityDistributionOutgoingMessages,
+	) -> Option<AvailabilityDistributionOutgoingMessages> {
+		match msg {
+			AvailabilityDistributionOutgoingMessages::AvailabilityStoreMessage(msg) =>
+				withhold(&self.behavior, msg).map(Into::into),
+			msg => Some(msg),
+		}
+	}
+}
+
+/// Withhold the data queried to answer full data requests.
+///
+/// Our own recoveries query the same data, so they always fall back to the network.
+#[derive(Clone, Debug)]
+struct WithholdFullData {
+	behavior: Behavior,
+}
+
+impl<Sender> MessageInterceptor<Sender> for WithholdFullData
+where
+	Sender: overseer::SubsystemSender<AvailabilityRecoveryOutgoingMessages>
+		+ Clone
+		+ Send
+		+ 'static,
+{
+	type Message = AvailabilityRecoveryMessage;
+
+	fn intercept_outgoing(
+		&self,
+		msg: AvailabilityRecoveryOutgoingMessages,
+	) -> Option<AvailabilityRecoveryOutgoingMessages> {
+		match msg {
+			AvailabilityRecoveryOutgoingMessages::AvailabilityStoreMessage(msg) =>
+				withhold(&self.behavior, msg).map(Into::into),
+			msg => Some(msg),
+		}
+	}
+}
+
+/// Generates an overseer that backs candidates, but withholds their data.
+pub(crate) struct WithholdAvailability {
+	pub(crate) behavior: Behavior,
+}
+
+impl OverseerGen for WithholdAvailability {
+	fn generate<'a, Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'a, Spawner, RuntimeClient>,
+	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		let distribution_filter = WithholdChunksAndPoVs { behavior: self.behavior.clone() };
+		let recovery_filter = WithholdFullData { behavior: self.behavior.clone() };
+		self.behavior.spawn_backchannel_updates(&args.spawner);
+
+		prepared_overseer_builder(args)?
+			.replace_availability_distribution(|ad| {
+				InterceptedSubsystem::new(ad, distribution_filter)
+			})
+			.replace_availability_recovery(|ar| InterceptedSubsystem::new(ar, recovery_filter))
+			.build_with_connector(connector)
+			.map_err(|e| e.into())
+	}
+}
@@ -25,12 +25,21 @@ use futures::{
 
 use std::{pin::Pin, result};
 
-use super::Meter;
+use super::{MaybeTimeOfFlight, Meter, TrySendError};
 
 /// Create a wrapped `mpsc::channel` pair of `MeteredSender` and `MeteredReceiver`.
 pub fn channel<T>(capacity: usize) -> (MeteredSender<T>, MeteredReceiver<T>) {
+	channel_with_tof(capacity, 0)
+}
+
+/// Create a wrapped `mpsc::channel` pair of `MeteredSender` and `MeteredReceiver`,
+/// which samples the time of flight of every `tof_sample_every`-th message.
+pub fn channel_with_tof<T>(
+	capacity: usize,
+	tof_sample_every: usize,
+) -> (MeteredSender<T>, MeteredReceiver<T>) {
 	let (tx, rx) = mpsc::channel(capacity);
-	let shared_meter = Meter::default();
+	let shared_meter = Meter::with_tof(tof_sample_every);
 	let tx = MeteredSender { meter: shared_meter.clone(), inner: tx };
 	let rx = MeteredReceiver { meter: shared_meter, inner: rx };
 	(tx, rx)
@@ -41,31 +50,15 @@ pub fn channel<T>(capacity: usize) -> (MeteredSender<T>, MeteredReceiver<T>) {
 pub struct MeteredReceiver<T> {
 	// count currently contained messages
 	meter: Meter,
-	inner: mpsc::Receiver<T>,
-}
-
-impl<T> std::ops::Deref for MeteredReceiver<T> {
-	type Target = mpsc::Receiver<T>;
-	fn deref(&self) -> &Self::Target {
-		&self.inner
-	}
-}
-
-impl<T> std::ops::DerefMut for MeteredReceiver<T> {
-	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.inner
-	}
+	inner: mpsc::Receiver<MaybeTimeOfFlight<T>>,
 }
 
 impl<T> Stream for MeteredReceiver<T> {
 	type Item = T;
 	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
 		match mpsc::Receiver::poll_next(Pin::new(&mut self.inner), cx) {
-			Poll::Ready(x) => {
-				self.meter.note_received();
-				Poll::Ready(x)
-			},
-			other => other,
+			Poll::Ready(x) => Poll::Ready(x.map(|x| self.meter.note_received(x))),
+			Poll::Pending => Poll::Pending,
 		}
 	}
 
@@ -84,10 +77,7 @@ impl<T> MeteredReceiver<T> {
 	/// Attempt to receive the next item.
 	pub fn try_next(&mut self) -> Result<Option<T>, mpsc::TryRecvError> {
 		match self.inner.try_next()? {
-			Some(x) => {
-				self.meter.note_received();
-				Ok(Some(x))
-			},
+			Some(x) => Ok(Some(self.meter.note_received(x))),
 			None => Ok(None),
 		}
 	}
@@ -104,7 +94,7 @@ impl<T> futures::stream::FusedStream for MeteredReceiver<T> {
 #[derive(Debug)]
 pub struct MeteredSender<T> {
 	meter: Meter,
-	inner: mpsc::Sender<T>,
+	inner: mpsc::Sender<MaybeTimeOfFlight<T>>,
 }
 
 impl<T> Clone for MeteredSender<T> {
@@ -113,19 +103,6 @@ impl<T> Clone for MeteredSender<T> {
 	}
 }
 
-impl<T> std::ops::Deref for MeteredSender<T> {
-	type Target = mpsc::Sender<T>;
-	fn deref(&self) -> &Self::Target {
-		&self.inner
-	}
-}
-
-impl<T> std::ops::DerefMut for MeteredSender<T> {
-	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.inner
-	}
-}
-
 impl<T> MeteredSender<T> {
 	/// Get an updated accessor object for all metrics collected.
 	pub fn meter(&self) -> &Meter {
@@ -137,7 +114,7 @@ impl<T> MeteredSender<T> {
 	where
 		Self: Unpin,
 	{
-		self.meter.note_sent();
+		let item = self.meter.note_sent(item);
 		let fut = self.inner.send(item);
 		futures::pin_mut!(fut);
 		fut.await.map_err(|e| {
@@ -147,11 +124,11 @@ impl<T> MeteredSender<T> {
 	}
 
 	/// Attempt to send message or fail immediately.
-	pub fn try_send(&mut self, msg: T) -> result::Result<(), mpsc::TrySendError<T>> {
-		self.meter.note_sent();
+	pub fn try_send(&mut self, msg: T) -> result::Result<(), TrySendError<T>> {
+		let msg = self.meter.note_sent(msg);
 		self.inner.try_send(msg).map_err(|e| {
 			self.meter.retract_sent();
-			e
+			TrySendError::from_inner(e)
 		})
 	}
 }
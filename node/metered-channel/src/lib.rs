@@ -16,12 +16,17 @@
 
 //! Metered variant of mpsc channels to be able to extract metrics.
 
-use std::sync::{
-	atomic::{AtomicUsize, Ordering},
-	Arc,
+use std::{
+	fmt,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Mutex,
+	},
+	time::{Duration, Instant},
 };
 
 use derive_more::{Add, Display};
+use futures::channel::mpsc;
 
 mod bounded;
 pub mod oneshot;
@@ -29,6 +34,72 @@ mod unbounded;
 
 pub use self::{bounded::*, unbounded::*};
 
+const LOG_TARGET: &str = "metered";
+
+/// Upper bound of time of flight samples kept until they are taken,
+/// any further samples are discarded.
+const MAX_TOF_SAMPLES: usize = 1024;
+
+/// A message as sent through a metered channel, which is timestamped
+/// if its time of flight is sampled.
+#[derive(Debug)]
+enum MaybeTimeOfFlight<T> {
+	/// A message without timestamp.
+	Bare(T),
+	/// A message with the time it was enqueued at.
+	WithTimeOfFlight(T, Instant),
+}
+
+impl<T> MaybeTimeOfFlight<T> {
+	/// Extract the message.
+	fn into_inner(self) -> T {
+		match self {
+			Self::Bare(item) | Self::WithTimeOfFlight(item, _) => item,
+		}
+	}
+}
+
+/// Error of a message which could not be sent immediately, either because
+/// the channel is full or because the receiver is gone.
+#[derive(Debug)]
+pub struct TrySendError<T> {
+	full: bool,
+	message: T,
+}
+
+impl<T> TrySendError<T> {
+	fn from_inner(err: mpsc::TrySendError<MaybeTimeOfFlight<T>>) -> Self {
+		Self { full: err.is_full(), message: err.into_inner().into_inner() }
+	}
+
+	/// Returns `true` if the channel was full.
+	pub fn is_full(&self) -> bool {
+		self.full
+	}
+
+	/// Returns `true` if the receiver was dropped.
+	pub fn is_disconnected(&self) -> bool {
+		!self.full
+	}
+
+	/// Returns the message that could not be sent.
+	pub fn into_inner(self) -> T {
+		self.message
+	}
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.full {
+			write!(f, "send failed because channel is full")
+		} else {
+			write!(f, "send failed because receiver is gone")
+		}
+	}
+}
+
+impl<T: fmt::Debug> std::error::Error for TrySendError<T> {}
+
 /// A peek into the inner state of a meter.
 #[derive(Debug, Clone, Default)]
 pub struct Meter {
@@ -36,6 +107,10 @@ pub struct Meter {
 	sent: Arc<AtomicUsize>,
 	// Number of receives on this channel.
 	received: Arc<AtomicUsize>,
+	// Sample the time of flight of every n-th message, never if zero.
+	tof_sample_every: usize,
+	// Time of flight samples collected since they were last taken.
+	tof: Arc<Mutex<Vec<Duration>>>,
 }
 
 /// A readout of sizes from the meter. Note that it is possible, due to asynchrony, for received
@@ -50,6 +125,12 @@ pub struct Readout {
 }
 
 impl Meter {
+	/// A meter sampling the time of flight of every `tof_sample_every`-th message,
+	/// or of none if it is zero.
+	fn with_tof(tof_sample_every: usize) -> Self {
+		Self { tof_sample_every, ..Default::default() }
+	}
+
 	/// Count the number of items queued up inside the channel.
 	pub fn read(&self) -> Readout {
 		// when obtaining we don't care much about off by one
@@ -60,16 +141,42 @@ impl Meter {
 		}
 	}
 
-	fn note_sent(&self) {
-		self.sent.fetch_add(1, Ordering::Relaxed);
+	/// Take the time of flight samples collected since they were last taken.
+	pub fn take_tof(&self) -> Vec<Duration> {
+		match self.tof.lock() {
+			Ok(mut tof) => std::mem::take(&mut *tof),
+			Err(poisoned) => std::mem::take(&mut *poisoned.into_inner()),
+		}
+	}
+
+	fn note_sent<T>(&self, item: T) -> MaybeTimeOfFlight<T> {
+		let previous = self.sent.fetch_add(1, Ordering::Relaxed);
+		if self.tof_sample_every > 0 && previous % self.tof_sample_every == 0 {
+			MaybeTimeOfFlight::WithTimeOfFlight(item, Instant::now())
+		} else {
+			MaybeTimeOfFlight::Bare(item)
+		}
 	}
 
 	fn retract_sent(&self) {
 		self.sent.fetch_sub(1, Ordering::Relaxed);
 	}
 
-	fn note_received(&self) {
+	fn note_received<T>(&self, item: MaybeTimeOfFlight<T>) -> T {
 		self.received.fetch_add(1, Ordering::Relaxed);
+		match item {
+			MaybeTimeOfFlight::Bare(item) => item,
+			MaybeTimeOfFlight::WithTimeOfFlight(item, sent_at) => {
+				let tof = sent_at.elapsed();
+				tracing::trace!(target: LOG_TARGET, ?tof, "Sampled time of flight");
+				if let Ok(mut samples) = self.tof.lock() {
+					if samples.len() < MAX_TOF_SAMPLES {
+						samples.push(tof);
+					}
+				}
+				item
+			},
+		}
 	}
 }
 
@@ -167,6 +274,34 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn samples_time_of_flight() {
+		let (mut tx, mut rx) = channel_with_tof::<Msg>(5, 2);
+		let (utx, mut urx) = unbounded_with_tof::<Msg>(1);
+
+		block_on(async move {
+			for _ in 0..4 {
+				tx.try_send(Msg::default()).unwrap();
+				utx.unbounded_send(Msg::default()).unwrap();
+			}
+			Delay::new(Duration::from_millis(10)).await;
+			for _ in 0..4 {
+				rx.next().await.unwrap();
+				urx.next().await.unwrap();
+			}
+
+			assert_eq!(rx.meter().read(), Readout { sent: 4, received: 4 });
+			let tof = rx.meter().take_tof();
+			assert_eq!(tof.len(), 2);
+			assert!(tof.iter().all(|tof| *tof >= Duration::from_millis(10)));
+			assert_eq!(urx.meter().take_tof().len(), 4);
+
+			// reading does not consume samples, taking does
+			assert_eq!(rx.meter().read(), Readout { sent: 4, received: 4 });
+			assert!(rx.meter().take_tof().is_empty());
+		});
+	}
+
 	#[test]
 	fn failed_send_does_not_inc_sent() {
 		let (mut bounded, _) = channel::<Msg>(5);
@@ -174,7 +309,7 @@ mod tests {
 
 		block_on(async move {
 			assert!(bounded.send(Msg::default()).await.is_err());
-			assert!(bounded.try_send(Msg::default()).is_err());
+			assert!(bounded.try_send(Msg::default()).unwrap_err().is_disconnected());
 			assert_eq!(bounded.meter().read(), Readout { sent: 0, received: 0 });
 
 			assert!(unbounded.unbounded_send(Msg::default()).is_err());
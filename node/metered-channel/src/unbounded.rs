@@ -24,12 +24,21 @@ use futures::{
 
 use std::{pin::Pin, result};
 
-use super::Meter;
+use super::{MaybeTimeOfFlight, Meter, TrySendError};
 
 /// Create a wrapped `mpsc::channel` pair of `MeteredSender` and `MeteredReceiver`.
 pub fn unbounded<T>() -> (UnboundedMeteredSender<T>, UnboundedMeteredReceiver<T>) {
+	unbounded_with_tof(0)
+}
+
+/// Create a wrapped `mpsc::unbounded` pair of `UnboundedMeteredSender` and
+/// `UnboundedMeteredReceiver`, which samples the time of flight of every
+/// `tof_sample_every`-th message.
+pub fn unbounded_with_tof<T>(
+	tof_sample_every: usize,
+) -> (UnboundedMeteredSender<T>, UnboundedMeteredReceiver<T>) {
 	let (tx, rx) = mpsc::unbounded();
-	let shared_meter = Meter::default();
+	let shared_meter = Meter::with_tof(tof_sample_every);
 	let tx = UnboundedMeteredSender { meter: shared_meter.clone(), inner: tx };
 	let rx = UnboundedMeteredReceiver { meter: shared_meter, inner: rx };
 	(tx, rx)
@@ -40,31 +49,15 @@ pub fn unbounded<T>() -> (UnboundedMeteredSender<T>, UnboundedMeteredReceiver<T>
 pub struct UnboundedMeteredReceiver<T> {
 	// count currently contained messages
 	meter: Meter,
-	inner: mpsc::UnboundedReceiver<T>,
-}
-
-impl<T> std::ops::Deref for UnboundedMeteredReceiver<T> {
-	type Target = mpsc::UnboundedReceiver<T>;
-	fn deref(&self) -> &Self::Target {
-		&self.inner
-	}
-}
-
-impl<T> std::ops::DerefMut for UnboundedMeteredReceiver<T> {
-	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.inner
-	}
+	inner: mpsc::UnboundedReceiver<MaybeTimeOfFlight<T>>,
 }
 
 impl<T> Stream for UnboundedMeteredReceiver<T> {
 	type Item = T;
 	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
 		match mpsc::UnboundedReceiver::poll_next(Pin::new(&mut self.inner), cx) {
-			Poll::Ready(x) => {
-				self.meter.note_received();
-				Poll::Ready(x)
-			},
-			other => other,
+			Poll::Ready(x) => Poll::Ready(x.map(|x| self.meter.note_received(x))),
+			Poll::Pending => Poll::Pending,
 		}
 	}
 
@@ -83,10 +76,7 @@ impl<T> UnboundedMeteredReceiver<T> {
 	/// Attempt to receive the next item.
 	pub fn try_next(&mut self) -> Result<Option<T>, mpsc::TryRecvError> {
 		match self.inner.try_next()? {
-			Some(x) => {
-				self.meter.note_received();
-				Ok(Some(x))
-			},
+			Some(x) => Ok(Some(self.meter.note_received(x))),
 			None => Ok(None),
 		}
 	}
@@ -103,7 +93,7 @@ impl<T> futures::stream::FusedStream for UnboundedMeteredReceiver<T> {
 #[derive(Debug)]
 pub struct UnboundedMeteredSender<T> {
 	meter: Meter,
-	inner: mpsc::UnboundedSender<T>,
+	inner: mpsc::UnboundedSender<MaybeTimeOfFlight<T>>,
 }
 
 impl<T> Clone for UnboundedMeteredSender<T> {
@@ -112,19 +102,6 @@ impl<T> Clone for UnboundedMeteredSender<T> {
 	}
 }
 
-impl<T> std::ops::Deref for UnboundedMeteredSender<T> {
-	type Target = mpsc::UnboundedSender<T>;
-	fn deref(&self) -> &Self::Target {
-		&self.inner
-	}
-}
-
-impl<T> std::ops::DerefMut for UnboundedMeteredSender<T> {
-	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.inner
-	}
-}
-
 impl<T> UnboundedMeteredSender<T> {
 	/// Get an updated accessor object for all metrics collected.
 	pub fn meter(&self) -> &Meter {
@@ -132,11 +109,11 @@ impl<T> UnboundedMeteredSender<T> {
 	}
 
 	/// Attempt to send message or fail immediately.
-	pub fn unbounded_send(&self, msg: T) -> result::Result<(), mpsc::TrySendError<T>> {
-		self.meter.note_sent();
+	pub fn unbounded_send(&self, msg: T) -> result::Result<(), TrySendError<T>> {
+		let msg = self.meter.note_sent(msg);
 		self.inner.unbounded_send(msg).map_err(|e| {
 			self.meter.retract_sent();
-			e
+			TrySendError::from_inner(e)
 		})
 	}
 }
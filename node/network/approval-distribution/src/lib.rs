@@ -22,10 +22,11 @@
 
 use futures::{channel::oneshot, FutureExt as _};
 use polkadot_node_network_protocol::{
-	v1 as protocol_v1, PeerId, UnifiedReputationChange as Rep, View,
+	v1 as protocol_v1, PeerId, ProtocolVersion, UnifiedReputationChange as Rep, View,
 };
 use polkadot_node_primitives::approval::{
-	AssignmentCert, BlockApprovalMeta, IndirectAssignmentCert, IndirectSignedApprovalVote,
+	AssignmentCert, AssignmentCertKind, BlockApprovalMeta, CandidateBitfield,
+	IndirectAssignmentCert, IndirectSignedApprovalVote,
 };
 use polkadot_node_subsystem::{
 	messages::{
@@ -61,6 +62,9 @@ const BENEFIT_VALID_MESSAGE: Rep = Rep::BenefitMinor("Peer sent a valid message"
 const BENEFIT_VALID_MESSAGE_FIRST: Rep =
 	Rep::BenefitMinorFirst("Valid message with new information");
 
+/// The first version of the validation protocol understanding compact assignments.
+const COMPACT_ASSIGNMENTS_VERSION: ProtocolVersion = 2;
+
 /// The Approval Distribution subsystem.
 pub struct ApprovalDistribution {
 	metrics: Metrics,
@@ -87,6 +91,9 @@ struct State {
 	/// Peer view data is partially stored here, and partially inline within the [`BlockEntry`]s
 	peer_views: HashMap<PeerId, View>,
 
+	/// The version of the validation protocol each peer speaks.
+	peer_versions: HashMap<PeerId, ProtocolVersion>,
+
 	/// Track all our neighbors in the current gossip topology.
 	/// We're not necessarily connected to all of them.
 	gossip_peers: HashSet<PeerId>,
@@ -108,9 +115,21 @@ impl Knowledge {
 		self.known_messages.contains(fingerprint)
 	}
 
+	fn contains_all(&self, fingerprints: &[MessageFingerprint]) -> bool {
+		fingerprints.iter().all(|f| self.contains(f))
+	}
+
 	fn insert(&mut self, fingerprint: MessageFingerprint) -> bool {
 		self.known_messages.insert(fingerprint)
 	}
+
+	fn insert_all(&mut self, fingerprints: impl IntoIterator<Item = MessageFingerprint>) {
+		self.known_messages.extend(fingerprints)
+	}
+
+	fn remove(&mut self, fingerprint: &MessageFingerprint) -> bool {
+		self.known_messages.remove(fingerprint)
+	}
 }
 
 #[derive(Debug, Clone, Default)]
@@ -142,10 +161,52 @@ struct BlockEntry {
 	candidates: Vec<CandidateEntry>,
 }
 
+impl BlockEntry {
+	// Our knowledge of messages in the block with the given hash, restricted to those a peer
+	// speaking the given version of the validation protocol can receive.
+	fn knowledge_understood_by(&self, block_hash: Hash, version: ProtocolVersion) -> Knowledge {
+		let mut knowledge = self.knowledge.clone();
+		if version >= COMPACT_ASSIGNMENTS_VERSION {
+			return knowledge
+		}
+
+		for (candidate_index, candidate_entry) in self.candidates.iter().enumerate() {
+			let candidate_index = candidate_index as CandidateIndex;
+			for (validator_index, (approval_state, _)) in candidate_entry.approvals.iter() {
+				let (cert, claimed) = approval_state.assignment();
+				if !is_legacy_assignment(cert, claimed) {
+					knowledge.remove(&MessageFingerprint::Assignment(
+						block_hash,
+						candidate_index,
+						*validator_index,
+					));
+					knowledge.remove(&MessageFingerprint::Approval(
+						block_hash,
+						candidate_index,
+						*validator_index,
+					));
+				}
+			}
+		}
+
+		knowledge
+	}
+}
+
+// Assignments are stored along with all candidates claimed by the cert.
 #[derive(Debug)]
 enum ApprovalState {
-	Assigned(AssignmentCert),
-	Approved(AssignmentCert, ValidatorSignature),
+	Assigned(AssignmentCert, CandidateBitfield),
+	Approved(AssignmentCert, CandidateBitfield, ValidatorSignature),
+}
+
+impl ApprovalState {
+	fn assignment(&self) -> (&AssignmentCert, &CandidateBitfield) {
+		match self {
+			ApprovalState::Assigned(cert, claimed) | ApprovalState::Approved(cert, claimed, _) =>
+				(cert, claimed),
+		}
+	}
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -185,7 +246,7 @@ impl MessageSource {
 }
 
 enum PendingMessage {
-	Assignment(IndirectAssignmentCert, CandidateIndex),
+	Assignment(IndirectAssignmentCert, CandidateBitfield),
 	Approval(IndirectSignedApprovalVote),
 }
 
@@ -198,19 +259,22 @@ impl State {
 		event: NetworkBridgeEvent<protocol_v1::ApprovalDistributionMessage>,
 	) {
 		match event {
-			NetworkBridgeEvent::PeerConnected(peer_id, role, _) => {
+			NetworkBridgeEvent::PeerConnected(peer_id, role, version, _) => {
 				// insert a blank view if none already present
-				tracing::trace!(target: LOG_TARGET, ?peer_id, ?role, "Peer connected");
+				tracing::trace!(target: LOG_TARGET, ?peer_id, ?role, version, "Peer connected");
+				self.peer_versions.insert(peer_id.clone(), version);
 				self.peer_views.entry(peer_id).or_default();
 			},
 			NetworkBridgeEvent::PeerDisconnected(peer_id) => {
 				tracing::trace!(target: LOG_TARGET, ?peer_id, "Peer disconnected");
 				self.peer_views.remove(&peer_id);
+				self.peer_versions.remove(&peer_id);
 				self.blocks.iter_mut().for_each(|(_hash, entry)| {
 					entry.known_by.remove(&peer_id);
 				})
 			},
-			NetworkBridgeEvent::NewGossipTopology(peers) => {
+			NetworkBridgeEvent::NewGossipTopology(topology) => {
+				let peers = topology.peers();
 				let newly_added: Vec<PeerId> =
 					peers.difference(&self.gossip_peers).cloned().collect();
 				self.gossip_peers = peers;
@@ -319,13 +383,13 @@ impl State {
 
 				for (peer_id, message) in to_import {
 					match message {
-						PendingMessage::Assignment(assignment, claimed_index) => {
+						PendingMessage::Assignment(assignment, claimed_indices) => {
 							self.import_and_circulate_assignment(
 								ctx,
 								metrics,
 								MessageSource::Peer(peer_id),
 								assignment,
-								claimed_index,
+								claimed_indices,
 							)
 							.await;
 						},
@@ -352,6 +416,7 @@ impl State {
 				metrics,
 				&mut self.blocks,
 				peer_id.clone(),
+				peer_version(&self.peer_versions, peer_id),
 				view_intersection,
 			)
 			.await;
@@ -368,44 +433,15 @@ impl State {
 	) {
 		match msg {
 			protocol_v1::ApprovalDistributionMessage::Assignments(assignments) => {
-				tracing::trace!(
-					target: LOG_TARGET,
-					peer_id = %peer_id,
-					num = assignments.len(),
-					"Processing assignments from a peer",
-				);
-				for (assignment, claimed_index) in assignments.into_iter() {
-					if let Some(pending) = self.pending_known.get_mut(&assignment.block_hash) {
-						let fingerprint = MessageFingerprint::Assignment(
-							assignment.block_hash,
-							claimed_index,
-							assignment.validator,
-						);
+				let assignments = assignments
+					.into_iter()
+					.map(|(assignment, claimed_index)| (assignment, claimed_index.into()))
+					.collect();
 
-						tracing::trace!(
-							target: LOG_TARGET,
-							%peer_id,
-							?fingerprint,
-							"Pending assignment",
-						);
-
-						pending.push((
-							peer_id.clone(),
-							PendingMessage::Assignment(assignment, claimed_index),
-						));
-
-						continue
-					}
-
-					self.import_and_circulate_assignment(
-						ctx,
-						metrics,
-						MessageSource::Peer(peer_id.clone()),
-						assignment,
-						claimed_index,
-					)
-					.await;
-				}
+				self.process_incoming_assignments(ctx, metrics, peer_id, assignments).await;
+			},
+			protocol_v1::ApprovalDistributionMessage::CompactAssignments(assignments) => {
+				self.process_incoming_assignments(ctx, metrics, peer_id, assignments).await;
 			},
 			protocol_v1::ApprovalDistributionMessage::Approvals(approvals) => {
 				tracing::trace!(
@@ -446,6 +482,50 @@ impl State {
 		}
 	}
 
+	async fn process_incoming_assignments(
+		&mut self,
+		ctx: &mut (impl SubsystemContext<Message = ApprovalDistributionMessage>
+		          + overseer::SubsystemContext<Message = ApprovalDistributionMessage>),
+		metrics: &Metrics,
+		peer_id: PeerId,
+		assignments: Vec<(IndirectAssignmentCert, CandidateBitfield)>,
+	) {
+		tracing::trace!(
+			target: LOG_TARGET,
+			peer_id = %peer_id,
+			num = assignments.len(),
+			"Processing assignments from a peer",
+		);
+		for (assignment, claimed_indices) in assignments.into_iter() {
+			if let Some(pending) = self.pending_known.get_mut(&assignment.block_hash) {
+				tracing::trace!(
+					target: LOG_TARGET,
+					%peer_id,
+					block_hash = ?assignment.block_hash,
+					?claimed_indices,
+					validator_index = ?assignment.validator,
+					"Pending assignment",
+				);
+
+				pending.push((
+					peer_id.clone(),
+					PendingMessage::Assignment(assignment, claimed_indices),
+				));
+
+				continue
+			}
+
+			self.import_and_circulate_assignment(
+				ctx,
+				metrics,
+				MessageSource::Peer(peer_id.clone()),
+				assignment,
+				claimed_indices,
+			)
+			.await;
+		}
+	}
+
 	async fn handle_peer_view_change(
 		&mut self,
 		ctx: &mut (impl SubsystemContext<Message = ApprovalDistributionMessage>
@@ -483,6 +563,7 @@ impl State {
 			metrics,
 			&mut self.blocks,
 			peer_id.clone(),
+			peer_version(&self.peer_versions, &peer_id),
 			view,
 		)
 		.await;
@@ -510,7 +591,7 @@ impl State {
 		metrics: &Metrics,
 		source: MessageSource,
 		assignment: IndirectAssignmentCert,
-		claimed_candidate_index: CandidateIndex,
+		claimed_candidate_indices: CandidateBitfield,
 	) {
 		let block_hash = assignment.block_hash.clone();
 		let validator_index = assignment.validator;
@@ -532,26 +613,30 @@ impl State {
 			},
 		};
 
-		// compute a fingerprint of the assignment
-		let fingerprint =
-			MessageFingerprint::Assignment(block_hash, claimed_candidate_index, validator_index);
+		// compute a fingerprint of the assignment for every claimed candidate
+		let fingerprints = claimed_candidate_indices
+			.iter_ones()
+			.map(|candidate_index| {
+				MessageFingerprint::Assignment(block_hash, candidate_index, validator_index)
+			})
+			.collect::<Vec<_>>();
 
 		if let Some(peer_id) = source.peer_id() {
 			// check if our knowledge of the peer already contains this assignment
 			match entry.known_by.entry(peer_id.clone()) {
 				hash_map::Entry::Occupied(mut peer_knowledge) => {
 					let peer_knowledge = peer_knowledge.get_mut();
-					if peer_knowledge.contains(&fingerprint) {
-						if peer_knowledge.received.contains(&fingerprint) {
+					if fingerprints.iter().all(|f| peer_knowledge.contains(f)) {
+						if fingerprints.iter().all(|f| peer_knowledge.received.contains(f)) {
 							tracing::debug!(
 								target: LOG_TARGET,
 								?peer_id,
-								?fingerprint,
+								?fingerprints,
 								"Duplicate assignment",
 							);
 							modify_reputation(ctx, peer_id, COST_DUPLICATE_MESSAGE).await;
 						}
-						peer_knowledge.received.insert(fingerprint);
+						peer_knowledge.received.insert_all(fingerprints);
 						return
 					}
 				},
@@ -559,7 +644,7 @@ impl State {
 					tracing::debug!(
 						target: LOG_TARGET,
 						?peer_id,
-						?fingerprint,
+						?fingerprints,
 						"Assignment from a peer is out of view",
 					);
 					modify_reputation(ctx, peer_id.clone(), COST_UNEXPECTED_MESSAGE).await;
@@ -567,11 +652,16 @@ impl State {
 			}
 
 			// if the assignment is known to be valid, reward the peer
-			if entry.knowledge.known_messages.contains(&fingerprint) {
+			if entry.knowledge.contains_all(&fingerprints) {
 				modify_reputation(ctx, peer_id.clone(), BENEFIT_VALID_MESSAGE).await;
 				if let Some(peer_knowledge) = entry.known_by.get_mut(&peer_id) {
-					tracing::trace!(target: LOG_TARGET, ?peer_id, ?fingerprint, "Known assignment");
-					peer_knowledge.received.insert(fingerprint.clone());
+					tracing::trace!(
+						target: LOG_TARGET,
+						?peer_id,
+						?fingerprints,
+						"Known assignment",
+					);
+					peer_knowledge.received.insert_all(fingerprints);
 				}
 				return
 			}
@@ -580,7 +670,7 @@ impl State {
 
 			ctx.send_message(ApprovalVotingMessage::CheckAndImportAssignment(
 				assignment.clone(),
-				claimed_candidate_index,
+				claimed_candidate_indices.clone(),
 				tx,
 			))
 			.await;
@@ -598,16 +688,16 @@ impl State {
 			tracing::trace!(
 				target: LOG_TARGET,
 				?source,
-				?fingerprint,
+				?fingerprints,
 				?result,
 				"Checked assignment",
 			);
 			match result {
 				AssignmentCheckResult::Accepted => {
 					modify_reputation(ctx, peer_id.clone(), BENEFIT_VALID_MESSAGE_FIRST).await;
-					entry.knowledge.known_messages.insert(fingerprint.clone());
+					entry.knowledge.insert_all(fingerprints.iter().cloned());
 					if let Some(peer_knowledge) = entry.known_by.get_mut(&peer_id) {
-						peer_knowledge.received.insert(fingerprint.clone());
+						peer_knowledge.received.insert_all(fingerprints.iter().cloned());
 					}
 				},
 				AssignmentCheckResult::AcceptedDuplicate => {
@@ -615,7 +705,7 @@ impl State {
 					// There is more than one way each validator can be assigned to each core.
 					// cf. https://github.com/paritytech/polkadot/pull/2160#discussion_r557628699
 					if let Some(peer_knowledge) = entry.known_by.get_mut(&peer_id) {
-						peer_knowledge.received.insert(fingerprint);
+						peer_knowledge.received.insert_all(fingerprints);
 					}
 					tracing::debug!(
 						target: LOG_TARGET,
@@ -645,18 +735,29 @@ impl State {
 				},
 			}
 		} else {
-			if !entry.knowledge.known_messages.insert(fingerprint.clone()) {
-				// if we already imported an assignment, there is no need to distribute it again
-				tracing::warn!(
-					target: LOG_TARGET,
-					?fingerprint,
-					"Importing locally an already known assignment",
-				);
+			if entry.knowledge.contains_all(&fingerprints) {
+				// if we already imported an assignment, there is no need to distribute it again.
+				// Assignments claiming multiple candidates are expected to be imported once for
+				// every candidate they trigger on.
+				if claimed_candidate_indices.count_ones() > 1 {
+					tracing::trace!(
+						target: LOG_TARGET,
+						?fingerprints,
+						"Importing locally an already known compact assignment",
+					);
+				} else {
+					tracing::warn!(
+						target: LOG_TARGET,
+						?fingerprints,
+						"Importing locally an already known assignment",
+					);
+				}
 				return
 			} else {
+				entry.knowledge.insert_all(fingerprints.iter().cloned());
 				tracing::debug!(
 					target: LOG_TARGET,
-					?fingerprint,
+					?fingerprints,
 					"Importing locally a new assignment",
 				);
 			}
@@ -667,45 +768,61 @@ impl State {
 		// Invariant: none of the peers except for the `source` know about the assignment.
 		metrics.on_assignment_imported();
 
-		match entry.candidates.get_mut(claimed_candidate_index as usize) {
-			Some(candidate_entry) => {
-				// set the approval state for validator_index to Assigned
-				// unless the approval state is set already
-				candidate_entry.approvals.entry(validator_index).or_insert_with(|| {
-					(ApprovalState::Assigned(assignment.cert.clone()), local_source)
-				});
-			},
-			None => {
-				tracing::warn!(
-					target: LOG_TARGET,
-					hash = ?block_hash,
-					?claimed_candidate_index,
-					"Expected a candidate entry on import_and_circulate_assignment",
-				);
-			},
+		for claimed_candidate_index in claimed_candidate_indices.iter_ones() {
+			match entry.candidates.get_mut(claimed_candidate_index as usize) {
+				Some(candidate_entry) => {
+					// set the approval state for validator_index to Assigned
+					// unless the approval state is set already
+					candidate_entry.approvals.entry(validator_index).or_insert_with(|| {
+						(
+							ApprovalState::Assigned(
+								assignment.cert.clone(),
+								claimed_candidate_indices.clone(),
+							),
+							local_source,
+						)
+					});
+				},
+				None => {
+					tracing::warn!(
+						target: LOG_TARGET,
+						hash = ?block_hash,
+						?claimed_candidate_index,
+						"Expected a candidate entry on import_and_circulate_assignment",
+					);
+				},
+			}
 		}
 
 		// Dispatch a ApprovalDistributionV1Message::Assignment(assignment, candidate_index)
 		// to all peers in the BlockEntry's known_by set who know about the block,
-		// excluding the peer in the source, if source has kind MessageSource::Peer.
+		// excluding the peer in the source, if source has kind MessageSource::Peer,
+		// and peers which can't receive the assignment.
 		let maybe_peer_id = source.peer_id();
+		let peer_versions = &self.peer_versions;
 		let peers = entry
 			.known_by
 			.keys()
 			.cloned()
 			.filter(|key| maybe_peer_id.as_ref().map_or(true, |id| id != key))
+			.filter(|key| {
+				understands_assignment(
+					peer_version(peer_versions, key),
+					&assignment.cert,
+					&claimed_candidate_indices,
+				)
+			})
 			.collect::<Vec<_>>();
 
-		let assignments = vec![(assignment, claimed_candidate_index)];
 		let gossip_peers = &self.gossip_peers;
 		let peers =
 			util::choose_random_subset(|e| gossip_peers.contains(e), peers, MIN_GOSSIP_PEERS);
 
-		// Add the fingerprint of the assignment to the knowledge of each peer.
+		// Add the fingerprints of the assignment to the knowledge of each peer.
 		for peer in peers.iter() {
 			// we already filtered peers above, so this should always be Some
 			if let Some(peer_knowledge) = entry.known_by.get_mut(peer) {
-				peer_knowledge.sent.insert(fingerprint.clone());
+				peer_knowledge.sent.insert_all(fingerprints.iter().cloned());
 			}
 		}
 
@@ -713,19 +830,19 @@ impl State {
 			tracing::trace!(
 				target: LOG_TARGET,
 				?block_hash,
-				?claimed_candidate_index,
+				?claimed_candidate_indices,
 				?local_source,
 				num_peers = peers.len(),
 				"Sending an assignment to peers",
 			);
 
-			ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
-				peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Assignments(assignments),
-				),
-			))
-			.await;
+			for message in assignments_messages(vec![(assignment, claimed_candidate_indices)]) {
+				ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
+					peers.clone(),
+					protocol_v1::ValidationProtocol::ApprovalDistribution(message),
+				))
+				.await;
+			}
 		}
 	}
 
@@ -883,10 +1000,13 @@ impl State {
 				// set the approval state for validator_index to Approved
 				// it should be in assigned state already
 				match candidate_entry.approvals.remove(&validator_index) {
-					Some((ApprovalState::Assigned(cert), _local)) => {
+					Some((ApprovalState::Assigned(cert, claimed), _local)) => {
 						candidate_entry.approvals.insert(
 							validator_index,
-							(ApprovalState::Approved(cert, vote.signature.clone()), local_source),
+							(
+								ApprovalState::Approved(cert, claimed, vote.signature.clone()),
+								local_source,
+							),
 						);
 					},
 					Some((ApprovalState::Approved(..), _)) => {
@@ -917,15 +1037,30 @@ impl State {
 			},
 		}
 
+		// Peers which can't receive the assignment would not accept the approval either.
+		let assignment_understood_by = |version| {
+			entry
+				.candidates
+				.get(candidate_index as usize)
+				.and_then(|candidate_entry| candidate_entry.approvals.get(&validator_index))
+				.map_or(false, |(approval_state, _)| {
+					let (cert, claimed) = approval_state.assignment();
+					understands_assignment(version, cert, claimed)
+				})
+		};
+
 		// Dispatch a ApprovalDistributionV1Message::Approval(vote)
 		// to all peers in the BlockEntry's known_by set who know about the block,
-		// excluding the peer in the source, if source has kind MessageSource::Peer.
+		// excluding the peer in the source, if source has kind MessageSource::Peer,
+		// and peers which can't receive the assignment.
 		let maybe_peer_id = source.peer_id();
+		let peer_versions = &self.peer_versions;
 		let peers = entry
 			.known_by
 			.keys()
 			.cloned()
 			.filter(|key| maybe_peer_id.as_ref().map_or(true, |id| id != key))
+			.filter(|key| assignment_understood_by(peer_version(peer_versions, key)))
 			.collect::<Vec<_>>();
 
 		let gossip_peers = &self.gossip_peers;
@@ -968,6 +1103,7 @@ impl State {
 		metrics: &Metrics,
 		entries: &mut HashMap<Hash, BlockEntry>,
 		peer_id: PeerId,
+		version: ProtocolVersion,
 		view: View,
 	) {
 		let is_gossip_peer = gossip_peers.contains(&peer_id);
@@ -1001,7 +1137,7 @@ impl State {
 					// step 4.
 					hash_map::Entry::Vacant(vacant) => {
 						let knowledge = PeerKnowledge {
-							sent: entry.knowledge.clone(),
+							sent: entry.knowledge_understood_by(block, version),
 							received: Default::default(),
 						};
 						vacant.insert(knowledge);
@@ -1016,7 +1152,7 @@ impl State {
 		}
 		// step 6.
 		// send all assignments and approvals for all candidates in those blocks to the peer
-		Self::send_gossip_messages_to_peer(entries, ctx, peer_id, to_send).await;
+		Self::send_gossip_messages_to_peer(entries, ctx, peer_id, version, to_send).await;
 	}
 
 	async fn send_gossip_messages_to_peer(
@@ -1024,6 +1160,7 @@ impl State {
 		ctx: &mut (impl SubsystemContext<Message = ApprovalDistributionMessage>
 		          + overseer::SubsystemContext<Message = ApprovalDistributionMessage>),
 		peer_id: PeerId,
+		version: ProtocolVersion,
 		blocks: Vec<Hash>,
 	) {
 		let mut assignments = Vec::new();
@@ -1044,37 +1181,35 @@ impl State {
 			);
 
 			for (candidate_index, candidate_entry) in entry.candidates.iter().enumerate() {
-				let candidate_index = candidate_index as u32;
+				let candidate_index = candidate_index as CandidateIndex;
 				for (validator_index, (approval_state, _is_local)) in
 					candidate_entry.approvals.iter()
 				{
-					match approval_state {
-						ApprovalState::Assigned(cert) => {
-							assignments.push((
-								IndirectAssignmentCert {
-									block_hash: block.clone(),
-									validator: validator_index.clone(),
-									cert: cert.clone(),
-								},
-								candidate_index.clone(),
-							));
-						},
-						ApprovalState::Approved(assignment_cert, signature) => {
-							assignments.push((
-								IndirectAssignmentCert {
-									block_hash: block.clone(),
-									validator: validator_index.clone(),
-									cert: assignment_cert.clone(),
-								},
-								candidate_index.clone(),
-							));
-							approvals.push(IndirectSignedApprovalVote {
+					let (assignment_cert, claimed) = approval_state.assignment();
+					if !understands_assignment(version, assignment_cert, claimed) {
+						continue
+					}
+
+					if let ApprovalState::Approved(_, _, signature) = approval_state {
+						approvals.push(IndirectSignedApprovalVote {
+							block_hash: block.clone(),
+							validator: validator_index.clone(),
+							candidate_index: candidate_index.clone(),
+							signature: signature.clone(),
+						});
+					}
+
+					// An assignment claiming multiple candidates is only sent once, along
+					// with its first candidate.
+					if claimed.first_one() == Some(candidate_index) {
+						assignments.push((
+							IndirectAssignmentCert {
 								block_hash: block.clone(),
 								validator: validator_index.clone(),
-								candidate_index: candidate_index.clone(),
-								signature: signature.clone(),
-							});
-						},
+								cert: assignment_cert.clone(),
+							},
+							claimed.clone(),
+						));
 					}
 				}
 			}
@@ -1089,13 +1224,13 @@ impl State {
 				"Sending assignments to a peer",
 			);
 
-			ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
-				vec![peer_id.clone()],
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Assignments(assignments),
-				),
-			))
-			.await;
+			for message in assignments_messages(assignments) {
+				ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
+					vec![peer_id.clone()],
+					protocol_v1::ValidationProtocol::ApprovalDistribution(message),
+				))
+				.await;
+			}
 		}
 
 		if !approvals.is_empty() {
@@ -1118,6 +1253,54 @@ impl State {
 	}
 }
 
+// Whether the assignment claims a single candidate with a cert understood by all peers, so it
+// can be sent as part of `Assignments`.
+fn is_legacy_assignment(cert: &AssignmentCert, claimed: &CandidateBitfield) -> bool {
+	claimed.count_ones() == 1 &&
+		!matches!(cert.kind, AssignmentCertKind::RelayVRFModuloCompact { .. })
+}
+
+// Whether a peer speaking the given version of the validation protocol can receive the
+// assignment, and thus approvals based on it.
+fn understands_assignment(
+	version: ProtocolVersion,
+	cert: &AssignmentCert,
+	claimed: &CandidateBitfield,
+) -> bool {
+	version >= COMPACT_ASSIGNMENTS_VERSION || is_legacy_assignment(cert, claimed)
+}
+
+// The version of the validation protocol a peer speaks, assuming the oldest for unknown peers.
+fn peer_version(versions: &HashMap<PeerId, ProtocolVersion>, peer: &PeerId) -> ProtocolVersion {
+	versions.get(peer).copied().unwrap_or(1)
+}
+
+// Split assignments into network messages. Legacy assignments are sent as `Assignments`, all
+// others as `CompactAssignments`, which only peers with version 2 of the validation protocol
+// can receive.
+fn assignments_messages(
+	assignments: Vec<(IndirectAssignmentCert, CandidateBitfield)>,
+) -> Vec<protocol_v1::ApprovalDistributionMessage> {
+	let (legacy, compact): (Vec<_>, Vec<_>) = assignments
+		.into_iter()
+		.partition(|(assignment, claimed)| is_legacy_assignment(&assignment.cert, claimed));
+
+	let legacy = legacy
+		.into_iter()
+		.filter_map(|(assignment, claimed)| claimed.first_one().map(|index| (assignment, index)))
+		.collect::<Vec<_>>();
+
+	let mut messages = Vec::new();
+	if !legacy.is_empty() {
+		messages.push(protocol_v1::ApprovalDistributionMessage::Assignments(legacy));
+	}
+	if !compact.is_empty() {
+		messages.push(protocol_v1::ApprovalDistributionMessage::CompactAssignments(compact));
+	}
+
+	messages
+}
+
 /// Modify the reputation of a peer based on its behavior.
 async fn modify_reputation(
 	ctx: &mut (impl SubsystemContext<Message = ApprovalDistributionMessage>
@@ -1177,13 +1360,13 @@ impl ApprovalDistribution {
 					state.handle_new_blocks(&mut ctx, &self.metrics, metas).await;
 				},
 				FromOverseer::Communication {
-					msg: ApprovalDistributionMessage::DistributeAssignment(cert, candidate_index),
+					msg: ApprovalDistributionMessage::DistributeAssignment(cert, candidate_indices),
 				} => {
 					tracing::debug!(
 						target: LOG_TARGET,
-						"Distributing our assignment on candidate (block={}, index={})",
+						"Distributing our assignment on candidates (block={}, indices={:?})",
 						cert.block_hash,
-						candidate_index,
+						candidate_indices,
 					);
 
 					state
@@ -1192,7 +1375,7 @@ impl ApprovalDistribution {
 							&self.metrics,
 							MessageSource::Local,
 							cert,
-							candidate_index,
+							candidate_indices,
 						)
 						.await;
 				},
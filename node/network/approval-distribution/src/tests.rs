@@ -17,10 +17,13 @@
 use super::*;
 use assert_matches::assert_matches;
 use futures::{executor, future, Future};
-use polkadot_node_network_protocol::{view, ObservedRole};
+use polkadot_node_network_protocol::{peer_set::PeerSet, view, ObservedRole};
 use polkadot_node_primitives::approval::{
-	AssignmentCertKind, VRFOutput, VRFProof, RELAY_VRF_MODULO_CONTEXT,
+	AssignmentCertKind, CoreBitfield, VRFOutput, VRFProof, RELAY_VRF_MODULO_COMPACT_CONTEXT,
+	RELAY_VRF_MODULO_CONTEXT,
 };
+use polkadot_primitives::v1::CoreIndex;
+use std::convert::TryFrom;
 use polkadot_node_subsystem::messages::{AllMessages, ApprovalCheckError};
 use polkadot_node_subsystem_test_helpers as test_helpers;
 use polkadot_node_subsystem_util::TimeoutExt as _;
@@ -103,12 +106,28 @@ async fn setup_peer_with_view(
 	virtual_overseer: &mut VirtualOverseer,
 	peer_id: &PeerId,
 	view: View,
+) {
+	setup_peer_with_view_and_version(
+		virtual_overseer,
+		peer_id,
+		PeerSet::Validation.get_main_version(),
+		view,
+	)
+	.await
+}
+
+async fn setup_peer_with_view_and_version(
+	virtual_overseer: &mut VirtualOverseer,
+	peer_id: &PeerId,
+	version: ProtocolVersion,
+	view: View,
 ) {
 	overseer_send(
 		virtual_overseer,
 		ApprovalDistributionMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerConnected(
 			peer_id.clone(),
 			ObservedRole::Full,
+			version,
 			None,
 		)),
 	)
@@ -156,6 +175,29 @@ fn fake_assignment_cert(block_hash: Hash, validator: ValidatorIndex) -> Indirect
 	}
 }
 
+fn fake_compact_assignment_cert(
+	block_hash: Hash,
+	validator: ValidatorIndex,
+	cores: Vec<CoreIndex>,
+) -> IndirectAssignmentCert {
+	let ctx = schnorrkel::signing_context(RELAY_VRF_MODULO_COMPACT_CONTEXT);
+	let msg = b"WhenParachains?";
+	let mut prng = rand_core::OsRng;
+	let keypair = schnorrkel::Keypair::generate_with(&mut prng);
+	let (inout, proof, _) = keypair.vrf_sign(ctx.bytes(msg));
+	let out = inout.to_output();
+	let core_bitfield = CoreBitfield::try_from(cores).expect("at least one core; qed");
+
+	IndirectAssignmentCert {
+		block_hash,
+		validator,
+		cert: AssignmentCert {
+			kind: AssignmentCertKind::RelayVRFModuloCompact { core_bitfield },
+			vrf: (VRFOutput(out), VRFProof(proof)),
+		},
+	}
+}
+
 async fn expect_reputation_change(
 	virtual_overseer: &mut VirtualOverseer,
 	peer_id: &PeerId,
@@ -220,10 +262,11 @@ fn try_import_the_same_assignment() {
 			overseer_recv(overseer).await,
 			AllMessages::ApprovalVoting(ApprovalVotingMessage::CheckAndImportAssignment(
 				assignment,
-				0u32,
+				claimed_candidate_indices,
 				tx,
 			)) => {
 				assert_eq!(assignment, cert);
+				assert_eq!(claimed_candidate_indices, 0u32.into());
 				tx.send(AssignmentCheckResult::Accepted).unwrap();
 			}
 		);
@@ -312,7 +355,7 @@ fn spam_attack_results_in_negative_reputation_change() {
 					tx,
 				)) => {
 					assert_eq!(assignment, assignments[i].0);
-					assert_eq!(claimed_candidate_index, assignments[i].1);
+					assert_eq!(claimed_candidate_index, assignments[i].1.into());
 					tx.send(AssignmentCheckResult::Accepted).unwrap();
 				}
 			);
@@ -375,7 +418,7 @@ fn peer_sending_us_the_same_we_just_sent_them_is_ok() {
 		let cert = fake_assignment_cert(hash, validator_index);
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index.into()),
 		)
 		.await;
 
@@ -452,7 +495,7 @@ fn import_approval_happy_path() {
 		let cert = fake_assignment_cert(hash, validator_index);
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert, candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(cert, candidate_index.into()),
 		)
 		.await;
 
@@ -561,7 +604,7 @@ fn import_approval_bad() {
 				tx,
 			)) => {
 				assert_eq!(assignment, cert);
-				assert_eq!(i, candidate_index);
+				assert_eq!(i, candidate_index.into());
 				tx.send(AssignmentCheckResult::Accepted).unwrap();
 			}
 		);
@@ -700,9 +743,17 @@ fn update_peer_view() {
 		let cert_a = fake_assignment_cert(hash_a, ValidatorIndex(0));
 		let cert_b = fake_assignment_cert(hash_b, ValidatorIndex(0));
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeAssignment(cert_a, 0)).await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(cert_a, 0u32.into()),
+		)
+		.await;
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeAssignment(cert_b, 0)).await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(cert_b, 0u32.into()),
+		)
+		.await;
 
 		// connect a peer
 		setup_peer_with_view(overseer, peer, view![hash_a]).await;
@@ -754,7 +805,7 @@ fn update_peer_view() {
 
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert_c.clone(), 0),
+			ApprovalDistributionMessage::DistributeAssignment(cert_c.clone(), 0u32.into()),
 		)
 		.await;
 
@@ -850,7 +901,7 @@ fn import_remotely_then_locally() {
 				tx,
 			)) => {
 				assert_eq!(assignment, cert);
-				assert_eq!(i, candidate_index);
+				assert_eq!(i, candidate_index.into());
 				tx.send(AssignmentCheckResult::Accepted).unwrap();
 			}
 		);
@@ -860,7 +911,7 @@ fn import_remotely_then_locally() {
 		// import the same assignment locally
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert, candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(cert, candidate_index.into()),
 		)
 		.await;
 
@@ -931,7 +982,7 @@ fn sends_assignments_even_when_state_is_approved() {
 
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index.into()),
 		)
 		.await;
 
@@ -974,3 +1025,185 @@ fn sends_assignments_even_when_state_is_approved() {
 		virtual_overseer
 	});
 }
+
+#[test]
+fn compact_assignments_are_sent_once_for_all_claimed_candidates() {
+	let peer_a = PeerId::random();
+	let parent_hash = Hash::repeat_byte(0xFF);
+	let hash = Hash::repeat_byte(0xAA);
+	let peer = &peer_a;
+
+	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+
+		// new block `hash` with 2 candidates
+		let meta = BlockApprovalMeta {
+			hash,
+			parent_hash,
+			number: 1,
+			candidates: vec![Default::default(); 2],
+			slot: 1.into(),
+		};
+		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
+		overseer_send(overseer, msg).await;
+
+		let validator_index = ValidatorIndex(0);
+		let cert =
+			fake_compact_assignment_cert(hash, validator_index, vec![CoreIndex(0), CoreIndex(1)]);
+		let claimed = CandidateBitfield::try_from(vec![0u32, 1]).unwrap();
+
+		// approval voting distributes the assignment once for every candidate it triggers on.
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), claimed.clone()),
+		)
+		.await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), claimed.clone()),
+		)
+		.await;
+
+		// connect the peer.
+		setup_peer_with_view(overseer, peer, view![hash]).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				peers,
+				protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::CompactAssignments(sent_assignments)
+				)
+			)) => {
+				assert_eq!(peers, vec![peer.clone()]);
+				assert_eq!(sent_assignments, vec![(cert.clone(), claimed.clone())]);
+			}
+		);
+
+		// the peer sending it back is not punished.
+		let msg =
+			protocol_v1::ApprovalDistributionMessage::CompactAssignments(vec![(cert, claimed)]);
+		send_message_from_peer(overseer, peer, msg).await;
+
+		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
+		virtual_overseer
+	});
+}
+
+#[test]
+fn legacy_peers_only_receive_assignments_they_understand() {
+	let peer_a = PeerId::random();
+	let parent_hash = Hash::repeat_byte(0xFF);
+	let hash = Hash::repeat_byte(0xAA);
+	let peer = &peer_a;
+
+	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+
+		// new block `hash` with 2 candidates
+		let meta = BlockApprovalMeta {
+			hash,
+			parent_hash,
+			number: 1,
+			candidates: vec![Default::default(); 2],
+			slot: 1.into(),
+		};
+		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
+		overseer_send(overseer, msg).await;
+
+		// import a legacy and a compact assignment along with approvals locally.
+		let legacy_cert = fake_assignment_cert(hash, ValidatorIndex(0));
+		let compact_cert =
+			fake_compact_assignment_cert(hash, ValidatorIndex(1), vec![CoreIndex(0), CoreIndex(1)]);
+		let compact_claimed = CandidateBitfield::try_from(vec![0u32, 1]).unwrap();
+		let approval = |validator| IndirectSignedApprovalVote {
+			block_hash: hash,
+			candidate_index: 0,
+			validator,
+			signature: dummy_signature(),
+		};
+
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(legacy_cert.clone(), 0u32.into()),
+		)
+		.await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(compact_cert, compact_claimed),
+		)
+		.await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeApproval(approval(ValidatorIndex(0))),
+		)
+		.await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeApproval(approval(ValidatorIndex(1))),
+		)
+		.await;
+
+		// connect a peer speaking the first version of the validation protocol.
+		setup_peer_with_view_and_version(overseer, peer, 1, view![hash]).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				peers,
+				protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
+				)
+			)) => {
+				assert_eq!(peers, vec![peer.clone()]);
+				assert_eq!(sent_assignments, vec![(legacy_cert, 0)]);
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				peers,
+				protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Approvals(sent_approvals)
+				)
+			)) => {
+				assert_eq!(peers, vec![peer.clone()]);
+				assert_eq!(sent_approvals, vec![approval(ValidatorIndex(0))]);
+			}
+		);
+
+		// new compact assignments are not circulated to the peer, legacy ones are.
+		let compact_cert =
+			fake_compact_assignment_cert(hash, ValidatorIndex(2), vec![CoreIndex(0), CoreIndex(1)]);
+		let compact_claimed = CandidateBitfield::try_from(vec![0u32, 1]).unwrap();
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(compact_cert, compact_claimed),
+		)
+		.await;
+
+		let legacy_cert = fake_assignment_cert(hash, ValidatorIndex(3));
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(legacy_cert.clone(), 1u32.into()),
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				peers,
+				protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
+				)
+			)) => {
+				assert_eq!(peers, vec![peer.clone()]);
+				assert_eq!(sent_assignments, vec![(legacy_cert, 1)]);
+			}
+		);
+
+		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
+		virtual_overseer
+	});
+}
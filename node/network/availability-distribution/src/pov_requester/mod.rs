@@ -18,18 +18,22 @@
 
 use futures::{channel::oneshot, future::BoxFuture, FutureExt};
 
-use polkadot_node_network_protocol::request_response::{
-	outgoing::{RequestError, Requests},
-	v1::{PoVFetchingRequest, PoVFetchingResponse},
-	OutgoingRequest, Recipient,
+use polkadot_node_network_protocol::{
+	request_response::{
+		outgoing::{RequestError, Requests},
+		v1::{PoVFetchingRequest, PoVFetchingResponse},
+		OutgoingRequest, Recipient,
+	},
+	UnifiedReputationChange as Rep,
 };
 use polkadot_node_primitives::PoV;
 use polkadot_node_subsystem_util::runtime::RuntimeInfo;
 use polkadot_primitives::v1::{AuthorityDiscoveryId, CandidateHash, Hash, ValidatorIndex};
 use polkadot_subsystem::{
 	jaeger,
-	messages::{IfDisconnected, NetworkBridgeMessage},
-	SubsystemContext,
+	messages::{AvailabilityDistributionMessage, IfDisconnected, NetworkBridgeMessage},
+	overseer::{self, AvailabilityDistributionOutgoingMessages},
+	SubsystemContext, SubsystemSender,
 };
 
 use crate::{
@@ -38,6 +42,9 @@ use crate::{
 	Metrics, LOG_TARGET,
 };
 
+const COST_MISSING_POV: Rep =
+	Rep::CostMajor("Validator did not serve the PoV of a candidate it backed");
+
 /// Start background worker for taking care of fetching the requested `PoV` from the network.
 pub async fn fetch_pov<Context>(
 	ctx: &mut Context,
@@ -50,7 +57,8 @@ pub async fn fetch_pov<Context>(
 	metrics: Metrics,
 ) -> super::Result<()>
 where
-	Context: SubsystemContext,
+	Context: SubsystemContext<Message = AvailabilityDistributionMessage>,
+	Context: overseer::SubsystemContext<Message = AvailabilityDistributionMessage>,
 {
 	let info = &runtime.get_session_info(ctx.sender(), parent).await?.session_info;
 	let authority_id = info
@@ -73,9 +81,11 @@ where
 	let span = jaeger::Span::new(candidate_hash, "fetch-pov")
 		.with_validator_index(from_validator)
 		.with_relay_parent(parent);
+	let sender = ctx.sender().clone();
 	ctx.spawn(
 		"pov-fetcher",
-		fetch_pov_job(pov_hash, authority_id, pending_response.boxed(), span, tx, metrics).boxed(),
+		fetch_pov_job(sender, pov_hash, authority_id, pending_response.boxed(), span, tx, metrics)
+			.boxed(),
 	)
 	.map_err(|e| Fatal::SpawnTask(e))?;
 	Ok(())
@@ -83,6 +93,7 @@ where
 
 /// Future to be spawned for taking care of handling reception and sending of PoV.
 async fn fetch_pov_job(
+	mut sender: impl SubsystemSender<AvailabilityDistributionOutgoingMessages>,
 	pov_hash: Hash,
 	authority_id: AuthorityDiscoveryId,
 	pending_response: BoxFuture<'static, Result<PoVFetchingResponse, RequestError>>,
@@ -92,6 +103,14 @@ async fn fetch_pov_job(
 ) {
 	if let Err(err) = do_fetch_pov(pov_hash, pending_response, span, tx, metrics).await {
 		tracing::warn!(target: LOG_TARGET, ?err, ?pov_hash, ?authority_id, "fetch_pov_job");
+		// We only ever fetch PoVs from validators which backed the candidate.
+		if let NonFatal::NoSuchPoV = err {
+			sender
+				.send_message(
+					NetworkBridgeMessage::ReportAuthority(authority_id, COST_MISSING_POV).into(),
+				)
+				.await;
+		}
 	}
 }
 
@@ -147,17 +166,23 @@ mod tests {
 	fn rejects_invalid_pov() {
 		sp_tracing::try_init_simple();
 		let pov = PoV { block_data: BlockData(vec![1, 2, 3, 4, 5, 6]) };
-		test_run(Hash::default(), pov);
+		test_run(Hash::default(), PoVFetchingResponse::PoV(pov));
 	}
 
 	#[test]
 	fn accepts_valid_pov() {
 		sp_tracing::try_init_simple();
 		let pov = PoV { block_data: BlockData(vec![1, 2, 3, 4, 5, 6]) };
-		test_run(pov.hash(), pov);
+		test_run(pov.hash(), PoVFetchingResponse::PoV(pov));
+	}
+
+	#[test]
+	fn reports_missing_pov() {
+		sp_tracing::try_init_simple();
+		test_run(Hash::default(), PoVFetchingResponse::NoSuchPoV);
 	}
 
-	fn test_run(pov_hash: Hash, pov: PoV) {
+	fn test_run(pov_hash: Hash, response: PoVFetchingResponse) {
 		let pool = TaskExecutor::new();
 		let (mut context, mut virtual_overseer) = test_helpers::make_subsystem_context::<
 			AvailabilityDistributionMessage,
@@ -202,18 +227,30 @@ mod tests {
 							reqs.pop(),
 							Some(Requests::PoVFetching(outgoing)) => {outgoing}
 						);
-						req.pending_response
-							.send(Ok(PoVFetchingResponse::PoV(pov.clone()).encode()))
-							.unwrap();
+						req.pending_response.send(Ok(response.encode())).unwrap();
 						break
 					},
 					msg => tracing::debug!(target: LOG_TARGET, msg = ?msg, "Received msg"),
 				}
 			}
-			if pov.hash() == pov_hash {
-				assert_eq!(rx.await, Ok(pov));
-			} else {
-				assert_eq!(rx.await, Err(oneshot::Canceled));
+			match response {
+				PoVFetchingResponse::PoV(pov) if pov.hash() == pov_hash => {
+					assert_eq!(rx.await, Ok(pov));
+				},
+				PoVFetchingResponse::PoV(_) => {
+					assert_eq!(rx.await, Err(oneshot::Canceled));
+				},
+				PoVFetchingResponse::NoSuchPoV => {
+					assert_matches!(
+						virtual_overseer.recv().await,
+						AllMessages::NetworkBridge(
+							NetworkBridgeMessage::ReportAuthority(_, rep)
+						) => {
+							assert_eq!(rep, COST_MISSING_POV);
+						}
+					);
+					assert_eq!(rx.await, Err(oneshot::Canceled));
+				},
 			}
 		};
 		futures::pin_mut!(testee);
@@ -23,9 +23,12 @@ use futures::{
 };
 
 use polkadot_erasure_coding::branch_hash;
-use polkadot_node_network_protocol::request_response::{
-	outgoing::{OutgoingRequest, Recipient, RequestError, Requests},
-	v1::{ChunkFetchingRequest, ChunkFetchingResponse},
+use polkadot_node_network_protocol::{
+	request_response::{
+		outgoing::{OutgoingRequest, Recipient, RequestError, Requests},
+		v1::{ChunkFetchingRequest, ChunkFetchingResponse},
+	},
+	UnifiedReputationChange as Rep,
 };
 use polkadot_node_primitives::ErasureChunk;
 use polkadot_primitives::v1::{
@@ -34,7 +37,11 @@ use polkadot_primitives::v1::{
 };
 use polkadot_subsystem::{
 	jaeger,
-	messages::{AllMessages, AvailabilityStoreMessage, IfDisconnected, NetworkBridgeMessage},
+	messages::{
+		AvailabilityDistributionMessage, AvailabilityStoreMessage, IfDisconnected,
+		NetworkBridgeMessage,
+	},
+	overseer::{self, AvailabilityDistributionOutgoingMessages},
 	SubsystemContext,
 };
 
@@ -48,6 +55,9 @@ use crate::{
 #[cfg(test)]
 mod tests;
 
+const COST_MISSING_CHUNK: Rep =
+	Rep::CostMajor("Validator did not serve a chunk of a candidate it backed");
+
 /// Configuration for a `FetchTask`
 ///
 /// This exists to separate preparation of a `FetchTask` from actual starting it, which is
@@ -84,7 +94,7 @@ enum FetchedState {
 /// Messages sent from `FetchTask`s to be handled/forwarded.
 pub enum FromFetchTask {
 	/// Message to other subsystem.
-	Message(AllMessages),
+	Message(AvailabilityDistributionOutgoingMessages),
 
 	/// Concluded with result.
 	///
@@ -177,7 +187,8 @@ impl FetchTask {
 	/// A task handling the fetching of the configured chunk will be spawned.
 	pub async fn start<Context>(config: FetchTaskConfig, ctx: &mut Context) -> Result<Self>
 	where
-		Context: SubsystemContext,
+		Context: SubsystemContext<Message = AvailabilityDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = AvailabilityDistributionMessage>,
 	{
 		let FetchTaskConfig { prepared_running, live_in } = config;
 
@@ -291,6 +302,8 @@ impl RunningTask {
 						validator = ?validator,
 						"Validator did not have our chunk"
 					);
+					// Backers store all chunks before they sign their statement.
+					self.report(validator.clone(), COST_MISSING_CHUNK).await;
 					bad_validators.push(validator);
 					continue
 				},
@@ -328,9 +341,10 @@ impl RunningTask {
 		let requests = Requests::ChunkFetching(full_request);
 
 		self.sender
-			.send(FromFetchTask::Message(AllMessages::NetworkBridge(
-				NetworkBridgeMessage::SendRequests(vec![requests], IfDisconnected::ImmediateError),
-			)))
+			.send(FromFetchTask::Message(
+				NetworkBridgeMessage::SendRequests(vec![requests], IfDisconnected::ImmediateError)
+					.into(),
+			))
 			.await
 			.map_err(|_| TaskError::ShuttingDown)?;
 
@@ -391,13 +405,14 @@ impl RunningTask {
 		let (tx, rx) = oneshot::channel();
 		let r = self
 			.sender
-			.send(FromFetchTask::Message(AllMessages::AvailabilityStore(
+			.send(FromFetchTask::Message(
 				AvailabilityStoreMessage::StoreChunk {
 					candidate_hash: self.request.candidate_hash,
 					chunk,
 					tx,
-				},
-			)))
+				}
+				.into(),
+			))
 			.await;
 		if let Err(err) = r {
 			tracing::error!(target: LOG_TARGET, err= ?err, "Storing erasure chunk failed, system shutting down?");
@@ -408,6 +423,19 @@ impl RunningTask {
 		}
 	}
 
+	/// Report the peer of a validator, log any error.
+	async fn report(&mut self, validator: AuthorityDiscoveryId, rep: Rep) {
+		let r = self
+			.sender
+			.send(FromFetchTask::Message(
+				NetworkBridgeMessage::ReportAuthority(validator, rep).into(),
+			))
+			.await;
+		if let Err(err) = r {
+			tracing::warn!(target: LOG_TARGET, err= ?err, "Reporting validator failed");
+		}
+	}
+
 	/// Tell subsystem we are done.
 	async fn conclude(&mut self, bad_validators: Vec<AuthorityDiscoveryId>) {
 		let payload = if bad_validators.is_empty() {
@@ -16,11 +16,12 @@
 
 use std::{collections::HashMap, convert::TryFrom};
 
+use assert_matches::assert_matches;
 use parity_scale_codec::Encode;
 
 use futures::{
 	channel::{mpsc, oneshot},
-	executor, select,
+	executor, future, select,
 	task::{noop_waker, Context, Poll},
 	Future, FutureExt, StreamExt,
 };
@@ -31,6 +32,7 @@ use sp_keyring::Sr25519Keyring;
 use polkadot_node_network_protocol::request_response::{v1, Recipient};
 use polkadot_node_primitives::{BlockData, PoV, Proof};
 use polkadot_primitives::v1::{CandidateHash, ValidatorIndex};
+use polkadot_subsystem::messages::AllMessages;
 
 use super::*;
 use crate::{metrics::Metrics, tests::mock::get_valid_chunk_data};
@@ -185,6 +187,45 @@ fn task_stores_valid_chunk_if_there_is_one() {
 	test.run(task, rx);
 }
 
+/// Validators answering `NoSuchChunk` get reported.
+#[test]
+fn task_reports_validator_without_chunk() {
+	let (mut task, mut rx) = get_test_running_task();
+	task.group = vec![Sr25519Keyring::Alice.public().into()];
+
+	let tester = async move {
+		assert_matches!(
+			rx.next().await,
+			Some(FromFetchTask::Message(msg)) => assert_matches!(
+				AllMessages::from(msg),
+				AllMessages::NetworkBridge(NetworkBridgeMessage::SendRequests(mut reqs, _)) => {
+					let req = assert_matches!(
+						reqs.pop(),
+						Some(Requests::ChunkFetching(req)) => req
+					);
+					req.pending_response
+						.send(Ok(ChunkFetchingResponse::NoSuchChunk.encode()))
+						.unwrap();
+				}
+			)
+		);
+		assert_matches!(
+			rx.next().await,
+			Some(FromFetchTask::Message(msg)) => assert_matches!(
+				AllMessages::from(msg),
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::ReportAuthority(authority, rep)
+				) => {
+					assert_eq!(authority, Sr25519Keyring::Alice.public().into());
+					assert_eq!(rep, COST_MISSING_CHUNK);
+				}
+			)
+		);
+		assert_matches!(rx.next().await, Some(FromFetchTask::Failed(_)));
+	};
+	executor::block_on(future::join(task.run_inner(), tester));
+}
+
 struct TestRun {
 	/// Response to deliver for a given validator index.
 	/// None means, answer with `NetworkError`.
@@ -215,7 +256,7 @@ impl TestRun {
 				match msg {
 					FromFetchTask::Concluded(_) => break,
 					FromFetchTask::Failed(_) => break,
-					FromFetchTask::Message(msg) => end_ok = self.handle_message(msg).await,
+					FromFetchTask::Message(msg) => end_ok = self.handle_message(msg.into()).await,
 				}
 			}
 			if !end_ok {
@@ -252,6 +293,14 @@ impl TestRun {
 				}
 				return (valid_responses == 0) && self.valid_chunks.is_empty()
 			},
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ReportAuthority(authority, rep)) => {
+				assert_matches!(
+					self.chunk_responses.get(&Recipient::Authority(authority)),
+					Some(ChunkFetchingResponse::NoSuchChunk)
+				);
+				assert_eq!(rep, COST_MISSING_CHUNK);
+				return false
+			},
 			AllMessages::AvailabilityStore(AvailabilityStoreMessage::StoreChunk {
 				chunk,
 				tx,
@@ -35,7 +35,9 @@ use futures::{
 use polkadot_node_subsystem_util::runtime::{get_occupied_cores, RuntimeInfo};
 use polkadot_primitives::v1::{CandidateHash, Hash, OccupiedCore};
 use polkadot_subsystem::{
-	messages::AllMessages, ActivatedLeaf, ActiveLeavesUpdate, LeafStatus, SubsystemContext,
+	messages::AvailabilityDistributionMessage,
+	overseer::{self, AvailabilityDistributionOutgoingMessages},
+	ActivatedLeaf, ActiveLeavesUpdate, LeafStatus, SubsystemContext,
 };
 
 use super::{Metrics, LOG_TARGET};
@@ -93,7 +95,8 @@ impl Requester {
 		update: ActiveLeavesUpdate,
 	) -> super::Result<()>
 	where
-		Context: SubsystemContext,
+		Context: SubsystemContext<Message = AvailabilityDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = AvailabilityDistributionMessage>,
 	{
 		tracing::trace!(target: LOG_TARGET, ?update, "Update fetching heads");
 		let ActiveLeavesUpdate { activated, deactivated } = update;
@@ -117,7 +120,8 @@ impl Requester {
 		new_heads: impl Iterator<Item = ActivatedLeaf>,
 	) -> super::Result<()>
 	where
-		Context: SubsystemContext,
+		Context: SubsystemContext<Message = AvailabilityDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = AvailabilityDistributionMessage>,
 	{
 		for ActivatedLeaf { hash: leaf, .. } in new_heads {
 			let cores = get_occupied_cores(ctx, leaf).await?;
@@ -156,7 +160,8 @@ impl Requester {
 		cores: impl IntoIterator<Item = OccupiedCore>,
 	) -> super::Result<()>
 	where
-		Context: SubsystemContext,
+		Context: SubsystemContext<Message = AvailabilityDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = AvailabilityDistributionMessage>,
 	{
 		for core in cores {
 			match self.fetches.entry(core.candidate_hash) {
@@ -194,9 +199,12 @@ impl Requester {
 }
 
 impl Stream for Requester {
-	type Item = AllMessages;
+	type Item = AvailabilityDistributionOutgoingMessages;
 
-	fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<AllMessages>> {
+	fn poll_next(
+		mut self: Pin<&mut Self>,
+		ctx: &mut Context,
+	) -> Poll<Option<AvailabilityDistributionOutgoingMessages>> {
 		loop {
 			match Pin::new(&mut self.rx).poll_next(ctx) {
 				Poll::Ready(Some(FromFetchTask::Message(m))) => return Poll::Ready(Some(m)),
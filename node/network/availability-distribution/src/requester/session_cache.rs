@@ -23,7 +23,7 @@ use polkadot_node_subsystem_util::runtime::RuntimeInfo;
 use polkadot_primitives::v1::{
 	AuthorityDiscoveryId, GroupIndex, Hash, SessionIndex, ValidatorIndex,
 };
-use polkadot_subsystem::SubsystemContext;
+use polkadot_subsystem::{messages::AvailabilityDistributionMessage, overseer, SubsystemContext};
 
 use crate::{
 	error::{Error, NonFatal},
@@ -102,7 +102,8 @@ impl SessionCache {
 		with_info: F,
 	) -> Result<Option<R>, Error>
 	where
-		Context: SubsystemContext,
+		Context: SubsystemContext<Message = AvailabilityDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = AvailabilityDistributionMessage>,
 		F: FnOnce(&SessionInfo) -> R,
 	{
 		let session_index = runtime.get_session_index(ctx.sender(), parent).await?;
@@ -181,7 +182,8 @@ impl SessionCache {
 		session_index: SessionIndex,
 	) -> Result<Option<SessionInfo>, Error>
 	where
-		Context: SubsystemContext,
+		Context: SubsystemContext<Message = AvailabilityDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = AvailabilityDistributionMessage>,
 	{
 		let info = runtime.get_session_info_by_index(ctx.sender(), parent, session_index).await?;
 
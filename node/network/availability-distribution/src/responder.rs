@@ -37,12 +37,13 @@ use crate::{
 const COST_INVALID_REQUEST: Rep = Rep::CostMajor("Received message could not be decoded.");
 
 /// Receiver task to be forked as a separate task to handle PoV requests.
-pub async fn run_pov_receiver<Sender>(
+pub async fn run_pov_receiver<Sender, Outgoing>(
 	mut sender: Sender,
 	mut receiver: IncomingRequestReceiver<v1::PoVFetchingRequest>,
 	metrics: Metrics,
 ) where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<AvailabilityStoreMessage>,
 {
 	loop {
 		match receiver.recv(|| vec![COST_INVALID_REQUEST]).await {
@@ -65,12 +66,13 @@ pub async fn run_pov_receiver<Sender>(
 }
 
 /// Receiver task to be forked as a separate task to handle chunk requests.
-pub async fn run_chunk_receiver<Sender>(
+pub async fn run_chunk_receiver<Sender, Outgoing>(
 	mut sender: Sender,
 	mut receiver: IncomingRequestReceiver<v1::ChunkFetchingRequest>,
 	metrics: Metrics,
 ) where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<AvailabilityStoreMessage>,
 {
 	loop {
 		match receiver.recv(|| vec![COST_INVALID_REQUEST]).await {
@@ -99,12 +101,13 @@ pub async fn run_chunk_receiver<Sender>(
 /// Variant of `answer_pov_request` that does Prometheus metric and logging on errors.
 ///
 /// Any errors of `answer_pov_request` will simply be logged.
-pub async fn answer_pov_request_log<Sender>(
+pub async fn answer_pov_request_log<Sender, Outgoing>(
 	sender: &mut Sender,
 	req: IncomingRequest<v1::PoVFetchingRequest>,
 	metrics: &Metrics,
 ) where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<AvailabilityStoreMessage>,
 {
 	let res = answer_pov_request(sender, req).await;
 	match res {
@@ -123,13 +126,14 @@ pub async fn answer_pov_request_log<Sender>(
 /// Variant of `answer_chunk_request` that does Prometheus metric and logging on errors.
 ///
 /// Any errors of `answer_request` will simply be logged.
-pub async fn answer_chunk_request_log<Sender>(
+pub async fn answer_chunk_request_log<Sender, Outgoing>(
 	sender: &mut Sender,
 	req: IncomingRequest<v1::ChunkFetchingRequest>,
 	metrics: &Metrics,
 ) -> ()
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<AvailabilityStoreMessage>,
 {
 	let res = answer_chunk_request(sender, req).await;
 	match res {
@@ -148,12 +152,13 @@ where
 /// Answer an incoming PoV fetch request by querying the av store.
 ///
 /// Returns: `Ok(true)` if chunk was found and served.
-pub async fn answer_pov_request<Sender>(
+pub async fn answer_pov_request<Sender, Outgoing>(
 	sender: &mut Sender,
 	req: IncomingRequest<v1::PoVFetchingRequest>,
 ) -> Result<bool>
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<AvailabilityStoreMessage>,
 {
 	let _span = jaeger::Span::new(req.payload.candidate_hash, "answer-pov-request");
 
@@ -176,12 +181,13 @@ where
 /// Answer an incoming chunk request by querying the av store.
 ///
 /// Returns: `Ok(true)` if chunk was found and served.
-pub async fn answer_chunk_request<Sender>(
+pub async fn answer_chunk_request<Sender, Outgoing>(
 	sender: &mut Sender,
 	req: IncomingRequest<v1::ChunkFetchingRequest>,
 ) -> Result<bool>
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<AvailabilityStoreMessage>,
 {
 	let span = jaeger::Span::new(req.payload.candidate_hash, "answer-chunk-request");
 
@@ -210,13 +216,14 @@ where
 }
 
 /// Query chunk from the availability store.
-async fn query_chunk<Sender>(
+async fn query_chunk<Sender, Outgoing>(
 	sender: &mut Sender,
 	candidate_hash: CandidateHash,
 	validator_index: ValidatorIndex,
 ) -> NonFatalResult<Option<ErasureChunk>>
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<AvailabilityStoreMessage>,
 {
 	let (tx, rx) = oneshot::channel();
 	sender
@@ -239,12 +246,13 @@ where
 }
 
 /// Query PoV from the availability store.
-async fn query_available_data<Sender>(
+async fn query_available_data<Sender, Outgoing>(
 	sender: &mut Sender,
 	candidate_hash: CandidateHash,
 ) -> NonFatalResult<Option<AvailableData>>
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<AvailabilityStoreMessage>,
 {
 	let (tx, rx) = oneshot::channel();
 	sender
@@ -59,7 +59,7 @@ use polkadot_subsystem::{
 	errors::RecoveryError,
 	jaeger,
 	messages::{AvailabilityRecoveryMessage, AvailabilityStoreMessage, NetworkBridgeMessage},
-	overseer::{self, Subsystem},
+	overseer::{self, AvailabilityRecoveryOutgoingMessages, Subsystem},
 	ActiveLeavesUpdate, FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext,
 	SubsystemError, SubsystemResult, SubsystemSender,
 };
@@ -84,6 +84,8 @@ const N_PARALLEL: usize = 50;
 const LRU_SIZE: usize = 16;
 
 const COST_INVALID_REQUEST: Rep = Rep::CostMajor("Peer sent unparsable request");
+const COST_MISSING_DATA: Rep =
+	Rep::CostMajor("Validator did not serve the data of a candidate it backed");
 
 /// Time after which we consider a request to have failed
 ///
@@ -180,7 +182,7 @@ impl RequestFromBackers {
 	async fn run(
 		&mut self,
 		params: &RecoveryParams,
-		sender: &mut impl SubsystemSender,
+		sender: &mut impl SubsystemSender<AvailabilityRecoveryOutgoingMessages>,
 	) -> Result<AvailableData, RecoveryError> {
 		tracing::trace!(
 			target: LOG_TARGET,
@@ -194,10 +196,9 @@ impl RequestFromBackers {
 				self.shuffled_backers.pop().ok_or_else(|| RecoveryError::Unavailable)?;
 
 			// Request data.
+			let authority = params.validator_authority_keys[validator_index.0 as usize].clone();
 			let (req, response) = OutgoingRequest::new(
-				Recipient::Authority(
-					params.validator_authority_keys[validator_index.0 as usize].clone(),
-				),
+				Recipient::Authority(authority.clone()),
 				req_res::v1::AvailableDataFetchingRequest { candidate_hash: params.candidate_hash },
 			);
 
@@ -236,7 +237,22 @@ impl RequestFromBackers {
 						// it doesn't help to report the peer with req/res.
 					}
 				},
-				Ok(req_res::v1::AvailableDataFetchingResponse::NoSuchData) => {},
+				Ok(req_res::v1::AvailableDataFetchingResponse::NoSuchData) => {
+					tracing::debug!(
+						target: LOG_TARGET,
+						candidate_hash = ?params.candidate_hash,
+						?validator_index,
+						"Backer did not have the full data",
+					);
+
+					// Backers store the full data before they sign their statement.
+					sender
+						.send_message(
+							NetworkBridgeMessage::ReportAuthority(authority, COST_MISSING_DATA)
+								.into(),
+						)
+						.await;
+				},
 				Err(e) => tracing::debug!(
 					target: LOG_TARGET,
 					candidate_hash = ?params.candidate_hash,
@@ -304,7 +320,7 @@ impl RequestChunksFromValidators {
 	async fn launch_parallel_requests(
 		&mut self,
 		params: &RecoveryParams,
-		sender: &mut impl SubsystemSender,
+		sender: &mut impl SubsystemSender<AvailabilityRecoveryOutgoingMessages>,
 	) {
 		let num_requests = self.get_desired_request_count(params.threshold);
 		let mut requests = Vec::with_capacity(num_requests - self.requesting_chunks.len());
@@ -458,7 +474,7 @@ impl RequestChunksFromValidators {
 	async fn run(
 		&mut self,
 		params: &RecoveryParams,
-		sender: &mut impl SubsystemSender,
+		sender: &mut impl SubsystemSender<AvailabilityRecoveryOutgoingMessages>,
 	) -> Result<AvailableData, RecoveryError> {
 		// First query the store for any chunks we've got.
 		{
@@ -605,7 +621,7 @@ fn reconstructed_data_matches_root(
 	branches.root() == *expected_root
 }
 
-impl<S: SubsystemSender> RecoveryTask<S> {
+impl<S: SubsystemSender<AvailabilityRecoveryOutgoingMessages>> RecoveryTask<S> {
 	async fn run(mut self) -> Result<AvailableData, RecoveryError> {
 		// First just see if we have the data available locally.
 		{
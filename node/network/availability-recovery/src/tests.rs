@@ -359,6 +359,7 @@ impl TestState {
 							};
 
 							let done = available_data.as_ref().ok().map_or(false, |x| x.is_some());
+							let missing = matches!(available_data, Ok(None));
 
 							let _ = req.pending_response.send(
 								available_data.map(|r|
@@ -367,6 +368,21 @@ impl TestState {
 							);
 
 							if done { break }
+
+							if missing {
+								assert_matches!(
+									overseer_recv(virtual_overseer).await,
+									AllMessages::NetworkBridge(
+										NetworkBridgeMessage::ReportAuthority(authority, rep)
+									) => {
+										assert_eq!(
+											authority,
+											self.validator_authority_id[validator_index],
+										);
+										assert_eq!(rep, COST_MISSING_DATA);
+									}
+								);
+							}
 						}
 					)
 				}
@@ -19,6 +19,9 @@
 //! In case this node is a validator, gossips its own signed availability bitfield
 //! for a particular relay parent.
 //! Independently of that, gossips on received messages from peers to other interested peers.
+//!
+//! As all bitfields pass through here, it also keeps track of how long candidates take to become
+//! available.
 
 #![deny(unused_crate_dependencies)]
 
@@ -27,17 +30,24 @@ use futures::{channel::oneshot, FutureExt};
 use polkadot_node_network_protocol::{
 	v1 as protocol_v1, OurView, PeerId, UnifiedReputationChange as Rep, View,
 };
+use polkadot_node_primitives::availability::{AvailabilityAttestations, CoreAvailability};
 use polkadot_node_subsystem_util::{
 	self as util,
 	metrics::{self, prometheus},
 	MIN_GOSSIP_PEERS,
 };
-use polkadot_primitives::v1::{Hash, SignedAvailabilityBitfield, SigningContext, ValidatorId};
+use polkadot_primitives::v1::{
+	availability_threshold, CandidateHash, CoreState, Hash, Id as ParaId,
+	SignedAvailabilityBitfield, SigningContext, ValidatorId, ValidatorIndex,
+};
 use polkadot_subsystem::{
 	jaeger, messages::*, overseer, ActiveLeavesUpdate, FromOverseer, OverseerSignal, PerLeafSpan,
 	SpawnedSubsystem, SubsystemContext, SubsystemError, SubsystemResult,
 };
-use std::collections::{HashMap, HashSet};
+use std::{
+	collections::{HashMap, HashSet, VecDeque},
+	time::{Duration, Instant},
+};
 
 #[cfg(test)]
 mod tests;
@@ -52,6 +62,9 @@ const BENEFIT_VALID_MESSAGE_FIRST: Rep =
 	Rep::BenefitMinorFirst("Valid message with new information");
 const BENEFIT_VALID_MESSAGE: Rep = Rep::BenefitMinor("Valid message");
 
+/// The number of relay parents which left our view whose availability attestations are kept.
+const MAX_ATTESTATION_HISTORY: usize = 64;
+
 /// Checked signed availability bitfield that is distributed
 /// to other peers.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -92,6 +105,47 @@ struct ProtocolState {
 
 	/// Additional data particular to a relay parent.
 	per_relay_parent: HashMap<Hash, PerRelayParentData>,
+
+	/// The availability timeline of the candidates pending availability in our view.
+	availability_timelines: HashMap<CandidateHash, AvailabilityTimeline>,
+
+	/// The availability attestations of the relay parents which most recently left our view,
+	/// oldest first.
+	attestation_history: VecDeque<(Hash, AvailabilityAttestations)>,
+}
+
+impl ProtocolState {
+	/// The availability attestations for a relay parent in our view or of the recent history.
+	fn availability_attestations(&self, relay_parent: &Hash) -> Option<AvailabilityAttestations> {
+		match self.per_relay_parent.get(relay_parent) {
+			Some(job_data) => Some(job_data.availability_attestations()),
+			None => self
+				.attestation_history
+				.iter()
+				.rev()
+				.find(|(hash, _)| hash == relay_parent)
+				.map(|(_, attestations)| attestations.clone()),
+		}
+	}
+}
+
+/// When a candidate pending availability was first seen and whether it became available.
+#[derive(Debug)]
+struct AvailabilityTimeline {
+	/// When we first saw the candidate occupying a core, i.e. when the relay chain block it was
+	/// backed in was activated.
+	backed_at: Instant,
+	/// Whether enough validators attested the availability of the candidate.
+	available: bool,
+}
+
+/// A candidate pending availability on a core, as of a particular relay parent.
+#[derive(Debug)]
+struct PendingAvailability {
+	para_id: ParaId,
+	candidate_hash: CandidateHash,
+	/// Validators whose bitfield for the relay parent attests the availability of their chunk.
+	attested: HashSet<ValidatorIndex>,
 }
 
 /// Data for a particular relay parent.
@@ -116,6 +170,12 @@ struct PerRelayParentData {
 	/// to prevent flooding.
 	message_received_from_peer: HashMap<PeerId, HashSet<ValidatorId>>,
 
+	/// The candidates pending availability, indexed by core.
+	availability_cores: Vec<Option<PendingAvailability>>,
+
+	/// Validators whose bitfield for the relay parent we received or signed.
+	bitfield_senders: HashSet<ValidatorIndex>,
+
 	/// The span for this leaf/relay parent.
 	span: PerLeafSpan,
 }
@@ -125,6 +185,7 @@ impl PerRelayParentData {
 	fn new(
 		signing_context: SigningContext,
 		validator_set: Vec<ValidatorId>,
+		availability_cores: Vec<Option<PendingAvailability>>,
 		span: PerLeafSpan,
 	) -> Self {
 		Self {
@@ -134,6 +195,8 @@ impl PerRelayParentData {
 			one_per_validator: Default::default(),
 			message_sent_to_peer: Default::default(),
 			message_received_from_peer: Default::default(),
+			availability_cores,
+			bitfield_senders: Default::default(),
 		}
 	}
 
@@ -152,11 +215,75 @@ impl PerRelayParentData {
 				.map(|v| !v.contains(validator))
 				.unwrap_or(true)
 	}
+
+	/// Account for the chunks attested by a bitfield. Returns the candidates which became
+	/// available with it.
+	fn note_bitfield(
+		&mut self,
+		bitfield: &SignedAvailabilityBitfield,
+	) -> Vec<(ParaId, CandidateHash)> {
+		let threshold = availability_threshold(self.validator_set.len());
+		let validator_index = bitfield.validator_index();
+		self.bitfield_senders.insert(validator_index);
+
+		let mut newly_available = Vec::new();
+		for (core, pending) in self.availability_cores.iter_mut().enumerate() {
+			let pending = match pending {
+				Some(pending) => pending,
+				None => continue,
+			};
+
+			let attested = bitfield.payload().0.get(core).map_or(false, |bit| *bit);
+			if attested &&
+				pending.attested.insert(validator_index) &&
+				pending.attested.len() == threshold
+			{
+				newly_available.push((pending.para_id, pending.candidate_hash));
+			}
+		}
+		newly_available
+	}
+
+	/// The availability attestations of the bitfields for the relay parent.
+	fn availability_attestations(&self) -> AvailabilityAttestations {
+		let n_validators = self.validator_set.len();
+		let threshold = availability_threshold(n_validators);
+		let all_validators = (0..n_validators as u32).map(ValidatorIndex);
+
+		let cores = self
+			.availability_cores
+			.iter()
+			.enumerate()
+			.filter_map(|(core, pending)| {
+				let pending = pending.as_ref()?;
+				Some(CoreAvailability {
+					core_index: core as u32,
+					para_id: pending.para_id,
+					candidate_hash: pending.candidate_hash.0,
+					attested: pending.attested.len() as u32,
+					missing: all_validators
+						.clone()
+						.filter(|v| !pending.attested.contains(v))
+						.collect(),
+					available: pending.attested.len() >= threshold,
+				})
+			})
+			.collect();
+
+		AvailabilityAttestations {
+			n_validators: n_validators as u32,
+			missing_bitfields: all_validators
+				.filter(|v| !self.bitfield_senders.contains(v))
+				.collect(),
+			cores,
+		}
+	}
 }
 
 const LOG_TARGET: &str = "parachain::bitfield-distribution";
 
 /// The bitfield distribution subsystem.
+#[derive(Clone)]
 pub struct BitfieldDistribution {
 	metrics: Metrics,
 }
@@ -204,6 +331,11 @@ impl BitfieldDistribution {
 					// a network message was received
 					handle_network_msg(&mut ctx, &mut state, &self.metrics, event).await;
 				},
+				FromOverseer::Communication {
+					msg: BitfieldDistributionMessage::GetAvailabilityAttestations(relay_parent, tx),
+				} => {
+					let _ = tx.send(state.availability_attestations(&relay_parent));
+				},
 				FromOverseer::Signal(OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
 					activated,
 					..
@@ -220,6 +352,19 @@ impl BitfieldDistribution {
 						// query validator set and signing context per relay_parent once only
 						match query_basics(&mut ctx, relay_parent).await {
 							Ok(Some((validator_set, signing_context))) => {
+								let availability_cores =
+									query_availability_cores(&mut ctx, relay_parent).await;
+								let now = Instant::now();
+								for pending in availability_cores.iter().flatten() {
+									state
+										.availability_timelines
+										.entry(pending.candidate_hash)
+										.or_insert(AvailabilityTimeline {
+											backed_at: now,
+											available: false,
+										});
+								}
+
 								// If our runtime API fails, we don't take down the node,
 								// but we might alter peers' reputations erroneously as a result
 								// of not having the correct bookkeeping. If we have lost a race
@@ -227,7 +372,12 @@ impl BitfieldDistribution {
 								// us anything to do with this relay-parent anyway.
 								let _ = state.per_relay_parent.insert(
 									relay_parent,
-									PerRelayParentData::new(signing_context, validator_set, span),
+									PerRelayParentData::new(
+										signing_context,
+										validator_set,
+										availability_cores,
+										span,
+									),
 								);
 							},
 							Err(e) => {
@@ -253,6 +403,7 @@ impl BitfieldDistribution {
 async fn modify_reputation<Context>(ctx: &mut Context, peer: PeerId, rep: Rep)
 where
 	Context: SubsystemContext<Message = BitfieldDistributionMessage>,
+	Context: overseer::SubsystemContext<Message = BitfieldDistributionMessage>,
 {
 	tracing::trace!(target: LOG_TARGET, ?rep, peer_id = %peer, "reputation change");
 
@@ -270,6 +421,7 @@ async fn handle_bitfield_distribution<Context>(
 	signed_availability: SignedAvailabilityBitfield,
 ) where
 	Context: SubsystemContext<Message = BitfieldDistributionMessage>,
+	Context: overseer::SubsystemContext<Message = BitfieldDistributionMessage>,
 {
 	let _timer = metrics.time_handle_bitfield_distribution();
 
@@ -304,6 +456,9 @@ async fn handle_bitfield_distribution<Context>(
 		return
 	};
 
+	let newly_available = job_data.note_bitfield(&signed_availability);
+	note_available(&mut state.availability_timelines, metrics, newly_available);
+
 	let msg = BitfieldGossipMessage { relay_parent, signed_availability };
 
 	let gossip_peers = &state.gossip_peers;
@@ -325,6 +480,7 @@ async fn relay_message<Context>(
 	message: BitfieldGossipMessage,
 ) where
 	Context: SubsystemContext<Message = BitfieldDistributionMessage>,
+	Context: overseer::SubsystemContext<Message = BitfieldDistributionMessage>,
 {
 	let span = job_data.span.child("relay-msg");
 
@@ -398,6 +554,7 @@ async fn process_incoming_peer_message<Context>(
 	message: protocol_v1::BitfieldDistributionMessage,
 ) where
 	Context: SubsystemContext<Message = BitfieldDistributionMessage>,
+	Context: overseer::SubsystemContext<Message = BitfieldDistributionMessage>,
 {
 	let protocol_v1::BitfieldDistributionMessage::Bitfield(relay_parent, bitfield) = message;
 	tracing::trace!(
@@ -492,6 +649,9 @@ async fn process_incoming_peer_message<Context>(
 	metrics.on_bitfield_received();
 	one_per_validator.insert(validator.clone(), message.clone());
 
+	let newly_available = job_data.note_bitfield(&message.signed_availability);
+	note_available(&mut state.availability_timelines, metrics, newly_available);
+
 	relay_message(ctx, job_data, &state.gossip_peers, &mut state.peer_views, validator, message)
 		.await;
 
@@ -507,11 +667,12 @@ async fn handle_network_msg<Context>(
 	bridge_message: NetworkBridgeEvent<protocol_v1::BitfieldDistributionMessage>,
 ) where
 	Context: SubsystemContext<Message = BitfieldDistributionMessage>,
+	Context: overseer::SubsystemContext<Message = BitfieldDistributionMessage>,
 {
 	let _timer = metrics.time_handle_network_msg();
 
 	match bridge_message {
-		NetworkBridgeEvent::PeerConnected(peerid, role, _) => {
+		NetworkBridgeEvent::PeerConnected(peerid, role, _, _) => {
 			tracing::trace!(target: LOG_TARGET, ?peerid, ?role, "Peer connected");
 			// insert if none already present
 			state.peer_views.entry(peerid).or_default();
@@ -521,7 +682,8 @@ async fn handle_network_msg<Context>(
 			// get rid of superfluous data
 			state.peer_views.remove(&peerid);
 		},
-		NetworkBridgeEvent::NewGossipTopology(peers) => {
+		NetworkBridgeEvent::NewGossipTopology(topology) => {
+			let peers = topology.peers();
 			let newly_added: Vec<PeerId> = peers.difference(&state.gossip_peers).cloned().collect();
 			state.gossip_peers = peers;
 			for peer in newly_added {
@@ -558,8 +720,50 @@ fn handle_our_view_change(state: &mut ProtocolState, view: OurView) {
 		}
 	}
 	for removed in old_view.difference(&state.view) {
-		// cleanup relay parents we are not interested in any more
-		let _ = state.per_relay_parent.remove(&removed);
+		// cleanup relay parents we are not interested in any more, but keep their attestations
+		if let Some(job_data) = state.per_relay_parent.remove(&removed) {
+			if state.attestation_history.len() == MAX_ATTESTATION_HISTORY {
+				state.attestation_history.pop_front();
+			}
+			state
+				.attestation_history
+				.push_back((*removed, job_data.availability_attestations()));
+		}
+	}
+
+	// Forget about candidates no longer pending availability in any relay parent of our view.
+	let pending: HashSet<_> = state
+		.per_relay_parent
+		.values()
+		.flat_map(|job_data| job_data.availability_cores.iter().flatten())
+		.map(|pending| pending.candidate_hash)
+		.collect();
+	state.availability_timelines.retain(|candidate_hash, _| pending.contains(candidate_hash));
+}
+
+/// Record the time it took for candidates to become available, the first time enough validators
+/// attested their availability on any relay parent.
+fn note_available(
+	availability_timelines: &mut HashMap<CandidateHash, AvailabilityTimeline>,
+	metrics: &Metrics,
+	newly_available: Vec<(ParaId, CandidateHash)>,
+) {
+	for (para_id, candidate_hash) in newly_available {
+		let timeline = match availability_timelines.get_mut(&candidate_hash) {
+			Some(timeline) if !timeline.available => timeline,
+			_ => continue,
+		};
+
+		timeline.available = true;
+		let elapsed = timeline.backed_at.elapsed();
+		tracing::trace!(
+			target: LOG_TARGET,
+			?para_id,
+			?candidate_hash,
+			?elapsed,
+			"Candidate became available",
+		);
+		metrics.on_candidate_available(para_id, elapsed);
 	}
 }
 
@@ -572,6 +776,7 @@ async fn handle_peer_view_change<Context>(
 	view: View,
 ) where
 	Context: SubsystemContext<Message = BitfieldDistributionMessage>,
+	Context: overseer::SubsystemContext<Message = BitfieldDistributionMessage>,
 {
 	let added = state
 		.peer_views
@@ -629,6 +834,7 @@ async fn send_tracked_gossip_message<Context>(
 	message: BitfieldGossipMessage,
 ) where
 	Context: SubsystemContext<Message = BitfieldDistributionMessage>,
+	Context: overseer::SubsystemContext<Message = BitfieldDistributionMessage>,
 {
 	let job_data = if let Some(job_data) = state.per_relay_parent.get_mut(&message.relay_parent) {
 		job_data
@@ -677,6 +883,7 @@ async fn query_basics<Context>(
 ) -> SubsystemResult<Option<(Vec<ValidatorId>, SigningContext)>>
 where
 	Context: SubsystemContext<Message = BitfieldDistributionMessage>,
+	Context: overseer::SubsystemContext<Message = BitfieldDistributionMessage>,
 {
 	let (validators_tx, validators_rx) = oneshot::channel();
 	let (session_tx, session_rx) = oneshot::channel();
@@ -705,6 +912,42 @@ where
 	}
 }
 
+/// Query the candidates pending availability for a particular relay parent, indexed by core.
+///
+/// Errors are only logged, as the cores are merely needed for availability metrics.
+async fn query_availability_cores<Context>(
+	ctx: &mut Context,
+	relay_parent: Hash,
+) -> Vec<Option<PendingAvailability>>
+where
+	Context: SubsystemContext<Message = BitfieldDistributionMessage>,
+	Context: overseer::SubsystemContext<Message = BitfieldDistributionMessage>,
+{
+	let cores = match util::request_availability_cores(relay_parent, ctx.sender()).await.await {
+		Ok(Ok(cores)) => cores,
+		Ok(Err(e)) => {
+			tracing::debug!(target: LOG_TARGET, err = ?e, "Failed to fetch availability cores");
+			return Vec::new()
+		},
+		Err(e) => {
+			tracing::debug!(target: LOG_TARGET, err = ?e, "Availability cores request canceled");
+			return Vec::new()
+		},
+	};
+
+	cores
+		.into_iter()
+		.map(|core| match core {
+			CoreState::Occupied(occupied) => Some(PendingAvailability {
+				para_id: occupied.para_id(),
+				candidate_hash: occupied.candidate_hash,
+				attested: HashSet::new(),
+			}),
+			CoreState::Scheduled(_) | CoreState::Free => None,
+		})
+		.collect()
+}
+
 #[derive(Clone)]
 struct MetricsInner {
 	gossipped_own_availability_bitfields: prometheus::Counter<prometheus::U64>,
@@ -712,8 +955,14 @@ struct MetricsInner {
 	active_leaves_update: prometheus::Histogram,
 	handle_bitfield_distribution: prometheus::Histogram,
 	handle_network_msg: prometheus::Histogram,
+	candidate_availability_time: prometheus::HistogramVec,
 }
 
+/// Buckets of the time it takes candidates to become available, in seconds. Candidates are
+/// expected to become available within a few relay chain blocks.
+const AVAILABILITY_TIME_BUCKETS: [f64; 12] =
+	[1.0, 2.0, 4.0, 6.0, 9.0, 12.0, 18.0, 24.0, 36.0, 48.0, 60.0, 120.0];
+
 /// Bitfield Distribution metrics.
 #[derive(Default, Clone)]
 pub struct Metrics(Option<MetricsInner>);
@@ -749,6 +998,15 @@ impl Metrics {
 	fn time_handle_network_msg(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.handle_network_msg.start_timer())
 	}
+
+	fn on_candidate_available(&self, para_id: ParaId, elapsed: Duration) {
+		if let Some(metrics) = &self.0 {
+			metrics
+				.candidate_availability_time
+				.with_label_values(&[&u32::from(para_id).to_string()])
+				.observe(elapsed.as_secs_f64());
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -789,6 +1047,18 @@ impl metrics::Metrics for Metrics {
 				))?,
 				registry,
 			)?,
+			candidate_availability_time: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_candidate_availability_time",
+						"Time from the activation of the block a candidate was backed in until \
+						bitfields of 2/3 of the validators attested its availability, per para.",
+					)
+					.buckets(AVAILABILITY_TIME_BUCKETS.to_vec()),
+					&["para_id"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
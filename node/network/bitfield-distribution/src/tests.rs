@@ -19,7 +19,7 @@ use assert_matches::assert_matches;
 use bitvec::bitvec;
 use futures::executor;
 use maplit::hashmap;
-use polkadot_node_network_protocol::{our_view, view, ObservedRole};
+use polkadot_node_network_protocol::{our_view, peer_set::PeerSet, view, ObservedRole};
 use polkadot_node_subsystem_test_helpers::make_subsystem_context;
 use polkadot_node_subsystem_util::TimeoutExt;
 use polkadot_primitives::v1::{AvailabilityBitfield, Signed, ValidatorIndex};
@@ -57,12 +57,15 @@ fn prewarmed_state(
 					},
 					message_received_from_peer: hashmap!{},
 					message_sent_to_peer: hashmap!{},
+					availability_cores: vec![],
+					bitfield_senders: HashSet::new(),
 					span: PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
 				},
 		},
 		peer_views: peers.iter().cloned().map(|peer| (peer, view!(relay_parent))).collect(),
 		gossip_peers: peers.into_iter().collect(),
 		view: our_view!(relay_parent),
+		availability_timelines: HashMap::new(),
 	}
 }
 
@@ -89,6 +92,8 @@ fn state_with_view(
 					one_per_validator: hashmap! {},
 					message_received_from_peer: hashmap! {},
 					message_sent_to_peer: hashmap! {},
+					availability_cores: vec![],
+					bitfield_senders: HashSet::new(),
 					span: PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
 				},
 			)
@@ -532,7 +537,12 @@ fn changing_view() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerConnected(peer_b.clone(), ObservedRole::Full, None),
+			NetworkBridgeEvent::PeerConnected(
+				peer_b.clone(),
+				ObservedRole::Full,
+				PeerSet::Validation.get_main_version(),
+				None,
+			),
 		));
 
 		// make peer b interested
@@ -720,3 +730,118 @@ fn do_not_send_message_back_to_origin() {
 		);
 	});
 }
+
+#[test]
+fn availability_is_tracked_from_bitfields() {
+	let hash = Hash::random();
+	let signing_context = SigningContext { session_index: 1, parent_hash: hash };
+
+	let keystore: SyncCryptoStorePtr = Arc::new(KeyStore::new());
+	let validators: Vec<ValidatorId> = (0..3)
+		.map(|_| {
+			SyncCryptoStore::sr25519_generate_new(&*keystore, ValidatorId::ID, None)
+				.expect("key created")
+				.into()
+		})
+		.collect();
+
+	let candidate_a = CandidateHash(Hash::repeat_byte(0xA));
+	let candidate_b = CandidateHash(Hash::repeat_byte(0xB));
+	let pending = |para_id: u32, candidate_hash| {
+		Some(PendingAvailability {
+			para_id: para_id.into(),
+			candidate_hash,
+			attested: HashSet::new(),
+		})
+	};
+
+	let mut state = ProtocolState::default();
+	state.view = our_view![hash];
+	state.per_relay_parent.insert(
+		hash,
+		PerRelayParentData::new(
+			signing_context.clone(),
+			validators.clone(),
+			vec![pending(1, candidate_a), None, pending(2, candidate_b)],
+			PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
+		),
+	);
+	for candidate_hash in [candidate_a, candidate_b] {
+		let timeline = AvailabilityTimeline { backed_at: Instant::now(), available: false };
+		state.availability_timelines.insert(candidate_hash, timeline);
+	}
+
+	let sign = |index: u32, bits| {
+		executor::block_on(Signed::<AvailabilityBitfield>::sign(
+			&keystore,
+			AvailabilityBitfield(bits),
+			&signing_context,
+			ValidatorIndex(index),
+			&validators[index as usize],
+		))
+		.ok()
+		.flatten()
+		.expect("should be signed")
+	};
+
+	// Two of three validators need to attest availability.
+	let job_data = state.per_relay_parent.get_mut(&hash).unwrap();
+	let bitfield = sign(0, bitvec![bitvec::order::Lsb0, u8; 1, 0, 1]);
+	assert!(job_data.note_bitfield(&bitfield).is_empty());
+
+	let bitfield = sign(1, bitvec![bitvec::order::Lsb0, u8; 1, 0, 0]);
+	let newly_available = job_data.note_bitfield(&bitfield);
+	assert_eq!(newly_available, vec![(1.into(), candidate_a)]);
+	note_available(&mut state.availability_timelines, &Default::default(), newly_available);
+	assert!(state.availability_timelines[&candidate_a].available);
+	assert!(!state.availability_timelines[&candidate_b].available);
+
+	let attestations = state.per_relay_parent[&hash].availability_attestations();
+	assert_eq!(attestations.n_validators, 3);
+	assert_eq!(attestations.missing_bitfields, vec![ValidatorIndex(2)]);
+	assert_eq!(
+		attestations.cores,
+		vec![
+			CoreAvailability {
+				core_index: 0,
+				para_id: 1.into(),
+				candidate_hash: candidate_a.0,
+				attested: 2,
+				missing: vec![ValidatorIndex(2)],
+				available: true,
+			},
+			CoreAvailability {
+				core_index: 2,
+				para_id: 2.into(),
+				candidate_hash: candidate_b.0,
+				attested: 1,
+				missing: vec![ValidatorIndex(1), ValidatorIndex(2)],
+				available: false,
+			},
+		],
+	);
+
+	// Timelines are pruned along with the relay parents, but the attestations are kept.
+	handle_our_view_change(&mut state, our_view![]);
+	assert!(state.availability_timelines.is_empty());
+	assert!(state.per_relay_parent.is_empty());
+	assert_eq!(state.availability_attestations(&hash), Some(attestations));
+
+	// Until enough other relay parents left our view.
+	for _ in 0..MAX_ATTESTATION_HISTORY {
+		let other = Hash::random();
+		state.view = our_view![other];
+		state.per_relay_parent.insert(
+			other,
+			PerRelayParentData::new(
+				signing_context.clone(),
+				validators.clone(),
+				Vec::new(),
+				PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
+			),
+		);
+		handle_our_view_change(&mut state, our_view![]);
+	}
+	assert_eq!(state.attestation_history.len(), MAX_ATTESTATION_HISTORY);
+	assert_eq!(state.availability_attestations(&hash), None);
+}
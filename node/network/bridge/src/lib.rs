@@ -26,7 +26,7 @@ use sc_network::Event as NetworkEvent;
 use sp_consensus::SyncOracle;
 
 use polkadot_node_network_protocol::{
-	peer_set::PeerSet, v1 as protocol_v1, ObservedRole, OurView, PeerId,
+	peer_set::PeerSet, v1 as protocol_v1, ObservedRole, OurView, PeerId, ProtocolVersion,
 	UnifiedReputationChange as Rep, View,
 };
 use polkadot_node_subsystem_util::metrics::{self, prometheus};
@@ -34,8 +34,12 @@ use polkadot_overseer::gen::{OverseerError, Subsystem};
 use polkadot_primitives::v1::{BlockNumber, Hash};
 use polkadot_subsystem::{
 	errors::{SubsystemError, SubsystemResult},
-	messages::{AllMessages, CollatorProtocolMessage, NetworkBridgeEvent, NetworkBridgeMessage},
-	overseer, ActivatedLeaf, ActiveLeavesUpdate, FromOverseer, OverseerSignal, SpawnedSubsystem,
+	messages::{
+		AllMessages, CollatorProtocolMessage, GossipSupportMessage, GossipTopology,
+		NetworkBridgeEvent, NetworkBridgeMessage,
+	},
+	overseer::{self, NetworkBridgeOutgoingMessages},
+	ActivatedLeaf, ActiveLeavesUpdate, FromOverseer, OverseerSignal, SpawnedSubsystem,
 	SubsystemContext, SubsystemSender,
 };
 
@@ -47,17 +51,22 @@ pub use polkadot_node_network_protocol::peer_set::{peer_sets_info, IsAuthority};
 use std::{
 	collections::{hash_map, HashMap, HashSet},
 	sync::Arc,
+	time::{Duration, Instant},
 };
 
 mod validator_discovery;
 
+/// Outbound bandwidth limits of the peer sets.
+mod rate_limit;
+use rate_limit::{OutboundMessage, Priority, RateLimiter};
+
 /// Actual interfacing to the network based on the `Network` trait.
 ///
 /// Defines the `Network` trait with an implementation for an `Arc<NetworkService>`.
 mod network;
 use network::{send_message, Network};
 
-use crate::network::get_peer_id_by_authority_id;
+use crate::network::{get_peer_id_by_authority_id, resolve_peer_ids};
 
 #[cfg(test)]
 mod tests;
@@ -75,6 +84,24 @@ const EMPTY_VIEW_COST: Rep = Rep::CostMajor("Peer sent us an empty view");
 // network bridge log target
 const LOG_TARGET: &'static str = "parachain::network-bridge";
 
+/// How often notifications queued due to the outbound rate limits are sent, as far as the limits
+/// allow.
+const RATE_LIMIT_FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Buckets of the per peer traffic histograms, from 1 KiB to 256 MiB.
+const PEER_TRAFFIC_BUCKETS: [f64; 10] = [
+	1024.0,
+	4096.0,
+	16384.0,
+	65536.0,
+	262144.0,
+	1048576.0,
+	4194304.0,
+	16777216.0,
+	67108864.0,
+	268435456.0,
+];
+
 /// Metrics for the network bridge.
 #[derive(Clone, Default)]
 pub struct Metrics(Option<MetricsInner>);
@@ -135,6 +162,60 @@ impl Metrics {
 		}
 	}
 
+	fn on_message_received(&self, peer_set: PeerSet, message_type: &'static str, size: usize) {
+		if let Some(metrics) = self.0.as_ref() {
+			let labels = [peer_set.get_protocol_name_static(), message_type];
+			metrics.messages_received.with_label_values(&labels).inc();
+			metrics.message_bytes_received.with_label_values(&labels).inc_by(size as u64);
+		}
+	}
+
+	fn on_message_sent(
+		&self,
+		peer_set: PeerSet,
+		message_type: &'static str,
+		size: usize,
+		to_peers: usize,
+	) {
+		if let Some(metrics) = self.0.as_ref() {
+			let labels = [peer_set.get_protocol_name_static(), message_type];
+			metrics.messages_sent.with_label_values(&labels).inc_by(to_peers as u64);
+			metrics
+				.message_bytes_sent
+				.with_label_values(&labels)
+				.inc_by((size * to_peers) as u64);
+		}
+	}
+
+	fn on_message_dropped(&self, peer_set: PeerSet, message_type: &'static str, to_peers: usize) {
+		self.0.as_ref().map(|metrics| {
+			metrics
+				.messages_dropped
+				.with_label_values(&[peer_set.get_protocol_name_static(), message_type])
+				.inc_by(to_peers as u64)
+		});
+	}
+
+	fn on_peer_traffic(&self, peer_set: PeerSet, traffic: &PeerTraffic) {
+		if let Some(metrics) = self.0.as_ref() {
+			metrics
+				.peer_bytes_sent
+				.with_label_values(&[peer_set.get_protocol_name_static()])
+				.observe(traffic.bytes_sent as f64);
+
+			metrics
+				.peer_bytes_received
+				.with_label_values(&[peer_set.get_protocol_name_static()])
+				.observe(traffic.bytes_received as f64);
+		}
+	}
+
+	fn on_report_cost(&self, reason: &'static str) {
+		self.0.as_ref().map(|metrics| {
+			metrics.reputation_costs.with_label_values(&[reason]).inc()
+		});
+	}
+
 	fn note_desired_peer_count(&self, peer_set: PeerSet, size: usize) {
 		self.0.as_ref().map(|metrics| {
 			metrics
@@ -151,12 +232,23 @@ struct MetricsInner {
 	connected_events: prometheus::CounterVec<prometheus::U64>,
 	disconnected_events: prometheus::CounterVec<prometheus::U64>,
 	desired_peer_count: prometheus::GaugeVec<prometheus::U64>,
+	reputation_costs: prometheus::CounterVec<prometheus::U64>,
 
 	notifications_received: prometheus::CounterVec<prometheus::U64>,
 	notifications_sent: prometheus::CounterVec<prometheus::U64>,
 
 	bytes_received: prometheus::CounterVec<prometheus::U64>,
 	bytes_sent: prometheus::CounterVec<prometheus::U64>,
+
+	messages_received: prometheus::CounterVec<prometheus::U64>,
+	messages_sent: prometheus::CounterVec<prometheus::U64>,
+	messages_dropped: prometheus::CounterVec<prometheus::U64>,
+
+	message_bytes_received: prometheus::CounterVec<prometheus::U64>,
+	message_bytes_sent: prometheus::CounterVec<prometheus::U64>,
+
+	peer_bytes_received: prometheus::HistogramVec,
+	peer_bytes_sent: prometheus::HistogramVec,
 }
 
 impl metrics::Metrics for Metrics {
@@ -204,6 +296,16 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			reputation_costs: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_peer_reputation_costs_total",
+						"The number of times peers were punished by a subsystem, by reason",
+					),
+					&["reason"]
+				)?,
+				registry,
+			)?,
 			notifications_received: prometheus::register(
 				prometheus::CounterVec::new(
 					prometheus::Opts::new(
@@ -244,6 +346,78 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			messages_received: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_messages_received_total",
+						"The number of messages received on a parachain protocol, by message type",
+					),
+					&["protocol", "message_type"]
+				)?,
+				registry,
+			)?,
+			messages_sent: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_messages_sent_total",
+						"The number of messages sent on a parachain protocol, by message type",
+					),
+					&["protocol", "message_type"]
+				)?,
+				registry,
+			)?,
+			messages_dropped: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_messages_dropped_total",
+						"The number of messages dropped as the outbound rate limit queue of a parachain protocol overflowed",
+					),
+					&["protocol", "message_type"]
+				)?,
+				registry,
+			)?,
+			message_bytes_received: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_message_bytes_received_total",
+						"The number of bytes received on a parachain protocol, by message type",
+					),
+					&["protocol", "message_type"]
+				)?,
+				registry,
+			)?,
+			message_bytes_sent: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_message_bytes_sent_total",
+						"The number of bytes sent on a parachain protocol, by message type",
+					),
+					&["protocol", "message_type"]
+				)?,
+				registry,
+			)?,
+			peer_bytes_received: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_peer_bytes_received",
+						"The number of bytes received from a peer on a parachain protocol while connected",
+					)
+					.buckets(PEER_TRAFFIC_BUCKETS.to_vec()),
+					&["protocol"],
+				)?,
+				registry,
+			)?,
+			peer_bytes_sent: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_peer_bytes_sent",
+						"The number of bytes sent to a peer on a parachain protocol while connected",
+					)
+					.buckets(PEER_TRAFFIC_BUCKETS.to_vec()),
+					&["protocol"],
+				)?,
+				registry,
+			)?,
 		};
 
 		Ok(Metrics(Some(metrics)))
@@ -263,6 +437,21 @@ pub enum WireMessage<M> {
 	ViewUpdate(View),
 }
 
+/// Configuration of the network bridge.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+	/// The maximum rate of outbound notifications on the validation peer set, in bytes per second.
+	///
+	/// Once saturated, approval distribution messages and view updates are prioritized and other
+	/// notifications are queued. Unlimited if `None` or zero.
+	pub validation_rate_limit: Option<u64>,
+	/// The maximum rate of outbound notifications on the collation peer set, in bytes per second.
+	///
+	/// Once saturated, declarations, `CollationSeconded` messages and view updates are prioritized
+	/// and advertisements are queued. Unlimited if `None` or zero.
+	pub collation_rate_limit: Option<u64>,
+}
+
 /// The network bridge subsystem.
 pub struct NetworkBridge<N, AD> {
 	/// `Network` trait implementing type.
@@ -270,6 +459,7 @@ pub struct NetworkBridge<N, AD> {
 	authority_discovery_service: AD,
 	sync_oracle: Box<dyn SyncOracle + Send>,
 	metrics: Metrics,
+	config: Config,
 }
 
 impl<N, AD> NetworkBridge<N, AD> {
@@ -282,8 +472,9 @@ impl<N, AD> NetworkBridge<N, AD> {
 		authority_discovery_service: AD,
 		sync_oracle: Box<dyn SyncOracle + Send>,
 		metrics: Metrics,
+		config: Config,
 	) -> Self {
-		NetworkBridge { network_service, authority_discovery_service, sync_oracle, metrics }
+		NetworkBridge { network_service, authority_discovery_service, sync_oracle, metrics, config }
 	}
 }
 
@@ -311,6 +502,19 @@ where
 struct PeerData {
 	/// The Latest view sent by the peer.
 	view: View,
+	/// The protocol version negotiated with the peer.
+	version: ProtocolVersion,
+	/// The traffic exchanged with the peer on the peer set.
+	traffic: PeerTraffic,
+}
+
+/// Traffic exchanged with a peer, since it connected.
+#[derive(Debug, Default)]
+struct PeerTraffic {
+	messages_received: u64,
+	bytes_received: u64,
+	messages_sent: u64,
+	bytes_sent: u64,
 }
 
 #[derive(Debug)]
@@ -336,11 +540,27 @@ impl From<OverseerError> for UnexpectedAbort {
 #[derive(Default, Clone)]
 struct Shared(Arc<Mutex<SharedInner>>);
 
+impl Shared {
+	fn new(rate_limiter: RateLimiter) -> Self {
+		Shared(Arc::new(Mutex::new(SharedInner { rate_limiter, ..Default::default() })))
+	}
+}
+
 #[derive(Default)]
 struct SharedInner {
 	local_view: Option<View>,
 	validation_peers: HashMap<PeerId, PeerData>,
 	collation_peers: HashMap<PeerId, PeerData>,
+	rate_limiter: RateLimiter,
+}
+
+impl SharedInner {
+	fn peers_mut(&mut self, peer_set: PeerSet) -> &mut HashMap<PeerId, PeerData> {
+		match peer_set {
+			PeerSet::Validation => &mut self.validation_peers,
+			PeerSet::Collation => &mut self.collation_peers,
+		}
+	}
 }
 
 enum Mode {
@@ -369,8 +589,14 @@ where
 
 	let mut mode = Mode::Syncing(sync_oracle);
 
+	let mut flush_rate_limited = futures_timer::Delay::new(RATE_LIMIT_FLUSH_INTERVAL).fuse();
+
 	loop {
 		futures::select! {
+			_ = flush_rate_limited => {
+				flush_rate_limited_messages(&mut network_service, &shared, &metrics);
+				flush_rate_limited = futures_timer::Delay::new(RATE_LIMIT_FLUSH_INTERVAL).fuse();
+			}
 			msg = ctx.recv().fuse() => match msg {
 				Ok(FromOverseer::Signal(OverseerSignal::ActiveLeaves(active_leaves))) => {
 					let ActiveLeavesUpdate { activated, deactivated } = active_leaves;
@@ -437,9 +663,35 @@ where
 								?rep,
 								action = "ReportPeer"
 							);
+							metrics.on_report_cost(rep.description());
 						}
 						network_service.report_peer(peer, rep);
 					}
+					NetworkBridgeMessage::ReportAuthority(authority, rep) => {
+						let ads = &mut authority_discovery_service;
+						match get_peer_id_by_authority_id(ads, authority.clone()).await {
+							Some(peer) => {
+								if !rep.is_benefit() {
+									tracing::debug!(
+										target: LOG_TARGET,
+										?authority,
+										?peer,
+										?rep,
+										action = "ReportAuthority"
+									);
+									metrics.on_report_cost(rep.description());
+								}
+								network_service.report_peer(peer, rep);
+							}
+							None => tracing::debug!(
+								target: LOG_TARGET,
+								?authority,
+								?rep,
+								action = "ReportAuthority",
+								"Discovering authority failed",
+							),
+						}
+					}
 					NetworkBridgeMessage::DisconnectPeer(peer, peer_set) => {
 						tracing::trace!(
 							target: LOG_TARGET,
@@ -456,11 +708,11 @@ where
 							num_messages = 1,
 						);
 
-						send_message(
+						send_message_by_version(
 							&mut network_service,
+							&shared,
 							peers,
-							PeerSet::Validation,
-							WireMessage::ProtocolMessage(msg),
+							msg,
 							&metrics,
 						);
 					}
@@ -472,11 +724,11 @@ where
 						);
 
 						for (peers, msg) in msgs {
-							send_message(
+							send_message_by_version(
 								&mut network_service,
+								&shared,
 								peers,
-								PeerSet::Validation,
-								WireMessage::ProtocolMessage(msg),
+								msg,
 								&metrics,
 							);
 						}
@@ -488,11 +740,11 @@ where
 							num_messages = 1,
 						);
 
-						send_message(
+						send_message_by_version(
 							&mut network_service,
+							&shared,
 							peers,
-							PeerSet::Collation,
-							WireMessage::ProtocolMessage(msg),
+							msg,
 							&metrics,
 						);
 					}
@@ -504,11 +756,11 @@ where
 						);
 
 						for (peers, msg) in msgs {
-							send_message(
+							send_message_by_version(
 								&mut network_service,
+								&shared,
 								peers,
-								PeerSet::Collation,
-								WireMessage::ProtocolMessage(msg),
+								msg,
 								&metrics,
 							);
 						}
@@ -574,30 +826,25 @@ where
 						).await;
 					}
 					NetworkBridgeMessage::NewGossipTopology {
-						our_neighbors,
+						row_neighbors,
+						column_neighbors,
 					} => {
 						tracing::debug!(
 							target: LOG_TARGET,
 							action = "NewGossipTopology",
-							neighbors = our_neighbors.len(),
+							row_neighbors = row_neighbors.len(),
+							column_neighbors = column_neighbors.len(),
 							"Gossip topology has changed",
 						);
 
 						let ads = &mut authority_discovery_service;
-						let mut gossip_peers = HashSet::with_capacity(our_neighbors.len());
-						for authority in our_neighbors {
-							let addr = get_peer_id_by_authority_id(
-								ads,
-								authority.clone(),
-							).await;
-
-							if let Some(peer_id) = addr {
-								gossip_peers.insert(peer_id);
-							}
-						}
+						let topology = GossipTopology {
+							row: resolve_peer_ids(ads, row_neighbors).await,
+							column: resolve_peer_ids(ads, column_neighbors).await,
+						};
 
 						dispatch_validation_event_to_all_unbounded(
-							NetworkBridgeEvent::NewGossipTopology(gossip_peers),
+							NetworkBridgeEvent::NewGossipTopology(topology),
 							ctx.sender(),
 						);
 					}
@@ -609,7 +856,7 @@ where
 }
 
 async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
-	mut sender: impl SubsystemSender,
+	mut sender: impl SubsystemSender<NetworkBridgeOutgoingMessages>,
 	mut network_service: impl Network,
 	network_stream: BoxStream<'static, NetworkEvent>,
 	mut authority_discovery_service: AD,
@@ -617,25 +864,67 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 	shared: Shared,
 ) -> Result<(), UnexpectedAbort> {
 	let mut network_stream = network_stream.fuse();
+	// Authorities connected for block sync, gossip support tells those apart which don't open a
+	// notification stream on the validation peer set.
+	let mut sync_connected_authorities = HashSet::new();
 	loop {
 		match network_stream.next().await {
 			None => return Err(UnexpectedAbort::EventStreamConcluded),
-			Some(NetworkEvent::Dht(_)) |
-			Some(NetworkEvent::SyncConnected { .. }) |
-			Some(NetworkEvent::SyncDisconnected { .. }) => {},
+			Some(NetworkEvent::Dht(_)) => {},
+			Some(NetworkEvent::SyncConnected { remote: peer }) => {
+				let authority_ids =
+					match authority_discovery_service.get_authority_ids_by_peer_id(peer).await {
+						None => continue,
+						Some(authority_ids) => authority_ids,
+					};
+
+				sync_connected_authorities.insert(peer);
+				let message = GossipSupportMessage::PeerSyncConnected { peer, authority_ids };
+				sender.send_message(message.into()).await;
+			},
+			Some(NetworkEvent::SyncDisconnected { remote: peer }) => {
+				if sync_connected_authorities.remove(&peer) {
+					let message = GossipSupportMessage::PeerSyncDisconnected(peer);
+					sender.send_message(message.into()).await;
+				}
+			},
 			Some(NetworkEvent::NotificationStreamOpened {
-				remote: peer, protocol, role, ..
+				remote: peer,
+				protocol,
+				negotiated_fallback,
+				role,
 			}) => {
 				let role = ObservedRole::from(role);
-				let peer_set = match PeerSet::try_from_protocol_name(&protocol) {
+				let (peer_set, version) = match PeerSet::try_get_protocol(&protocol) {
 					None => continue,
-					Some(peer_set) => peer_set,
+					Some(p) => p,
+				};
+
+				// A fallback is negotiated with peers which don't support the main version yet.
+				let version = match negotiated_fallback {
+					None => version,
+					Some(fallback) => match PeerSet::try_get_protocol(&fallback) {
+						Some((fallback_peer_set, version)) if fallback_peer_set == peer_set =>
+							version,
+						_ => {
+							tracing::debug!(
+								target: LOG_TARGET,
+								?fallback,
+								?protocol,
+								?peer,
+								"Unknown fallback protocol negotiated",
+							);
+
+							continue
+						},
+					},
 				};
 
 				tracing::debug!(
 					target: LOG_TARGET,
 					action = "PeerConnected",
 					peer_set = ?peer_set,
+					version,
 					peer = ?peer,
 					role = ?role
 				);
@@ -650,7 +939,11 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 					match peer_map.entry(peer.clone()) {
 						hash_map::Entry::Occupied(_) => continue,
 						hash_map::Entry::Vacant(vacant) => {
-							vacant.insert(PeerData { view: View::default() });
+							vacant.insert(PeerData {
+								view: View::default(),
+								version,
+								traffic: PeerTraffic::default(),
+							});
 						},
 					}
 
@@ -670,6 +963,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 								NetworkBridgeEvent::PeerConnected(
 									peer.clone(),
 									role,
+									version,
 									maybe_authority,
 								),
 								NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
@@ -678,8 +972,9 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 						)
 						.await;
 
-						send_message(
+						send_wire_message(
 							&mut network_service,
+							&shared,
 							vec![peer],
 							PeerSet::Validation,
 							WireMessage::<protocol_v1::ValidationProtocol>::ViewUpdate(local_view),
@@ -692,6 +987,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 								NetworkBridgeEvent::PeerConnected(
 									peer.clone(),
 									role,
+									version,
 									maybe_authority,
 								),
 								NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
@@ -700,8 +996,9 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 						)
 						.await;
 
-						send_message(
+						send_wire_message(
 							&mut network_service,
+							&shared,
 							vec![peer],
 							PeerSet::Collation,
 							WireMessage::<protocol_v1::CollationProtocol>::ViewUpdate(local_view),
@@ -723,14 +1020,11 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 					peer = ?peer
 				);
 
-				let was_connected = {
+				let peer_data = {
 					let mut shared = shared.0.lock();
-					let peer_map = match peer_set {
-						PeerSet::Validation => &mut shared.validation_peers,
-						PeerSet::Collation => &mut shared.collation_peers,
-					};
+					let peer_map = shared.peers_mut(peer_set);
 
-					let w = peer_map.remove(&peer).is_some();
+					let w = peer_map.remove(&peer);
 
 					metrics.on_peer_disconnected(peer_set);
 					metrics.note_peer_count(peer_set, peer_map.len());
@@ -738,7 +1032,16 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 					w
 				};
 
-				if was_connected {
+				if let Some(peer_data) = peer_data {
+					tracing::debug!(
+						target: LOG_TARGET,
+						peer_set = ?peer_set,
+						peer = ?peer,
+						traffic = ?peer_data.traffic,
+						"Traffic exchanged with disconnected peer",
+					);
+					metrics.on_peer_traffic(peer_set, &peer_data.traffic);
+
 					match peer_set {
 						PeerSet::Validation =>
 							dispatch_validation_event_to_all(
@@ -861,10 +1164,15 @@ where
 	Context: SubsystemContext<Message = NetworkBridgeMessage>
 		+ overseer::SubsystemContext<Message = NetworkBridgeMessage>,
 {
-	let shared = Shared::default();
+	let NetworkBridge {
+		network_service,
+		authority_discovery_service,
+		metrics,
+		sync_oracle,
+		config,
+	} = bridge;
 
-	let NetworkBridge { network_service, authority_discovery_service, metrics, sync_oracle } =
-		bridge;
+	let shared = Shared::new(RateLimiter::new(&config));
 
 	let (remote, network_event_handler) = handle_network_messages(
 		ctx.sender().clone(),
@@ -926,7 +1234,8 @@ fn construct_view(
 
 fn update_our_view(
 	net: &mut impl Network,
-	ctx: &mut impl SubsystemContext<Message = NetworkBridgeMessage, AllMessages = AllMessages>,
+	ctx: &mut (impl SubsystemContext<Message = NetworkBridgeMessage>
+	          + overseer::SubsystemContext<Message = NetworkBridgeMessage>),
 	live_heads: &[ActivatedLeaf],
 	shared: &Shared,
 	finalized_number: BlockNumber,
@@ -961,12 +1270,19 @@ fn update_our_view(
 
 	send_validation_message(
 		net,
+		shared,
 		validation_peers,
 		WireMessage::ViewUpdate(new_view.clone()),
 		metrics,
 	);
 
-	send_collation_message(net, collation_peers, WireMessage::ViewUpdate(new_view), metrics);
+	send_collation_message(
+		net,
+		shared,
+		collation_peers,
+		WireMessage::ViewUpdate(new_view),
+		metrics,
+	);
 
 	let our_view = OurView::new(
 		live_heads.iter().take(MAX_VIEW_HEADS).cloned().map(|a| (a.hash, a.span)),
@@ -986,7 +1302,7 @@ fn update_our_view(
 
 // Handle messages on a specific peer-set. The peer is expected to be connected on that
 // peer-set.
-fn handle_peer_messages<M>(
+fn handle_peer_messages<M: ClassifiedMessage>(
 	peer: PeerId,
 	peer_set: PeerSet,
 	peers: &mut HashMap<PeerId, PeerData>,
@@ -1003,6 +1319,9 @@ fn handle_peer_messages<M>(
 
 	for (message, size_bytes) in messages {
 		metrics.on_notification_received(peer_set, size_bytes);
+		metrics.on_message_received(peer_set, message.message_type(), size_bytes);
+		peer_data.traffic.messages_received += 1;
+		peer_data.traffic.bytes_received += size_bytes as u64;
 
 		outgoing_messages.push(match message {
 			WireMessage::ViewUpdate(new_view) => {
@@ -1030,41 +1349,271 @@ fn handle_peer_messages<M>(
 	(outgoing_messages, reports)
 }
 
+/// Messages classified for traffic accounting and outbound rate limiting.
+trait ClassifiedMessage {
+	/// The type of the message, as used in metrics.
+	fn message_type(&self) -> &'static str;
+
+	/// The priority of the message once the outbound bandwidth of its peer set is saturated.
+	fn priority(&self) -> Priority;
+}
+
+impl<M: ClassifiedMessage> ClassifiedMessage for WireMessage<M> {
+	fn message_type(&self) -> &'static str {
+		match self {
+			WireMessage::ProtocolMessage(message) => message.message_type(),
+			WireMessage::ViewUpdate(_) => "view_update",
+		}
+	}
+
+	fn priority(&self) -> Priority {
+		match self {
+			WireMessage::ProtocolMessage(message) => message.priority(),
+			// View updates are small and other peers rely on them.
+			WireMessage::ViewUpdate(_) => Priority::High,
+		}
+	}
+}
+
+impl ClassifiedMessage for protocol_v1::ValidationProtocol {
+	fn message_type(&self) -> &'static str {
+		use protocol_v1::{ApprovalDistributionMessage, ValidationProtocol};
+
+		match self {
+			ValidationProtocol::BitfieldDistribution(_) => "bitfield",
+			ValidationProtocol::StatementDistribution(_) => "statement",
+			ValidationProtocol::ApprovalDistribution(ApprovalDistributionMessage::Approvals(_)) =>
+				"approval",
+			ValidationProtocol::ApprovalDistribution(_) => "assignment",
+		}
+	}
+
+	fn priority(&self) -> Priority {
+		match self {
+			// Approval checking is on the critical path of finality.
+			protocol_v1::ValidationProtocol::ApprovalDistribution(_) => Priority::High,
+			_ => Priority::Normal,
+		}
+	}
+}
+
+impl ClassifiedMessage for protocol_v1::CollationProtocol {
+	fn message_type(&self) -> &'static str {
+		use protocol_v1::{CollationProtocol, CollatorProtocolMessage};
+
+		match self {
+			CollationProtocol::CollatorProtocol(CollatorProtocolMessage::Declare(..)) => "declare",
+			CollationProtocol::CollatorProtocol(CollatorProtocolMessage::CollationSeconded(..)) =>
+				"collation_seconded",
+			CollationProtocol::CollatorProtocol(_) => "advertisement",
+		}
+	}
+
+	fn priority(&self) -> Priority {
+		use protocol_v1::{CollationProtocol, CollatorProtocolMessage};
+
+		match self {
+			// Validators drop advertisements of undeclared collators and collators only learn
+			// about their collations getting seconded this way.
+			CollationProtocol::CollatorProtocol(CollatorProtocolMessage::Declare(..)) |
+			CollationProtocol::CollatorProtocol(CollatorProtocolMessage::CollationSeconded(..)) =>
+				Priority::High,
+			CollationProtocol::CollatorProtocol(_) => Priority::Normal,
+		}
+	}
+}
+
+/// Wire messages which might not be understood by peers speaking an older protocol version.
+trait VersionedMessage: ClassifiedMessage + Encode + Clone {
+	/// The peer set the message is sent on.
+	const PEER_SET: PeerSet;
+
+	/// The minimum protocol version a peer needs to speak to decode the message.
+	fn required_version(&self) -> ProtocolVersion;
+
+	/// An equivalent message for peers speaking an older `version`, if there is any.
+	fn downgrade(&self, version: ProtocolVersion) -> Option<Self>;
+}
+
+impl VersionedMessage for protocol_v1::ValidationProtocol {
+	const PEER_SET: PeerSet = PeerSet::Validation;
+
+	fn required_version(&self) -> ProtocolVersion {
+		protocol_v1::ValidationProtocol::required_version(self)
+	}
+
+	fn downgrade(&self, version: ProtocolVersion) -> Option<Self> {
+		protocol_v1::ValidationProtocol::downgrade(self, version)
+	}
+}
+
+impl VersionedMessage for protocol_v1::CollationProtocol {
+	const PEER_SET: PeerSet = PeerSet::Collation;
+
+	fn required_version(&self) -> ProtocolVersion {
+		protocol_v1::CollationProtocol::required_version(self)
+	}
+
+	fn downgrade(&self, version: ProtocolVersion) -> Option<Self> {
+		protocol_v1::CollationProtocol::downgrade(self, version)
+	}
+}
+
+// Send `message` to all peers able to decode it. Peers which negotiated an older protocol
+// version get an equivalent message, if there is any, and are skipped otherwise.
+fn send_message_by_version<M: VersionedMessage>(
+	net: &mut impl Network,
+	shared: &Shared,
+	peers: Vec<PeerId>,
+	message: M,
+	metrics: &Metrics,
+) {
+	let required_version = message.required_version();
+	if required_version <= 1 {
+		let message = WireMessage::ProtocolMessage(message);
+		return send_wire_message(net, shared, peers, M::PEER_SET, message, metrics)
+	}
+
+	let mut supported = Vec::with_capacity(peers.len());
+	let mut outdated: HashMap<ProtocolVersion, Vec<PeerId>> = HashMap::new();
+	{
+		let shared = shared.0.lock();
+		let peer_map = match M::PEER_SET {
+			PeerSet::Validation => &shared.validation_peers,
+			PeerSet::Collation => &shared.collation_peers,
+		};
+		for peer in peers {
+			match peer_map.get(&peer).map(|data| data.version) {
+				Some(version) if version >= required_version => supported.push(peer),
+				Some(version) => outdated.entry(version).or_default().push(peer),
+				None => {},
+			}
+		}
+	}
+
+	for (version, peers) in outdated {
+		if let Some(downgraded) = message.downgrade(version) {
+			let downgraded = WireMessage::ProtocolMessage(downgraded);
+			send_wire_message(net, shared, peers, M::PEER_SET, downgraded, metrics);
+		}
+	}
+	let message = WireMessage::ProtocolMessage(message);
+	send_wire_message(net, shared, supported, M::PEER_SET, message, metrics);
+}
+
+// Send `message` to `peers`, or queue it if the outbound rate limit of the peer set is
+// saturated, and account for the traffic per peer and per message type.
+fn send_wire_message<M: ClassifiedMessage + Encode>(
+	net: &mut impl Network,
+	shared: &Shared,
+	peers: Vec<PeerId>,
+	peer_set: PeerSet,
+	message: WireMessage<M>,
+	metrics: &Metrics,
+) {
+	if peers.is_empty() {
+		return
+	}
+
+	let priority = message.priority();
+	let message =
+		OutboundMessage { peers, message_type: message.message_type(), encoded: message.encode() };
+
+	let submitted = {
+		let mut shared = shared.0.lock();
+		shared.rate_limiter.submit(peer_set, priority, message, Instant::now())
+	};
+
+	if let Some(dropped) = submitted.dropped {
+		tracing::debug!(
+			target: LOG_TARGET,
+			?peer_set,
+			message_type = dropped.message_type,
+			num_peers = dropped.peers.len(),
+			"Outbound rate limit queue full, dropping oldest message",
+		);
+		metrics.on_message_dropped(peer_set, dropped.message_type, dropped.peers.len());
+	}
+
+	send_outbound_messages(net, shared, peer_set, submitted.send, metrics);
+}
+
+// Send the notifications queued due to the outbound rate limits, as far as the limits allow.
+fn flush_rate_limited_messages(net: &mut impl Network, shared: &Shared, metrics: &Metrics) {
+	for peer_set in [PeerSet::Validation, PeerSet::Collation] {
+		let messages = shared.0.lock().rate_limiter.flush(peer_set, Instant::now());
+		send_outbound_messages(net, shared, peer_set, messages, metrics);
+	}
+}
+
+// Send notifications released by the rate limiter and account for the traffic.
+fn send_outbound_messages(
+	net: &mut impl Network,
+	shared: &Shared,
+	peer_set: PeerSet,
+	messages: Vec<OutboundMessage>,
+	metrics: &Metrics,
+) {
+	if messages.is_empty() {
+		return
+	}
+
+	{
+		let mut shared = shared.0.lock();
+		let peer_map = shared.peers_mut(peer_set);
+		for message in &messages {
+			for peer in &message.peers {
+				if let Some(peer_data) = peer_map.get_mut(peer) {
+					peer_data.traffic.messages_sent += 1;
+					peer_data.traffic.bytes_sent += message.encoded.len() as u64;
+				}
+			}
+		}
+	}
+
+	for OutboundMessage { peers, message_type, encoded } in messages {
+		metrics.on_message_sent(peer_set, message_type, encoded.len(), peers.len());
+		send_message(net, peers, peer_set, encoded, metrics);
+	}
+}
+
 fn send_validation_message(
 	net: &mut impl Network,
+	shared: &Shared,
 	peers: Vec<PeerId>,
 	message: WireMessage<protocol_v1::ValidationProtocol>,
 	metrics: &Metrics,
 ) {
-	send_message(net, peers, PeerSet::Validation, message, metrics);
+	send_wire_message(net, shared, peers, PeerSet::Validation, message, metrics);
 }
 
 fn send_collation_message(
 	net: &mut impl Network,
+	shared: &Shared,
 	peers: Vec<PeerId>,
 	message: WireMessage<protocol_v1::CollationProtocol>,
 	metrics: &Metrics,
 ) {
-	send_message(net, peers, PeerSet::Collation, message, metrics)
+	send_wire_message(net, shared, peers, PeerSet::Collation, message, metrics)
 }
 
 async fn dispatch_validation_event_to_all(
 	event: NetworkBridgeEvent<protocol_v1::ValidationProtocol>,
-	ctx: &mut impl SubsystemSender,
+	ctx: &mut impl SubsystemSender<NetworkBridgeOutgoingMessages>,
 ) {
 	dispatch_validation_events_to_all(std::iter::once(event), ctx).await
 }
 
 async fn dispatch_collation_event_to_all(
 	event: NetworkBridgeEvent<protocol_v1::CollationProtocol>,
-	ctx: &mut impl SubsystemSender,
+	ctx: &mut impl SubsystemSender<NetworkBridgeOutgoingMessages>,
 ) {
 	dispatch_collation_events_to_all(std::iter::once(event), ctx).await
 }
 
 fn dispatch_validation_event_to_all_unbounded(
 	event: NetworkBridgeEvent<protocol_v1::ValidationProtocol>,
-	ctx: &mut impl SubsystemSender,
+	ctx: &mut impl SubsystemSender<NetworkBridgeOutgoingMessages>,
 ) {
 	for msg in AllMessages::dispatch_iter(event) {
 		ctx.send_unbounded_message(msg);
@@ -1073,30 +1622,36 @@ fn dispatch_validation_event_to_all_unbounded(
 
 fn dispatch_collation_event_to_all_unbounded(
 	event: NetworkBridgeEvent<protocol_v1::CollationProtocol>,
-	ctx: &mut impl SubsystemSender,
+	ctx: &mut impl SubsystemSender<NetworkBridgeOutgoingMessages>,
 ) {
 	if let Some(msg) = event.focus().ok().map(CollatorProtocolMessage::NetworkBridgeUpdateV1) {
 		ctx.send_unbounded_message(msg.into());
 	}
 }
 
-async fn dispatch_validation_events_to_all<I>(events: I, ctx: &mut impl SubsystemSender)
-where
+async fn dispatch_validation_events_to_all<I>(
+	events: I,
+	ctx: &mut impl SubsystemSender<NetworkBridgeOutgoingMessages>,
+) where
 	I: IntoIterator<Item = NetworkBridgeEvent<protocol_v1::ValidationProtocol>>,
 	I::IntoIter: Send,
 {
 	ctx.send_messages(events.into_iter().flat_map(AllMessages::dispatch_iter)).await
 }
 
-async fn dispatch_collation_events_to_all<I>(events: I, ctx: &mut impl SubsystemSender)
-where
+async fn dispatch_collation_events_to_all<I>(
+	events: I,
+	ctx: &mut impl SubsystemSender<NetworkBridgeOutgoingMessages>,
+) where
 	I: IntoIterator<Item = NetworkBridgeEvent<protocol_v1::CollationProtocol>>,
 	I::IntoIter: Send,
 {
 	let messages_for = |event: NetworkBridgeEvent<protocol_v1::CollationProtocol>| {
-		event.focus().ok().map(|m| {
-			AllMessages::CollatorProtocol(CollatorProtocolMessage::NetworkBridgeUpdateV1(m))
-		})
+		event
+			.focus()
+			.ok()
+			.map(CollatorProtocolMessage::NetworkBridgeUpdateV1)
+			.map(From::from)
 	};
 
 	ctx.send_messages(events.into_iter().flat_map(messages_for)).await
@@ -19,8 +19,6 @@ use std::{borrow::Cow, collections::HashSet, sync::Arc};
 use async_trait::async_trait;
 use futures::{prelude::*, stream::BoxStream};
 
-use parity_scale_codec::Encode;
-
 use sc_network::{
 	config::parse_addr, multiaddr::Multiaddr, Event as NetworkEvent, IfDisconnected,
 	NetworkService, OutboundFailure, RequestFailure,
@@ -37,25 +35,20 @@ use crate::validator_discovery::AuthorityDiscovery;
 
 use super::LOG_TARGET;
 
-/// Send a message to the network.
+/// Send an encoded message to the network.
 ///
 /// This function is only used internally by the network-bridge, which is responsible to only send
 /// messages that are compatible with the passed peer set, as that is currently not enforced by
-/// this function. These are messages of type `WireMessage` parameterized on the matching type.
-pub(crate) fn send_message<M>(
+/// this function. These are encoded messages of type `WireMessage` parameterized on the matching
+/// type.
+pub(crate) fn send_message(
 	net: &mut impl Network,
 	mut peers: Vec<PeerId>,
 	peer_set: PeerSet,
-	message: M,
+	message: Vec<u8>,
 	metrics: &super::Metrics,
-) where
-	M: Encode + Clone,
-{
-	let message = {
-		let encoded = message.encode();
-		metrics.on_notification_sent(peer_set, encoded.len(), peers.len());
-		encoded
-	};
+) {
+	metrics.on_notification_sent(peer_set, message.len(), peers.len());
 
 	// optimization: avoid cloning the message for the last peer in the
 	// list. The message payload can be quite large. If the underlying
@@ -216,3 +209,17 @@ pub async fn get_peer_id_by_authority_id<AD: AuthorityDiscovery>(
 		.flat_map(|list| list.into_iter())
 		.find_map(|addr| parse_addr(addr).ok().map(|(p, _)| p))
 }
+
+/// Resolve the peer ids of `authorities`, skipping those we don't know an address of yet.
+pub async fn resolve_peer_ids<AD: AuthorityDiscovery>(
+	authority_discovery: &mut AD,
+	authorities: HashSet<AuthorityDiscoveryId>,
+) -> HashSet<PeerId> {
+	let mut peer_ids = HashSet::with_capacity(authorities.len());
+	for authority in authorities {
+		if let Some(peer_id) = get_peer_id_by_authority_id(authority_discovery, authority).await {
+			peer_ids.insert(peer_id);
+		}
+	}
+	peer_ids
+}
@@ -0,0 +1,310 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Outbound bandwidth limits of the peer sets.
+//!
+//! Every limited peer set has a token bucket, which is refilled with the configured rate of bytes
+//! per second and holds at most one second worth of bytes. Notifications of normal priority are
+//! only sent as long as the bucket holds more than the share reserved for high priority traffic.
+//! High priority notifications are always sent, but still drain the bucket, so they take
+//! precedence over normal traffic once the peer set is saturated.
+//!
+//! Notifications of normal priority which can't be sent right away are queued and sent in order
+//! once the bucket is refilled. Only if the queue overflows, the oldest notification is dropped.
+
+use std::{collections::VecDeque, time::Instant};
+
+use polkadot_node_network_protocol::{
+	peer_set::{PeerSet, PerPeerSet},
+	PeerId,
+};
+
+use crate::Config;
+
+/// The share of the bucket capacity reserved for high priority notifications.
+const HIGH_PRIORITY_RESERVE: f64 = 0.25;
+
+/// The maximum number of notifications queued per peer set while it is saturated.
+const MAX_QUEUED_MESSAGES: usize = 1024;
+
+/// The priority of an outbound notification once the bandwidth of its peer set is saturated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Priority {
+	/// Sent whenever the share of the bandwidth not reserved for high priority traffic allows it.
+	Normal,
+	/// Always sent.
+	High,
+}
+
+/// An encoded notification to be sent to some peers.
+pub(crate) struct OutboundMessage {
+	pub(crate) peers: Vec<PeerId>,
+	/// The type of the message, as used in metrics.
+	pub(crate) message_type: &'static str,
+	pub(crate) encoded: Vec<u8>,
+}
+
+impl OutboundMessage {
+	/// The number of bytes sent in total.
+	fn size(&self) -> usize {
+		self.encoded.len() * self.peers.len()
+	}
+}
+
+/// The notifications released by [`RateLimiter::submit`].
+pub(crate) struct Submitted {
+	/// The notifications to send now, in order.
+	pub(crate) send: Vec<OutboundMessage>,
+	/// The notification dropped as the queue of the peer set overflowed, if any.
+	pub(crate) dropped: Option<OutboundMessage>,
+}
+
+struct TokenBucket {
+	/// Bytes per second.
+	rate: f64,
+	/// Tokens, i.e. bytes, currently available. Negative if high priority traffic or large
+	/// messages exceeded the limit.
+	tokens: f64,
+	last_refill: Instant,
+}
+
+impl TokenBucket {
+	fn new(rate: u64, now: Instant) -> Self {
+		let rate = rate as f64;
+		TokenBucket { rate, tokens: rate, last_refill: now }
+	}
+
+	fn refill(&mut self, now: Instant) {
+		let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+		self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+		self.last_refill = now;
+	}
+
+	fn try_consume(&mut self, priority: Priority, bytes: usize, now: Instant) -> bool {
+		self.refill(now);
+
+		if priority == Priority::Normal && self.tokens <= self.rate * HIGH_PRIORITY_RESERVE {
+			return false
+		}
+
+		// Bound the debt, so normal traffic is resumed at most two seconds after high priority
+		// traffic calmed down.
+		self.tokens = (self.tokens - bytes as f64).max(-self.rate);
+		true
+	}
+}
+
+/// Rate limiter for the outbound notifications of all peer sets.
+pub(crate) struct RateLimiter {
+	buckets: PerPeerSet<Option<TokenBucket>>,
+	queues: PerPeerSet<VecDeque<OutboundMessage>>,
+}
+
+impl RateLimiter {
+	/// Create a rate limiter with the limits of the given configuration.
+	///
+	/// A zero limit is ignored, its bucket would never be refilled.
+	pub(crate) fn new(config: &Config) -> Self {
+		let now = Instant::now();
+		let bucket = |limit: Option<u64>| {
+			limit.filter(|rate| *rate > 0).map(|rate| TokenBucket::new(rate, now))
+		};
+		let mut buckets = PerPeerSet::default();
+		buckets[PeerSet::Validation] = bucket(config.validation_rate_limit);
+		buckets[PeerSet::Collation] = bucket(config.collation_rate_limit);
+
+		RateLimiter { buckets, queues: PerPeerSet::default() }
+	}
+
+	/// Submit `message` to be sent on `peer_set`.
+	///
+	/// Queued notifications which fit into the limit again are released first. Notifications of
+	/// normal priority are queued behind them if the peer set is saturated, so they are always
+	/// sent in order.
+	pub(crate) fn submit(
+		&mut self,
+		peer_set: PeerSet,
+		priority: Priority,
+		message: OutboundMessage,
+		now: Instant,
+	) -> Submitted {
+		let mut submitted = Submitted { send: self.flush(peer_set, now), dropped: None };
+
+		let jumps_queue = priority == Priority::High || self.queues[peer_set].is_empty();
+		if jumps_queue && self.try_consume(peer_set, priority, message.size(), now) {
+			submitted.send.push(message);
+			return submitted
+		}
+
+		let queue = &mut self.queues[peer_set];
+		queue.push_back(message);
+		if queue.len() > MAX_QUEUED_MESSAGES {
+			submitted.dropped = queue.pop_front();
+		}
+
+		submitted
+	}
+
+	/// Release the queued notifications of `peer_set` which fit into the limit again, in order.
+	pub(crate) fn flush(&mut self, peer_set: PeerSet, now: Instant) -> Vec<OutboundMessage> {
+		let mut released = Vec::new();
+		while let Some(size) = self.queues[peer_set].front().map(OutboundMessage::size) {
+			if !self.try_consume(peer_set, Priority::Normal, size, now) {
+				break
+			}
+
+			released.extend(self.queues[peer_set].pop_front());
+		}
+
+		released
+	}
+
+	/// Account for sending `bytes` on `peer_set`. Returns `false` if the notification can't be
+	/// sent as the peer set is saturated.
+	fn try_consume(
+		&mut self,
+		peer_set: PeerSet,
+		priority: Priority,
+		bytes: usize,
+		now: Instant,
+	) -> bool {
+		match self.buckets[peer_set] {
+			None => true,
+			Some(ref mut bucket) => bucket.try_consume(priority, bytes, now),
+		}
+	}
+}
+
+impl Default for RateLimiter {
+	fn default() -> Self {
+		RateLimiter::new(&Config::default())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+
+	fn limiter(rate: u64) -> RateLimiter {
+		RateLimiter::new(&Config {
+			validation_rate_limit: Some(rate),
+			collation_rate_limit: None,
+		})
+	}
+
+	#[test]
+	fn unlimited_peer_sets_are_not_throttled() {
+		let mut limiter = limiter(100);
+		let now = Instant::now();
+
+		for _ in 0..10 {
+			assert!(limiter.try_consume(PeerSet::Collation, Priority::Normal, 1000, now));
+		}
+	}
+
+	#[test]
+	fn zero_rate_limits_are_ignored() {
+		let mut limiter = limiter(0);
+		let now = Instant::now();
+
+		for _ in 0..10 {
+			assert!(limiter.try_consume(PeerSet::Validation, Priority::Normal, 1000, now));
+		}
+	}
+
+	#[test]
+	fn normal_traffic_is_throttled_when_saturated() {
+		let mut limiter = limiter(100);
+		let now = Instant::now();
+
+		assert!(limiter.try_consume(PeerSet::Validation, Priority::Normal, 50, now));
+		assert!(limiter.try_consume(PeerSet::Validation, Priority::Normal, 30, now));
+		// Only the reserved share is left.
+		assert!(!limiter.try_consume(PeerSet::Validation, Priority::Normal, 1, now));
+
+		// Refilled after some time.
+		let later = now + Duration::from_millis(500);
+		assert!(limiter.try_consume(PeerSet::Validation, Priority::Normal, 1, later));
+	}
+
+	#[test]
+	fn high_priority_traffic_takes_precedence() {
+		let mut limiter = limiter(100);
+		let now = Instant::now();
+
+		assert!(limiter.try_consume(PeerSet::Validation, Priority::Normal, 80, now));
+		assert!(!limiter.try_consume(PeerSet::Validation, Priority::Normal, 1, now));
+
+		// High priority traffic is sent regardless and delays normal traffic further.
+		assert!(limiter.try_consume(PeerSet::Validation, Priority::High, 100, now));
+		assert!(limiter.try_consume(PeerSet::Validation, Priority::High, 1000, now));
+
+		let later = now + Duration::from_secs(1);
+		assert!(!limiter.try_consume(PeerSet::Validation, Priority::Normal, 1, later));
+
+		// The debt is bounded to a second worth of bytes.
+		let later = now + Duration::from_millis(2_500);
+		assert!(limiter.try_consume(PeerSet::Validation, Priority::Normal, 1, later));
+	}
+
+	fn message(size: usize) -> OutboundMessage {
+		OutboundMessage {
+			peers: vec![PeerId::random()],
+			message_type: "test",
+			encoded: vec![0; size],
+		}
+	}
+
+	#[test]
+	fn throttled_traffic_is_queued_and_sent_in_order() {
+		let mut limiter = limiter(100);
+		let now = Instant::now();
+
+		let submitted = limiter.submit(PeerSet::Validation, Priority::Normal, message(80), now);
+		assert_eq!(submitted.send.len(), 1);
+
+		// Saturated, so normal traffic is queued while high priority traffic is sent.
+		let submitted = limiter.submit(PeerSet::Validation, Priority::Normal, message(10), now);
+		assert!(submitted.send.is_empty());
+		let submitted = limiter.submit(PeerSet::Validation, Priority::High, message(1), now);
+		assert_eq!(submitted.send.len(), 1);
+		assert!(limiter.flush(PeerSet::Validation, now).is_empty());
+
+		// Once refilled, queued traffic is sent ahead of new traffic.
+		let later = now + Duration::from_millis(500);
+		let submitted = limiter.submit(PeerSet::Validation, Priority::Normal, message(20), later);
+		let sizes: Vec<_> = submitted.send.iter().map(|m| m.encoded.len()).collect();
+		assert_eq!(sizes, vec![10, 20]);
+		assert!(submitted.dropped.is_none());
+	}
+
+	#[test]
+	fn oldest_queued_traffic_is_dropped_on_overflow() {
+		let mut limiter = limiter(100);
+		let now = Instant::now();
+
+		limiter.submit(PeerSet::Validation, Priority::Normal, message(100), now);
+		for size in 0..MAX_QUEUED_MESSAGES {
+			let submitted = limiter.submit(PeerSet::Validation, Priority::Normal, message(size), now);
+			assert!(submitted.send.is_empty());
+			assert!(submitted.dropped.is_none());
+		}
+
+		let submitted = limiter.submit(PeerSet::Validation, Priority::Normal, message(1), now);
+		assert_eq!(submitted.dropped.map(|m| m.encoded.len()), Some(0));
+	}
+}
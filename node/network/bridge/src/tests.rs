@@ -33,7 +33,7 @@ use polkadot_node_subsystem_test_helpers::{
 	SingleItemSink, SingleItemStream, TestSubsystemContextHandle,
 };
 use polkadot_node_subsystem_util::metered;
-use polkadot_primitives::v1::AuthorityDiscoveryId;
+use polkadot_primitives::v1::{AuthorityDiscoveryId, CandidateHash};
 use polkadot_primitives_test_helpers::dummy_collator_signature;
 use polkadot_subsystem::{
 	jaeger,
@@ -182,6 +182,22 @@ impl TestNetworkHandle {
 		.await;
 	}
 
+	async fn connect_peer_with_fallback(
+		&mut self,
+		peer: PeerId,
+		peer_set: PeerSet,
+		role: ObservedRole,
+		fallback: &'static str,
+	) {
+		self.send_network_event(NetworkEvent::NotificationStreamOpened {
+			remote: peer,
+			protocol: peer_set.into_protocol_name(),
+			negotiated_fallback: Some(fallback.into()),
+			role: role.into(),
+		})
+		.await;
+	}
+
 	async fn disconnect_peer(&mut self, peer: PeerId, peer_set: PeerSet) {
 		self.send_network_event(NetworkEvent::NotificationStreamClosed {
 			remote: peer,
@@ -275,6 +291,14 @@ struct TestHarness {
 fn test_harness<T: Future<Output = VirtualOverseer>>(
 	sync_oracle: Box<dyn SyncOracle + Send>,
 	test: impl FnOnce(TestHarness) -> T,
+) {
+	test_harness_with_config(Config::default(), sync_oracle, test)
+}
+
+fn test_harness_with_config<T: Future<Output = VirtualOverseer>>(
+	config: Config,
+	sync_oracle: Box<dyn SyncOracle + Send>,
+	test: impl FnOnce(TestHarness) -> T,
 ) {
 	let pool = sp_core::testing::TaskExecutor::new();
 	let (mut network, network_handle, discovery) = new_test_network();
@@ -287,6 +311,7 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 		authority_discovery_service: discovery,
 		metrics: Metrics(None),
 		sync_oracle,
+		config,
 	};
 
 	let network_bridge = run_network(bridge, context, network_stream)
@@ -642,7 +667,12 @@ fn peer_view_updates_sent_via_overseer() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					PeerSet::Validation.get_main_version(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -685,7 +715,12 @@ fn peer_messages_sent_via_overseer() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					PeerSet::Validation.get_main_version(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -755,7 +790,12 @@ fn peer_disconnect_from_just_one_peerset() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					PeerSet::Validation.get_main_version(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -769,7 +809,12 @@ fn peer_disconnect_from_just_one_peerset() {
 
 		{
 			assert_sends_collation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					PeerSet::Collation.get_main_version(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -838,7 +883,12 @@ fn relays_collation_protocol_messages() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer_a.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer_a.clone(),
+					ObservedRole::Full,
+					PeerSet::Validation.get_main_version(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -852,7 +902,12 @@ fn relays_collation_protocol_messages() {
 
 		{
 			assert_sends_collation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer_b.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer_b.clone(),
+					ObservedRole::Full,
+					PeerSet::Collation.get_main_version(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -931,7 +986,12 @@ fn different_views_on_different_peer_sets() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					PeerSet::Validation.get_main_version(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -945,7 +1005,12 @@ fn different_views_on_different_peer_sets() {
 
 		{
 			assert_sends_collation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					PeerSet::Collation.get_main_version(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -1094,7 +1159,12 @@ fn send_messages_to_peers() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					PeerSet::Validation.get_main_version(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -1108,7 +1178,12 @@ fn send_messages_to_peers() {
 
 		{
 			assert_sends_collation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					PeerSet::Collation.get_main_version(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -1188,6 +1263,313 @@ fn send_messages_to_peers() {
 	});
 }
 
+#[test]
+fn compact_assignments_are_only_sent_to_peers_supporting_them() {
+	test_harness(done_syncing_oracle(), |test_harness| async move {
+		let TestHarness { mut network_handle, mut virtual_overseer } = test_harness;
+
+		let peer_a = PeerId::random();
+		let peer_b = PeerId::random();
+
+		network_handle
+			.connect_peer(peer_a.clone(), PeerSet::Validation, ObservedRole::Full)
+			.await;
+		network_handle
+			.connect_peer_with_fallback(
+				peer_b.clone(),
+				PeerSet::Validation,
+				ObservedRole::Full,
+				"/polkadot/validation/1",
+			)
+			.await;
+
+		let peers = [(peer_a.clone(), PeerSet::Validation.get_main_version()), (peer_b.clone(), 1)];
+		for (peer, version) in peers {
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, version, None),
+				&mut virtual_overseer,
+			)
+			.await;
+
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerViewChange(peer, View::default()),
+				&mut virtual_overseer,
+			)
+			.await;
+		}
+
+		// consume peer view changes
+		{
+			let _peer_view_changes = network_handle.next_network_actions(2).await;
+		}
+
+		let compact = protocol_v1::ValidationProtocol::ApprovalDistribution(
+			protocol_v1::ApprovalDistributionMessage::CompactAssignments(Vec::new()),
+		);
+		let approvals = protocol_v1::ValidationProtocol::ApprovalDistribution(
+			protocol_v1::ApprovalDistributionMessage::Approvals(Vec::new()),
+		);
+
+		for message in [compact.clone(), approvals.clone()] {
+			virtual_overseer
+				.send(FromOverseer::Communication {
+					msg: NetworkBridgeMessage::SendValidationMessage(
+						vec![peer_a.clone(), peer_b.clone()],
+						message,
+					),
+				})
+				.await;
+		}
+
+		let actions = network_handle.next_network_actions(3).await;
+		assert_eq!(
+			actions[0],
+			NetworkAction::WriteNotification(
+				peer_a.clone(),
+				PeerSet::Validation,
+				WireMessage::ProtocolMessage(compact).encode(),
+			),
+		);
+
+		for peer in [peer_a, peer_b] {
+			assert_network_actions_contains(
+				&actions[1..],
+				&NetworkAction::WriteNotification(
+					peer,
+					PeerSet::Validation,
+					WireMessage::ProtocolMessage(approvals.clone()).encode(),
+				),
+			);
+		}
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn candidate_advertisements_are_downgraded_for_old_collation_peers() {
+	test_harness(done_syncing_oracle(), |test_harness| async move {
+		let TestHarness { mut network_handle, mut virtual_overseer } = test_harness;
+
+		let peer_a = PeerId::random();
+		let peer_b = PeerId::random();
+
+		network_handle
+			.connect_peer(peer_a.clone(), PeerSet::Collation, ObservedRole::Full)
+			.await;
+		network_handle
+			.connect_peer_with_fallback(
+				peer_b.clone(),
+				PeerSet::Collation,
+				ObservedRole::Full,
+				"/polkadot/collation/1",
+			)
+			.await;
+
+		let peers = [(peer_a.clone(), PeerSet::Collation.get_main_version()), (peer_b.clone(), 1)];
+		for (peer, version) in peers {
+			assert_sends_collation_event_to_all(
+				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, version, None),
+				&mut virtual_overseer,
+			)
+			.await;
+
+			assert_sends_collation_event_to_all(
+				NetworkBridgeEvent::PeerViewChange(peer, View::default()),
+				&mut virtual_overseer,
+			)
+			.await;
+		}
+
+		// consume peer view changes
+		{
+			let _peer_view_changes = network_handle.next_network_actions(2).await;
+		}
+
+		let relay_parent = Hash::repeat_byte(0x01);
+		let candidates = protocol_v1::CollationProtocol::CollatorProtocol(
+			protocol_v1::CollatorProtocolMessage::AdvertiseCollationCandidates(
+				relay_parent,
+				vec![CandidateHash(Hash::repeat_byte(0x02))],
+			),
+		);
+		let additional = protocol_v1::CollationProtocol::CollatorProtocol(
+			protocol_v1::CollatorProtocolMessage::AdvertiseAdditionalCandidates(
+				relay_parent,
+				vec![CandidateHash(Hash::repeat_byte(0x03))],
+			),
+		);
+
+		for message in [candidates.clone(), additional.clone()] {
+			virtual_overseer
+				.send(FromOverseer::Communication {
+					msg: NetworkBridgeMessage::SendCollationMessage(
+						vec![peer_a.clone(), peer_b.clone()],
+						message,
+					),
+				})
+				.await;
+		}
+
+		// Additional candidates are of no use to peers which can't fetch specific candidates, so
+		// there are only three notifications.
+		let actions = network_handle.next_network_actions(3).await;
+		let downgraded = protocol_v1::CollationProtocol::CollatorProtocol(
+			protocol_v1::CollatorProtocolMessage::AdvertiseCollation(relay_parent),
+		);
+
+		for action in [
+			NetworkAction::WriteNotification(
+				peer_a.clone(),
+				PeerSet::Collation,
+				WireMessage::ProtocolMessage(candidates).encode(),
+			),
+			NetworkAction::WriteNotification(
+				peer_b.clone(),
+				PeerSet::Collation,
+				WireMessage::ProtocolMessage(downgraded).encode(),
+			),
+			NetworkAction::WriteNotification(
+				peer_a.clone(),
+				PeerSet::Collation,
+				WireMessage::ProtocolMessage(additional).encode(),
+			),
+		] {
+			assert_network_actions_contains(&actions, &action);
+		}
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn outbound_rate_limit_prioritizes_and_queues() {
+	let config = Config { validation_rate_limit: Some(1), collation_rate_limit: Some(40) };
+	test_harness_with_config(config, done_syncing_oracle(), |test_harness| async move {
+		let TestHarness { mut network_handle, mut virtual_overseer } = test_harness;
+
+		let peer = PeerId::random();
+
+		network_handle
+			.connect_peer(peer.clone(), PeerSet::Validation, ObservedRole::Full)
+			.await;
+		network_handle
+			.connect_peer(peer.clone(), PeerSet::Collation, ObservedRole::Full)
+			.await;
+
+		assert_sends_validation_event_to_all(
+			NetworkBridgeEvent::PeerConnected(
+				peer.clone(),
+				ObservedRole::Full,
+				PeerSet::Validation.get_main_version(),
+				None,
+			),
+			&mut virtual_overseer,
+		)
+		.await;
+
+		assert_sends_validation_event_to_all(
+			NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
+			&mut virtual_overseer,
+		)
+		.await;
+
+		assert_sends_collation_event_to_all(
+			NetworkBridgeEvent::PeerConnected(
+				peer.clone(),
+				ObservedRole::Full,
+				PeerSet::Collation.get_main_version(),
+				None,
+			),
+			&mut virtual_overseer,
+		)
+		.await;
+
+		assert_sends_collation_event_to_all(
+			NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
+			&mut virtual_overseer,
+		)
+		.await;
+
+		// View updates are sent, even though they exceed the limit of the validation peer set.
+		{
+			let _peer_view_changes = network_handle.next_network_actions(2).await;
+		}
+
+		let advertisement = |byte| {
+			protocol_v1::CollationProtocol::CollatorProtocol(
+				protocol_v1::CollatorProtocolMessage::AdvertiseCollation(Hash::repeat_byte(byte)),
+			)
+		};
+		let declare = protocol_v1::CollationProtocol::CollatorProtocol(
+			protocol_v1::CollatorProtocolMessage::Declare(
+				Sr25519Keyring::Alice.public().into(),
+				0_u32.into(),
+				dummy_collator_signature(),
+			),
+		);
+		let approvals = protocol_v1::ValidationProtocol::ApprovalDistribution(
+			protocol_v1::ApprovalDistributionMessage::Approvals(Vec::new()),
+		);
+
+		// The second advertisement exceeds the limit of the collation peer set and is queued,
+		// while the declaration is sent regardless.
+		for message in [advertisement(1), advertisement(2), declare.clone()] {
+			virtual_overseer
+				.send(FromOverseer::Communication {
+					msg: NetworkBridgeMessage::SendCollationMessage(vec![peer.clone()], message),
+				})
+				.await;
+		}
+
+		// Approvals are sent regardless of the limit.
+		for _ in 0..2 {
+			virtual_overseer
+				.send(FromOverseer::Communication {
+					msg: NetworkBridgeMessage::SendValidationMessage(
+						vec![peer.clone()],
+						approvals.clone(),
+					),
+				})
+				.await;
+		}
+
+		for message in [advertisement(1), declare] {
+			assert_eq!(
+				network_handle.next_network_action().await,
+				NetworkAction::WriteNotification(
+					peer.clone(),
+					PeerSet::Collation,
+					WireMessage::ProtocolMessage(message).encode(),
+				)
+			);
+		}
+
+		for _ in 0..2 {
+			assert_eq!(
+				network_handle.next_network_action().await,
+				NetworkAction::WriteNotification(
+					peer.clone(),
+					PeerSet::Validation,
+					WireMessage::ProtocolMessage(approvals.clone()).encode(),
+				)
+			);
+		}
+
+		// The queued advertisement is sent once the limit allows it again.
+		assert_eq!(
+			network_handle.next_network_action().await,
+			NetworkAction::WriteNotification(
+				peer.clone(),
+				PeerSet::Collation,
+				WireMessage::ProtocolMessage(advertisement(2)).encode(),
+			)
+		);
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn spread_event_to_subsystems_is_up_to_date() {
 	// Number of subsystems expected to be interested in a network event,
@@ -1195,7 +1577,8 @@ fn spread_event_to_subsystems_is_up_to_date() {
 	const EXPECTED_COUNT: usize = 4;
 
 	let mut cnt = 0_usize;
-	for msg in AllMessages::dispatch_iter(NetworkBridgeEvent::PeerDisconnected(PeerId::random())) {
+	let event = NetworkBridgeEvent::PeerDisconnected(PeerId::random());
+	for msg in AllMessages::dispatch_iter::<AllMessages>(event) {
 		match msg {
 			AllMessages::Empty => unreachable!("Nobody cares about the dummy"),
 			AllMessages::CandidateValidation(_) => unreachable!("Not interested in network events"),
@@ -29,7 +29,10 @@ use polkadot_node_network_protocol::{
 	peer_set::PeerSet,
 	request_response::{
 		incoming::{self, OutgoingResponse},
-		v1::{self as request_v1, CollationFetchingRequest, CollationFetchingResponse},
+		v1::{
+			self as request_v1, CandidateCollationFetchingRequest, CollationFetchingRequest,
+			CollationFetchingResponse,
+		},
 		IncomingRequest, IncomingRequestReceiver,
 	},
 	v1 as protocol_v1, OurView, PeerId, UnifiedReputationChange as Rep, View,
@@ -50,7 +53,7 @@ use polkadot_subsystem::{
 	overseer, FromOverseer, OverseerSignal, PerLeafSpan, SubsystemContext,
 };
 
-use super::{Result, LOG_TARGET};
+use super::{Result, LOG_TARGET, MAX_CANDIDATES_PER_RELAY_PARENT};
 use crate::error::{log_error, Fatal, FatalResult, NonFatal};
 
 #[cfg(test)]
@@ -146,42 +149,56 @@ impl metrics::Metrics for Metrics {
 
 /// Info about validators we are currently connected to.
 ///
-/// It keeps track to which validators we advertised our collation.
+/// It keeps track of how many of our collations we advertised to which validators.
 #[derive(Debug)]
 struct ValidatorGroup {
-	/// All [`ValidatorId`]'s of the current group to that we advertised our collation.
-	advertised_to: HashSet<AuthorityDiscoveryId>,
+	/// The discovery keys of the validators in the group.
+	validators: HashSet<AuthorityDiscoveryId>,
+	/// The number of collations we advertised to the [`ValidatorId`]'s of the current group.
+	advertised_to: HashMap<AuthorityDiscoveryId, usize>,
 }
 
 impl ValidatorGroup {
-	/// Create a new `ValidatorGroup`
+	/// Create a new `ValidatorGroup` consisting of the given validators
 	///
 	/// without any advertisements.
-	fn new() -> Self {
-		Self { advertised_to: HashSet::new() }
+	fn new(validators: HashSet<AuthorityDiscoveryId>) -> Self {
+		Self { validators, advertised_to: HashMap::new() }
+	}
+
+	/// Whether any of the given discovery keys belongs to a validator of the group.
+	fn contains_any(&self, discovery_ids: &HashSet<AuthorityDiscoveryId>) -> bool {
+		discovery_ids.iter().any(|d| self.validators.contains(d))
 	}
 
-	/// Returns `true` if we should advertise our collation to the given peer.
-	fn should_advertise_to(
+	/// Returns the number of our collations we advertised to the given peer so far, or `None`
+	/// if the peer is not a validator we should advertise our collations to.
+	fn advertised_to(
 		&self,
 		peer_ids: &HashMap<PeerId, HashSet<AuthorityDiscoveryId>>,
 		peer: &PeerId,
-	) -> bool {
-		match peer_ids.get(peer) {
-			Some(discovery_ids) => !discovery_ids.iter().any(|d| self.advertised_to.contains(d)),
-			None => false,
-		}
+	) -> Option<usize> {
+		peer_ids.get(peer).map(|discovery_ids| {
+			discovery_ids
+				.iter()
+				.filter_map(|d| self.advertised_to.get(d))
+				.copied()
+				.max()
+				.unwrap_or(0)
+		})
 	}
 
-	/// Should be called after we advertised our collation to the given `peer` to keep track of it.
+	/// Should be called after we advertised `count` collations in total to the given `peer` to
+	/// keep track of it.
 	fn advertised_to_peer(
 		&mut self,
 		peer_ids: &HashMap<PeerId, HashSet<AuthorityDiscoveryId>>,
 		peer: &PeerId,
+		count: usize,
 	) {
 		if let Some(authority_ids) = peer_ids.get(peer) {
 			authority_ids.iter().for_each(|a| {
-				self.advertised_to.insert(a.clone());
+				self.advertised_to.insert(a.clone(), count);
 			});
 		}
 	}
@@ -220,13 +237,61 @@ struct Collation {
 	status: CollationStatus,
 }
 
+/// A request for one of our collations.
+enum CollationRequest {
+	/// Request for the collation at a relay parent, served with the first one we built.
+	RelayParent(IncomingRequest<CollationFetchingRequest>),
+	/// Request for the collation of a specific candidate we advertised.
+	Candidate(IncomingRequest<CandidateCollationFetchingRequest>),
+}
+
+impl CollationRequest {
+	fn peer(&self) -> PeerId {
+		match self {
+			Self::RelayParent(req) => req.peer,
+			Self::Candidate(req) => req.peer,
+		}
+	}
+
+	fn relay_parent(&self) -> Hash {
+		match self {
+			Self::RelayParent(req) => req.payload.relay_parent,
+			Self::Candidate(req) => req.payload.relay_parent,
+		}
+	}
+
+	fn para_id(&self) -> ParaId {
+		match self {
+			Self::RelayParent(req) => req.payload.para_id,
+			Self::Candidate(req) => req.payload.para_id,
+		}
+	}
+
+	fn candidate_hash(&self) -> Option<CandidateHash> {
+		match self {
+			Self::RelayParent(_) => None,
+			Self::Candidate(req) => Some(req.payload.candidate_hash),
+		}
+	}
+
+	fn send_outgoing_response(
+		self,
+		response: OutgoingResponse<CollationFetchingResponse>,
+	) -> std::result::Result<(), ()> {
+		match self {
+			Self::RelayParent(req) => req.send_outgoing_response(response),
+			Self::Candidate(req) => req.send_outgoing_response(response),
+		}
+	}
+}
+
 /// Stores the state for waiting collation fetches.
 #[derive(Default)]
 struct WaitingCollationFetches {
 	/// Is there currently a collation getting fetched?
 	collation_fetch_active: bool,
 	/// The collation fetches waiting to be fulfilled.
-	waiting: VecDeque<IncomingRequest<CollationFetchingRequest>>,
+	waiting: VecDeque<CollationRequest>,
 	/// All peers that are waiting or actively uploading.
 	///
 	/// We will not accept multiple requests from the same peer, otherwise our DoS protection of
@@ -244,9 +309,9 @@ struct State {
 	/// Our collator pair.
 	collator_pair: CollatorPair,
 
-	/// The para this collator is collating on.
-	/// Starts as `None` and is updated with every `CollateOn` message.
-	collating_on: Option<ParaId>,
+	/// The paras this collator is collating on.
+	/// Starts empty and is extended with every `CollateOn` message.
+	collating_on: HashSet<ParaId>,
 
 	/// Track all active peers and their views
 	/// to determine what is relevant to them.
@@ -260,14 +325,20 @@ struct State {
 
 	/// Possessed collations.
 	///
-	/// We will keep up to one local collation per relay-parent.
-	collations: HashMap<Hash, Collation>,
+	/// We will keep up to `MAX_CANDIDATES_PER_RELAY_PARENT` local collations per relay-parent.
+	collations: HashMap<Hash, HashMap<ParaId, Vec<Collation>>>,
 
 	/// The result senders per collation.
 	collation_result_senders: HashMap<CandidateHash, oneshot::Sender<CollationSecondedSignal>>,
 
-	/// Our validator groups per active leaf.
-	our_validators_groups: HashMap<Hash, ValidatorGroup>,
+	/// Our validator groups per active leaf and para.
+	///
+	/// Validators are assigned per relay parent, so the same validator may be assigned to
+	/// different paras of ours at different relay parents.
+	our_validators_groups: HashMap<Hash, HashMap<ParaId, ValidatorGroup>>,
+
+	/// The para we last declared to be collating on to each peer.
+	declared_to: HashMap<PeerId, ParaId>,
 
 	/// The mapping from [`PeerId`] to [`HashSet<AuthorityDiscoveryId>`]. This is filled over time as we learn the [`PeerId`]'s
 	/// by `PeerConnected` events.
@@ -303,6 +374,7 @@ impl State {
 			collations: Default::default(),
 			collation_result_senders: Default::default(),
 			our_validators_groups: Default::default(),
+			declared_to: Default::default(),
 			peer_ids: Default::default(),
 			waiting_collation_fetches: Default::default(),
 			active_collation_fetches: Default::default(),
@@ -317,13 +389,59 @@ impl State {
 			.map(|(peer, _)| *peer)
 			.collect()
 	}
+
+	/// Get the collation asked for by `request`, if we have it.
+	fn requested_collation(&mut self, request: &CollationRequest) -> Option<&mut Collation> {
+		let collations =
+			self.collations.get_mut(&request.relay_parent())?.get_mut(&request.para_id())?;
+		match request.candidate_hash() {
+			None => collations.first_mut(),
+			Some(candidate_hash) =>
+				collations.iter_mut().find(|collation| collation.receipt.hash() == candidate_hash),
+		}
+	}
+
+	/// The para whose collations the given peer is interested in at the given relay parent.
+	///
+	/// This is the para the peer is assigned to at the relay parent, if any.
+	fn para_for_peer(&self, peer: &PeerId, relay_parent: &Hash) -> Option<ParaId> {
+		let assigned = self.peer_ids.get(peer).and_then(|discovery_ids| {
+			self.our_validators_groups
+				.get(relay_parent)?
+				.iter()
+				.find(|(_, group)| group.contains_any(discovery_ids))
+				.map(|(para_id, _)| *para_id)
+		});
+
+		assigned.or_else(|| self.single_para())
+	}
+
+	/// The para to declare to the given peer when it connects.
+	///
+	/// This is the para the peer is assigned to at any relay parent in our view, if any.
+	fn para_to_declare(&self, peer: &PeerId) -> Option<ParaId> {
+		self.view
+			.iter()
+			.find_map(|relay_parent| self.para_for_peer(peer, relay_parent))
+			.or_else(|| self.single_para())
+	}
+
+	/// The para we are collating on, if it is the only one.
+	///
+	/// Collating on a single para, any validator may be interested in it.
+	fn single_para(&self) -> Option<ParaId> {
+		match self.collating_on.len() {
+			1 => self.collating_on.iter().next().copied(),
+			_ => None,
+		}
+	}
 }
 
 /// Distribute a collation.
 ///
 /// If the para is not scheduled on any core, at the relay parent,
-/// or the relay parent isn't in our view or we already collated on the relay parent,
-/// we ignore the message as it must be invalid in that case -
+/// or the relay parent isn't in our view or we already have the collation or the maximum number
+/// of collations on the relay parent, we ignore the message as it must be invalid in that case -
 /// although this indicates a logic error elsewhere in the node.
 ///
 /// Otherwise, start advertising the collation to interested peers.
@@ -352,12 +470,31 @@ where
 		return Ok(())
 	}
 
-	// We have already seen collation for this relay parent.
-	if state.collations.contains_key(&relay_parent) {
-		return Ok(())
+	let candidate_hash = receipt.hash();
+
+	if let Some(collations) = state.collations.get(&relay_parent).and_then(|c| c.get(&id)) {
+		// We have already seen this collation.
+		if collations.iter().any(|collation| collation.receipt.hash() == candidate_hash) {
+			return Ok(())
+		}
+
+		if collations.len() >= MAX_CANDIDATES_PER_RELAY_PARENT {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?relay_parent,
+				?candidate_hash,
+				"Ignoring collation as we have the maximum number of collations for the relay parent.",
+			);
+
+			return Ok(())
+		}
 	}
 
-	if !state.our_validators_groups.contains_key(&relay_parent) {
+	let has_validators = state
+		.our_validators_groups
+		.get(&relay_parent)
+		.map_or(false, |groups| groups.contains_key(&id));
+	if !has_validators {
 		tracing::warn!(target: LOG_TARGET, "Could not determine validators assigned to the core.");
 
 		return Ok(())
@@ -367,18 +504,20 @@ where
 		target: LOG_TARGET,
 		para_id = %id,
 		relay_parent = %relay_parent,
-		candidate_hash = ?receipt.hash(),
+		?candidate_hash,
 		pov_hash = ?pov.hash(),
 		"Accepted collation",
 	);
 
 	if let Some(result_sender) = result_sender {
-		state.collation_result_senders.insert(receipt.hash(), result_sender);
+		state.collation_result_senders.insert(candidate_hash, result_sender);
 	}
 
-	state
-		.collations
-		.insert(relay_parent, Collation { receipt, pov, status: CollationStatus::Created });
+	state.collations.entry(relay_parent).or_default().entry(id).or_default().push(Collation {
+		receipt,
+		pov,
+		status: CollationStatus::Created,
+	});
 
 	let interested = state.peers_interested_in_leaf(&relay_parent);
 	// Make sure already connected peers get collations:
@@ -464,22 +603,41 @@ async fn declare<Context>(ctx: &mut Context, state: &mut State, peer: PeerId)
 where
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
+{
+	if let Some(para_id) = state.para_to_declare(&peer) {
+		declare_para(ctx, state, peer, para_id).await;
+	}
+}
+
+/// Issue a `Declare` collation message for the given `para_id` to the given `peer`.
+///
+/// Validators only accept advertisements for the para we declared to them, so this is also used
+/// to re-declare to a peer once it is assigned to another one of our paras. The peer keeps our
+/// advertisements for the previously declared para.
+async fn declare_para<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	peer: PeerId,
+	para_id: ParaId,
+) where
+	Context: SubsystemContext<Message = CollatorProtocolMessage>,
+	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
 {
 	let declare_signature_payload = protocol_v1::declare_signature_payload(&state.local_peer_id);
 
-	if let Some(para_id) = state.collating_on {
-		let wire_message = protocol_v1::CollatorProtocolMessage::Declare(
-			state.collator_pair.public(),
-			para_id,
-			state.collator_pair.sign(&declare_signature_payload),
-		);
+	let wire_message = protocol_v1::CollatorProtocolMessage::Declare(
+		state.collator_pair.public(),
+		para_id,
+		state.collator_pair.sign(&declare_signature_payload),
+	);
 
-		ctx.send_message(NetworkBridgeMessage::SendCollationMessage(
-			vec![peer],
-			protocol_v1::CollationProtocol::CollatorProtocol(wire_message),
-		))
-		.await;
-	}
+	ctx.send_message(NetworkBridgeMessage::SendCollationMessage(
+		vec![peer],
+		protocol_v1::CollationProtocol::CollatorProtocol(wire_message),
+	))
+	.await;
+
+	state.declared_to.insert(peer, para_id);
 }
 
 /// Issue a connection request to a set of validators and
@@ -500,10 +658,14 @@ where
 	.await;
 }
 
-/// Advertise collation to the given `peer`.
+/// Advertise collations to the given `peer`.
+///
+/// This will only advertise collations if there exist any for the given `relay_parent` we didn't
+/// advertise to the given `peer` yet and the `peer` is set as validator for one of our paras at
+/// the given `relay_parent`.
 ///
-/// This will only advertise a collation if there exists one for the given `relay_parent` and the given `peer` is
-/// set as validator for our para at the given `relay_parent`.
+/// If we declared a different para to the `peer` before, we re-declare ourselves for the para the
+/// `peer` is assigned to at the given `relay_parent` first.
 async fn advertise_collation<Context>(
 	ctx: &mut Context,
 	state: &mut State,
@@ -513,43 +675,86 @@ async fn advertise_collation<Context>(
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
 {
-	let should_advertise = state
-		.our_validators_groups
-		.get(&relay_parent)
-		.map(|g| g.should_advertise_to(&state.peer_ids, &peer))
-		.unwrap_or(false);
-
-	match (state.collations.get_mut(&relay_parent), should_advertise) {
-		(None, _) => {
+	let para_id = match state.para_for_peer(&peer, &relay_parent) {
+		Some(para_id) => para_id,
+		None => {
 			tracing::trace!(
 				target: LOG_TARGET,
 				?relay_parent,
 				peer_id = %peer,
-				"No collation to advertise.",
+				"Peer is not interested in any of our paras.",
 			);
 			return
 		},
-		(_, false) => {
-			tracing::debug!(
+	};
+
+	let has_collations =
+		state.collations.get(&relay_parent).map_or(false, |c| c.contains_key(&para_id));
+	if has_collations && state.declared_to.get(&peer) != Some(&para_id) {
+		tracing::debug!(
+			target: LOG_TARGET,
+			?relay_parent,
+			peer_id = %peer,
+			%para_id,
+			"Re-declaring as the peer is assigned to another para.",
+		);
+		declare_para(ctx, state, peer, para_id).await;
+	}
+
+	let advertised = state
+		.our_validators_groups
+		.get(&relay_parent)
+		.and_then(|groups| groups.get(&para_id))
+		.and_then(|g| g.advertised_to(&state.peer_ids, &peer));
+
+	let collations = state.collations.get_mut(&relay_parent).and_then(|c| c.get_mut(&para_id));
+	let collations = match collations {
+		None => {
+			tracing::trace!(
 				target: LOG_TARGET,
 				?relay_parent,
 				peer_id = %peer,
-				"Not advertising collation as we already advertised it to this validator.",
+				"No collation to advertise.",
 			);
 			return
 		},
-		(Some(collation), true) => {
+		Some(collations) => collations,
+	};
+
+	let advertised = match advertised {
+		Some(advertised) if advertised < collations.len() => advertised,
+		_ => {
 			tracing::debug!(
 				target: LOG_TARGET,
 				?relay_parent,
 				peer_id = %peer,
-				"Advertising collation.",
+				"Not advertising collation as we already advertised it to this validator.",
 			);
-			collation.status.advance_to_advertised()
+			return
 		},
-	}
+	};
 
-	let wire_message = protocol_v1::CollatorProtocolMessage::AdvertiseCollation(relay_parent);
+	tracing::debug!(target: LOG_TARGET, ?relay_parent, peer_id = %peer, "Advertising collation.");
+
+	let count = collations.len();
+	let candidates = collations[advertised..]
+		.iter_mut()
+		.map(|collation| {
+			collation.status.advance_to_advertised();
+			collation.receipt.hash()
+		})
+		.collect();
+
+	// Validators which know about the relay parent already only need to learn about the new
+	// candidates.
+	let wire_message = if advertised == 0 {
+		protocol_v1::CollatorProtocolMessage::AdvertiseCollationCandidates(relay_parent, candidates)
+	} else {
+		protocol_v1::CollatorProtocolMessage::AdvertiseAdditionalCandidates(
+			relay_parent,
+			candidates,
+		)
+	};
 
 	ctx.send_message(NetworkBridgeMessage::SendCollationMessage(
 		vec![peer.clone()],
@@ -557,8 +762,10 @@ async fn advertise_collation<Context>(
 	))
 	.await;
 
-	if let Some(validators) = state.our_validators_groups.get_mut(&relay_parent) {
-		validators.advertised_to_peer(&state.peer_ids, &peer);
+	if let Some(validators) =
+		state.our_validators_groups.get_mut(&relay_parent).and_then(|g| g.get_mut(&para_id))
+	{
+		validators.advertised_to_peer(&state.peer_ids, &peer, count);
 	}
 
 	state.metrics.on_advertisment_made();
@@ -581,7 +788,7 @@ where
 
 	match msg {
 		CollateOn(id) => {
-			state.collating_on = Some(id);
+			state.collating_on.insert(id);
 		},
 		DistributeCollation(receipt, pov, result_sender) => {
 			let _span1 = state
@@ -589,27 +796,24 @@ where
 				.get(&receipt.descriptor.relay_parent)
 				.map(|s| s.child("distributing-collation"));
 			let _span2 = jaeger::Span::new(&pov, "distributing-collation");
-			match state.collating_on {
-				Some(id) if receipt.descriptor.para_id != id => {
-					// If the ParaId of a collation requested to be distributed does not match
-					// the one we expect, we ignore the message.
-					tracing::warn!(
-						target: LOG_TARGET,
-						para_id = %receipt.descriptor.para_id,
-						collating_on = %id,
-						"DistributeCollation for unexpected para_id",
-					);
-				},
-				Some(id) => {
-					distribute_collation(ctx, state, id, receipt, pov, result_sender).await?;
-				},
-				None => {
-					tracing::warn!(
-						target: LOG_TARGET,
-						para_id = %receipt.descriptor.para_id,
-						"DistributeCollation message while not collating on any",
-					);
-				},
+			let id = receipt.descriptor.para_id;
+			if state.collating_on.contains(&id) {
+				distribute_collation(ctx, state, id, receipt, pov, result_sender).await?;
+			} else if state.collating_on.is_empty() {
+				tracing::warn!(
+					target: LOG_TARGET,
+					para_id = %id,
+					"DistributeCollation message while not collating on any",
+				);
+			} else {
+				// If the ParaId of a collation requested to be distributed does not match
+				// the ones we expect, we ignore the message.
+				tracing::warn!(
+					target: LOG_TARGET,
+					para_id = %id,
+					collating_on = ?state.collating_on,
+					"DistributeCollation for unexpected para_id",
+				);
 			}
 		},
 		ReportCollator(_) => {
@@ -636,14 +840,14 @@ where
 /// Issue a response to a previously requested collation.
 async fn send_collation(
 	state: &mut State,
-	request: IncomingRequest<CollationFetchingRequest>,
+	request: CollationRequest,
 	receipt: CandidateReceipt,
 	pov: PoV,
 ) {
 	let (tx, rx) = oneshot::channel();
 
-	let relay_parent = request.payload.relay_parent;
-	let peer_id = request.peer;
+	let relay_parent = request.relay_parent();
+	let peer_id = request.peer();
 
 	let response = OutgoingResponse {
 		result: Ok(CollationFetchingResponse::Collation(receipt, pov)),
@@ -700,7 +904,9 @@ where
 			ctx.send_message(NetworkBridgeMessage::DisconnectPeer(origin, PeerSet::Collation))
 				.await;
 		},
-		AdvertiseCollation(_) => {
+		AdvertiseCollation(_) |
+		AdvertiseCollationCandidates(..) |
+		AdvertiseAdditionalCandidates(..) => {
 			tracing::trace!(
 				target: LOG_TARGET,
 				?origin,
@@ -754,73 +960,64 @@ where
 async fn handle_incoming_request<Context>(
 	ctx: &mut Context,
 	state: &mut State,
-	req: IncomingRequest<request_v1::CollationFetchingRequest>,
+	req: CollationRequest,
 ) -> Result<()>
 where
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
 {
+	let relay_parent = req.relay_parent();
 	let _span = state
 		.span_per_relay_parent
-		.get(&req.payload.relay_parent)
+		.get(&relay_parent)
 		.map(|s| s.child("request-collation"));
 
-	match state.collating_on {
-		Some(our_para_id) if our_para_id == req.payload.para_id => {
-			let (receipt, pov) =
-				if let Some(collation) = state.collations.get_mut(&req.payload.relay_parent) {
-					collation.status.advance_to_requested();
-					(collation.receipt.clone(), collation.pov.clone())
-				} else {
-					tracing::warn!(
-						target: LOG_TARGET,
-						relay_parent = %req.payload.relay_parent,
-						"received a `RequestCollation` for a relay parent we don't have collation stored.",
-					);
+	if !state.collating_on.contains(&req.para_id()) {
+		tracing::warn!(
+			target: LOG_TARGET,
+			for_para_id = %req.para_id(),
+			collating_on = ?state.collating_on,
+			"received a `CollationFetchingRequest` for a para we are not collating on",
+		);
+		return Ok(())
+	}
 
-					return Ok(())
-				};
+	let (receipt, pov) = if let Some(collation) = state.requested_collation(&req) {
+		collation.status.advance_to_requested();
+		(collation.receipt.clone(), collation.pov.clone())
+	} else {
+		tracing::warn!(
+			target: LOG_TARGET,
+			relay_parent = %relay_parent,
+			candidate_hash = ?req.candidate_hash(),
+			"received a `RequestCollation` for a relay parent we don't have collation stored.",
+		);
 
-			state.metrics.on_collation_sent_requested();
+		return Ok(())
+	};
 
-			let _span = _span.as_ref().map(|s| s.child("sending"));
+	state.metrics.on_collation_sent_requested();
 
-			let waiting =
-				state.waiting_collation_fetches.entry(req.payload.relay_parent).or_default();
+	let _span = _span.as_ref().map(|s| s.child("sending"));
 
-			if !waiting.waiting_peers.insert(req.peer) {
-				tracing::debug!(
-					target: LOG_TARGET,
-					"Dropping incoming request as peer has a request in flight already."
-				);
-				ctx.send_message(NetworkBridgeMessage::ReportPeer(req.peer, COST_APPARENT_FLOOD))
-					.await;
-				return Ok(())
-			}
+	let waiting = state.waiting_collation_fetches.entry(relay_parent).or_default();
 
-			if waiting.collation_fetch_active {
-				waiting.waiting.push_back(req);
-			} else {
-				waiting.collation_fetch_active = true;
-				send_collation(state, req, receipt, pov).await;
-			}
-		},
-		Some(our_para_id) => {
-			tracing::warn!(
-				target: LOG_TARGET,
-				for_para_id = %req.payload.para_id,
-				our_para_id = %our_para_id,
-				"received a `CollationFetchingRequest` for unexpected para_id",
-			);
-		},
-		None => {
-			tracing::warn!(
-				target: LOG_TARGET,
-				for_para_id = %req.payload.para_id,
-				"received a `RequestCollation` while not collating on any para",
-			);
-		},
+	if !waiting.waiting_peers.insert(req.peer()) {
+		tracing::debug!(
+			target: LOG_TARGET,
+			"Dropping incoming request as peer has a request in flight already."
+		);
+		ctx.send_message(NetworkBridgeMessage::ReportPeer(req.peer(), COST_APPARENT_FLOOD)).await;
+		return Ok(())
+	}
+
+	if waiting.collation_fetch_active {
+		waiting.waiting.push_back(req);
+	} else {
+		waiting.collation_fetch_active = true;
+		send_collation(state, req, receipt, pov).await;
 	}
+
 	Ok(())
 }
 
@@ -859,7 +1056,7 @@ where
 	use NetworkBridgeEvent::*;
 
 	match bridge_message {
-		PeerConnected(peer_id, observed_role, maybe_authority) => {
+		PeerConnected(peer_id, observed_role, _, maybe_authority) => {
 			// If it is possible that a disconnected validator would attempt a reconnect
 			// it should be handled here.
 			tracing::trace!(target: LOG_TARGET, ?peer_id, ?observed_role, "Peer connected");
@@ -883,6 +1080,7 @@ where
 			tracing::trace!(target: LOG_TARGET, ?peer_id, "Peer disconnected");
 			state.peer_views.remove(&peer_id);
 			state.peer_ids.remove(&peer_id);
+			state.declared_to.remove(&peer_id);
 		},
 		OurViewChange(view) => {
 			tracing::trace!(target: LOG_TARGET, ?view, "Own view change");
@@ -913,7 +1111,8 @@ where
 	for removed in state.view.difference(&view) {
 		tracing::debug!(target: LOG_TARGET, relay_parent = ?removed, "Removing relay parent because our view changed.");
 
-		if let Some(collation) = state.collations.remove(removed) {
+		let collations = state.collations.remove(removed).into_iter().flatten();
+		for collation in collations.flat_map(|(_, collations)| collations) {
 			state.collation_result_senders.remove(&collation.receipt.hash());
 
 			match collation.status {
@@ -947,53 +1146,60 @@ where
 		return Ok(())
 	}
 
-	let id = match state.collating_on {
-		Some(id) => id,
-		None => return Ok(()),
-	};
+	if state.collating_on.is_empty() {
+		return Ok(())
+	}
+	let para_ids: Vec<ParaId> = state.collating_on.iter().copied().collect();
 
-	// all validators assigned to the core
+	// all validators assigned to the cores of our paras
 	// across all active leaves
-	// this is typically our current group
-	// but can also include the previous group at
+	// this is typically our current groups
+	// but can also include the previous groups at
 	// rotation boundaries and considering forks
 	let mut group_validators = HashSet::new();
 
 	for relay_parent in state.view.iter().cloned() {
-		tracing::debug!(
-			target: LOG_TARGET,
-			?relay_parent,
-			para_id = ?id,
-			"Processing relay parent.",
-		);
-
-		// Determine our assigned core.
-		// If it is not scheduled then ignore the relay parent.
-		let (our_core, num_cores) = match determine_core(ctx, id, relay_parent).await? {
-			Some(core) => core,
-			None => continue,
-		};
-
-		// Determine the group on that core.
-		let current_validators =
-			determine_our_validators(ctx, runtime, our_core, num_cores, relay_parent).await?;
-
-		let validators = current_validators.validators;
-		group_validators.extend(validators);
+		for para_id in para_ids.iter().copied() {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?relay_parent,
+				?para_id,
+				"Processing relay parent.",
+			);
 
-		state.our_validators_groups.entry(relay_parent).or_insert(ValidatorGroup::new());
+			// Determine our assigned core.
+			// If it is not scheduled then ignore the relay parent.
+			let (our_core, num_cores) = match determine_core(ctx, para_id, relay_parent).await? {
+				Some(core) => core,
+				None => continue,
+			};
+
+			// Determine the group on that core.
+			let current_validators =
+				determine_our_validators(ctx, runtime, our_core, num_cores, relay_parent).await?;
+
+			let validators: HashSet<_> = current_validators.validators.into_iter().collect();
+			group_validators.extend(validators.iter().cloned());
+
+			state
+				.our_validators_groups
+				.entry(relay_parent)
+				.or_default()
+				.entry(para_id)
+				.or_insert_with(|| ValidatorGroup::new(validators));
+		}
 	}
 
 	let validators: Vec<_> = group_validators.into_iter().collect();
 	let no_one_is_assigned = validators.is_empty();
 	if no_one_is_assigned {
-		tracing::warn!(target: LOG_TARGET, "No validators assigned to our core.",);
+		tracing::warn!(target: LOG_TARGET, "No validators assigned to our cores.",);
 		return Ok(())
 	}
 	tracing::debug!(
 		target: LOG_TARGET,
 		?validators,
-		para_id = ?id,
+		?para_ids,
 		"Connecting to validators.",
 	);
 	connect_to_validators(ctx, validators).await;
@@ -1007,6 +1213,9 @@ pub(crate) async fn run<Context>(
 	local_peer_id: PeerId,
 	collator_pair: CollatorPair,
 	mut req_receiver: IncomingRequestReceiver<request_v1::CollationFetchingRequest>,
+	mut candidate_req_receiver: IncomingRequestReceiver<
+		request_v1::CandidateCollationFetchingRequest,
+	>,
 	metrics: Metrics,
 ) -> FatalResult<()>
 where
@@ -1020,7 +1229,8 @@ where
 
 	loop {
 		let recv_req = req_receiver.recv(|| vec![COST_INVALID_REQUEST]).fuse();
-		pin_mut!(recv_req);
+		let recv_candidate_req = candidate_req_receiver.recv(|| vec![COST_INVALID_REQUEST]).fuse();
+		pin_mut!(recv_req, recv_candidate_req);
 		select! {
 			msg = ctx.recv().fuse() => match msg.map_err(Fatal::SubsystemReceive)? {
 				FromOverseer::Communication { msg } => {
@@ -1047,7 +1257,7 @@ where
 					continue
 				};
 
-				if let Some(collation) = state.collations.get(&relay_parent) {
+				if let Some(collation) = state.requested_collation(&next) {
 					let receipt = collation.receipt.clone();
 					let pov = collation.pov.clone();
 
@@ -1055,24 +1265,34 @@ where
 				}
 			}
 			in_req = recv_req => {
-				match in_req {
-					Ok(req) => {
-						log_error(
-							handle_incoming_request(&mut ctx, &mut state, req).await,
-							"Handling incoming request"
-						)?;
-					}
-					Err(incoming::Error::Fatal(f)) => return Err(f.into()),
-					Err(incoming::Error::NonFatal(err)) => {
-						tracing::debug!(
-							target: LOG_TARGET,
-							?err,
-							"Decoding incoming request failed"
-						);
-						continue
-					}
-				}
+				let in_req = in_req.map(CollationRequest::RelayParent);
+				handle_incoming_request_result(&mut ctx, &mut state, in_req).await?;
+			}
+			in_req = recv_candidate_req => {
+				let in_req = in_req.map(CollationRequest::Candidate);
+				handle_incoming_request_result(&mut ctx, &mut state, in_req).await?;
 			}
 		}
 	}
 }
+
+/// Handle an incoming request for a collation, as far as it could be received.
+async fn handle_incoming_request_result<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	in_req: incoming::Result<CollationRequest>,
+) -> FatalResult<()>
+where
+	Context: SubsystemContext<Message = CollatorProtocolMessage>,
+	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
+{
+	match in_req {
+		Ok(req) =>
+			log_error(handle_incoming_request(ctx, state, req).await, "Handling incoming request"),
+		Err(incoming::Error::Fatal(f)) => Err(f.into()),
+		Err(incoming::Error::NonFatal(err)) => {
+			tracing::debug!(target: LOG_TARGET, ?err, "Decoding incoming request failed");
+			Ok(())
+		},
+	}
+}
@@ -16,7 +16,11 @@
 
 use super::*;
 
-use std::{collections::HashSet, sync::Arc, time::Duration};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+	time::Duration,
+};
 
 use assert_matches::assert_matches;
 use futures::{executor, future, Future, SinkExt};
@@ -181,6 +185,7 @@ type VirtualOverseer = test_helpers::TestSubsystemContextHandle<CollatorProtocol
 struct TestHarness {
 	virtual_overseer: VirtualOverseer,
 	req_cfg: sc_network::config::RequestResponseConfig,
+	candidate_req_cfg: sc_network::config::RequestResponseConfig,
 }
 
 fn test_harness<T: Future<Output = TestHarness>>(
@@ -199,13 +204,21 @@ fn test_harness<T: Future<Output = TestHarness>>(
 	let (context, virtual_overseer) = test_helpers::make_subsystem_context(pool.clone());
 
 	let (collation_req_receiver, req_cfg) = IncomingRequest::get_config_receiver();
+	let (candidate_req_receiver, candidate_req_cfg) = IncomingRequest::get_config_receiver();
 	let subsystem = async {
-		run(context, local_peer_id, collator_pair, collation_req_receiver, Default::default())
-			.await
-			.unwrap();
+		run(
+			context,
+			local_peer_id,
+			collator_pair,
+			collation_req_receiver,
+			candidate_req_receiver,
+			Default::default(),
+		)
+		.await
+		.unwrap();
 	};
 
-	let test_fut = test(TestHarness { virtual_overseer, req_cfg });
+	let test_fut = test(TestHarness { virtual_overseer, req_cfg, candidate_req_cfg });
 
 	futures::pin_mut!(test_fut);
 	futures::pin_mut!(subsystem);
@@ -351,6 +364,59 @@ async fn set_our_view(
 	);
 }
 
+/// Inform the subsystem about our new view, with the given availability cores per relay parent.
+///
+/// Answers the runtime requests of all paras we are collating on until we connect to validators.
+async fn set_our_view_with_cores(
+	virtual_overseer: &mut VirtualOverseer,
+	test_state: &TestState,
+	our_view: OurView,
+	cores: &HashMap<Hash, Vec<CoreState>>,
+) {
+	overseer_send(
+		virtual_overseer,
+		CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+			our_view,
+		)),
+	)
+	.await;
+
+	loop {
+		match overseer_recv(virtual_overseer).await {
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				relay_parent,
+				RuntimeApiRequest::AvailabilityCores(tx),
+			)) => {
+				tx.send(Ok(cores[&relay_parent].clone())).unwrap();
+			},
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionIndexForChild(tx),
+			)) => {
+				tx.send(Ok(test_state.current_session_index())).unwrap();
+			},
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionInfo(_, tx),
+			)) => {
+				tx.send(Ok(Some(test_state.session_info.clone()))).unwrap();
+			},
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::ValidatorGroups(tx),
+			)) => {
+				tx.send(Ok((
+					test_state.session_info.validator_groups.clone(),
+					test_state.group_rotation_info.clone(),
+				)))
+				.unwrap();
+			},
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ConnectToValidators { .. }) => break,
+			other => panic!("Unexpected message received: {:?}", other),
+		}
+	}
+}
+
 /// Result of [`distribute_collation`]
 struct DistributeCollation {
 	candidate: CandidateReceipt,
@@ -395,6 +461,7 @@ async fn connect_peer(
 		CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerConnected(
 			peer.clone(),
 			polkadot_node_network_protocol::ObservedRole::Authority,
+			PeerSet::Collation.get_main_version(),
 			authority_id.map(|v| HashSet::from([v])),
 		)),
 	)
@@ -424,6 +491,16 @@ async fn expect_declare_msg(
 	virtual_overseer: &mut VirtualOverseer,
 	test_state: &TestState,
 	peer: &PeerId,
+) {
+	expect_declare_msg_for_para(virtual_overseer, test_state, peer, test_state.para_id).await;
+}
+
+/// Check that the next received message is a `Declare` message for the given para.
+async fn expect_declare_msg_for_para(
+	virtual_overseer: &mut VirtualOverseer,
+	test_state: &TestState,
+	peer: &PeerId,
+	expected_para_id: ParaId,
 ) {
 	assert_matches!(
 		overseer_recv(virtual_overseer).await,
@@ -446,7 +523,7 @@ async fn expect_declare_msg(
 						&collator_id),
 					);
 					assert_eq!(collator_id, test_state.collator_pair.public());
-					assert_eq!(para_id, test_state.para_id);
+					assert_eq!(para_id, expected_para_id);
 				}
 			);
 		}
@@ -470,8 +547,9 @@ async fn expect_advertise_collation_msg(
 			assert_eq!(to[0], *peer);
 			assert_matches!(
 				wire_message,
-				protocol_v1::CollatorProtocolMessage::AdvertiseCollation(
+				protocol_v1::CollatorProtocolMessage::AdvertiseCollationCandidates(
 					relay_parent,
+					_,
 				) => {
 					assert_eq!(relay_parent, expected_relay_parent);
 				}
@@ -505,6 +583,7 @@ fn advertise_and_send_collation() {
 	test_harness(local_peer_id, collator_pair, |test_harness| async move {
 		let mut virtual_overseer = test_harness.virtual_overseer;
 		let mut req_cfg = test_harness.req_cfg;
+		let candidate_req_cfg = test_harness.candidate_req_cfg;
 
 		setup_system(&mut virtual_overseer, &test_state).await;
 
@@ -639,7 +718,115 @@ fn advertise_and_send_collation() {
 		.await;
 
 		expect_advertise_collation_msg(&mut virtual_overseer, &peer, test_state.relay_parent).await;
-		TestHarness { virtual_overseer, req_cfg }
+		TestHarness { virtual_overseer, req_cfg, candidate_req_cfg }
+	});
+}
+
+#[test]
+fn advertise_and_send_multiple_collations() {
+	let test_state = TestState::default();
+	let local_peer_id = test_state.local_peer_id.clone();
+	let collator_pair = test_state.collator_pair.clone();
+
+	test_harness(local_peer_id, collator_pair, |mut test_harness| async move {
+		let virtual_overseer = &mut test_harness.virtual_overseer;
+
+		let peer = test_state.current_group_validator_peer_ids()[0].clone();
+		let validator_id = test_state.current_group_validator_authority_ids()[0].clone();
+
+		setup_system(virtual_overseer, &test_state).await;
+
+		connect_peer(virtual_overseer, peer.clone(), Some(validator_id)).await;
+		expect_declare_msg(virtual_overseer, &test_state, &peer).await;
+
+		let DistributeCollation { candidate: candidate_a, .. } =
+			distribute_collation(virtual_overseer, &test_state).await;
+
+		send_peer_view_change(virtual_overseer, &peer, vec![test_state.relay_parent]).await;
+		assert_matches!(
+			overseer_recv(virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendCollationMessage(
+				to,
+				protocol_v1::CollationProtocol::CollatorProtocol(
+					protocol_v1::CollatorProtocolMessage::AdvertiseCollationCandidates(
+						relay_parent,
+						candidates,
+					),
+				),
+			)) => {
+				assert_eq!(to, vec![peer]);
+				assert_eq!(relay_parent, test_state.relay_parent);
+				assert_eq!(candidates, vec![candidate_a.hash()]);
+			}
+		);
+
+		// A second collation at the same relay parent is advertised as an additional candidate.
+		let pov_b = PoV { block_data: BlockData(vec![45, 46, 47]) };
+		let candidate_b = TestCandidateBuilder {
+			para_id: test_state.para_id,
+			relay_parent: test_state.relay_parent,
+			pov_hash: pov_b.hash(),
+			..Default::default()
+		}
+		.build();
+		overseer_send(
+			virtual_overseer,
+			CollatorProtocolMessage::DistributeCollation(candidate_b.clone(), pov_b.clone(), None),
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendCollationMessage(
+				to,
+				protocol_v1::CollationProtocol::CollatorProtocol(
+					protocol_v1::CollatorProtocolMessage::AdvertiseAdditionalCandidates(
+						relay_parent,
+						candidates,
+					),
+				),
+			)) => {
+				assert_eq!(to, vec![peer]);
+				assert_eq!(relay_parent, test_state.relay_parent);
+				assert_eq!(candidates, vec![candidate_b.hash()]);
+			}
+		);
+
+		// The second collation can be fetched by its candidate hash.
+		let (pending_response, rx) = oneshot::channel();
+		test_harness
+			.candidate_req_cfg
+			.inbound_queue
+			.as_mut()
+			.unwrap()
+			.send(RawIncomingRequest {
+				peer,
+				payload: CandidateCollationFetchingRequest {
+					relay_parent: test_state.relay_parent,
+					para_id: test_state.para_id,
+					candidate_hash: candidate_b.hash(),
+				}
+				.encode(),
+				pending_response,
+			})
+			.await
+			.unwrap();
+
+		assert_matches!(
+			rx.await,
+			Ok(full_response) => {
+				let CollationFetchingResponse::Collation(receipt, pov): CollationFetchingResponse
+					= CollationFetchingResponse::decode(
+						&mut full_response.result
+						.expect("We should have a proper answer").as_ref()
+				)
+				.expect("Decoding should work");
+				assert_eq!(receipt, candidate_b);
+				assert_eq!(pov, pov_b);
+			}
+		);
+
+		test_harness
 	});
 }
 
@@ -807,6 +994,106 @@ fn validator_reconnect_does_not_advertise_a_second_time() {
 	})
 }
 
+#[test]
+fn collating_on_multiple_paras_declares_the_para_assigned_at_the_relay_parent() {
+	let test_state = TestState::default();
+	let local_peer_id = test_state.local_peer_id.clone();
+	let collator_pair = test_state.collator_pair.clone();
+
+	test_harness(local_peer_id, collator_pair, |mut test_harness| async move {
+		let virtual_overseer = &mut test_harness.virtual_overseer;
+
+		let para_a = test_state.para_id;
+		let para_b = ParaId::from(2);
+		let relay_parent_a = test_state.relay_parent;
+		let relay_parent_b = Hash::repeat_byte(0xBB);
+
+		// Groups are not rotated, so group 0 is assigned to the first core and group 1 to the
+		// second one. The paras swap cores at the second relay parent.
+		let scheduled = |para_id| CoreState::Scheduled(ScheduledCore { para_id, collator: None });
+		let cores: HashMap<_, _> = vec![
+			(relay_parent_a, vec![scheduled(para_a), scheduled(para_b)]),
+			(relay_parent_b, vec![scheduled(para_b), scheduled(para_a)]),
+		]
+		.into_iter()
+		.collect();
+
+		// Alice is only part of group 0, Dave only of group 1.
+		let alice = test_state.validator_peer_id[0].clone();
+		let alice_id = test_state.session_info.discovery_keys[0].clone();
+		let dave = test_state.validator_peer_id[3].clone();
+		let dave_id = test_state.session_info.discovery_keys[3].clone();
+
+		overseer_send(virtual_overseer, CollatorProtocolMessage::CollateOn(para_a)).await;
+		overseer_send(virtual_overseer, CollatorProtocolMessage::CollateOn(para_b)).await;
+
+		set_our_view_with_cores(virtual_overseer, &test_state, our_view![relay_parent_a], &cores)
+			.await;
+
+		// Each validator learns about the para it is assigned to.
+		connect_peer(virtual_overseer, alice.clone(), Some(alice_id)).await;
+		expect_declare_msg_for_para(virtual_overseer, &test_state, &alice, para_a).await;
+
+		connect_peer(virtual_overseer, dave.clone(), Some(dave_id)).await;
+		expect_declare_msg_for_para(virtual_overseer, &test_state, &dave, para_b).await;
+
+		let pov_block = PoV { block_data: BlockData(vec![1]) };
+		let candidate = TestCandidateBuilder {
+			para_id: para_a,
+			relay_parent: relay_parent_a,
+			pov_hash: pov_block.hash(),
+			..Default::default()
+		}
+		.build();
+		overseer_send(
+			virtual_overseer,
+			CollatorProtocolMessage::DistributeCollation(candidate, pov_block, None),
+		)
+		.await;
+
+		// Only the validator assigned to `para_a` gets the collation advertised.
+		send_peer_view_change(virtual_overseer, &alice, vec![relay_parent_a]).await;
+		expect_advertise_collation_msg(virtual_overseer, &alice, relay_parent_a).await;
+
+		send_peer_view_change(virtual_overseer, &dave, vec![relay_parent_a]).await;
+		assert!(overseer_recv_with_timeout(virtual_overseer, TIMEOUT).await.is_none());
+
+		set_our_view_with_cores(
+			virtual_overseer,
+			&test_state,
+			our_view![relay_parent_a, relay_parent_b],
+			&cores,
+		)
+		.await;
+
+		let pov_block = PoV { block_data: BlockData(vec![2]) };
+		let candidate = TestCandidateBuilder {
+			para_id: para_b,
+			relay_parent: relay_parent_b,
+			pov_hash: pov_block.hash(),
+			..Default::default()
+		}
+		.build();
+		overseer_send(
+			virtual_overseer,
+			CollatorProtocolMessage::DistributeCollation(candidate, pov_block, None),
+		)
+		.await;
+
+		// Alice is assigned to `para_b` at the second relay parent, so we re-declare first.
+		send_peer_view_change(virtual_overseer, &alice, vec![relay_parent_a, relay_parent_b])
+			.await;
+		expect_declare_msg_for_para(virtual_overseer, &test_state, &alice, para_b).await;
+		expect_advertise_collation_msg(virtual_overseer, &alice, relay_parent_b).await;
+
+		// Dave is assigned to `para_a` at the second relay parent, which has no collation there.
+		send_peer_view_change(virtual_overseer, &dave, vec![relay_parent_a, relay_parent_b])
+			.await;
+		assert!(overseer_recv_with_timeout(virtual_overseer, TIMEOUT).await.is_none());
+		test_harness
+	})
+}
+
 #[test]
 fn collators_reject_declare_messages() {
 	let test_state = TestState::default();
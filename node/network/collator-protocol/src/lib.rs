@@ -20,10 +20,11 @@
 #![deny(missing_docs, unused_crate_dependencies)]
 #![recursion_limit = "256"]
 
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use futures::{FutureExt, TryFutureExt};
 
+use kvdb::KeyValueDB;
 use sp_keystore::SyncCryptoStorePtr;
 
 use polkadot_node_network_protocol::{
@@ -35,7 +36,7 @@ use polkadot_primitives::v1::CollatorPair;
 use polkadot_subsystem::{
 	errors::SubsystemError,
 	messages::{CollatorProtocolMessage, NetworkBridgeMessage},
-	overseer, SpawnedSubsystem, SubsystemContext, SubsystemSender,
+	overseer, SpawnedSubsystem, SubsystemContext,
 };
 
 mod error;
@@ -46,6 +47,9 @@ mod validator_side;
 
 const LOG_TARGET: &'static str = "parachain::collator-protocol";
 
+/// The maximum number of collations a collator may advertise at a single relay parent.
+const MAX_CANDIDATES_PER_RELAY_PARENT: usize = 3;
+
 /// A collator eviction policy - how fast to evict collators which are inactive.
 #[derive(Debug, Clone, Copy)]
 pub struct CollatorEvictionPolicy {
@@ -64,6 +68,25 @@ impl Default for CollatorEvictionPolicy {
 	}
 }
 
+/// Configuration of the validator side of the collator protocol.
+#[derive(Debug, Clone, Copy)]
+pub struct ValidatorConfig {
+	/// The database column to persist the reputation of collators in.
+	pub col_reputation: u32,
+	/// How many advertisements of a single peer may be waiting to be fetched at a time.
+	/// Further advertisements of the peer are dropped.
+	pub max_pending_advertisements: usize,
+	/// How many collations are fetched in parallel per relay parent.
+	pub max_parallel_fetches: usize,
+}
+
+impl ValidatorConfig {
+	/// The default limit of pending advertisements per peer.
+	pub const DEFAULT_MAX_PENDING_ADVERTISEMENTS: usize = 4;
+	/// The default number of collations fetched in parallel per relay parent.
+	pub const DEFAULT_MAX_PARALLEL_FETCHES: usize = 2;
+}
+
 /// What side of the collator protocol is being engaged
 pub enum ProtocolSide {
 	/// Validators operate on the relay chain.
@@ -72,6 +95,10 @@ pub enum ProtocolSide {
 		keystore: SyncCryptoStorePtr,
 		/// An eviction policy for inactive peers or validators.
 		eviction_policy: CollatorEvictionPolicy,
+		/// The database to persist the reputation of collators in.
+		db: Arc<dyn KeyValueDB>,
+		/// Configuration of the validator side.
+		config: ValidatorConfig,
 		/// Prometheus metrics for validators.
 		metrics: validator_side::Metrics,
 	},
@@ -80,6 +107,7 @@ pub enum ProtocolSide {
 		PeerId,
 		CollatorPair,
 		IncomingRequestReceiver<request_v1::CollationFetchingRequest>,
+		IncomingRequestReceiver<request_v1::CandidateCollationFetchingRequest>,
 		collator_side::Metrics,
 	),
 }
@@ -104,10 +132,24 @@ impl CollatorProtocolSubsystem {
 		Context: SubsystemContext<Message = CollatorProtocolMessage>,
 	{
 		match self.protocol_side {
-			ProtocolSide::Validator { keystore, eviction_policy, metrics } =>
-				validator_side::run(ctx, keystore, eviction_policy, metrics).await,
-			ProtocolSide::Collator(local_peer_id, collator_pair, req_receiver, metrics) =>
-				collator_side::run(ctx, local_peer_id, collator_pair, req_receiver, metrics).await,
+			ProtocolSide::Validator { keystore, eviction_policy, db, config, metrics } =>
+				validator_side::run(ctx, keystore, eviction_policy, db, config, metrics).await,
+			ProtocolSide::Collator(
+				local_peer_id,
+				collator_pair,
+				req_receiver,
+				candidate_req_receiver,
+				metrics,
+			) =>
+				collator_side::run(
+					ctx,
+					local_peer_id,
+					collator_pair,
+					req_receiver,
+					candidate_req_receiver,
+					metrics,
+				)
+				.await,
 		}
 	}
 }
@@ -116,7 +158,6 @@ impl<Context> overseer::Subsystem<Context, SubsystemError> for CollatorProtocolS
 where
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
-	<Context as SubsystemContext>::Sender: SubsystemSender,
 {
 	fn start(self, ctx: Context) -> SpawnedSubsystem {
 		let future = self
@@ -131,7 +172,8 @@ where
 /// Modify the reputation of a peer based on its behavior.
 async fn modify_reputation<Context>(ctx: &mut Context, peer: PeerId, rep: Rep)
 where
-	Context: SubsystemContext,
+	Context: SubsystemContext<Message = CollatorProtocolMessage>,
+	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
 {
 	tracing::trace!(
 		target: LOG_TARGET,
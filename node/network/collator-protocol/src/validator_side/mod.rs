@@ -23,8 +23,9 @@ use futures::{
 	FutureExt, StreamExt,
 };
 use futures_timer::Delay;
+use kvdb::KeyValueDB;
 use std::{
-	collections::{hash_map::Entry, HashMap, HashSet},
+	collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
 	sync::Arc,
 	task::Poll,
 	time::{Duration, Instant},
@@ -37,26 +38,31 @@ use polkadot_node_network_protocol::{
 	request_response as req_res,
 	request_response::{
 		outgoing::{Recipient, RequestError},
-		v1::{CollationFetchingRequest, CollationFetchingResponse},
+		v1::{
+			CandidateCollationFetchingRequest, CollationFetchingRequest, CollationFetchingResponse,
+		},
 		OutgoingRequest, Requests,
 	},
 	v1 as protocol_v1, OurView, PeerId, UnifiedReputationChange as Rep, View,
 };
 use polkadot_node_primitives::{PoV, SignedFullStatement};
 use polkadot_node_subsystem_util::metrics::{self, prometheus};
-use polkadot_primitives::v1::{CandidateReceipt, CollatorId, Hash, Id as ParaId};
+use polkadot_primitives::v1::{CandidateHash, CandidateReceipt, CollatorId, Hash, Id as ParaId};
 use polkadot_subsystem::{
 	jaeger,
 	messages::{
 		CandidateBackingMessage, CollatorProtocolMessage, IfDisconnected, NetworkBridgeEvent,
-		NetworkBridgeMessage,
+		NetworkBridgeMessage, RuntimeApiMessage,
 	},
 	overseer, FromOverseer, OverseerSignal, PerLeafSpan, SubsystemContext, SubsystemSender,
 };
 
-use crate::error::FatalResult;
+use crate::{error::FatalResult, ValidatorConfig};
+
+use super::{modify_reputation, Result, LOG_TARGET, MAX_CANDIDATES_PER_RELAY_PARENT};
 
-use super::{modify_reputation, Result, LOG_TARGET};
+mod reputation;
+use reputation::ReputationStore;
 
 #[cfg(test)]
 mod tests;
@@ -69,7 +75,13 @@ const COST_NETWORK_ERROR: Rep = Rep::CostMinor("Some network error");
 const COST_INVALID_SIGNATURE: Rep = Rep::Malicious("Invalid network message signature");
 const COST_REPORT_BAD: Rep = Rep::Malicious("A collator was reported by another subsystem");
 const COST_WRONG_PARA: Rep = Rep::Malicious("A collator provided a collation for the wrong para");
+const COST_WRONG_CANDIDATE: Rep =
+	Rep::Malicious("A collator provided a different collation than the requested candidate");
 const COST_UNNEEDED_COLLATOR: Rep = Rep::CostMinor("An unneeded collator connected");
+const COST_BANNED_COLLATOR: Rep =
+	Rep::CostMajor("A collator with a bad persisted reputation connected");
+const COST_TOO_MANY_ADVERTISEMENTS: Rep =
+	Rep::CostMinor("A collator exceeded the limit of pending advertisements");
 const BENEFIT_NOTIFY_GOOD: Rep =
 	Rep::BenefitMinor("A collator was noted good by another subsystem");
 
@@ -131,6 +143,13 @@ impl Metrics {
 			.as_ref()
 			.map(|metrics| metrics.collator_peer_count.set(collator_peers as u64));
 	}
+
+	/// Note an advertisement which was dropped for the given reason.
+	fn on_advertisement_dropped(&self, reason: &'static str) {
+		if let Some(metrics) = &self.0 {
+			metrics.dropped_advertisements.with_label_values(&[reason]).inc();
+		}
+	}
 }
 
 #[derive(Clone)]
@@ -139,6 +158,7 @@ struct MetricsInner {
 	process_msg: prometheus::Histogram,
 	handle_collation_request_result: prometheus::Histogram,
 	collator_peer_count: prometheus::Gauge<prometheus::U64>,
+	dropped_advertisements: prometheus::CounterVec<prometheus::U64>,
 }
 
 impl metrics::Metrics for Metrics {
@@ -181,6 +201,16 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			dropped_advertisements: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_collator_protocol_dropped_advertisements_total",
+						"Number of collation advertisements dropped, by reason.",
+					),
+					&["reason"],
+				)?,
+				registry,
+			)?,
 		};
 
 		Ok(Metrics(Some(metrics)))
@@ -200,8 +230,8 @@ struct PerRequest {
 struct CollatingPeerState {
 	collator_id: CollatorId,
 	para_id: ParaId,
-	// Advertised relay parents.
-	advertisements: HashSet<Hash>,
+	// Advertised paras and relay parents, with the number of candidates advertised on each.
+	advertisements: HashMap<(ParaId, Hash), usize>,
 	last_active: Instant,
 }
 
@@ -218,6 +248,23 @@ enum AdvertisementError {
 	Duplicate,
 	OutOfOurView,
 	UndeclaredCollator,
+	NoCandidates,
+	NotAdvertised,
+	TooManyCandidates,
+}
+
+impl AdvertisementError {
+	/// The reason to report in the dropped advertisements metric.
+	fn reason(&self) -> &'static str {
+		match self {
+			Self::Duplicate => "duplicate",
+			Self::OutOfOurView => "out_of_view",
+			Self::UndeclaredCollator => "undeclared",
+			Self::NoCandidates => "no_candidates",
+			Self::NotAdvertised => "not_advertised",
+			Self::TooManyCandidates => "too_many_candidates",
+		}
+	}
 }
 
 #[derive(Debug)]
@@ -236,59 +283,74 @@ impl PeerData {
 	fn update_view(&mut self, new_view: View) {
 		let old_view = std::mem::replace(&mut self.view, new_view);
 		if let PeerState::Collating(ref mut peer_state) = self.state {
-			for removed in old_view.difference(&self.view) {
-				let _ = peer_state.advertisements.remove(&removed);
-			}
+			let removed = old_view.difference(&self.view).collect::<HashSet<_>>();
+			peer_state.advertisements.retain(|(_, a), _| !removed.contains(a));
 		}
 	}
 
 	/// Prune old advertisements relative to our view.
 	fn prune_old_advertisements(&mut self, our_view: &View) {
 		if let PeerState::Collating(ref mut peer_state) = self.state {
-			peer_state.advertisements.retain(|a| our_view.contains(a));
+			peer_state.advertisements.retain(|(_, a), _| our_view.contains(a));
 		}
 	}
 
-	/// Note an advertisement by the collator. Returns `true` if the advertisement was imported
-	/// successfully. Fails if the advertisement is duplicate, out of view, or the peer has not
-	/// declared itself a collator.
+	/// Note an advertisement of `num_candidates` candidates by the collator. `additional`
+	/// advertisements add candidates to an earlier advertisement on the same relay parent.
+	///
+	/// Returns the collator and para ids if the advertisement was imported successfully. Fails if
+	/// the advertisement is duplicate, out of view, exceeds the maximum number of candidates per
+	/// relay parent, or the peer has not declared itself a collator.
 	fn insert_advertisement(
 		&mut self,
 		on_relay_parent: Hash,
+		num_candidates: usize,
+		additional: bool,
 		our_view: &View,
 	) -> std::result::Result<(CollatorId, ParaId), AdvertisementError> {
 		match self.state {
 			PeerState::Connected(_) => Err(AdvertisementError::UndeclaredCollator),
 			_ if !our_view.contains(&on_relay_parent) => Err(AdvertisementError::OutOfOurView),
-			PeerState::Collating(ref mut state) =>
-				if state.advertisements.insert(on_relay_parent) {
-					state.last_active = Instant::now();
-					Ok((state.collator_id.clone(), state.para_id.clone()))
-				} else {
-					Err(AdvertisementError::Duplicate)
-				},
-		}
-	}
+			_ if num_candidates == 0 => Err(AdvertisementError::NoCandidates),
+			PeerState::Collating(ref mut state) => {
+				let key = (state.para_id, on_relay_parent);
+				let advertised = state.advertisements.get(&key).copied();
+				let total = match (advertised, additional) {
+					(None, false) => num_candidates,
+					(Some(_), false) => return Err(AdvertisementError::Duplicate),
+					(None, true) => return Err(AdvertisementError::NotAdvertised),
+					(Some(advertised), true) => advertised + num_candidates,
+				};
+				if total > MAX_CANDIDATES_PER_RELAY_PARENT {
+					return Err(AdvertisementError::TooManyCandidates)
+				}
 
-	/// Whether a peer is collating.
-	fn is_collating(&self) -> bool {
-		match self.state {
-			PeerState::Connected(_) => false,
-			PeerState::Collating(_) => true,
+				state.advertisements.insert(key, total);
+				state.last_active = Instant::now();
+				Ok((state.collator_id.clone(), state.para_id.clone()))
+			},
 		}
 	}
 
 	/// Note that a peer is now collating with the given collator and para ids.
 	///
-	/// This will overwrite any previous call to `set_collating` and should only be called
-	/// if `is_collating` is false.
+	/// This will overwrite any previous call to `set_collating`. The advertisements made for the
+	/// previously declared para are kept.
 	fn set_collating(&mut self, collator_id: CollatorId, para_id: ParaId) {
-		self.state = PeerState::Collating(CollatingPeerState {
-			collator_id,
-			para_id,
-			advertisements: HashSet::new(),
-			last_active: Instant::now(),
-		});
+		match self.state {
+			PeerState::Connected(_) =>
+				self.state = PeerState::Collating(CollatingPeerState {
+					collator_id,
+					para_id,
+					advertisements: HashMap::new(),
+					last_active: Instant::now(),
+				}),
+			PeerState::Collating(ref mut state) => {
+				state.collator_id = collator_id;
+				state.para_id = para_id;
+				state.last_active = Instant::now();
+			},
+		}
 	}
 
 	fn collator_id(&self) -> Option<&CollatorId> {
@@ -305,11 +367,12 @@ impl PeerData {
 		}
 	}
 
-	/// Whether the peer has advertised the given collation.
-	fn has_advertised(&self, relay_parent: &Hash) -> bool {
+	/// Whether the peer has advertised a collation of the given para on the given relay parent.
+	fn has_advertised(&self, para_id: ParaId, relay_parent: Hash) -> bool {
 		match self.state {
 			PeerState::Connected(_) => false,
-			PeerState::Collating(ref state) => state.advertisements.contains(relay_parent),
+			PeerState::Collating(ref state) =>
+				state.advertisements.contains_key(&(para_id, relay_parent)),
 		}
 	}
 
@@ -340,9 +403,9 @@ struct ActiveParas {
 }
 
 impl ActiveParas {
-	async fn assign_incoming(
+	async fn assign_incoming<Outgoing: From<RuntimeApiMessage>>(
 		&mut self,
-		sender: &mut impl SubsystemSender,
+		sender: &mut impl SubsystemSender<Outgoing>,
 		keystore: &SyncCryptoStorePtr,
 		new_relay_parents: impl IntoIterator<Item = Hash>,
 	) {
@@ -457,15 +520,23 @@ struct PendingCollation {
 	relay_parent: Hash,
 	para_id: ParaId,
 	peer_id: PeerId,
+	/// The advertised candidate, if the collator advertised its candidates.
+	candidate_hash: Option<CandidateHash>,
 	commitments_hash: Option<Hash>,
 }
 
 impl PendingCollation {
-	fn new(relay_parent: Hash, para_id: &ParaId, peer_id: &PeerId) -> Self {
+	fn new(
+		relay_parent: Hash,
+		para_id: &ParaId,
+		peer_id: &PeerId,
+		candidate_hash: Option<CandidateHash>,
+	) -> Self {
 		Self {
 			relay_parent,
 			para_id: para_id.clone(),
 			peer_id: peer_id.clone(),
+			candidate_hash,
 			commitments_hash: None,
 		}
 	}
@@ -479,10 +550,8 @@ type PendingCollationFetch =
 /// The status of the collations in [`CollationsPerRelayParent`].
 #[derive(Debug, Clone, Copy)]
 enum CollationStatus {
-	/// We are waiting for a collation to be advertised to us.
+	/// We are waiting for a collation to be advertised or fetched.
 	Waiting,
-	/// We are currently fetching a collation.
-	Fetching,
 	/// We are waiting that a collation is being validated.
 	WaitingOnValidation,
 	/// We have seconded a collation.
@@ -510,55 +579,118 @@ impl CollationStatus {
 struct CollationsPerRelayParent {
 	/// What is the current status in regards to a collation for this relay parent?
 	status: CollationStatus,
-	/// Collation currently being fetched.
+	/// Collators we are currently fetching collations from.
 	///
-	/// This is the currently last started fetch, which did not exceed `MAX_UNSHARED_DOWNLOAD_TIME`
-	/// yet.
-	waiting_collation: Option<CollatorId>,
+	/// These are the fetches which were started last and did not exceed
+	/// `MAX_UNSHARED_DOWNLOAD_TIME` yet, up to `max_parallel_fetches` of them.
+	fetching: Vec<CollatorId>,
 	/// Collation that were advertised to us, but we did not yet fetch.
 	unfetched_collations: Vec<(PendingCollation, CollatorId)>,
+	/// Candidates advertised to us, so we fetch each of them only once.
+	advertised_candidates: HashSet<CandidateHash>,
+	/// Collations fetched while another one was being validated, to be seconded in case the
+	/// other one turns out to be invalid.
+	prefetched_collations: VecDeque<(CollationEvent, CandidateReceipt, PoV)>,
 }
 
 impl CollationsPerRelayParent {
-	/// Returns the next collation to fetch from the `unfetched_collations`.
+	/// Returns the next collations to fetch from the `unfetched_collations`, which are the ones of
+	/// the collators ranked lowest by `rank`, so that at most `max_parallel_fetches` collations
+	/// are being fetched at once.
 	///
-	/// This will reset the status back to `Waiting` using [`CollationStatus::back_to_waiting`].
+	/// A passed in `finished_one` frees up its fetching slot and resets the status back to
+	/// `Waiting` using [`CollationStatus::back_to_waiting`].
 	///
-	/// Returns `Some(_)` if there is any collation to fetch, the `status` is not `Seconded` and
-	/// the passed in `finished_one` is the currently `waiting_collation`.
-	pub fn get_next_collation_to_fetch(
+	/// Returns nothing if the `status` is `Seconded` or the passed in `finished_one` is not being
+	/// fetched anymore.
+	pub fn get_next_collations_to_fetch<K: Ord>(
 		&mut self,
-		finished_one: Option<CollatorId>,
-	) -> Option<(PendingCollation, CollatorId)> {
-		// If finished one does not match waiting_collation, then we already dequeued another fetch
-		// to replace it.
-		if self.waiting_collation != finished_one {
-			tracing::trace!(
-				target: LOG_TARGET,
-				waiting_collation = ?self.waiting_collation,
-				?finished_one,
-				"Not proceeding to the next collation - has already been done."
-			);
-			return None
+		finished_one: Option<&CollatorId>,
+		max_parallel_fetches: usize,
+		rank: impl Fn(&CollatorId) -> K,
+	) -> Vec<(PendingCollation, CollatorId)> {
+		if let Some(finished_one) = finished_one {
+			match self.fetching.iter().position(|collator_id| collator_id == finished_one) {
+				Some(index) => {
+					self.fetching.remove(index);
+				},
+				None => {
+					// We already dequeued another fetch to replace it.
+					tracing::trace!(
+						target: LOG_TARGET,
+						fetching = ?self.fetching,
+						?finished_one,
+						"Not proceeding to the next collation - has already been done."
+					);
+					return Vec::new()
+				},
+			}
+			self.status.back_to_waiting();
 		}
-		self.status.back_to_waiting();
-
-		match self.status {
-			// We don't need to fetch any other collation when we already have seconded one.
-			CollationStatus::Seconded => None,
-			CollationStatus::Waiting => {
-				let next = self.unfetched_collations.pop();
-				self.waiting_collation = next.as_ref().map(|(_, collator_id)| collator_id.clone());
-				next
-			},
-			CollationStatus::WaitingOnValidation | CollationStatus::Fetching =>
-				unreachable!("We have reset the status above!"),
+
+		// We don't need to fetch any other collation when we already have seconded one.
+		if let CollationStatus::Seconded = self.status {
+			return Vec::new()
 		}
+
+		let mut next = Vec::new();
+		while self.fetching.len() < max_parallel_fetches {
+			let index = match self
+				.unfetched_collations
+				.iter()
+				.enumerate()
+				.min_by_key(|(_, (_, collator_id))| rank(collator_id))
+			{
+				Some((index, _)) => index,
+				None => break,
+			};
+			let (pending_collation, collator_id) = self.unfetched_collations.remove(index);
+			self.fetching.push(collator_id.clone());
+			next.push((pending_collation, collator_id));
+		}
+		next
+	}
+
+	/// How many advertisements of the given peer are waiting to be fetched.
+	fn pending_advertisements(&self, peer_id: &PeerId) -> usize {
+		self.unfetched_collations.iter().filter(|(pc, _)| &pc.peer_id == peer_id).count()
 	}
 }
 
-/// All state relevant for the validator side of the protocol lives here.
+/// Keeps track of when we last fetched a collation from each collator, so we can fetch from the
+/// advertising collators in a round-robin fashion instead of on a first-come basis.
 #[derive(Default)]
+struct FetchRotation {
+	/// The number of fetches so far.
+	fetches: u64,
+	/// The number of fetches at the time of the last fetch from a collator.
+	last_fetched: HashMap<CollatorId, u64>,
+}
+
+impl FetchRotation {
+	/// Note that we are fetching a collation from the given collator.
+	fn note_fetch(&mut self, collator_id: &CollatorId) {
+		self.fetches += 1;
+		self.last_fetched.insert(collator_id.clone(), self.fetches);
+	}
+
+	/// Forget about a collator, e.g. once it disconnected.
+	fn forget(&mut self, collator_id: &CollatorId) {
+		self.last_fetched.remove(collator_id);
+	}
+
+	/// Rank a collator for being fetched from next, lowest first: collators with a negative
+	/// reputation come last, before them those we never fetched from and the others in order of
+	/// their last fetch.
+	fn rank(&self, reputations: &ReputationStore, collator_id: &CollatorId) -> (bool, u64) {
+		(
+			reputations.get(collator_id) < 0,
+			self.last_fetched.get(collator_id).copied().unwrap_or(u64::MAX),
+		)
+	}
+}
+
+/// All state relevant for the validator side of the protocol lives here.
 struct State {
 	/// Our own view.
 	view: OurView,
@@ -597,6 +729,39 @@ struct State {
 
 	/// Keep track of all pending candidate collations
 	pending_candidates: HashMap<Hash, CollationEvent>,
+
+	/// Persisted reputation of collators.
+	reputations: ReputationStore,
+
+	/// When we fetched from which collator, for round-robin fairness between collators.
+	fetch_rotation: FetchRotation,
+
+	/// How many advertisements of a single peer may be waiting to be fetched.
+	max_pending_advertisements: usize,
+
+	/// How many collations are fetched in parallel per relay parent.
+	max_parallel_fetches: usize,
+}
+
+impl State {
+	fn new(db: Arc<dyn KeyValueDB>, config: ValidatorConfig, metrics: Metrics) -> Self {
+		State {
+			view: Default::default(),
+			active_paras: Default::default(),
+			peer_data: Default::default(),
+			requested_collations: Default::default(),
+			metrics,
+			span_per_relay_parent: Default::default(),
+			collation_fetches: Default::default(),
+			collation_fetch_timeouts: Default::default(),
+			collations_per_relay_parent: Default::default(),
+			pending_candidates: Default::default(),
+			reputations: ReputationStore::new(db, config.col_reputation),
+			fetch_rotation: Default::default(),
+			max_pending_advertisements: config.max_pending_advertisements,
+			max_parallel_fetches: config.max_parallel_fetches,
+		}
+	}
 }
 
 // O(n) search for collator ID by iterating through the peers map. This should be fast enough
@@ -631,7 +796,7 @@ async fn fetch_collation<Context>(
 {
 	let (tx, rx) = oneshot::channel();
 
-	let PendingCollation { relay_parent, para_id, peer_id, .. } = pc;
+	let PendingCollation { relay_parent, para_id, peer_id, candidate_hash, .. } = pc;
 
 	let timeout = |collator_id, relay_parent| async move {
 		Delay::new(MAX_UNSHARED_DOWNLOAD_TIME).await;
@@ -640,9 +805,10 @@ async fn fetch_collation<Context>(
 	state
 		.collation_fetch_timeouts
 		.push(timeout(id.clone(), relay_parent.clone()).boxed());
+	state.fetch_rotation.note_fetch(&id);
 
-	if state.peer_data.get(&peer_id).map_or(false, |d| d.has_advertised(&relay_parent)) {
-		request_collation(ctx, state, relay_parent, para_id, peer_id, tx).await;
+	if state.peer_data.get(&peer_id).map_or(false, |d| d.has_advertised(para_id, relay_parent)) {
+		request_collation(ctx, state, relay_parent, para_id, peer_id, candidate_hash, tx).await;
 	}
 
 	state.collation_fetches.push(rx.map(|r| ((id, pc), r)).boxed());
@@ -652,10 +818,13 @@ async fn fetch_collation<Context>(
 async fn report_collator<Context>(
 	ctx: &mut Context,
 	peer_data: &HashMap<PeerId, PeerData>,
+	reputations: &mut ReputationStore,
 	id: CollatorId,
 ) where
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
+	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
 {
+	reputations.modify(&id, reputation::COST_REPORTED);
 	if let Some(peer_id) = collator_peer_id(peer_data, &id) {
 		modify_reputation(ctx, peer_id, COST_REPORT_BAD).await;
 	}
@@ -665,11 +834,13 @@ async fn report_collator<Context>(
 async fn note_good_collation<Context>(
 	ctx: &mut Context,
 	peer_data: &HashMap<PeerId, PeerData>,
+	reputations: &mut ReputationStore,
 	id: CollatorId,
 ) where
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
 {
+	reputations.modify(&id, reputation::BENEFIT_SECONDED);
 	if let Some(peer_id) = collator_peer_id(peer_data, &id) {
 		modify_reputation(ctx, peer_id, BENEFIT_NOTIFY_GOOD).await;
 	}
@@ -703,9 +874,9 @@ async fn handle_peer_view_change(state: &mut State, peer_id: PeerId, view: View)
 	let peer_data = state.peer_data.entry(peer_id.clone()).or_default();
 
 	peer_data.update_view(view);
-	state
-		.requested_collations
-		.retain(|pc, _| pc.peer_id != peer_id || !peer_data.has_advertised(&pc.relay_parent));
+	state.requested_collations.retain(|pc, _| {
+		pc.peer_id != peer_id || !peer_data.has_advertised(pc.para_id, pc.relay_parent)
+	});
 
 	Ok(())
 }
@@ -722,6 +893,7 @@ async fn request_collation<Context>(
 	relay_parent: Hash,
 	para_id: ParaId,
 	peer_id: PeerId,
+	candidate_hash: Option<CandidateHash>,
 	result: oneshot::Sender<(CandidateReceipt, PoV)>,
 ) where
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
@@ -737,7 +909,7 @@ async fn request_collation<Context>(
 		);
 		return
 	}
-	let pending_collation = PendingCollation::new(relay_parent, &para_id, &peer_id);
+	let pending_collation = PendingCollation::new(relay_parent, &para_id, &peer_id, candidate_hash);
 	if state.requested_collations.contains_key(&pending_collation) {
 		tracing::warn!(
 			target: LOG_TARGET,
@@ -749,14 +921,25 @@ async fn request_collation<Context>(
 		return
 	}
 
-	let (full_request, response_recv) = OutgoingRequest::new(
-		Recipient::Peer(peer_id),
-		CollationFetchingRequest { relay_parent, para_id },
-	);
-	let requests = Requests::CollationFetching(full_request);
+	let (requests, response_recv) = match candidate_hash {
+		None => {
+			let (full_request, response_recv) = OutgoingRequest::new(
+				Recipient::Peer(peer_id),
+				CollationFetchingRequest { relay_parent, para_id },
+			);
+			(Requests::CollationFetching(full_request), response_recv.boxed())
+		},
+		Some(candidate_hash) => {
+			let (full_request, response_recv) = OutgoingRequest::new(
+				Recipient::Peer(peer_id),
+				CandidateCollationFetchingRequest { relay_parent, para_id, candidate_hash },
+			);
+			(Requests::CandidateCollationFetching(full_request), response_recv.boxed())
+		},
+	};
 
 	let per_request = PerRequest {
-		from_collator: response_recv.boxed().fuse(),
+		from_collator: response_recv.fuse(),
 		to_requester: result,
 		span: state
 			.span_per_relay_parent
@@ -764,15 +947,14 @@ async fn request_collation<Context>(
 			.map(|s| s.child("collation-request").with_para_id(para_id)),
 	};
 
-	state
-		.requested_collations
-		.insert(PendingCollation::new(relay_parent, &para_id, &peer_id), per_request);
+	state.requested_collations.insert(pending_collation, per_request);
 
 	tracing::debug!(
 		target: LOG_TARGET,
 		peer_id = %peer_id,
 		%para_id,
 		?relay_parent,
+		?candidate_hash,
 		"Requesting collation",
 	);
 
@@ -797,7 +979,7 @@ async fn process_incoming_peer_message<Context>(
 	use sp_runtime::traits::AppVerify;
 	match msg {
 		Declare(collator_id, para_id, signature) => {
-			if collator_peer_id(&state.peer_data, &collator_id).is_some() {
+			if collator_peer_id(&state.peer_data, &collator_id).map_or(false, |p| p != origin) {
 				modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
 				return
 			}
@@ -810,7 +992,11 @@ async fn process_incoming_peer_message<Context>(
 				},
 			};
 
-			if peer_data.is_collating() {
+			// Collators may declare themselves again to switch to another para they collate on, but
+			// must not change their collator id or declare the para they are already collating on.
+			if peer_data.collator_id().map_or(false, |c| c != &collator_id) ||
+				peer_data.collating_para() == Some(para_id)
+			{
 				modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
 				return
 			}
@@ -820,6 +1006,20 @@ async fn process_incoming_peer_message<Context>(
 				return
 			}
 
+			if state.reputations.is_banned(&collator_id) {
+				tracing::debug!(
+					target: LOG_TARGET,
+					peer_id = ?origin,
+					?collator_id,
+					?para_id,
+					"Declared as collator with bad reputation",
+				);
+
+				modify_reputation(ctx, origin.clone(), COST_BANNED_COLLATOR).await;
+				disconnect_peer(ctx, origin).await;
+				return
+			}
+
 			if state.active_paras.is_current(&para_id) {
 				tracing::debug!(
 					target: LOG_TARGET,
@@ -844,79 +1044,136 @@ async fn process_incoming_peer_message<Context>(
 				disconnect_peer(ctx, origin).await;
 			}
 		},
-		AdvertiseCollation(relay_parent) => {
-			let _span = state
-				.span_per_relay_parent
-				.get(&relay_parent)
-				.map(|s| s.child("advertise-collation"));
-			if !state.view.contains(&relay_parent) {
+		AdvertiseCollation(relay_parent) =>
+			handle_advertisement(ctx, state, origin, relay_parent, None, false).await,
+		AdvertiseCollationCandidates(relay_parent, candidates) =>
+			handle_advertisement(ctx, state, origin, relay_parent, Some(candidates), false).await,
+		AdvertiseAdditionalCandidates(relay_parent, candidates) =>
+			handle_advertisement(ctx, state, origin, relay_parent, Some(candidates), true).await,
+		CollationSeconded(_, _) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				peer_id = ?origin,
+				"Unexpected `CollationSeconded` message, decreasing reputation",
+			);
+		},
+	}
+}
+
+/// A collator advertised a collation, or specific `candidates` if given, on a relay parent.
+///
+/// `additional` candidates add to an earlier advertisement on the same relay parent.
+async fn handle_advertisement<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	origin: PeerId,
+	relay_parent: Hash,
+	candidates: Option<Vec<CandidateHash>>,
+	additional: bool,
+) where
+	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
+	Context: SubsystemContext<Message = CollatorProtocolMessage>,
+{
+	let _span = state
+		.span_per_relay_parent
+		.get(&relay_parent)
+		.map(|s| s.child("advertise-collation"));
+	if !state.view.contains(&relay_parent) {
+		tracing::debug!(
+			target: LOG_TARGET,
+			peer_id = ?origin,
+			?relay_parent,
+			"Advertise collation out of view",
+		);
+
+		state.metrics.on_advertisement_dropped("out_of_view");
+		modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
+		return
+	}
+
+	let pending_advertisements: usize = state
+		.collations_per_relay_parent
+		.values()
+		.map(|collations| collations.pending_advertisements(&origin))
+		.sum();
+	if pending_advertisements >= state.max_pending_advertisements {
+		tracing::debug!(
+			target: LOG_TARGET,
+			peer_id = ?origin,
+			?relay_parent,
+			pending_advertisements,
+			"Too many pending advertisements",
+		);
+
+		state.metrics.on_advertisement_dropped("too_many_pending");
+		modify_reputation(ctx, origin, COST_TOO_MANY_ADVERTISEMENTS).await;
+		return
+	}
+
+	let peer_data = match state.peer_data.get_mut(&origin) {
+		None => {
+			state.metrics.on_advertisement_dropped("unknown_peer");
+			modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
+			return
+		},
+		Some(p) => p,
+	};
+
+	let num_candidates = candidates.as_ref().map_or(1, Vec::len);
+	let (id, para_id) =
+		match peer_data.insert_advertisement(relay_parent, num_candidates, additional, &state.view) {
+			Ok(ids) => ids,
+			Err(error) => {
 				tracing::debug!(
 					target: LOG_TARGET,
 					peer_id = ?origin,
 					?relay_parent,
-					"Advertise collation out of view",
+					?error,
+					"Invalid advertisement",
 				);
 
+				state.metrics.on_advertisement_dropped(error.reason());
 				modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
 				return
-			}
-
-			let peer_data = match state.peer_data.get_mut(&origin) {
-				None => {
-					modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
-					return
-				},
-				Some(p) => p,
-			};
-
-			match peer_data.insert_advertisement(relay_parent, &state.view) {
-				Ok((id, para_id)) => {
-					tracing::debug!(
-						target: LOG_TARGET,
-						peer_id = ?origin,
-						%para_id,
-						?relay_parent,
-						"Received advertise collation",
-					);
+			},
+		};
 
-					let pending_collation = PendingCollation::new(relay_parent, &para_id, &origin);
+	tracing::debug!(
+		target: LOG_TARGET,
+		peer_id = ?origin,
+		%para_id,
+		?relay_parent,
+		?candidates,
+		"Received advertise collation",
+	);
 
-					let collations =
-						state.collations_per_relay_parent.entry(relay_parent).or_default();
+	let collations = state.collations_per_relay_parent.entry(relay_parent).or_default();
 
-					match collations.status {
-						CollationStatus::Fetching | CollationStatus::WaitingOnValidation =>
-							collations.unfetched_collations.push((pending_collation, id)),
-						CollationStatus::Waiting => {
-							collations.status = CollationStatus::Fetching;
-							collations.waiting_collation = Some(id.clone());
+	if let CollationStatus::Seconded = collations.status {
+		state.metrics.on_advertisement_dropped("already_seconded");
+		return
+	}
 
-							fetch_collation(ctx, state, pending_collation.clone(), id).await;
-						},
-						CollationStatus::Seconded => {},
-					}
-				},
-				Err(error) => {
-					tracing::debug!(
-						target: LOG_TARGET,
-						peer_id = ?origin,
-						?relay_parent,
-						?error,
-						"Invalid advertisement",
+	match candidates {
+		None => collations
+			.unfetched_collations
+			.push((PendingCollation::new(relay_parent, &para_id, &origin, None), id)),
+		Some(candidates) =>
+			for candidate_hash in candidates {
+				// Collators of the same para may advertise the same candidate.
+				if collations.advertised_candidates.insert(candidate_hash) {
+					let pending_collation = PendingCollation::new(
+						relay_parent,
+						&para_id,
+						&origin,
+						Some(candidate_hash),
 					);
-
-					modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
-				},
-			}
-		},
-		CollationSeconded(_, _) => {
-			tracing::warn!(
-				target: LOG_TARGET,
-				peer_id = ?origin,
-				"Unexpected `CollationSeconded` message, decreasing reputation",
-			);
-		},
+					collations.unfetched_collations.push((pending_collation, id.clone()));
+				}
+			},
 	}
+
+	dequeue_next_collation_and_fetch(ctx, state, relay_parent, None).await;
 }
 
 /// A leaf has become inactive so we want to
@@ -999,12 +1256,18 @@ where
 	use NetworkBridgeEvent::*;
 
 	match bridge_message {
-		PeerConnected(peer_id, _role, _) => {
+		PeerConnected(peer_id, _role, _version, _) => {
 			state.peer_data.entry(peer_id).or_default();
 			state.metrics.note_collator_peer_count(state.peer_data.len());
 		},
 		PeerDisconnected(peer_id) => {
-			state.peer_data.remove(&peer_id);
+			let collator_id =
+				state.peer_data.remove(&peer_id).and_then(|data| data.collator_id().cloned());
+			if let Some(collator_id) = collator_id {
+				if collator_peer_id(&state.peer_data, &collator_id).is_none() {
+					state.fetch_rotation.forget(&collator_id);
+				}
+			}
 			state.metrics.note_collator_peer_count(state.peer_data.len());
 		},
 		NewGossipTopology(..) => {
@@ -1053,7 +1316,7 @@ async fn process_msg<Context>(
 			);
 		},
 		ReportCollator(id) => {
-			report_collator(ctx, &state.peer_data, id).await;
+			report_collator(ctx, &state.peer_data, &mut state.reputations, id).await;
 		},
 		NetworkBridgeUpdateV1(event) => {
 			if let Err(e) = handle_network_msg(ctx, state, keystore, event).await {
@@ -1068,12 +1331,19 @@ async fn process_msg<Context>(
 			if let Some(collation_event) = state.pending_candidates.remove(&parent) {
 				let (collator_id, pending_collation) = collation_event;
 				let PendingCollation { relay_parent, peer_id, .. } = pending_collation;
-				note_good_collation(ctx, &state.peer_data, collator_id).await;
+				note_good_collation(ctx, &state.peer_data, &mut state.reputations, collator_id)
+					.await;
 				notify_collation_seconded(ctx, peer_id, relay_parent, stmt).await;
 
 				if let Some(collations) = state.collations_per_relay_parent.get_mut(&parent) {
 					collations.status = CollationStatus::Seconded;
+					collations.fetching.clear();
+					collations.unfetched_collations.clear();
+					collations.prefetched_collations.clear();
 				}
+
+				// Cancel all other fetches on the relay parent, we don't need them anymore.
+				state.requested_collations.retain(|pc, _| pc.relay_parent != parent);
 			} else {
 				tracing::debug!(
 					target: LOG_TARGET,
@@ -1100,9 +1370,17 @@ async fn process_msg<Context>(
 				Entry::Vacant(_) => return,
 			};
 
-			report_collator(ctx, &state.peer_data, id.clone()).await;
+			report_collator(ctx, &state.peer_data, &mut state.reputations, id.clone()).await;
 
-			dequeue_next_collation_and_fetch(ctx, state, parent, id).await;
+			let prefetched = state
+				.collations_per_relay_parent
+				.get_mut(&parent)
+				.and_then(|collations| collations.prefetched_collations.pop_front());
+			if let Some((collation_event, candidate_receipt, pov)) = prefetched {
+				second_or_prefetch(ctx, state, collation_event, candidate_receipt, pov).await;
+			}
+
+			dequeue_next_collation_and_fetch(ctx, state, parent, Some(id)).await;
 		},
 	}
 }
@@ -1131,13 +1409,15 @@ pub(crate) async fn run<Context>(
 	mut ctx: Context,
 	keystore: SyncCryptoStorePtr,
 	eviction_policy: crate::CollatorEvictionPolicy,
+	db: Arc<dyn KeyValueDB>,
+	config: ValidatorConfig,
 	metrics: Metrics,
 ) -> FatalResult<()>
 where
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
 {
-	let mut state = State { metrics, ..Default::default() };
+	let mut state = State::new(db, config, metrics);
 
 	let next_inactivity_stream = infinite_stream(ACTIVITY_POLL);
 	futures::pin_mut!(next_inactivity_stream);
@@ -1176,7 +1456,7 @@ where
 					?collator_id,
 					"Timeout hit - already seconded?"
 				);
-				dequeue_next_collation_and_fetch(&mut ctx, &mut state, relay_parent, collator_id).await;
+				dequeue_next_collation_and_fetch(&mut ctx, &mut state, relay_parent, Some(collator_id)).await;
 			}
 			_ = check_collations_stream.next() => {
 				let reputation_changes = poll_requests(
@@ -1219,20 +1499,29 @@ async fn poll_requests(
 	reputation_changes
 }
 
-/// Dequeue another collation and fetch.
+/// Dequeue further collations and fetch them, as far as there are free fetching slots.
 async fn dequeue_next_collation_and_fetch(
 	ctx: &mut (impl SubsystemContext<Message = CollatorProtocolMessage>
 	          + overseer::SubsystemContext<Message = CollatorProtocolMessage>),
 	state: &mut State,
 	relay_parent: Hash,
-	// The collator we tried to fetch from last.
-	previous_fetch: CollatorId,
+	// The collator we finished fetching from, if any.
+	previous_fetch: Option<CollatorId>,
 ) {
-	if let Some((next, id)) = state
+	let (fetch_rotation, reputations) = (&state.fetch_rotation, &state.reputations);
+	let max_parallel_fetches = state.max_parallel_fetches;
+	let next = state
 		.collations_per_relay_parent
 		.get_mut(&relay_parent)
-		.and_then(|c| c.get_next_collation_to_fetch(Some(previous_fetch)))
-	{
+		.map(|c| {
+			c.get_next_collations_to_fetch(
+				previous_fetch.as_ref(),
+				max_parallel_fetches,
+				|collator_id| fetch_rotation.rank(reputations, collator_id),
+			)
+		})
+		.unwrap_or_default();
+	for (next, id) in next {
 		tracing::debug!(
 			target: LOG_TARGET,
 			?relay_parent,
@@ -1270,12 +1559,13 @@ async fn handle_collation_fetched_result<Context>(
 				"Failed to fetch collation.",
 			);
 
-			dequeue_next_collation_and_fetch(ctx, state, relay_parent, collation_event.0).await;
+			dequeue_next_collation_and_fetch(ctx, state, relay_parent, Some(collation_event.0))
+				.await;
 			return
 		},
 	};
 
-	if let Some(collations) = state.collations_per_relay_parent.get_mut(&relay_parent) {
+	if let Some(collations) = state.collations_per_relay_parent.get(&relay_parent) {
 		if let CollationStatus::Seconded = collations.status {
 			tracing::debug!(
 				target: LOG_TARGET,
@@ -1284,26 +1574,58 @@ async fn handle_collation_fetched_result<Context>(
 			);
 			return
 		}
-		collations.status = CollationStatus::WaitingOnValidation;
 	}
 
-	if let Entry::Vacant(entry) = state.pending_candidates.entry(relay_parent) {
-		collation_event.1.commitments_hash = Some(candidate_receipt.commitments_hash);
-		ctx.send_message(CandidateBackingMessage::Second(
-			relay_parent.clone(),
-			candidate_receipt,
-			pov,
-		))
-		.await;
+	second_or_prefetch(ctx, state, collation_event, candidate_receipt, pov).await;
+}
 
-		entry.insert(collation_event);
-	} else {
-		tracing::trace!(
-			target: LOG_TARGET,
-			?relay_parent,
-			candidate = ?candidate_receipt.hash(),
-			"Trying to insert a pending candidate failed, because there is already one.",
-		)
+/// Have a fetched collation seconded, or keep it around in case there is already a collation
+/// being validated on the relay parent.
+async fn second_or_prefetch<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	mut collation_event: CollationEvent,
+	candidate_receipt: CandidateReceipt,
+	pov: PoV,
+) where
+	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
+	Context: SubsystemContext<Message = CollatorProtocolMessage>,
+{
+	let relay_parent = collation_event.1.relay_parent;
+	let collations = state.collations_per_relay_parent.get_mut(&relay_parent);
+
+	match state.pending_candidates.entry(relay_parent) {
+		Entry::Vacant(entry) => {
+			if let Some(collations) = collations {
+				collations.status = CollationStatus::WaitingOnValidation;
+			}
+
+			collation_event.1.commitments_hash = Some(candidate_receipt.commitments_hash);
+			ctx.send_message(CandidateBackingMessage::Second(
+				relay_parent.clone(),
+				candidate_receipt,
+				pov,
+			))
+			.await;
+
+			entry.insert(collation_event);
+		},
+		Entry::Occupied(_) => {
+			tracing::trace!(
+				target: LOG_TARGET,
+				?relay_parent,
+				candidate = ?candidate_receipt.hash(),
+				"Prefetched collation, as there is already a pending candidate.",
+			);
+
+			if let Some(collations) = collations {
+				collations.prefetched_collations.push_back((
+					collation_event,
+					candidate_receipt,
+					pov,
+				));
+			}
+		},
 	}
 }
 
@@ -1432,6 +1754,21 @@ async fn poll_collation_response(
 
 				CollationFetchResult::Error(Some(COST_WRONG_PARA))
 			},
+			Ok(CollationFetchingResponse::Collation(receipt, _))
+				if pending_collation
+					.candidate_hash
+					.map_or(false, |candidate_hash| candidate_hash != receipt.hash()) =>
+			{
+				tracing::debug!(
+					target: LOG_TARGET,
+					expected_candidate_hash = ?pending_collation.candidate_hash,
+					got_candidate_hash = ?receipt.hash(),
+					peer_id = ?pending_collation.peer_id,
+					"Got wrong candidate for requested collation."
+				);
+
+				CollationFetchResult::Error(Some(COST_WRONG_CANDIDATE))
+			},
 			Ok(CollationFetchingResponse::Collation(receipt, pov)) => {
 				tracing::debug!(
 					target: LOG_TARGET,
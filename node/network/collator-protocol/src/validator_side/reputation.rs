@@ -0,0 +1,190 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reputation of collators, keyed by their `CollatorId` and persisted across restarts.
+//!
+//! The reputation of peers in the network bridge is tied to their `PeerId` and is lost
+//! on reconnect, which makes it useless against collators misbehaving repeatedly.
+
+use std::{
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use kvdb::{DBTransaction, KeyValueDB};
+use lru::LruCache;
+use parity_scale_codec::{Decode, Encode};
+
+use polkadot_primitives::v1::CollatorId;
+
+use crate::LOG_TARGET;
+
+const REPUTATION_PREFIX: &[u8; 19] = b"collator_reputation";
+
+/// Reputation gained by a collator for each of its collations getting seconded.
+pub const BENEFIT_SECONDED: i32 = 1;
+/// Reputation lost by a collator for each report about it, e.g. for an invalid collation.
+pub const COST_REPORTED: i32 = -10;
+/// The maximum reputation, so collators can't build up credit to spend on misbehaving later.
+pub const MAX_REPUTATION: i32 = 100;
+/// Collators at or below this reputation are disconnected on declaration.
+pub const BANNED_REPUTATION: i32 = -100;
+/// Reputation moving back towards zero per hour without changes, so a ban expires
+/// after ten hours and credit doesn't last forever either.
+pub const DECAY_PER_HOUR: i32 = 10;
+
+/// Number of collator reputations kept in memory.
+const CACHE_SIZE: usize = 1024;
+
+const SECS_PER_HOUR: u64 = 60 * 60;
+
+/// A reputation as persisted, along with the time of its last decay in seconds since the
+/// unix epoch.
+#[derive(Debug, Clone, Copy, Encode, Decode)]
+struct StoredReputation {
+	reputation: i32,
+	updated_at: u64,
+}
+
+impl StoredReputation {
+	fn new(reputation: i32, now: u64) -> Self {
+		Self { reputation, updated_at: now }
+	}
+
+	/// The reputation after decaying for all full hours passed until `now`.
+	fn decayed(self, now: u64) -> Self {
+		let hours = now.saturating_sub(self.updated_at) / SECS_PER_HOUR;
+		if hours == 0 {
+			return self
+		}
+
+		let decay = hours.saturating_mul(DECAY_PER_HOUR as u64).min(i32::MAX as u64) as i32;
+		let reputation = if self.reputation < 0 {
+			self.reputation.saturating_add(decay).min(0)
+		} else {
+			self.reputation.saturating_sub(decay).max(0)
+		};
+		// Partial hours are kept, so frequent updates don't prevent the decay.
+		Self { reputation, updated_at: self.updated_at + hours * SECS_PER_HOUR }
+	}
+}
+
+/// Reputations of collators, cached in memory and written through to the database.
+pub struct ReputationStore {
+	db: Arc<dyn KeyValueDB>,
+	column: u32,
+	cache: LruCache<CollatorId, StoredReputation>,
+}
+
+impl ReputationStore {
+	/// Create a new store on top of the given database column.
+	pub fn new(db: Arc<dyn KeyValueDB>, column: u32) -> Self {
+		Self { db, column, cache: LruCache::new(CACHE_SIZE) }
+	}
+
+	/// Load the reputation of a collator into the cache and return it.
+	pub fn load(&mut self, collator: &CollatorId) -> i32 {
+		self.load_at(collator, unix_now())
+	}
+
+	/// The reputation of a collator, as far as it has been loaded already.
+	pub fn get(&self, collator: &CollatorId) -> i32 {
+		self.get_at(collator, unix_now())
+	}
+
+	/// Whether a collator has misbehaved so much that it must not be accepted anymore.
+	pub fn is_banned(&mut self, collator: &CollatorId) -> bool {
+		self.load(collator) <= BANNED_REPUTATION
+	}
+
+	/// Apply a reputation change to a collator and persist its new reputation.
+	pub fn modify(&mut self, collator: &CollatorId, change: i32) -> i32 {
+		self.modify_at(collator, change, unix_now())
+	}
+
+	pub(super) fn load_at(&mut self, collator: &CollatorId, now: u64) -> i32 {
+		self.load_stored(collator, now).reputation
+	}
+
+	pub(super) fn get_at(&self, collator: &CollatorId, now: u64) -> i32 {
+		self.cache.peek(collator).map_or(0, |stored| stored.decayed(now).reputation)
+	}
+
+	pub(super) fn modify_at(&mut self, collator: &CollatorId, change: i32, now: u64) -> i32 {
+		let stored = self.load_stored(collator, now);
+		let reputation = stored
+			.reputation
+			.saturating_add(change)
+			.clamp(BANNED_REPUTATION, MAX_REPUTATION);
+		let stored = StoredReputation { reputation, ..stored };
+		self.cache.put(collator.clone(), stored);
+
+		let mut tx = DBTransaction::new();
+		tx.put_vec(self.column, &reputation_key(collator), stored.encode());
+		if let Err(err) = self.db.write(tx) {
+			tracing::warn!(
+				target: LOG_TARGET,
+				?err,
+				?collator,
+				"Writing collator reputation failed",
+			);
+		}
+
+		reputation
+	}
+
+	fn load_stored(&mut self, collator: &CollatorId, now: u64) -> StoredReputation {
+		if let Some(stored) = self.cache.get(collator) {
+			let stored = stored.decayed(now);
+			self.cache.put(collator.clone(), stored);
+			return stored
+		}
+
+		let stored = match self.db.get(self.column, &reputation_key(collator)) {
+			Ok(Some(raw)) => StoredReputation::decode(&mut &raw[..])
+				.map(|stored| stored.decayed(now))
+				.unwrap_or_else(|err| {
+					tracing::warn!(
+						target: LOG_TARGET,
+						?err,
+						?collator,
+						"Corrupted collator reputation",
+					);
+					StoredReputation::new(0, now)
+				}),
+			Ok(None) => StoredReputation::new(0, now),
+			Err(err) => {
+				tracing::warn!(
+					target: LOG_TARGET,
+					?err,
+					?collator,
+					"Reading collator reputation failed",
+				);
+				StoredReputation::new(0, now)
+			},
+		};
+		self.cache.put(collator.clone(), stored);
+		stored
+	}
+}
+
+fn unix_now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+fn reputation_key(collator: &CollatorId) -> Vec<u8> {
+	(REPUTATION_PREFIX, collator).encode()
+}
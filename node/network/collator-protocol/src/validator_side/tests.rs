@@ -119,6 +119,22 @@ struct TestHarness {
 }
 
 fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarness) -> T) {
+	test_harness_with_db(Arc::new(kvdb_memorydb::create(1)), test_config(), test)
+}
+
+fn test_config() -> ValidatorConfig {
+	ValidatorConfig {
+		col_reputation: 0,
+		max_pending_advertisements: ValidatorConfig::DEFAULT_MAX_PENDING_ADVERTISEMENTS,
+		max_parallel_fetches: 1,
+	}
+}
+
+fn test_harness_with_db<T: Future<Output = VirtualOverseer>>(
+	db: Arc<dyn KeyValueDB>,
+	config: ValidatorConfig,
+	test: impl FnOnce(TestHarness) -> T,
+) {
 	let _ = env_logger::builder()
 		.is_test(true)
 		.filter(Some("polkadot_collator_protocol"), log::LevelFilter::Trace)
@@ -144,6 +160,8 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarne
 			inactive_collator: ACTIVITY_TIMEOUT,
 			undeclared: DECLARE_TIMEOUT,
 		},
+		db,
+		config,
 		Metrics::default(),
 	);
 
@@ -306,6 +324,7 @@ async fn connect_and_declare_collator(
 		CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerConnected(
 			peer.clone(),
 			ObservedRole::Full,
+			PeerSet::Collation.get_main_version(),
 			None,
 		)),
 	)
@@ -442,6 +461,236 @@ fn collator_reporting_works() {
 	});
 }
 
+// Test that the reputation of collators survives a restart.
+#[test]
+fn collator_reputation_is_persisted() {
+	let db: Arc<dyn KeyValueDB> = Arc::new(kvdb_memorydb::create(1));
+	let collator = CollatorPair::generate().0.public();
+
+	let mut reputations = ReputationStore::new(db.clone(), 0);
+	assert_eq!(reputations.modify(&collator, reputation::COST_REPORTED), reputation::COST_REPORTED);
+	assert_eq!(reputations.modify(&collator, reputation::BENEFIT_SECONDED), -9);
+
+	let mut reputations = ReputationStore::new(db, 0);
+	assert_eq!(reputations.get(&collator), 0, "nothing loaded yet");
+	assert_eq!(reputations.load(&collator), -9);
+	assert!(!reputations.is_banned(&collator));
+	assert_eq!(
+		reputations.modify(&collator, 10 * reputation::COST_REPORTED),
+		reputation::BANNED_REPUTATION,
+	);
+	assert!(reputations.is_banned(&collator));
+}
+
+// Test that reputations decay towards zero over time, so bans expire.
+#[test]
+fn collator_reputation_decays() {
+	const HOUR: u64 = 60 * 60;
+
+	let db: Arc<dyn KeyValueDB> = Arc::new(kvdb_memorydb::create(1));
+	let banned = CollatorPair::generate().0.public();
+	let trusted = CollatorPair::generate().0.public();
+	let start = 1_000_000;
+
+	let mut reputations = ReputationStore::new(db.clone(), 0);
+	reputations.modify_at(&banned, reputation::BANNED_REPUTATION, start);
+	reputations.modify_at(&trusted, reputation::MAX_REPUTATION, start);
+
+	// partial hours don't count, but aren't lost on updates either.
+	assert_eq!(reputations.get_at(&banned, start + HOUR - 1), reputation::BANNED_REPUTATION);
+	assert_eq!(
+		reputations.modify_at(&banned, 0, start + HOUR / 2),
+		reputation::BANNED_REPUTATION,
+	);
+	assert_eq!(
+		reputations.get_at(&banned, start + HOUR),
+		reputation::BANNED_REPUTATION + reputation::DECAY_PER_HOUR,
+	);
+	assert_eq!(
+		reputations.get_at(&trusted, start + 2 * HOUR),
+		reputation::MAX_REPUTATION - 2 * reputation::DECAY_PER_HOUR,
+	);
+
+	// the decay also applies to persisted reputations and stops at zero.
+	let mut reputations = ReputationStore::new(db, 0);
+	let expired = start + 10 * HOUR;
+	assert_eq!(reputations.load_at(&banned, expired), 0);
+	assert_eq!(reputations.load_at(&trusted, start + 100 * HOUR), 0);
+	assert_eq!(
+		reputations.modify_at(&banned, reputation::COST_REPORTED, expired),
+		reputation::COST_REPORTED,
+	);
+}
+
+// Test that collators are fetched from in a round-robin fashion, after those we fetched from
+// before and before those with a negative reputation.
+#[test]
+fn fetch_rotation_ranks_collators() {
+	let db: Arc<dyn KeyValueDB> = Arc::new(kvdb_memorydb::create(1));
+	let mut reputations = ReputationStore::new(db, 0);
+	let [first, second, unknown, bad] = [(); 4].map(|_| CollatorPair::generate().0.public());
+	reputations.modify(&bad, reputation::COST_REPORTED);
+
+	let mut rotation = FetchRotation::default();
+	rotation.note_fetch(&first);
+	rotation.note_fetch(&bad);
+	rotation.note_fetch(&second);
+
+	let rank = |rotation: &FetchRotation, collator_id: &CollatorId| {
+		rotation.rank(&reputations, collator_id)
+	};
+	assert!(rank(&rotation, &first) < rank(&rotation, &second));
+	assert!(rank(&rotation, &second) < rank(&rotation, &unknown));
+	assert!(rank(&rotation, &unknown) < rank(&rotation, &bad));
+
+	rotation.note_fetch(&first);
+	assert!(rank(&rotation, &second) < rank(&rotation, &first));
+
+	// forgotten collators are ranked like ones we never fetched from.
+	rotation.forget(&second);
+	assert!(rank(&rotation, &first) < rank(&rotation, &second));
+	assert_eq!(rank(&rotation, &second), rank(&rotation, &unknown));
+}
+
+// Test that advertisements are kept per para when a collator declares itself for another para.
+#[test]
+fn advertisements_are_kept_per_para() {
+	let collator_id = CollatorPair::generate().0.public();
+	let (para_a, para_b) = (ParaId::from(1), ParaId::from(2));
+	let relay_parent = Hash::repeat_byte(0x05);
+	let our_view = View::new(vec![relay_parent], 0);
+
+	let mut peer_data = PeerData::default();
+	peer_data.set_collating(collator_id.clone(), para_a);
+	assert_matches!(peer_data.insert_advertisement(relay_parent, 1, false, &our_view), Ok(_));
+
+	peer_data.set_collating(collator_id.clone(), para_b);
+	assert!(peer_data.has_advertised(para_a, relay_parent));
+	assert!(!peer_data.has_advertised(para_b, relay_parent));
+	assert_matches!(peer_data.insert_advertisement(relay_parent, 1, false, &our_view), Ok(_));
+
+	peer_data.set_collating(collator_id, para_a);
+	assert_matches!(
+		peer_data.insert_advertisement(relay_parent, 1, false, &our_view),
+		Err(AdvertisementError::Duplicate)
+	);
+}
+
+// Test that collators with a bad reputation are disconnected when declaring themselves.
+#[test]
+fn banned_collator_is_disconnected() {
+	let test_state = TestState::default();
+	let db: Arc<dyn KeyValueDB> = Arc::new(kvdb_memorydb::create(1));
+	let banned = test_state.collators[0].clone();
+
+	ReputationStore::new(db.clone(), 0).modify(&banned.public(), reputation::BANNED_REPUTATION);
+
+	test_harness_with_db(db, test_config(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			banned,
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(
+				NetworkBridgeMessage::ReportPeer(peer, rep),
+			) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_BANNED_COLLATOR);
+			}
+		);
+
+		assert_collator_disconnect(&mut virtual_overseer, peer_b).await;
+
+		virtual_overseer
+	});
+}
+
+// Test that a peer can't queue up more advertisements than allowed.
+#[test]
+fn pending_advertisements_are_limited() {
+	let test_state = TestState::default();
+	let config = ValidatorConfig { max_pending_advertisements: 1, ..test_config() };
+
+	test_harness_with_db(Arc::new(kvdb_memorydb::create(1)), config, |test_harness| async move {
+		let TestHarness { mut virtual_overseer } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+		let peer_c = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_c.clone(),
+			test_state.collators[1].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		advertise_collation(&mut virtual_overseer, peer_b.clone(), test_state.relay_parent).await;
+
+		let _response_channel = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		// Queued while the collation of `peer_b` is being fetched.
+		advertise_collation(&mut virtual_overseer, peer_c.clone(), test_state.relay_parent).await;
+
+		// Exceeds the limit of pending advertisements.
+		advertise_collation(&mut virtual_overseer, peer_c.clone(), test_state.relay_parent).await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(
+				NetworkBridgeMessage::ReportPeer(peer, rep),
+			) => {
+				assert_eq!(peer, peer_c);
+				assert_eq!(rep, COST_TOO_MANY_ADVERTISEMENTS);
+			}
+		);
+
+		virtual_overseer
+	});
+}
+
 // Test that we verify the signatures on `Declare` and `AdvertiseCollation` messages.
 #[test]
 fn collator_authentication_verification_works() {
@@ -457,6 +706,7 @@ fn collator_authentication_verification_works() {
 			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerConnected(
 				peer_b,
 				ObservedRole::Full,
+				PeerSet::Collation.get_main_version(),
 				None,
 			)),
 		)
@@ -944,6 +1194,7 @@ fn disconnect_if_no_declare() {
 			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerConnected(
 				peer_b.clone(),
 				ObservedRole::Full,
+				PeerSet::Collation.get_main_version(),
 				None,
 			)),
 		)
@@ -981,6 +1232,7 @@ fn disconnect_if_wrong_declare() {
 			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerConnected(
 				peer_b.clone(),
 				ObservedRole::Full,
+				PeerSet::Collation.get_main_version(),
 				None,
 			)),
 		)
@@ -1016,6 +1268,99 @@ fn disconnect_if_wrong_declare() {
 	})
 }
 
+#[test]
+fn collators_may_not_declare_their_para_again_or_change_their_id() {
+	let test_state = TestState::default();
+
+	test_harness(|test_harness| async move {
+		let TestHarness { mut virtual_overseer } = test_harness;
+
+		let pair = CollatorPair::generate().0;
+		let other_pair = CollatorPair::generate().0;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			pair.clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		// Declaring again for the same para is not accepted, but the collator keeps its state.
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerMessage(
+				peer_b.clone(),
+				protocol_v1::CollatorProtocolMessage::Declare(
+					pair.public(),
+					test_state.chain_ids[0],
+					pair.sign(&protocol_v1::declare_signature_payload(&peer_b)),
+				),
+			)),
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(
+				peer,
+				rep,
+			)) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_UNEXPECTED_MESSAGE);
+			}
+		);
+
+		advertise_collation(&mut virtual_overseer, peer_b.clone(), test_state.relay_parent).await;
+
+		assert_fetch_collation_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		// Declaring with another collator id is not.
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerMessage(
+				peer_b.clone(),
+				protocol_v1::CollatorProtocolMessage::Declare(
+					other_pair.public(),
+					test_state.chain_ids[0],
+					other_pair.sign(&protocol_v1::declare_signature_payload(&peer_b)),
+				),
+			)),
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(
+				peer,
+				rep,
+			)) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_UNEXPECTED_MESSAGE);
+			}
+		);
+
+		virtual_overseer
+	})
+}
+
 #[test]
 fn view_change_clears_old_collators() {
 	let mut test_state = TestState::default();
@@ -1063,3 +1408,203 @@ fn view_change_clears_old_collators() {
 		virtual_overseer
 	})
 }
+
+// Test that collations are fetched in parallel and a prefetched one is seconded once the first
+// one turned out to be invalid.
+#[test]
+fn parallel_fetches_and_prefetched_collation_on_invalid() {
+	let test_state = TestState::default();
+	let config = ValidatorConfig { max_parallel_fetches: 2, ..test_config() };
+
+	test_harness_with_db(Arc::new(kvdb_memorydb::create(1)), config, |test_harness| async move {
+		let TestHarness { mut virtual_overseer } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+		let peer_c = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_c.clone(),
+			test_state.collators[1].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		advertise_collation(&mut virtual_overseer, peer_b.clone(), test_state.relay_parent).await;
+		advertise_collation(&mut virtual_overseer, peer_c.clone(), test_state.relay_parent).await;
+
+		// Both collations are fetched at once.
+		let response_channel_b = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+		)
+		.await;
+		let response_channel_c = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		let pov_b = PoV { block_data: BlockData(vec![1]) };
+		let mut candidate_b =
+			dummy_candidate_receipt_bad_sig(dummy_hash(), Some(Default::default()));
+		candidate_b.descriptor.para_id = test_state.chain_ids[0];
+		candidate_b.descriptor.relay_parent = test_state.relay_parent;
+		candidate_b.descriptor.pov_hash = pov_b.hash();
+
+		let pov_c = PoV { block_data: BlockData(vec![2]) };
+		let mut candidate_c = candidate_b.clone();
+		candidate_c.descriptor.pov_hash = pov_c.hash();
+
+		response_channel_b
+			.send(Ok(
+				CollationFetchingResponse::Collation(candidate_b.clone(), pov_b.clone()).encode()
+			))
+			.expect("Sending response should succeed");
+
+		let receipt = assert_candidate_backing_second(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+			&pov_b,
+		)
+		.await;
+
+		// Kept around while the first collation is being validated.
+		response_channel_c
+			.send(Ok(
+				CollationFetchingResponse::Collation(candidate_c.clone(), pov_c.clone()).encode()
+			))
+			.expect("Sending response should succeed");
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::Invalid(test_state.relay_parent, receipt),
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(peer, rep)) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_REPORT_BAD);
+			}
+		);
+
+		// The prefetched collation is seconded without fetching it again.
+		let receipt = assert_candidate_backing_second(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+			&pov_c,
+		)
+		.await;
+		assert_eq!(receipt, candidate_c);
+
+		virtual_overseer
+	});
+}
+
+// Test that advertised candidates are fetched by their hash and the collator is punished for
+// providing a different one.
+#[test]
+fn advertised_candidates_are_fetched_by_hash() {
+	let test_state = TestState::default();
+
+	test_harness(|test_harness| async move {
+		let TestHarness { mut virtual_overseer } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		let candidate_hash = CandidateHash(Hash::repeat_byte(0xAA));
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerMessage(
+				peer_b.clone(),
+				protocol_v1::CollatorProtocolMessage::AdvertiseCollationCandidates(
+					test_state.relay_parent,
+					vec![candidate_hash],
+				),
+			)),
+		)
+		.await;
+
+		let response_channel = assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendRequests(
+				reqs,
+				IfDisconnected::ImmediateError,
+			)) => {
+				assert_matches!(
+					reqs.into_iter().next(),
+					Some(Requests::CandidateCollationFetching(req)) => {
+						assert_eq!(req.payload.relay_parent, test_state.relay_parent);
+						assert_eq!(req.payload.para_id, test_state.chain_ids[0]);
+						assert_eq!(req.payload.candidate_hash, candidate_hash);
+						req.pending_response
+					}
+				)
+			}
+		);
+
+		let mut candidate_a =
+			dummy_candidate_receipt_bad_sig(dummy_hash(), Some(Default::default()));
+		candidate_a.descriptor.para_id = test_state.chain_ids[0];
+		candidate_a.descriptor.relay_parent = test_state.relay_parent;
+		response_channel
+			.send(Ok(CollationFetchingResponse::Collation(
+				candidate_a,
+				PoV { block_data: BlockData(vec![]) },
+			)
+			.encode()))
+			.expect("Sending response should succeed");
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(peer, rep)) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_WRONG_CANDIDATE);
+			}
+		);
+
+		virtual_overseer
+	});
+}
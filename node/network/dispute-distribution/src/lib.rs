@@ -205,11 +205,15 @@ where
 	}
 
 	/// Handle overseer signals.
-	async fn handle_signals<Context: SubsystemContext>(
+	async fn handle_signals<Context>(
 		&mut self,
 		ctx: &mut Context,
 		signal: OverseerSignal,
-	) -> Result<SignalResult> {
+	) -> Result<SignalResult>
+	where
+		Context: SubsystemContext<Message = DisputeDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeDistributionMessage>,
+	{
 		match signal {
 			OverseerSignal::Conclude => return Ok(SignalResult::Conclude),
 			OverseerSignal::ActiveLeaves(update) => {
@@ -221,11 +225,15 @@ where
 	}
 
 	/// Handle `DisputeDistributionMessage`s.
-	async fn handle_subsystem_message<Context: SubsystemContext>(
+	async fn handle_subsystem_message<Context>(
 		&mut self,
 		ctx: &mut Context,
 		msg: DisputeDistributionMessage,
-	) -> Result<()> {
+	) -> Result<()>
+	where
+		Context: SubsystemContext<Message = DisputeDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeDistributionMessage>,
+	{
 		match msg {
 			DisputeDistributionMessage::SendDispute(dispute_msg) =>
 				self.disputes_sender.start_sender(ctx, &mut self.runtime, dispute_msg).await?,
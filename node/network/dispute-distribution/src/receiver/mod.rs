@@ -41,7 +41,8 @@ use polkadot_node_network_protocol::{
 use polkadot_node_primitives::DISPUTE_WINDOW;
 use polkadot_node_subsystem_util::{runtime, runtime::RuntimeInfo};
 use polkadot_subsystem::{
-	messages::{AllMessages, DisputeCoordinatorMessage, ImportStatementsResult},
+	messages::{DisputeCoordinatorMessage, ImportStatementsResult},
+	overseer::DisputeDistributionOutgoingMessages,
 	SubsystemSender,
 };
 
@@ -132,8 +133,9 @@ impl MuxedMessage {
 	}
 }
 
-impl<Sender: SubsystemSender, AD> DisputesReceiver<Sender, AD>
+impl<Sender, AD> DisputesReceiver<Sender, AD>
 where
+	Sender: SubsystemSender<DisputeDistributionOutgoingMessages>,
 	AD: AuthorityDiscovery,
 {
 	/// Create a new receiver which can be `run`.
@@ -265,15 +267,16 @@ where
 		let (pending_confirmation, confirmation_rx) = oneshot::channel();
 		let candidate_hash = candidate_receipt.hash();
 		self.sender
-			.send_message(AllMessages::DisputeCoordinator(
+			.send_message(
 				DisputeCoordinatorMessage::ImportStatements {
 					candidate_hash,
 					candidate_receipt,
 					session: valid_vote.0.session_index(),
 					statements: vec![valid_vote, invalid_vote],
 					pending_confirmation,
-				},
-			))
+				}
+				.into(),
+			)
 			.await;
 
 		self.pending_imports.push(peer, confirmation_rx, pending_response);
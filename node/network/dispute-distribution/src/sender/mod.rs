@@ -23,8 +23,8 @@ use polkadot_node_primitives::{CandidateVotes, DisputeMessage, SignedDisputeStat
 use polkadot_node_subsystem_util::runtime::RuntimeInfo;
 use polkadot_primitives::v1::{CandidateHash, DisputeStatement, Hash, SessionIndex};
 use polkadot_subsystem::{
-	messages::{AllMessages, DisputeCoordinatorMessage},
-	ActiveLeavesUpdate, SubsystemContext,
+	messages::{DisputeCoordinatorMessage, DisputeDistributionMessage},
+	overseer, ActiveLeavesUpdate, SubsystemContext,
 };
 
 /// For each ongoing dispute we have a `SendTask` which takes care of it.
@@ -79,12 +79,16 @@ impl DisputeSender {
 	}
 
 	/// Create a `SendTask` for a particular new dispute.
-	pub async fn start_sender<Context: SubsystemContext>(
+	pub async fn start_sender<Context>(
 		&mut self,
 		ctx: &mut Context,
 		runtime: &mut RuntimeInfo,
 		msg: DisputeMessage,
-	) -> Result<()> {
+	) -> Result<()>
+	where
+		Context: SubsystemContext<Message = DisputeDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeDistributionMessage>,
+	{
 		let req: DisputeRequest = msg.into();
 		let candidate_hash = req.0.candidate_receipt.hash();
 		match self.disputes.entry(candidate_hash) {
@@ -118,12 +122,16 @@ impl DisputeSender {
 	/// - Get new authorities to send messages to.
 	/// - Get rid of obsolete tasks and disputes.
 	/// - Get dispute sending started in case we missed one for some reason (e.g. on node startup)
-	pub async fn update_leaves<Context: SubsystemContext>(
+	pub async fn update_leaves<Context>(
 		&mut self,
 		ctx: &mut Context,
 		runtime: &mut RuntimeInfo,
 		update: ActiveLeavesUpdate,
-	) -> Result<()> {
+	) -> Result<()>
+	where
+		Context: SubsystemContext<Message = DisputeDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeDistributionMessage>,
+	{
 		let ActiveLeavesUpdate { activated, deactivated } = update;
 		let deactivated: HashSet<_> = deactivated.into_iter().collect();
 		self.active_heads.retain(|h| !deactivated.contains(h));
@@ -183,12 +191,16 @@ impl DisputeSender {
 	/// Call `start_sender` on all passed in disputes.
 	///
 	/// Recover necessary votes for building up `DisputeMessage` and start sending for all of them.
-	async fn start_send_for_dispute<Context: SubsystemContext>(
+	async fn start_send_for_dispute<Context>(
 		&mut self,
 		ctx: &mut Context,
 		runtime: &mut RuntimeInfo,
 		dispute: (SessionIndex, CandidateHash),
-	) -> Result<()> {
+	) -> Result<()>
+	where
+		Context: SubsystemContext<Message = DisputeDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeDistributionMessage>,
+	{
 		let (session_index, candidate_hash) = dispute;
 		// We need some relay chain head for context for receiving session info information:
 		let ref_head = self.active_sessions.values().next().ok_or(NonFatal::NoActiveHeads)?;
@@ -288,11 +300,15 @@ impl DisputeSender {
 	/// Make active sessions correspond to currently active heads.
 	///
 	/// Returns: true if sessions changed.
-	async fn refresh_sessions<Context: SubsystemContext>(
+	async fn refresh_sessions<Context>(
 		&mut self,
 		ctx: &mut Context,
 		runtime: &mut RuntimeInfo,
-	) -> Result<bool> {
+	) -> Result<bool>
+	where
+		Context: SubsystemContext<Message = DisputeDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeDistributionMessage>,
+	{
 		let new_sessions = get_active_session_indeces(ctx, runtime, &self.active_heads).await?;
 		let new_sessions_raw: HashSet<_> = new_sessions.keys().collect();
 		let old_sessions_raw: HashSet<_> = self.active_sessions.keys().collect();
@@ -306,11 +322,15 @@ impl DisputeSender {
 /// Retrieve the currently active sessions.
 ///
 /// List is all indices of all active sessions together with the head that was used for the query.
-async fn get_active_session_indeces<Context: SubsystemContext>(
+async fn get_active_session_indeces<Context>(
 	ctx: &mut Context,
 	runtime: &mut RuntimeInfo,
 	active_heads: &Vec<Hash>,
-) -> Result<HashMap<SessionIndex, Hash>> {
+) -> Result<HashMap<SessionIndex, Hash>>
+where
+	Context: SubsystemContext<Message = DisputeDistributionMessage>,
+	Context: overseer::SubsystemContext<Message = DisputeDistributionMessage>,
+{
 	let mut indeces = HashMap::new();
 	for head in active_heads {
 		let session_index = runtime.get_session_index(ctx.sender(), *head).await?;
@@ -320,26 +340,32 @@ async fn get_active_session_indeces<Context: SubsystemContext>(
 }
 
 /// Retrieve Set of active disputes from the dispute coordinator.
-async fn get_active_disputes<Context: SubsystemContext>(
+async fn get_active_disputes<Context>(
 	ctx: &mut Context,
-) -> NonFatalResult<Vec<(SessionIndex, CandidateHash)>> {
+) -> NonFatalResult<Vec<(SessionIndex, CandidateHash)>>
+where
+	Context: SubsystemContext<Message = DisputeDistributionMessage>,
+	Context: overseer::SubsystemContext<Message = DisputeDistributionMessage>,
+{
 	let (tx, rx) = oneshot::channel();
-	ctx.send_message(AllMessages::DisputeCoordinator(DisputeCoordinatorMessage::ActiveDisputes(
-		tx,
-	)))
-	.await;
+	ctx.send_message(DisputeCoordinatorMessage::ActiveDisputes(tx)).await;
 	rx.await.map_err(|_| NonFatal::AskActiveDisputesCanceled)
 }
 
 /// Get all locally available dispute votes for a given dispute.
-async fn get_candidate_votes<Context: SubsystemContext>(
+async fn get_candidate_votes<Context>(
 	ctx: &mut Context,
 	session_index: SessionIndex,
 	candidate_hash: CandidateHash,
-) -> NonFatalResult<Option<CandidateVotes>> {
+) -> NonFatalResult<Option<CandidateVotes>>
+where
+	Context: SubsystemContext<Message = DisputeDistributionMessage>,
+	Context: overseer::SubsystemContext<Message = DisputeDistributionMessage>,
+{
 	let (tx, rx) = oneshot::channel();
-	ctx.send_message(AllMessages::DisputeCoordinator(
-		DisputeCoordinatorMessage::QueryCandidateVotes(vec![(session_index, candidate_hash)], tx),
+	ctx.send_message(DisputeCoordinatorMessage::QueryCandidateVotes(
+		vec![(session_index, candidate_hash)],
+		tx,
 	))
 	.await;
 	rx.await
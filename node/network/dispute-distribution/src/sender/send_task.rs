@@ -31,8 +31,8 @@ use polkadot_primitives::v1::{
 	AuthorityDiscoveryId, CandidateHash, Hash, SessionIndex, ValidatorIndex,
 };
 use polkadot_subsystem::{
-	messages::{AllMessages, NetworkBridgeMessage},
-	SubsystemContext,
+	messages::{DisputeDistributionMessage, NetworkBridgeMessage},
+	overseer, SubsystemContext,
 };
 
 use super::error::{Fatal, Result};
@@ -102,14 +102,18 @@ impl TaskResult {
 
 impl SendTask {
 	/// Initiates sending a dispute message to peers.
-	pub async fn new<Context: SubsystemContext>(
+	pub async fn new<Context>(
 		ctx: &mut Context,
 		runtime: &mut RuntimeInfo,
 		active_sessions: &HashMap<SessionIndex, Hash>,
 		tx: mpsc::Sender<TaskFinish>,
 		request: DisputeRequest,
 		metrics: &Metrics,
-	) -> Result<Self> {
+	) -> Result<Self>
+	where
+		Context: SubsystemContext<Message = DisputeDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeDistributionMessage>,
+	{
 		let mut send_task =
 			Self { request, deliveries: HashMap::new(), has_failed_sends: false, tx };
 		send_task.refresh_sends(ctx, runtime, active_sessions, metrics).await?;
@@ -120,13 +124,17 @@ impl SendTask {
 	///
 	/// This function is called at construction and should also be called whenever a session change
 	/// happens and on a regular basis to ensure we are retrying failed attempts.
-	pub async fn refresh_sends<Context: SubsystemContext>(
+	pub async fn refresh_sends<Context>(
 		&mut self,
 		ctx: &mut Context,
 		runtime: &mut RuntimeInfo,
 		active_sessions: &HashMap<SessionIndex, Hash>,
 		metrics: &Metrics,
-	) -> Result<()> {
+	) -> Result<()>
+	where
+		Context: SubsystemContext<Message = DisputeDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeDistributionMessage>,
+	{
 		let new_authorities = self.get_relevant_validators(ctx, runtime, active_sessions).await?;
 
 		let add_authorities = new_authorities
@@ -197,12 +205,16 @@ impl SendTask {
 	///
 	/// This is all parachain validators of the session the candidate occurred and all authorities
 	/// of all currently active sessions, determined by currently active heads.
-	async fn get_relevant_validators<Context: SubsystemContext>(
+	async fn get_relevant_validators<Context>(
 		&self,
 		ctx: &mut Context,
 		runtime: &mut RuntimeInfo,
 		active_sessions: &HashMap<SessionIndex, Hash>,
-	) -> Result<HashSet<AuthorityDiscoveryId>> {
+	) -> Result<HashSet<AuthorityDiscoveryId>>
+	where
+		Context: SubsystemContext<Message = DisputeDistributionMessage>,
+		Context: overseer::SubsystemContext<Message = DisputeDistributionMessage>,
+	{
 		let ref_head = self.request.0.candidate_receipt.descriptor.relay_parent;
 		// Parachain validators:
 		let info = runtime
@@ -239,13 +251,17 @@ impl SendTask {
 /// Start sending of the given message to all given authorities.
 ///
 /// And spawn tasks for handling the response.
-async fn send_requests<Context: SubsystemContext>(
+async fn send_requests<Context>(
 	ctx: &mut Context,
 	tx: mpsc::Sender<TaskFinish>,
 	receivers: Vec<AuthorityDiscoveryId>,
 	req: DisputeRequest,
 	metrics: &Metrics,
-) -> Result<HashMap<AuthorityDiscoveryId, DeliveryStatus>> {
+) -> Result<HashMap<AuthorityDiscoveryId, DeliveryStatus>>
+where
+	Context: SubsystemContext<Message = DisputeDistributionMessage>,
+	Context: overseer::SubsystemContext<Message = DisputeDistributionMessage>,
+{
 	let mut statuses = HashMap::with_capacity(receivers.len());
 	let mut reqs = Vec::with_capacity(receivers.len());
 
@@ -269,7 +285,7 @@ async fn send_requests<Context: SubsystemContext>(
 	}
 
 	let msg = NetworkBridgeMessage::SendRequests(reqs, IfDisconnected::ImmediateError);
-	ctx.send_message(AllMessages::NetworkBridge(msg)).await;
+	ctx.send_message(msg).await;
 	Ok(statuses)
 }
 
@@ -23,17 +23,19 @@
 //! to be an order of sqrt of the validators. Our neighbors
 //! in this graph will be forwarded to the network bridge with
 //! the `NetworkBridgeMessage::NewGossipTopology` message.
+//! The arrangement of the topology is up to a [`TopologyStrategy`].
+//!
+//! It also periodically reports on our connectivity to the other authorities of the session.
 
 use std::{
 	collections::{HashMap, HashSet},
 	fmt,
+	sync::Arc,
 	time::{Duration, Instant},
 };
 
 use futures::{channel::oneshot, select, FutureExt as _};
 use futures_timer::Delay;
-use rand::{seq::SliceRandom as _, SeedableRng};
-use rand_chacha::ChaCha20Rng;
 
 use sc_network::Multiaddr;
 use sp_application_crypto::{AppKey, ByteArray};
@@ -51,6 +53,10 @@ use polkadot_node_subsystem::{
 	overseer, ActiveLeavesUpdate, FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext,
 	SubsystemError, SubsystemSender,
 };
+use polkadot_node_primitives::gossip_support::{
+	ConnectivityFailure, ConnectivityReport, GossipTopologyInfo, TopologyNeighbor,
+	UnreachableAuthority,
+};
 use polkadot_node_subsystem_util as util;
 use polkadot_primitives::v1::{AuthorityDiscoveryId, Hash, SessionIndex};
 
@@ -59,6 +65,9 @@ mod tests;
 
 mod metrics;
 
+mod topology;
+pub use topology::{GridNeighbors, LatencyAwareGrid, RandomGrid, TopologyStrategy};
+
 use metrics::Metrics;
 
 const LOG_TARGET: &str = "parachain::gossip-support";
@@ -78,7 +87,11 @@ const LOW_CONNECTIVITY_WARN_DELAY: Duration = Duration::from_secs(600);
 /// If connectivity is lower than this in percent, issue warning in logs.
 const LOW_CONNECTIVITY_WARN_THRESHOLD: usize = 90;
 
+/// How often the connectivity metrics are updated.
+const CONNECTIVITY_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
 /// The Gossip Support subsystem.
+#[derive(Clone)]
 pub struct GossipSupport<AD> {
 	keystore: SyncCryptoStorePtr,
 
@@ -106,19 +119,49 @@ pub struct GossipSupport<AD> {
 	///
 	/// Needed for efficient handling of disconnect events.
 	connected_authorities_by_peer_id: HashMap<PeerId, HashSet<AuthorityDiscoveryId>>,
+	/// Authorities connected to us for block sync, by `PeerId`.
+	sync_connected_authorities: HashMap<PeerId, HashSet<AuthorityDiscoveryId>>,
 	/// Authority discovery service.
 	authority_discovery: AD,
 
+	/// The strategy arranging the authorities into the gossip topology.
+	topology_strategy: Arc<dyn TopologyStrategy>,
+	/// The topology of the current session, if we are an authority.
+	topology: Option<SessionTopology>,
+
+	/// The other authorities of the current session, if we are an authority.
+	session_authorities: Option<(SessionIndex, Vec<AuthorityDiscoveryId>)>,
+
 	/// Subsystem metrics.
 	metrics: Metrics,
 }
 
+/// The gossip topology of a session.
+#[derive(Clone)]
+struct SessionTopology {
+	session_index: SessionIndex,
+	row_neighbors: Vec<AuthorityDiscoveryId>,
+	column_neighbors: Vec<AuthorityDiscoveryId>,
+}
+
 impl<AD> GossipSupport<AD>
 where
 	AD: AuthorityDiscovery,
 {
-	/// Create a new instance of the [`GossipSupport`] subsystem.
+	/// Create a new instance of the [`GossipSupport`] subsystem, arranging the gossip topology
+	/// in a [`RandomGrid`].
 	pub fn new(keystore: SyncCryptoStorePtr, authority_discovery: AD, metrics: Metrics) -> Self {
+		Self::with_topology_strategy(keystore, authority_discovery, metrics, Arc::new(RandomGrid))
+	}
+
+	/// Create a new instance of the [`GossipSupport`] subsystem, arranging the gossip topology
+	/// with the given strategy.
+	pub fn with_topology_strategy(
+		keystore: SyncCryptoStorePtr,
+		authority_discovery: AD,
+		metrics: Metrics,
+		topology_strategy: Arc<dyn TopologyStrategy>,
+	) -> Self {
 		// Initialize metrics to `0`.
 		metrics.on_is_not_authority();
 		metrics.on_is_not_parachain_validator();
@@ -131,7 +174,11 @@ where
 			resolved_authorities: HashMap::new(),
 			connected_authorities: HashMap::new(),
 			connected_authorities_by_peer_id: HashMap::new(),
+			sync_connected_authorities: HashMap::new(),
 			authority_discovery,
+			topology_strategy,
+			topology: None,
+			session_authorities: None,
 			metrics,
 		}
 	}
@@ -145,6 +192,7 @@ where
 			Delay::new(LOW_CONNECTIVITY_WARN_DELAY)
 		}
 		let mut next_connectivity_check = get_connectivity_check_delay().fuse();
+		let mut next_connectivity_report = Delay::new(CONNECTIVITY_REPORT_INTERVAL).fuse();
 		loop {
 			let message = select!(
 				_ = next_connectivity_check => {
@@ -152,6 +200,12 @@ where
 					next_connectivity_check = get_connectivity_check_delay().fuse();
 					continue
 				}
+				_ = next_connectivity_report => {
+					let report = self.connectivity_report();
+					self.metrics.on_connectivity_report(report.as_ref());
+					next_connectivity_report = Delay::new(CONNECTIVITY_REPORT_INTERVAL).fuse();
+					continue
+				}
 				result = ctx.recv().fuse() =>
 					match result {
 						Ok(message) => message,
@@ -169,6 +223,24 @@ where
 				FromOverseer::Communication {
 					msg: GossipSupportMessage::NetworkBridgeUpdateV1(ev),
 				} => self.handle_connect_disconnect(ev),
+				FromOverseer::Communication { msg: GossipSupportMessage::GetTopology(tx) } => {
+					let _ = tx.send(self.topology_info());
+				},
+				FromOverseer::Communication {
+					msg: GossipSupportMessage::PeerSyncConnected { peer, authority_ids },
+				} => {
+					self.sync_connected_authorities.insert(peer, authority_ids);
+				},
+				FromOverseer::Communication {
+					msg: GossipSupportMessage::PeerSyncDisconnected(peer),
+				} => {
+					self.sync_connected_authorities.remove(&peer);
+				},
+				FromOverseer::Communication {
+					msg: GossipSupportMessage::GetConnectivityReport(tx),
+				} => {
+					let _ = tx.send(self.connectivity_report());
+				},
 				FromOverseer::Signal(OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
 					activated,
 					..
@@ -234,7 +306,17 @@ where
 				self.issue_connection_request(ctx, other_authorities).await;
 
 				if is_new_session {
-					update_gossip_topology(ctx, our_index, all_authorities, relay_parent).await?;
+					let (row_neighbors, column_neighbors) = update_gossip_topology(
+						ctx,
+						&*self.topology_strategy,
+						our_index,
+						all_authorities,
+						&self.resolved_authorities,
+						relay_parent,
+					)
+					.await?;
+					self.topology =
+						Some(SessionTopology { session_index, row_neighbors, column_neighbors });
 					self.update_authority_status_metrics(leaf, ctx.sender()).await?;
 				}
 			}
@@ -242,19 +324,17 @@ where
 		Ok(())
 	}
 
-	async fn update_authority_status_metrics(
+	/// Update the authority status metrics and remember the other authorities of the session.
+	async fn update_authority_status_metrics<Outgoing: From<RuntimeApiMessage>>(
 		&mut self,
 		leaf: Hash,
-		sender: &mut impl SubsystemSender,
+		sender: &mut impl SubsystemSender<Outgoing>,
 	) -> Result<(), util::Error> {
-		if let Some(session_info) = util::request_session_info(
-			leaf,
-			self.last_session_index
-				.expect("Last session index is always set on every session index change"),
-			sender,
-		)
-		.await
-		.await??
+		let session_index = self
+			.last_session_index
+			.expect("Last session index is always set on every session index change");
+		if let Some(session_info) =
+			util::request_session_info(leaf, session_index, sender).await.await??
 		{
 			let maybe_index = match ensure_i_am_an_authority(
 				&self.keystore,
@@ -269,6 +349,7 @@ where
 				Err(util::Error::NotAValidator) => {
 					self.metrics.on_is_not_authority();
 					self.metrics.on_is_not_parachain_validator();
+					self.session_authorities = None;
 					None
 				},
 				// Don't update on runtime errors.
@@ -276,6 +357,10 @@ where
 			};
 
 			if let Some(validator_index) = maybe_index {
+				let mut other_authorities = session_info.discovery_keys;
+				other_authorities.remove(validator_index);
+				self.session_authorities = Some((session_index, other_authorities));
+
 				// The subset of authorities participating in parachain consensus.
 				let parachain_validators_this_session = session_info.validators;
 
@@ -360,7 +445,7 @@ where
 
 	fn handle_connect_disconnect(&mut self, ev: NetworkBridgeEvent<GossipSuppportNetworkMessage>) {
 		match ev {
-			NetworkBridgeEvent::PeerConnected(peer_id, _, o_authority) => {
+			NetworkBridgeEvent::PeerConnected(peer_id, _, _, o_authority) => {
 				if let Some(authority_ids) = o_authority {
 					authority_ids.iter().for_each(|a| {
 						self.connected_authorities.insert(a.clone(), peer_id);
@@ -385,6 +470,62 @@ where
 		}
 	}
 
+	/// The topology of the current session along with the connection status of our neighbors.
+	fn topology_info(&self) -> Option<GossipTopologyInfo> {
+		let topology = self.topology.as_ref()?;
+		let neighbor = |authority_id: &AuthorityDiscoveryId| TopologyNeighbor {
+			authority_id: authority_id.clone(),
+			resolved: self.resolved_authorities.contains_key(authority_id),
+			peer_id: self.connected_authorities.get(authority_id).map(|p| p.to_base58()),
+		};
+
+		Some(GossipTopologyInfo {
+			session_index: topology.session_index,
+			strategy: self.topology_strategy.name().to_owned(),
+			row: topology.row_neighbors.iter().map(neighbor).collect(),
+			column: topology.column_neighbors.iter().map(neighbor).collect(),
+		})
+	}
+
+	/// Our connectivity to the other authorities of the current session.
+	///
+	/// Authorities connected to us for block sync, but not on the validation peer set, are
+	/// considered not to support our version of the validation protocol. Other authorities we are
+	/// not connected to are considered to have failed to be dialed as long as their addresses were
+	/// resolved, which includes authorities we are still dialing.
+	fn connectivity_report(&self) -> Option<ConnectivityReport> {
+		let (session_index, authorities) = self.session_authorities.as_ref()?;
+		let mut resolved = 0;
+		let mut connected = 0;
+		let mut unreachable = Vec::new();
+		for authority_id in authorities {
+			let is_resolved = self.resolved_authorities.contains_key(authority_id);
+			if is_resolved {
+				resolved += 1;
+			}
+
+			let reason = if self.connected_authorities.contains_key(authority_id) {
+				connected += 1;
+				continue
+			} else if self.sync_connected_authorities.values().any(|a| a.contains(authority_id)) {
+				ConnectivityFailure::ProtocolMismatch
+			} else if is_resolved {
+				ConnectivityFailure::DialFailure
+			} else {
+				ConnectivityFailure::UnresolvedAddress
+			};
+			unreachable.push(UnreachableAuthority { authority_id: authority_id.clone(), reason });
+		}
+
+		Some(ConnectivityReport {
+			session_index: *session_index,
+			authorities: authorities.len() as u32,
+			resolved,
+			connected,
+			unreachable,
+		})
+	}
+
 	/// Check connectivity and report on it in logs.
 	fn check_connectivity(&mut self) {
 		let absolute_connected = self.connected_authorities.len();
@@ -451,16 +592,20 @@ async fn ensure_i_am_an_authority(
 /// and form a matrix where each validator is connected to all validators in its row and column.
 /// This is similar to `[web3]` research proposed topology, except for the groups are not parachain
 /// groups (because not all validators are parachain validators and the group size is small),
-/// but formed randomly via BABE randomness from two epochs ago.
+/// but formed by the `strategy`, randomly via BABE randomness from two epochs ago by default.
 /// This limits the amount of gossip peers to 2 * `sqrt(len)` and ensures the diameter of 2.
 ///
+/// Returns our row and column neighbors.
+///
 /// [web3]: https://research.web3.foundation/en/latest/polkadot/networking/3-avail-valid.html#topology
 async fn update_gossip_topology<Context>(
 	ctx: &mut Context,
+	strategy: &dyn TopologyStrategy,
 	our_index: usize,
 	authorities: Vec<AuthorityDiscoveryId>,
+	addresses: &HashMap<AuthorityDiscoveryId, HashSet<Multiaddr>>,
 	relay_parent: Hash,
-) -> Result<(), util::Error>
+) -> Result<(Vec<AuthorityDiscoveryId>, Vec<AuthorityDiscoveryId>), util::Error>
 where
 	Context: SubsystemContext<Message = GossipSupportMessage>,
 	Context: overseer::SubsystemContext<Message = GossipSupportMessage>,
@@ -482,27 +627,25 @@ where
 		sp_core::blake2_256(&subject)
 	};
 
-	// shuffle the indices
-	let mut rng: ChaCha20Rng = SeedableRng::from_seed(random_seed);
-	let len = authorities.len();
-	let mut indices: Vec<usize> = (0..len).collect();
-	indices.shuffle(&mut rng);
-	let our_shuffled_position = indices
-		.iter()
-		.position(|i| *i == our_index)
-		.expect("our_index < len; indices contains it; qed");
-
-	let neighbors = matrix_neighbors(our_shuffled_position, len);
-	let our_neighbors = neighbors.map(|i| authorities[indices[i]].clone()).collect();
+	let GridNeighbors { row, column } =
+		strategy.neighbors(our_index, &authorities, addresses, random_seed);
+	let row_neighbors: Vec<_> = row.into_iter().map(|i| authorities[i].clone()).collect();
+	let column_neighbors: Vec<_> = column.into_iter().map(|i| authorities[i].clone()).collect();
 
-	ctx.send_message(NetworkBridgeMessage::NewGossipTopology { our_neighbors })
-		.await;
+	ctx.send_message(NetworkBridgeMessage::NewGossipTopology {
+		row_neighbors: row_neighbors.clone(),
+		column_neighbors: column_neighbors.clone(),
+	})
+	.await;
 
-	Ok(())
+	Ok((row_neighbors, column_neighbors))
 }
 
 /// Compute our row and column neighbors in a matrix
-fn matrix_neighbors(our_index: usize, len: usize) -> impl Iterator<Item = usize> {
+fn matrix_neighbors(
+	our_index: usize,
+	len: usize,
+) -> (impl Iterator<Item = usize>, impl Iterator<Item = usize>) {
 	assert!(our_index < len, "our_index is computed using `enumerate`; qed");
 
 	// e.g. for size 11 the matrix would be
@@ -520,7 +663,10 @@ fn matrix_neighbors(our_index: usize, len: usize) -> impl Iterator<Item = usize>
 	let row_neighbors = our_row * sqrt..std::cmp::min(our_row * sqrt + sqrt, len);
 	let column_neighbors = (our_column..len).step_by(sqrt);
 
-	row_neighbors.chain(column_neighbors).filter(move |i| *i != our_index)
+	(
+		row_neighbors.filter(move |i| *i != our_index),
+		column_neighbors.filter(move |i| *i != our_index),
+	)
 }
 
 impl<Context, AD> overseer::Subsystem<Context, SubsystemError> for GossipSupport<AD>
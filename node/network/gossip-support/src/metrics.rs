@@ -18,9 +18,10 @@ use polkadot_node_subsystem_util::{
 	metrics,
 	metrics::{
 		prometheus,
-		prometheus::{Gauge, PrometheusError, Registry, U64},
+		prometheus::{Gauge, GaugeVec, Opts, PrometheusError, Registry, U64},
 	},
 };
+use polkadot_node_primitives::gossip_support::{ConnectivityFailure, ConnectivityReport};
 
 /// Dispute Distribution metrics.
 #[derive(Clone, Default)]
@@ -32,6 +33,12 @@ struct MetricsInner {
 	is_authority: Gauge<U64>,
 	/// Tracks authority status for parachain approval checking.
 	is_parachain_validator: Gauge<U64>,
+	/// Percentage of the other authorities of the session with resolved addresses.
+	authorities_resolved_percent: Gauge<U64>,
+	/// Percentage of the other authorities of the session we are connected to.
+	authorities_connected_percent: Gauge<U64>,
+	/// Authorities of the session we are not connected to, by reason.
+	unreachable_authorities: GaugeVec<U64>,
 }
 
 impl Metrics {
@@ -68,6 +75,41 @@ impl Metrics {
 			metrics.is_parachain_validator.set(0);
 		}
 	}
+
+	/// Update the connectivity metrics with the latest report, if we are an authority.
+	pub fn on_connectivity_report(&self, report: Option<&ConnectivityReport>) {
+		if let Some(metrics) = &self.0 {
+			let report = match report {
+				Some(report) => report,
+				None => return,
+			};
+
+			let percent_of_authorities =
+				|n: u32| (100 * n as u64).checked_div(report.authorities as u64).unwrap_or(100);
+			metrics.authorities_resolved_percent.set(percent_of_authorities(report.resolved));
+			metrics.authorities_connected_percent.set(percent_of_authorities(report.connected));
+
+			for reason in [
+				ConnectivityFailure::UnresolvedAddress,
+				ConnectivityFailure::DialFailure,
+				ConnectivityFailure::ProtocolMismatch,
+			] {
+				let count = report.unreachable.iter().filter(|u| u.reason == reason).count();
+				metrics
+					.unreachable_authorities
+					.with_label_values(&[reason_label(reason)])
+					.set(count as u64);
+			}
+		}
+	}
+}
+
+fn reason_label(reason: ConnectivityFailure) -> &'static str {
+	match reason {
+		ConnectivityFailure::UnresolvedAddress => "unresolved-address",
+		ConnectivityFailure::DialFailure => "dial-failure",
+		ConnectivityFailure::ProtocolMismatch => "protocol-mismatch",
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -84,6 +126,32 @@ impl metrics::Metrics for Metrics {
 				subset of authorities that perform approval checking of all parachain candidates in a session.")?,
 				registry,
 			)?,
+			authorities_resolved_percent: prometheus::register(
+				Gauge::new(
+					"polkadot_parachain_authorities_resolved_percent",
+					"Percentage of the other authorities of the session whose addresses were \
+					resolved via authority discovery.",
+				)?,
+				registry,
+			)?,
+			authorities_connected_percent: prometheus::register(
+				Gauge::new(
+					"polkadot_parachain_authorities_connected_percent",
+					"Percentage of the other authorities of the session we are connected to on the \
+					validation peer set.",
+				)?,
+				registry,
+			)?,
+			unreachable_authorities: prometheus::register(
+				GaugeVec::new(
+					Opts::new(
+						"polkadot_parachain_unreachable_authorities",
+						"Authorities of the session we are not connected to, by reason.",
+					),
+					&["reason"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
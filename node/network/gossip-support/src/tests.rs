@@ -27,6 +27,7 @@ use sc_network::multiaddr::Protocol;
 use sp_consensus_babe::{AllowedSlots, BabeEpochConfiguration, Epoch as BabeEpoch};
 use sp_keyring::Sr25519Keyring;
 
+use polkadot_node_network_protocol::ObservedRole;
 use polkadot_node_subsystem::{
 	jaeger,
 	messages::{AllMessages, RuntimeApiMessage, RuntimeApiRequest},
@@ -34,6 +35,7 @@ use polkadot_node_subsystem::{
 };
 use polkadot_node_subsystem_test_helpers as test_helpers;
 use polkadot_node_subsystem_util::TimeoutExt as _;
+use polkadot_primitives::v2::SessionInfo;
 use test_helpers::mock::make_ferdie_keystore;
 
 use super::*;
@@ -209,9 +211,11 @@ async fn test_neighbors(overseer: &mut VirtualOverseer) {
 	assert_matches!(
 		overseer_recv(overseer).await,
 		AllMessages::NetworkBridge(NetworkBridgeMessage::NewGossipTopology {
-			our_neighbors,
+			row_neighbors,
+			column_neighbors,
 		}) => {
-			let mut got: Vec<_> = our_neighbors.into_iter().collect();
+			assert!(row_neighbors.is_disjoint(&column_neighbors));
+			let mut got: Vec<_> = row_neighbors.into_iter().chain(column_neighbors).collect();
 			got.sort();
 			assert_eq!(got, NEIGHBORS.clone());
 		}
@@ -354,6 +358,270 @@ fn issues_a_connection_request_on_new_session() {
 	assert!(state.last_failure.is_none());
 }
 
+#[test]
+fn topology_can_be_inspected() {
+	let hash = Hash::repeat_byte(0xAA);
+	let state = test_harness(make_subsystem(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+
+		// Not an authority of any session yet.
+		let (tx, rx) = oneshot::channel();
+		overseer
+			.send(FromOverseer::Communication { msg: GossipSupportMessage::GetTopology(tx) })
+			.await;
+		assert_eq!(rx.await.unwrap(), None);
+
+		overseer_signal_active_leaves(overseer, hash).await;
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionIndexForChild(tx),
+			)) => {
+				tx.send(Ok(1)).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::Authorities(tx),
+			)) => {
+				tx.send(Ok(AUTHORITIES.clone())).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ConnectToResolvedValidators { .. })
+		);
+
+		test_neighbors(overseer).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionInfo(1, sender),
+			)) => {
+				sender.send(Ok(None)).unwrap();
+			}
+		);
+
+		virtual_overseer
+	});
+
+	let two: AuthorityDiscoveryId = Sr25519Keyring::Two.public().into();
+	let two_peer = MOCK_AUTHORITY_DISCOVERY
+		.authorities
+		.iter()
+		.find_map(|(peer, authorities)| authorities.contains(&two).then(|| *peer))
+		.unwrap();
+
+	test_harness(state, |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		overseer
+			.send(FromOverseer::Communication {
+				msg: GossipSupportMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerConnected(
+						two_peer,
+						ObservedRole::Authority,
+						PeerSet::Validation.get_main_version(),
+						Some(HashSet::from([two.clone()])),
+					),
+				),
+			})
+			.await;
+		// Alice and Bob are connected for block sync, but Bob disconnected again.
+		for authority in [&alice, &bob, &two] {
+			overseer
+				.send(FromOverseer::Communication {
+					msg: GossipSupportMessage::PeerSyncConnected {
+						peer: peer_of(authority),
+						authority_ids: HashSet::from([authority.clone()]),
+					},
+				})
+				.await;
+		}
+		overseer
+			.send(FromOverseer::Communication {
+				msg: GossipSupportMessage::PeerSyncDisconnected(peer_of(&bob)),
+			})
+			.await;
+
+		let (tx, rx) = oneshot::channel();
+		overseer
+			.send(FromOverseer::Communication { msg: GossipSupportMessage::GetTopology(tx) })
+			.await;
+		let topology = rx.await.unwrap().expect("we are an authority of session 1");
+
+		assert_eq!(topology.session_index, 1);
+		assert_eq!(topology.strategy, "random-grid");
+
+		let mut neighbors: Vec<_> = topology.row.iter().chain(&topology.column).collect();
+		neighbors.sort_by(|a, b| a.authority_id.cmp(&b.authority_id));
+		assert_eq!(
+			neighbors.iter().map(|n| n.authority_id.clone()).collect::<Vec<_>>(),
+			NEIGHBORS.clone(),
+		);
+		for neighbor in neighbors {
+			assert!(neighbor.resolved);
+			if neighbor.authority_id == two {
+				assert_eq!(neighbor.peer_id, Some(two_peer.to_base58()));
+			} else {
+				assert_eq!(neighbor.peer_id, None);
+			}
+		}
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn connectivity_failures_are_categorized() {
+	let hash = Hash::repeat_byte(0xAA);
+	let state = test_harness(make_subsystem(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+
+		// Not an authority of any session yet.
+		let (tx, rx) = oneshot::channel();
+		overseer
+			.send(FromOverseer::Communication {
+				msg: GossipSupportMessage::GetConnectivityReport(tx),
+			})
+			.await;
+		assert_eq!(rx.await.unwrap(), None);
+
+		overseer_signal_active_leaves(overseer, hash).await;
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionIndexForChild(tx),
+			)) => {
+				tx.send(Ok(1)).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::Authorities(tx),
+			)) => {
+				tx.send(Ok(AUTHORITIES.clone())).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ConnectToResolvedValidators { .. })
+		);
+
+		test_neighbors(overseer).await;
+
+		// Dave is an authority of the session, whose addresses can't be resolved.
+		let mut discovery_keys = AUTHORITIES.clone();
+		discovery_keys.push(Sr25519Keyring::Dave.public().into());
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionInfo(1, sender),
+			)) => {
+				sender.send(Ok(Some(SessionInfo {
+					validators: vec![],
+					discovery_keys,
+					assignment_keys: vec![],
+					validator_groups: vec![],
+					n_cores: 0,
+					zeroth_delay_tranche_width: 0,
+					relay_vrf_modulo_samples: 0,
+					n_delay_tranches: 0,
+					no_show_slots: 0,
+					needed_approvals: 0,
+					active_validator_indices: vec![],
+					dispute_period: 6,
+					random_seed: [0u8; 32],
+				}))).unwrap();
+			}
+		);
+
+		virtual_overseer
+	});
+
+	let peer_of = |authority: &AuthorityDiscoveryId| {
+		MOCK_AUTHORITY_DISCOVERY
+			.authorities
+			.iter()
+			.find_map(|(peer, authorities)| authorities.contains(authority).then(|| *peer))
+			.unwrap()
+	};
+	let alice: AuthorityDiscoveryId = Sr25519Keyring::Alice.public().into();
+	let bob: AuthorityDiscoveryId = Sr25519Keyring::Bob.public().into();
+	let two: AuthorityDiscoveryId = Sr25519Keyring::Two.public().into();
+	let dave: AuthorityDiscoveryId = Sr25519Keyring::Dave.public().into();
+
+	test_harness(state, |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		overseer
+			.send(FromOverseer::Communication {
+				msg: GossipSupportMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerConnected(
+						peer_of(&two),
+						ObservedRole::Authority,
+						PeerSet::Validation.get_main_version(),
+						Some(HashSet::from([two.clone()])),
+					),
+				),
+			})
+			.await;
+		// Alice and Bob are connected for block sync, but Bob disconnected again.
+		for authority in [&alice, &bob, &two] {
+			overseer
+				.send(FromOverseer::Communication {
+					msg: GossipSupportMessage::PeerSyncConnected {
+						peer: peer_of(authority),
+						authority_ids: HashSet::from([authority.clone()]),
+					},
+				})
+				.await;
+		}
+		overseer
+			.send(FromOverseer::Communication {
+				msg: GossipSupportMessage::PeerSyncDisconnected(peer_of(&bob)),
+			})
+			.await;
+
+		let (tx, rx) = oneshot::channel();
+		overseer
+			.send(FromOverseer::Communication {
+				msg: GossipSupportMessage::GetConnectivityReport(tx),
+			})
+			.await;
+		let report = rx.await.unwrap().expect("we are an authority of session 1");
+
+		assert_eq!(report.session_index, 1);
+		assert_eq!(report.authorities, 7);
+		assert_eq!(report.resolved, 6);
+		assert_eq!(report.connected, 1);
+		assert_eq!(report.unreachable.len(), 6);
+		for UnreachableAuthority { authority_id, reason } in report.unreachable {
+			let expected = if authority_id == alice {
+				ConnectivityFailure::ProtocolMismatch
+			} else if authority_id == dave {
+				ConnectivityFailure::UnresolvedAddress
+			} else {
+				ConnectivityFailure::DialFailure
+			};
+			assert_eq!(reason, expected);
+		}
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn test_log_output() {
 	sp_tracing::try_init_simple();
@@ -514,8 +782,13 @@ fn test_matrix_neighbors() {
 	]
 	.into_iter()
 	{
-		let mut result: Vec<_> = matrix_neighbors(our_index, len).collect();
+		let (row, column) = matrix_neighbors(our_index, len);
+		let mut result: Vec<_> = row.chain(column).collect();
 		result.sort();
 		assert_eq!(result, expected);
 	}
+
+	let (row, column) = matrix_neighbors(10, 11);
+	assert_eq!(row.collect::<Vec<_>>(), vec![9]);
+	assert_eq!(column.collect::<Vec<_>>(), vec![1, 4, 7]);
 }
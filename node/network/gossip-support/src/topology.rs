@@ -0,0 +1,282 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Strategies to arrange the authorities of a session into a gossip topology.
+
+use std::{
+	collections::{HashMap, HashSet},
+	net::Ipv4Addr,
+};
+
+use rand::{seq::SliceRandom as _, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use sc_network::{multiaddr::Protocol, Multiaddr};
+
+use polkadot_primitives::v1::AuthorityDiscoveryId;
+
+use crate::matrix_neighbors;
+
+/// Our neighbors in a gossip topology, as indices into the authorities of the session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GridNeighbors {
+	/// The neighbors in our row of the grid.
+	pub row: Vec<usize>,
+	/// The neighbors in our column of the grid.
+	pub column: Vec<usize>,
+}
+
+/// A strategy to arrange the authorities of a session into a gossip topology.
+///
+/// All authorities need to arrive at the same topology for the grid to be symmetric, so the
+/// arrangement should only depend on the inputs shared by all of them.
+pub trait TopologyStrategy: Send + Sync {
+	/// The name of the strategy, as reported by topology introspection.
+	fn name(&self) -> &'static str;
+
+	/// Arrange the `authorities` into a grid, based on the `random_seed` of the session, and
+	/// return the neighbors of the authority at `our_index`.
+	///
+	/// `addresses` are the addresses of the other authorities which could be resolved via
+	/// authority discovery.
+	fn neighbors(
+		&self,
+		our_index: usize,
+		authorities: &[AuthorityDiscoveryId],
+		addresses: &HashMap<AuthorityDiscoveryId, HashSet<Multiaddr>>,
+		random_seed: [u8; 32],
+	) -> GridNeighbors;
+}
+
+/// The default strategy, which partitions the authorities shuffled with the session randomness
+/// into a grid of `sqrt(len)` rows of `sqrt(len)` authorities.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomGrid;
+
+impl TopologyStrategy for RandomGrid {
+	fn name(&self) -> &'static str {
+		"random-grid"
+	}
+
+	fn neighbors(
+		&self,
+		our_index: usize,
+		authorities: &[AuthorityDiscoveryId],
+		_addresses: &HashMap<AuthorityDiscoveryId, HashSet<Multiaddr>>,
+		random_seed: [u8; 32],
+	) -> GridNeighbors {
+		grid_neighbors(our_index, shuffled_indices(authorities.len(), random_seed))
+	}
+}
+
+/// An experimental strategy, which places authorities with addresses in the same network next to
+/// each other, so that row neighbors are likely close to each other, while columns span
+/// different networks.
+///
+/// The network of an authority is approximated by the `/16` prefix of its public IPv4 addresses,
+/// the `/32` prefix of its IPv6 addresses or the last two labels of its DNS names. As authority
+/// discovery doesn't resolve our own addresses, our network is derived from the public addresses
+/// we were configured with, which should match the ones we publish. Unlike with the random grid,
+/// authorities may still arrive at different topologies, as they might not have resolved the same
+/// addresses of the others.
+#[derive(Debug, Clone, Default)]
+pub struct LatencyAwareGrid {
+	public_addresses: HashSet<Multiaddr>,
+}
+
+impl LatencyAwareGrid {
+	/// Create the strategy with our own public addresses.
+	pub fn new(public_addresses: impl IntoIterator<Item = Multiaddr>) -> Self {
+		Self { public_addresses: public_addresses.into_iter().collect() }
+	}
+}
+
+impl TopologyStrategy for LatencyAwareGrid {
+	fn name(&self) -> &'static str {
+		"latency-aware-grid"
+	}
+
+	fn neighbors(
+		&self,
+		our_index: usize,
+		authorities: &[AuthorityDiscoveryId],
+		addresses: &HashMap<AuthorityDiscoveryId, HashSet<Multiaddr>>,
+		random_seed: [u8; 32],
+	) -> GridNeighbors {
+		let mut indices = shuffled_indices(authorities.len(), random_seed);
+
+		// The sort is stable, so authorities of the same network stay shuffled. Authorities with
+		// unknown networks are placed last.
+		indices.sort_by_cached_key(|i| {
+			let network = if *i == our_index {
+				network_of(&self.public_addresses)
+			} else {
+				addresses.get(&authorities[*i]).and_then(network_of)
+			};
+			(network.is_none(), network)
+		});
+
+		grid_neighbors(our_index, indices)
+	}
+}
+
+/// An approximation of the network an authority is located in.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Network {
+	Ip4([u8; 2]),
+	Ip6([u16; 2]),
+	Dns(String),
+}
+
+/// The smallest network of all addresses, to not depend on the order of the addresses.
+fn network_of(addresses: &HashSet<Multiaddr>) -> Option<Network> {
+	addresses
+		.iter()
+		.filter_map(|address| {
+			address.iter().find_map(|protocol| match protocol {
+				Protocol::Ip4(ip) if is_public_ip4(&ip) => {
+					let [a, b, _, _] = ip.octets();
+					Some(Network::Ip4([a, b]))
+				},
+				Protocol::Ip6(ip) if !(ip.is_loopback() || ip.is_unspecified()) => {
+					let [a, b, ..] = ip.segments();
+					Some(Network::Ip6([a, b]))
+				},
+				Protocol::Dns(name) | Protocol::Dns4(name) | Protocol::Dns6(name) => {
+					let mut labels: Vec<_> = name.rsplit('.').take(2).collect();
+					labels.reverse();
+					Some(Network::Dns(labels.join(".")))
+				},
+				_ => None,
+			})
+		})
+		.min()
+}
+
+/// Whether the given IPv4 address is routable on the internet.
+fn is_public_ip4(ip: &Ipv4Addr) -> bool {
+	!(ip.is_private() || ip.is_loopback() || ip.is_link_local() || ip.is_unspecified())
+}
+
+/// The indices of `len` authorities, shuffled with the given seed.
+fn shuffled_indices(len: usize, random_seed: [u8; 32]) -> Vec<usize> {
+	let mut rng: ChaCha20Rng = SeedableRng::from_seed(random_seed);
+	let mut indices: Vec<usize> = (0..len).collect();
+	indices.shuffle(&mut rng);
+	indices
+}
+
+/// Our neighbors in the grid formed by the authorities in the order of `indices`.
+fn grid_neighbors(our_index: usize, indices: Vec<usize>) -> GridNeighbors {
+	let our_position = indices
+		.iter()
+		.position(|i| *i == our_index)
+		.expect("our_index < len; indices contains it; qed");
+
+	let (row, column) = matrix_neighbors(our_position, indices.len());
+	GridNeighbors {
+		row: row.map(|i| indices[i]).collect(),
+		column: column.map(|i| indices[i]).collect(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::{sr25519, Pair as _};
+
+	fn authorities(len: u8) -> Vec<AuthorityDiscoveryId> {
+		(0..len).map(|i| sr25519::Pair::from_seed(&[i; 32]).public().into()).collect()
+	}
+
+	fn address(s: &str) -> HashSet<Multiaddr> {
+		HashSet::from([s.parse().expect("valid multiaddr")])
+	}
+
+	#[test]
+	fn random_grid_is_symmetric() {
+		let authorities = authorities(11);
+		let seed = [7u8; 32];
+		let no_addresses = HashMap::new();
+
+		let neighbors: Vec<_> = (0..authorities.len())
+			.map(|i| RandomGrid.neighbors(i, &authorities, &no_addresses, seed))
+			.collect();
+
+		for (i, n) in neighbors.iter().enumerate() {
+			for j in &n.row {
+				assert!(neighbors[*j].row.contains(&i));
+			}
+			for j in &n.column {
+				assert!(neighbors[*j].column.contains(&i));
+			}
+		}
+	}
+
+	#[test]
+	fn latency_aware_grid_groups_networks_in_rows() {
+		let authorities = authorities(9);
+		let networks = [
+			"/ip4/1.2.3.4/tcp/30333",
+			"/ip4/5.6.7.8/tcp/30333",
+			"/dns/validator.example.com/tcp/30333",
+		];
+		let addresses: HashMap<_, _> = authorities
+			.iter()
+			.enumerate()
+			.map(|(i, a)| (a.clone(), address(networks[i % 3])))
+			.collect();
+
+		// Authority discovery only resolves the addresses of the others.
+		let neighbors: Vec<_> = (0..authorities.len())
+			.map(|our_index| {
+				let mut addresses = addresses.clone();
+				let public_addresses = addresses.remove(&authorities[our_index]).unwrap();
+				LatencyAwareGrid::new(public_addresses).neighbors(
+					our_index,
+					&authorities,
+					&addresses,
+					[1u8; 32],
+				)
+			})
+			.collect();
+
+		for (our_index, n) in neighbors.iter().enumerate() {
+			assert_eq!(n.row.len(), 2);
+			assert_eq!(n.column.len(), 2);
+			for i in &n.row {
+				assert_eq!(i % 3, our_index % 3);
+				assert!(neighbors[*i].row.contains(&our_index));
+			}
+			for i in &n.column {
+				assert_ne!(i % 3, our_index % 3);
+				assert!(neighbors[*i].column.contains(&our_index));
+			}
+		}
+	}
+
+	#[test]
+	fn networks_ignore_private_addresses() {
+		assert_eq!(network_of(&address("/ip4/192.168.1.1/tcp/30333")), None);
+		assert_eq!(network_of(&address("/ip4/127.0.0.1/tcp/30333")), None);
+		assert_eq!(network_of(&address("/ip4/0.0.0.0/tcp/30333")), None);
+		assert_eq!(network_of(&address("/ip4/1.2.3.4/tcp/30333")), Some(Network::Ip4([1, 2])));
+		assert_eq!(
+			network_of(&address("/dns/a.validators.example.org/tcp/30333")),
+			Some(Network::Dns("example.org".into())),
+		);
+	}
+}
@@ -290,11 +290,11 @@ pub mod v1 {
 	};
 
 	use polkadot_node_primitives::{
-		approval::{IndirectAssignmentCert, IndirectSignedApprovalVote},
+		approval::{CandidateBitfield, IndirectAssignmentCert, IndirectSignedApprovalVote},
 		UncheckedSignedFullStatement,
 	};
 
-	use crate::WrongVariant;
+	use crate::{ProtocolVersion, WrongVariant};
 
 	/// Network messages used by the bitfield distribution subsystem.
 	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
@@ -316,6 +316,17 @@ pub mod v1 {
 		/// via request/response.
 		#[codec(index = 1)]
 		LargeStatement(StatementMetadata),
+		/// A `Statement` forwarded along the gossip topology by a node other than the one
+		/// which originally circulated it. Not to be forwarded any further.
+		///
+		/// Only understood by peers speaking version 3 of the validation protocol or later.
+		#[codec(index = 2)]
+		ForwardedStatement(Hash, UncheckedSignedFullStatement),
+		/// A `LargeStatement` forwarded along the gossip topology, see `ForwardedStatement`.
+		///
+		/// Only understood by peers speaking version 3 of the validation protocol or later.
+		#[codec(index = 3)]
+		ForwardedLargeStatement(StatementMetadata),
 	}
 
 	/// Data that makes a statement unique.
@@ -335,24 +346,26 @@ pub mod v1 {
 		/// Get meta data of the given `StatementDistributionMessage`.
 		pub fn get_metadata(&self) -> StatementMetadata {
 			match self {
-				Self::Statement(relay_parent, statement) => StatementMetadata {
+				Self::Statement(relay_parent, statement) |
+				Self::ForwardedStatement(relay_parent, statement) => StatementMetadata {
 					relay_parent: *relay_parent,
 					candidate_hash: statement.unchecked_payload().candidate_hash(),
 					signed_by: statement.unchecked_validator_index(),
 					signature: statement.unchecked_signature().clone(),
 				},
-				Self::LargeStatement(metadata) => metadata.clone(),
+				Self::LargeStatement(metadata) | Self::ForwardedLargeStatement(metadata) =>
+					metadata.clone(),
 			}
 		}
 
 		/// Get fingerprint describing the contained statement uniquely.
 		pub fn get_fingerprint(&self) -> (CompactStatement, ValidatorIndex) {
 			match self {
-				Self::Statement(_, statement) => (
+				Self::Statement(_, statement) | Self::ForwardedStatement(_, statement) => (
 					statement.unchecked_payload().to_compact(),
 					statement.unchecked_validator_index(),
 				),
-				Self::LargeStatement(meta) =>
+				Self::LargeStatement(meta) | Self::ForwardedLargeStatement(meta) =>
 					(CompactStatement::Seconded(meta.candidate_hash), meta.signed_by),
 			}
 		}
@@ -360,17 +373,45 @@ pub mod v1 {
 		/// Get contained relay parent.
 		pub fn get_relay_parent(&self) -> Hash {
 			match self {
-				Self::Statement(r, _) => *r,
-				Self::LargeStatement(meta) => meta.relay_parent,
+				Self::Statement(r, _) | Self::ForwardedStatement(r, _) => *r,
+				Self::LargeStatement(meta) | Self::ForwardedLargeStatement(meta) =>
+					meta.relay_parent,
 			}
 		}
 
 		/// Whether this message contains a large statement.
 		pub fn is_large_statement(&self) -> bool {
-			if let Self::LargeStatement(_) = self {
-				true
-			} else {
-				false
+			match self {
+				Self::LargeStatement(_) | Self::ForwardedLargeStatement(_) => true,
+				Self::Statement(..) | Self::ForwardedStatement(..) => false,
+			}
+		}
+
+		/// Whether this message was forwarded along the gossip topology.
+		pub fn is_forwarded(&self) -> bool {
+			match self {
+				Self::ForwardedStatement(..) | Self::ForwardedLargeStatement(_) => true,
+				Self::Statement(..) | Self::LargeStatement(_) => false,
+			}
+		}
+
+		/// The same statement, marked as forwarded along the gossip topology.
+		pub fn into_forwarded(self) -> Self {
+			match self {
+				Self::Statement(relay_parent, statement) =>
+					Self::ForwardedStatement(relay_parent, statement),
+				Self::LargeStatement(meta) => Self::ForwardedLargeStatement(meta),
+				msg => msg,
+			}
+		}
+
+		/// The same statement, as understood by peers not supporting forwarded statements.
+		pub fn into_unforwarded(self) -> Self {
+			match self {
+				Self::ForwardedStatement(relay_parent, statement) =>
+					Self::Statement(relay_parent, statement),
+				Self::ForwardedLargeStatement(meta) => Self::LargeStatement(meta),
+				msg => msg,
 			}
 		}
 	}
@@ -386,6 +427,12 @@ pub mod v1 {
 		/// Approvals for candidates in some recent, unfinalized block.
 		#[codec(index = 1)]
 		Approvals(Vec<IndirectSignedApprovalVote>),
+		/// Assignments for candidates in recent, unfinalized blocks, where each assignment may
+		/// claim multiple candidates of a block.
+		///
+		/// Only understood by peers speaking version 2 of the validation protocol or later.
+		#[codec(index = 2)]
+		CompactAssignments(Vec<(IndirectAssignmentCert, CandidateBitfield)>),
 	}
 
 	/// Dummy network message type, so we will receive connect/disconnect events.
@@ -403,6 +450,19 @@ pub mod v1 {
 		/// declared that they are a collator with given ID.
 		#[codec(index = 1)]
 		AdvertiseCollation(Hash),
+		/// Advertise the collations of the given candidates at a relay parent to a validator.
+		/// Can only be sent once the peer has declared that they are a collator with given ID.
+		///
+		/// Only understood by peers speaking version 2 of the collation protocol or later, older
+		/// peers get an `AdvertiseCollation` instead.
+		#[codec(index = 2)]
+		AdvertiseCollationCandidates(Hash, Vec<CandidateHash>),
+		/// Advertise further candidates at a relay parent, which has been advertised to the
+		/// validator before.
+		///
+		/// Only understood by peers speaking version 2 of the collation protocol or later.
+		#[codec(index = 3)]
+		AdvertiseAdditionalCandidates(Hash, Vec<CandidateHash>),
 		/// A collation sent to a validator was seconded.
 		#[codec(index = 4)]
 		CollationSeconded(Hash, UncheckedSignedFullStatement),
@@ -422,6 +482,31 @@ pub mod v1 {
 		ApprovalDistribution(ApprovalDistributionMessage),
 	}
 
+	impl ValidationProtocol {
+		/// The minimum version of the validation protocol a peer needs to speak to decode
+		/// this message.
+		pub fn required_version(&self) -> ProtocolVersion {
+			match self {
+				ValidationProtocol::ApprovalDistribution(
+					ApprovalDistributionMessage::CompactAssignments(_),
+				) => 2,
+				ValidationProtocol::StatementDistribution(msg) if msg.is_forwarded() => 3,
+				_ => 1,
+			}
+		}
+
+		/// An equivalent message for peers speaking an older `version` of the validation
+		/// protocol than required, if there is any.
+		pub fn downgrade(&self, version: ProtocolVersion) -> Option<Self> {
+			match self {
+				ValidationProtocol::StatementDistribution(msg) if msg.is_forwarded() =>
+					Some(ValidationProtocol::StatementDistribution(msg.clone().into_unforwarded()))
+						.filter(|downgraded| downgraded.required_version() <= version),
+				_ => None,
+			}
+		}
+	}
+
 	impl_try_from!(ValidationProtocol, BitfieldDistribution, BitfieldDistributionMessage);
 	impl_try_from!(ValidationProtocol, StatementDistribution, StatementDistributionMessage);
 	impl_try_from!(ValidationProtocol, ApprovalDistribution, ApprovalDistributionMessage);
@@ -448,6 +533,35 @@ pub mod v1 {
 		CollatorProtocol(CollatorProtocolMessage),
 	}
 
+	impl CollationProtocol {
+		/// The minimum version of the collation protocol a peer needs to speak to decode this
+		/// message.
+		pub fn required_version(&self) -> ProtocolVersion {
+			match self {
+				CollationProtocol::CollatorProtocol(
+					CollatorProtocolMessage::AdvertiseCollationCandidates(..),
+				) |
+				CollationProtocol::CollatorProtocol(
+					CollatorProtocolMessage::AdvertiseAdditionalCandidates(..),
+				) => 2,
+				_ => 1,
+			}
+		}
+
+		/// An equivalent message for peers speaking an older `version` of the collation
+		/// protocol than required, if there is any.
+		pub fn downgrade(&self, _version: ProtocolVersion) -> Option<Self> {
+			match self {
+				CollationProtocol::CollatorProtocol(
+					CollatorProtocolMessage::AdvertiseCollationCandidates(relay_parent, _),
+				) => Some(CollationProtocol::CollatorProtocol(
+					CollatorProtocolMessage::AdvertiseCollation(*relay_parent),
+				)),
+				_ => None,
+			}
+		}
+	}
+
 	impl_try_from!(CollationProtocol, CollatorProtocol, CollatorProtocolMessage);
 
 	/// Get the payload that should be signed and included in a `Declare` message.
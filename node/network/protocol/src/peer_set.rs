@@ -16,6 +16,7 @@
 
 //! All peersets and protocols used for parachains.
 
+use super::ProtocolVersion;
 use sc_network::config::{NonDefaultSetConfig, SetConfig};
 use std::{
 	borrow::Cow,
@@ -51,12 +52,13 @@ impl PeerSet {
 	/// network service.
 	pub fn get_info(self, is_authority: IsAuthority) -> NonDefaultSetConfig {
 		let protocol = self.into_protocol_name();
+		let fallback_names = self.get_fallback_names();
 		let max_notification_size = 100 * 1024;
 
 		match self {
 			PeerSet::Validation => NonDefaultSetConfig {
 				notifications_protocol: protocol,
-				fallback_names: Vec::new(),
+				fallback_names,
 				max_notification_size,
 				set_config: sc_network::config::SetConfig {
 					// we allow full nodes to connect to validators for gossip
@@ -71,7 +73,7 @@ impl PeerSet {
 			},
 			PeerSet::Collation => NonDefaultSetConfig {
 				notifications_protocol: protocol,
-				fallback_names: Vec::new(),
+				fallback_names,
 				max_notification_size,
 				set_config: SetConfig {
 					// Non-authority nodes don't need to accept incoming connections on this peer set:
@@ -88,14 +90,49 @@ impl PeerSet {
 		}
 	}
 
-	/// Get the protocol name associated with each peer set as static str.
+	/// Get the main protocol version of the peer set.
+	///
+	/// Version 2 of the validation protocol adds compact approval assignments, version 3
+	/// statements forwarded along the gossip topology. Version 2 of the collation protocol adds
+	/// advertisements of multiple candidates per relay parent.
+	pub const fn get_main_version(self) -> ProtocolVersion {
+		match self {
+			PeerSet::Validation => 3,
+			PeerSet::Collation => 2,
+		}
+	}
+
+	/// Get the protocol name associated with each peer set as static str, for the main version.
 	pub const fn get_protocol_name_static(self) -> &'static str {
 		match self {
-			PeerSet::Validation => "/polkadot/validation/1",
-			PeerSet::Collation => "/polkadot/collation/1",
+			PeerSet::Validation => "/polkadot/validation/3",
+			PeerSet::Collation => "/polkadot/collation/2",
 		}
 	}
 
+	/// Get the protocol name of an older, still supported version of the peer set, if any.
+	const fn get_legacy_protocol_name_static(
+		self,
+		version: ProtocolVersion,
+	) -> Option<&'static str> {
+		match (self, version) {
+			(PeerSet::Validation, 1) => Some("/polkadot/validation/1"),
+			(PeerSet::Validation, 2) => Some("/polkadot/validation/2"),
+			(PeerSet::Collation, 1) => Some("/polkadot/collation/1"),
+			_ => None,
+		}
+	}
+
+	/// Get the names of older protocol versions we fall back to, for peers which don't support
+	/// the main version.
+	pub fn get_fallback_names(self) -> Vec<Cow<'static, str>> {
+		(1..self.get_main_version())
+			.rev()
+			.filter_map(|version| self.get_legacy_protocol_name_static(version))
+			.map(Into::into)
+			.collect()
+	}
+
 	/// Convert a peer set into a protocol name as understood by Substrate.
 	pub fn into_protocol_name(self) -> Cow<'static, str> {
 		self.get_protocol_name_static().into()
@@ -103,11 +140,23 @@ impl PeerSet {
 
 	/// Try parsing a protocol name into a peer set.
 	pub fn try_from_protocol_name(name: &Cow<'static, str>) -> Option<PeerSet> {
-		match name {
-			n if n == &PeerSet::Validation.into_protocol_name() => Some(PeerSet::Validation),
-			n if n == &PeerSet::Collation.into_protocol_name() => Some(PeerSet::Collation),
-			_ => None,
-		}
+		Self::try_get_protocol(name).map(|(peer_set, _)| peer_set)
+	}
+
+	/// Try parsing a protocol name, main or fallback, into a peer set and protocol version.
+	pub fn try_get_protocol(name: &Cow<'static, str>) -> Option<(PeerSet, ProtocolVersion)> {
+		PeerSet::iter().find_map(|peer_set| {
+			if name == &peer_set.into_protocol_name() {
+				return Some((peer_set, peer_set.get_main_version()))
+			}
+
+			(1..peer_set.get_main_version()).find_map(|version| {
+				peer_set
+					.get_legacy_protocol_name_static(version)
+					.filter(|legacy| name == legacy)
+					.map(|_| (peer_set, version))
+			})
+		})
 	}
 }
 
@@ -63,6 +63,8 @@ pub enum Protocol {
 	ChunkFetching,
 	/// Protocol for fetching collations from collators.
 	CollationFetching,
+	/// Protocol for fetching a specific one of the collations a collator advertised.
+	CandidateCollationFetching,
 	/// Protocol for fetching seconded PoVs from validators of the same group.
 	PoVFetching,
 	/// Protocol for fetching available data.
@@ -138,14 +140,15 @@ impl Protocol {
 				request_timeout: CHUNK_REQUEST_TIMEOUT,
 				inbound_queue: Some(tx),
 			},
-			Protocol::CollationFetching => RequestResponseConfig {
-				name: p_name,
-				max_request_size: 1_000,
-				max_response_size: POV_RESPONSE_SIZE,
-				// Taken from initial implementation in collator protocol:
-				request_timeout: POV_REQUEST_TIMEOUT_CONNECTED,
-				inbound_queue: Some(tx),
-			},
+			Protocol::CollationFetching | Protocol::CandidateCollationFetching =>
+				RequestResponseConfig {
+					name: p_name,
+					max_request_size: 1_000,
+					max_response_size: POV_RESPONSE_SIZE,
+					// Taken from initial implementation in collator protocol:
+					request_timeout: POV_REQUEST_TIMEOUT_CONNECTED,
+					inbound_queue: Some(tx),
+				},
 			Protocol::PoVFetching => RequestResponseConfig {
 				name: p_name,
 				max_request_size: 1_000,
@@ -203,7 +206,7 @@ impl Protocol {
 			// as well.
 			Protocol::ChunkFetching => 100,
 			// 10 seems reasonable, considering group sizes of max 10 validators.
-			Protocol::CollationFetching => 10,
+			Protocol::CollationFetching | Protocol::CandidateCollationFetching => 10,
 			// 10 seems reasonable, considering group sizes of max 10 validators.
 			Protocol::PoVFetching => 10,
 			// Validators are constantly self-selecting to request available data which may lead
@@ -247,6 +250,7 @@ impl Protocol {
 		match self {
 			Protocol::ChunkFetching => "/polkadot/req_chunk/1",
 			Protocol::CollationFetching => "/polkadot/req_collation/1",
+			Protocol::CandidateCollationFetching => "/polkadot/req_candidate_collation/1",
 			Protocol::PoVFetching => "/polkadot/req_pov/1",
 			Protocol::AvailableDataFetching => "/polkadot/req_available_data/1",
 			Protocol::StatementFetching => "/polkadot/req_statement/1",
@@ -33,6 +33,8 @@ pub enum Requests {
 	ChunkFetching(OutgoingRequest<v1::ChunkFetchingRequest>),
 	/// Fetch a collation from a collator which previously announced it.
 	CollationFetching(OutgoingRequest<v1::CollationFetchingRequest>),
+	/// Fetch the collation of a specific candidate a collator previously announced.
+	CandidateCollationFetching(OutgoingRequest<v1::CandidateCollationFetchingRequest>),
 	/// Fetch a PoV from a validator which previously sent out a seconded statement.
 	PoVFetching(OutgoingRequest<v1::PoVFetchingRequest>),
 	/// Request full available data from a node.
@@ -49,6 +51,7 @@ impl Requests {
 		match self {
 			Self::ChunkFetching(_) => Protocol::ChunkFetching,
 			Self::CollationFetching(_) => Protocol::CollationFetching,
+			Self::CandidateCollationFetching(_) => Protocol::CandidateCollationFetching,
 			Self::PoVFetching(_) => Protocol::PoVFetching,
 			Self::AvailableDataFetching(_) => Protocol::AvailableDataFetching,
 			Self::StatementFetching(_) => Protocol::StatementFetching,
@@ -67,6 +70,7 @@ impl Requests {
 		match self {
 			Self::ChunkFetching(r) => r.encode_request(),
 			Self::CollationFetching(r) => r.encode_request(),
+			Self::CandidateCollationFetching(r) => r.encode_request(),
 			Self::PoVFetching(r) => r.encode_request(),
 			Self::AvailableDataFetching(r) => r.encode_request(),
 			Self::StatementFetching(r) => r.encode_request(),
@@ -110,6 +110,22 @@ impl IsRequest for CollationFetchingRequest {
 	const PROTOCOL: Protocol = Protocol::CollationFetching;
 }
 
+/// Request the collation of a specific candidate advertised at that relay-parent.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct CandidateCollationFetchingRequest {
+	/// Relay parent we want a collation for.
+	pub relay_parent: Hash,
+	/// The `ParaId` of the collation.
+	pub para_id: ParaId,
+	/// The hash of the advertised candidate.
+	pub candidate_hash: CandidateHash,
+}
+
+impl IsRequest for CandidateCollationFetchingRequest {
+	type Response = CollationFetchingResponse;
+	const PROTOCOL: Protocol = Protocol::CandidateCollationFetching;
+}
+
 /// Request the advertised collation at that relay-parent.
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct PoVFetchingRequest {
@@ -44,7 +44,7 @@ use polkadot_primitives::v1::{
 use polkadot_subsystem::{
 	jaeger,
 	messages::{
-		AllMessages, CandidateBackingMessage, NetworkBridgeEvent, NetworkBridgeMessage,
+		CandidateBackingMessage, GossipTopology, NetworkBridgeEvent, NetworkBridgeMessage,
 		StatementDistributionMessage,
 	},
 	overseer, ActiveLeavesUpdate, FromOverseer, OverseerSignal, PerLeafSpan, SpawnedSubsystem,
@@ -84,6 +84,7 @@ const COST_WRONG_HASH: Rep = Rep::CostMajor("Received candidate had wrong hash")
 const COST_DUPLICATE_STATEMENT: Rep =
 	Rep::CostMajorRepeated("Statement sent more than once by peer");
 const COST_APPARENT_FLOOD: Rep = Rep::Malicious("Peer appears to be flooding us with statements");
+const COST_EQUIVOCATION: Rep = Rep::Malicious("Validator seconded conflicting candidates");
 
 const BENEFIT_VALID_STATEMENT: Rep = Rep::BenefitMajor("Peer provided a valid statement");
 const BENEFIT_VALID_STATEMENT_FIRST: Rep =
@@ -614,6 +615,65 @@ enum DeniedStatement {
 	UsefulButKnown,
 }
 
+/// The peers a statement needs to be circulated to.
+///
+/// Statements are routed along the grid topology established by `gossip-support`: the
+/// originator sends a statement to its row and column, and every recipient forwards it
+/// once, along the other dimension of the grid. This way, each validator receives
+/// each statement within two hops, while only exchanging messages with `O(sqrt(n))` peers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RequiredRouting {
+	/// All gossip peers, topped up with random peers. Used for statements originating from us
+	/// and as long as we don't know the topology.
+	All,
+	/// Forward to our row and our column. Used for statements received from peers which are
+	/// not our neighbors.
+	RowAndColumn,
+	/// Forward to our row, as we received the statement from our column.
+	Row,
+	/// Forward to our column, as we received the statement from our row.
+	Column,
+	/// The statement was forwarded to us already, so it must not be forwarded again.
+	None,
+}
+
+impl RequiredRouting {
+	/// The routing of a statement received from `peer`.
+	fn for_incoming(
+		topology: &GossipTopology,
+		peer: &PeerId,
+		message: &protocol_v1::StatementDistributionMessage,
+	) -> Self {
+		if message.is_forwarded() {
+			RequiredRouting::None
+		} else if topology.is_empty() {
+			RequiredRouting::All
+		} else if topology.row.contains(peer) {
+			RequiredRouting::Column
+		} else if topology.column.contains(peer) {
+			RequiredRouting::Row
+		} else {
+			RequiredRouting::RowAndColumn
+		}
+	}
+
+	/// Whether the statement is to be sent to `peer`.
+	fn routes_to(&self, topology: &GossipTopology, peer: &PeerId) -> bool {
+		match self {
+			RequiredRouting::All => true,
+			RequiredRouting::RowAndColumn => topology.contains(peer),
+			RequiredRouting::Row => topology.row.contains(peer),
+			RequiredRouting::Column => topology.column.contains(peer),
+			RequiredRouting::None => false,
+		}
+	}
+
+	/// Whether we are forwarding a statement, which the recipients must not forward again.
+	fn is_forwarding(&self) -> bool {
+		*self != RequiredRouting::All
+	}
+}
+
 struct ActiveHeadData {
 	/// All candidates we are aware of for this head, keyed by hash.
 	candidates: HashSet<CandidateHash>,
@@ -626,6 +686,8 @@ struct ActiveHeadData {
 	waiting_large_statements: HashMap<CandidateHash, LargeStatementStatus>,
 	/// The validators at this head.
 	validators: Vec<ValidatorId>,
+	/// The authority discovery keys of the validators at this head.
+	discovery_keys: Vec<AuthorityDiscoveryId>,
 	/// The session index this head is at.
 	session_index: sp_staking::SessionIndex,
 	/// How many `Seconded` statements we've seen per validator.
@@ -637,6 +699,7 @@ struct ActiveHeadData {
 impl ActiveHeadData {
 	fn new(
 		validators: Vec<ValidatorId>,
+		discovery_keys: Vec<AuthorityDiscoveryId>,
 		session_index: sp_staking::SessionIndex,
 		span: PerLeafSpan,
 	) -> Self {
@@ -645,6 +708,7 @@ impl ActiveHeadData {
 			statements: Default::default(),
 			waiting_large_statements: Default::default(),
 			validators,
+			discovery_keys,
 			session_index,
 			seconded_counts: Default::default(),
 			span,
@@ -751,6 +815,20 @@ impl ActiveHeadData {
 		}
 	}
 
+	/// Whether the given statement seconds a candidate, while we already know of another
+	/// candidate seconded by the same validator.
+	fn is_equivocation(&self, statement: &SignedFullStatement) -> bool {
+		let candidate_hash = match statement.payload() {
+			Statement::Seconded(candidate) => candidate.hash(),
+			Statement::Valid(_) => return false,
+		};
+
+		self.statements.keys().any(|known| {
+			known.validator_index == statement.validator_index() &&
+				matches!(known.compact, CompactStatement::Seconded(h) if h != candidate_hash)
+		})
+	}
+
 	/// Returns an error if the statement is already known or not useful
 	/// without modifying the internal state.
 	fn check_useful_or_unknown(
@@ -848,10 +926,11 @@ fn check_statement_signature(
 /// sends all statements dependent on that statement to peers who could previously not receive
 /// them but now can.
 async fn circulate_statement_and_dependents(
-	gossip_peers: &HashSet<PeerId>,
+	topology: &GossipTopology,
 	peers: &mut HashMap<PeerId, PeerData>,
 	active_heads: &mut HashMap<Hash, ActiveHeadData>,
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	ctx: &mut (impl SubsystemContext<Message = StatementDistributionMessage>
+	          + overseer::SubsystemContext<Message = StatementDistributionMessage>),
 	relay_parent: Hash,
 	statement: SignedFullStatement,
 	priority_peers: Vec<PeerId>,
@@ -874,8 +953,16 @@ async fn circulate_statement_and_dependents(
 		match active_head.note_statement(statement) {
 			NotedStatement::Fresh(stored) => Some((
 				*stored.compact().candidate_hash(),
-				circulate_statement(gossip_peers, peers, ctx, relay_parent, stored, priority_peers)
-					.await,
+				circulate_statement(
+					topology,
+					peers,
+					ctx,
+					relay_parent,
+					stored,
+					priority_peers,
+					RequiredRouting::All,
+				)
+				.await,
 			)),
 			_ => None,
 		}
@@ -943,22 +1030,24 @@ fn is_statement_large(statement: &SignedFullStatement) -> bool {
 	}
 }
 
-/// Circulates a statement to all peers who have not seen it yet, and returns
+/// Circulates a statement to all peers on the `routing` who have not seen it yet, and returns
 /// an iterator over peers who need to have dependent statements sent.
 async fn circulate_statement<'a>(
-	gossip_peers: &HashSet<PeerId>,
+	topology: &GossipTopology,
 	peers: &mut HashMap<PeerId, PeerData>,
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	ctx: &mut (impl SubsystemContext<Message = StatementDistributionMessage>
+	          + overseer::SubsystemContext<Message = StatementDistributionMessage>),
 	relay_parent: Hash,
 	stored: StoredStatement<'a>,
 	mut priority_peers: Vec<PeerId>,
+	routing: RequiredRouting,
 ) -> Vec<PeerId> {
 	let fingerprint = stored.fingerprint();
 
 	let mut peers_to_send: Vec<PeerId> = peers
 		.iter()
 		.filter_map(|(peer, data)| {
-			if data.can_send(&relay_parent, &fingerprint) {
+			if routing.routes_to(topology, peer) && data.can_send(&relay_parent, &fingerprint) {
 				Some(peer.clone())
 			} else {
 				None
@@ -974,8 +1063,12 @@ async fn circulate_statement<'a>(
 	let priority_set: HashSet<&PeerId> = priority_peers.iter().collect();
 	peers_to_send.retain(|p| !priority_set.contains(p));
 
-	let mut peers_to_send =
-		util::choose_random_subset(|e| gossip_peers.contains(e), peers_to_send, MIN_GOSSIP_PEERS);
+	// Forwarded statements go to all our neighbors along the route, which are few enough:
+	let mut peers_to_send = if routing.is_forwarding() {
+		peers_to_send
+	} else {
+		util::choose_random_subset(|e| topology.contains(e), peers_to_send, MIN_GOSSIP_PEERS)
+	};
 	// We don't want to use less peers, than we would without any priority peers:
 	let min_size = std::cmp::max(peers_to_send.len(), MIN_GOSSIP_PEERS);
 	// Make set full:
@@ -1006,18 +1099,24 @@ async fn circulate_statement<'a>(
 
 	// Send all these peers the initial statement.
 	if !peers_to_send.is_empty() {
-		let payload = statement_message(relay_parent, stored.statement.clone());
+		let payload = match statement_message(relay_parent, stored.statement.clone()) {
+			protocol_v1::ValidationProtocol::StatementDistribution(msg)
+				if routing.is_forwarding() =>
+				protocol_v1::ValidationProtocol::StatementDistribution(msg.into_forwarded()),
+			payload => payload,
+		};
 		tracing::trace!(
 			target: LOG_TARGET,
 			?peers_to_send,
 			?relay_parent,
+			?routing,
 			statement = ?stored.statement,
 			"Sending statement",
 		);
-		ctx.send_message(AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+		ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
 			peers_to_send.iter().map(|(p, _)| p.clone()).collect(),
 			payload,
-		)))
+		))
 		.await;
 	}
 
@@ -1031,7 +1130,8 @@ async fn circulate_statement<'a>(
 async fn send_statements_about(
 	peer: PeerId,
 	peer_data: &mut PeerData,
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	ctx: &mut (impl SubsystemContext<Message = StatementDistributionMessage>
+	          + overseer::SubsystemContext<Message = StatementDistributionMessage>),
 	relay_parent: Hash,
 	candidate_hash: CandidateHash,
 	active_head: &ActiveHeadData,
@@ -1053,11 +1153,8 @@ async fn send_statements_about(
 			statement = ?statement.statement,
 			"Sending statement",
 		);
-		ctx.send_message(AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
-			vec![peer.clone()],
-			payload,
-		)))
-		.await;
+		ctx.send_message(NetworkBridgeMessage::SendValidationMessage(vec![peer.clone()], payload))
+			.await;
 
 		metrics.on_statement_distributed();
 	}
@@ -1067,7 +1164,8 @@ async fn send_statements_about(
 async fn send_statements(
 	peer: PeerId,
 	peer_data: &mut PeerData,
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	ctx: &mut (impl SubsystemContext<Message = StatementDistributionMessage>
+	          + overseer::SubsystemContext<Message = StatementDistributionMessage>),
 	relay_parent: Hash,
 	active_head: &ActiveHeadData,
 	metrics: &Metrics,
@@ -1087,23 +1185,20 @@ async fn send_statements(
 			statement = ?statement.statement,
 			"Sending statement"
 		);
-		ctx.send_message(AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
-			vec![peer.clone()],
-			payload,
-		)))
-		.await;
+		ctx.send_message(NetworkBridgeMessage::SendValidationMessage(vec![peer.clone()], payload))
+			.await;
 
 		metrics.on_statement_distributed();
 	}
 }
 
 async fn report_peer(
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	ctx: &mut (impl SubsystemContext<Message = StatementDistributionMessage>
+	          + overseer::SubsystemContext<Message = StatementDistributionMessage>),
 	peer: PeerId,
 	rep: Rep,
 ) {
-	ctx.send_message(AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(peer, rep)))
-		.await
+	ctx.send_message(NetworkBridgeMessage::ReportPeer(peer, rep)).await
 }
 
 /// If message contains a statement, then retrieve it, otherwise fork task to fetch it.
@@ -1117,7 +1212,8 @@ async fn retrieve_statement_from_message<'a>(
 	peer: PeerId,
 	message: protocol_v1::StatementDistributionMessage,
 	active_head: &'a mut ActiveHeadData,
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	ctx: &mut (impl SubsystemContext<Message = StatementDistributionMessage>
+	          + overseer::SubsystemContext<Message = StatementDistributionMessage>),
 	req_sender: &mpsc::Sender<RequesterMessage>,
 	metrics: &Metrics,
 ) -> Option<UncheckedSignedFullStatement> {
@@ -1125,13 +1221,12 @@ async fn retrieve_statement_from_message<'a>(
 	let candidate_hash = *fingerprint.0.candidate_hash();
 
 	// Immediately return any Seconded statement:
-	let message = if let protocol_v1::StatementDistributionMessage::Statement(h, s) = message {
-		if let Statement::Seconded(_) = s.unchecked_payload() {
-			return Some(s)
-		}
-		protocol_v1::StatementDistributionMessage::Statement(h, s)
-	} else {
-		message
+	let message = match message {
+		protocol_v1::StatementDistributionMessage::Statement(_, s) |
+		protocol_v1::StatementDistributionMessage::ForwardedStatement(_, s)
+			if matches!(s.unchecked_payload(), Statement::Seconded(_)) =>
+			return Some(s),
+		message => message,
 	};
 
 	match active_head.waiting_large_statements.entry(candidate_hash) {
@@ -1166,31 +1261,37 @@ async fn retrieve_statement_from_message<'a>(
 				},
 				LargeStatementStatus::FetchedOrShared(committed) => {
 					match message {
-						protocol_v1::StatementDistributionMessage::Statement(_, s) => {
+						protocol_v1::StatementDistributionMessage::Statement(_, s) |
+						protocol_v1::StatementDistributionMessage::ForwardedStatement(_, s) => {
 							// We can now immediately return any statements (should only be
 							// `Statement::Valid` ones, but we don't care at this point.)
 							return Some(s)
 						},
-						protocol_v1::StatementDistributionMessage::LargeStatement(metadata) =>
+						protocol_v1::StatementDistributionMessage::LargeStatement(_) |
+						protocol_v1::StatementDistributionMessage::ForwardedLargeStatement(_) => {
+							let metadata = message.get_metadata();
 							return Some(UncheckedSignedFullStatement::new(
 								Statement::Seconded(committed.clone()),
 								metadata.signed_by,
-								metadata.signature.clone(),
-							)),
+								metadata.signature,
+							))
+						},
 					}
 				},
 			}
 		},
 		Entry::Vacant(vacant) => {
 			match message {
-				protocol_v1::StatementDistributionMessage::LargeStatement(metadata) => {
+				protocol_v1::StatementDistributionMessage::LargeStatement(_) |
+				protocol_v1::StatementDistributionMessage::ForwardedLargeStatement(_) => {
 					if let Some(new_status) =
-						launch_request(metadata, peer, req_sender.clone(), ctx, metrics).await
+						launch_request(message, peer, req_sender.clone(), ctx, metrics).await
 					{
 						vacant.insert(new_status);
 					}
 				},
-				protocol_v1::StatementDistributionMessage::Statement(_, s) => {
+				protocol_v1::StatementDistributionMessage::Statement(_, s) |
+				protocol_v1::StatementDistributionMessage::ForwardedStatement(_, s) => {
 					// No fetch in progress, safe to return any statement immediately (we don't bother
 					// about normal network jitter which might cause `Valid` statements to arrive early
 					// for now.).
@@ -1206,12 +1307,14 @@ async fn retrieve_statement_from_message<'a>(
 ///
 /// Returns `None` if spawning task failed.
 async fn launch_request(
-	meta: StatementMetadata,
+	message: protocol_v1::StatementDistributionMessage,
 	peer: PeerId,
 	req_sender: mpsc::Sender<RequesterMessage>,
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	ctx: &mut (impl SubsystemContext<Message = StatementDistributionMessage>
+	          + overseer::SubsystemContext<Message = StatementDistributionMessage>),
 	metrics: &Metrics,
 ) -> Option<LargeStatementStatus> {
+	let meta = message.get_metadata();
 	let (task, handle) =
 		fetch(meta.relay_parent, meta.candidate_hash, vec![peer], req_sender, metrics.clone())
 			.remote_handle();
@@ -1223,7 +1326,7 @@ async fn launch_request(
 	}
 	let available_peers = {
 		let mut m = IndexMap::new();
-		m.insert(peer, vec![protocol_v1::StatementDistributionMessage::LargeStatement(meta)]);
+		m.insert(peer, vec![message]);
 		m
 	};
 	Some(LargeStatementStatus::Fetching(FetchingInfo {
@@ -1238,14 +1341,16 @@ async fn launch_request(
 ///
 async fn handle_incoming_message_and_circulate<'a>(
 	peer: PeerId,
-	gossip_peers: &HashSet<PeerId>,
+	topology: &GossipTopology,
 	peers: &mut HashMap<PeerId, PeerData>,
 	active_heads: &'a mut HashMap<Hash, ActiveHeadData>,
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	ctx: &mut (impl SubsystemContext<Message = StatementDistributionMessage>
+	          + overseer::SubsystemContext<Message = StatementDistributionMessage>),
 	message: protocol_v1::StatementDistributionMessage,
 	req_sender: &mpsc::Sender<RequesterMessage>,
 	metrics: &Metrics,
 ) {
+	let routing = RequiredRouting::for_incoming(topology, &peer, &message);
 	let handled_incoming = match peers.get_mut(&peer) {
 		Some(data) =>
 			handle_incoming_message(peer, data, active_heads, ctx, message, req_sender, metrics)
@@ -1262,8 +1367,16 @@ async fn handle_incoming_message_and_circulate<'a>(
 		// statement before a `Seconded` statement. `Seconded` statements are the only ones
 		// that require dependents. Thus, if this is a `Seconded` statement for a candidate we
 		// were not aware of before, we cannot have any dependent statements from the candidate.
-		let _ = circulate_statement(gossip_peers, peers, ctx, relay_parent, statement, Vec::new())
-			.await;
+		let _ = circulate_statement(
+			topology,
+			peers,
+			ctx,
+			relay_parent,
+			statement,
+			Vec::new(),
+			routing,
+		)
+		.await;
 	}
 }
 
@@ -1276,7 +1389,8 @@ async fn handle_incoming_message<'a>(
 	peer: PeerId,
 	peer_data: &mut PeerData,
 	active_heads: &'a mut HashMap<Hash, ActiveHeadData>,
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	ctx: &mut (impl SubsystemContext<Message = StatementDistributionMessage>
+	          + overseer::SubsystemContext<Message = StatementDistributionMessage>),
 	message: protocol_v1::StatementDistributionMessage,
 	req_sender: &mpsc::Sender<RequesterMessage>,
 	metrics: &Metrics,
@@ -1296,7 +1410,7 @@ async fn handle_incoming_message<'a>(
 		},
 	};
 
-	if let protocol_v1::StatementDistributionMessage::LargeStatement(_) = message {
+	if message.is_large_statement() {
 		if let Err(rep) = peer_data.receive_large_statement(&relay_parent) {
 			tracing::debug!(
 				target: LOG_TARGET,
@@ -1383,6 +1497,15 @@ async fn handle_incoming_message<'a>(
 		Ok(false) => {},
 	}
 
+	// Equivocations are circulated, so they can be reported. Only the validator which
+	// equivocated gets punished, not the peers passing its statements on.
+	let from_signer = active_head
+		.discovery_keys
+		.get(statement.validator_index().0 as usize)
+		.zip(peer_data.maybe_authority.as_ref())
+		.map_or(false, |(key, authorities)| authorities.contains(key));
+	let equivocation = from_signer && active_head.is_equivocation(&statement);
+
 	// Note: `peer_data.receive` already ensures that the statement is not an unbounded equivocation
 	// or unpinned to a seconded candidate. So it is safe to place it into the storage.
 	match active_head.note_statement(statement) {
@@ -1390,7 +1513,12 @@ async fn handle_incoming_message<'a>(
 			unreachable!("checked in `is_useful_or_unknown` above; qed");
 		},
 		NotedStatement::Fresh(statement) => {
-			report_peer(ctx, peer, BENEFIT_VALID_STATEMENT_FIRST).await;
+			if equivocation {
+				tracing::debug!(target: LOG_TARGET, ?peer, "Peer seconded conflicting candidates");
+				report_peer(ctx, peer, COST_EQUIVOCATION).await;
+			} else {
+				report_peer(ctx, peer, BENEFIT_VALID_STATEMENT_FIRST).await;
+			}
 
 			let mut _span = handle_incoming_span.child("notify-backing");
 
@@ -1410,9 +1538,10 @@ async fn handle_incoming_message<'a>(
 /// Update a peer's view. Sends all newly unlocked statements based on the previous
 async fn update_peer_view_and_maybe_send_unlocked(
 	peer: PeerId,
-	gossip_peers: &HashSet<PeerId>,
+	topology: &GossipTopology,
 	peer_data: &mut PeerData,
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	ctx: &mut (impl SubsystemContext<Message = StatementDistributionMessage>
+	          + overseer::SubsystemContext<Message = StatementDistributionMessage>),
 	active_heads: &HashMap<Hash, ActiveHeadData>,
 	new_view: View,
 	metrics: &Metrics,
@@ -1424,10 +1553,10 @@ async fn update_peer_view_and_maybe_send_unlocked(
 		let _ = peer_data.view_knowledge.remove(removed);
 	}
 
-	let is_gossip_peer = gossip_peers.contains(&peer);
+	let is_gossip_peer = topology.contains(&peer);
 	let lucky = is_gossip_peer ||
 		util::gen_ratio(
-			util::MIN_GOSSIP_PEERS.saturating_sub(gossip_peers.len()),
+			util::MIN_GOSSIP_PEERS.saturating_sub(topology.peers().len()),
 			util::MIN_GOSSIP_PEERS,
 		);
 
@@ -1447,16 +1576,17 @@ async fn update_peer_view_and_maybe_send_unlocked(
 
 async fn handle_network_update(
 	peers: &mut HashMap<PeerId, PeerData>,
-	gossip_peers: &mut HashSet<PeerId>,
+	topology: &mut GossipTopology,
 	authorities: &mut HashMap<AuthorityDiscoveryId, PeerId>,
 	active_heads: &mut HashMap<Hash, ActiveHeadData>,
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	ctx: &mut (impl SubsystemContext<Message = StatementDistributionMessage>
+	          + overseer::SubsystemContext<Message = StatementDistributionMessage>),
 	req_sender: &mpsc::Sender<RequesterMessage>,
 	update: NetworkBridgeEvent<protocol_v1::StatementDistributionMessage>,
 	metrics: &Metrics,
 ) {
 	match update {
-		NetworkBridgeEvent::PeerConnected(peer, role, maybe_authority) => {
+		NetworkBridgeEvent::PeerConnected(peer, role, _, maybe_authority) => {
 			tracing::trace!(target: LOG_TARGET, ?peer, ?role, "Peer connected");
 			peers.insert(
 				peer,
@@ -1480,15 +1610,16 @@ async fn handle_network_update(
 				});
 			}
 		},
-		NetworkBridgeEvent::NewGossipTopology(new_peers) => {
-			let newly_added: Vec<PeerId> = new_peers.difference(gossip_peers).cloned().collect();
-			*gossip_peers = new_peers;
+		NetworkBridgeEvent::NewGossipTopology(new_topology) => {
+			let newly_added: Vec<PeerId> =
+				new_topology.peers().difference(&topology.peers()).cloned().collect();
+			*topology = new_topology;
 			for peer in newly_added {
 				if let Some(data) = peers.get_mut(&peer) {
 					let view = std::mem::take(&mut data.view);
 					update_peer_view_and_maybe_send_unlocked(
 						peer,
-						gossip_peers,
+						topology,
 						data,
 						ctx,
 						&*active_heads,
@@ -1502,7 +1633,7 @@ async fn handle_network_update(
 		NetworkBridgeEvent::PeerMessage(peer, message) => {
 			handle_incoming_message_and_circulate(
 				peer,
-				gossip_peers,
+				topology,
 				peers,
 				active_heads,
 				ctx,
@@ -1518,7 +1649,7 @@ async fn handle_network_update(
 				Some(data) =>
 					update_peer_view_and_maybe_send_unlocked(
 						peer,
-						gossip_peers,
+						topology,
 						data,
 						ctx,
 						&*active_heads,
@@ -1542,7 +1673,7 @@ impl StatementDistributionSubsystem {
 		     + overseer::SubsystemContext<Message = StatementDistributionMessage>),
 	) -> std::result::Result<(), Fatal> {
 		let mut peers: HashMap<PeerId, PeerData> = HashMap::new();
-		let mut gossip_peers: HashSet<PeerId> = HashSet::new();
+		let mut topology = GossipTopology::default();
 		let mut authorities: HashMap<AuthorityDiscoveryId, PeerId> = HashMap::new();
 		let mut active_heads: HashMap<Hash, ActiveHeadData> = HashMap::new();
 
@@ -1573,7 +1704,7 @@ impl StatementDistributionSubsystem {
 							&mut ctx,
 							&mut runtime,
 							&mut peers,
-							&mut gossip_peers,
+							&mut topology,
 							&mut authorities,
 							&mut active_heads,
 							&req_sender,
@@ -1591,7 +1722,7 @@ impl StatementDistributionSubsystem {
 					let result = self
 						.handle_requester_message(
 							&mut ctx,
-							&gossip_peers,
+							&topology,
 							&mut peers,
 							&mut active_heads,
 							&req_sender,
@@ -1656,8 +1787,9 @@ impl StatementDistributionSubsystem {
 
 	async fn handle_requester_message(
 		&self,
-		ctx: &mut impl SubsystemContext,
-		gossip_peers: &HashSet<PeerId>,
+		ctx: &mut (impl SubsystemContext<Message = StatementDistributionMessage>
+		          + overseer::SubsystemContext<Message = StatementDistributionMessage>),
+		topology: &GossipTopology,
 		peers: &mut HashMap<PeerId, PeerData>,
 		active_heads: &mut HashMap<Hash, ActiveHeadData>,
 		req_sender: &mpsc::Sender<RequesterMessage>,
@@ -1704,7 +1836,7 @@ impl StatementDistributionSubsystem {
 					for message in messages {
 						handle_incoming_message_and_circulate(
 							peer,
-							gossip_peers,
+							topology,
 							peers,
 							active_heads,
 							ctx,
@@ -1717,10 +1849,10 @@ impl StatementDistributionSubsystem {
 				}
 			},
 			RequesterMessage::SendRequest(req) => {
-				ctx.send_message(AllMessages::NetworkBridge(NetworkBridgeMessage::SendRequests(
+				ctx.send_message(NetworkBridgeMessage::SendRequests(
 					vec![req],
 					IfDisconnected::ImmediateError,
-				)))
+				))
 				.await;
 			},
 			RequesterMessage::GetMorePeers { relay_parent, candidate_hash, tx } => {
@@ -1761,10 +1893,11 @@ impl StatementDistributionSubsystem {
 
 	async fn handle_subsystem_message(
 		&self,
-		ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+		ctx: &mut (impl SubsystemContext<Message = StatementDistributionMessage>
+		          + overseer::SubsystemContext<Message = StatementDistributionMessage>),
 		runtime: &mut RuntimeInfo,
 		peers: &mut HashMap<PeerId, PeerData>,
-		gossip_peers: &mut HashSet<PeerId>,
+		topology: &mut GossipTopology,
 		authorities: &mut HashMap<AuthorityDiscoveryId, PeerId>,
 		active_heads: &mut HashMap<Hash, ActiveHeadData>,
 		req_sender: &mpsc::Sender<RequesterMessage>,
@@ -1807,6 +1940,7 @@ impl StatementDistributionSubsystem {
 
 					active_heads.entry(relay_parent).or_insert(ActiveHeadData::new(
 						session_info.validators.clone(),
+						session_info.discovery_keys.clone(),
 						session_index,
 						span,
 					));
@@ -1860,7 +1994,7 @@ impl StatementDistributionSubsystem {
 						}
 					};
 					circulate_statement_and_dependents(
-						gossip_peers,
+						topology,
 						peers,
 						active_heads,
 						ctx,
@@ -1876,7 +2010,7 @@ impl StatementDistributionSubsystem {
 
 					handle_network_update(
 						peers,
-						gossip_peers,
+						topology,
 						authorities,
 						active_heads,
 						ctx,
@@ -32,7 +32,7 @@ use polkadot_primitives::{v1::ValidationCode, v2::SessionInfo};
 use polkadot_primitives_test_helpers::{dummy_committed_candidate_receipt, dummy_hash};
 use polkadot_subsystem::{
 	jaeger,
-	messages::{RuntimeApiMessage, RuntimeApiRequest},
+	messages::{AllMessages, RuntimeApiMessage, RuntimeApiRequest},
 	ActivatedLeaf, LeafStatus,
 };
 use sc_keystore::LocalKeystore;
@@ -76,6 +76,7 @@ fn active_head_accepts_only_2_seconded_per_validator() {
 
 	let mut head_data = ActiveHeadData::new(
 		validators,
+		Vec::new(),
 		session_index,
 		PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
 	);
@@ -414,6 +415,7 @@ fn peer_view_update_sends_messages() {
 	let new_head_data = {
 		let mut data = ActiveHeadData::new(
 			validators,
+			Vec::new(),
 			session_index,
 			PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
 		);
@@ -488,10 +490,13 @@ fn peer_view_update_sends_messages() {
 	let peer = PeerId::random();
 
 	executor::block_on(async move {
-		let gossip_peers = HashSet::from_iter(vec![peer.clone()].into_iter());
+		let topology = GossipTopology {
+			row: HashSet::from_iter(vec![peer.clone()].into_iter()),
+			column: HashSet::new(),
+		};
 		update_peer_view_and_maybe_send_unlocked(
 			peer.clone(),
-			&gossip_peers,
+			&topology,
 			&mut peer_data,
 			&mut ctx,
 			&active_heads,
@@ -615,15 +620,18 @@ fn circulated_statement_goes_to_all_peers_with_view() {
 		};
 		let statement = StoredStatement { comparator: &comparator, statement: &statement };
 
-		let gossip_peers =
-			HashSet::from_iter(vec![peer_a.clone(), peer_b.clone(), peer_c.clone()].into_iter());
+		let topology = GossipTopology {
+			row: HashSet::from_iter(vec![peer_a.clone(), peer_b.clone()].into_iter()),
+			column: HashSet::from_iter(vec![peer_c.clone()].into_iter()),
+		};
 		let needs_dependents = circulate_statement(
-			&gossip_peers,
+			&topology,
 			&mut peer_data,
 			&mut ctx,
 			hash_b,
 			statement,
 			Vec::new(),
+			RequiredRouting::All,
 		)
 		.await;
 
@@ -673,6 +681,196 @@ fn circulated_statement_goes_to_all_peers_with_view() {
 	});
 }
 
+#[test]
+fn required_routing_follows_grid() {
+	let row_peer = PeerId::random();
+	let column_peer = PeerId::random();
+	let other_peer = PeerId::random();
+
+	let topology = GossipTopology {
+		row: HashSet::from_iter(vec![row_peer.clone()].into_iter()),
+		column: HashSet::from_iter(vec![column_peer.clone()].into_iter()),
+	};
+
+	let message = protocol_v1::StatementDistributionMessage::LargeStatement(StatementMetadata {
+		relay_parent: Hash::repeat_byte(1),
+		candidate_hash: CandidateHash(Hash::repeat_byte(2)),
+		signed_by: ValidatorIndex(0),
+		signature: Sr25519Keyring::Alice.sign(&[42]).into(),
+	});
+
+	assert_eq!(
+		RequiredRouting::for_incoming(&topology, &row_peer, &message),
+		RequiredRouting::Column,
+	);
+	assert_eq!(
+		RequiredRouting::for_incoming(&topology, &column_peer, &message),
+		RequiredRouting::Row,
+	);
+	assert_eq!(
+		RequiredRouting::for_incoming(&topology, &other_peer, &message),
+		RequiredRouting::RowAndColumn,
+	);
+	assert_eq!(
+		RequiredRouting::for_incoming(&Default::default(), &row_peer, &message),
+		RequiredRouting::All,
+	);
+	assert_eq!(
+		RequiredRouting::for_incoming(&topology, &row_peer, &message.clone().into_forwarded()),
+		RequiredRouting::None,
+	);
+
+	assert!(RequiredRouting::Row.routes_to(&topology, &row_peer));
+	assert!(!RequiredRouting::Row.routes_to(&topology, &column_peer));
+	assert!(RequiredRouting::Column.routes_to(&topology, &column_peer));
+	assert!(!RequiredRouting::RowAndColumn.routes_to(&topology, &other_peer));
+	assert!(RequiredRouting::All.routes_to(&topology, &other_peer));
+}
+
+#[test]
+fn forwarded_statement_goes_to_other_dimension_only() {
+	let hash_a = Hash::repeat_byte(1);
+
+	let candidate = {
+		let mut c = dummy_committed_candidate_receipt(dummy_hash());
+		c.descriptor.relay_parent = hash_a;
+		c.descriptor.para_id = 1.into();
+		c
+	};
+
+	let peer_row = PeerId::random();
+	let peer_column_a = PeerId::random();
+	let peer_column_b = PeerId::random();
+	let peer_other = PeerId::random();
+
+	let session_index = 1;
+
+	let peer_data_from_view = |view: View| PeerData {
+		view: view.clone(),
+		view_knowledge: view.iter().map(|v| (v.clone(), Default::default())).collect(),
+		maybe_authority: None,
+	};
+
+	let mut peer_data: HashMap<_, _> = vec![
+		(peer_row.clone(), peer_data_from_view(view![hash_a])),
+		(peer_column_a.clone(), peer_data_from_view(view![hash_a])),
+		(peer_column_b.clone(), peer_data_from_view(view![hash_a])),
+		(peer_other.clone(), peer_data_from_view(view![hash_a])),
+	]
+	.into_iter()
+	.collect();
+
+	let topology = GossipTopology {
+		row: HashSet::from_iter(vec![peer_row.clone()].into_iter()),
+		column: HashSet::from_iter(vec![peer_column_a.clone(), peer_column_b.clone()].into_iter()),
+	};
+
+	let pool = sp_core::testing::TaskExecutor::new();
+	let (mut ctx, mut handle) = polkadot_node_subsystem_test_helpers::make_subsystem_context::<
+		StatementDistributionMessage,
+		_,
+	>(pool);
+
+	executor::block_on(async move {
+		let signing_context = SigningContext { parent_hash: hash_a, session_index };
+
+		let keystore: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+		let alice_public = CryptoStore::sr25519_generate_new(
+			&*keystore,
+			ValidatorId::ID,
+			Some(&Sr25519Keyring::Alice.to_seed()),
+		)
+		.await
+		.unwrap();
+
+		let statement = SignedFullStatement::sign(
+			&keystore,
+			Statement::Seconded(candidate),
+			&signing_context,
+			ValidatorIndex(0),
+			&alice_public.into(),
+		)
+		.await
+		.ok()
+		.flatten()
+		.expect("should be signed");
+
+		let comparator = StoredStatementComparator {
+			compact: statement.payload().to_compact(),
+			validator_index: ValidatorIndex(0),
+			signature: statement.signature().clone(),
+		};
+		let statement = StoredStatement { comparator: &comparator, statement: &statement };
+
+		// Received from our row, so it is forwarded to our column:
+		let needs_dependents = circulate_statement(
+			&topology,
+			&mut peer_data,
+			&mut ctx,
+			hash_a,
+			statement,
+			Vec::new(),
+			RequiredRouting::Column,
+		)
+		.await;
+
+		assert_eq!(needs_dependents.len(), 2);
+		assert!(needs_dependents.contains(&peer_column_a));
+		assert!(needs_dependents.contains(&peer_column_b));
+
+		let message = handle.recv().await;
+		assert_matches!(
+			message,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				to,
+				payload,
+			)) => {
+				assert_eq!(to.len(), 2);
+				assert!(to.contains(&peer_column_a));
+				assert!(to.contains(&peer_column_b));
+
+				assert_matches!(
+					payload,
+					protocol_v1::ValidationProtocol::StatementDistribution(msg) => {
+						assert!(msg.is_forwarded());
+						assert_eq!(
+							protocol_v1::ValidationProtocol::StatementDistribution(
+								msg.into_unforwarded(),
+							),
+							statement_message(hash_a, statement.statement.clone()),
+						);
+					}
+				);
+			}
+		);
+
+		// Already forwarded statements are not circulated any further:
+		let needs_dependents = circulate_statement(
+			&topology,
+			&mut peer_data,
+			&mut ctx,
+			hash_a,
+			statement,
+			Vec::new(),
+			RequiredRouting::None,
+		)
+		.await;
+		assert!(needs_dependents.is_empty());
+
+		let fingerprint = (statement.compact().clone(), ValidatorIndex(0));
+		for peer in &[peer_row, peer_other] {
+			assert!(!peer_data
+				.get(peer)
+				.unwrap()
+				.view_knowledge
+				.get(&hash_a)
+				.unwrap()
+				.sent_statements
+				.contains(&fingerprint));
+		}
+	});
+}
+
 #[test]
 fn receiving_from_one_sends_to_another_and_to_candidate_backing() {
 	let hash_a = Hash::repeat_byte(1);
@@ -750,7 +948,12 @@ fn receiving_from_one_sends_to_another_and_to_candidate_backing() {
 		handle
 			.send(FromOverseer::Communication {
 				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
-					NetworkBridgeEvent::PeerConnected(peer_a.clone(), ObservedRole::Full, None),
+					NetworkBridgeEvent::PeerConnected(
+						peer_a.clone(),
+						ObservedRole::Full,
+						PeerSet::Validation.get_main_version(),
+						None,
+					),
 				),
 			})
 			.await;
@@ -758,7 +961,12 @@ fn receiving_from_one_sends_to_another_and_to_candidate_backing() {
 		handle
 			.send(FromOverseer::Communication {
 				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
-					NetworkBridgeEvent::PeerConnected(peer_b.clone(), ObservedRole::Full, None),
+					NetworkBridgeEvent::PeerConnected(
+						peer_b.clone(),
+						ObservedRole::Full,
+						PeerSet::Validation.get_main_version(),
+						None,
+					),
 				),
 			})
 			.await;
@@ -858,6 +1066,174 @@ fn receiving_from_one_sends_to_another_and_to_candidate_backing() {
 	executor::block_on(future::join(test_fut, bg));
 }
 
+#[test]
+fn equivocating_validator_gets_reported() {
+	let hash_a = Hash::repeat_byte(1);
+
+	let candidate_a = {
+		let mut c = dummy_committed_candidate_receipt(dummy_hash());
+		c.descriptor.relay_parent = hash_a;
+		c.descriptor.para_id = 1.into();
+		c
+	};
+
+	let candidate_b = {
+		let mut c = dummy_committed_candidate_receipt(dummy_hash());
+		c.descriptor.relay_parent = hash_a;
+		c.descriptor.para_id = 2.into();
+		c
+	};
+
+	let peer_a = PeerId::random();
+
+	let validators = vec![
+		Sr25519Keyring::Alice.pair(),
+		Sr25519Keyring::Bob.pair(),
+		Sr25519Keyring::Charlie.pair(),
+	];
+
+	let session_info = make_session_info(validators, vec![]);
+
+	let session_index = 1;
+
+	let pool = sp_core::testing::TaskExecutor::new();
+	let (ctx, mut handle) = polkadot_node_subsystem_test_helpers::make_subsystem_context(pool);
+
+	let (statement_req_receiver, _) = IncomingRequest::get_config_receiver();
+
+	let bg = async move {
+		let s = StatementDistributionSubsystem::new(
+			Arc::new(LocalKeystore::in_memory()),
+			statement_req_receiver,
+			Default::default(),
+		);
+		s.run(ctx).await.unwrap();
+	};
+
+	let test_fut = async move {
+		// register our active heads.
+		handle
+			.send(FromOverseer::Signal(OverseerSignal::ActiveLeaves(
+				ActiveLeavesUpdate::start_work(ActivatedLeaf {
+					hash: hash_a,
+					number: 1,
+					status: LeafStatus::Fresh,
+					span: Arc::new(jaeger::Span::Disabled),
+				}),
+			)))
+			.await;
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(r, RuntimeApiRequest::SessionIndexForChild(tx))
+			)
+				if r == hash_a
+			=> {
+				let _ = tx.send(Ok(session_index));
+			}
+		);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(r, RuntimeApiRequest::SessionInfo(sess_index, tx))
+			)
+				if r == hash_a && sess_index == session_index
+			=> {
+				let _ = tx.send(Ok(Some(session_info)));
+			}
+		);
+
+		// peer A is Alice.
+		handle
+			.send(FromOverseer::Communication {
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerConnected(
+						peer_a.clone(),
+						ObservedRole::Full,
+						PeerSet::Validation.get_main_version(),
+						Some(HashSet::from_iter(vec![Sr25519Keyring::Alice.public().into()])),
+					),
+				),
+			})
+			.await;
+
+		handle
+			.send(FromOverseer::Communication {
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerViewChange(peer_a.clone(), view![hash_a]),
+				),
+			})
+			.await;
+
+		let signing_context = SigningContext { parent_hash: hash_a, session_index };
+		let keystore: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+		let alice_public = CryptoStore::sr25519_generate_new(
+			&*keystore,
+			ValidatorId::ID,
+			Some(&Sr25519Keyring::Alice.to_seed()),
+		)
+		.await
+		.unwrap();
+
+		// Alice seconds two candidates at the same relay parent, the second one is an
+		// equivocation.
+		for (candidate, rep) in vec![
+			(candidate_a, BENEFIT_VALID_STATEMENT_FIRST),
+			(candidate_b, COST_EQUIVOCATION),
+		] {
+			let statement = SignedFullStatement::sign(
+				&keystore,
+				Statement::Seconded(candidate),
+				&signing_context,
+				ValidatorIndex(0),
+				&alice_public.into(),
+			)
+			.await
+			.ok()
+			.flatten()
+			.expect("should be signed");
+
+			handle
+				.send(FromOverseer::Communication {
+					msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+						NetworkBridgeEvent::PeerMessage(
+							peer_a.clone(),
+							protocol_v1::StatementDistributionMessage::Statement(
+								hash_a,
+								statement.clone().into(),
+							),
+						),
+					),
+				})
+				.await;
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::ReportPeer(p, r)
+				) if p == peer_a && r == rep => {}
+			);
+
+			// equivocations are still passed on, so they can be reported.
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::CandidateBacking(
+					CandidateBackingMessage::Statement(r, s)
+				) if r == hash_a && s == statement => {}
+			);
+		}
+
+		handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::pin_mut!(test_fut);
+	futures::pin_mut!(bg);
+
+	executor::block_on(future::join(test_fut, bg));
+}
+
 #[test]
 fn receiving_large_statement_from_one_sends_to_another_and_to_candidate_backing() {
 	sp_tracing::try_init_simple();
@@ -945,6 +1321,7 @@ fn receiving_large_statement_from_one_sends_to_another_and_to_candidate_backing(
 					NetworkBridgeEvent::PeerConnected(
 						peer_a.clone(),
 						ObservedRole::Full,
+						PeerSet::Validation.get_main_version(),
 						Some(HashSet::from([Sr25519Keyring::Alice.public().into()])),
 					),
 				),
@@ -957,6 +1334,7 @@ fn receiving_large_statement_from_one_sends_to_another_and_to_candidate_backing(
 					NetworkBridgeEvent::PeerConnected(
 						peer_b.clone(),
 						ObservedRole::Full,
+						PeerSet::Validation.get_main_version(),
 						Some(HashSet::from([Sr25519Keyring::Bob.public().into()])),
 					),
 				),
@@ -968,6 +1346,7 @@ fn receiving_large_statement_from_one_sends_to_another_and_to_candidate_backing(
 					NetworkBridgeEvent::PeerConnected(
 						peer_c.clone(),
 						ObservedRole::Full,
+						PeerSet::Validation.get_main_version(),
 						Some(HashSet::from([Sr25519Keyring::Charlie.public().into()])),
 					),
 				),
@@ -976,7 +1355,12 @@ fn receiving_large_statement_from_one_sends_to_another_and_to_candidate_backing(
 		handle
 			.send(FromOverseer::Communication {
 				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
-					NetworkBridgeEvent::PeerConnected(peer_bad.clone(), ObservedRole::Full, None),
+					NetworkBridgeEvent::PeerConnected(
+						peer_bad.clone(),
+						ObservedRole::Full,
+						PeerSet::Validation.get_main_version(),
+						None,
+					),
 				),
 			})
 			.await;
@@ -1445,6 +1829,7 @@ fn share_prioritizes_backing_group() {
 						NetworkBridgeEvent::PeerConnected(
 							peer,
 							ObservedRole::Full,
+							PeerSet::Validation.get_main_version(),
 							Some(HashSet::from([pair.public().into()])),
 						),
 					),
@@ -1467,6 +1852,7 @@ fn share_prioritizes_backing_group() {
 					NetworkBridgeEvent::PeerConnected(
 						peer_a.clone(),
 						ObservedRole::Full,
+						PeerSet::Validation.get_main_version(),
 						Some(HashSet::from([Sr25519Keyring::Alice.public().into()])),
 					),
 				),
@@ -1478,6 +1864,7 @@ fn share_prioritizes_backing_group() {
 					NetworkBridgeEvent::PeerConnected(
 						peer_b.clone(),
 						ObservedRole::Full,
+						PeerSet::Validation.get_main_version(),
 						Some(HashSet::from([Sr25519Keyring::Bob.public().into()])),
 					),
 				),
@@ -1489,6 +1876,7 @@ fn share_prioritizes_backing_group() {
 					NetworkBridgeEvent::PeerConnected(
 						peer_c.clone(),
 						ObservedRole::Full,
+						PeerSet::Validation.get_main_version(),
 						Some(HashSet::from([Sr25519Keyring::Charlie.public().into()])),
 					),
 				),
@@ -1497,7 +1885,12 @@ fn share_prioritizes_backing_group() {
 		handle
 			.send(FromOverseer::Communication {
 				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
-					NetworkBridgeEvent::PeerConnected(peer_bad.clone(), ObservedRole::Full, None),
+					NetworkBridgeEvent::PeerConnected(
+						peer_bad.clone(),
+						ObservedRole::Full,
+						PeerSet::Validation.get_main_version(),
+						None,
+					),
 				),
 			})
 			.await;
@@ -1507,6 +1900,7 @@ fn share_prioritizes_backing_group() {
 					NetworkBridgeEvent::PeerConnected(
 						peer_other_group.clone(),
 						ObservedRole::Full,
+						PeerSet::Validation.get_main_version(),
 						Some(HashSet::from([Sr25519Keyring::Dave.public().into()])),
 					),
 				),
@@ -1729,6 +2123,7 @@ fn peer_cant_flood_with_large_statements() {
 					NetworkBridgeEvent::PeerConnected(
 						peer_a.clone(),
 						ObservedRole::Full,
+						PeerSet::Validation.get_main_version(),
 						Some(HashSet::from([Sr25519Keyring::Alice.public().into()])),
 					),
 				),
@@ -79,8 +79,7 @@ impl Subsystem1 {
 				Default::default(),
 				tx,
 			);
-			ctx.send_message(<Ctx as overseer::SubsystemContext>::AllMessages::from(msg))
-				.await;
+			ctx.send_message(msg).await;
 		}
 		()
 	}
@@ -95,18 +95,6 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 
 	let support_crate = info.support_crate_name();
 
-	let blocking = &info
-		.subsystems()
-		.iter()
-		.map(|x| {
-			if x.blocking {
-				quote! { Blocking }
-			} else {
-				quote! { Regular }
-			}
-		})
-		.collect::<Vec<_>>();
-
 	let generics = quote! {
 		< S, #( #baggage_generic_ty, )* >
 	};
@@ -130,15 +118,109 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 	let subsyste_ctx_name =
 		Ident::new(&(overseer_name.to_string() + "SubsystemContext"), overseer_name.span());
 
+	let event = &info.extern_event_ty;
+
+	// Subsystems with a restart policy are started by a supervisor,
+	// all others directly.
+	let spawn_subsystem = &info
+		.subsystems()
+		.iter()
+		.filter(|ssf| !ssf.wip)
+		.map(|ssf| {
+			let name = &ssf.name;
+			let consumes = &ssf.consumes;
+			let channel_name_tx = format_ident!("{}_tx", ssf.name);
+			let blocking = if ssf.blocking { quote! { Blocking } } else { quote! { Regular } };
+			match ssf.restart {
+				None => quote! {
+					spawn::<_,_, #blocking, _, _, _>(
+						&mut spawner,
+						#channel_name_tx,
+						signal_tx,
+						unbounded_meter,
+						#subsyste_ctx_name::< #consumes >::new(
+							signal_rx,
+							message_rx,
+							channels_out.clone(),
+							to_overseer_tx.clone(),
+							subsystem_static_str
+						),
+						#name,
+						subsystem_static_str,
+						&mut running_subsystems,
+					)?
+				},
+				Some(RestartPolicy { ref max, ref backoff, ref state, .. }) => {
+					let state = match state {
+						Some(state) => quote! { <#state as ::std::default::Default>::default() },
+						None => quote! { () },
+					};
+					quote! {
+						{
+							let subsystem_static_str: &'static str = subsystem_static_str;
+							let restarts = #support_crate ::RestartCounter::default();
+							let channels_out = channels_out.clone();
+							let to_overseer_tx = to_overseer_tx.clone();
+							let future = #support_crate ::supervise::<_, _, _, #error_ty, _, _, _>(
+								subsystem_static_str,
+								#support_crate ::RestartPolicy {
+									max_restarts: #max,
+									backoff: ::std::time::Duration::from_millis(#backoff),
+								},
+								#name,
+								move |signals, messages| #subsyste_ctx_name::< #consumes >::new(
+									signals,
+									messages,
+									channels_out.clone(),
+									to_overseer_tx.clone(),
+									subsystem_static_str
+								),
+								signal_rx,
+								message_rx,
+								SIGNAL_CHANNEL_CAPACITY,
+								CHANNEL_CAPACITY,
+								restarts.clone(),
+								#state,
+							);
+
+							spawn_future::<_, _, #blocking, _>(
+								&mut spawner,
+								#channel_name_tx,
+								signal_tx,
+								unbounded_meter,
+								restarts,
+								subsystem_static_str,
+								subsystem_static_str,
+								future,
+								&mut running_subsystems,
+							)
+						}
+					}
+				},
+			}
+		})
+		.collect::<Vec<_>>();
+
+	// Supervised subsystems are restarted from a pristine clone.
+	let supervised_generic_ty = &info
+		.subsystems()
+		.iter()
+		.filter(|ssf| !ssf.wip && ssf.restart.is_some())
+		.map(|ssf| ssf.generic.clone())
+		.collect::<Vec<_>>();
+
 	let builder_where_clause = quote! {
 		where
 			S: #support_crate ::SpawnNamed,
 		#(
 			#builder_generic_ty : Subsystem<#subsyste_ctx_name< #consumes >, #error_ty>,
 		)*
+		#(
+			#supervised_generic_ty : Clone + Send + 'static,
+		)*
 	};
-
-	let event = &info.extern_event_ty;
+	let message_wrapper = &info.message_wrapper;
+	let signal_ty = &info.extern_signal_ty;
 
 	let mut ts = quote! {
 		impl #generics #overseer_name #generics #where_clause {
@@ -221,6 +303,7 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 				#baggage_name : ::std::option::Option< #baggage_ty >,
 			)*
 			spawner: ::std::option::Option< S >,
+			recorder: #support_crate ::Recorder< #message_wrapper, #signal_ty >,
 		}
 
 		impl #builder_generics Default for #builder #builder_generics {
@@ -241,6 +324,7 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 					#baggage_name: None,
 				)*
 					spawner: None,
+					recorder: Default::default(),
 				}
 			}
 		}
@@ -255,6 +339,18 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 				self
 			}
 
+			/// Attach a recorder which observes every message routed between
+			/// subsystems and every signal broadcast by the overseer.
+			pub fn recorder(
+				mut self,
+				recorder: ::std::sync::Arc<
+					dyn #support_crate ::MessageRecorder< #message_wrapper, #signal_ty >
+				>,
+			) -> Self {
+				self.recorder = #support_crate ::Recorder::new(recorder);
+				self
+			}
+
 			#(
 				/// Specify the particular subsystem implementation.
 				pub fn #subsystem_name (mut self, subsystem: #builder_generic_ty ) -> Self {
@@ -305,16 +401,16 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 				#(
 					let (#channel_name_tx, #channel_name_rx)
 					=
-						#support_crate ::metered::channel::<
+						#support_crate ::metered::channel_with_tof::<
 							MessagePacket< #consumes >
-						>(CHANNEL_CAPACITY);
+						>(CHANNEL_CAPACITY, TIME_OF_FLIGHT_SAMPLE_EVERY);
 				)*
 
 				#(
 					let (#channel_name_unbounded_tx, #channel_name_unbounded_rx) =
-						#support_crate ::metered::unbounded::<
+						#support_crate ::metered::unbounded_with_tof::<
 							MessagePacket< #consumes >
-						>();
+						>(TIME_OF_FLIGHT_SAMPLE_EVERY);
 				)*
 
 				let channels_out =
@@ -325,6 +421,7 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 						#(
 							#channel_name_unbounded: #channel_name_unbounded_tx,
 						)*
+						recorder: self.recorder.clone(),
 					};
 
 				let mut spawner = self.spawner.expect("Spawner is set. qed");
@@ -355,25 +452,7 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 					// Convert owned `snake case` string to a `kebab case` static str.
 					let subsystem_static_str = Box::leak(subsystem_string.replace("_", "-").into_boxed_str());
 
-					let ctx = #subsyste_ctx_name::< #consumes >::new(
-						signal_rx,
-						message_rx,
-						channels_out.clone(),
-						to_overseer_tx.clone(),
-						subsystem_static_str
-					);
-
-					let #subsystem_name: OverseenSubsystem< #consumes > =
-						spawn::<_,_, #blocking, _, _, _>(
-							&mut spawner,
-							#channel_name_tx,
-							signal_tx,
-							unbounded_meter,
-							ctx,
-							#subsystem_name,
-							subsystem_static_str,
-							&mut running_subsystems,
-						)?;
+					let #subsystem_name: OverseenSubsystem< #consumes > = #spawn_subsystem;
 				)*
 
 				#(
@@ -401,6 +480,7 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 					running_subsystems,
 					events_rx,
 					to_overseer_rx,
+					recorder: self.recorder,
 				};
 
 				Ok((overseer, handle))
@@ -426,6 +506,11 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 		.zip(subsystem_name_replace_with.iter())
 		.zip(consumes.iter())
 	{
+		let supervised_bound = if supervised_generic_ty.contains(to_be_replaced_ty) {
+			quote! { NEW: Clone + Send + 'static, }
+		} else {
+			quote! {}
+		};
 		let replace1 = quote! {
 			/// Replace a subsystem by another implementation for the
 			/// consumable message type.
@@ -435,6 +520,7 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 				#to_be_replaced_ty: 'static,
 				F: 'static + FnOnce(#to_be_replaced_ty) -> NEW,
 				NEW: #support_crate ::Subsystem<#subsystem_ctx_name< #consumes >, #error_ty>,
+				#supervised_bound
 			{
 
 				let Self {
@@ -446,6 +532,7 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 						#baggage_name,
 					)*
 					spawner,
+					recorder,
 				} = self;
 
 				// Some cases require that parts of the original are copied
@@ -470,6 +557,7 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 						#baggage_name,
 					)*
 					spawner,
+					recorder,
 				}
 			}
 		};
@@ -538,6 +626,39 @@ pub(crate) fn impl_task_kind(info: &OverseerInfo) -> proc_macro2::TokenStream {
 		{
 			let #support_crate ::SpawnedSubsystem::<E> { future, name } = s.start(ctx);
 
+			spawn_future::<S, M, TK, E>(
+				spawner,
+				message_tx,
+				signal_tx,
+				unbounded_meter,
+				Default::default(),
+				name,
+				subsystem_name,
+				future,
+				futures,
+			)
+		}
+
+		/// Spawn the future of a started, possibly supervised, subsystem
+		/// as task of kind `TK` using spawner `S`.
+		pub fn spawn_future<S, M, TK, E>(
+			spawner: &mut S,
+			message_tx: #support_crate ::metered::MeteredSender<MessagePacket<M>>,
+			signal_tx: #support_crate ::metered::MeteredSender< #signal >,
+			// meter for the unbounded channel
+			unbounded_meter: #support_crate ::metered::Meter,
+			restarts: #support_crate ::RestartCounter,
+			name: &'static str,
+			subsystem_name: &'static str,
+			future: BoxFuture<'static, ::std::result::Result<(), E>>,
+			futures: &mut #support_crate ::FuturesUnordered<BoxFuture<'static, ::std::result::Result<(), #error_ty> >>,
+		) -> ::std::result::Result<OverseenSubsystem<M>, #error_ty >
+		where
+			S: #support_crate ::SpawnNamed,
+			M: std::fmt::Debug + Send + 'static,
+			TK: TaskKind,
+			E: std::error::Error + Send + Sync + 'static + From<#support_crate ::OverseerError>,
+		{
 			let (tx, rx) = #support_crate ::oneshot::channel();
 
 			let fut = Box::pin(async move {
@@ -563,6 +684,7 @@ pub(crate) fn impl_task_kind(info: &OverseerInfo) -> proc_macro2::TokenStream {
 					unbounded: unbounded_meter,
 					bounded: message_tx.meter().clone(),
 					signals: signal_tx.meter().clone(),
+					restarts,
 				},
 				tx_signal: signal_tx,
 				tx_bounded: message_tx,
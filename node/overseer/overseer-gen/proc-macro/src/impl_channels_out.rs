@@ -22,6 +22,7 @@ use super::*;
 /// Implement the helper type `ChannelsOut` and `MessagePacket<T>`.
 pub(crate) fn impl_channels_out_struct(info: &OverseerInfo) -> Result<proc_macro2::TokenStream> {
 	let message_wrapper = info.message_wrapper.clone();
+	let signal_ty = &info.extern_signal_ty;
 
 	let channel_name = &info.channel_names_without_wip("");
 	let channel_name_unbounded = &info.channel_names_without_wip("_unbounded");
@@ -54,6 +55,9 @@ pub(crate) fn impl_channels_out_struct(info: &OverseerInfo) -> Result<proc_macro
 						MessagePacket< #consumes >
 					>,
 			)*
+
+			/// Observer of all messages sent through these channels.
+			pub recorder: #support_crate ::Recorder< #message_wrapper, #signal_ty >,
 		}
 
 		#[allow(unreachable_code)]
@@ -63,8 +67,10 @@ pub(crate) fn impl_channels_out_struct(info: &OverseerInfo) -> Result<proc_macro
 			pub async fn send_and_log_error(
 				&mut self,
 				signals_received: usize,
-				message: #message_wrapper,
+				mut message: #message_wrapper,
 			) {
+				self.recorder.record_message(&mut message);
+
 				let res: ::std::result::Result<_, _> = match message {
 				#(
 					#message_wrapper :: #consumes_variant ( inner ) => {
@@ -94,8 +100,10 @@ pub(crate) fn impl_channels_out_struct(info: &OverseerInfo) -> Result<proc_macro
 			pub fn send_unbounded_and_log_error(
 				&self,
 				signals_received: usize,
-				message: #message_wrapper,
+				mut message: #message_wrapper,
 			) {
+				self.recorder.record_message(&mut message);
+
 				let res: ::std::result::Result<_, _> = match message {
 				#(
 					#message_wrapper :: #consumes_variant (inner) => {
@@ -15,21 +15,13 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::*;
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::TokenStream;
 use quote::quote;
 use syn::Path;
 
 pub(crate) fn impl_dispatch(info: &OverseerInfo) -> TokenStream {
 	let message_wrapper = &info.message_wrapper;
 
-	let dispatchable_variant = info
-		.subsystems()
-		.into_iter()
-		.filter(|ssf| !ssf.no_dispatch)
-		.filter(|ssf| !ssf.wip)
-		.map(|ssf| ssf.generic.clone())
-		.collect::<Vec<Ident>>();
-
 	let dispatchable_message = info
 		.subsystems()
 		.into_iter()
@@ -43,7 +35,13 @@ pub(crate) fn impl_dispatch(info: &OverseerInfo) -> TokenStream {
 		ts.extend(quote! {
 			impl #message_wrapper {
 				/// Generated dispatch iterator generator.
-				pub fn dispatch_iter(extern_msg: #extern_network_ty) -> impl Iterator<Item=Self> + Send {
+				///
+				/// Yields the messages for all subsystems interested in `extern_msg`, converted into `T`,
+				/// e.g. the overarching message type or the outgoing messages of the dispatching subsystem.
+				pub fn dispatch_iter<T>(extern_msg: #extern_network_ty) -> impl Iterator<Item=T> + Send
+				where
+					T: Send #( + ::std::convert::From< #dispatchable_message > )*,
+				{
 					::std::array::IntoIter::new([
 					#(
 						extern_msg
@@ -53,10 +51,8 @@ pub(crate) fn impl_dispatch(info: &OverseerInfo) -> TokenStream {
 							.focus()
 							.ok()
 							.map(|event| {
-								#message_wrapper :: #dispatchable_variant (
-									// the inner type of the enum variant
-									#dispatchable_message :: from( event )
-								)
+								// the inner type of the enum variant
+								T::from( #dispatchable_message :: from( event ) )
 							}),
 					)*
 					])
@@ -0,0 +1,97 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use quote::{quote, ToTokens};
+use std::fmt::Write;
+use syn::{Ident, Path};
+
+use super::*;
+
+/// Render a path without the whitespace `to_string` puts between its tokens.
+fn path_to_string(path: &Path) -> String {
+	path.to_token_stream().to_string().replace(' ', "")
+}
+
+/// `FooBar` becomes `FOO_BAR`.
+fn screaming_snake_case(name: &str) -> String {
+	let mut out = String::with_capacity(name.len() + 4);
+	for (i, c) in name.chars().enumerate() {
+		if i > 0 && c.is_uppercase() {
+			out.push('_');
+		}
+		out.push(c.to_ascii_uppercase());
+	}
+	out
+}
+
+/// Render the message flow between `subsystems` as declared by `sends = [..]`
+/// in graphviz DOT format.
+///
+/// Subsystems which did not declare what they send are drawn dashed and
+/// without outgoing edges, work in progress subsystems are drawn gray.
+pub(crate) fn message_flow_graph(name: &Ident, subsystems: &[SubSysField]) -> String {
+	let mut dot = String::new();
+	let _ = writeln!(dot, "digraph {} {{", name);
+	let _ = writeln!(dot, "\tnode [shape = box];");
+
+	for ssf in subsystems {
+		let mut attrs = format!("label = \"{}\\n{}\"", ssf.name, path_to_string(&ssf.consumes));
+		if ssf.sends.is_none() {
+			attrs.push_str(", style = dashed");
+		}
+		if ssf.wip {
+			attrs.push_str(", color = gray");
+		}
+		let _ = writeln!(dot, "\t{} [{}];", ssf.name, attrs);
+	}
+
+	for sender in subsystems {
+		for sent in sender.sends.iter().flatten() {
+			let sent = path_to_string(sent);
+			for receiver in subsystems.iter().filter(|ssf| path_to_string(&ssf.consumes) == sent) {
+				let _ = writeln!(
+					dot,
+					"\t{} -> {} [label = \"{}\"];",
+					sender.name, receiver.name, sent
+				);
+			}
+		}
+	}
+
+	dot.push_str("}\n");
+	dot
+}
+
+/// Expose the message flow graph as a `const`, e.g. `OVERSEER_MESSAGE_FLOW`.
+pub(crate) fn impl_message_flow_graph(info: &OverseerInfo) -> proc_macro2::TokenStream {
+	let overseer_name = &info.overseer_name;
+	let graph_name = Ident::new(
+		&(screaming_snake_case(&overseer_name.to_string()) + "_MESSAGE_FLOW"),
+		overseer_name.span(),
+	);
+	let graph = message_flow_graph(overseer_name, info.subsystems());
+	let doc = format!(
+		"The message flow between the subsystems of [`{}`] in graphviz DOT format, \
+		as declared with `sends = [..]`. The senders given to the subsystems only accept \
+		the declared messages.",
+		overseer_name,
+	);
+
+	quote! {
+		#[doc = #doc]
+		pub const #graph_name: &str = #graph;
+	}
+}
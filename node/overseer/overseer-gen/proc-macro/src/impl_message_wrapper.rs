@@ -27,6 +27,7 @@ pub(crate) fn impl_message_wrapper_enum(info: &OverseerInfo) -> Result<proc_macr
 	let outgoing = &info.outgoing_ty;
 
 	let message_wrapper = &info.message_wrapper;
+	let support_crate = info.support_crate_name();
 
 	let (outgoing_from_impl, outgoing_decl) = if let Some(outgoing) = outgoing {
 		let outgoing_variant = outgoing.get_ident().ok_or_else(|| {
@@ -70,6 +71,11 @@ pub(crate) fn impl_message_wrapper_enum(info: &OverseerInfo) -> Result<proc_macr
 			}
 		}
 
+		// Contexts consuming the dummy message type may send anything.
+		impl #support_crate ::AssociateOutgoing< #message_wrapper > for () {
+			type OutgoingMessages = #message_wrapper;
+		}
+
 		#(
 			impl ::std::convert::From< #consumes > for #message_wrapper {
 				fn from(message: #consumes) -> Self {
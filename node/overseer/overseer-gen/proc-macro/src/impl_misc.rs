@@ -14,7 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use quote::quote;
+use quote::{quote, ToTokens};
+use std::collections::HashSet;
 use syn::Ident;
 
 use super::*;
@@ -27,7 +28,11 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 		Ident::new(&(overseer_name.to_string() + "SubsystemSender"), overseer_name.span());
 	let subsystem_ctx_name =
 		Ident::new(&(overseer_name.to_string() + "SubsystemContext"), overseer_name.span());
-	let consumes = &info.consumes();
+	let outgoing = info
+		.subsystems()
+		.iter()
+		.map(|ssf| impl_outgoing(info, ssf, &subsystem_sender_name))
+		.collect::<Vec<_>>();
 	let signal = &info.extern_signal_ty;
 	let wrapper_message = &info.message_wrapper;
 	let error_ty = &info.extern_error_ty;
@@ -36,63 +41,37 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 	let ts = quote! {
 		/// Connector to send messages towards all subsystems,
 		/// while tracking the which signals where already received.
-		#[derive(Debug, Clone)]
-		pub struct #subsystem_sender_name {
+		///
+		/// Given to the subsystem consuming `M`, it only accepts the messages that subsystem
+		/// may send, see [`AssociateOutgoing`].
+		pub struct #subsystem_sender_name<M> {
 			/// Collection of channels to all subsystems.
 			channels: ChannelsOut,
 			/// Systemwide tick for which signals were received by all subsystems.
 			signals_received: SignalsReceived,
+			_phantom: ::std::marker::PhantomData<fn() -> M>,
 		}
 
-		/// implementation for wrapping message type...
-		#[#support_crate ::async_trait]
-		impl SubsystemSender< #wrapper_message > for #subsystem_sender_name {
-			async fn send_message(&mut self, msg: #wrapper_message) {
-				self.channels.send_and_log_error(self.signals_received.load(), msg).await;
-			}
-
-			async fn send_messages<T>(&mut self, msgs: T)
-			where
-				T: IntoIterator<Item = #wrapper_message> + Send,
-				T::IntoIter: Send,
-			{
-				// This can definitely be optimized if necessary.
-				for msg in msgs {
-					self.send_message(msg).await;
+		impl<M> Clone for #subsystem_sender_name<M> {
+			fn clone(&self) -> Self {
+				Self {
+					channels: self.channels.clone(),
+					signals_received: self.signals_received.clone(),
+					_phantom: ::std::marker::PhantomData,
 				}
 			}
-
-			fn send_unbounded_message(&mut self, msg: #wrapper_message) {
-				self.channels.send_unbounded_and_log_error(self.signals_received.load(), msg);
-			}
 		}
 
-		// ... but also implement for all individual messages to avoid
-		// the necessity for manual wrapping, and do the conversion
-		// based on the generated `From::from` impl for the individual variants.
-		#(
-		#[#support_crate ::async_trait]
-		impl SubsystemSender< #consumes > for #subsystem_sender_name {
-			async fn send_message(&mut self, msg: #consumes) {
-				self.channels.send_and_log_error(self.signals_received.load(), #wrapper_message ::from ( msg )).await;
-			}
-
-			async fn send_messages<T>(&mut self, msgs: T)
-			where
-				T: IntoIterator<Item = #consumes> + Send,
-				T::IntoIter: Send,
-			{
-				// This can definitely be optimized if necessary.
-				for msg in msgs {
-					self.send_message(msg).await;
-				}
-			}
-
-			fn send_unbounded_message(&mut self, msg: #consumes) {
-				self.channels.send_unbounded_and_log_error(self.signals_received.load(), #wrapper_message ::from ( msg ));
+		impl<M> ::std::fmt::Debug for #subsystem_sender_name<M> {
+			fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+				f.debug_struct(stringify!(#subsystem_sender_name))
+					.field("channels", &self.channels)
+					.field("signals_received", &self.signals_received)
+					.finish()
 			}
 		}
-		)*
+
+		#( #outgoing )*
 
 		/// A context type that is given to the [`Subsystem`] upon spawning.
 		/// It can be used by [`Subsystem`] to communicate with other [`Subsystem`]s
@@ -106,7 +85,7 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 		pub struct #subsystem_ctx_name<M>{
 			signals: #support_crate ::metered::MeteredReceiver< #signal >,
 			messages: SubsystemIncomingMessages<M>,
-			to_subsystems: #subsystem_sender_name,
+			to_subsystems: #subsystem_sender_name<M>,
 			to_overseer: #support_crate ::metered::UnboundedMeteredSender<
 				#support_crate ::ToOverseer
 				>,
@@ -131,6 +110,7 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 					to_subsystems: #subsystem_sender_name {
 						channels: to_subsystems,
 						signals_received: signals_received.clone(),
+						_phantom: ::std::marker::PhantomData,
 					},
 					to_overseer,
 					signals_received,
@@ -145,14 +125,17 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 		}
 
 		#[#support_crate ::async_trait]
-		impl<M: std::fmt::Debug + Send + 'static> #support_crate ::SubsystemContext for #subsystem_ctx_name<M>
+		impl<M> #support_crate ::SubsystemContext for #subsystem_ctx_name<M>
 		where
-			#subsystem_sender_name: #support_crate ::SubsystemSender< #wrapper_message >,
+			M: #support_crate ::AssociateOutgoing< #wrapper_message > + std::fmt::Debug + Send + 'static,
+			#subsystem_sender_name<M>: #support_crate ::SubsystemSender<
+				<M as #support_crate ::AssociateOutgoing< #wrapper_message >>::OutgoingMessages
+			>,
 			#wrapper_message: From<M>,
 		{
 			type Message = M;
 			type Signal = #signal;
-			type Sender = #subsystem_sender_name;
+			type Sender = #subsystem_sender_name<M>;
 			type AllMessages = #wrapper_message;
 			type Error = #error_ty;
 
@@ -257,3 +240,104 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 
 	ts
 }
+
+/// The messages the subsystem `ssf` may send and the sender implementation accepting them.
+///
+/// Subsystems which declared `sends = [..]` get an enum of the declared messages and their
+/// own message type, all others may send any message wrapped in the overarching message type.
+fn impl_outgoing(
+	info: &OverseerInfo,
+	ssf: &SubSysField,
+	subsystem_sender_name: &Ident,
+) -> proc_macro2::TokenStream {
+	let consumes = &ssf.consumes;
+	let wrapper_message = &info.message_wrapper;
+	let support_crate = info.support_crate_name();
+
+	let (outgoing, outgoing_decl) = match ssf.sends {
+		Some(ref sends) => {
+			let outgoing = Ident::new(
+				&(ssf.generic.to_string() + "OutgoingMessages"),
+				ssf.generic.span(),
+			);
+			let mut seen = HashSet::new();
+			let sends = sends
+				.iter()
+				.chain(std::iter::once(consumes))
+				.filter(|sent| seen.insert(sent.to_token_stream().to_string()))
+				.collect::<Vec<_>>();
+			let variants = sends
+				.iter()
+				.map(|sent| {
+					sent.segments.last().expect("Paths have at least one segment. qed").ident.clone()
+				})
+				.collect::<Vec<_>>();
+			let doc = format!(
+				"The messages the subsystem consuming `{}` declared to send with `sends = [..]`, \
+				including its own.",
+				consumes.to_token_stream().to_string().replace(' ', ""),
+			);
+
+			let decl = quote! {
+				#[doc = #doc]
+				#[allow(missing_docs)]
+				#[derive(Debug)]
+				pub enum #outgoing {
+					#(
+						#variants ( #sends ),
+					)*
+				}
+
+				#(
+					impl ::std::convert::From< #sends > for #outgoing {
+						fn from(message: #sends) -> Self {
+							#outgoing :: #variants ( message )
+						}
+					}
+				)*
+
+				impl ::std::convert::From< #outgoing > for #wrapper_message {
+					fn from(message: #outgoing) -> Self {
+						match message {
+							#(
+								#outgoing :: #variants ( message ) => #wrapper_message ::from( message ),
+							)*
+						}
+					}
+				}
+			};
+			(outgoing.to_token_stream(), decl)
+		},
+		None => (wrapper_message.to_token_stream(), TokenStream::new()),
+	};
+
+	quote! {
+		#outgoing_decl
+
+		impl #support_crate ::AssociateOutgoing< #wrapper_message > for #consumes {
+			type OutgoingMessages = #outgoing;
+		}
+
+		#[#support_crate ::async_trait]
+		impl SubsystemSender< #outgoing > for #subsystem_sender_name< #consumes > {
+			async fn send_message(&mut self, msg: #outgoing) {
+				self.channels.send_and_log_error(self.signals_received.load(), #wrapper_message ::from( msg )).await;
+			}
+
+			async fn send_messages<T>(&mut self, msgs: T)
+			where
+				T: IntoIterator<Item = #outgoing> + Send,
+				T::IntoIter: Send,
+			{
+				// This can definitely be optimized if necessary.
+				for msg in msgs {
+					self.send_message(msg).await;
+				}
+			}
+
+			fn send_unbounded_message(&mut self, msg: #outgoing) {
+				self.channels.send_unbounded_and_log_error(self.signals_received.load(), #wrapper_message ::from( msg ));
+			}
+		}
+	}
+}
@@ -51,6 +51,7 @@ pub(crate) fn impl_overseer_struct(info: &OverseerInfo) -> proc_macro2::TokenStr
 
 	let message_channel_capacity = info.message_channel_capacity;
 	let signal_channel_capacity = info.signal_channel_capacity;
+	let tof_sample_every = info.tof_sample_every;
 
 	let log_target =
 		syn::LitStr::new(overseer_name.to_string().to_lowercase().as_str(), overseer_name.span());
@@ -63,6 +64,10 @@ pub(crate) fn impl_overseer_struct(info: &OverseerInfo) -> proc_macro2::TokenStr
 		/// Capacity of a signal channel between a subsystem and the overseer.
 		const SIGNAL_CHANNEL_CAPACITY: usize = #signal_channel_capacity;
 
+		/// Every n-th message sent to a subsystem is timestamped,
+		/// to measure the time it spends queued, none if zero.
+		const TIME_OF_FLIGHT_SAMPLE_EVERY: usize = #tof_sample_every;
+
 		/// The log target tag.
 		const LOG_TARGET: &'static str = #log_target;
 
@@ -94,6 +99,9 @@ pub(crate) fn impl_overseer_struct(info: &OverseerInfo) -> proc_macro2::TokenStr
 
 			/// Events that are sent to the overseer from the outside world.
 			events_rx: #support_crate ::metered::MeteredReceiver< #event_ty >,
+
+			/// Observer of all routed messages and broadcast signals.
+			recorder: #support_crate ::Recorder< #message_wrapper, #signal_ty >,
 		}
 
 		impl #generics #overseer_name #generics #where_clause {
@@ -103,6 +111,8 @@ pub(crate) fn impl_overseer_struct(info: &OverseerInfo) -> proc_macro2::TokenStr
 			/// The definition of a termination signal is up to the user and
 			/// implementation specific.
 			pub async fn wait_terminate(&mut self, signal: #signal_ty, timeout: ::std::time::Duration) -> ::std::result::Result<(), #error_ty > {
+				self.recorder.record_signal(&signal);
+
 				#(
 					::std::mem::drop(self. #subsystem_name .send_signal(signal.clone()).await);
 				)*
@@ -128,6 +138,8 @@ pub(crate) fn impl_overseer_struct(info: &OverseerInfo) -> proc_macro2::TokenStr
 
 			/// Broadcast a signal to all subsystems.
 			pub async fn broadcast_signal(&mut self, signal: #signal_ty) -> ::std::result::Result<(), #error_ty > {
+				self.recorder.record_signal(&signal);
+
 				#(
 					let _ = self. #subsystem_name .send_signal(signal.clone()).await;
 				)*
@@ -137,7 +149,9 @@ pub(crate) fn impl_overseer_struct(info: &OverseerInfo) -> proc_macro2::TokenStr
 			}
 
 			/// Route a particular message to a subsystem that consumes the message.
-			pub async fn route_message(&mut self, message: #message_wrapper, origin: &'static str) -> ::std::result::Result<(), #error_ty > {
+			pub async fn route_message(&mut self, mut message: #message_wrapper, origin: &'static str) -> ::std::result::Result<(), #error_ty > {
+				self.recorder.record_message(&mut message);
+
 				match message {
 					#(
 						#message_wrapper :: #consumes_variant ( inner ) =>
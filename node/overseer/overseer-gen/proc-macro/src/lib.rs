@@ -23,6 +23,7 @@ use syn::{parse2, Result};
 mod impl_builder;
 mod impl_channels_out;
 mod impl_dispatch;
+mod impl_graph;
 mod impl_message_wrapper;
 mod impl_misc;
 mod impl_overseer;
@@ -32,6 +33,7 @@ mod parse_struct;
 use impl_builder::*;
 use impl_channels_out::*;
 use impl_dispatch::*;
+use impl_graph::*;
 use impl_message_wrapper::*;
 use impl_misc::*;
 use impl_overseer::*;
@@ -81,6 +83,7 @@ pub(crate) fn impl_overseer_gen(
 		message_wrapper,
 		message_channel_capacity: args.message_channel_capacity,
 		signal_channel_capacity: args.signal_channel_capacity,
+		tof_sample_every: args.tof_sample_every,
 		extern_event_ty: args.extern_event_ty,
 		extern_signal_ty: args.extern_signal_ty,
 		extern_error_ty: args.extern_error_ty,
@@ -97,6 +100,7 @@ pub(crate) fn impl_overseer_gen(
 
 	additive.extend(impl_message_wrapper_enum(&info)?);
 	additive.extend(impl_dispatch(&info));
+	additive.extend(impl_message_flow_graph(&info));
 
 	// Write to a file for expansion, and then use it via `include!()`
 	// in order to obtain better compiler errors when modifying `overlord`.
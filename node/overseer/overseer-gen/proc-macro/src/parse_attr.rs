@@ -33,6 +33,7 @@ mod kw {
 	syn::custom_keyword!(gen);
 	syn::custom_keyword!(signal_capacity);
 	syn::custom_keyword!(message_capacity);
+	syn::custom_keyword!(tof_sample_every);
 }
 
 #[derive(Clone, Debug)]
@@ -45,6 +46,7 @@ enum OverseerAttrItem {
 	MessageWrapperName { tag: kw::gen, eq_token: Token![=], value: Ident },
 	SignalChannelCapacity { tag: kw::signal_capacity, eq_token: Token![=], value: usize },
 	MessageChannelCapacity { tag: kw::message_capacity, eq_token: Token![=], value: usize },
+	TofSampleEvery { tag: kw::tof_sample_every, eq_token: Token![=], value: usize },
 }
 
 impl ToTokens for OverseerAttrItem {
@@ -74,6 +76,9 @@ impl ToTokens for OverseerAttrItem {
 			Self::MessageChannelCapacity { tag, eq_token, value } => {
 				quote! { #tag #eq_token, #value }
 			},
+			Self::TofSampleEvery { tag, eq_token, value } => {
+				quote! { #tag #eq_token, #value }
+			},
 		};
 		tokens.extend(ts.into_iter());
 	}
@@ -130,6 +135,12 @@ impl Parse for OverseerAttrItem {
 				eq_token: input.parse()?,
 				value: input.parse::<LitInt>()?.base10_parse::<usize>()?,
 			})
+		} else if lookahead.peek(kw::tof_sample_every) {
+			Ok(OverseerAttrItem::TofSampleEvery {
+				tag: input.parse::<kw::tof_sample_every>()?,
+				eq_token: input.parse()?,
+				value: input.parse::<LitInt>()?.base10_parse::<usize>()?,
+			})
 		} else {
 			Err(lookahead.error())
 		}
@@ -150,6 +161,8 @@ pub(crate) struct AttrArgs {
 	pub(crate) outgoing_ty: Option<Path>,
 	pub(crate) signal_channel_capacity: usize,
 	pub(crate) message_channel_capacity: usize,
+	/// Sample the time of flight of every n-th message sent to a subsystem, never if zero.
+	pub(crate) tof_sample_every: usize,
 }
 
 macro_rules! extract_variant {
@@ -195,6 +208,7 @@ impl Parse for AttrArgs {
 			extract_variant!(unique, SignalChannelCapacity; default = 64_usize);
 		let message_channel_capacity =
 			extract_variant!(unique, MessageChannelCapacity; default = 1024_usize);
+		let tof_sample_every = extract_variant!(unique, TofSampleEvery; default = 16_usize);
 
 		let error = extract_variant!(unique, ExternErrorType; err = "Must declare the overseer error type via `error=..`.")?;
 		let event = extract_variant!(unique, ExternEventType; err = "Must declare the overseer event type via `event=..`.")?;
@@ -206,6 +220,7 @@ impl Parse for AttrArgs {
 		Ok(AttrArgs {
 			signal_channel_capacity,
 			message_channel_capacity,
+			tof_sample_every,
 			extern_event_ty: event,
 			extern_signal_ty: signal,
 			extern_error_ty: error,
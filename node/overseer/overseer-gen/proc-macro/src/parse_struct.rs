@@ -30,6 +30,90 @@ mod kw {
 	syn::custom_keyword!(wip);
 	syn::custom_keyword!(no_dispatch);
 	syn::custom_keyword!(blocking);
+	syn::custom_keyword!(restart);
+	syn::custom_keyword!(max);
+	syn::custom_keyword!(backoff);
+	syn::custom_keyword!(state);
+	syn::custom_keyword!(sends);
+}
+
+/// Restart policy of a subsystem,
+/// `restart(max = 3, backoff = 500, state = MyState)` with the backoff in milliseconds
+/// and an optional `RestartState` to bring a fresh instance up to date.
+#[derive(Clone, Debug)]
+pub(crate) struct RestartPolicy {
+	pub(crate) keyword: kw::restart,
+	pub(crate) max: syn::LitInt,
+	pub(crate) backoff: syn::LitInt,
+	pub(crate) state: Option<Type>,
+}
+
+impl Parse for RestartPolicy {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let keyword = input.parse::<kw::restart>()?;
+		let content;
+		let _ = syn::parenthesized!(content in input);
+
+		let _ = content.parse::<kw::max>()?;
+		let _ = content.parse::<Token![=]>()?;
+		let max = content.parse::<syn::LitInt>()?;
+		let _ = max.base10_parse::<u32>()?;
+
+		let backoff = if content.peek(Token![,]) && content.peek2(kw::backoff) {
+			let _ = content.parse::<Token![,]>()?;
+			let _ = content.parse::<kw::backoff>()?;
+			let _ = content.parse::<Token![=]>()?;
+			content.parse::<syn::LitInt>()?
+		} else {
+			syn::LitInt::new("1000", Span::call_site())
+		};
+		let _ = backoff.base10_parse::<u64>()?;
+
+		let state = if content.peek(Token![,]) && content.peek2(kw::state) {
+			let _ = content.parse::<Token![,]>()?;
+			let _ = content.parse::<kw::state>()?;
+			let _ = content.parse::<Token![=]>()?;
+			Some(content.parse::<Type>()?)
+		} else {
+			None
+		};
+
+		if !content.is_empty() {
+			return Err(content.error("Expected `restart(max = .., backoff = .., state = ..)`."))
+		}
+
+		Ok(Self { keyword, max, backoff, state })
+	}
+}
+
+/// Message types a subsystem sends to other subsystems,
+/// `sends = [A, B]`.
+#[derive(Clone, Debug)]
+pub(crate) struct Sends {
+	pub(crate) keyword: kw::sends,
+	pub(crate) sends: Punctuated<Path, Token![,]>,
+}
+
+impl Parse for Sends {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let keyword = input.parse::<kw::sends>()?;
+		let _ = input.parse::<Token![=]>()?;
+		let content;
+		let _ = syn::bracketed!(content in input);
+		let sends = content.parse_terminated(Path::parse)?;
+
+		let mut unique = HashSet::<String>::new();
+		for path in sends.iter() {
+			if !unique.insert(path.to_token_stream().to_string()) {
+				return Err(Error::new(
+					path.span(),
+					format!("Duplicate sent message type `{}`", path.to_token_stream()),
+				))
+			}
+		}
+
+		Ok(Self { keyword, sends })
+	}
 }
 
 #[derive(Clone, Debug)]
@@ -43,6 +127,23 @@ enum SubSysAttrItem {
 	/// External messages should not be - after being converted -
 	/// be dispatched to the annotated subsystem.
 	NoDispatch(kw::no_dispatch),
+	/// The subsystem is restarted when it fails, instead of
+	/// terminating the overseer.
+	Restart(RestartPolicy),
+	/// The message types the subsystem is allowed to send.
+	Sends(Sends),
+}
+
+impl SubSysAttrItem {
+	/// Whether the next tokens start an attribute item,
+	/// rather than the consumed message type.
+	fn peek(input: ParseStream) -> bool {
+		input.peek(kw::wip) ||
+			input.peek(kw::blocking) ||
+			input.peek(kw::no_dispatch) ||
+			input.peek(kw::restart) ||
+			input.peek(kw::sends)
+	}
 }
 
 impl Parse for SubSysAttrItem {
@@ -54,6 +155,10 @@ impl Parse for SubSysAttrItem {
 			Self::Blocking(input.parse::<kw::blocking>()?)
 		} else if lookahead.peek(kw::no_dispatch) {
 			Self::NoDispatch(input.parse::<kw::no_dispatch>()?)
+		} else if lookahead.peek(kw::restart) {
+			Self::Restart(input.parse::<RestartPolicy>()?)
+		} else if lookahead.peek(kw::sends) {
+			Self::Sends(input.parse::<Sends>()?)
 		} else {
 			return Err(lookahead.error())
 		})
@@ -72,6 +177,15 @@ impl ToTokens for SubSysAttrItem {
 			Self::NoDispatch(no_dispatch) => {
 				quote! { #no_dispatch }
 			},
+			Self::Restart(RestartPolicy { keyword, max, backoff, state: None }) => {
+				quote! { #keyword ( max = #max, backoff = #backoff ) }
+			},
+			Self::Restart(RestartPolicy { keyword, max, backoff, state: Some(state) }) => {
+				quote! { #keyword ( max = #max, backoff = #backoff, state = #state ) }
+			},
+			Self::Sends(Sends { keyword, sends }) => {
+				quote! { #keyword = [ #sends ] }
+			},
 		};
 		tokens.extend(ts.into_iter());
 	}
@@ -99,6 +213,12 @@ pub(crate) struct SubSysField {
 	/// Avoids dispatching `Wrapper` type messages, but generates the variants.
 	/// Does not require the subsystem to be instantiated with the builder pattern.
 	pub(crate) wip: bool,
+	/// Restart the subsystem according to this policy if it fails.
+	/// Requires the subsystem type to be `Clone`.
+	pub(crate) restart: Option<RestartPolicy>,
+	/// Message types the subsystem sends, if declared.
+	/// Restricts the messages the `SubsystemSender` given to it accepts.
+	pub(crate) sends: Option<Vec<Path>>,
 }
 
 fn try_type_to_path(ty: Type, span: Span) -> Result<Path> {
@@ -135,6 +255,8 @@ pub(crate) struct SubSystemTags {
 	/// and also not include the subsystem in the list of subsystems.
 	pub(crate) wip: bool,
 	pub(crate) blocking: bool,
+	pub(crate) restart: Option<RestartPolicy>,
+	pub(crate) sends: Option<Vec<Path>>,
 	pub(crate) consumes: Path,
 }
 
@@ -147,8 +269,8 @@ impl Parse for SubSystemTags {
 		let _ = syn::parenthesized!(content in input);
 
 		let mut items = Punctuated::new();
-		while let Ok(tag) = content.call(SubSysAttrItem::parse) {
-			items.push_value(tag);
+		while SubSysAttrItem::peek(&content) {
+			items.push_value(content.call(SubSysAttrItem::parse)?);
 			items.push_punct(content.call(<Token![,]>::parse)?);
 		}
 
@@ -175,8 +297,16 @@ impl Parse for SubSystemTags {
 		let no_dispatch = extract_variant!(unique, NoDispatch; default = false);
 		let blocking = extract_variant!(unique, Blocking; default = false);
 		let wip = extract_variant!(unique, Wip; default = false);
-
-		Ok(Self { attrs, no_dispatch, blocking, consumes, wip })
+		let restart = unique.values().find_map(|item| match item {
+			SubSysAttrItem::Restart(policy) => Some(policy.clone()),
+			_ => None,
+		});
+		let sends = unique.values().find_map(|item| match item {
+			SubSysAttrItem::Sends(sends) => Some(sends.sends.iter().cloned().collect()),
+			_ => None,
+		});
+
+		Ok(Self { attrs, no_dispatch, blocking, restart, sends, consumes, wip })
 	}
 }
 
@@ -209,6 +339,8 @@ pub(crate) struct OverseerInfo {
 	pub(crate) message_channel_capacity: usize,
 	/// Size of the bounded signal channel.
 	pub(crate) signal_channel_capacity: usize,
+	/// Sample the time of flight of every n-th message sent to a subsystem, never if zero.
+	pub(crate) tof_sample_every: usize,
 
 	/// Signals to be sent, sparse information that is used intermittently.
 	pub(crate) extern_signal_ty: Path,
@@ -389,6 +521,8 @@ impl OverseerGuts {
 					no_dispatch: variant.no_dispatch,
 					wip: variant.wip,
 					blocking: variant.blocking,
+					restart: variant.restart,
+					sends: variant.sends,
 				});
 			} else {
 				let field_ty = try_type_to_path(ty, ident.span())?;
@@ -399,6 +533,22 @@ impl OverseerGuts {
 				baggage.push(BaggageField { field_name: ident, generic, field_ty, vis });
 			}
 		}
+
+		// Every message a subsystem declares to send must have a consumer,
+		// otherwise it could never be routed.
+		let consumed = subsystems
+			.iter()
+			.map(|ssf| ssf.consumes.to_token_stream().to_string())
+			.collect::<HashSet<_>>();
+		for sent in subsystems.iter().filter_map(|ssf| ssf.sends.as_ref()).flatten() {
+			if !consumed.contains(&sent.to_token_stream().to_string()) {
+				return Err(Error::new(
+					sent.span(),
+					format!("`{}` is not consumed by any subsystem", sent.to_token_stream()),
+				))
+			}
+		}
+
 		Ok(Self { name, subsystems, baggage })
 	}
 }
@@ -27,6 +27,7 @@ fn print() {
 		signal=SigSigSig,
 		signal_capacity=111,
 		message_capacity=222,
+		tof_sample_every=4,
 		error=OverseerError,
 	};
 
@@ -35,13 +36,13 @@ fn print() {
 			#[subsystem(no_dispatch, Foo)]
 			sub0: FooSubsystem,
 
-			#[subsystem(blocking, Bar)]
+			#[subsystem(blocking, sends = [Foo, Rope], Bar)]
 			yyy: BaersBuyBilliardBalls,
 
 			#[subsystem(no_dispatch, blocking, Twain)]
 			fff: Beeeeep,
 
-			#[subsystem(Rope)]
+			#[subsystem(restart(max = 2, backoff = 100), Rope)]
 			mc: MountainCave,
 
 			metrics: Metrics,
@@ -86,19 +87,100 @@ fn struct_parse_basic() {
 	let _ = dbg!(item);
 }
 
+#[test]
+fn struct_parse_restart() {
+	let item: OverseerGuts = parse_quote! {
+		pub struct Ooooh {
+			#[subsystem(no_dispatch, restart(max = 3, backoff = 250), Foo)]
+			sub0: FooSubsystem,
+
+			#[subsystem(restart(max = 1), Bar)]
+			sub1: BarSubsystem,
+
+			#[subsystem(Baz)]
+			sub2: BazSubsystem,
+
+			#[subsystem(restart(max = 2, state = QuxState<Qux>), Qux)]
+			sub3: QuxSubsystem,
+		}
+	};
+	let policies = item
+		.subsystems
+		.iter()
+		.map(|ssf| {
+			ssf.restart.as_ref().map(|policy| {
+				(
+					policy.max.base10_parse::<u32>().unwrap(),
+					policy.backoff.base10_parse::<u64>().unwrap(),
+				)
+			})
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(policies, vec![Some((3, 250)), Some((1, 1000)), None, Some((2, 1000))]);
+	assert!(item.subsystems[0].no_dispatch);
+	assert!(item.subsystems[0].restart.as_ref().unwrap().state.is_none());
+	assert!(item.subsystems[3].restart.as_ref().unwrap().state.is_some());
+}
+
+#[test]
+fn struct_parse_sends() {
+	let item: OverseerGuts = parse_quote! {
+		pub struct Ooooh {
+			#[subsystem(sends = [Bar, Baz], Foo)]
+			sub0: FooSubsystem,
+
+			#[subsystem(no_dispatch, sends = [], Bar)]
+			sub1: BarSubsystem,
+
+			#[subsystem(Baz)]
+			sub2: BazSubsystem,
+		}
+	};
+	let sends = item
+		.subsystems
+		.iter()
+		.map(|ssf| {
+			ssf.sends.as_ref().map(|sends| {
+				sends.iter().map(|path| quote!(#path).to_string()).collect::<Vec<_>>()
+			})
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(sends, vec![Some(vec!["Bar".to_owned(), "Baz".to_owned()]), Some(vec![]), None]);
+
+	let graph = message_flow_graph(&item.name, &item.subsystems);
+	assert!(graph.starts_with("digraph Ooooh {"));
+	assert!(graph.contains("\tsub0 -> sub1 [label = \"Bar\"];"));
+	assert!(graph.contains("\tsub0 -> sub2 [label = \"Baz\"];"));
+	assert!(graph.contains("\tsub2 [label = \"sub2\\nBaz\", style = dashed];"));
+	assert!(!graph.contains("sub1 ->"));
+}
+
+#[test]
+fn struct_parse_sends_unconsumed() {
+	let item = syn::parse2::<OverseerGuts>(quote! {
+		pub struct Ooooh {
+			#[subsystem(sends = [Qux], Foo)]
+			sub0: FooSubsystem,
+		}
+	});
+	assert!(item.is_err());
+}
+
 #[test]
 fn attr_full() {
 	let attr: AttrArgs = parse_quote! {
 		gen=AllMessage, event=::some::why::ExternEvent, signal=SigSigSig, signal_capacity=111, message_capacity=222,
-		error=OverseerError,
+		tof_sample_every=4, error=OverseerError,
 	};
 	assert_matches!(attr, AttrArgs {
 		message_channel_capacity,
 		signal_channel_capacity,
+		tof_sample_every,
 		..
 	} => {
 		assert_eq!(message_channel_capacity, 222);
 		assert_eq!(signal_channel_capacity, 111);
+		assert_eq!(tof_sample_every, 4);
 	});
 }
 
@@ -98,6 +98,9 @@ pub use polkadot_node_network_protocol::WrongVariant;
 
 use std::fmt;
 
+mod supervisor;
+pub use self::supervisor::{supervise, RestartCounter, RestartPolicy, RestartState, MAX_BACKOFF};
+
 #[cfg(test)]
 mod tests;
 
@@ -142,6 +145,69 @@ impl fmt::Debug for ToOverseer {
 	}
 }
 
+/// Observes every message routed between subsystems and every signal
+/// broadcast by the overseer.
+///
+/// Implementations are invoked synchronously on the sending path, so they
+/// must not block; hand the data off to a background task instead.
+pub trait MessageRecorder<Message, Signal>: Send + Sync {
+	/// Called for each message before it is delivered to its consumer.
+	///
+	/// The message is passed mutably, so that response channels within it can be
+	/// replaced by ones which observe the response before forwarding it.
+	fn record_message(&self, message: &mut Message);
+
+	/// Called once for each signal before it is broadcast to all subsystems.
+	fn record_signal(&self, signal: &Signal);
+}
+
+/// An optional, shareable [`MessageRecorder`], disabled by default.
+pub struct Recorder<Message, Signal>(Option<Arc<dyn MessageRecorder<Message, Signal>>>);
+
+impl<Message, Signal> Recorder<Message, Signal> {
+	/// Create a recorder which forwards everything to `inner`.
+	pub fn new(inner: Arc<dyn MessageRecorder<Message, Signal>>) -> Self {
+		Self(Some(inner))
+	}
+
+	/// Whether a recorder is attached.
+	pub fn is_enabled(&self) -> bool {
+		self.0.is_some()
+	}
+
+	/// Record a message, if a recorder is attached.
+	pub fn record_message(&self, message: &mut Message) {
+		if let Some(ref inner) = self.0 {
+			inner.record_message(message);
+		}
+	}
+
+	/// Record a signal, if a recorder is attached.
+	pub fn record_signal(&self, signal: &Signal) {
+		if let Some(ref inner) = self.0 {
+			inner.record_signal(signal);
+		}
+	}
+}
+
+impl<Message, Signal> Default for Recorder<Message, Signal> {
+	fn default() -> Self {
+		Self(None)
+	}
+}
+
+impl<Message, Signal> Clone for Recorder<Message, Signal> {
+	fn clone(&self) -> Self {
+		Self(self.0.clone())
+	}
+}
+
+impl<Message, Signal> fmt::Debug for Recorder<Message, Signal> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Recorder {{ enabled: {} }}", self.is_enabled())
+	}
+}
+
 /// A helper trait to map a subsystem to smth. else.
 pub trait MapSubsystem<T> {
 	/// The output type of the mapping.
@@ -283,6 +349,8 @@ pub struct SubsystemMeters {
 	pub unbounded: metered::Meter,
 	#[allow(missing_docs)]
 	pub signals: metered::Meter,
+	/// Restarts of a supervised subsystem, always zero otherwise.
+	pub restarts: RestartCounter,
 }
 
 impl SubsystemMeters {
@@ -292,6 +360,16 @@ impl SubsystemMeters {
 			bounded: self.bounded.read(),
 			unbounded: self.unbounded.read(),
 			signals: self.signals.read(),
+			restarts: self.restarts.read(),
+		}
+	}
+
+	/// Take the time of flight samples of the messages sent to the subsystem,
+	/// collected since they were last taken.
+	pub fn take_tof(&self) -> SubsystemTofSamples {
+		SubsystemTofSamples {
+			bounded: self.bounded.take_tof(),
+			unbounded: self.unbounded.take_tof(),
 		}
 	}
 }
@@ -304,6 +382,16 @@ pub struct SubsystemMeterReadouts {
 	pub unbounded: metered::Readout,
 	#[allow(missing_docs)]
 	pub signals: metered::Readout,
+	/// Number of restarts of the subsystem.
+	pub restarts: usize,
+}
+
+/// Time of flight samples of the messages sent to a subsystem.
+pub struct SubsystemTofSamples {
+	/// Samples of the bounded message channel.
+	pub bounded: Vec<Duration>,
+	/// Samples of the unbounded message channel.
+	pub unbounded: Vec<Duration>,
 }
 
 /// A running instance of some [`Subsystem`].
@@ -360,14 +448,19 @@ pub trait SubsystemContext: Send + 'static {
 	/// The message type of this context. Subsystems launched with this context will expect
 	/// to receive messages of this type. Commonly uses the wrapping `enum` commonly called
 	/// `AllMessages`.
-	type Message: std::fmt::Debug + Send + 'static;
+	type Message: AssociateOutgoing<Self::AllMessages> + std::fmt::Debug + Send + 'static;
 	/// And the same for signals.
 	type Signal: std::fmt::Debug + Send + 'static;
 	/// The overarching all messages `enum`.
 	/// In some cases can be identical to `Self::Message`.
 	type AllMessages: From<Self::Message> + Send + 'static;
 	/// The sender type as provided by `sender()` and underlying.
-	type Sender: SubsystemSender<Self::AllMessages> + Send + 'static;
+	///
+	/// Only accepts the messages the subsystem declared to send.
+	type Sender: SubsystemSender<
+			<Self::Message as AssociateOutgoing<Self::AllMessages>>::OutgoingMessages,
+		> + Send
+		+ 'static;
 	/// The error type.
 	type Error: ::std::error::Error + ::std::convert::From<OverseerError> + Sync + Send + 'static;
 
@@ -395,12 +488,14 @@ pub trait SubsystemContext: Send + 'static {
 	) -> Result<(), Self::Error>;
 
 	/// Send a direct message to some other `Subsystem`, routed based on message type.
+	///
+	/// Only message types the subsystem declared to send are accepted.
 	async fn send_message<X>(&mut self, msg: X)
 	where
-		Self::AllMessages: From<X>,
+		<Self::Message as AssociateOutgoing<Self::AllMessages>>::OutgoingMessages: From<X>,
 		X: Send,
 	{
-		self.sender().send_message(<Self::AllMessages>::from(msg)).await
+		self.sender().send_message(From::from(msg)).await
 	}
 
 	/// Send multiple direct messages to other `Subsystem`s, routed based on message type.
@@ -408,21 +503,19 @@ pub trait SubsystemContext: Send + 'static {
 	where
 		T: IntoIterator<Item = X> + Send,
 		T::IntoIter: Send,
-		Self::AllMessages: From<X>,
+		<Self::Message as AssociateOutgoing<Self::AllMessages>>::OutgoingMessages: From<X>,
 		X: Send,
 	{
-		self.sender()
-			.send_messages(msgs.into_iter().map(|x| <Self::AllMessages>::from(x)))
-			.await
+		self.sender().send_messages(msgs.into_iter().map(From::from)).await
 	}
 
 	/// Send a message using the unbounded connection.
 	fn send_unbounded_message<X>(&mut self, msg: X)
 	where
-		Self::AllMessages: From<X>,
+		<Self::Message as AssociateOutgoing<Self::AllMessages>>::OutgoingMessages: From<X>,
 		X: Send,
 	{
-		self.sender().send_unbounded_message(Self::AllMessages::from(msg))
+		self.sender().send_unbounded_message(From::from(msg))
 	}
 
 	/// Obtain the sender.
@@ -446,6 +539,16 @@ where
 	fn start(self, ctx: Ctx) -> SpawnedSubsystem<E>;
 }
 
+/// Associates a message type consumed by a subsystem with the messages that subsystem may send.
+///
+/// Implemented by the generated code for every consumed message type. Subsystems which
+/// declared `sends = [..]` get a generated enum of the declared messages and their own,
+/// all others may send any message wrapped in `AllMessages`.
+pub trait AssociateOutgoing<AllMessages> {
+	/// The messages the subsystem consuming `Self` may send.
+	type OutgoingMessages: Into<AllMessages> + std::fmt::Debug + Send + 'static;
+}
+
 /// Sender end of a channel to interface with a subsystem.
 #[async_trait::async_trait]
 pub trait SubsystemSender<Message>: Send + Clone + 'static {
@@ -0,0 +1,239 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Supervision of subsystems annotated with `restart(..)`.
+//!
+//! A supervised subsystem does not receive the channels the overseer and the other
+//! subsystems send on directly. Instead, the supervisor owns them and forwards all
+//! signals and messages into a set of channels private to the running instance.
+//! If the instance fails, these are discarded and a fresh instance is started with
+//! fresh channels, while the channels known to the rest of the overseer stay intact.
+//!
+//! Signals and messages already forwarded to the failed instance are lost with its
+//! channels. Before the fresh instance sees anything sent after it was started, it is
+//! brought up to date by the [`RestartState`] of the subsystem, which observes everything
+//! forwarded to the running instance. Hence only subsystems which can rebuild all of their
+//! state from what their [`RestartState`] restores should be supervised.
+
+use std::{
+	panic::AssertUnwindSafe,
+	sync::{
+		atomic::{self, AtomicUsize},
+		Arc,
+	},
+	time::Duration,
+};
+
+use futures::{
+	future::{self, BoxFuture, Either},
+	select_biased, FutureExt, SinkExt, StreamExt,
+};
+
+use crate::{
+	make_packet, MessagePacket, OverseerError, Subsystem, SubsystemContext,
+	SubsystemIncomingMessages,
+};
+
+const LOG_TARGET: &str = "overseer::supervisor";
+
+/// How often and how quickly a failed subsystem is restarted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+	/// Maximum number of restarts over the lifetime of the overseer.
+	pub max_restarts: u32,
+	/// Delay before the first restart, doubled for every subsequent one
+	/// up to [`MAX_BACKOFF`].
+	pub backoff: Duration,
+}
+
+/// Upper bound of the delay before a restart.
+pub const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+impl RestartPolicy {
+	/// The delay before restarting after `restarts_so_far` restarts.
+	pub fn delay(&self, restarts_so_far: u32) -> Duration {
+		2_u32
+			.checked_pow(restarts_so_far)
+			.and_then(|factor| self.backoff.checked_mul(factor))
+			.map_or(MAX_BACKOFF, |delay| delay.min(MAX_BACKOFF))
+	}
+}
+
+/// Keeps track of what a supervised subsystem was told, so that a fresh instance can be
+/// brought up to date after a restart.
+pub trait RestartState<Signal, M>: Send + 'static {
+	/// Observe a signal forwarded to the running instance.
+	fn observe_signal(&mut self, signal: &Signal);
+
+	/// Observe a message forwarded to the running instance.
+	fn observe_message(&mut self, message: &M);
+
+	/// The signals, followed by the messages, a fresh instance receives before anything else.
+	fn restore(&self) -> (Vec<Signal>, Vec<M>);
+}
+
+/// Restores nothing, a fresh instance only sees what is sent after it was started.
+impl<Signal, M> RestartState<Signal, M> for () {
+	fn observe_signal(&mut self, _signal: &Signal) {}
+
+	fn observe_message(&mut self, _message: &M) {}
+
+	fn restore(&self) -> (Vec<Signal>, Vec<M>) {
+		(Vec::new(), Vec::new())
+	}
+}
+
+/// Counts the restarts of a single subsystem.
+#[derive(Debug, Default, Clone)]
+pub struct RestartCounter(Arc<AtomicUsize>);
+
+impl RestartCounter {
+	/// Number of restarts so far.
+	pub fn read(&self) -> usize {
+		self.0.load(atomic::Ordering::Relaxed)
+	}
+
+	fn inc(&self) {
+		self.0.fetch_add(1, atomic::Ordering::Relaxed);
+	}
+}
+
+/// Run `subsystem` under supervision of `policy`.
+///
+/// `signals` and `messages` are the channels the rest of the overseer sends on,
+/// `make_ctx` creates a context for a fresh instance from a fresh set of channels.
+/// `state` observes everything forwarded and restores it into a fresh instance.
+///
+/// An instance which fails or panics is replaced by a clone of the original
+/// `subsystem`, until the restarts are exhausted. After that, the error or panic
+/// is passed on, just as for an unsupervised subsystem. An instance which returns
+/// `Ok(())` is considered concluded and is not restarted.
+///
+/// Nothing sent to a failed instance is delivered to its replacement, apart from what
+/// `state` restores.
+pub fn supervise<M, Signal, Ctx, E, SubSys, F, St>(
+	name: &'static str,
+	policy: RestartPolicy,
+	subsystem: SubSys,
+	mut make_ctx: F,
+	mut signals: crate::metered::MeteredReceiver<Signal>,
+	mut messages: SubsystemIncomingMessages<M>,
+	signal_capacity: usize,
+	message_capacity: usize,
+	restarts: RestartCounter,
+	mut state: St,
+) -> BoxFuture<'static, Result<(), E>>
+where
+	M: Send + 'static,
+	Signal: Send + 'static,
+	Ctx: SubsystemContext<Message = M, Signal = Signal>,
+	E: std::error::Error + Send + Sync + 'static + From<OverseerError>,
+	SubSys: Subsystem<Ctx, E> + Clone + Send + 'static,
+	F: FnMut(crate::metered::MeteredReceiver<Signal>, SubsystemIncomingMessages<M>) -> Ctx,
+	F: Send + 'static,
+	St: RestartState<Signal, M>,
+{
+	Box::pin(async move {
+		// Signals received from the overseer so far.
+		let mut signals_received = 0_usize;
+
+		loop {
+			let (mut signal_tx, signal_rx) = crate::metered::channel(signal_capacity);
+			let (mut message_tx, message_rx) = crate::metered::channel(message_capacity);
+			let (_unbounded_tx, unbounded_rx) = crate::metered::unbounded();
+
+			// Signals already seen by others are never seen by this instance,
+			// apart from those restored.
+			let base = signals_received;
+			let (restored_signals, restored_messages) = state.restore();
+			let restored = restored_signals.len();
+			let ctx = make_ctx(signal_rx, crate::select(message_rx, unbounded_rx));
+			let instance = AssertUnwindSafe(subsystem.clone().start(ctx).future).catch_unwind();
+
+			// Owns the senders, such that the instance learns about the overseer going away.
+			let (signals, messages, state) = (&mut signals, &mut messages, &mut state);
+			let received = &mut signals_received;
+			let forward = async move {
+				for signal in restored_signals {
+					if signal_tx.send(signal).await.is_err() {
+						return
+					}
+				}
+				for message in restored_messages {
+					if message_tx.send(make_packet(restored, message)).await.is_err() {
+						return
+					}
+				}
+
+				loop {
+					select_biased! {
+						signal = signals.next() => match signal {
+							Some(signal) => {
+								state.observe_signal(&signal);
+								if signal_tx.send(signal).await.is_err() {
+									break
+								}
+								*received += 1;
+							},
+							None => break,
+						},
+						packet = messages.next() => match packet {
+							Some(MessagePacket { signals_received: level, message }) => {
+								state.observe_message(&message);
+								let level = level.saturating_sub(base) + restored;
+								if message_tx.send(make_packet(level, message)).await.is_err() {
+									break
+								}
+							},
+							None => break,
+						},
+					}
+				}
+			};
+
+			futures::pin_mut!(forward);
+			let res = match future::select(instance, forward).await {
+				Either::Left((res, _)) => res,
+				// Either the overseer or the instance went away.
+				Either::Right(((), instance)) => instance.await,
+			};
+
+			let restarts_so_far = restarts.read() as u32;
+			let exhausted = restarts_so_far >= policy.max_restarts;
+			match res {
+				Ok(Ok(())) => return Ok(()),
+				Ok(Err(err)) if exhausted => return Err(err),
+				Err(panic) if exhausted => std::panic::resume_unwind(panic),
+				Ok(Err(err)) => tracing::warn!(
+					target: LOG_TARGET,
+					subsystem = name,
+					?err,
+					restarts = restarts_so_far,
+					"Subsystem failed, restarting",
+				),
+				Err(_) => tracing::warn!(
+					target: LOG_TARGET,
+					subsystem = name,
+					restarts = restarts_so_far,
+					"Subsystem panicked, restarting",
+				),
+			}
+
+			restarts.inc();
+			crate::Delay::new(policy.delay(restarts_so_far)).await;
+		}
+	})
+}
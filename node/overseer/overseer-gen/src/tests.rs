@@ -7,3 +7,17 @@ fn ui_compile_fail() {
 	let t = trybuild::TestCases::new();
 	t.compile_fail("tests/ui/err-*.rs");
 }
+
+#[test]
+fn restart_delay_doubles_up_to_max_backoff() {
+	use std::time::Duration;
+
+	let policy =
+		crate::RestartPolicy { max_restarts: u32::MAX, backoff: Duration::from_millis(500) };
+	assert_eq!(policy.delay(0), Duration::from_millis(500));
+	assert_eq!(policy.delay(3), Duration::from_millis(4000));
+	assert_eq!(policy.delay(7), crate::MAX_BACKOFF);
+	// Neither the factor nor the product overflow.
+	assert_eq!(policy.delay(40), crate::MAX_BACKOFF);
+	assert_eq!(policy.delay(u32::MAX), crate::MAX_BACKOFF);
+}
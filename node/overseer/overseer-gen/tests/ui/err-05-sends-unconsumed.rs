@@ -0,0 +1,42 @@
+#![allow(dead_code)]
+
+use polkadot_overseer_gen::*;
+
+#[derive(Default)]
+struct AwesomeSubSys;
+
+#[derive(Default)]
+struct AwesomeSubSys2;
+
+#[derive(Clone, Debug)]
+struct SigSigSig;
+
+struct Event;
+
+#[derive(Clone, Debug)]
+struct MsgStrukt(u8);
+
+#[derive(Clone, Debug)]
+struct MsgStrukt2(f64);
+
+#[overlord(signal=SigSigSig, event=Event, gen=AllMessages, error=OverseerError)]
+struct Overseer {
+	#[subsystem(sends = [Unknown], MsgStrukt)]
+	sub0: AwesomeSubSys,
+
+	#[subsystem(MsgStrukt2)]
+	sub1: AwesomeSubSys2,
+}
+
+#[derive(Debug, Clone)]
+struct DummySpawner;
+
+struct DummyCtx;
+
+fn main() {
+	let overseer = Overseer::<_,_>::builder()
+		.sub0(AwesomeSubSys::default())
+		.sub1(AwesomeSubSys2::default())
+		.spawner(DummySpawner)
+		.build(|| -> DummyCtx { DummyCtx } );
+}
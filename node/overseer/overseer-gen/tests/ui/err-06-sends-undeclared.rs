@@ -0,0 +1,35 @@
+#![allow(dead_code)]
+
+use polkadot_overseer_gen::*;
+
+#[derive(Default)]
+struct AwesomeSubSys;
+
+#[derive(Default)]
+struct AwesomeSubSys2;
+
+#[derive(Clone, Debug)]
+struct SigSigSig;
+
+struct Event;
+
+#[derive(Clone, Debug)]
+struct MsgStrukt(u8);
+
+#[derive(Clone, Debug)]
+struct MsgStrukt2(f64);
+
+#[overlord(signal=SigSigSig, event=Event, gen=AllMessages, error=OverseerError)]
+struct Overseer {
+	#[subsystem(sends = [], MsgStrukt)]
+	sub0: AwesomeSubSys,
+
+	#[subsystem(sends = [MsgStrukt], MsgStrukt2)]
+	sub1: AwesomeSubSys2,
+}
+
+async fn run(mut ctx: OverseerSubsystemContext<MsgStrukt>) {
+	ctx.sender().send_message(MsgStrukt2(1.0)).await;
+}
+
+fn main() {}
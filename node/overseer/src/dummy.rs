@@ -138,6 +138,8 @@ where
 	Spawner: SpawnNamed + Send + Sync + 'static,
 	SupportsParachains: HeadSupportsParachains,
 	Sub: Clone
+		+ Send
+		+ 'static
 		+ Subsystem<OverseerSubsystemContext<AvailabilityDistributionMessage>, SubsystemError>
 		+ Subsystem<OverseerSubsystemContext<AvailabilityRecoveryMessage>, SubsystemError>
 		+ Subsystem<OverseerSubsystemContext<AvailabilityStoreMessage>, SubsystemError>
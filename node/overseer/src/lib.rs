@@ -99,6 +99,13 @@ pub use self::metrics::Metrics as OverseerMetrics;
 pub mod dummy;
 pub use self::dummy::DummySubsystem;
 
+/// Recording and replay of overseer traffic.
+pub mod recording;
+
+/// State restored into restarted subsystems.
+pub mod restart;
+pub use self::restart::NetworkState;
+
 pub use polkadot_node_metrics::{
 	metrics::{prometheus, Metrics as MetricsTrait},
 	Metronome,
@@ -108,11 +115,15 @@ use parity_util_mem::MemoryAllocationTracker;
 
 pub use polkadot_overseer_gen as gen;
 pub use polkadot_overseer_gen::{
-	overlord, FromOverseer, MapSubsystem, MessagePacket, SignalsReceived, SpawnNamed, Subsystem,
-	SubsystemContext, SubsystemIncomingMessages, SubsystemInstance, SubsystemMeterReadouts,
-	SubsystemMeters, SubsystemSender, TimeoutExt, ToOverseer,
+	overlord, AssociateOutgoing, FromOverseer, MapSubsystem, MessagePacket, MessageRecorder,
+	SignalsReceived, SpawnNamed, Subsystem, SubsystemContext, SubsystemIncomingMessages,
+	SubsystemInstance, SubsystemMeterReadouts, SubsystemMeters, SubsystemSender,
+	SubsystemTofSamples, TimeoutExt, ToOverseer,
 };
 
+/// The messages the subsystem consuming `M` may send, as declared with `sends = [..]`.
+pub type OutgoingMessagesOf<M> = <M as AssociateOutgoing<AllMessages>>::OutgoingMessages;
+
 /// Store 2 days worth of blocks, not accounting for forks,
 /// in the LRU cache. Assumes a 6-second block time.
 pub const KNOWN_LEAVES_CACHE_SIZE: usize = 2 * 24 * 3600 / 6;
@@ -417,67 +428,142 @@ pub async fn forward_events<P: BlockchainEvents<Block>>(client: Arc<P>, mut hand
 	network=NetworkBridgeEvent<protocol_v1::ValidationProtocol>,
 )]
 pub struct Overseer<SupportsParachains> {
-	#[subsystem(no_dispatch, CandidateValidationMessage)]
+	#[subsystem(no_dispatch, sends = [RuntimeApiMessage], CandidateValidationMessage)]
 	candidate_validation: CandidateValidation,
 
-	#[subsystem(no_dispatch, PvfCheckerMessage)]
+	#[subsystem(no_dispatch, sends = [
+		CandidateValidationMessage,
+		RuntimeApiMessage,
+	], PvfCheckerMessage)]
 	pvf_checker: PvfChecker,
 
-	#[subsystem(no_dispatch, CandidateBackingMessage)]
+	#[subsystem(no_dispatch, sends = [
+		CandidateValidationMessage,
+		StatementDistributionMessage,
+		AvailabilityDistributionMessage,
+		ProvisionerMessage,
+		RuntimeApiMessage,
+		AvailabilityStoreMessage,
+		CollatorProtocolMessage,
+		DisputeCoordinatorMessage,
+	], CandidateBackingMessage)]
 	candidate_backing: CandidateBacking,
 
-	#[subsystem(StatementDistributionMessage)]
+	#[subsystem(sends = [
+		CandidateBackingMessage,
+		RuntimeApiMessage,
+		NetworkBridgeMessage,
+	], StatementDistributionMessage)]
 	statement_distribution: StatementDistribution,
 
-	#[subsystem(no_dispatch, AvailabilityDistributionMessage)]
+	#[subsystem(no_dispatch, sends = [
+		RuntimeApiMessage,
+		AvailabilityStoreMessage,
+		NetworkBridgeMessage,
+	], AvailabilityDistributionMessage)]
 	availability_distribution: AvailabilityDistribution,
 
-	#[subsystem(no_dispatch, AvailabilityRecoveryMessage)]
+	#[subsystem(no_dispatch, sends = [
+		RuntimeApiMessage,
+		AvailabilityStoreMessage,
+		NetworkBridgeMessage,
+	], AvailabilityRecoveryMessage)]
 	availability_recovery: AvailabilityRecovery,
 
-	#[subsystem(blocking, no_dispatch, BitfieldSigningMessage)]
+	#[subsystem(blocking, no_dispatch, sends = [
+		BitfieldDistributionMessage,
+		RuntimeApiMessage,
+		AvailabilityStoreMessage,
+	], BitfieldSigningMessage)]
 	bitfield_signing: BitfieldSigning,
 
-	#[subsystem(BitfieldDistributionMessage)]
+	#[subsystem(restart(max = 3, backoff = 500, state = NetworkState<BitfieldDistributionMessage>), sends = [
+		ProvisionerMessage,
+		RuntimeApiMessage,
+		NetworkBridgeMessage,
+	], BitfieldDistributionMessage)]
 	bitfield_distribution: BitfieldDistribution,
 
-	#[subsystem(no_dispatch, ProvisionerMessage)]
+	#[subsystem(no_dispatch, sends = [
+		CandidateBackingMessage,
+		RuntimeApiMessage,
+		ChainApiMessage,
+		DisputeCoordinatorMessage,
+	], ProvisionerMessage)]
 	provisioner: Provisioner,
 
-	#[subsystem(no_dispatch, blocking, RuntimeApiMessage)]
+	#[subsystem(no_dispatch, blocking, sends = [], RuntimeApiMessage)]
 	runtime_api: RuntimeApi,
 
-	#[subsystem(no_dispatch, blocking, AvailabilityStoreMessage)]
+	#[subsystem(no_dispatch, blocking, sends = [
+		RuntimeApiMessage,
+		ChainApiMessage,
+	], AvailabilityStoreMessage)]
 	availability_store: AvailabilityStore,
 
-	#[subsystem(no_dispatch, NetworkBridgeMessage)]
+	#[subsystem(no_dispatch, sends = [
+		StatementDistributionMessage,
+		BitfieldDistributionMessage,
+		CollatorProtocolMessage,
+		ApprovalDistributionMessage,
+		GossipSupportMessage,
+	], NetworkBridgeMessage)]
 	network_bridge: NetworkBridge,
 
-	#[subsystem(no_dispatch, blocking, ChainApiMessage)]
+	#[subsystem(no_dispatch, blocking, sends = [], ChainApiMessage)]
 	chain_api: ChainApi,
 
-	#[subsystem(no_dispatch, CollationGenerationMessage)]
+	#[subsystem(no_dispatch, sends = [
+		RuntimeApiMessage,
+		CollatorProtocolMessage,
+	], CollationGenerationMessage)]
 	collation_generation: CollationGeneration,
 
-	#[subsystem(no_dispatch, CollatorProtocolMessage)]
+	#[subsystem(no_dispatch, sends = [
+		CandidateBackingMessage,
+		RuntimeApiMessage,
+		NetworkBridgeMessage,
+	], CollatorProtocolMessage)]
 	collator_protocol: CollatorProtocol,
 
-	#[subsystem(ApprovalDistributionMessage)]
+	#[subsystem(sends = [NetworkBridgeMessage, ApprovalVotingMessage], ApprovalDistributionMessage)]
 	approval_distribution: ApprovalDistribution,
 
-	#[subsystem(no_dispatch, ApprovalVotingMessage)]
+	#[subsystem(no_dispatch, sends = [
+		CandidateValidationMessage,
+		AvailabilityRecoveryMessage,
+		RuntimeApiMessage,
+		ChainApiMessage,
+		ApprovalDistributionMessage,
+		DisputeCoordinatorMessage,
+		ChainSelectionMessage,
+	], ApprovalVotingMessage)]
 	approval_voting: ApprovalVoting,
 
-	#[subsystem(GossipSupportMessage)]
+	#[subsystem(restart(max = 3, backoff = 500, state = NetworkState<GossipSupportMessage>), sends = [
+		RuntimeApiMessage,
+		NetworkBridgeMessage,
+	], GossipSupportMessage)]
 	gossip_support: GossipSupport,
 
-	#[subsystem(no_dispatch, DisputeCoordinatorMessage)]
+	#[subsystem(no_dispatch, sends = [
+		CandidateValidationMessage,
+		AvailabilityRecoveryMessage,
+		RuntimeApiMessage,
+		AvailabilityStoreMessage,
+		ChainApiMessage,
+		DisputeDistributionMessage,
+	], DisputeCoordinatorMessage)]
 	dispute_coordinator: DisputeCoordinator,
 
-	#[subsystem(no_dispatch, DisputeDistributionMessage)]
+	#[subsystem(no_dispatch, sends = [
+		RuntimeApiMessage,
+		NetworkBridgeMessage,
+		DisputeCoordinatorMessage,
+	], DisputeDistributionMessage)]
 	dispute_distribution: DisputeDistribution,
 
-	#[subsystem(no_dispatch, ChainSelectionMessage)]
+	#[subsystem(no_dispatch, sends = [ChainApiMessage], ChainSelectionMessage)]
 	chain_selection: ChainSelection,
 
 	/// External listeners waiting for a hash to be in the active-leave set.
@@ -568,6 +654,13 @@ where
 				.filter_map(|x| x)
 				.map(|(name, ref meters)| (name, meters.read())),
 		);
+		metronome_metrics.time_of_flight_samples(
+			subsystem_meters
+				.iter()
+				.cloned()
+				.filter_map(|x| x)
+				.map(|(name, ref meters)| (name, meters.take_tof())),
+		);
 
 		futures::future::ready(())
 	});
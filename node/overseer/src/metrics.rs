@@ -21,6 +21,10 @@ pub use polkadot_node_metrics::metrics::{self, prometheus, Metrics as MetricsTra
 
 use parity_util_mem::MemoryAllocationSnapshot;
 
+/// Buckets for the time messages spend queued, in seconds.
+const TOF_BUCKETS: [f64; 14] =
+	[0.0001, 0.0005, 0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
 /// Overseer Prometheus metrics.
 #[derive(Clone)]
 struct MetricsInner {
@@ -31,8 +35,11 @@ struct MetricsInner {
 	to_subsystem_bounded_received: prometheus::GaugeVec<prometheus::U64>,
 	to_subsystem_unbounded_sent: prometheus::GaugeVec<prometheus::U64>,
 	to_subsystem_unbounded_received: prometheus::GaugeVec<prometheus::U64>,
+	to_subsystem_bounded_tof: prometheus::HistogramVec,
+	to_subsystem_unbounded_tof: prometheus::HistogramVec,
 	signals_sent: prometheus::GaugeVec<prometheus::U64>,
 	signals_received: prometheus::GaugeVec<prometheus::U64>,
+	subsystem_restarts: prometheus::GaugeVec<prometheus::U64>,
 
 	memory_stats_resident: prometheus::Gauge<prometheus::U64>,
 	memory_stats_allocated: prometheus::Gauge<prometheus::U64>,
@@ -105,9 +112,33 @@ impl Metrics {
 						.signals_received
 						.with_label_values(&[name])
 						.set(readouts.signals.received as u64);
+
+					metrics
+						.subsystem_restarts
+						.with_label_values(&[name])
+						.set(readouts.restarts as u64);
 				});
 		}
 	}
+
+	pub(crate) fn time_of_flight_samples(
+		&self,
+		collection: impl IntoIterator<Item = (&'static str, SubsystemTofSamples)>,
+	) {
+		if let Some(metrics) = &self.0 {
+			for (name, samples) in collection {
+				let bounded_tof = metrics.to_subsystem_bounded_tof.with_label_values(&[name]);
+				for tof in samples.bounded {
+					bounded_tof.observe(tof.as_secs_f64());
+				}
+
+				let unbounded_tof = metrics.to_subsystem_unbounded_tof.with_label_values(&[name]);
+				for tof in samples.unbounded {
+					unbounded_tof.observe(tof.as_secs_f64());
+				}
+			}
+		}
+	}
 }
 
 impl MetricsTrait for Metrics {
@@ -174,6 +205,28 @@ impl MetricsTrait for Metrics {
 				)?,
 				registry,
 			)?,
+			to_subsystem_bounded_tof: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_subsystem_bounded_tof",
+						"Duration sampled messages spent in subsystems' bounded queues",
+					)
+					.buckets(TOF_BUCKETS.to_vec()),
+					&["subsystem_name"],
+				)?,
+				registry,
+			)?,
+			to_subsystem_unbounded_tof: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_subsystem_unbounded_tof",
+						"Duration sampled messages spent in subsystems' unbounded queues",
+					)
+					.buckets(TOF_BUCKETS.to_vec()),
+					&["subsystem_name"],
+				)?,
+				registry,
+			)?,
 			signals_sent: prometheus::register(
 				prometheus::GaugeVec::<prometheus::U64>::new(
 					prometheus::Opts::new(
@@ -194,6 +247,16 @@ impl MetricsTrait for Metrics {
 				)?,
 				registry,
 			)?,
+			subsystem_restarts: prometheus::register(
+				prometheus::GaugeVec::<prometheus::U64>::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_subsystem_restarts",
+						"Number of times a subsystem was restarted after failing",
+					),
+					&["subsystem_name"],
+				)?,
+				registry,
+			)?,
 
 			memory_stats_allocated: prometheus::register(
 				prometheus::Gauge::<prometheus::U64>::new(
@@ -0,0 +1,885 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Conversion of message contents into their recorded form and back.
+
+use std::{
+	collections::{BTreeMap, HashSet},
+	convert::TryFrom,
+	fmt,
+	hash::Hash as StdHash,
+	sync::Arc,
+	time::Duration,
+};
+
+use futures::{
+	channel::oneshot,
+	future::{self, BoxFuture, Either},
+	FutureExt,
+};
+use parity_scale_codec::{Decode, Encode};
+use parking_lot::Mutex;
+
+use polkadot_node_network_protocol::{
+	peer_set::PeerSet,
+	request_response::{v1 as req_v1, OutgoingRequest, Recipient},
+	v1 as protocol_v1, IfDisconnected, ObservedRole, OurView, PeerId, UnifiedReputationChange,
+	View,
+};
+use polkadot_node_primitives::{
+	approval::{
+		BlockApprovalMeta, BlockApprovalStatus, CandidateBitfield, IndirectAssignmentCert,
+		IndirectSignedApprovalVote,
+	},
+	availability::AvailabilityAttestations,
+	backing::BackingTraceEntry,
+	disputes::UncheckedDisputeMessage,
+	gossip_support::{ConnectivityReport, GossipTopologyInfo},
+	AvailableData, BabeEpoch, CandidateVotes, CollationGenerationConfig, CollationSecondedSignal,
+	DisputeMessage, ErasureChunk, PoV, SignedDisputeStatement, SignedFullStatement,
+	ValidationResult,
+};
+use polkadot_node_subsystem_types::{
+	errors::{ChainApiError, RecoveryError, RuntimeApiError},
+	messages::{
+		ApprovalCheckResult, AssignmentCheckResult, BlockDescription, GossipTopology,
+		HighestApprovedAncestorBlock, ImportStatementsResult, PreCheckOutcome,
+		ProvisionerInherentData, ValidationFailed,
+	},
+};
+use polkadot_primitives::{
+	v1::{
+		AuthorityDiscoveryId, BackedCandidate, CandidateCommitments, CandidateDescriptor,
+		CandidateEvent, CandidateHash, CandidateReceipt, CollatorId, CommittedCandidateReceipt,
+		CoreState, DisputeStatement, DisputeStatementSet, GroupIndex, GroupRotationInfo, Hash,
+		Header, Id as ParaId, InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption,
+		PersistedValidationData, ScrapedOnChainVotes, SessionIndex, Signed,
+		SignedAvailabilityBitfields, UncheckedSigned, ValidationCode, ValidationCodeHash,
+		ValidatorId, ValidatorIndex, ValidatorSignature,
+	},
+	v2::{slashing, InherentWeightInfo, PvfCheckStatement, SessionInfo},
+};
+use sc_network::{Multiaddr, OutboundFailure, RequestFailure};
+
+use super::{RecordEntry, RecordedEvent};
+use crate::jaeger;
+
+/// A part of a message which can be recorded and reconstructed.
+pub trait Field: Sized {
+	/// The recorded form of the field, which holds no response channels.
+	type Recorded: Encode + Decode + fmt::Debug + Send + 'static;
+
+	/// Capture the field. Response channels within it are treated according to `responses`.
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded;
+
+	/// Reconstruct the field, `None` if that is not possible.
+	///
+	/// Response channels are replaced by fresh channels whose receiver is dropped.
+	fn replay(recorded: Self::Recorded) -> Option<Self>;
+}
+
+/// Forwards the response on an intercepted channel and yields it as an entry to record.
+pub(crate) type Forwarder = BoxFuture<'static, Option<RecordEntry>>;
+
+/// Sends a recorded response on a captured channel. Returns whether it was sent.
+type Answer = Box<dyn FnOnce(&[u8]) -> bool + Send>;
+
+enum Mode {
+	Ignore,
+	Record { request: u64, forwarders: Vec<Forwarder> },
+	Capture { answers: Vec<Option<Answer>> },
+}
+
+/// Determines what happens to the response channels of a message when it is recorded.
+///
+/// Channels are numbered in the order they are encountered within the message.
+pub struct Responses {
+	mode: Mode,
+	channels: u32,
+}
+
+impl Responses {
+	/// Leave response channels untouched.
+	pub fn ignore() -> Self {
+		Responses { mode: Mode::Ignore, channels: 0 }
+	}
+
+	/// Replace response channels by ones which forward the response to the original channel
+	/// and record it as a response to the message with sequence number `request`.
+	pub(crate) fn record(request: u64) -> Self {
+		Responses { mode: Mode::Record { request, forwarders: Vec::new() }, channels: 0 }
+	}
+
+	/// Take the response channels out of the message, so that they can be served from
+	/// recorded responses by [`Responses::answer`].
+	pub fn capture() -> Self {
+		Responses { mode: Mode::Capture { answers: Vec::new() }, channels: 0 }
+	}
+
+	/// The number of response channels encountered so far.
+	pub fn channels(&self) -> u32 {
+		self.channels
+	}
+
+	/// Send the recorded response `payload` on the captured channel `index`.
+	///
+	/// Returns `false` if there is no such channel, the payload does not decode or the
+	/// receiver is gone.
+	pub fn answer(&mut self, index: u32, payload: &[u8]) -> bool {
+		match self.mode {
+			Mode::Capture { ref mut answers } => answers
+				.get_mut(index as usize)
+				.and_then(Option::take)
+				.map_or(false, |answer| answer(payload)),
+			_ => false,
+		}
+	}
+
+	pub(crate) fn into_forwarders(self) -> Vec<Forwarder> {
+		match self.mode {
+			Mode::Record { forwarders, .. } => forwarders,
+			_ => Vec::new(),
+		}
+	}
+}
+
+impl<T> Field for oneshot::Sender<T>
+where
+	T: Field + Send + 'static,
+{
+	type Recorded = ();
+
+	fn record(&mut self, responses: &mut Responses) {
+		let index = responses.channels;
+		responses.channels += 1;
+
+		match responses.mode {
+			Mode::Ignore => {},
+			Mode::Record { request, ref mut forwarders } => {
+				let (tx, rx) = oneshot::channel();
+				let mut original = std::mem::replace(self, tx);
+
+				forwarders.push(
+					async move {
+						// Dropping `rx` tells the responder that the requester is gone.
+						let mut value = {
+							let cancelled = original.cancellation();
+							futures::pin_mut!(cancelled);
+							match future::select(rx, cancelled).await {
+								Either::Left((Ok(value), _)) => value,
+								_ => return None,
+							}
+						};
+
+						let payload = value.record(&mut Responses::ignore()).encode();
+						let _ = original.send(value);
+
+						Some(RecordEntry::now(RecordedEvent::Response { request, index, payload }))
+					}
+					.boxed(),
+				);
+			},
+			Mode::Capture { ref mut answers } => {
+				let original = std::mem::replace(self, oneshot::channel().0);
+
+				answers.push(Some(Box::new(move |mut payload: &[u8]| {
+					match T::Recorded::decode(&mut payload).ok().and_then(T::replay) {
+						Some(value) => original.send(value).is_ok(),
+						None => false,
+					}
+				})));
+			},
+		}
+	}
+
+	fn replay(_: ()) -> Option<Self> {
+		Some(oneshot::channel().0)
+	}
+}
+
+macro_rules! plain_fields {
+	($($ty:ty),* $(,)?) => {
+		$(
+			impl Field for $ty {
+				type Recorded = Self;
+
+				fn record(&mut self, _: &mut Responses) -> Self {
+					self.clone()
+				}
+
+				fn replay(recorded: Self) -> Option<Self> {
+					Some(recorded)
+				}
+			}
+		)*
+	};
+}
+
+plain_fields!(
+	(),
+	bool,
+	u8,
+	u32,
+	u64,
+	String,
+	Hash,
+	CandidateHash,
+	ParaId,
+	CollatorId,
+	ValidatorId,
+	AuthorityDiscoveryId,
+	ValidatorIndex,
+	ValidatorSignature,
+	GroupIndex,
+	ValidationCode,
+	ValidationCodeHash,
+	CandidateReceipt,
+	CommittedCandidateReceipt,
+	CandidateDescriptor,
+	CandidateCommitments,
+	PersistedValidationData,
+	OccupiedCoreAssumption,
+	BackedCandidate,
+	GroupRotationInfo,
+	CoreState,
+	CandidateEvent,
+	SessionInfo,
+	InboundDownwardMessage,
+	InboundHrmpMessage,
+	ScrapedOnChainVotes,
+	PvfCheckStatement,
+	InherentWeightInfo,
+	DisputeStatementSet,
+	Header,
+	BabeEpoch,
+	slashing::PendingSlashes,
+	slashing::OpaqueKeyOwnershipProof,
+	slashing::DisputeProof,
+	slashing::BackingMisbehaviorProof,
+	PoV,
+	AvailableData,
+	ErasureChunk,
+	CandidateVotes,
+	ValidationResult,
+	BackingTraceEntry,
+	AvailabilityAttestations,
+	GossipTopologyInfo,
+	ConnectivityReport,
+	BlockApprovalMeta,
+	BlockApprovalStatus,
+	IndirectAssignmentCert,
+	IndirectSignedApprovalVote,
+	CandidateBitfield,
+	View,
+	protocol_v1::ValidationProtocol,
+	protocol_v1::CollationProtocol,
+	protocol_v1::BitfieldDistributionMessage,
+	protocol_v1::StatementDistributionMessage,
+	protocol_v1::ApprovalDistributionMessage,
+	protocol_v1::CollatorProtocolMessage,
+	req_v1::ChunkFetchingRequest,
+	req_v1::CollationFetchingRequest,
+	req_v1::CandidateCollationFetchingRequest,
+	req_v1::PoVFetchingRequest,
+	req_v1::AvailableDataFetchingRequest,
+	req_v1::StatementFetchingRequest,
+	req_v1::DisputeRequest,
+	PreCheckOutcome,
+	ImportStatementsResult,
+	AssignmentCheckResult,
+	ApprovalCheckResult,
+	BlockDescription,
+	HighestApprovedAncestorBlock,
+	RecoveryError,
+	polkadot_statement_table::v1::Misbehavior,
+);
+
+impl<T: Field> Field for Option<T> {
+	type Recorded = Option<T::Recorded>;
+
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded {
+		self.as_mut().map(|inner| inner.record(responses))
+	}
+
+	fn replay(recorded: Self::Recorded) -> Option<Self> {
+		match recorded {
+			Some(inner) => T::replay(inner).map(Some),
+			None => Some(None),
+		}
+	}
+}
+
+impl<T: Field> Field for Vec<T> {
+	type Recorded = Vec<T::Recorded>;
+
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded {
+		self.iter_mut().map(|item| item.record(responses)).collect()
+	}
+
+	fn replay(recorded: Self::Recorded) -> Option<Self> {
+		recorded.into_iter().map(T::replay).collect()
+	}
+}
+
+impl<T: Field + Clone + Eq + StdHash> Field for HashSet<T> {
+	type Recorded = Vec<T::Recorded>;
+
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded {
+		// Items of a set can't be borrowed mutably, so they are recorded from a copy.
+		self.iter().map(|item| item.clone().record(responses)).collect()
+	}
+
+	fn replay(recorded: Self::Recorded) -> Option<Self> {
+		recorded.into_iter().map(T::replay).collect()
+	}
+}
+
+impl<K, V> Field for BTreeMap<K, V>
+where
+	K: Field + Clone + Ord,
+	V: Field,
+{
+	type Recorded = Vec<(K::Recorded, V::Recorded)>;
+
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded {
+		self.iter_mut()
+			.map(|(key, value)| (key.clone().record(responses), value.record(responses)))
+			.collect()
+	}
+
+	fn replay(recorded: Self::Recorded) -> Option<Self> {
+		recorded
+			.into_iter()
+			.map(|(key, value)| Some((K::replay(key)?, V::replay(value)?)))
+			.collect()
+	}
+}
+
+impl<T: Field, E: Field> Field for Result<T, E> {
+	type Recorded = Result<T::Recorded, E::Recorded>;
+
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded {
+		match self {
+			Ok(value) => Ok(value.record(responses)),
+			Err(err) => Err(err.record(responses)),
+		}
+	}
+
+	fn replay(recorded: Self::Recorded) -> Option<Self> {
+		match recorded {
+			Ok(value) => T::replay(value).map(Ok),
+			Err(err) => E::replay(err).map(Err),
+		}
+	}
+}
+
+impl<A: Field, B: Field> Field for (A, B) {
+	type Recorded = (A::Recorded, B::Recorded);
+
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded {
+		(self.0.record(responses), self.1.record(responses))
+	}
+
+	fn replay((a, b): Self::Recorded) -> Option<Self> {
+		Some((A::replay(a)?, B::replay(b)?))
+	}
+}
+
+impl<A: Field, B: Field, C: Field> Field for (A, B, C) {
+	type Recorded = (A::Recorded, B::Recorded, C::Recorded);
+
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded {
+		(self.0.record(responses), self.1.record(responses), self.2.record(responses))
+	}
+
+	fn replay((a, b, c): Self::Recorded) -> Option<Self> {
+		Some((A::replay(a)?, B::replay(b)?, C::replay(c)?))
+	}
+}
+
+impl<T: Field + Clone> Field for Arc<T> {
+	type Recorded = T::Recorded;
+
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded {
+		(**self).clone().record(responses)
+	}
+
+	fn replay(recorded: Self::Recorded) -> Option<Self> {
+		T::replay(recorded).map(Arc::new)
+	}
+}
+
+impl Field for usize {
+	type Recorded = u64;
+
+	fn record(&mut self, _: &mut Responses) -> u64 {
+		*self as u64
+	}
+
+	fn replay(recorded: u64) -> Option<Self> {
+		Some(recorded as usize)
+	}
+}
+
+impl Field for Duration {
+	type Recorded = (u64, u32);
+
+	fn record(&mut self, _: &mut Responses) -> Self::Recorded {
+		(self.as_secs(), self.subsec_nanos())
+	}
+
+	fn replay((secs, nanos): Self::Recorded) -> Option<Self> {
+		Some(Duration::new(secs, nanos))
+	}
+}
+
+impl Field for PeerId {
+	type Recorded = Vec<u8>;
+
+	fn record(&mut self, _: &mut Responses) -> Vec<u8> {
+		self.to_bytes()
+	}
+
+	fn replay(recorded: Vec<u8>) -> Option<Self> {
+		PeerId::from_bytes(&recorded).ok()
+	}
+}
+
+impl Field for Multiaddr {
+	type Recorded = Vec<u8>;
+
+	fn record(&mut self, _: &mut Responses) -> Vec<u8> {
+		self.to_vec()
+	}
+
+	fn replay(recorded: Vec<u8>) -> Option<Self> {
+		Multiaddr::try_from(recorded).ok()
+	}
+}
+
+impl Field for ObservedRole {
+	type Recorded = u8;
+
+	fn record(&mut self, _: &mut Responses) -> u8 {
+		match self {
+			ObservedRole::Light => 0,
+			ObservedRole::Full => 1,
+			ObservedRole::Authority => 2,
+		}
+	}
+
+	fn replay(recorded: u8) -> Option<Self> {
+		match recorded {
+			0 => Some(ObservedRole::Light),
+			1 => Some(ObservedRole::Full),
+			2 => Some(ObservedRole::Authority),
+			_ => None,
+		}
+	}
+}
+
+impl Field for PeerSet {
+	type Recorded = u8;
+
+	fn record(&mut self, _: &mut Responses) -> u8 {
+		match self {
+			PeerSet::Validation => 0,
+			PeerSet::Collation => 1,
+		}
+	}
+
+	fn replay(recorded: u8) -> Option<Self> {
+		match recorded {
+			0 => Some(PeerSet::Validation),
+			1 => Some(PeerSet::Collation),
+			_ => None,
+		}
+	}
+}
+
+impl Field for IfDisconnected {
+	type Recorded = bool;
+
+	fn record(&mut self, _: &mut Responses) -> bool {
+		match self {
+			IfDisconnected::TryConnect => true,
+			IfDisconnected::ImmediateError => false,
+		}
+	}
+
+	fn replay(try_connect: bool) -> Option<Self> {
+		Some(if try_connect { IfDisconnected::TryConnect } else { IfDisconnected::ImmediateError })
+	}
+}
+
+impl Field for OurView {
+	type Recorded = View;
+
+	fn record(&mut self, _: &mut Responses) -> View {
+		(**self).clone()
+	}
+
+	/// The heads are given disabled jaeger spans.
+	fn replay(view: View) -> Option<Self> {
+		let heads = view.iter().map(|head| (*head, Arc::new(jaeger::Span::Disabled)));
+		Some(OurView::new(heads, view.finalized_number))
+	}
+}
+
+impl Field for GossipTopology {
+	type Recorded = (Vec<Vec<u8>>, Vec<Vec<u8>>);
+
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded {
+		(self.row.record(responses), self.column.record(responses))
+	}
+
+	fn replay((row, column): Self::Recorded) -> Option<Self> {
+		Some(GossipTopology { row: Field::replay(row)?, column: Field::replay(column)? })
+	}
+}
+
+/// Reputation changes and runtime API errors carry static descriptions. Replayed ones are
+/// leaked once per distinct description.
+fn intern(description: String) -> &'static str {
+	static INTERNED: Mutex<Option<HashSet<&'static str>>> = parking_lot::const_mutex(None);
+
+	let mut interned = INTERNED.lock();
+	let interned = interned.get_or_insert_with(HashSet::new);
+	match interned.get(description.as_str()) {
+		Some(known) => known,
+		None => {
+			let leaked: &'static str = Box::leak(description.into_boxed_str());
+			interned.insert(leaked);
+			leaked
+		},
+	}
+}
+
+impl Field for UnifiedReputationChange {
+	type Recorded = (u8, String);
+
+	fn record(&mut self, _: &mut Responses) -> Self::Recorded {
+		let kind = match self {
+			UnifiedReputationChange::CostMajor(_) => 0,
+			UnifiedReputationChange::CostMinor(_) => 1,
+			UnifiedReputationChange::CostMajorRepeated(_) => 2,
+			UnifiedReputationChange::CostMinorRepeated(_) => 3,
+			UnifiedReputationChange::Malicious(_) => 4,
+			UnifiedReputationChange::BenefitMinorFirst(_) => 5,
+			UnifiedReputationChange::BenefitMinor(_) => 6,
+			UnifiedReputationChange::BenefitMajorFirst(_) => 7,
+			UnifiedReputationChange::BenefitMajor(_) => 8,
+		};
+
+		(kind, self.description().to_owned())
+	}
+
+	fn replay((kind, description): Self::Recorded) -> Option<Self> {
+		let description = intern(description);
+		Some(match kind {
+			0 => UnifiedReputationChange::CostMajor(description),
+			1 => UnifiedReputationChange::CostMinor(description),
+			2 => UnifiedReputationChange::CostMajorRepeated(description),
+			3 => UnifiedReputationChange::CostMinorRepeated(description),
+			4 => UnifiedReputationChange::Malicious(description),
+			5 => UnifiedReputationChange::BenefitMinorFirst(description),
+			6 => UnifiedReputationChange::BenefitMinor(description),
+			7 => UnifiedReputationChange::BenefitMajorFirst(description),
+			8 => UnifiedReputationChange::BenefitMajor(description),
+			_ => return None,
+		})
+	}
+}
+
+/// The error of a replayed [`RuntimeApiError::Execution`].
+#[derive(Debug)]
+pub struct ReplayedError(pub String);
+
+impl fmt::Display for ReplayedError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+impl std::error::Error for ReplayedError {}
+
+/// The recorded form of a [`RuntimeApiError`].
+#[derive(Debug, Encode, Decode)]
+pub enum RecordedRuntimeApiError {
+	/// See [`RuntimeApiError::Execution`], the source is kept as its display representation.
+	Execution {
+		/// The runtime API being called.
+		runtime_api_name: String,
+		/// The wrapped error.
+		source: String,
+	},
+	/// See [`RuntimeApiError::NotSupported`].
+	NotSupported {
+		/// The runtime API being called.
+		runtime_api_name: String,
+	},
+}
+
+impl Field for RuntimeApiError {
+	type Recorded = RecordedRuntimeApiError;
+
+	fn record(&mut self, _: &mut Responses) -> Self::Recorded {
+		match self {
+			RuntimeApiError::Execution { runtime_api_name, source } =>
+				RecordedRuntimeApiError::Execution {
+					runtime_api_name: runtime_api_name.to_string(),
+					source: source.to_string(),
+				},
+			RuntimeApiError::NotSupported { runtime_api_name } =>
+				RecordedRuntimeApiError::NotSupported {
+					runtime_api_name: runtime_api_name.to_string(),
+				},
+		}
+	}
+
+	fn replay(recorded: Self::Recorded) -> Option<Self> {
+		Some(match recorded {
+			RecordedRuntimeApiError::Execution { runtime_api_name, source } =>
+				RuntimeApiError::Execution {
+					runtime_api_name: intern(runtime_api_name),
+					source: Arc::new(ReplayedError(source)),
+				},
+			RecordedRuntimeApiError::NotSupported { runtime_api_name } =>
+				RuntimeApiError::NotSupported { runtime_api_name: intern(runtime_api_name) },
+		})
+	}
+}
+
+impl Field for ChainApiError {
+	type Recorded = String;
+
+	fn record(&mut self, _: &mut Responses) -> String {
+		self.to_string()
+	}
+
+	fn replay(recorded: String) -> Option<Self> {
+		Some(recorded.into())
+	}
+}
+
+impl Field for ValidationFailed {
+	type Recorded = String;
+
+	fn record(&mut self, _: &mut Responses) -> String {
+		self.0.clone()
+	}
+
+	fn replay(recorded: String) -> Option<Self> {
+		Some(ValidationFailed(recorded))
+	}
+}
+
+impl Field for RequestFailure {
+	type Recorded = u8;
+
+	fn record(&mut self, _: &mut Responses) -> u8 {
+		match self {
+			RequestFailure::NotConnected => 0,
+			RequestFailure::UnknownProtocol => 1,
+			RequestFailure::Refused => 2,
+			RequestFailure::Obsolete => 3,
+			RequestFailure::Network(OutboundFailure::DialFailure) => 4,
+			RequestFailure::Network(OutboundFailure::Timeout) => 5,
+			RequestFailure::Network(OutboundFailure::ConnectionClosed) => 6,
+			RequestFailure::Network(OutboundFailure::UnsupportedProtocols) => 7,
+		}
+	}
+
+	fn replay(recorded: u8) -> Option<Self> {
+		Some(match recorded {
+			0 => RequestFailure::NotConnected,
+			1 => RequestFailure::UnknownProtocol,
+			2 => RequestFailure::Refused,
+			3 => RequestFailure::Obsolete,
+			4 => RequestFailure::Network(OutboundFailure::DialFailure),
+			5 => RequestFailure::Network(OutboundFailure::Timeout),
+			6 => RequestFailure::Network(OutboundFailure::ConnectionClosed),
+			7 => RequestFailure::Network(OutboundFailure::UnsupportedProtocols),
+			_ => return None,
+		})
+	}
+}
+
+/// The recorded form of a [`Recipient`].
+#[derive(Debug, Encode, Decode)]
+pub enum RecordedRecipient {
+	/// See [`Recipient::Peer`].
+	Peer(Vec<u8>),
+	/// See [`Recipient::Authority`].
+	Authority(AuthorityDiscoveryId),
+}
+
+impl Field for Recipient {
+	type Recorded = RecordedRecipient;
+
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded {
+		match self {
+			Recipient::Peer(peer) => RecordedRecipient::Peer(peer.record(responses)),
+			Recipient::Authority(id) => RecordedRecipient::Authority(id.clone()),
+		}
+	}
+
+	fn replay(recorded: Self::Recorded) -> Option<Self> {
+		Some(match recorded {
+			RecordedRecipient::Peer(peer) => Recipient::Peer(Field::replay(peer)?),
+			RecordedRecipient::Authority(id) => Recipient::Authority(id),
+		})
+	}
+}
+
+impl<Req: Field> Field for OutgoingRequest<Req> {
+	type Recorded = (RecordedRecipient, Req::Recorded);
+
+	/// The response is recorded in its wire encoding.
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded {
+		let recorded = (self.peer.record(responses), self.payload.record(responses));
+		self.pending_response.record(responses);
+		recorded
+	}
+
+	fn replay((peer, payload): Self::Recorded) -> Option<Self> {
+		Some(OutgoingRequest {
+			peer: Field::replay(peer)?,
+			payload: Field::replay(payload)?,
+			pending_response: oneshot::channel().0,
+		})
+	}
+}
+
+impl<Payload, RealPayload> Field for Signed<Payload, RealPayload>
+where
+	Payload: Clone + Send + 'static,
+	RealPayload: Clone + Send + 'static,
+	UncheckedSigned<Payload, RealPayload>: Encode + Decode + fmt::Debug,
+{
+	type Recorded = UncheckedSigned<Payload, RealPayload>;
+
+	fn record(&mut self, _: &mut Responses) -> Self::Recorded {
+		self.clone().into_unchecked()
+	}
+
+	/// The signature was checked before the message was recorded.
+	fn replay(recorded: Self::Recorded) -> Option<Self> {
+		Some(Signed::new_unchecked_from_trusted_source(recorded))
+	}
+}
+
+/// The recorded form of a [`SignedDisputeStatement`].
+#[derive(Debug, Encode, Decode)]
+pub struct RecordedSignedDisputeStatement {
+	/// The kind of statement.
+	pub dispute_statement: DisputeStatement,
+	/// The candidate the statement is about.
+	pub candidate_hash: CandidateHash,
+	/// The validator which signed the statement.
+	pub validator_public: ValidatorId,
+	/// The signature of the statement.
+	pub validator_signature: ValidatorSignature,
+	/// The session the candidate appeared in.
+	pub session_index: SessionIndex,
+}
+
+impl Field for SignedDisputeStatement {
+	type Recorded = RecordedSignedDisputeStatement;
+
+	fn record(&mut self, _: &mut Responses) -> Self::Recorded {
+		RecordedSignedDisputeStatement {
+			dispute_statement: self.statement().clone(),
+			candidate_hash: *self.candidate_hash(),
+			validator_public: self.validator_public().clone(),
+			validator_signature: self.validator_signature().clone(),
+			session_index: self.session_index(),
+		}
+	}
+
+	/// The signature was checked before the message was recorded.
+	fn replay(recorded: Self::Recorded) -> Option<Self> {
+		Some(SignedDisputeStatement::new_unchecked_from_trusted_source(
+			recorded.dispute_statement,
+			recorded.candidate_hash,
+			recorded.session_index,
+			recorded.validator_public,
+			recorded.validator_signature,
+		))
+	}
+}
+
+impl Field for DisputeMessage {
+	type Recorded = UncheckedDisputeMessage;
+
+	fn record(&mut self, _: &mut Responses) -> Self::Recorded {
+		self.clone().into()
+	}
+
+	/// The statements were checked before the message was recorded.
+	fn replay(recorded: Self::Recorded) -> Option<Self> {
+		Some(DisputeMessage::new_unchecked_from_trusted_source(recorded))
+	}
+}
+
+impl Field for CollationSecondedSignal {
+	type Recorded = (Hash, <SignedFullStatement as Field>::Recorded);
+
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded {
+		(self.relay_parent, self.statement.record(responses))
+	}
+
+	fn replay((relay_parent, statement): Self::Recorded) -> Option<Self> {
+		Some(CollationSecondedSignal { relay_parent, statement: Field::replay(statement)? })
+	}
+}
+
+impl Field for ProvisionerInherentData {
+	type Recorded = (
+		<SignedAvailabilityBitfields as Field>::Recorded,
+		Vec<BackedCandidate>,
+		Vec<DisputeStatementSet>,
+	);
+
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded {
+		(
+			self.bitfields.record(responses),
+			self.backed_candidates.record(responses),
+			self.disputes.record(responses),
+		)
+	}
+
+	fn replay((bitfields, backed_candidates, disputes): Self::Recorded) -> Option<Self> {
+		Some(ProvisionerInherentData {
+			bitfields: Field::replay(bitfields)?,
+			backed_candidates: Field::replay(backed_candidates)?,
+			disputes: Field::replay(disputes)?,
+		})
+	}
+}
+
+impl Field for CollationGenerationConfig {
+	/// Only the para is recorded, the collator key and function can't be.
+	type Recorded = ParaId;
+
+	fn record(&mut self, _: &mut Responses) -> ParaId {
+		self.para_id
+	}
+
+	fn replay(_: ParaId) -> Option<Self> {
+		None
+	}
+}
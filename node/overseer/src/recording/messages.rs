@@ -0,0 +1,678 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The recorded forms of all subsystem messages.
+//!
+//! Each message enum is mirrored by an enum without response channels. Listing the
+//! variants here, rather than deriving them, keeps the mirrors exhaustive: adding a variant
+//! or changing its fields fails to compile until the mirror is updated as well.
+
+use std::{
+	collections::{BTreeMap, HashSet},
+	sync::Arc,
+	time::Duration,
+};
+
+use futures::channel::oneshot;
+use parity_scale_codec::{Decode, Encode};
+
+use polkadot_node_network_protocol::{
+	peer_set::PeerSet,
+	request_response::{v1 as req_v1, OutgoingRequest, Requests},
+	v1 as protocol_v1, IfDisconnected, PeerId, UnifiedReputationChange, View,
+};
+use polkadot_node_primitives::{
+	approval::{
+		BlockApprovalMeta, BlockApprovalStatus, CandidateBitfield, IndirectAssignmentCert,
+		IndirectSignedApprovalVote,
+	},
+	availability::AvailabilityAttestations,
+	backing::BackingTraceEntry,
+	gossip_support::{ConnectivityReport, GossipTopologyInfo},
+	AvailableData, BabeEpoch, BlockWeight, CandidateVotes, CollationGenerationConfig,
+	CollationSecondedSignal, DisputeMessage, ErasureChunk, PoV, SignedDisputeStatement,
+	SignedFullStatement, ValidationResult,
+};
+use polkadot_node_subsystem_types::{
+	errors::RecoveryError,
+	messages::{
+		ApprovalCheckResult, ApprovalDistributionMessage, ApprovalVotingMessage,
+		AssignmentCheckResult, AvailabilityDistributionMessage, AvailabilityRecoveryMessage,
+		AvailabilityStoreMessage, BitfieldDistributionMessage, BitfieldSigningMessage,
+		BlockDescription, CandidateBackingMessage, CandidateValidationMessage,
+		ChainApiMessage, ChainApiResponseChannel, ChainSelectionMessage,
+		CollationGenerationMessage, CollatorProtocolMessage, DisputeCoordinatorMessage,
+		DisputeDistributionMessage, GossipSupportMessage, GossipTopology,
+		HighestApprovedAncestorBlock, ImportStatementsResult, NetworkBridgeEvent,
+		NetworkBridgeMessage, PreCheckOutcome, ProvisionableData, ProvisionerInherentData,
+		ProvisionerMessage, PvfCheckerMessage, RuntimeApiMessage, RuntimeApiRequest,
+		RuntimeApiSender, StatementDistributionMessage, ValidationFailed,
+	},
+};
+use polkadot_primitives::{
+	v1::{
+		AuthorityDiscoveryId, BackedCandidate, BlockNumber, CandidateCommitments,
+		CandidateDescriptor, CandidateEvent, CandidateHash, CandidateReceipt, CollatorId,
+		CommittedCandidateReceipt, CoreState, GroupIndex, GroupRotationInfo, Hash, Header,
+		Id as ParaId, InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption,
+		PersistedValidationData, ScrapedOnChainVotes, SessionIndex, SignedAvailabilityBitfield,
+		ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
+	},
+	v2::{slashing, InherentWeightInfo, PvfCheckStatement, SessionInfo},
+};
+use polkadot_statement_table::v1::Misbehavior;
+use sc_network::Multiaddr;
+
+use super::field::{Field, Responses};
+use crate::AllMessages;
+
+/// Define the recorded form of a message enum, along with its [`Field`] implementation.
+///
+/// All variants must be listed, tuple fields are given names to bind them to.
+macro_rules! recorded_message {
+	(
+		$(#[$meta:meta])*
+		$message:ident => $recorded:ident {
+			$(
+				$variant:ident
+				$( ( $( $tname:ident : $tty:ty ),* $(,)? ) )?
+				$( { $( $sname:ident : $sty:ty ),* $(,)? } )?
+			),* $(,)?
+		}
+	) => {
+		$(#[$meta])*
+		#[derive(Debug, Encode, Decode)]
+		#[allow(missing_docs)]
+		pub enum $recorded {
+			$(
+				$variant
+				$( ( $( <$tty as Field>::Recorded ),* ) )?
+				$( { $( $sname: <$sty as Field>::Recorded ),* } )?
+			),*
+		}
+
+		impl Field for $message {
+			type Recorded = $recorded;
+
+			fn record(&mut self, responses: &mut Responses) -> $recorded {
+				match self {
+					$(
+						$message::$variant
+						$( ( $( $tname ),* ) )?
+						$( { $( $sname ),* } )?
+						=> $recorded::$variant
+						$( ( $( $tname.record(responses) ),* ) )?
+						$( { $( $sname: $sname.record(responses) ),* } )?
+					),*
+				}
+			}
+
+			fn replay(recorded: $recorded) -> Option<Self> {
+				Some(match recorded {
+					$(
+						$recorded::$variant
+						$( ( $( $tname ),* ) )?
+						$( { $( $sname ),* } )?
+						=> $message::$variant
+						$( ( $( Field::replay($tname)? ),* ) )?
+						$( { $( $sname: Field::replay($sname)? ),* } )?
+					),*
+				})
+			}
+		}
+	};
+}
+
+/// A message type consumed by a subsystem, which can be picked out of a recording.
+pub trait Replayable: Field {
+	/// Reconstruct the message, if `recorded` was sent to the subsystem consuming `Self`.
+	fn replay_from(recorded: RecordedAllMessages) -> Option<Self>;
+}
+
+/// Define the recorded form of [`AllMessages`] and implement [`Replayable`] for all messages.
+macro_rules! recorded_all_messages {
+	($( $variant:ident ( $message:ty ) ),* $(,)?) => {
+		recorded_message! {
+			/// The recorded form of [`AllMessages`].
+			AllMessages => RecordedAllMessages {
+				$( $variant(message: $message), )*
+				Empty,
+			}
+		}
+
+		$(
+			impl Replayable for $message {
+				fn replay_from(recorded: RecordedAllMessages) -> Option<Self> {
+					match recorded {
+						RecordedAllMessages::$variant(message) => Field::replay(message),
+						_ => None,
+					}
+				}
+			}
+		)*
+	};
+}
+
+recorded_all_messages!(
+	CandidateValidation(CandidateValidationMessage),
+	PvfChecker(PvfCheckerMessage),
+	CandidateBacking(CandidateBackingMessage),
+	StatementDistribution(StatementDistributionMessage),
+	AvailabilityDistribution(AvailabilityDistributionMessage),
+	AvailabilityRecovery(AvailabilityRecoveryMessage),
+	BitfieldSigning(BitfieldSigningMessage),
+	BitfieldDistribution(BitfieldDistributionMessage),
+	Provisioner(ProvisionerMessage),
+	RuntimeApi(RuntimeApiMessage),
+	AvailabilityStore(AvailabilityStoreMessage),
+	NetworkBridge(NetworkBridgeMessage),
+	ChainApi(ChainApiMessage),
+	CollationGeneration(CollationGenerationMessage),
+	CollatorProtocol(CollatorProtocolMessage),
+	ApprovalDistribution(ApprovalDistributionMessage),
+	ApprovalVoting(ApprovalVotingMessage),
+	GossipSupport(GossipSupportMessage),
+	DisputeCoordinator(DisputeCoordinatorMessage),
+	DisputeDistribution(DisputeDistributionMessage),
+	ChainSelection(ChainSelectionMessage),
+);
+
+recorded_message! {
+	/// The recorded form of [`CandidateValidationMessage`].
+	CandidateValidationMessage => RecordedCandidateValidationMessage {
+		ValidateFromChainState(
+			descriptor: CandidateDescriptor,
+			pov: Arc<PoV>,
+			timeout: Duration,
+			tx: oneshot::Sender<Result<ValidationResult, ValidationFailed>>,
+		),
+		ValidateFromExhaustive(
+			validation_data: PersistedValidationData,
+			validation_code: ValidationCode,
+			descriptor: CandidateDescriptor,
+			pov: Arc<PoV>,
+			timeout: Duration,
+			tx: oneshot::Sender<Result<ValidationResult, ValidationFailed>>,
+		),
+		PreCheck(
+			relay_parent: Hash,
+			code_hash: ValidationCodeHash,
+			tx: oneshot::Sender<PreCheckOutcome>,
+		),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`CandidateBackingMessage`].
+	CandidateBackingMessage => RecordedCandidateBackingMessage {
+		GetBackedCandidates(
+			relay_parent: Hash,
+			candidates: Vec<CandidateHash>,
+			tx: oneshot::Sender<Vec<BackedCandidate>>,
+		),
+		Second(relay_parent: Hash, candidate: CandidateReceipt, pov: PoV),
+		Statement(relay_parent: Hash, statement: SignedFullStatement),
+		GetCandidateTrace(
+			relay_parent: Hash,
+			candidate_hash: Option<CandidateHash>,
+			tx: oneshot::Sender<Vec<BackingTraceEntry>>,
+		),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`CollatorProtocolMessage`].
+	CollatorProtocolMessage => RecordedCollatorProtocolMessage {
+		CollateOn(para_id: ParaId),
+		DistributeCollation(
+			candidate: CandidateReceipt,
+			pov: PoV,
+			tx: Option<oneshot::Sender<CollationSecondedSignal>>,
+		),
+		ReportCollator(collator: CollatorId),
+		NetworkBridgeUpdateV1(event: NetworkBridgeEvent<protocol_v1::CollatorProtocolMessage>),
+		Invalid(relay_parent: Hash, candidate: CandidateReceipt),
+		Seconded(relay_parent: Hash, statement: SignedFullStatement),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`DisputeCoordinatorMessage`].
+	DisputeCoordinatorMessage => RecordedDisputeCoordinatorMessage {
+		ImportStatements {
+			candidate_hash: CandidateHash,
+			candidate_receipt: CandidateReceipt,
+			session: SessionIndex,
+			statements: Vec<(SignedDisputeStatement, ValidatorIndex)>,
+			pending_confirmation: oneshot::Sender<ImportStatementsResult>,
+		},
+		RecentDisputes(tx: oneshot::Sender<Vec<(SessionIndex, CandidateHash)>>),
+		ActiveDisputes(tx: oneshot::Sender<Vec<(SessionIndex, CandidateHash)>>),
+		QueryCandidateVotes(
+			query: Vec<(SessionIndex, CandidateHash)>,
+			tx: oneshot::Sender<Vec<(SessionIndex, CandidateHash, CandidateVotes)>>,
+		),
+		IssueLocalStatement(
+			session: SessionIndex,
+			candidate_hash: CandidateHash,
+			candidate: CandidateReceipt,
+			valid: bool,
+		),
+		DetermineUndisputedChain {
+			base: (BlockNumber, Hash),
+			block_descriptions: Vec<BlockDescription>,
+			tx: oneshot::Sender<(BlockNumber, Hash)>,
+		},
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`DisputeDistributionMessage`].
+	DisputeDistributionMessage => RecordedDisputeDistributionMessage {
+		SendDispute(message: DisputeMessage),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`NetworkBridgeMessage`].
+	NetworkBridgeMessage => RecordedNetworkBridgeMessage {
+		ReportPeer(peer: PeerId, rep: UnifiedReputationChange),
+		DisconnectPeer(peer: PeerId, peer_set: PeerSet),
+		SendValidationMessage(peers: Vec<PeerId>, message: protocol_v1::ValidationProtocol),
+		SendCollationMessage(peers: Vec<PeerId>, message: protocol_v1::CollationProtocol),
+		SendValidationMessages(messages: Vec<(Vec<PeerId>, protocol_v1::ValidationProtocol)>),
+		SendCollationMessages(messages: Vec<(Vec<PeerId>, protocol_v1::CollationProtocol)>),
+		SendRequests(requests: Vec<Requests>, if_disconnected: IfDisconnected),
+		ConnectToValidators {
+			validator_ids: Vec<AuthorityDiscoveryId>,
+			peer_set: PeerSet,
+			failed: oneshot::Sender<usize>,
+		},
+		ConnectToResolvedValidators {
+			validator_addrs: Vec<HashSet<Multiaddr>>,
+			peer_set: PeerSet,
+		},
+		NewGossipTopology {
+			row_neighbors: HashSet<AuthorityDiscoveryId>,
+			column_neighbors: HashSet<AuthorityDiscoveryId>,
+		},
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`Requests`]. Responses are recorded in their wire encoding.
+	Requests => RecordedRequests {
+		ChunkFetching(request: OutgoingRequest<req_v1::ChunkFetchingRequest>),
+		CollationFetching(request: OutgoingRequest<req_v1::CollationFetchingRequest>),
+		CandidateCollationFetching(
+			request: OutgoingRequest<req_v1::CandidateCollationFetchingRequest>,
+		),
+		PoVFetching(request: OutgoingRequest<req_v1::PoVFetchingRequest>),
+		AvailableDataFetching(request: OutgoingRequest<req_v1::AvailableDataFetchingRequest>),
+		StatementFetching(request: OutgoingRequest<req_v1::StatementFetchingRequest>),
+		DisputeSending(request: OutgoingRequest<req_v1::DisputeRequest>),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`AvailabilityDistributionMessage`].
+	AvailabilityDistributionMessage => RecordedAvailabilityDistributionMessage {
+		FetchPoV {
+			relay_parent: Hash,
+			from_validator: ValidatorIndex,
+			candidate_hash: CandidateHash,
+			pov_hash: Hash,
+			tx: oneshot::Sender<PoV>,
+		},
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`AvailabilityRecoveryMessage`].
+	AvailabilityRecoveryMessage => RecordedAvailabilityRecoveryMessage {
+		RecoverAvailableData(
+			candidate: CandidateReceipt,
+			session: SessionIndex,
+			backing_group: Option<GroupIndex>,
+			tx: oneshot::Sender<Result<AvailableData, RecoveryError>>,
+		),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`BitfieldDistributionMessage`].
+	BitfieldDistributionMessage => RecordedBitfieldDistributionMessage {
+		DistributeBitfield(relay_parent: Hash, bitfield: SignedAvailabilityBitfield),
+		NetworkBridgeUpdateV1(
+			event: NetworkBridgeEvent<protocol_v1::BitfieldDistributionMessage>,
+		),
+		GetAvailabilityAttestations(
+			relay_parent: Hash,
+			tx: oneshot::Sender<Option<AvailabilityAttestations>>,
+		),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`AvailabilityStoreMessage`].
+	AvailabilityStoreMessage => RecordedAvailabilityStoreMessage {
+		QueryAvailableData(
+			candidate_hash: CandidateHash,
+			tx: oneshot::Sender<Option<AvailableData>>,
+		),
+		QueryDataAvailability(candidate_hash: CandidateHash, tx: oneshot::Sender<bool>),
+		QueryChunk(
+			candidate_hash: CandidateHash,
+			validator_index: ValidatorIndex,
+			tx: oneshot::Sender<Option<ErasureChunk>>,
+		),
+		QueryAllChunks(candidate_hash: CandidateHash, tx: oneshot::Sender<Vec<ErasureChunk>>),
+		QueryChunkAvailability(
+			candidate_hash: CandidateHash,
+			validator_index: ValidatorIndex,
+			tx: oneshot::Sender<bool>,
+		),
+		StoreChunk {
+			candidate_hash: CandidateHash,
+			chunk: ErasureChunk,
+			tx: oneshot::Sender<Result<(), ()>>,
+		},
+		StoreAvailableData {
+			candidate_hash: CandidateHash,
+			n_validators: u32,
+			available_data: AvailableData,
+			tx: oneshot::Sender<Result<(), ()>>,
+		},
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`ChainApiMessage`].
+	ChainApiMessage => RecordedChainApiMessage {
+		BlockNumber(hash: Hash, tx: ChainApiResponseChannel<Option<BlockNumber>>),
+		BlockHeader(hash: Hash, tx: ChainApiResponseChannel<Option<Header>>),
+		BlockWeight(hash: Hash, tx: ChainApiResponseChannel<Option<BlockWeight>>),
+		FinalizedBlockHash(number: BlockNumber, tx: ChainApiResponseChannel<Option<Hash>>),
+		FinalizedBlockNumber(tx: ChainApiResponseChannel<BlockNumber>),
+		Ancestors { hash: Hash, k: usize, response_channel: ChainApiResponseChannel<Vec<Hash>> },
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`ChainSelectionMessage`].
+	ChainSelectionMessage => RecordedChainSelectionMessage {
+		Approved(hash: Hash),
+		Leaves(tx: oneshot::Sender<Vec<Hash>>),
+		BestLeafContaining(hash: Hash, tx: oneshot::Sender<Option<Hash>>),
+		Blacklist(hash: Hash, tx: oneshot::Sender<bool>),
+		ClearBlacklist(hash: Hash, tx: oneshot::Sender<bool>),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`RuntimeApiRequest`].
+	RuntimeApiRequest => RecordedRuntimeApiRequest {
+		Authorities(tx: RuntimeApiSender<Vec<AuthorityDiscoveryId>>),
+		Validators(tx: RuntimeApiSender<Vec<ValidatorId>>),
+		ValidatorGroups(tx: RuntimeApiSender<(Vec<Vec<ValidatorIndex>>, GroupRotationInfo)>),
+		AvailabilityCores(tx: RuntimeApiSender<Vec<CoreState>>),
+		PersistedValidationData(
+			para_id: ParaId,
+			assumption: OccupiedCoreAssumption,
+			tx: RuntimeApiSender<Option<PersistedValidationData>>,
+		),
+		AssumedValidationData(
+			para_id: ParaId,
+			expected_hash: Hash,
+			tx: RuntimeApiSender<Option<(PersistedValidationData, ValidationCodeHash)>>,
+		),
+		CheckValidationOutputs(
+			para_id: ParaId,
+			commitments: CandidateCommitments,
+			tx: RuntimeApiSender<bool>,
+		),
+		SessionIndexForChild(tx: RuntimeApiSender<SessionIndex>),
+		ValidationCode(
+			para_id: ParaId,
+			assumption: OccupiedCoreAssumption,
+			tx: RuntimeApiSender<Option<ValidationCode>>,
+		),
+		ValidationCodeByHash(
+			code_hash: ValidationCodeHash,
+			tx: RuntimeApiSender<Option<ValidationCode>>,
+		),
+		CandidatePendingAvailability(
+			para_id: ParaId,
+			tx: RuntimeApiSender<Option<CommittedCandidateReceipt>>,
+		),
+		CandidateEvents(tx: RuntimeApiSender<Vec<CandidateEvent>>),
+		SessionInfo(session: SessionIndex, tx: RuntimeApiSender<Option<SessionInfo>>),
+		DmqContents(para_id: ParaId, tx: RuntimeApiSender<Vec<InboundDownwardMessage>>),
+		InboundHrmpChannelsContents(
+			para_id: ParaId,
+			tx: RuntimeApiSender<BTreeMap<ParaId, Vec<InboundHrmpMessage>>>,
+		),
+		CurrentBabeEpoch(tx: RuntimeApiSender<BabeEpoch>),
+		FetchOnChainVotes(tx: RuntimeApiSender<Option<ScrapedOnChainVotes>>),
+		SubmitPvfCheckStatement(
+			statement: PvfCheckStatement,
+			signature: ValidatorSignature,
+			tx: RuntimeApiSender<()>,
+		),
+		PvfsRequirePrecheck(tx: RuntimeApiSender<Vec<ValidationCodeHash>>),
+		ValidationCodeHash(
+			para_id: ParaId,
+			assumption: OccupiedCoreAssumption,
+			tx: RuntimeApiSender<Option<ValidationCodeHash>>,
+		),
+		UnappliedSlashes(
+			tx: RuntimeApiSender<Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>>,
+		),
+		KeyOwnershipProof(
+			validator_id: ValidatorId,
+			tx: RuntimeApiSender<Option<slashing::OpaqueKeyOwnershipProof>>,
+		),
+		SubmitReportDisputeLost(
+			dispute_proof: slashing::DisputeProof,
+			key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+			tx: RuntimeApiSender<Option<()>>,
+		),
+		InherentWeightInfo(tx: RuntimeApiSender<InherentWeightInfo>),
+		SubmitReportBackingMisbehavior(
+			misbehavior_proof: slashing::BackingMisbehaviorProof,
+			key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+			tx: RuntimeApiSender<Option<()>>,
+		),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`RuntimeApiMessage`].
+	RuntimeApiMessage => RecordedRuntimeApiMessage {
+		Request(relay_parent: Hash, request: RuntimeApiRequest),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`StatementDistributionMessage`].
+	StatementDistributionMessage => RecordedStatementDistributionMessage {
+		Share(relay_parent: Hash, statement: SignedFullStatement),
+		NetworkBridgeUpdateV1(
+			event: NetworkBridgeEvent<protocol_v1::StatementDistributionMessage>,
+		),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`ProvisionableData`].
+	ProvisionableData => RecordedProvisionableData {
+		Bitfield(relay_parent: Hash, bitfield: SignedAvailabilityBitfield),
+		BackedCandidate(candidate: CandidateReceipt),
+		MisbehaviorReport(relay_parent: Hash, validator: ValidatorIndex, misbehavior: Misbehavior),
+		Dispute(relay_parent: Hash, signature: ValidatorSignature),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`ProvisionerMessage`].
+	ProvisionerMessage => RecordedProvisionerMessage {
+		RequestInherentData(relay_parent: Hash, tx: oneshot::Sender<ProvisionerInherentData>),
+		ProvisionableData(relay_parent: Hash, data: ProvisionableData),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`CollationGenerationMessage`].
+	CollationGenerationMessage => RecordedCollationGenerationMessage {
+		Initialize(config: CollationGenerationConfig),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`ApprovalVotingMessage`].
+	ApprovalVotingMessage => RecordedApprovalVotingMessage {
+		CheckAndImportAssignment(
+			cert: IndirectAssignmentCert,
+			claimed: CandidateBitfield,
+			tx: oneshot::Sender<AssignmentCheckResult>,
+		),
+		CheckAndImportApproval(
+			vote: IndirectSignedApprovalVote,
+			tx: oneshot::Sender<ApprovalCheckResult>,
+		),
+		ApprovedAncestor(
+			hash: Hash,
+			number: BlockNumber,
+			tx: oneshot::Sender<Option<HighestApprovedAncestorBlock>>,
+		),
+		ApprovalStatus(hash: Hash, tx: oneshot::Sender<Option<BlockApprovalStatus>>),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`ApprovalDistributionMessage`].
+	ApprovalDistributionMessage => RecordedApprovalDistributionMessage {
+		NewBlocks(blocks: Vec<BlockApprovalMeta>),
+		DistributeAssignment(cert: IndirectAssignmentCert, claimed: CandidateBitfield),
+		DistributeApproval(vote: IndirectSignedApprovalVote),
+		NetworkBridgeUpdateV1(
+			event: NetworkBridgeEvent<protocol_v1::ApprovalDistributionMessage>,
+		),
+	}
+}
+
+recorded_message! {
+	/// The recorded form of [`GossipSupportMessage`].
+	GossipSupportMessage => RecordedGossipSupportMessage {
+		NetworkBridgeUpdateV1(
+			event: NetworkBridgeEvent<protocol_v1::GossipSuppportNetworkMessage>,
+		),
+		GetTopology(tx: oneshot::Sender<Option<GossipTopologyInfo>>),
+		PeerSyncConnected {
+			peer: PeerId,
+			authority_ids: HashSet<AuthorityDiscoveryId>,
+		},
+		PeerSyncDisconnected(peer: PeerId),
+		GetConnectivityReport(tx: oneshot::Sender<Option<ConnectivityReport>>),
+	}
+}
+
+/// The recorded form of a [`NetworkBridgeEvent`]. Peers are stored as their encoded ids.
+#[derive(Debug, Encode, Decode)]
+#[allow(missing_docs)]
+pub enum RecordedNetworkBridgeEvent<M> {
+	PeerConnected(Vec<u8>, u8, u32, Option<Vec<AuthorityDiscoveryId>>),
+	PeerDisconnected(Vec<u8>),
+	NewGossipTopology(<GossipTopology as Field>::Recorded),
+	PeerMessage(Vec<u8>, M),
+	PeerViewChange(Vec<u8>, View),
+	OurViewChange(View),
+}
+
+impl<M: Field> Field for NetworkBridgeEvent<M> {
+	type Recorded = RecordedNetworkBridgeEvent<M::Recorded>;
+
+	fn record(&mut self, responses: &mut Responses) -> Self::Recorded {
+		match self {
+			NetworkBridgeEvent::PeerConnected(peer, role, version, authority_ids) =>
+				RecordedNetworkBridgeEvent::PeerConnected(
+					peer.record(responses),
+					role.record(responses),
+					*version,
+					authority_ids.record(responses),
+				),
+			NetworkBridgeEvent::PeerDisconnected(peer) =>
+				RecordedNetworkBridgeEvent::PeerDisconnected(peer.record(responses)),
+			NetworkBridgeEvent::NewGossipTopology(topology) =>
+				RecordedNetworkBridgeEvent::NewGossipTopology(topology.record(responses)),
+			NetworkBridgeEvent::PeerMessage(peer, message) =>
+				RecordedNetworkBridgeEvent::PeerMessage(
+					peer.record(responses),
+					message.record(responses),
+				),
+			NetworkBridgeEvent::PeerViewChange(peer, view) =>
+				RecordedNetworkBridgeEvent::PeerViewChange(peer.record(responses), view.clone()),
+			NetworkBridgeEvent::OurViewChange(view) =>
+				RecordedNetworkBridgeEvent::OurViewChange(view.record(responses)),
+		}
+	}
+
+	fn replay(recorded: Self::Recorded) -> Option<Self> {
+		Some(match recorded {
+			RecordedNetworkBridgeEvent::PeerConnected(peer, role, version, authority_ids) =>
+				NetworkBridgeEvent::PeerConnected(
+					Field::replay(peer)?,
+					Field::replay(role)?,
+					version,
+					Field::replay(authority_ids)?,
+				),
+			RecordedNetworkBridgeEvent::PeerDisconnected(peer) =>
+				NetworkBridgeEvent::PeerDisconnected(Field::replay(peer)?),
+			RecordedNetworkBridgeEvent::NewGossipTopology(topology) =>
+				NetworkBridgeEvent::NewGossipTopology(Field::replay(topology)?),
+			RecordedNetworkBridgeEvent::PeerMessage(peer, message) =>
+				NetworkBridgeEvent::PeerMessage(Field::replay(peer)?, Field::replay(message)?),
+			RecordedNetworkBridgeEvent::PeerViewChange(peer, view) =>
+				NetworkBridgeEvent::PeerViewChange(Field::replay(peer)?, view),
+			RecordedNetworkBridgeEvent::OurViewChange(view) =>
+				NetworkBridgeEvent::OurViewChange(Field::replay(view)?),
+		})
+	}
+}
+
+macro_rules! uninhabited_messages {
+	($($message:ty),* $(,)?) => {
+		$(
+			impl Field for $message {
+				type Recorded = ();
+
+				fn record(&mut self, _: &mut Responses) {
+					match *self {}
+				}
+
+				fn replay(_: ()) -> Option<Self> {
+					None
+				}
+			}
+		)*
+	};
+}
+
+uninhabited_messages!(
+	BitfieldSigningMessage,
+	PvfCheckerMessage,
+	protocol_v1::GossipSuppportNetworkMessage,
+);
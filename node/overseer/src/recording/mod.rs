@@ -0,0 +1,360 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Recording of overseer traffic for later, deterministic replay.
+//!
+//! A [`FileRecorder`] attached to the overseer builder captures every message routed between
+//! subsystems and every signal, together with a timestamp, and appends them as SCALE-encoded
+//! [`RecordEntry`]s to a file. [`read_recording`] loads such a file again.
+//!
+//! Messages are recorded in the form given by their [`Field`] implementation, which leaves out
+//! response channels. Instead, each response channel of a recorded message is replaced by one
+//! which forwards the response to the original receiver, and the response is recorded as a
+//! separate entry referring to the message. This way, a subsystem replayed from a recording can
+//! be served the same responses to its requests as it got when the recording was taken.
+//!
+//! Capturing a message only clones it, encoding and writing happens on a dedicated thread. The
+//! number of entries waiting for that thread is bounded, further entries are dropped and the
+//! number of dropped entries is recorded instead.
+
+use std::{
+	fs::File,
+	io::{self, BufWriter, Write},
+	path::Path,
+	sync::{
+		atomic::{AtomicU64, AtomicUsize, Ordering},
+		Arc,
+	},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures::{channel::mpsc, select, stream::FuturesUnordered, FutureExt, StreamExt};
+use parity_scale_codec::{Decode, Encode};
+
+use polkadot_primitives::v1::{BlockNumber, Hash};
+
+use crate::{
+	gen::MessageRecorder, jaeger, ActivatedLeaf, ActiveLeavesUpdate, AllMessages, LeafStatus,
+	OverseerSignal,
+};
+
+mod field;
+mod messages;
+
+pub use self::{
+	field::{
+		Field, RecordedRecipient, RecordedRuntimeApiError, RecordedSignedDisputeStatement,
+		ReplayedError, Responses,
+	},
+	messages::*,
+};
+
+const LOG_TARGET: &str = "parachain::overseer-recording";
+
+/// The default number of entries which may wait to be written.
+pub const DEFAULT_QUEUE_CAPACITY: usize = 4096;
+
+/// A recorded overseer signal.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub enum RecordedSignal {
+	/// See [`OverseerSignal::ActiveLeaves`]. The activated leaf is stored as
+	/// hash, number and whether it was fresh.
+	ActiveLeaves {
+		/// The activated leaf, if any.
+		activated: Option<(Hash, BlockNumber, bool)>,
+		/// Deactivated leaves.
+		deactivated: Vec<Hash>,
+	},
+	/// See [`OverseerSignal::BlockFinalized`].
+	BlockFinalized(Hash, BlockNumber),
+	/// See [`OverseerSignal::Conclude`].
+	Conclude,
+}
+
+impl From<&OverseerSignal> for RecordedSignal {
+	fn from(signal: &OverseerSignal) -> Self {
+		match signal {
+			OverseerSignal::ActiveLeaves(update) => RecordedSignal::ActiveLeaves {
+				activated: update
+					.activated
+					.as_ref()
+					.map(|leaf| (leaf.hash, leaf.number, leaf.status.is_fresh())),
+				deactivated: update.deactivated.iter().cloned().collect(),
+			},
+			OverseerSignal::BlockFinalized(hash, number) =>
+				RecordedSignal::BlockFinalized(*hash, *number),
+			OverseerSignal::Conclude => RecordedSignal::Conclude,
+		}
+	}
+}
+
+impl RecordedSignal {
+	/// Turn the recorded signal back into an [`OverseerSignal`].
+	///
+	/// Activated leaves are given a disabled jaeger span.
+	pub fn into_signal(self) -> OverseerSignal {
+		match self {
+			RecordedSignal::ActiveLeaves { activated, deactivated } =>
+				OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
+					activated: activated.map(|(hash, number, fresh)| ActivatedLeaf {
+						hash,
+						number,
+						status: if fresh { LeafStatus::Fresh } else { LeafStatus::Stale },
+						span: Arc::new(jaeger::Span::Disabled),
+					}),
+					deactivated: deactivated.into_iter().collect(),
+				}),
+			RecordedSignal::BlockFinalized(hash, number) =>
+				OverseerSignal::BlockFinalized(hash, number),
+			RecordedSignal::Conclude => OverseerSignal::Conclude,
+		}
+	}
+}
+
+/// A single recorded overseer event.
+#[derive(Debug, Encode, Decode)]
+pub enum RecordedEvent {
+	/// A signal broadcast to all subsystems.
+	Signal(RecordedSignal),
+	/// A message sent to a single subsystem.
+	Message {
+		/// Identifies the message among all recorded ones.
+		seq: u64,
+		/// The message, without its response channels.
+		message: RecordedAllMessages,
+	},
+	/// A response sent on one of the response channels of a message.
+	Response {
+		/// The sequence number of the message.
+		request: u64,
+		/// The index of the response channel within the message.
+		index: u32,
+		/// The encoding of the recorded form of the response.
+		payload: Vec<u8>,
+	},
+	/// Entries which were dropped, as the queue of entries to be written was full.
+	Dropped(u64),
+}
+
+impl RecordedEvent {
+	/// Capture a signal broadcast to all subsystems.
+	pub fn signal(signal: &OverseerSignal) -> Self {
+		RecordedEvent::Signal(signal.into())
+	}
+}
+
+/// A recorded event, with the time it was observed at.
+#[derive(Debug, Encode, Decode)]
+pub struct RecordEntry {
+	/// Milliseconds since the unix epoch.
+	pub timestamp_ms: u64,
+	/// The recorded event.
+	pub event: RecordedEvent,
+}
+
+impl RecordEntry {
+	pub(crate) fn now(event: RecordedEvent) -> Self {
+		let timestamp_ms = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|d| d.as_millis() as u64)
+			.unwrap_or_default();
+
+		RecordEntry { timestamp_ms, event }
+	}
+}
+
+/// An entry to be written, along with the forwarders of its response channels.
+struct Queued {
+	entry: RecordEntry,
+	forwarders: Vec<field::Forwarder>,
+}
+
+/// State shared between a [`FileRecorder`] and its writer thread.
+#[derive(Default)]
+struct Shared {
+	queued: AtomicUsize,
+	dropped: AtomicU64,
+}
+
+/// A [`MessageRecorder`] which appends all events to a file.
+///
+/// Messages are cloned on the calling task, encoded and written on a dedicated thread, which
+/// also forwards the responses to recorded messages. The file is flushed whenever that thread
+/// runs out of pending entries.
+pub struct FileRecorder {
+	tx: mpsc::UnboundedSender<Queued>,
+	shared: Arc<Shared>,
+	capacity: usize,
+	next_seq: AtomicU64,
+}
+
+impl FileRecorder {
+	/// Create or truncate the file at `path` and start recording into it, allowing for
+	/// [`DEFAULT_QUEUE_CAPACITY`] entries waiting to be written.
+	pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+		Self::with_capacity(path, DEFAULT_QUEUE_CAPACITY)
+	}
+
+	/// Create or truncate the file at `path` and start recording into it, allowing for
+	/// `capacity` entries waiting to be written.
+	pub fn with_capacity(path: impl AsRef<Path>, capacity: usize) -> io::Result<Self> {
+		let file = File::create(path)?;
+		let (tx, rx) = mpsc::unbounded();
+		let shared = Arc::new(Shared::default());
+
+		let writer = Writer { file: Some(BufWriter::new(file)), shared: shared.clone() };
+		std::thread::Builder::new()
+			.name("overseer-recorder".into())
+			.spawn(move || futures::executor::block_on(writer.run(rx)))?;
+
+		Ok(FileRecorder { tx, shared, capacity, next_seq: AtomicU64::new(0) })
+	}
+
+	/// Take a slot in the queue, or count the entry as dropped if there is none.
+	fn reserve(&self) -> bool {
+		if self.shared.queued.fetch_add(1, Ordering::AcqRel) < self.capacity {
+			return true
+		}
+
+		self.shared.queued.fetch_sub(1, Ordering::AcqRel);
+		self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+		false
+	}
+
+	fn queue(&self, event: RecordedEvent, forwarders: Vec<field::Forwarder>) {
+		let entry = RecordEntry::now(event);
+		// The writer thread only quits once all senders are gone.
+		let _ = self.tx.unbounded_send(Queued { entry, forwarders });
+	}
+}
+
+impl MessageRecorder<AllMessages, OverseerSignal> for FileRecorder {
+	fn record_message(&self, message: &mut AllMessages) {
+		if !self.reserve() {
+			return
+		}
+
+		let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+		let mut responses = Responses::record(seq);
+		let message = message.record(&mut responses);
+		self.queue(RecordedEvent::Message { seq, message }, responses.into_forwarders());
+	}
+
+	fn record_signal(&self, signal: &OverseerSignal) {
+		if self.reserve() {
+			self.queue(RecordedEvent::signal(signal), Vec::new());
+		}
+	}
+}
+
+struct Writer {
+	/// `None` once writing failed. Responses are still forwarded then.
+	file: Option<BufWriter<File>>,
+	shared: Arc<Shared>,
+}
+
+impl Writer {
+	async fn run(mut self, mut rx: mpsc::UnboundedReceiver<Queued>) {
+		let mut forwarders = FuturesUnordered::new();
+
+		loop {
+			select! {
+				queued = rx.next() => match queued {
+					Some(queued) => self.write_queued(queued, &mut forwarders),
+					None => break,
+				},
+				response = forwarders.select_next_some() => if let Some(entry) = response {
+					self.write(&entry);
+				},
+			}
+
+			// Write whatever else is ready before flushing.
+			while let Some(Some(queued)) = rx.next().now_or_never() {
+				self.write_queued(queued, &mut forwarders);
+			}
+			self.flush();
+		}
+
+		// The overseer is gone, but some requests may still be answered.
+		while let Some(response) = forwarders.next().await {
+			if let Some(entry) = response {
+				self.write(&entry);
+				self.flush();
+			}
+		}
+	}
+
+	fn write_queued(
+		&mut self,
+		queued: Queued,
+		forwarders: &mut FuturesUnordered<field::Forwarder>,
+	) {
+		self.shared.queued.fetch_sub(1, Ordering::AcqRel);
+
+		let dropped = self.shared.dropped.swap(0, Ordering::Relaxed);
+		if dropped > 0 {
+			self.write(&RecordEntry::now(RecordedEvent::Dropped(dropped)));
+		}
+
+		self.write(&queued.entry);
+		forwarders.extend(queued.forwarders);
+	}
+
+	fn write(&mut self, entry: &RecordEntry) {
+		if let Some(ref mut file) = self.file {
+			if let Err(err) = file.write_all(&entry.encode()) {
+				tracing::warn!(target: LOG_TARGET, ?err, "Failed to write recording");
+				self.file = None;
+			}
+		}
+	}
+
+	fn flush(&mut self) {
+		if let Some(ref mut file) = self.file {
+			if let Err(err) = file.flush() {
+				tracing::warn!(target: LOG_TARGET, ?err, "Failed to write recording");
+				self.file = None;
+			}
+		}
+	}
+}
+
+/// Read all entries from a recording file.
+///
+/// A truncated trailing entry, as left behind by a node that was killed while
+/// recording, is ignored.
+pub fn read_recording(path: impl AsRef<Path>) -> io::Result<Vec<RecordEntry>> {
+	let data = std::fs::read(path)?;
+	let mut input = &data[..];
+	let mut entries = Vec::new();
+
+	while !input.is_empty() {
+		match RecordEntry::decode(&mut input) {
+			Ok(entry) => entries.push(entry),
+			Err(err) => {
+				tracing::warn!(
+					target: LOG_TARGET,
+					?err,
+					decoded = entries.len(),
+					"Ignoring undecodable tail of recording",
+				);
+				break
+			},
+		}
+	}
+
+	Ok(entries)
+}
@@ -0,0 +1,163 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State restored into restarted subsystems.
+
+use std::{
+	collections::{HashMap, HashSet},
+	marker::PhantomData,
+};
+
+use polkadot_node_network_protocol::{
+	v1 as protocol_v1, ObservedRole, OurView, PeerId, ProtocolVersion, View,
+};
+use polkadot_node_subsystem_types::messages::{
+	BitfieldDistributionMessage, GossipSupportMessage, GossipTopology, NetworkBridgeEvent,
+};
+use polkadot_primitives::v1::{AuthorityDiscoveryId, Hash};
+
+use crate::{gen::RestartState, ActivatedLeaf, ActiveLeavesUpdate, OverseerSignal};
+
+/// A message which carries the events of a peer set of the network bridge.
+pub trait NetworkBridgeUpdate: From<NetworkBridgeEvent<Self::Protocol>> + Send + 'static {
+	/// The protocol message of the peer set.
+	type Protocol: Send + 'static;
+
+	/// The network bridge event within the message, if any.
+	fn network_bridge_event(&self) -> Option<&NetworkBridgeEvent<Self::Protocol>>;
+}
+
+impl NetworkBridgeUpdate for BitfieldDistributionMessage {
+	type Protocol = protocol_v1::BitfieldDistributionMessage;
+
+	fn network_bridge_event(&self) -> Option<&NetworkBridgeEvent<Self::Protocol>> {
+		match self {
+			BitfieldDistributionMessage::NetworkBridgeUpdateV1(event) => Some(event),
+			_ => None,
+		}
+	}
+}
+
+impl NetworkBridgeUpdate for GossipSupportMessage {
+	type Protocol = protocol_v1::GossipSuppportNetworkMessage;
+
+	fn network_bridge_event(&self) -> Option<&NetworkBridgeEvent<Self::Protocol>> {
+		match self {
+			GossipSupportMessage::NetworkBridgeUpdateV1(event) => Some(event),
+			_ => None,
+		}
+	}
+}
+
+struct Peer {
+	role: ObservedRole,
+	version: ProtocolVersion,
+	authority_ids: Option<HashSet<AuthorityDiscoveryId>>,
+	view: Option<View>,
+}
+
+/// Restores the active leaves, our view, the connected peers with their views and the
+/// gossip topology into a restarted subsystem, which consumes `M`.
+///
+/// The leaves are restored as one `ActiveLeaves` signal each, in the order of their block
+/// numbers. Then our view and the gossip topology are restored, followed by a
+/// `PeerConnected` and the last `PeerViewChange` for each connected peer.
+pub struct NetworkState<M> {
+	leaves: HashMap<Hash, ActivatedLeaf>,
+	our_view: Option<OurView>,
+	topology: Option<GossipTopology>,
+	peers: HashMap<PeerId, Peer>,
+	_phantom: PhantomData<fn() -> M>,
+}
+
+impl<M> Default for NetworkState<M> {
+	fn default() -> Self {
+		NetworkState {
+			leaves: HashMap::new(),
+			our_view: None,
+			topology: None,
+			peers: HashMap::new(),
+			_phantom: PhantomData,
+		}
+	}
+}
+
+impl<M: NetworkBridgeUpdate> RestartState<OverseerSignal, M> for NetworkState<M> {
+	fn observe_signal(&mut self, signal: &OverseerSignal) {
+		if let OverseerSignal::ActiveLeaves(update) = signal {
+			for hash in &update.deactivated {
+				self.leaves.remove(hash);
+			}
+			if let Some(ref leaf) = update.activated {
+				self.leaves.insert(leaf.hash, leaf.clone());
+			}
+		}
+	}
+
+	fn observe_message(&mut self, message: &M) {
+		match message.network_bridge_event() {
+			Some(NetworkBridgeEvent::PeerConnected(peer, role, version, authority_ids)) => {
+				let peer_data = Peer {
+					role: *role,
+					version: *version,
+					authority_ids: authority_ids.clone(),
+					view: None,
+				};
+				self.peers.insert(peer.clone(), peer_data);
+			},
+			Some(NetworkBridgeEvent::PeerDisconnected(peer)) => {
+				self.peers.remove(peer);
+			},
+			Some(NetworkBridgeEvent::PeerViewChange(peer, view)) =>
+				if let Some(peer_data) = self.peers.get_mut(peer) {
+					peer_data.view = Some(view.clone());
+				},
+			Some(NetworkBridgeEvent::OurViewChange(view)) => self.our_view = Some(view.clone()),
+			Some(NetworkBridgeEvent::NewGossipTopology(topology)) =>
+				self.topology = Some(topology.clone()),
+			Some(NetworkBridgeEvent::PeerMessage(..)) | None => {},
+		}
+	}
+
+	fn restore(&self) -> (Vec<OverseerSignal>, Vec<M>) {
+		let mut leaves = self.leaves.values().cloned().collect::<Vec<_>>();
+		leaves.sort_by_key(|leaf| leaf.number);
+		let signals = leaves
+			.into_iter()
+			.map(|leaf| OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(leaf)))
+			.collect();
+
+		let mut events = Vec::new();
+		events.extend(self.our_view.clone().map(NetworkBridgeEvent::OurViewChange));
+		events.extend(self.topology.clone().map(NetworkBridgeEvent::NewGossipTopology));
+		for (peer, peer_data) in &self.peers {
+			events.push(NetworkBridgeEvent::PeerConnected(
+				peer.clone(),
+				peer_data.role,
+				peer_data.version,
+				peer_data.authority_ids.clone(),
+			));
+			events.extend(
+				peer_data
+					.view
+					.clone()
+					.map(|view| NetworkBridgeEvent::PeerViewChange(peer.clone(), view)),
+			);
+		}
+
+		(signals, events.into_iter().map(M::from).collect())
+	}
+}
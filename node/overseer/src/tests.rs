@@ -37,9 +37,11 @@ use crate::{
 	self as overseer,
 	dummy::{dummy_overseer_builder, one_for_all_overseer_builder},
 	gen::Delay,
+	recording::Field as _,
 	HeadSupportsParachains,
 };
 use metered_channel as metered;
+use parity_scale_codec::{Decode, Encode};
 
 use assert_matches::assert_matches;
 use sp_core::crypto::Pair as _;
@@ -1127,6 +1129,8 @@ fn context_holds_onto_message_until_enough_signals_received() {
 		dispute_distribution_unbounded: dispute_distribution_unbounded_tx.clone(),
 		chain_selection_unbounded: chain_selection_unbounded_tx.clone(),
 		pvf_checker_unbounded: pvf_checker_unbounded_tx.clone(),
+
+		recorder: Default::default(),
 	};
 
 	let (mut signal_tx, signal_rx) = metered::channel(CHANNEL_CAPACITY);
@@ -1173,3 +1177,328 @@ fn context_holds_onto_message_until_enough_signals_received() {
 
 	futures::executor::block_on(test_fut);
 }
+
+#[test]
+fn message_flow_graph_follows_declared_sends() {
+	let graph = OVERSEER_MESSAGE_FLOW;
+
+	assert!(graph.starts_with("digraph Overseer {"));
+	assert!(graph.contains(
+		"\tcandidate_backing -> candidate_validation [label = \"CandidateValidationMessage\"];"
+	));
+	assert!(
+		graph.contains("\tnetwork_bridge -> gossip_support [label = \"GossipSupportMessage\"];")
+	);
+	assert!(!graph.contains("\truntime_api ->"));
+	assert!(!graph.contains("style = dashed"));
+}
+
+// Fails on its first start once asked for attestations, passes on everything
+// received afterwards.
+#[derive(Clone)]
+struct FailOnce {
+	starts: Arc<atomic::AtomicUsize>,
+	received: metered::MeteredSender<FromOverseer<BitfieldDistributionMessage>>,
+}
+
+impl<C> overseer::Subsystem<C, SubsystemError> for FailOnce
+where
+	C: overseer::SubsystemContext<
+		Message = BitfieldDistributionMessage,
+		Signal = OverseerSignal,
+		AllMessages = AllMessages,
+	>,
+{
+	fn start(self, mut ctx: C) -> SpawnedSubsystem {
+		let first = self.starts.fetch_add(1, atomic::Ordering::SeqCst) == 0;
+		let mut received = self.received;
+		SpawnedSubsystem {
+			name: "test-fail-once",
+			future: Box::pin(async move {
+				loop {
+					match ctx.recv().await {
+						Ok(FromOverseer::Communication {
+							msg: BitfieldDistributionMessage::GetAvailabilityAttestations(..),
+						}) if first => return Err(SubsystemError::Context("failing".to_owned())),
+						Ok(FromOverseer::Signal(OverseerSignal::Conclude)) | Err(_) =>
+							return Ok(()),
+						Ok(msg) =>
+							if !first {
+								let _ = received.send(msg).await;
+							},
+					}
+				}
+			}),
+		}
+	}
+}
+
+struct ExtractRestarts;
+
+impl<'a, T: 'a> MapSubsystem<&'a OverseenSubsystem<T>> for ExtractRestarts {
+	type Output = Option<(&'static str, gen::RestartCounter)>;
+
+	fn map_subsystem(&self, subsystem: &'a OverseenSubsystem<T>) -> Self::Output {
+		subsystem
+			.instance
+			.as_ref()
+			.map(|instance| (instance.name, instance.meters.restarts.clone()))
+	}
+}
+
+#[test]
+fn supervised_subsystem_is_restarted() {
+	use polkadot_node_network_protocol::{ObservedRole, View};
+
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	executor::block_on(async move {
+		let starts = Arc::new(atomic::AtomicUsize::new(0));
+		let (received, mut received_rx) = metered::channel(64);
+		let subsystem = FailOnce { starts: starts.clone(), received };
+
+		let (overseer, handle) = dummy_overseer_builder(spawner, MockSupportsParachains, None)
+			.unwrap()
+			.replace_bitfield_distribution(move |_| subsystem)
+			.build()
+			.unwrap();
+
+		let restarts = overseer
+			.map_subsystems(ExtractRestarts)
+			.into_iter()
+			.flatten()
+			.find(|(name, _)| *name == "bitfield_distribution")
+			.map(|(_, restarts)| restarts)
+			.unwrap();
+
+		let mut handle = Handle::new(handle);
+		let overseer_fut = overseer.run().fuse();
+		pin_mut!(overseer_fut);
+
+		let hash = Hash::repeat_byte(1);
+		let peer = PeerId::random();
+		let network_update = |event| {
+			AllMessages::BitfieldDistribution(BitfieldDistributionMessage::NetworkBridgeUpdateV1(
+				event,
+			))
+		};
+
+		handle.block_imported(BlockInfo { hash, parent_hash: Default::default(), number: 1 }).await;
+		handle
+			.send_msg_anon(network_update(NetworkBridgeEvent::PeerConnected(
+				peer.clone(),
+				ObservedRole::Full,
+				1,
+				None,
+			)))
+			.await;
+		handle
+			.send_msg_anon(network_update(NetworkBridgeEvent::PeerViewChange(
+				peer.clone(),
+				View::new(vec![hash], 0),
+			)))
+			.await;
+		let (tx, _) = oneshot::channel();
+		handle
+			.send_msg_anon(AllMessages::BitfieldDistribution(
+				BitfieldDistributionMessage::GetAvailabilityAttestations(hash, tx),
+			))
+			.await;
+
+		// The restarted instance learns about the leaf, the peer and its view.
+		let mut restored = Vec::new();
+		while restored.len() < 3 {
+			select! {
+				_ = overseer_fut => panic!("overseer must not exit on a restartable failure"),
+				msg = received_rx.next() => restored.push(msg.unwrap()),
+			}
+		}
+
+		assert_matches!(
+			&restored[0],
+			FromOverseer::Signal(OverseerSignal::ActiveLeaves(update)) => {
+				assert_eq!(update.activated.as_ref().map(|leaf| leaf.hash), Some(hash));
+			}
+		);
+		assert_matches!(
+			&restored[1],
+			FromOverseer::Communication {
+				msg: BitfieldDistributionMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerConnected(p, ObservedRole::Full, 1, None),
+				),
+			} => {
+				assert_eq!(p, &peer);
+			}
+		);
+		assert_matches!(
+			&restored[2],
+			FromOverseer::Communication {
+				msg: BitfieldDistributionMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerViewChange(p, view),
+				),
+			} => {
+				assert_eq!(p, &peer);
+				assert!(view.contains(&hash));
+			}
+		);
+
+		assert_eq!(starts.load(atomic::Ordering::SeqCst), 2);
+		assert_eq!(restarts.read(), 1);
+
+		handle.stop().await;
+		assert!(overseer_fut.await.is_ok());
+	});
+}
+
+#[derive(Default)]
+struct VecRecorder(parking_lot::Mutex<Vec<recording::RecordedEvent>>);
+
+impl MessageRecorder<AllMessages, OverseerSignal> for VecRecorder {
+	fn record_message(&self, message: &mut AllMessages) {
+		let mut events = self.0.lock();
+		let seq = events.len() as u64;
+		let message = message.record(&mut recording::Responses::ignore());
+		events.push(recording::RecordedEvent::Message { seq, message });
+	}
+
+	fn record_signal(&self, signal: &OverseerSignal) {
+		self.0.lock().push(recording::RecordedEvent::signal(signal));
+	}
+}
+
+#[test]
+fn recorder_observes_signals_and_messages() {
+	use recording::{
+		RecordedAllMessages, RecordedApprovalVotingMessage, RecordedEvent, RecordedSignal,
+	};
+
+	let spawner = sp_core::testing::TaskExecutor::new();
+	executor::block_on(async move {
+		let subsystem = CounterSubsystem::new(
+			Arc::new(atomic::AtomicUsize::new(0)),
+			Arc::new(atomic::AtomicUsize::new(0)),
+			Arc::new(atomic::AtomicUsize::new(0)),
+		);
+		let recorder = Arc::new(VecRecorder::default());
+
+		let (overseer, handle) =
+			one_for_all_overseer_builder(spawner, MockSupportsParachains, subsystem, None)
+				.unwrap()
+				.recorder(recorder.clone())
+				.build()
+				.unwrap();
+
+		let mut handle = Handle::new(handle);
+		let overseer_fut = overseer.run().fuse();
+		pin_mut!(overseer_fut);
+
+		let hash = Hash::repeat_byte(1);
+		handle.block_imported(BlockInfo { hash, parent_hash: Default::default(), number: 1 }).await;
+		handle
+			.send_msg_anon(AllMessages::ApprovalVoting(test_approval_voting_msg()))
+			.await;
+		handle.stop().await;
+
+		assert!(overseer_fut.await.is_ok());
+
+		let events = std::mem::take(&mut *recorder.0.lock());
+		assert_eq!(events.len(), 3);
+		assert_matches!(
+			&events[0],
+			RecordedEvent::Signal(RecordedSignal::ActiveLeaves { activated, deactivated }) => {
+				assert_eq!(activated, &Some((hash, 1, true)));
+				assert!(deactivated.is_empty());
+			}
+		);
+		assert_matches!(
+			&events[1],
+			RecordedEvent::Message {
+				message: RecordedAllMessages::ApprovalVoting(
+					RecordedApprovalVotingMessage::ApprovedAncestor(h, 0, ()),
+				),
+				..
+			} => {
+				assert_eq!(h, &Hash::default());
+			}
+		);
+		assert_matches!(&events[2], RecordedEvent::Signal(RecordedSignal::Conclude));
+	});
+}
+
+#[test]
+fn recorded_statement_is_replayed_to_candidate_backing() {
+	use polkadot_node_primitives::{SignedFullStatement, Statement};
+	use polkadot_primitives::v1::UncheckedSigned;
+	use recording::{Replayable, Responses};
+
+	let relay_parent = Hash::repeat_byte(1);
+	let receipt = ::test_helpers::dummy_committed_candidate_receipt(relay_parent);
+	let signature = sp_core::sr25519::Signature::from_raw([1; 64]).into();
+	let statement = SignedFullStatement::new_unchecked_from_trusted_source(UncheckedSigned::new(
+		Statement::Seconded(receipt.clone()),
+		ValidatorIndex(2),
+		signature,
+	));
+
+	let mut message = AllMessages::CandidateBacking(CandidateBackingMessage::Statement(
+		relay_parent,
+		statement.clone(),
+	));
+	let recorded = message.record(&mut Responses::ignore());
+	let recorded = recording::RecordedAllMessages::decode(&mut &recorded.encode()[..]).unwrap();
+
+	assert_matches!(
+		CandidateBackingMessage::replay_from(recorded),
+		Some(CandidateBackingMessage::Statement(r, s)) => {
+			assert_eq!(r, relay_parent);
+			assert_eq!(s, statement);
+			assert_matches!(s.payload(), Statement::Seconded(c) => assert_eq!(c, &receipt));
+		}
+	);
+}
+
+#[test]
+fn file_recorder_records_responses() {
+	use recording::{read_recording, FileRecorder, RecordedAllMessages, RecordedEvent};
+
+	let path =
+		std::env::temp_dir().join(format!("overseer-recording-responses-{}", std::process::id()));
+	let recorder = FileRecorder::create(&path).unwrap();
+
+	let (tx, rx) = oneshot::channel();
+	let mut message = AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(tx));
+	recorder.record_message(&mut message);
+
+	// The recorder forwards the response to the original requester.
+	assert_matches!(
+		message,
+		AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(tx)) => {
+			tx.send(Ok(5)).unwrap();
+		}
+	);
+	assert_eq!(executor::block_on(rx).unwrap().unwrap(), 5);
+	drop(recorder);
+
+	// Wait for the writer thread to catch up.
+	let mut entries = Vec::new();
+	for _ in 0..500 {
+		entries = read_recording(&path).unwrap();
+		if entries.len() == 2 {
+			break
+		}
+		std::thread::sleep(Duration::from_millis(10));
+	}
+	let _ = std::fs::remove_file(&path);
+
+	assert_eq!(entries.len(), 2);
+	assert_matches!(
+		&entries[0].event,
+		RecordedEvent::Message { seq: 0, message: RecordedAllMessages::ChainApi(_) }
+	);
+	assert_matches!(
+		&entries[1].event,
+		RecordedEvent::Response { request: 0, index: 0, payload } => {
+			assert_eq!(payload, &Ok::<u32, String>(5).encode());
+		}
+	);
+}
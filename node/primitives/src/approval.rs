@@ -19,13 +19,16 @@
 pub use sp_consensus_babe::Slot;
 pub use sp_consensus_vrf::schnorrkel::{Randomness, VRFOutput, VRFProof};
 
+use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
 use parity_scale_codec::{Decode, Encode};
 use polkadot_primitives::v1::{
-	BlockNumber, CandidateHash, CandidateIndex, CoreIndex, Hash, Header, ValidatorIndex,
-	ValidatorSignature,
+	BlockNumber, CandidateHash, CandidateIndex, CoreIndex, Hash, Header, SessionIndex,
+	ValidatorIndex, ValidatorSignature,
 };
+use serde::{Deserialize, Serialize};
 use sp_application_crypto::ByteArray;
 use sp_consensus_babe as babe_primitives;
+use std::convert::TryFrom;
 
 /// Validators assigning to check a particular candidate are split up into tranches.
 /// Earlier tranches of validators check first, with later tranches serving as backup.
@@ -38,12 +41,18 @@ pub const RELAY_VRF_STORY_CONTEXT: &[u8] = b"A&V RC-VRF";
 /// A static context used for all relay-vrf-modulo VRFs.
 pub const RELAY_VRF_MODULO_CONTEXT: &[u8] = b"A&V MOD";
 
+/// A static context used for all relay-vrf-modulo-compact VRFs.
+pub const RELAY_VRF_MODULO_COMPACT_CONTEXT: &[u8] = b"A&V MOD COMPACT";
+
 /// A static context used for all relay-vrf-modulo VRFs.
 pub const RELAY_VRF_DELAY_CONTEXT: &[u8] = b"A&V DELAY";
 
 /// A static context used for transcripts indicating assigned availability core.
 pub const ASSIGNED_CORE_CONTEXT: &[u8] = b"A&V ASSIGNED";
 
+/// A static context used for transcripts indicating multiple assigned availability cores.
+pub const ASSIGNED_CORES_CONTEXT: &[u8] = b"A&V ASSIGNED CORES";
+
 /// A static context associated with producing randomness for a core.
 pub const CORE_RANDOMNESS_CONTEXT: &[u8] = b"A&V CORE";
 
@@ -75,8 +84,103 @@ pub enum AssignmentCertKind {
 		/// The core index chosen in this cert.
 		core_index: CoreIndex,
 	},
+	/// An assignment story based on the VRF that authorized the relay-chain block where the
+	/// candidates were included. A single VRF output is used to sample all cores at once, so one
+	/// certificate may claim multiple cores.
+	///
+	/// The context is [`RELAY_VRF_MODULO_COMPACT_CONTEXT`]
+	RelayVRFModuloCompact {
+		/// The cores claimed by this cert.
+		core_bitfield: CoreBitfield,
+	},
+}
+
+/// Errors that may occur when constructing a [`CoreBitfield`] or [`CandidateBitfield`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitfieldError {
+	/// No bits are set, so nothing would be claimed.
+	NullAssignment,
+}
+
+macro_rules! impl_index_bitfield {
+	($bitfield:ident, $index:ty, $to_bit:expr, $from_bit:expr) => {
+		impl $bitfield {
+			/// Whether the bit for the given index is set.
+			pub fn bit_at(&self, index: $index) -> bool {
+				let bit: usize = $to_bit(index);
+				self.0.get(bit).map_or(false, |b| *b)
+			}
+
+			/// The number of bits set.
+			pub fn count_ones(&self) -> usize {
+				self.0.count_ones()
+			}
+
+			/// The index of the lowest bit set, if any.
+			pub fn first_one(&self) -> Option<$index> {
+				self.0.iter_ones().next().map($from_bit)
+			}
+
+			/// Iterate over all indices which have their bit set, in ascending order.
+			pub fn iter_ones(&self) -> impl Iterator<Item = $index> + '_ {
+				self.0.iter_ones().map($from_bit)
+			}
+		}
+
+		impl TryFrom<Vec<$index>> for $bitfield {
+			type Error = BitfieldError;
+
+			fn try_from(indices: Vec<$index>) -> Result<Self, Self::Error> {
+				let bits: Vec<usize> = indices.into_iter().map($to_bit).collect();
+				let len = match bits.iter().max() {
+					Some(max) => max + 1,
+					None => return Err(BitfieldError::NullAssignment),
+				};
+
+				let mut bitfield = BitVec::<BitOrderLsb0, u8>::repeat(false, len);
+				for bit in bits {
+					bitfield.set(bit, true);
+				}
+
+				Ok($bitfield(bitfield))
+			}
+		}
+
+		impl From<$index> for $bitfield {
+			fn from(index: $index) -> Self {
+				let bit: usize = $to_bit(index);
+				let mut bitfield = BitVec::<BitOrderLsb0, u8>::repeat(false, bit + 1);
+				bitfield.set(bit, true);
+
+				$bitfield(bitfield)
+			}
+		}
+	};
 }
 
+/// A bitfield of availability cores, where bit `i` denotes the core with index `i`.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct CoreBitfield(pub BitVec<BitOrderLsb0, u8>);
+
+impl_index_bitfield!(
+	CoreBitfield,
+	CoreIndex,
+	|c: CoreIndex| c.0 as usize,
+	|b: usize| CoreIndex(b as u32)
+);
+
+/// A bitfield of candidates included by a block, where bit `i` denotes the candidate with
+/// [`CandidateIndex`] `i`.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct CandidateBitfield(pub BitVec<BitOrderLsb0, u8>);
+
+impl_index_bitfield!(
+	CandidateBitfield,
+	CandidateIndex,
+	|c: CandidateIndex| c as usize,
+	|b: usize| b as CandidateIndex
+);
+
 /// A certification of assignment.
 #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
 pub struct AssignmentCert {
@@ -115,7 +219,7 @@ pub struct IndirectSignedApprovalVote {
 }
 
 /// Metadata about a block which is now live in the approval protocol.
-#[derive(Debug)]
+#[derive(Debug, Clone, Encode, Decode)]
 pub struct BlockApprovalMeta {
 	/// The hash of the block.
 	pub hash: Hash,
@@ -130,6 +234,85 @@ pub struct BlockApprovalMeta {
 	pub slot: Slot,
 }
 
+/// The tranches of assignments required to approve a candidate, as currently determined by
+/// the approval checking logic.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RequiredTranchesStatus {
+	/// All validators appear to be required.
+	All,
+	/// More tranches are required - assignments are still awaited.
+	#[serde(rename_all = "camelCase")]
+	Pending {
+		/// The highest tranche considered so far.
+		considered: DelayTranche,
+		/// The highest tranche validators may broadcast their assignment for.
+		maximum_broadcast: DelayTranche,
+	},
+	/// Enough tranches have been taken, approval only depends on outstanding votes.
+	#[serde(rename_all = "camelCase")]
+	Exact {
+		/// The tranche to inspect up to.
+		needed: DelayTranche,
+		/// The amount of missing votes that are tolerated.
+		tolerated_missing: u32,
+	},
+}
+
+/// Assignments and approvals received within a single delay tranche of a candidate.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrancheApprovalStatus {
+	/// The delay tranche.
+	pub tranche: DelayTranche,
+	/// Validators assigned to check the candidate in this tranche.
+	pub assignments: Vec<ValidatorIndex>,
+	/// Assigned validators which have approved the candidate.
+	pub approvals: Vec<ValidatorIndex>,
+	/// Assigned validators which have not approved the candidate within the no-show duration.
+	pub no_shows: Vec<ValidatorIndex>,
+}
+
+/// The approval progress of a candidate included by a relay chain block.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateApprovalStatus {
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The core the candidate occupied.
+	pub core_index: u32,
+	/// Whether the candidate is approved under the block.
+	pub approved: bool,
+	/// The amount of approvals needed in the absence of no-shows.
+	pub needed_approvals: u32,
+	/// The required tranches of assignments, `None` if the candidate is approved.
+	pub required_tranches: Option<RequiredTranchesStatus>,
+	/// All tranches with at least one assignment, ordered by tranche.
+	pub tranches: Vec<TrancheApprovalStatus>,
+	/// A rough estimate of the milliseconds until the candidate is approved, assuming
+	/// outstanding assignments and votes arrive in time. `None` if there is no sensible
+	/// estimate, i.e. all validators are required to check the candidate.
+	pub estimated_approval_millis: Option<u64>,
+}
+
+/// The approval progress of a relay chain block and all candidates it included.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockApprovalStatus {
+	/// The hash of the block.
+	pub block_hash: Hash,
+	/// The number of the block.
+	pub block_number: BlockNumber,
+	/// The session the block belongs to.
+	pub session: SessionIndex,
+	/// The current delay tranche, relative to the slot of the block.
+	pub tranche_now: DelayTranche,
+	/// Whether all candidates of the block are approved.
+	pub approved: bool,
+	/// The approval progress of the candidates included by the block.
+	pub candidates: Vec<CandidateApprovalStatus>,
+}
+
 /// Errors that can occur during the approvals protocol.
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
@@ -0,0 +1,53 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Types describing the availability of candidates as attested by signed bitfields.
+
+use polkadot_primitives::v1::{Hash, Id as ParaId, ValidatorIndex};
+use parity_scale_codec::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+/// The availability of a candidate pending availability on a core, as attested by the bitfields
+/// signed on a relay parent.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoreAvailability {
+	/// The index of the core the candidate occupies.
+	pub core_index: u32,
+	/// The para the candidate is for.
+	pub para_id: ParaId,
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The number of validators which attested the availability of their chunk.
+	pub attested: u32,
+	/// The validators which did not attest the availability of their chunk, including those we
+	/// did not receive a bitfield from.
+	pub missing: Vec<ValidatorIndex>,
+	/// Whether enough validators attested for the candidate to be included.
+	pub available: bool,
+}
+
+/// The availability attestations of all validators for a relay parent.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailabilityAttestations {
+	/// The number of validators of the session.
+	pub n_validators: u32,
+	/// The validators we did not receive a bitfield from.
+	pub missing_bitfields: Vec<ValidatorIndex>,
+	/// The candidates pending availability.
+	pub cores: Vec<CoreAvailability>,
+}
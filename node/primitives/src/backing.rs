@@ -0,0 +1,113 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Types describing the lifecycle of candidates in candidate backing.
+
+use polkadot_primitives::v1::{Hash, Id as ParaId, ValidatorIndex};
+use parity_scale_codec::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+/// The kind of a backing statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackingStatementKind {
+	/// The validator seconded the candidate.
+	Seconded,
+	/// The validator attested the validity of the candidate.
+	Valid,
+}
+
+/// An event in the lifecycle of a candidate within candidate backing.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackingEvent {
+	/// We were asked to second the candidate, after it got fetched from a collator.
+	Received {
+		/// The para the candidate is for.
+		para_id: ParaId,
+	},
+	/// The candidate is not for the para assigned to our group, so it was ignored.
+	NotOurAssignment {
+		/// The para the candidate is for.
+		para_id: ParaId,
+	},
+	/// The candidate was not collated by the collator required for the core, so it was ignored.
+	WrongCollator,
+	/// We did not second the candidate, as we seconded another one at the relay parent already.
+	AlreadySeconded {
+		/// The hash of the candidate we seconded.
+		seconded: Hash,
+	},
+	/// Validation of the candidate was started.
+	ValidationStarted {
+		/// The validator the PoV is fetched from, `None` if the collator provided it.
+		from_validator: Option<ValidatorIndex>,
+	},
+	/// The PoV could not be fetched from the given validator.
+	PoVUnavailable {
+		/// The validator the PoV was requested from.
+		from_validator: ValidatorIndex,
+	},
+	/// The candidate was found to be valid and its data was made available.
+	Valid,
+	/// The candidate was found to be invalid.
+	Invalid {
+		/// Why the candidate is considered invalid.
+		reason: String,
+	},
+	/// A statement about the candidate was imported into the statement table.
+	Statement {
+		/// The validator who issued the statement.
+		validator: ValidatorIndex,
+		/// The kind of the statement.
+		kind: BackingStatementKind,
+	},
+	/// A statement about the candidate was not accepted by the statement table, e.g. because
+	/// the validator is not a member of the group assigned to the para.
+	StatementIgnored {
+		/// The validator who issued the statement.
+		validator: ValidatorIndex,
+		/// The kind of the statement.
+		kind: BackingStatementKind,
+	},
+	/// The state of the candidate in the statement table after importing a statement.
+	Attestation {
+		/// The number of validity votes witnessed.
+		validity_votes: u32,
+		/// The number of validity votes required for the candidate to be backed.
+		requisite_votes: u32,
+	},
+	/// The candidate got backed and was handed to the provisioner.
+	Backed,
+	/// A validator misbehaved with regards to the candidate, e.g. by issuing conflicting
+	/// statements.
+	Misbehavior {
+		/// The misbehaving validator.
+		validator: ValidatorIndex,
+	},
+}
+
+/// An event in the lifecycle of a candidate, as recorded by candidate backing.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackingTraceEntry {
+	/// Milliseconds since the UNIX epoch at which the event was recorded.
+	pub timestamp: u64,
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The event.
+	pub event: BackingEvent,
+}
@@ -94,6 +94,15 @@ pub enum Error {
 }
 
 impl DisputeMessage {
+	/// Create a `DisputeMessage` from an `UncheckedDisputeMessage` which is known to have been
+	/// built from checked statements, without checking it again.
+	///
+	/// Attention: Only to be used for messages that have been checked before, e.g. when reading
+	/// back a message which was recorded as `DisputeMessage`.
+	pub fn new_unchecked_from_trusted_source(message: UncheckedDisputeMessage) -> Self {
+		DisputeMessage(message)
+	}
+
 	/// Build a `SignedDisputeMessage` and check what can be checked.
 	///
 	/// This function checks that:
@@ -42,7 +42,7 @@ pub struct SignedDisputeStatement {
 }
 
 /// Tracked votes on candidates, for the purposes of dispute resolution.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Encode, Decode)]
 pub struct CandidateVotes {
 	/// The receipt of the candidate itself.
 	pub candidate_receipt: CandidateReceipt,
@@ -0,0 +1,87 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Types describing the gossip topology of a session and our connectivity to its authorities.
+
+use polkadot_primitives::v1::{AuthorityDiscoveryId, SessionIndex};
+use parity_scale_codec::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+/// A neighbor in the gossip topology along with its connection status.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopologyNeighbor {
+	/// The authority discovery key of the neighbor.
+	pub authority_id: AuthorityDiscoveryId,
+	/// Whether the addresses of the neighbor were resolved via authority discovery.
+	pub resolved: bool,
+	/// The peer id of the neighbor, if we are connected to it.
+	pub peer_id: Option<String>,
+}
+
+/// The gossip topology of a session, from the perspective of the local authority.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GossipTopologyInfo {
+	/// The session the topology was computed for.
+	pub session_index: SessionIndex,
+	/// The name of the strategy the topology was arranged with.
+	pub strategy: String,
+	/// Our neighbors in our row of the grid.
+	pub row: Vec<TopologyNeighbor>,
+	/// Our neighbors in our column of the grid.
+	pub column: Vec<TopologyNeighbor>,
+}
+
+/// The reason we are not connected to an authority of the session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectivityFailure {
+	/// The addresses of the authority could not be resolved via authority discovery.
+	UnresolvedAddress,
+	/// The addresses were resolved, but no connection could be established.
+	DialFailure,
+	/// The authority is connected to us for block sync, but didn't open a notification stream on
+	/// the validation peer set, presumably as it does not support a version of the protocol we
+	/// know.
+	ProtocolMismatch,
+}
+
+/// An authority of the session we are not connected to.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnreachableAuthority {
+	/// The authority discovery key of the authority.
+	pub authority_id: AuthorityDiscoveryId,
+	/// Why we are not connected to it.
+	pub reason: ConnectivityFailure,
+}
+
+/// Our connectivity to the other authorities of a session.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityReport {
+	/// The session the report is about.
+	pub session_index: SessionIndex,
+	/// The number of other authorities in the session.
+	pub authorities: u32,
+	/// The number of those whose addresses were resolved via authority discovery.
+	pub resolved: u32,
+	/// The number of those we are connected to on the validation peer set.
+	pub connected: u32,
+	/// The authorities we are not connected to.
+	pub unreachable: Vec<UnreachableAuthority>,
+}
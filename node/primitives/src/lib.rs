@@ -45,6 +45,15 @@ pub use polkadot_parachain::primitives::BlockData;
 
 pub mod approval;
 
+/// Availability attestation diagnostics types.
+pub mod availability;
+
+/// Candidate backing diagnostics types.
+pub mod backing;
+
+/// Gossip topology introspection types.
+pub mod gossip_support;
+
 /// Disputes related types.
 pub mod disputes;
 pub use disputes::{
@@ -203,7 +212,7 @@ pub type SignedFullStatement = Signed<Statement, CompactStatement>;
 pub type UncheckedSignedFullStatement = UncheckedSigned<Statement, CompactStatement>;
 
 /// Candidate invalidity details
-#[derive(Debug)]
+#[derive(Debug, Clone, Encode, Decode)]
 pub enum InvalidCandidate {
 	/// Failed to execute.`validate_block`. This includes function panicking.
 	ExecutionError(String),
@@ -234,7 +243,7 @@ pub enum InvalidCandidate {
 }
 
 /// Result of the validation of the candidate.
-#[derive(Debug)]
+#[derive(Debug, Clone, Encode, Decode)]
 pub enum ValidationResult {
 	/// Candidate is valid. The validation process yields these outputs and the persisted validation
 	/// data used to form inputs.
@@ -27,7 +27,9 @@ mod relay_chain_selection;
 pub mod overseer;
 
 #[cfg(feature = "full-node")]
-pub use self::overseer::{OverseerGen, OverseerGenArgs, RealOverseerGen};
+pub use self::overseer::{
+	NetworkBridgeConfig, OverseerGen, OverseerGenArgs, RealOverseerGen, RecordingOverseerGen,
+};
 
 #[cfg(test)]
 mod tests;
@@ -63,7 +65,7 @@ pub use {
 };
 
 #[cfg(feature = "full-node")]
-use polkadot_subsystem::jaeger;
+pub use polkadot_subsystem::jaeger;
 
 use std::{sync::Arc, time::Duration};
 
@@ -279,19 +281,15 @@ impl IdentifyVariant for Box<dyn ChainSpec> {
 	}
 }
 
-/// Initialize the `Jeager` collector. The destination must listen
-/// on the given address and port for `UDP` packets.
+/// Initialize the `Jeager` collector, exporting to the destination configured in `jaeger_config`.
 #[cfg(any(test, feature = "full-node"))]
-fn jaeger_launch_collector_with_agent(
+fn jaeger_launch_collector(
 	spawner: impl SpawnNamed,
 	config: &Configuration,
-	agent: Option<std::net::SocketAddr>,
+	jaeger_config: Option<jaeger::JaegerConfigBuilder>,
 ) -> Result<(), Error> {
-	if let Some(agent) = agent {
-		let cfg = jaeger::JaegerConfig::builder()
-			.agent(agent)
-			.named(&config.network.node_name)
-			.build();
+	if let Some(jaeger_config) = jaeger_config {
+		let cfg = jaeger_config.named(&config.network.node_name).build();
 
 		jaeger::Jaeger::new(cfg).launch(spawner)?;
 	}
@@ -330,7 +328,7 @@ where
 #[cfg(feature = "full-node")]
 fn new_partial_basics<RuntimeApi, ExecutorDispatch>(
 	config: &mut Configuration,
-	jaeger_agent: Option<std::net::SocketAddr>,
+	jaeger_config: Option<jaeger::JaegerConfigBuilder>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
 ) -> Result<Basics<RuntimeApi, ExecutorDispatch>, Error>
 where
@@ -385,7 +383,7 @@ where
 		telemetry
 	});
 
-	jaeger_launch_collector_with_agent(task_manager.spawn_handle(), &*config, jaeger_agent)?;
+	jaeger_launch_collector(task_manager.spawn_handle(), &*config, jaeger_config)?;
 
 	Ok(Basics { task_manager, client, backend, keystore_container, telemetry })
 }
@@ -398,6 +396,7 @@ fn new_partial<RuntimeApi, ExecutorDispatch, ChainSelection>(
 		ExecutorDispatch,
 	>,
 	select_chain: ChainSelection,
+	overseer_handle: Option<Handle>,
 ) -> Result<
 	service::PartialComponents<
 		FullClient<RuntimeApi, ExecutorDispatch>,
@@ -539,6 +538,7 @@ where
 					beefy_best_block_stream: beefy_best_block_stream.clone(),
 					subscription_executor,
 				},
+				overseer_handle: overseer_handle.clone(),
 			};
 
 			polkadot_rpc::create_full(deps).map_err(Into::into)
@@ -671,7 +671,9 @@ pub fn new_full<RuntimeApi, ExecutorDispatch, OverseerGenerator>(
 	is_collator: IsCollator,
 	grandpa_pause: Option<(u32, u32)>,
 	enable_beefy: bool,
-	jaeger_agent: Option<std::net::SocketAddr>,
+	network_bridge_config: NetworkBridgeConfig,
+	latency_aware_gossip_topology: bool,
+	jaeger_config: Option<jaeger::JaegerConfigBuilder>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
 	program_path: Option<std::path::PathBuf>,
 	overseer_gen: OverseerGenerator,
@@ -711,7 +713,7 @@ where
 
 	let basics = new_partial_basics::<RuntimeApi, ExecutorDispatch>(
 		&mut config,
-		jaeger_agent,
+		jaeger_config,
 		telemetry_worker_handle,
 	)?;
 
@@ -761,6 +763,7 @@ where
 		&mut config,
 		basics,
 		select_chain,
+		Some(overseer_handle.clone()),
 	)?;
 
 	let shared_voter_state = rpc_setup;
@@ -801,6 +804,8 @@ where
 	config.network.request_response_protocols.push(cfg);
 	let (collation_req_receiver, cfg) = IncomingRequest::get_config_receiver();
 	config.network.request_response_protocols.push(cfg);
+	let (candidate_collation_req_receiver, cfg) = IncomingRequest::get_config_receiver();
+	config.network.request_response_protocols.push(cfg);
 	let (available_data_req_receiver, cfg) = IncomingRequest::get_config_receiver();
 	config.network.request_response_protocols.push(cfg);
 	let (statement_req_receiver, cfg) = IncomingRequest::get_config_receiver();
@@ -864,6 +869,10 @@ where
 	let approval_voting_config = ApprovalVotingConfig {
 		col_data: crate::parachains_db::REAL_COLUMNS.col_approval_data,
 		slot_duration_millis: slot_duration.as_millis() as u64,
+		// Peers speaking version 1 of the validation protocol can't receive compact
+		// assignments and would drop our approvals based on them, so they stay disabled
+		// until all validators have upgraded.
+		enable_compact_assignments: false,
 	};
 
 	let candidate_validation_config = CandidateValidationConfig {
@@ -887,6 +896,21 @@ where
 		col_data: crate::parachains_db::REAL_COLUMNS.col_dispute_coordinator_data,
 	};
 
+	let collator_protocol_config = overseer::CollatorProtocolValidatorConfig {
+		col_reputation: crate::parachains_db::REAL_COLUMNS.col_collator_reputation,
+		max_pending_advertisements:
+			overseer::CollatorProtocolValidatorConfig::DEFAULT_MAX_PENDING_ADVERTISEMENTS,
+		max_parallel_fetches:
+			overseer::CollatorProtocolValidatorConfig::DEFAULT_MAX_PARALLEL_FETCHES,
+	};
+
+	let gossip_topology_strategy: Arc<dyn overseer::TopologyStrategy> =
+		if latency_aware_gossip_topology {
+			Arc::new(overseer::LatencyAwareGrid::new(config.network.public_addresses.clone()))
+		} else {
+			Arc::new(overseer::RandomGrid)
+		};
+
 	let rpc_handlers = service::spawn_tasks(service::SpawnTasksParams {
 		config,
 		backend: backend.clone(),
@@ -969,6 +993,7 @@ where
 					pov_req_receiver,
 					chunk_req_receiver,
 					collation_req_receiver,
+					candidate_collation_req_receiver,
 					available_data_req_receiver,
 					statement_req_receiver,
 					dispute_req_receiver,
@@ -982,6 +1007,9 @@ where
 					dispute_coordinator_config,
 					disputes_enabled,
 					pvf_checker_enabled,
+					collator_protocol_config,
+					network_bridge_config,
+					gossip_topology_strategy,
 				},
 			)
 			.map_err(|e| {
@@ -1184,13 +1212,13 @@ where
 
 #[cfg(feature = "full-node")]
 macro_rules! chain_ops {
-	($config:expr, $jaeger_agent:expr, $telemetry_worker_handle:expr; $scope:ident, $executor:ident, $variant:ident) => {{
+	($config:expr, $jaeger_config:expr, $telemetry_worker_handle:expr; $scope:ident, $executor:ident, $variant:ident) => {{
 		let telemetry_worker_handle = $telemetry_worker_handle;
-		let jaeger_agent = $jaeger_agent;
+		let jaeger_config = $jaeger_config;
 		let mut config = $config;
 		let basics = new_partial_basics::<$scope::RuntimeApi, $executor>(
 			config,
-			jaeger_agent,
+			jaeger_config,
 			telemetry_worker_handle,
 		)?;
 
@@ -1203,6 +1231,7 @@ macro_rules! chain_ops {
 				&mut config,
 				basics,
 				chain_selection,
+				None,
 			)?;
 		Ok((Arc::new(Client::$variant(client)), backend, import_queue, task_manager))
 	}};
@@ -1212,7 +1241,7 @@ macro_rules! chain_ops {
 #[cfg(feature = "full-node")]
 pub fn new_chain_ops(
 	mut config: &mut Configuration,
-	jaeger_agent: Option<std::net::SocketAddr>,
+	jaeger_config: Option<jaeger::JaegerConfigBuilder>,
 ) -> Result<
 	(
 		Arc<Client>,
@@ -1231,22 +1260,22 @@ pub fn new_chain_ops(
 		config.chain_spec.is_wococo() ||
 		config.chain_spec.is_versi()
 	{
-		return chain_ops!(config, jaeger_agent, telemetry_worker_handle; rococo_runtime, RococoExecutorDispatch, Rococo)
+		return chain_ops!(config, jaeger_config, telemetry_worker_handle; rococo_runtime, RococoExecutorDispatch, Rococo)
 	}
 
 	#[cfg(feature = "kusama-native")]
 	if config.chain_spec.is_kusama() {
-		return chain_ops!(config, jaeger_agent, telemetry_worker_handle; kusama_runtime, KusamaExecutorDispatch, Kusama)
+		return chain_ops!(config, jaeger_config, telemetry_worker_handle; kusama_runtime, KusamaExecutorDispatch, Kusama)
 	}
 
 	#[cfg(feature = "westend-native")]
 	if config.chain_spec.is_westend() {
-		return chain_ops!(config, jaeger_agent, telemetry_worker_handle; westend_runtime, WestendExecutorDispatch, Westend)
+		return chain_ops!(config, jaeger_config, telemetry_worker_handle; westend_runtime, WestendExecutorDispatch, Westend)
 	}
 
 	#[cfg(feature = "polkadot-native")]
 	{
-		return chain_ops!(config, jaeger_agent, telemetry_worker_handle; polkadot_runtime, PolkadotExecutorDispatch, Polkadot)
+		return chain_ops!(config, jaeger_config, telemetry_worker_handle; polkadot_runtime, PolkadotExecutorDispatch, Polkadot)
 	}
 	#[cfg(not(feature = "polkadot-native"))]
 	Err(Error::NoRuntime)
@@ -1258,7 +1287,9 @@ pub fn build_full(
 	is_collator: IsCollator,
 	grandpa_pause: Option<(u32, u32)>,
 	enable_beefy: bool,
-	jaeger_agent: Option<std::net::SocketAddr>,
+	network_bridge_config: NetworkBridgeConfig,
+	latency_aware_gossip_topology: bool,
+	jaeger_config: Option<jaeger::JaegerConfigBuilder>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
 	overseer_gen: impl OverseerGen,
 ) -> Result<NewFull<Client>, Error> {
@@ -1272,7 +1303,9 @@ pub fn build_full(
 			is_collator,
 			grandpa_pause,
 			enable_beefy,
-			jaeger_agent,
+			network_bridge_config,
+			latency_aware_gossip_topology,
+			jaeger_config,
 			telemetry_worker_handle,
 			None,
 			overseer_gen,
@@ -1287,7 +1320,9 @@ pub fn build_full(
 			is_collator,
 			grandpa_pause,
 			enable_beefy,
-			jaeger_agent,
+			network_bridge_config,
+			latency_aware_gossip_topology,
+			jaeger_config,
 			telemetry_worker_handle,
 			None,
 			overseer_gen,
@@ -1302,7 +1337,9 @@ pub fn build_full(
 			is_collator,
 			grandpa_pause,
 			enable_beefy,
-			jaeger_agent,
+			network_bridge_config,
+			latency_aware_gossip_topology,
+			jaeger_config,
 			telemetry_worker_handle,
 			None,
 			overseer_gen,
@@ -1317,7 +1354,9 @@ pub fn build_full(
 			is_collator,
 			grandpa_pause,
 			enable_beefy,
-			jaeger_agent,
+			network_bridge_config,
+			latency_aware_gossip_topology,
+			jaeger_config,
 			telemetry_worker_handle,
 			None,
 			overseer_gen,
@@ -30,8 +30,8 @@ pub use polkadot_overseer::{
 	HeadSupportsParachains,
 };
 use polkadot_overseer::{
-	metrics::Metrics as OverseerMetrics, BlockInfo, MetricsTrait, Overseer, OverseerBuilder,
-	OverseerConnector, OverseerHandle,
+	metrics::Metrics as OverseerMetrics, recording::FileRecorder, BlockInfo, MetricsTrait, Overseer,
+	OverseerBuilder, OverseerConnector, OverseerHandle,
 };
 
 use polkadot_primitives::v2::ParachainHost;
@@ -41,16 +41,22 @@ use sc_keystore::LocalKeystore;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_consensus_babe::BabeApi;
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 pub use polkadot_approval_distribution::ApprovalDistribution as ApprovalDistributionSubsystem;
 pub use polkadot_availability_bitfield_distribution::BitfieldDistribution as BitfieldDistributionSubsystem;
 pub use polkadot_availability_distribution::AvailabilityDistributionSubsystem;
 pub use polkadot_availability_recovery::AvailabilityRecoverySubsystem;
-pub use polkadot_collator_protocol::{CollatorProtocolSubsystem, ProtocolSide};
+pub use polkadot_collator_protocol::{
+	CollatorProtocolSubsystem, ProtocolSide, ValidatorConfig as CollatorProtocolValidatorConfig,
+};
 pub use polkadot_dispute_distribution::DisputeDistributionSubsystem;
-pub use polkadot_gossip_support::GossipSupport as GossipSupportSubsystem;
-pub use polkadot_network_bridge::NetworkBridge as NetworkBridgeSubsystem;
+pub use polkadot_gossip_support::{
+	GossipSupport as GossipSupportSubsystem, LatencyAwareGrid, RandomGrid, TopologyStrategy,
+};
+pub use polkadot_network_bridge::{
+	Config as NetworkBridgeConfig, NetworkBridge as NetworkBridgeSubsystem,
+};
 pub use polkadot_node_collation_generation::CollationGenerationSubsystem;
 pub use polkadot_node_core_approval_voting::ApprovalVotingSubsystem;
 pub use polkadot_node_core_av_store::AvailabilityStoreSubsystem;
@@ -88,6 +94,8 @@ where
 	pub pov_req_receiver: IncomingRequestReceiver<request_v1::PoVFetchingRequest>,
 	pub chunk_req_receiver: IncomingRequestReceiver<request_v1::ChunkFetchingRequest>,
 	pub collation_req_receiver: IncomingRequestReceiver<request_v1::CollationFetchingRequest>,
+	pub candidate_collation_req_receiver:
+		IncomingRequestReceiver<request_v1::CandidateCollationFetchingRequest>,
 	pub available_data_req_receiver:
 		IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
 	pub statement_req_receiver: IncomingRequestReceiver<request_v1::StatementFetchingRequest>,
@@ -112,6 +120,12 @@ where
 	pub disputes_enabled: bool,
 	/// Enable PVF pre-checking
 	pub pvf_checker_enabled: bool,
+	/// Configuration for the validator side of the collator protocol.
+	pub collator_protocol_config: CollatorProtocolValidatorConfig,
+	/// Configuration for the network bridge.
+	pub network_bridge_config: NetworkBridgeConfig,
+	/// The strategy arranging the authorities into the gossip topology.
+	pub gossip_topology_strategy: Arc<dyn TopologyStrategy>,
 }
 
 /// Obtain a prepared `OverseerBuilder`, that is initialized
@@ -127,6 +141,7 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 		pov_req_receiver,
 		chunk_req_receiver,
 		collation_req_receiver,
+		candidate_collation_req_receiver,
 		available_data_req_receiver,
 		statement_req_receiver,
 		dispute_req_receiver,
@@ -140,6 +155,9 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 		dispute_coordinator_config,
 		disputes_enabled,
 		pvf_checker_enabled,
+		collator_protocol_config,
+		network_bridge_config,
+		gossip_topology_strategy,
 	}: OverseerGenArgs<'a, Spawner, RuntimeClient>,
 ) -> Result<
 	OverseerBuilder<
@@ -205,7 +223,7 @@ where
 		))
 		.candidate_backing(CandidateBackingSubsystem::new(
 			spawner.clone(),
-			keystore.clone(),
+			(keystore.clone(), Default::default()),
 			Metrics::register(registry)?,
 		))
 		.candidate_validation(CandidateValidationSubsystem::with_config(
@@ -226,11 +244,14 @@ where
 					network_service.local_peer_id().clone(),
 					collator_pair,
 					collation_req_receiver,
+					candidate_collation_req_receiver,
 					Metrics::register(registry)?,
 				),
 				IsCollator::No => ProtocolSide::Validator {
 					keystore: keystore.clone(),
 					eviction_policy: Default::default(),
+					db: parachains_db.clone(),
+					config: collator_protocol_config,
 					metrics: Metrics::register(registry)?,
 				},
 			};
@@ -241,17 +262,21 @@ where
 			authority_discovery_service.clone(),
 			Box::new(network_service.clone()),
 			Metrics::register(registry)?,
+			network_bridge_config,
 		))
 		.provisioner(ProvisionerSubsystem::new(
 			spawner.clone(),
 			ProvisionerConfig { disputes_enabled },
 			Metrics::register(registry)?,
 		))
-		.runtime_api(RuntimeApiSubsystem::new(
-			runtime_client.clone(),
-			Metrics::register(registry)?,
-			spawner.clone(),
-		))
+		.runtime_api(
+			RuntimeApiSubsystem::new(
+				runtime_client.clone(),
+				Metrics::register(registry)?,
+				spawner.clone(),
+			)
+			.with_prefetch(true),
+		)
 		.statement_distribution(StatementDistributionSubsystem::new(
 			keystore.clone(),
 			statement_req_receiver,
@@ -265,10 +290,11 @@ where
 			Box::new(network_service.clone()),
 			Metrics::register(registry)?,
 		))
-		.gossip_support(GossipSupportSubsystem::new(
+		.gossip_support(GossipSupportSubsystem::with_topology_strategy(
 			keystore.clone(),
 			authority_discovery_service.clone(),
 			Metrics::register(registry)?,
+			gossip_topology_strategy,
 		))
 		.dispute_coordinator(if disputes_enabled {
 			DisputeCoordinatorSubsystem::new(
@@ -347,3 +373,27 @@ impl OverseerGen for RealOverseerGen {
 			.map_err(|e| e.into())
 	}
 }
+
+/// The regular set of subsystems, with all overseer traffic recorded to the given file.
+///
+/// See [`polkadot_overseer::recording`] for the format and how to replay it.
+pub struct RecordingOverseerGen(pub PathBuf);
+
+impl OverseerGen for RecordingOverseerGen {
+	fn generate<'a, Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'a, Spawner, RuntimeClient>,
+	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		let recorder = FileRecorder::create(&self.0)?;
+		prepared_overseer_builder(args)?
+			.recorder(Arc::new(recorder))
+			.build_with_connector(connector)
+			.map_err(|e| e.into())
+	}
+}
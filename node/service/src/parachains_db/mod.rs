@@ -24,13 +24,17 @@ pub(crate) mod columns {
 	pub mod v0 {
 		pub const NUM_COLUMNS: u32 = 3;
 	}
-	pub const NUM_COLUMNS: u32 = 5;
+	pub mod v1 {
+		pub const NUM_COLUMNS: u32 = 5;
+	}
+	pub const NUM_COLUMNS: u32 = 6;
 
 	pub const COL_AVAILABILITY_DATA: u32 = 0;
 	pub const COL_AVAILABILITY_META: u32 = 1;
 	pub const COL_APPROVAL_DATA: u32 = 2;
 	pub const COL_CHAIN_SELECTION_DATA: u32 = 3;
 	pub const COL_DISPUTE_COORDINATOR_DATA: u32 = 4;
+	pub const COL_COLLATOR_REPUTATION: u32 = 5;
 }
 
 /// Columns used by different subsystems.
@@ -47,6 +51,8 @@ pub struct ColumnsConfig {
 	pub col_chain_selection_data: u32,
 	/// The column used by dispute coordinator for data.
 	pub col_dispute_coordinator_data: u32,
+	/// The column used by the collator protocol for the reputation of collators.
+	pub col_collator_reputation: u32,
 }
 
 /// The real columns used by the parachains DB.
@@ -57,6 +63,7 @@ pub const REAL_COLUMNS: ColumnsConfig = ColumnsConfig {
 	col_approval_data: columns::COL_APPROVAL_DATA,
 	col_chain_selection_data: columns::COL_CHAIN_SELECTION_DATA,
 	col_dispute_coordinator_data: columns::COL_DISPUTE_COORDINATOR_DATA,
+	col_collator_reputation: columns::COL_COLLATOR_REPUTATION,
 };
 
 /// The cache size for each column, in megabytes.
@@ -19,8 +19,10 @@
 #![warn(missing_docs)]
 
 use polkadot_node_subsystem::{
-	messages::AllMessages, overseer, FromOverseer, OverseerSignal, SpawnedSubsystem,
-	SubsystemContext, SubsystemError, SubsystemResult,
+	messages::AllMessages,
+	overseer::{self, OutgoingMessagesOf},
+	FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext, SubsystemError,
+	SubsystemResult,
 };
 use polkadot_node_subsystem_util::TimeoutExt;
 
@@ -30,6 +32,7 @@ use sp_core::{testing::TaskExecutor, traits::SpawnNamed};
 
 use std::{
 	convert::Infallible,
+	marker::PhantomData,
 	pin::Pin,
 	sync::Arc,
 	task::{Context, Poll, Waker},
@@ -39,6 +42,9 @@ use std::{
 /// Generally useful mock data providers for unit tests.
 pub mod mock;
 
+/// Replay of overseer recordings.
+pub mod replay;
+
 enum SinkState<T> {
 	Empty { read_waker: Option<Waker> },
 	Item { item: T, ready_waker: Option<Waker>, flush_waker: Option<Waker> },
@@ -137,44 +143,53 @@ pub fn single_item_sink<T>() -> (SingleItemSink<T>, SingleItemStream<T>) {
 	(SingleItemSink(inner.clone()), SingleItemStream(inner))
 }
 
-/// A test subsystem sender.
-#[derive(Clone)]
-pub struct TestSubsystemSender {
+/// A test subsystem sender of the subsystem consuming `M`.
+///
+/// Only accepts the messages that subsystem may send, the default accepts all messages.
+pub struct TestSubsystemSender<M = ()> {
 	tx: mpsc::UnboundedSender<AllMessages>,
+	_phantom: PhantomData<fn() -> M>,
+}
+
+// Derive clone not possible, as it puts `Clone` constraint on `M` which is not sensible here.
+impl<M> Clone for TestSubsystemSender<M> {
+	fn clone(&self) -> Self {
+		Self { tx: self.tx.clone(), _phantom: PhantomData }
+	}
 }
 
 /// Construct a sender/receiver pair.
 pub fn sender_receiver() -> (TestSubsystemSender, mpsc::UnboundedReceiver<AllMessages>) {
 	let (tx, rx) = mpsc::unbounded();
-	(TestSubsystemSender { tx }, rx)
+	(TestSubsystemSender { tx, _phantom: PhantomData }, rx)
 }
 
 #[async_trait::async_trait]
-impl<T> overseer::SubsystemSender<T> for TestSubsystemSender
+impl<M> overseer::SubsystemSender<OutgoingMessagesOf<M>> for TestSubsystemSender<M>
 where
-	T: Into<AllMessages> + Send + 'static,
+	M: overseer::AssociateOutgoing<AllMessages> + 'static,
 {
-	async fn send_message(&mut self, msg: T) {
+	async fn send_message(&mut self, msg: OutgoingMessagesOf<M>) {
 		self.tx.send(msg.into()).await.expect("test overseer no longer live");
 	}
 
 	async fn send_messages<X>(&mut self, msgs: X)
 	where
-		X: IntoIterator<Item = T> + Send,
+		X: IntoIterator<Item = OutgoingMessagesOf<M>> + Send,
 		X::IntoIter: Send,
 	{
 		let mut iter = stream::iter(msgs.into_iter().map(|msg| Ok(msg.into())));
 		self.tx.send_all(&mut iter).await.expect("test overseer no longer live");
 	}
 
-	fn send_unbounded_message(&mut self, msg: T) {
+	fn send_unbounded_message(&mut self, msg: OutgoingMessagesOf<M>) {
 		self.tx.unbounded_send(msg.into()).expect("test overseer no longer live");
 	}
 }
 
 /// A test subsystem context.
 pub struct TestSubsystemContext<M, S> {
-	tx: TestSubsystemSender,
+	tx: TestSubsystemSender<M>,
 	rx: SingleItemStream<FromOverseer<M>>,
 	spawn: S,
 }
@@ -182,12 +197,12 @@ pub struct TestSubsystemContext<M, S> {
 #[async_trait::async_trait]
 impl<M, S> overseer::SubsystemContext for TestSubsystemContext<M, S>
 where
-	M: std::fmt::Debug + Send + 'static,
+	M: overseer::AssociateOutgoing<AllMessages> + std::fmt::Debug + Send + 'static,
 	AllMessages: From<M>,
 	S: SpawnNamed + Send + 'static,
 {
 	type Message = M;
-	type Sender = TestSubsystemSender;
+	type Sender = TestSubsystemSender<M>;
 	type Signal = OverseerSignal;
 	type AllMessages = AllMessages;
 	type Error = SubsystemError;
@@ -225,7 +240,7 @@ where
 		Ok(())
 	}
 
-	fn sender(&mut self) -> &mut TestSubsystemSender {
+	fn sender(&mut self) -> &mut TestSubsystemSender<M> {
 		&mut self.tx
 	}
 }
@@ -269,7 +284,7 @@ pub fn make_subsystem_context<M, S>(
 
 	(
 		TestSubsystemContext {
-			tx: TestSubsystemSender { tx: all_messages_tx },
+			tx: TestSubsystemSender { tx: all_messages_tx, _phantom: PhantomData },
 			rx: overseer_rx,
 			spawn,
 		},
@@ -420,4 +435,89 @@ mod tests {
 		assert_eq!(first, 11_usize);
 		assert_eq!(second, 0);
 	}
+
+	#[test]
+	fn replay_feeds_subsystem_and_serves_its_requests() {
+		use futures::channel::oneshot;
+		use parity_scale_codec::Encode;
+		use polkadot_node_subsystem::{
+			errors::ChainApiError,
+			messages::{ApprovalVotingMessage, ChainApiMessage},
+		};
+		use polkadot_overseer::{
+			gen::SubsystemContext as _,
+			recording::{Field, RecordEntry, RecordedEvent, Responses},
+		};
+		use replay::{replay_recording, ReplayOutcome};
+
+		let hash = Hash::repeat_byte(1);
+		let message = |seq: u64, mut message: AllMessages| RecordedEvent::Message {
+			seq,
+			message: message.record(&mut Responses::ignore()),
+		};
+		let recording = vec![
+			RecordedEvent::signal(&OverseerSignal::BlockFinalized(hash, 1)),
+			message(
+				0,
+				AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(oneshot::channel().0)),
+			),
+			RecordedEvent::Response {
+				request: 0,
+				index: 0,
+				payload: Ok::<_, ChainApiError>(7u32).record(&mut Responses::ignore()).encode(),
+			},
+			message(
+				1,
+				AllMessages::ApprovalVoting(ApprovalVotingMessage::ApprovalStatus(
+					hash,
+					oneshot::channel().0,
+				)),
+			),
+			message(
+				2,
+				AllMessages::CollatorProtocol(CollatorProtocolMessage::CollateOn(
+					Default::default(),
+				)),
+			),
+			RecordedEvent::signal(&OverseerSignal::Conclude),
+		]
+		.into_iter()
+		.map(|event| RecordEntry { timestamp_ms: 0, event });
+
+		let (mut ctx, mut handle) =
+			make_subsystem_context::<ApprovalVotingMessage, _>(TaskExecutor::new());
+
+		let subsystem = async move {
+			let mut statuses = Vec::new();
+			loop {
+				match ctx.recv().await.unwrap() {
+					FromOverseer::Communication {
+						msg: ApprovalVotingMessage::ApprovalStatus(hash, _),
+					} => {
+						// The first request was recorded, the second one is left to the mock.
+						let mut numbers = Vec::new();
+						for _ in 0..2 {
+							let (tx, rx) = oneshot::channel();
+							ctx.send_message(ChainApiMessage::FinalizedBlockNumber(tx)).await;
+							numbers.push(rx.await.unwrap().unwrap());
+						}
+						statuses.push((hash, numbers));
+					},
+					FromOverseer::Signal(OverseerSignal::Conclude) => return statuses,
+					_ => {},
+				}
+			}
+		};
+
+		let replay = replay_recording(&mut handle, recording, |msg| match msg {
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(tx)) => {
+				let _ = tx.send(Ok(1));
+			},
+			other => panic!("unexpected message {:?}", other),
+		});
+
+		let (statuses, outcome) = block_on(future::join(subsystem, replay));
+		assert_eq!(statuses, vec![(hash, vec![7, 1])]);
+		assert_eq!(outcome, ReplayOutcome { signals: 2, messages: 1, skipped: 2, answered: 1 });
+	}
 }
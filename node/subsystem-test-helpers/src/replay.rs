@@ -0,0 +1,151 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Replay of overseer recordings into a single subsystem under test.
+
+use std::collections::{HashMap, VecDeque};
+
+use futures::{prelude::*, select};
+use parity_scale_codec::Encode;
+
+use polkadot_node_subsystem::{
+	messages::AllMessages,
+	overseer::recording::{Field, RecordEntry, RecordedEvent, Replayable, Responses},
+	FromOverseer,
+};
+
+use crate::TestSubsystemContextHandle;
+
+/// Summary of a replay run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayOutcome {
+	/// Number of signals fed into the subsystem.
+	pub signals: usize,
+	/// Number of messages fed into the subsystem.
+	pub messages: usize,
+	/// Number of messages not addressed to the subsystem, or not replayable.
+	pub skipped: usize,
+	/// Number of requests of the subsystem answered from recorded responses.
+	pub answered: usize,
+}
+
+/// Recorded responses, by the encoding of the recorded request they were sent for.
+type Answers = HashMap<Vec<u8>, VecDeque<Vec<(u32, Vec<u8>)>>>;
+
+/// Feed a recording into the subsystem behind `handle`, in order.
+///
+/// All signals are replayed, messages only if they were recorded for `M`.
+///
+/// A message the subsystem sends while the recording is fed is answered with the responses
+/// recorded for an identical message, in the order such messages were recorded. All other
+/// messages are passed to `mock`, which stands in for all other subsystems and peers, e.g. by
+/// answering runtime API requests that were not part of the recording. Response channels of
+/// replayed messages are not connected to anything.
+///
+/// Returns early if the subsystem exits.
+pub async fn replay_recording<M, F>(
+	handle: &mut TestSubsystemContextHandle<M>,
+	recording: impl IntoIterator<Item = RecordEntry>,
+	mut mock: F,
+) -> ReplayOutcome
+where
+	M: Replayable,
+	F: FnMut(AllMessages),
+{
+	let mut outcome = ReplayOutcome::default();
+
+	let mut responses = HashMap::<u64, Vec<(u32, Vec<u8>)>>::new();
+	let mut events = Vec::new();
+	for entry in recording {
+		match entry.event {
+			RecordedEvent::Response { request, index, payload } =>
+				responses.entry(request).or_default().push((index, payload)),
+			event => events.push(event),
+		}
+	}
+
+	let mut answers = Answers::new();
+	let mut feed = Vec::new();
+	for event in events {
+		match event {
+			RecordedEvent::Signal(signal) => {
+				outcome.signals += 1;
+				feed.push(FromOverseer::Signal(signal.into_signal()));
+			},
+			RecordedEvent::Message { seq, message } => {
+				let key = message.encode();
+				match M::replay_from(message) {
+					Some(msg) => {
+						outcome.messages += 1;
+						feed.push(FromOverseer::Communication { msg });
+					},
+					None => {
+						outcome.skipped += 1;
+						if let Some(responses) = responses.remove(&seq) {
+							answers.entry(key).or_default().push_back(responses);
+						}
+					},
+				}
+			},
+			RecordedEvent::Response { .. } | RecordedEvent::Dropped(_) => {},
+		}
+	}
+
+	for from_overseer in feed {
+		// Serve the subsystem while it is busy with the previous item,
+		// it might be waiting on a response before reading the next one.
+		let mut send = handle.tx.send(from_overseer).fuse();
+		loop {
+			select! {
+				res = send => {
+					res.expect("Test subsystem no longer live");
+					break
+				},
+				msg = handle.rx.next() => match msg {
+					Some(msg) => if answer(msg, &mut answers, &mut mock) {
+						outcome.answered += 1;
+					},
+					// The subsystem exited, e.g. on `Conclude`.
+					None => return outcome,
+				},
+			}
+		}
+	}
+
+	outcome
+}
+
+/// Answer `msg` from `answers` if possible, pass it to `mock` otherwise.
+///
+/// Returns whether `msg` was answered from `answers`.
+fn answer(mut msg: AllMessages, answers: &mut Answers, mock: &mut impl FnMut(AllMessages)) -> bool {
+	let key = msg.record(&mut Responses::ignore()).encode();
+	let recorded = match answers.get_mut(&key).and_then(VecDeque::pop_front) {
+		Some(recorded) => recorded,
+		None => {
+			mock(msg);
+			return false
+		},
+	};
+
+	let mut responses = Responses::capture();
+	msg.record(&mut responses);
+	for (index, payload) in recorded {
+		responses.answer(index, &payload);
+	}
+
+	true
+}
@@ -17,6 +17,7 @@
 //! Error types for the subsystem requests.
 
 use crate::JaegerError;
+use parity_scale_codec::{Decode, Encode};
 
 /// A description of an error causing the runtime API request to be unservable.
 #[derive(thiserror::Error, Debug, Clone)]
@@ -67,7 +68,7 @@ impl core::fmt::Display for ChainApiError {
 impl std::error::Error for ChainApiError {}
 
 /// An error that may happen during Availability Recovery process.
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Encode, Decode)]
 pub enum RecoveryError {
 	/// A chunk is recovered but is invalid.
 	Invalid,
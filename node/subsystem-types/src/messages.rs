@@ -23,6 +23,7 @@
 //! Subsystems' APIs are defined separately from their implementation, leading to easier mocking.
 
 use futures::channel::oneshot;
+use parity_scale_codec::{Decode, Encode};
 use sc_network::Multiaddr;
 use thiserror::Error;
 
@@ -33,7 +34,13 @@ use polkadot_node_network_protocol::{
 	UnifiedReputationChange,
 };
 use polkadot_node_primitives::{
-	approval::{BlockApprovalMeta, IndirectAssignmentCert, IndirectSignedApprovalVote},
+	approval::{
+		BlockApprovalMeta, BlockApprovalStatus, CandidateBitfield, IndirectAssignmentCert,
+		IndirectSignedApprovalVote,
+	},
+	availability::AvailabilityAttestations,
+	backing::BackingTraceEntry,
+	gossip_support::{ConnectivityReport, GossipTopologyInfo},
 	AvailableData, BabeEpoch, BlockWeight, CandidateVotes, CollationGenerationConfig,
 	CollationSecondedSignal, DisputeMessage, ErasureChunk, PoV, SignedDisputeStatement,
 	SignedFullStatement, ValidationResult,
@@ -48,7 +55,7 @@ use polkadot_primitives::{
 		SignedAvailabilityBitfields, ValidationCode, ValidationCodeHash, ValidatorId,
 		ValidatorIndex, ValidatorSignature,
 	},
-	v2::{PvfCheckStatement, SessionInfo},
+	v2::{slashing, InherentWeightInfo, PvfCheckStatement, SessionInfo},
 };
 use polkadot_statement_table::v1::Misbehavior;
 use std::{
@@ -59,7 +66,7 @@ use std::{
 
 /// Network events as transmitted to other subsystems, wrapped in their message types.
 pub mod network_bridge_event;
-pub use network_bridge_event::NetworkBridgeEvent;
+pub use network_bridge_event::{GossipTopology, NetworkBridgeEvent};
 
 /// Subsystem messages where each message is always bound to a relay parent.
 pub trait BoundToRelayParent {
@@ -79,6 +86,12 @@ pub enum CandidateBackingMessage {
 	/// Note a validator's statement about a particular candidate. Disagreements about validity must be escalated
 	/// to a broader check by Misbehavior Arbitration. Agreements are simply tallied until a quorum is reached.
 	Statement(Hash, SignedFullStatement),
+	/// Get the recent lifecycle events of candidates at the given relay-parent, optionally only
+	/// those of the given candidate, oldest first.
+	///
+	/// Only the events of a bounded number of recently traced candidates are kept, but they are
+	/// kept after the relay-parent left the view.
+	GetCandidateTrace(Hash, Option<CandidateHash>, oneshot::Sender<Vec<BackingTraceEntry>>),
 }
 
 impl BoundToRelayParent for CandidateBackingMessage {
@@ -87,6 +100,7 @@ impl BoundToRelayParent for CandidateBackingMessage {
 			Self::GetBackedCandidates(hash, _, _) => *hash,
 			Self::Second(hash, _, _) => *hash,
 			Self::Statement(hash, _) => *hash,
+			Self::GetCandidateTrace(hash, _, _) => *hash,
 		}
 	}
 }
@@ -97,7 +111,7 @@ impl BoundToRelayParent for CandidateBackingMessage {
 pub struct ValidationFailed(pub String);
 
 /// The outcome of the candidate-validation's PVF pre-check request.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
 pub enum PreCheckOutcome {
 	/// The PVF has been compiled successfully within the given constraints.
 	Valid,
@@ -188,9 +202,9 @@ impl CandidateValidationMessage {
 #[derive(Debug, derive_more::From)]
 pub enum CollatorProtocolMessage {
 	/// Signal to the collator protocol that it should connect to validators with the expectation
-	/// of collating on the given para. This is only expected to be called once, early on, if at all,
-	/// and only by the Collation Generation subsystem. As such, it will overwrite the value of
-	/// the previous signal.
+	/// of collating on the given para. This is only expected to be called once per para, early on,
+	/// if at all, and only by the Collation Generation subsystem. Each signal adds the para to the
+	/// set of paras collated on.
 	///
 	/// This should be sent before any `DistributeCollation` message.
 	CollateOn(ParaId),
@@ -305,7 +319,7 @@ pub enum DisputeCoordinatorMessage {
 }
 
 /// The result of `DisputeCoordinatorMessage::ImportStatements`.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Encode, Decode)]
 pub enum ImportStatementsResult {
 	/// Import was invalid (candidate was not available)  and the sending peer should get banned.
 	InvalidImport,
@@ -327,6 +341,11 @@ pub enum NetworkBridgeMessage {
 	/// Report a peer for their actions.
 	ReportPeer(PeerId, UnifiedReputationChange),
 
+	/// Report the peer of an authority for their actions.
+	///
+	/// For use with request/response protocols, where only the authority of the peer is known.
+	ReportAuthority(AuthorityDiscoveryId, UnifiedReputationChange),
+
 	/// Disconnect a peer from the given peer-set without affecting their reputation.
 	DisconnectPeer(PeerId, PeerSet),
 
@@ -381,9 +400,11 @@ pub enum NetworkBridgeMessage {
 	/// Inform the distribution subsystems about the new
 	/// gossip network topology formed.
 	NewGossipTopology {
-		/// Ids of our neighbors in the new gossip topology.
+		/// Ids of our neighbors in our row of the new gossip topology.
 		/// We're not necessarily connected to all of them, but we should.
-		our_neighbors: HashSet<AuthorityDiscoveryId>,
+		row_neighbors: HashSet<AuthorityDiscoveryId>,
+		/// Ids of our neighbors in our column of the new gossip topology.
+		column_neighbors: HashSet<AuthorityDiscoveryId>,
 	},
 }
 
@@ -392,6 +413,7 @@ impl NetworkBridgeMessage {
 	pub fn relay_parent(&self) -> Option<Hash> {
 		match self {
 			Self::ReportPeer(_, _) => None,
+			Self::ReportAuthority(_, _) => None,
 			Self::DisconnectPeer(_, _) => None,
 			Self::SendValidationMessage(_, _) => None,
 			Self::SendCollationMessage(_, _) => None,
@@ -448,6 +470,11 @@ pub enum BitfieldDistributionMessage {
 	/// Event from the network bridge.
 	#[from]
 	NetworkBridgeUpdateV1(NetworkBridgeEvent<protocol_v1::BitfieldDistributionMessage>),
+
+	/// Get the availability attestations of the bitfields for the given relay parent, which
+	/// show the validators which did not attest the availability of pending candidates.
+	/// `None` if the relay parent is neither in our view nor among those which recently left it.
+	GetAvailabilityAttestations(Hash, oneshot::Sender<Option<AvailabilityAttestations>>),
 }
 
 impl BitfieldDistributionMessage {
@@ -456,6 +483,7 @@ impl BitfieldDistributionMessage {
 		match self {
 			Self::DistributeBitfield(hash, _) => Some(*hash),
 			Self::NetworkBridgeUpdateV1(_) => None,
+			Self::GetAvailabilityAttestations(hash, _) => Some(*hash),
 		}
 	}
 }
@@ -593,6 +621,14 @@ pub enum ChainSelectionMessage {
 	/// Request the best leaf containing the given block in its ancestry. Return `None` if
 	/// there is no such leaf.
 	BestLeafContaining(Hash, oneshot::Sender<Option<Hash>>),
+	/// Manually mark a block and all of its descendants as non-viable, regardless of
+	/// approvals. Responds with `false` if the block is unknown, e.g. because it is
+	/// already finalized.
+	Blacklist(Hash, oneshot::Sender<bool>),
+	/// Clear a mark set by `Blacklist`, restoring the viability of the block and its
+	/// descendants unless they are non-viable for other reasons. Responds with `false` if the
+	/// block is unknown.
+	ClearBlacklist(Hash, oneshot::Sender<bool>),
 }
 
 impl ChainSelectionMessage {
@@ -605,6 +641,8 @@ impl ChainSelectionMessage {
 			ChainSelectionMessage::Approved(_) => None,
 			ChainSelectionMessage::Leaves(_) => None,
 			ChainSelectionMessage::BestLeafContaining(..) => None,
+			ChainSelectionMessage::Blacklist(..) => None,
+			ChainSelectionMessage::ClearBlacklist(..) => None,
 		}
 	}
 }
@@ -683,6 +721,28 @@ pub enum RuntimeApiRequest {
 		OccupiedCoreAssumption,
 		RuntimeApiSender<Option<ValidationCodeHash>>,
 	),
+	/// Returns all validators that lost a dispute and still await a key ownership proof to be
+	/// slashed.
+	UnappliedSlashes(
+		RuntimeApiSender<Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>>,
+	),
+	/// Returns a merkle proof of a validator session key.
+	KeyOwnershipProof(ValidatorId, RuntimeApiSender<Option<slashing::OpaqueKeyOwnershipProof>>),
+	/// Submits an unsigned extrinsic to slash validators who lost a dispute.
+	SubmitReportDisputeLost(
+		slashing::DisputeProof,
+		slashing::OpaqueKeyOwnershipProof,
+		RuntimeApiSender<Option<()>>,
+	),
+	/// Get the weights the runtime charges for the components of the parachains inherent.
+	InherentWeightInfo(RuntimeApiSender<InherentWeightInfo>),
+	/// Submits an unsigned extrinsic to slash a validator who issued conflicting backing
+	/// statements.
+	SubmitReportBackingMisbehavior(
+		slashing::BackingMisbehaviorProof,
+		slashing::OpaqueKeyOwnershipProof,
+		RuntimeApiSender<Option<()>>,
+	),
 }
 
 /// A message to the Runtime API subsystem.
@@ -764,7 +824,9 @@ impl BoundToRelayParent for ProvisionerMessage {
 /// Message to the Collation Generation subsystem.
 #[derive(Debug)]
 pub enum CollationGenerationMessage {
-	/// Initialize the collation generation subsystem
+	/// Initialize the collation generation subsystem for the para of the given config.
+	///
+	/// May be sent once per para to collate on several paras from a single node.
 	Initialize(CollationGenerationConfig),
 }
 
@@ -776,7 +838,7 @@ impl CollationGenerationMessage {
 }
 
 /// The result type of [`ApprovalVotingMessage::CheckAndImportAssignment`] request.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 pub enum AssignmentCheckResult {
 	/// The vote was accepted and should be propagated onwards.
 	Accepted,
@@ -789,7 +851,7 @@ pub enum AssignmentCheckResult {
 }
 
 /// The error result type of [`ApprovalVotingMessage::CheckAndImportAssignment`] request.
-#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq, Encode, Decode)]
 #[allow(missing_docs)]
 pub enum AssignmentCheckError {
 	#[error("Unknown block: {0:?}")]
@@ -804,10 +866,12 @@ pub enum AssignmentCheckError {
 	InvalidCert(ValidatorIndex),
 	#[error("Internal state mismatch: {0:?}, {1:?}")]
 	Internal(Hash, CandidateHash),
+	#[error("The assignment claims no candidates")]
+	NullAssignment,
 }
 
 /// The result type of [`ApprovalVotingMessage::CheckAndImportApproval`] request.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 pub enum ApprovalCheckResult {
 	/// The vote was accepted and should be propagated onwards.
 	Accepted,
@@ -816,7 +880,7 @@ pub enum ApprovalCheckResult {
 }
 
 /// The error result type of [`ApprovalVotingMessage::CheckAndImportApproval`] request.
-#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq, Encode, Decode)]
 #[allow(missing_docs)]
 pub enum ApprovalCheckError {
 	#[error("Unknown block: {0:?}")]
@@ -839,7 +903,7 @@ pub enum ApprovalCheckError {
 
 /// Describes a relay-chain block by the para-chain candidates
 /// it includes.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Encode, Decode)]
 pub struct BlockDescription {
 	/// The relay-chain block hash.
 	pub block_hash: Hash,
@@ -850,7 +914,7 @@ pub struct BlockDescription {
 }
 
 /// Response type to `ApprovalVotingMessage::ApprovedAncestor`.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Encode, Decode)]
 pub struct HighestApprovedAncestorBlock {
 	/// The block hash of the highest viable ancestor.
 	pub hash: Hash,
@@ -868,9 +932,11 @@ pub struct HighestApprovedAncestorBlock {
 pub enum ApprovalVotingMessage {
 	/// Check if the assignment is valid and can be accepted by our view of the protocol.
 	/// Should not be sent unless the block hash is known.
+	///
+	/// The assignment is checked and imported for all claimed candidates at once.
 	CheckAndImportAssignment(
 		IndirectAssignmentCert,
-		CandidateIndex,
+		CandidateBitfield,
 		oneshot::Sender<AssignmentCheckResult>,
 	),
 	/// Check if the approval vote is valid and can be accepted by our view of the
@@ -886,6 +952,11 @@ pub enum ApprovalVotingMessage {
 	/// It can also return the same block hash, if that is acceptable to vote upon.
 	/// Return `None` if the input hash is unrecognized.
 	ApprovedAncestor(Hash, BlockNumber, oneshot::Sender<Option<HighestApprovedAncestorBlock>>),
+	/// Returns the approval progress of all candidates included by the given relay chain block,
+	/// broken down by tranche.
+	///
+	/// Returns `None` if the block is unknown to approval voting.
+	ApprovalStatus(Hash, oneshot::Sender<Option<BlockApprovalStatus>>),
 }
 
 /// Message to the Approval Distribution subsystem.
@@ -896,7 +967,8 @@ pub enum ApprovalDistributionMessage {
 	NewBlocks(Vec<BlockApprovalMeta>),
 	/// Distribute an assignment cert from the local validator. The cert is assumed
 	/// to be valid, relevant, and for the given relay-parent and validator index.
-	DistributeAssignment(IndirectAssignmentCert, CandidateIndex),
+	/// The bitfield holds all candidates claimed by the cert.
+	DistributeAssignment(IndirectAssignmentCert, CandidateBitfield),
 	/// Distribute an approval vote for the local validator. The approval vote is assumed to be
 	/// valid, relevant, and the corresponding approval already issued.
 	/// If not, the subsystem is free to drop the message.
@@ -912,6 +984,24 @@ pub enum GossipSupportMessage {
 	/// Dummy constructor, so we can receive networking events.
 	#[from]
 	NetworkBridgeUpdateV1(NetworkBridgeEvent<protocol_v1::GossipSuppportNetworkMessage>),
+	/// Get the gossip topology of the current session along with the connection status of our
+	/// neighbors. `None` if we are not an authority of the session.
+	GetTopology(oneshot::Sender<Option<GossipTopologyInfo>>),
+	/// A peer with the given authority ids connected to us for block sync.
+	///
+	/// An authority which stays connected like this without opening a notification stream on the
+	/// validation peer set is assumed not to support a version of the protocol we know.
+	PeerSyncConnected {
+		/// The peer which connected.
+		peer: PeerId,
+		/// The authority ids of the peer.
+		authority_ids: HashSet<AuthorityDiscoveryId>,
+	},
+	/// A peer previously reported with `PeerSyncConnected` disconnected.
+	PeerSyncDisconnected(PeerId),
+	/// Get a report of our connectivity to the other authorities of the current session. `None`
+	/// if we are not an authority of the session.
+	GetConnectivityReport(oneshot::Sender<Option<ConnectivityReport>>),
 }
 
 /// PVF checker message.
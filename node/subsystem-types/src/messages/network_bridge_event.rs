@@ -18,14 +18,43 @@ use std::{collections::HashSet, convert::TryFrom};
 
 pub use sc_network::{PeerId, ReputationChange};
 
-use polkadot_node_network_protocol::{ObservedRole, OurView, View, WrongVariant};
+use polkadot_node_network_protocol::{ObservedRole, OurView, ProtocolVersion, View, WrongVariant};
 use polkadot_primitives::v1::AuthorityDiscoveryId;
 
+/// Our neighbors in the gossip topology.
+///
+/// The validators of a session are arranged in a matrix and each validator is
+/// connected to all validators in its row and its column.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GossipTopology {
+	/// Neighbors in our row of the matrix.
+	pub row: HashSet<PeerId>,
+	/// Neighbors in our column of the matrix.
+	pub column: HashSet<PeerId>,
+}
+
+impl GossipTopology {
+	/// Whether `peer` is in our row or our column.
+	pub fn contains(&self, peer: &PeerId) -> bool {
+		self.row.contains(peer) || self.column.contains(peer)
+	}
+
+	/// All neighbors, in our row or our column.
+	pub fn peers(&self) -> HashSet<PeerId> {
+		self.row.union(&self.column).cloned().collect()
+	}
+
+	/// Whether we have no neighbors at all, e.g. because the topology is not known yet.
+	pub fn is_empty(&self) -> bool {
+		self.row.is_empty() && self.column.is_empty()
+	}
+}
+
 /// Events from network.
 #[derive(Debug, Clone, PartialEq)]
 pub enum NetworkBridgeEvent<M> {
-	/// A peer has connected.
-	PeerConnected(PeerId, ObservedRole, Option<HashSet<AuthorityDiscoveryId>>),
+	/// A peer has connected, speaking the given version of the peer set's protocol.
+	PeerConnected(PeerId, ObservedRole, ProtocolVersion, Option<HashSet<AuthorityDiscoveryId>>),
 
 	/// A peer has disconnected.
 	PeerDisconnected(PeerId),
@@ -37,7 +66,7 @@ pub enum NetworkBridgeEvent<M> {
 	///
 	/// Note, that the distribution subsystems need to handle the last
 	/// view update of the newly added gossip peers manually.
-	NewGossipTopology(HashSet<PeerId>),
+	NewGossipTopology(GossipTopology),
 
 	/// Peer has sent a message.
 	PeerMessage(PeerId, M),
@@ -73,12 +102,17 @@ impl<M> NetworkBridgeEvent<M> {
 		Ok(match *self {
 			NetworkBridgeEvent::PeerMessage(ref peer, ref msg) =>
 				NetworkBridgeEvent::PeerMessage(peer.clone(), <&'a T>::try_from(msg)?.clone()),
-			NetworkBridgeEvent::PeerConnected(ref peer, ref role, ref authority_id) =>
-				NetworkBridgeEvent::PeerConnected(peer.clone(), role.clone(), authority_id.clone()),
+			NetworkBridgeEvent::PeerConnected(ref peer, ref role, version, ref authority_id) =>
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					role.clone(),
+					version,
+					authority_id.clone(),
+				),
 			NetworkBridgeEvent::PeerDisconnected(ref peer) =>
 				NetworkBridgeEvent::PeerDisconnected(peer.clone()),
-			NetworkBridgeEvent::NewGossipTopology(ref peers) =>
-				NetworkBridgeEvent::NewGossipTopology(peers.clone()),
+			NetworkBridgeEvent::NewGossipTopology(ref topology) =>
+				NetworkBridgeEvent::NewGossipTopology(topology.clone()),
 			NetworkBridgeEvent::PeerViewChange(ref peer, ref view) =>
 				NetworkBridgeEvent::PeerViewChange(peer.clone(), view.clone()),
 			NetworkBridgeEvent::OurViewChange(ref view) =>
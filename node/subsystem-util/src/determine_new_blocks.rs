@@ -31,7 +31,7 @@ use polkadot_primitives::v1::{BlockNumber, Hash, Header};
 /// then the returned list will be empty.
 ///
 /// This may be somewhat expensive when first recovering from major sync.
-pub async fn determine_new_blocks<E, Sender>(
+pub async fn determine_new_blocks<E, Sender, Outgoing>(
 	sender: &mut Sender,
 	is_known: impl Fn(&Hash) -> Result<bool, E>,
 	head: Hash,
@@ -39,7 +39,8 @@ pub async fn determine_new_blocks<E, Sender>(
 	lower_bound_number: BlockNumber,
 ) -> Result<Vec<(Hash, Header)>, E>
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<ChainApiMessage>,
 {
 	const ANCESTRY_STEP: usize = 4;
 
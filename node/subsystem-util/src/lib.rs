@@ -29,7 +29,8 @@ use polkadot_node_subsystem::{
 	messages::{
 		AllMessages, BoundToRelayParent, RuntimeApiMessage, RuntimeApiRequest, RuntimeApiSender,
 	},
-	overseer, ActivatedLeaf, ActiveLeavesUpdate, FromOverseer, OverseerSignal, SpawnedSubsystem,
+	overseer::{self, OutgoingMessagesOf},
+	ActivatedLeaf, ActiveLeavesUpdate, FromOverseer, OverseerSignal, SpawnedSubsystem,
 	SubsystemContext, SubsystemSender,
 };
 
@@ -56,7 +57,7 @@ use polkadot_primitives::{
 		PersistedValidationData, SessionIndex, Signed, SigningContext, ValidationCode,
 		ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 	},
-	v2::SessionInfo,
+	v2::{InherentWeightInfo, SessionInfo},
 };
 use sp_application_crypto::AppKey;
 use sp_core::{traits::SpawnNamed, ByteArray};
@@ -136,14 +137,15 @@ impl From<OverseerError> for Error {
 pub type RuntimeApiReceiver<T> = oneshot::Receiver<Result<T, RuntimeApiError>>;
 
 /// Request some data from the `RuntimeApi`.
-pub async fn request_from_runtime<RequestBuilder, Response, Sender>(
+pub async fn request_from_runtime<RequestBuilder, Response, Sender, Outgoing>(
 	parent: Hash,
 	sender: &mut Sender,
 	request_builder: RequestBuilder,
 ) -> RuntimeApiReceiver<Response>
 where
 	RequestBuilder: FnOnce(RuntimeApiSender<Response>) -> RuntimeApiRequest,
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<RuntimeApiMessage>,
 {
 	let (tx, rx) = oneshot::channel();
 
@@ -170,12 +172,12 @@ macro_rules! specialize_requests {
 		#[doc = "Request `"]
 		#[doc = $doc_name]
 		#[doc = "` from the runtime"]
-		pub async fn $func_name (
+		pub async fn $func_name<Outgoing: From<RuntimeApiMessage>> (
 			parent: Hash,
 			$(
 				$param_name: $param_ty,
 			)*
-			sender: &mut impl SubsystemSender,
+			sender: &mut impl SubsystemSender<Outgoing>,
 		) -> RuntimeApiReceiver<$return_ty>
 		{
 			request_from_runtime(parent, sender, |tx| RuntimeApiRequest::$request_variant(
@@ -217,6 +219,7 @@ specialize_requests! {
 	fn request_session_info(index: SessionIndex) -> Option<SessionInfo>; SessionInfo;
 	fn request_validation_code_hash(para_id: ParaId, assumption: OccupiedCoreAssumption)
 		-> Option<ValidationCodeHash>; ValidationCodeHash;
+	fn request_inherent_weight_info() -> InherentWeightInfo; InherentWeightInfo;
 }
 
 /// From the given set of validators, find the first key we can sign with, if any.
@@ -321,10 +324,10 @@ pub struct Validator {
 
 impl Validator {
 	/// Get a struct representing this node's validator if this node is in fact a validator in the context of the given block.
-	pub async fn new(
+	pub async fn new<Outgoing: From<RuntimeApiMessage>>(
 		parent: Hash,
 		keystore: SyncCryptoStorePtr,
-		sender: &mut impl SubsystemSender,
+		sender: &mut impl SubsystemSender<Outgoing>,
 	) -> Result<Self, Error> {
 		// Note: request_validators and request_session_index_for_child do not and cannot
 		// run concurrently: they both have a mutable handle to the same sender.
@@ -410,38 +413,43 @@ pub enum FromJobCommand {
 }
 
 /// A sender for messages from jobs, as well as commands to the overseer.
-pub struct JobSender<S: SubsystemSender> {
+pub struct JobSender<S> {
 	sender: S,
 	from_job: mpsc::Sender<FromJobCommand>,
 }
 
 // A custom clone impl, since M does not need to impl `Clone`
 // which `#[derive(Clone)]` requires.
-impl<S: SubsystemSender> Clone for JobSender<S> {
+impl<S: Clone> Clone for JobSender<S> {
 	fn clone(&self) -> Self {
 		Self { sender: self.sender.clone(), from_job: self.from_job.clone() }
 	}
 }
 
-impl<S: SubsystemSender> JobSender<S> {
+impl<S> JobSender<S> {
 	/// Get access to the underlying subsystem sender.
 	pub fn subsystem_sender(&mut self) -> &mut S {
 		&mut self.sender
 	}
 
 	/// Send a direct message to some other `Subsystem`, routed based on message type.
-	pub async fn send_message(&mut self, msg: impl Into<AllMessages>) {
-		self.sender.send_message(msg.into()).await
+	pub async fn send_message<M, Outgoing>(&mut self, msg: M)
+	where
+		S: SubsystemSender<Outgoing>,
+		Outgoing: From<M>,
+	{
+		self.sender.send_message(Outgoing::from(msg)).await
 	}
 
 	/// Send multiple direct messages to other `Subsystem`s, routed based on message type.
-	pub async fn send_messages<T, M>(&mut self, msgs: T)
+	pub async fn send_messages<T, M, Outgoing>(&mut self, msgs: T)
 	where
 		T: IntoIterator<Item = M> + Send,
 		T::IntoIter: Send,
-		M: Into<AllMessages>,
+		S: SubsystemSender<Outgoing>,
+		Outgoing: From<M>,
 	{
-		self.sender.send_messages(msgs.into_iter().map(|m| m.into())).await
+		self.sender.send_messages(msgs.into_iter().map(Outgoing::from)).await
 	}
 
 	/// Send a message onto the unbounded queue of some other `Subsystem`, routed based on message
@@ -449,8 +457,12 @@ impl<S: SubsystemSender> JobSender<S> {
 	///
 	/// This function should be used only when there is some other bounding factor on the messages
 	/// sent with it. Otherwise, it risks a memory leak.
-	pub fn send_unbounded_message(&mut self, msg: impl Into<AllMessages>) {
-		self.sender.send_unbounded_message(msg.into())
+	pub fn send_unbounded_message<M, Outgoing>(&mut self, msg: M)
+	where
+		S: SubsystemSender<Outgoing>,
+		Outgoing: From<M>,
+	{
+		self.sender.send_unbounded_message(Outgoing::from(msg))
 	}
 
 	/// Send a command to the subsystem, to be relayed onwards to the overseer.
@@ -460,25 +472,25 @@ impl<S: SubsystemSender> JobSender<S> {
 }
 
 #[async_trait::async_trait]
-impl<S, M> overseer::SubsystemSender<M> for JobSender<S>
+impl<S, Outgoing> SubsystemSender<Outgoing> for JobSender<S>
 where
-	M: Send + 'static + Into<AllMessages>,
-	S: SubsystemSender + Clone,
+	Outgoing: Send + 'static,
+	S: SubsystemSender<Outgoing>,
 {
-	async fn send_message(&mut self, msg: M) {
-		self.sender.send_message(msg.into()).await
+	async fn send_message(&mut self, msg: Outgoing) {
+		self.sender.send_message(msg).await
 	}
 
 	async fn send_messages<T>(&mut self, msgs: T)
 	where
-		T: IntoIterator<Item = M> + Send,
+		T: IntoIterator<Item = Outgoing> + Send,
 		T::IntoIter: Send,
 	{
-		self.sender.send_messages(msgs.into_iter().map(|m| m.into())).await
+		self.sender.send_messages(msgs).await
 	}
 
-	fn send_unbounded_message(&mut self, msg: M) {
-		self.sender.send_unbounded_message(msg.into())
+	fn send_unbounded_message(&mut self, msg: Outgoing) {
+		self.sender.send_unbounded_message(msg)
 	}
 }
 
@@ -497,7 +509,7 @@ impl fmt::Debug for FromJobCommand {
 /// Other messages are passed along to and from the job via the overseer to other subsystems.
 pub trait JobTrait: Unpin + Sized {
 	/// Message type used to send messages to the job.
-	type ToJob: 'static + BoundToRelayParent + Send;
+	type ToJob: 'static + BoundToRelayParent + overseer::AssociateOutgoing<AllMessages> + Send;
 	/// Job runtime error.
 	type Error: 'static + std::error::Error + Send;
 	/// Extra arguments this job needs to run properly.
@@ -514,10 +526,16 @@ pub trait JobTrait: Unpin + Sized {
 	/// Name of the job, i.e. `candidate-backing-job`
 	const NAME: &'static str;
 
+	/// Handle a message for a relay parent no job is running for.
+	///
+	/// Such messages are dropped by default.
+	fn handle_orphaned(_run_args: &Self::RunArgs, _msg: Self::ToJob) {}
+
 	/// Run a job for the given relay `parent`.
 	///
-	/// The job should be ended when `receiver` returns `None`.
-	fn run<S: SubsystemSender>(
+	/// The job should be ended when `receiver` returns `None`. The `sender` only accepts
+	/// the messages the subsystem declared to send.
+	fn run<S: SubsystemSender<OutgoingMessagesOf<Self::ToJob>>>(
 		leaf: ActivatedLeaf,
 		run_args: Self::RunArgs,
 		metrics: Self::Metrics,
@@ -572,7 +590,7 @@ where
 		sender: Sender,
 	) where
 		Job: JobTrait<ToJob = ToJob>,
-		Sender: SubsystemSender,
+		Sender: SubsystemSender<OutgoingMessagesOf<ToJob>>,
 	{
 		let hash = leaf.hash;
 		let (to_job_tx, to_job_rx) = mpsc::channel(JOB_CHANNEL_CAPACITY);
@@ -619,11 +637,17 @@ where
 	}
 
 	/// Send a message to the appropriate job for this `parent_hash`.
-	async fn send_msg(&mut self, parent_hash: Hash, msg: ToJob) {
-		if let Entry::Occupied(mut job) = self.running.entry(parent_hash) {
-			if job.get_mut().send_msg(msg).await.is_err() {
-				job.remove();
-			}
+	///
+	/// Returns the message if no job is running for this `parent_hash`.
+	async fn send_msg(&mut self, parent_hash: Hash, msg: ToJob) -> Option<ToJob> {
+		match self.running.entry(parent_hash) {
+			Entry::Occupied(mut job) => {
+				if job.get_mut().send_msg(msg).await.is_err() {
+					job.remove();
+				}
+				None
+			},
+			Entry::Vacant(_) => Some(msg),
 		}
 	}
 }
@@ -690,7 +714,7 @@ impl<Job: JobTrait, Spawner> JobSubsystem<Job, Spawner> {
 	where
 		Spawner: SpawnNamed + Send + Clone + Unpin + 'static,
 		Context: SubsystemContext<Message = <Job as JobTrait>::ToJob, Signal = OverseerSignal>,
-		<Context as SubsystemContext>::Sender: SubsystemSender,
+		Context: overseer::SubsystemContext<Message = <Job as JobTrait>::ToJob>,
 		Job: 'static + JobTrait + Send,
 		<Job as JobTrait>::RunArgs: Clone + Sync,
 		<Job as JobTrait>::ToJob:
@@ -730,7 +754,10 @@ impl<Job: JobTrait, Spawner> JobSubsystem<Job, Spawner> {
 						Ok(FromOverseer::Signal(OverseerSignal::BlockFinalized(..))) => {}
 						Ok(FromOverseer::Communication { msg }) => {
 							if let Ok(to_job) = <<Context as SubsystemContext>::Message>::try_from(msg) {
-								jobs.send_msg(to_job.relay_parent(), to_job).await;
+								let relay_parent = to_job.relay_parent();
+								if let Some(to_job) = jobs.send_msg(relay_parent, to_job).await {
+									Job::handle_orphaned(&run_args, to_job);
+								}
 							}
 						}
 						Err(err) => {
@@ -766,6 +793,7 @@ impl<Context, Job, Spawner> Subsystem<Context, SubsystemError> for JobSubsystem<
 where
 	Spawner: SpawnNamed + Send + Clone + Unpin + 'static,
 	Context: SubsystemContext<Message = Job::ToJob, Signal = OverseerSignal>,
+	Context: overseer::SubsystemContext<Message = Job::ToJob>,
 	Job: 'static + JobTrait + Send,
 	Job::RunArgs: Clone + Sync,
 	<Job as JobTrait>::ToJob:
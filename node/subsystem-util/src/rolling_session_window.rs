@@ -29,7 +29,8 @@ use futures::channel::oneshot;
 use polkadot_node_subsystem::{
 	errors::RuntimeApiError,
 	messages::{RuntimeApiMessage, RuntimeApiRequest},
-	overseer, SubsystemContext,
+	overseer::{self, OutgoingMessagesOf},
+	SubsystemContext,
 };
 use thiserror::Error;
 
@@ -97,11 +98,16 @@ pub struct RollingSessionWindow {
 
 impl RollingSessionWindow {
 	/// Initialize a new session info cache with the given window size.
-	pub async fn new(
-		ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	pub async fn new<Context>(
+		ctx: &mut Context,
 		window_size: SessionWindowSize,
 		block_hash: Hash,
-	) -> Result<Self, SessionsUnavailable> {
+	) -> Result<Self, SessionsUnavailable>
+	where
+		Context: SubsystemContext,
+		OutgoingMessagesOf<<Context as overseer::SubsystemContext>::Message>:
+			From<RuntimeApiMessage>,
+	{
 		let session_index = get_session_index_for_head(ctx, block_hash).await?;
 
 		let window_start = session_index.saturating_sub(window_size.get() - 1);
@@ -155,11 +161,16 @@ impl RollingSessionWindow {
 	/// not change often and import notifications are expected to be typically increasing in session number.
 	///
 	/// some backwards drift in session index is acceptable.
-	pub async fn cache_session_info_for_head(
+	pub async fn cache_session_info_for_head<Context>(
 		&mut self,
-		ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+		ctx: &mut Context,
 		block_hash: Hash,
-	) -> Result<SessionWindowUpdate, SessionsUnavailable> {
+	) -> Result<SessionWindowUpdate, SessionsUnavailable>
+	where
+		Context: SubsystemContext,
+		OutgoingMessagesOf<<Context as overseer::SubsystemContext>::Message>:
+			From<RuntimeApiMessage>,
+	{
 		let session_index = get_session_index_for_head(ctx, block_hash).await?;
 
 		let old_window_start = self.earliest_session;
@@ -212,10 +223,14 @@ impl RollingSessionWindow {
 	}
 }
 
-async fn get_session_index_for_head(
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+async fn get_session_index_for_head<Context>(
+	ctx: &mut Context,
 	block_hash: Hash,
-) -> Result<SessionIndex, SessionsUnavailable> {
+) -> Result<SessionIndex, SessionsUnavailable>
+where
+	Context: SubsystemContext,
+	OutgoingMessagesOf<<Context as overseer::SubsystemContext>::Message>: From<RuntimeApiMessage>,
+{
 	let (s_tx, s_rx) = oneshot::channel();
 
 	// We're requesting session index of a child to populate the cache in advance.
@@ -240,12 +255,16 @@ async fn get_session_index_for_head(
 	}
 }
 
-async fn load_all_sessions(
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+async fn load_all_sessions<Context>(
+	ctx: &mut Context,
 	block_hash: Hash,
 	start: SessionIndex,
 	end_inclusive: SessionIndex,
-) -> Result<Vec<SessionInfo>, SessionsUnavailableKind> {
+) -> Result<Vec<SessionInfo>, SessionsUnavailableKind>
+where
+	Context: SubsystemContext,
+	OutgoingMessagesOf<<Context as overseer::SubsystemContext>::Message>: From<RuntimeApiMessage>,
+{
 	let mut v = Vec::new();
 	for i in start..=end_inclusive {
 		let (tx, rx) = oneshot::channel();
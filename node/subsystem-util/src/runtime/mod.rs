@@ -25,7 +25,11 @@ use sp_application_crypto::AppKey;
 use sp_core::crypto::ByteArray;
 use sp_keystore::{CryptoStore, SyncCryptoStorePtr};
 
-use polkadot_node_subsystem::{SubsystemContext, SubsystemSender};
+use polkadot_node_subsystem::{
+	messages::RuntimeApiMessage,
+	overseer::{self, OutgoingMessagesOf},
+	SubsystemContext, SubsystemSender,
+};
 use polkadot_primitives::{
 	v1::{
 		CandidateEvent, CoreState, EncodeAs, GroupIndex, GroupRotationInfo, Hash, OccupiedCore,
@@ -118,13 +122,14 @@ impl RuntimeInfo {
 	}
 
 	/// Retrieve the current session index.
-	pub async fn get_session_index<Sender>(
+	pub async fn get_session_index<Sender, Outgoing>(
 		&mut self,
 		sender: &mut Sender,
 		parent: Hash,
 	) -> Result<SessionIndex>
 	where
-		Sender: SubsystemSender,
+		Sender: SubsystemSender<Outgoing>,
+		Outgoing: From<RuntimeApiMessage>,
 	{
 		match self.session_index_cache.get(&parent) {
 			Some(index) => Ok(*index),
@@ -138,13 +143,14 @@ impl RuntimeInfo {
 	}
 
 	/// Get `ExtendedSessionInfo` by relay parent hash.
-	pub async fn get_session_info<'a, Sender>(
+	pub async fn get_session_info<'a, Sender, Outgoing>(
 		&'a mut self,
 		sender: &mut Sender,
 		parent: Hash,
 	) -> Result<&'a ExtendedSessionInfo>
 	where
-		Sender: SubsystemSender,
+		Sender: SubsystemSender<Outgoing>,
+		Outgoing: From<RuntimeApiMessage>,
 	{
 		let session_index = self.get_session_index(sender, parent).await?;
 
@@ -155,14 +161,15 @@ impl RuntimeInfo {
 	///
 	/// `request_session_info` still requires the parent to be passed in, so we take the parent
 	/// in addition to the `SessionIndex`.
-	pub async fn get_session_info_by_index<'a, Sender>(
+	pub async fn get_session_info_by_index<'a, Sender, Outgoing>(
 		&'a mut self,
 		sender: &mut Sender,
 		parent: Hash,
 		session_index: SessionIndex,
 	) -> Result<&'a ExtendedSessionInfo>
 	where
-		Sender: SubsystemSender,
+		Sender: SubsystemSender<Outgoing>,
+		Outgoing: From<RuntimeApiMessage>,
 	{
 		if !self.session_info_cache.contains(&session_index) {
 			let session_info =
@@ -182,7 +189,7 @@ impl RuntimeInfo {
 	}
 
 	/// Convenience function for checking the signature of something signed.
-	pub async fn check_signature<Sender, Payload, RealPayload>(
+	pub async fn check_signature<Sender, Outgoing, Payload, RealPayload>(
 		&mut self,
 		sender: &mut Sender,
 		parent: Hash,
@@ -191,7 +198,8 @@ impl RuntimeInfo {
 		std::result::Result<Signed<Payload, RealPayload>, UncheckedSigned<Payload, RealPayload>>,
 	>
 	where
-		Sender: SubsystemSender,
+		Sender: SubsystemSender<Outgoing>,
+		Outgoing: From<RuntimeApiMessage>,
 		Payload: EncodeAs<RealPayload> + Clone,
 		RealPayload: Encode + Clone,
 	{
@@ -264,6 +272,7 @@ pub async fn get_availability_cores<Context>(
 ) -> Result<Vec<CoreState>>
 where
 	Context: SubsystemContext,
+	OutgoingMessagesOf<<Context as overseer::SubsystemContext>::Message>: From<RuntimeApiMessage>,
 {
 	recv_runtime(request_availability_cores(relay_parent, ctx.sender()).await).await
 }
@@ -275,6 +284,7 @@ pub async fn get_occupied_cores<Context>(
 ) -> Result<Vec<OccupiedCore>>
 where
 	Context: SubsystemContext,
+	OutgoingMessagesOf<<Context as overseer::SubsystemContext>::Message>: From<RuntimeApiMessage>,
 {
 	let cores = get_availability_cores(ctx, relay_parent).await?;
 
@@ -297,6 +307,7 @@ pub async fn get_group_rotation_info<Context>(
 ) -> Result<GroupRotationInfo>
 where
 	Context: SubsystemContext,
+	OutgoingMessagesOf<<Context as overseer::SubsystemContext>::Message>: From<RuntimeApiMessage>,
 {
 	// We drop `groups` here as we don't need them, because of `RuntimeInfo`. Ideally we would not
 	// fetch them in the first place.
@@ -306,24 +317,26 @@ where
 }
 
 /// Get `CandidateEvent`s for the given `relay_parent`.
-pub async fn get_candidate_events<Sender>(
+pub async fn get_candidate_events<Sender, Outgoing>(
 	sender: &mut Sender,
 	relay_parent: Hash,
 ) -> Result<Vec<CandidateEvent>>
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<RuntimeApiMessage>,
 {
 	recv_runtime(request_candidate_events(relay_parent, sender).await).await
 }
 
 /// Fetch `ValidationCode` by hash from the runtime.
-pub async fn get_validation_code_by_hash<Sender>(
+pub async fn get_validation_code_by_hash<Sender, Outgoing>(
 	sender: &mut Sender,
 	relay_parent: Hash,
 	validation_code_hash: ValidationCodeHash,
 ) -> Result<Option<ValidationCode>>
 where
-	Sender: SubsystemSender,
+	Sender: SubsystemSender<Outgoing>,
+	Outgoing: From<RuntimeApiMessage>,
 {
 	recv_runtime(request_validation_code_by_hash(relay_parent, validation_code_hash, sender).await)
 		.await
@@ -25,7 +25,7 @@ pub use jaeger::*;
 pub use polkadot_node_jaeger as jaeger;
 
 pub use polkadot_overseer::{
-	self as overseer, ActiveLeavesUpdate, OverseerConnector, OverseerSignal,
+	self as overseer, ActiveLeavesUpdate, OverseerConnector, OverseerSignal, SubsystemSender,
 };
 
 pub use polkadot_node_subsystem_types::{
@@ -54,12 +54,6 @@ pub type FromOverseer<M> = polkadot_overseer::gen::FromOverseer<M, OverseerSigna
 pub type SubsystemInstance<Message> =
 	polkadot_overseer::gen::SubsystemInstance<Message, OverseerSignal>;
 
-/// Sender trait for the `AllMessages` wrapper.
-pub trait SubsystemSender: polkadot_overseer::gen::SubsystemSender<messages::AllMessages> {}
-
-impl<T> SubsystemSender for T where T: polkadot_overseer::gen::SubsystemSender<messages::AllMessages>
-{}
-
 /// Spawned subsystem.
 pub type SpawnedSubsystem = polkadot_overseer::gen::SpawnedSubsystem<SubsystemError>;
 
@@ -73,8 +67,6 @@ pub trait SubsystemContext:
 {
 	/// The message type the subsystem consumes.
 	type Message: std::fmt::Debug + Send + 'static;
-	/// Sender type to communicate with other subsystems.
-	type Sender: SubsystemSender + Send + Clone + 'static;
 }
 
 impl<T> SubsystemContext for T
@@ -86,5 +78,4 @@ where
 	>,
 {
 	type Message = <Self as polkadot_overseer::gen::SubsystemContext>::Message;
-	type Sender = <Self as polkadot_overseer::gen::SubsystemContext>::Sender;
 }
@@ -92,6 +92,8 @@ pub fn new_full(
 		is_collator,
 		None,
 		true,
+		Default::default(),
+		false,
 		None,
 		None,
 		worker_program_path,
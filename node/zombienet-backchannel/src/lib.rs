@@ -24,7 +24,7 @@ use lazy_static::lazy_static;
 use parity_scale_codec as codec;
 use serde::{Deserialize, Serialize};
 use std::{env, sync::Mutex};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, Mutex as AsyncMutex};
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 
 mod errors;
@@ -32,6 +32,8 @@ use errors::BackchannelError;
 
 lazy_static! {
 	pub static ref ZOMBIENET_BACKCHANNEL: Mutex<Option<ZombienetBackchannel>> = Mutex::new(None);
+	/// Held during initialization, such that only one connection is ever established.
+	static ref INIT_LOCK: AsyncMutex<()> = AsyncMutex::new(());
 }
 
 #[derive(Debug)]
@@ -46,6 +48,18 @@ pub struct BackchannelItem {
 	value: String,
 }
 
+impl BackchannelItem {
+	/// The key the item was sent with.
+	pub fn key(&self) -> &str {
+		&self.key
+	}
+
+	/// The value of the item.
+	pub fn value(&self) -> &str {
+		&self.value
+	}
+}
+
 pub struct Broadcaster;
 
 pub const ZOMBIENET: &str = "🧟ZOMBIENET🧟";
@@ -87,8 +101,11 @@ impl Broadcaster {
 
 impl ZombienetBackchannel {
 	pub async fn init() -> Result<(), BackchannelError> {
-		let mut zombienet_bkc = ZOMBIENET_BACKCHANNEL.lock().unwrap();
-		if zombienet_bkc.is_none() {
+		// Concurrent calls wait here, rather than on `ZOMBIENET_BACKCHANNEL`, which must not be
+		// held while connecting for the returned future to be `Send`.
+		let _init = INIT_LOCK.lock().await;
+		let is_initialized = ZOMBIENET_BACKCHANNEL.lock().unwrap().is_some();
+		if !is_initialized {
 			let backchannel_host =
 				env::var("BACKCHANNEL_HOST").unwrap_or_else(|_| "backchannel".to_string());
 			let backchannel_port =
@@ -140,7 +157,8 @@ impl ZombienetBackchannel {
 				}
 			});
 
-			*zombienet_bkc = Some(ZombienetBackchannel { broadcast_tx: tx, ws_tx: tx_relay });
+			*ZOMBIENET_BACKCHANNEL.lock().unwrap() =
+				Some(ZombienetBackchannel { broadcast_tx: tx, ws_tx: tx_relay });
 			return Ok(())
 		}
 
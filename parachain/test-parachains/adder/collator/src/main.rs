@@ -66,6 +66,8 @@ fn main() -> Result<()> {
 							polkadot_service::IsCollator::Yes(collator.collator_key()),
 							None,
 							true,
+							Default::default(),
+							false,
 							None,
 							None,
 							polkadot_service::RealOverseerGen,
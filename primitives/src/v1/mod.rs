@@ -1366,6 +1366,14 @@ pub fn supermajority_threshold(n: usize) -> usize {
 	n - byzantine_threshold(n)
 }
 
+/// The number of validators which need to attest the availability of a candidate for it to be
+/// included, i.e. two thirds of `n_validators` rounded up.
+pub const fn availability_threshold(n_validators: usize) -> usize {
+	let mut threshold = (n_validators * 2) / 3;
+	threshold += (n_validators * 2) % 3;
+	threshold
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -46,6 +46,18 @@ impl<Payload, RealPayload> Signed<Payload, RealPayload> {
 	pub fn into_unchecked(self) -> UncheckedSigned<Payload, RealPayload> {
 		self.0
 	}
+
+	/// Create a `Signed` from an `UncheckedSigned` whose signature is known to be valid,
+	/// without checking it again.
+	///
+	/// Attention: Only to be used for data that has been checked before, e.g. when reading back
+	/// data which was recorded as `Signed`.
+	#[cfg(feature = "std")]
+	pub fn new_unchecked_from_trusted_source(
+		unchecked: UncheckedSigned<Payload, RealPayload>,
+	) -> Self {
+		Self(unchecked)
+	}
 }
 
 /// Unchecked signed data, can be converted to `Signed` by checking the signature.
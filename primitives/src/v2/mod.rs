@@ -26,6 +26,8 @@ use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 #[cfg(feature = "std")]
 use parity_util_mem::MallocSizeOf;
 
+pub mod slashing;
+
 /// Information about validator sets of a session.
 #[derive(Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(PartialEq, MallocSizeOf))]
@@ -133,9 +135,51 @@ impl PvfCheckStatement {
 	}
 }
 
+/// The weights the runtime charges for the components of the parachains inherent.
+///
+/// The weights of backed candidates and dispute statement sets are linear in the number of votes
+/// they carry, so they are given as a base weight plus a weight per vote.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct InherentWeightInfo {
+	/// The maximum weight of a block, which the inherent must not exceed.
+	pub max_block_weight: u64,
+	/// The weight of a single signed availability bitfield.
+	pub bitfield: u64,
+	/// The weight of a backed candidate without any validity votes.
+	pub backed_candidate_base: u64,
+	/// The weight of each validity vote on a backed candidate.
+	pub backed_candidate_per_vote: u64,
+	/// The weight of a backed candidate upgrading the validation code of its para.
+	pub backed_candidate_code_upgrade: u64,
+	/// The weight of a dispute statement set without any statements.
+	pub dispute_statement_set_base: u64,
+	/// The weight of each statement in a dispute statement set.
+	pub dispute_statement: u64,
+}
+
+impl InherentWeightInfo {
+	/// The weight of a backed candidate with the given number of validity votes.
+	pub fn backed_candidate_weight(&self, validity_votes: usize, code_upgrade: bool) -> u64 {
+		if code_upgrade {
+			self.backed_candidate_code_upgrade
+		} else {
+			self.backed_candidate_per_vote
+				.saturating_mul(validity_votes as u64)
+				.saturating_add(self.backed_candidate_base)
+		}
+	}
+
+	/// The weight of a dispute statement set with the given number of statements.
+	pub fn dispute_statement_set_weight(&self, statements: usize) -> u64 {
+		self.dispute_statement
+			.saturating_mul(statements as u64)
+			.saturating_add(self.dispute_statement_set_base)
+	}
+}
+
 sp_api::decl_runtime_apis! {
 	/// The API for querying the state of parachains on-chain.
-	#[api_version(2)]
+	#[api_version(5)]
 	pub trait ParachainHost<H: Encode + Decode = v1::Hash, N: Encode + Decode = v1::BlockNumber> {
 		/// Get the current validators.
 		fn validators() -> Vec<v1::ValidatorId>;
@@ -228,5 +272,46 @@ sp_api::decl_runtime_apis! {
 		/// NOTE: This function is only available since parachain host version 2.
 		fn validation_code_hash(para_id: v1::Id, assumption: v1::OccupiedCoreAssumption)
 			-> Option<v1::ValidationCodeHash>;
+
+		/***** Added in v3 *****/
+
+		/// Returns a list of validators that lost a past session dispute and need to be slashed.
+		///
+		/// NOTE: This function is only available since parachain host version 3.
+		fn unapplied_slashes() -> Vec<(v1::SessionIndex, v1::CandidateHash, slashing::PendingSlashes)>;
+
+		/// Returns a merkle proof of a validator session key.
+		///
+		/// NOTE: This function is only available since parachain host version 3.
+		fn key_ownership_proof(
+			validator_id: v1::ValidatorId,
+		) -> Option<slashing::OpaqueKeyOwnershipProof>;
+
+		/// Submit an unsigned extrinsic to slash validators who lost a dispute about
+		/// a candidate of a past session.
+		///
+		/// NOTE: This function is only available since parachain host version 3.
+		fn submit_report_dispute_lost(
+			dispute_proof: slashing::DisputeProof,
+			key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+		) -> Option<()>;
+
+		/***** Added in v4 *****/
+
+		/// Returns the weights the runtime charges for the components of the parachains inherent.
+		///
+		/// NOTE: This function is only available since parachain host version 4.
+		fn inherent_weight_info() -> InherentWeightInfo;
+
+		/***** Added in v5 *****/
+
+		/// Submit an unsigned extrinsic to slash a validator who issued conflicting backing
+		/// statements.
+		///
+		/// NOTE: This function is only available since parachain host version 5.
+		fn submit_report_backing_misbehavior(
+			misbehavior_proof: slashing::BackingMisbehaviorProof,
+			key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+		) -> Option<()>;
 	}
 }
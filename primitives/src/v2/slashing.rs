@@ -0,0 +1,172 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Primitives types used for dispute and backing slashing.
+
+use crate::v1::{
+	CandidateHash, CompactStatement, Hash, SessionIndex, SigningContext, ValidatorId,
+	ValidatorIndex, ValidatorSignature,
+};
+use parity_scale_codec::{Decode, Encode};
+use primitives::RuntimeDebug;
+use runtime_primitives::traits::AppVerify;
+use scale_info::TypeInfo;
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+/// The kind of the dispute offence.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, TypeInfo, RuntimeDebug)]
+pub enum SlashingOffenceKind {
+	/// A severe offence when a validator backed an invalid block.
+	#[codec(index = 0)]
+	ForInvalid,
+	/// A minor offence when a validator disputed a valid block.
+	#[codec(index = 1)]
+	AgainstValid,
+}
+
+/// Timeslots should uniquely identify offences and are used for the offence
+/// deduplication.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Encode, Decode, TypeInfo, RuntimeDebug)]
+pub struct DisputesTimeSlot {
+	/// The session index the disputed candidate appeared in.
+	pub session_index: SessionIndex,
+	/// The hash of the disputed candidate.
+	pub candidate_hash: CandidateHash,
+}
+
+impl DisputesTimeSlot {
+	/// Create a new instance of `Self`.
+	pub fn new(session_index: SessionIndex, candidate_hash: CandidateHash) -> Self {
+		Self { session_index, candidate_hash }
+	}
+}
+
+/// We store most of the information about a lost dispute on chain. This struct
+/// is required to identify and verify it.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo, RuntimeDebug)]
+pub struct DisputeProof {
+	/// Time slot when the dispute occurred.
+	pub time_slot: DisputesTimeSlot,
+	/// The dispute outcome.
+	pub kind: SlashingOffenceKind,
+	/// The index of the validator who lost a dispute.
+	pub validator_index: ValidatorIndex,
+	/// The parachain session key of the validator.
+	pub validator_id: ValidatorId,
+}
+
+/// Slashes that are waiting to be applied once we have validator key
+/// identification.
+#[derive(Encode, Decode, TypeInfo, RuntimeDebug, Clone, PartialEq, Eq)]
+pub struct PendingSlashes {
+	/// Indices and keys of the validators who lost a dispute and are pending
+	/// slashes.
+	pub keys: BTreeMap<ValidatorIndex, ValidatorId>,
+	/// The dispute outcome.
+	pub kind: SlashingOffenceKind,
+}
+
+/// Timeslots of backing offences, identifying the signing context of the conflicting
+/// statements.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Encode, Decode, TypeInfo, RuntimeDebug)]
+pub struct BackingTimeSlot {
+	/// The session index the statements were signed in.
+	pub session_index: SessionIndex,
+	/// The relay parent the statements were signed for.
+	pub relay_parent: Hash,
+}
+
+/// Conflicting backing statements signed by a single validator.
+///
+/// Seconding a candidate and also attesting its validity is not included, as both statements
+/// count as a single validity vote and are harmless.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo, RuntimeDebug)]
+pub enum BackingMisbehavior {
+	/// Seconded two different candidates.
+	#[codec(index = 0)]
+	MultipleCandidates {
+		/// The first candidate seconded and the signature on the statement.
+		first: (CandidateHash, ValidatorSignature),
+		/// The second candidate seconded and the signature on the statement.
+		second: (CandidateHash, ValidatorSignature),
+	},
+}
+
+/// Evidence of a validator issuing conflicting backing statements, suitable for submission
+/// on-chain.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo, RuntimeDebug)]
+pub struct BackingMisbehaviorProof {
+	/// The signing context of the statements.
+	pub time_slot: BackingTimeSlot,
+	/// The index of the misbehaving validator in the session.
+	pub validator_index: ValidatorIndex,
+	/// The conflicting statements.
+	pub misbehavior: BackingMisbehavior,
+}
+
+impl BackingMisbehaviorProof {
+	/// The signed statements of the evidence, along with their signatures.
+	pub fn statements(&self) -> [(CompactStatement, &ValidatorSignature); 2] {
+		match self.misbehavior {
+			BackingMisbehavior::MultipleCandidates { ref first, ref second } => [
+				(CompactStatement::Seconded(first.0), &first.1),
+				(CompactStatement::Seconded(second.0), &second.1),
+			],
+		}
+	}
+
+	/// Check that the statements actually conflict and that both are signed by the given
+	/// validator.
+	pub fn check(&self, validator_id: &ValidatorId) -> bool {
+		let BackingMisbehavior::MultipleCandidates { ref first, ref second } = self.misbehavior;
+		if first.0 == second.0 {
+			return false
+		}
+
+		let context = SigningContext {
+			session_index: self.time_slot.session_index,
+			parent_hash: self.time_slot.relay_parent,
+		};
+		self.statements().iter().all(|(statement, signature)| {
+			signature.verify(&statement.signing_payload(&context)[..], validator_id)
+		})
+	}
+}
+
+/// An opaque type used to represent a key ownership proof at the runtime API
+/// boundary.
+///
+/// The inner value is an encoded representation of the actual key ownership
+/// proof which will be parameterized when defining the runtime. At the runtime
+/// API boundary this type is unknown and as such we keep this opaque
+/// representation, implementors of the runtime API will have to make sure that
+/// all usages of `OpaqueKeyOwnershipProof` refer to the same type.
+#[derive(Decode, Encode, PartialEq, Eq, Clone, TypeInfo, RuntimeDebug)]
+pub struct OpaqueKeyOwnershipProof(Vec<u8>);
+
+impl OpaqueKeyOwnershipProof {
+	/// Create a new `OpaqueKeyOwnershipProof` using the given encoded
+	/// representation.
+	pub fn new(inner: Vec<u8>) -> OpaqueKeyOwnershipProof {
+		OpaqueKeyOwnershipProof(inner)
+	}
+
+	/// Try to decode this `OpaqueKeyOwnershipProof` into the given concrete key
+	/// ownership proof type.
+	pub fn decode<T: Decode>(self) -> Option<T> {
+		Decode::decode(&mut &self.0[..]).ok()
+	}
+}
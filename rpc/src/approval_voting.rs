@@ -0,0 +1,85 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC interface to inspect the progress of approval voting.
+
+use futures::{channel::oneshot, FutureExt};
+use jsonrpc_core::{Error as RpcError, ErrorCode};
+use jsonrpc_derive::rpc;
+use polkadot_node_primitives::approval::BlockApprovalStatus;
+use polkadot_node_subsystem_types::messages::ApprovalVotingMessage;
+use polkadot_overseer::Handle;
+use polkadot_primitives::v1::Hash;
+use sc_rpc::DenyUnsafe;
+
+type FutureResult<T> = jsonrpc_core::BoxFuture<Result<T, RpcError>>;
+
+/// Approval voting RPC methods.
+#[rpc]
+pub trait ApprovalVotingApi {
+	/// Returns the approval progress of all candidates included by the given relay chain block:
+	/// the required tranches, the assignments, approvals and no-shows per tranche and an
+	/// estimate of the time left until approval.
+	///
+	/// Returns `None` if the block is not tracked by approval voting.
+	#[rpc(name = "parachains_approvalStatus")]
+	fn approval_status(&self, block_hash: Hash) -> FutureResult<Option<BlockApprovalStatus>>;
+}
+
+/// Implements the [`ApprovalVotingApi`] RPC trait by querying the approval voting subsystem.
+pub struct ApprovalVoting {
+	overseer_handle: Handle,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl ApprovalVoting {
+	/// Create a new instance talking to the overseer via the given handle.
+	pub fn new(overseer_handle: Handle, deny_unsafe: DenyUnsafe) -> Self {
+		Self { overseer_handle, deny_unsafe }
+	}
+}
+
+impl ApprovalVotingApi for ApprovalVoting {
+	fn approval_status(&self, block_hash: Hash) -> FutureResult<Option<BlockApprovalStatus>> {
+		// The status reveals which validators are assigned to check which candidates and
+		// who is currently a no-show.
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			return async move { Err(err.into()) }.boxed()
+		}
+
+		let mut overseer_handle = self.overseer_handle.clone();
+
+		async move {
+			let (tx, rx) = oneshot::channel();
+			overseer_handle
+				.send_msg(ApprovalVotingMessage::ApprovalStatus(block_hash, tx), "rpc")
+				.await;
+
+			rx.await.map_err(|_| subsystem_unavailable())
+		}
+		.boxed()
+	}
+}
+
+/// The error returned if the subsystem dropped the request, e.g. because the node is not
+/// running as a validator.
+pub(crate) fn subsystem_unavailable() -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(1),
+		message: "Parachain subsystem unavailable".into(),
+		data: None,
+	}
+}
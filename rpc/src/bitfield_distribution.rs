@@ -0,0 +1,87 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC interface to find out which validators did not attest the availability of candidates.
+
+use futures::{channel::oneshot, FutureExt};
+use jsonrpc_core::Error as RpcError;
+use jsonrpc_derive::rpc;
+use polkadot_node_primitives::availability::AvailabilityAttestations;
+use polkadot_node_subsystem_types::messages::BitfieldDistributionMessage;
+use polkadot_overseer::Handle;
+use polkadot_primitives::v1::Hash;
+use sc_rpc::DenyUnsafe;
+
+use crate::approval_voting::subsystem_unavailable;
+
+type FutureResult<T> = jsonrpc_core::BoxFuture<Result<T, RpcError>>;
+
+/// Bitfield distribution RPC methods.
+#[rpc]
+pub trait BitfieldDistributionApi {
+	/// Returns the availability attestations of the bitfields signed on the given relay parent:
+	/// the validators we did not receive a bitfield from and, for every candidate pending
+	/// availability, the validators which did not attest the availability of their chunk.
+	///
+	/// Returns `None` if the relay parent is neither in the view of the node nor among the relay
+	/// parents which recently left it.
+	#[rpc(name = "parachains_availabilityAttestations")]
+	fn availability_attestations(
+		&self,
+		relay_parent: Hash,
+	) -> FutureResult<Option<AvailabilityAttestations>>;
+}
+
+/// Implements the [`BitfieldDistributionApi`] RPC trait by querying the bitfield distribution
+/// subsystem.
+pub struct BitfieldDistribution {
+	overseer_handle: Handle,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl BitfieldDistribution {
+	/// Create a new instance talking to the overseer via the given handle.
+	pub fn new(overseer_handle: Handle, deny_unsafe: DenyUnsafe) -> Self {
+		Self { overseer_handle, deny_unsafe }
+	}
+}
+
+impl BitfieldDistributionApi for BitfieldDistribution {
+	fn availability_attestations(
+		&self,
+		relay_parent: Hash,
+	) -> FutureResult<Option<AvailabilityAttestations>> {
+		// The validators failing to attest availability are candidates for targeted attacks.
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			return async move { Err(err.into()) }.boxed()
+		}
+
+		let mut overseer_handle = self.overseer_handle.clone();
+
+		async move {
+			let (tx, rx) = oneshot::channel();
+			overseer_handle
+				.send_msg(
+					BitfieldDistributionMessage::GetAvailabilityAttestations(relay_parent, tx),
+					"rpc",
+				)
+				.await;
+
+			rx.await.map_err(|_| subsystem_unavailable())
+		}
+		.boxed()
+	}
+}
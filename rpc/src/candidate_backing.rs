@@ -0,0 +1,89 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC interface to find out why candidates did or did not get backed.
+
+use futures::{channel::oneshot, FutureExt};
+use jsonrpc_core::Error as RpcError;
+use jsonrpc_derive::rpc;
+use polkadot_node_primitives::backing::BackingTraceEntry;
+use polkadot_node_subsystem_types::messages::CandidateBackingMessage;
+use polkadot_overseer::Handle;
+use polkadot_primitives::v1::{CandidateHash, Hash};
+use sc_rpc::DenyUnsafe;
+
+type FutureResult<T> = jsonrpc_core::BoxFuture<Result<T, RpcError>>;
+
+/// Candidate backing RPC methods.
+#[rpc]
+pub trait CandidateBackingApi {
+	/// Returns the recent lifecycle events of candidates at the given relay parent, oldest first:
+	/// their receipt, validation results, statements of the backing group and the attestation
+	/// state in the statement table. Only the events of the given candidate are returned,
+	/// if any.
+	///
+	/// The events of a bounded number of recently traced candidates are kept, also after their
+	/// relay parent left the view. Returns `None` if candidate backing didn't answer.
+	#[rpc(name = "parachains_candidateBackingTrace")]
+	fn candidate_backing_trace(
+		&self,
+		relay_parent: Hash,
+		candidate_hash: Option<Hash>,
+	) -> FutureResult<Option<Vec<BackingTraceEntry>>>;
+}
+
+/// Implements the [`CandidateBackingApi`] RPC trait by querying the candidate backing subsystem.
+pub struct CandidateBacking {
+	overseer_handle: Handle,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl CandidateBacking {
+	/// Create a new instance talking to the overseer via the given handle.
+	pub fn new(overseer_handle: Handle, deny_unsafe: DenyUnsafe) -> Self {
+		Self { overseer_handle, deny_unsafe }
+	}
+}
+
+impl CandidateBackingApi for CandidateBacking {
+	fn candidate_backing_trace(
+		&self,
+		relay_parent: Hash,
+		candidate_hash: Option<Hash>,
+	) -> FutureResult<Option<Vec<BackingTraceEntry>>> {
+		// The trace reveals the statements and validation results of our backing group.
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			return async move { Err(err.into()) }.boxed()
+		}
+
+		let mut overseer_handle = self.overseer_handle.clone();
+
+		async move {
+			let (tx, rx) = oneshot::channel();
+			let candidate_hash = candidate_hash.map(CandidateHash);
+			overseer_handle
+				.send_msg(
+					CandidateBackingMessage::GetCandidateTrace(relay_parent, candidate_hash, tx),
+					"rpc",
+				)
+				.await;
+
+			// The request is dropped if the backing job of the relay parent ended early.
+			Ok(rx.await.ok())
+		}
+		.boxed()
+	}
+}
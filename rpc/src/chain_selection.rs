@@ -0,0 +1,88 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Unsafe RPC interface to manually override the chain selection of the node.
+
+use futures::{channel::oneshot, FutureExt};
+use jsonrpc_core::Error as RpcError;
+use jsonrpc_derive::rpc;
+use polkadot_node_subsystem_types::messages::ChainSelectionMessage;
+use polkadot_overseer::Handle;
+use polkadot_primitives::v1::Hash;
+use sc_rpc::DenyUnsafe;
+
+use crate::approval_voting::subsystem_unavailable;
+
+type FutureResult<T> = jsonrpc_core::BoxFuture<Result<T, RpcError>>;
+
+/// Chain selection administration RPC methods.
+#[rpc]
+pub trait ChainSelectionApi {
+	/// Mark the given unfinalized block and all of its descendants as non-viable, so that
+	/// the node stops building on and voting for them. The mark is persisted across restarts.
+	///
+	/// Returns `false` if the block is unknown to chain selection, e.g. because it is finalized.
+	#[rpc(name = "parachains_blacklistBlock")]
+	fn blacklist_block(&self, block_hash: Hash) -> FutureResult<bool>;
+
+	/// Clear a mark previously set by `parachains_blacklistBlock`.
+	///
+	/// Returns `false` if the block is unknown to chain selection.
+	#[rpc(name = "parachains_clearBlacklistedBlock")]
+	fn clear_blacklisted_block(&self, block_hash: Hash) -> FutureResult<bool>;
+}
+
+/// Implements the [`ChainSelectionApi`] RPC trait by messaging the chain selection subsystem.
+pub struct ChainSelection {
+	overseer_handle: Handle,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl ChainSelection {
+	/// Create a new instance talking to the overseer via the given handle.
+	pub fn new(overseer_handle: Handle, deny_unsafe: DenyUnsafe) -> Self {
+		Self { overseer_handle, deny_unsafe }
+	}
+
+	fn send_request(
+		&self,
+		make_msg: impl FnOnce(oneshot::Sender<bool>) -> ChainSelectionMessage + Send + 'static,
+	) -> FutureResult<bool> {
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			return async move { Err(err.into()) }.boxed()
+		}
+
+		let mut overseer_handle = self.overseer_handle.clone();
+
+		async move {
+			let (tx, rx) = oneshot::channel();
+			overseer_handle.send_msg(make_msg(tx), "rpc").await;
+
+			rx.await.map_err(|_| subsystem_unavailable())
+		}
+		.boxed()
+	}
+}
+
+impl ChainSelectionApi for ChainSelection {
+	fn blacklist_block(&self, block_hash: Hash) -> FutureResult<bool> {
+		self.send_request(move |tx| ChainSelectionMessage::Blacklist(block_hash, tx))
+	}
+
+	fn clear_blacklisted_block(&self, block_hash: Hash) -> FutureResult<bool> {
+		self.send_request(move |tx| ChainSelectionMessage::ClearBlacklist(block_hash, tx))
+	}
+}
@@ -0,0 +1,99 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Unsafe RPC interface to inspect the gossip topology and the connectivity of the node.
+
+use futures::{channel::oneshot, FutureExt};
+use jsonrpc_core::Error as RpcError;
+use jsonrpc_derive::rpc;
+use polkadot_node_primitives::gossip_support::{ConnectivityReport, GossipTopologyInfo};
+use polkadot_node_subsystem_types::messages::GossipSupportMessage;
+use polkadot_overseer::Handle;
+use sc_rpc::DenyUnsafe;
+
+use crate::approval_voting::subsystem_unavailable;
+
+type FutureResult<T> = jsonrpc_core::BoxFuture<Result<T, RpcError>>;
+
+/// Gossip support RPC methods.
+#[rpc]
+pub trait GossipSupportApi {
+	/// Returns the gossip topology of the current session: our neighbors in the row and column of
+	/// the grid, whether their addresses were resolved and the peer we are connected to them with.
+	///
+	/// Returns `None` if the node is not an authority in the current session.
+	#[rpc(name = "parachains_gossipTopology")]
+	fn gossip_topology(&self) -> FutureResult<Option<GossipTopologyInfo>>;
+
+	/// Returns how many of the other authorities of the current session were resolved via
+	/// authority discovery and how many we are connected to, along with the reason we are not
+	/// connected to the others.
+	///
+	/// Returns `None` if the node is not an authority in the current session.
+	#[rpc(name = "parachains_authorityConnectivity")]
+	fn authority_connectivity(&self) -> FutureResult<Option<ConnectivityReport>>;
+}
+
+/// Implements the [`GossipSupportApi`] RPC trait by querying the gossip support subsystem.
+pub struct GossipSupport {
+	overseer_handle: Handle,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl GossipSupport {
+	/// Create a new instance talking to the overseer via the given handle.
+	pub fn new(overseer_handle: Handle, deny_unsafe: DenyUnsafe) -> Self {
+		Self { overseer_handle, deny_unsafe }
+	}
+}
+
+impl GossipSupportApi for GossipSupport {
+	fn gossip_topology(&self) -> FutureResult<Option<GossipTopologyInfo>> {
+		// Our neighbors are of interest to anyone trying to eclipse the node.
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			return async move { Err(err.into()) }.boxed()
+		}
+
+		let mut overseer_handle = self.overseer_handle.clone();
+
+		async move {
+			let (tx, rx) = oneshot::channel();
+			overseer_handle.send_msg(GossipSupportMessage::GetTopology(tx), "rpc").await;
+
+			rx.await.map_err(|_| subsystem_unavailable())
+		}
+		.boxed()
+	}
+
+	fn authority_connectivity(&self) -> FutureResult<Option<ConnectivityReport>> {
+		// As is the list of authorities we fail to reach.
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			return async move { Err(err.into()) }.boxed()
+		}
+
+		let mut overseer_handle = self.overseer_handle.clone();
+
+		async move {
+			let (tx, rx) = oneshot::channel();
+			overseer_handle
+				.send_msg(GossipSupportMessage::GetConnectivityReport(tx), "rpc")
+				.await;
+
+			rx.await.map_err(|_| subsystem_unavailable())
+		}
+		.boxed()
+	}
+}
@@ -34,6 +34,13 @@ use sp_consensus_babe::BabeApi;
 use sp_keystore::SyncCryptoStorePtr;
 use txpool_api::TransactionPool;
 
+pub mod approval_voting;
+pub mod bitfield_distribution;
+pub mod candidate_backing;
+pub mod chain_selection;
+pub mod gossip_support;
+pub mod parachain_host;
+
 /// A type representing all RPC extensions.
 pub type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
 
@@ -90,6 +97,10 @@ pub struct FullDeps<C, P, SC, B> {
 	pub grandpa: GrandpaDeps<B>,
 	/// BEEFY specific dependencies.
 	pub beefy: BeefyDeps,
+	/// A handle to the overseer, used to query the parachain subsystems.
+	///
+	/// The RPCs inspecting subsystems are only available if this is set.
+	pub overseer_handle: Option<polkadot_overseer::Handle>,
 }
 
 /// Instantiate all RPC extensions.
@@ -109,6 +120,7 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
+	C::Api: polkadot_primitives::v2::ParachainHost<Block>,
 	P: TransactionPool + Sync + Send + 'static,
 	SC: SelectChain<Block> + 'static,
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
@@ -117,12 +129,22 @@ where
 	use frame_rpc_system::{FullSystem, SystemApi};
 	use pallet_mmr_rpc::{Mmr, MmrApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
+	use parachain_host::{ParachainHost, ParachainHostApi};
 	use sc_consensus_babe_rpc::BabeRpcHandler;
 	use sc_finality_grandpa_rpc::{GrandpaApi, GrandpaRpcHandler};
 
 	let mut io = jsonrpc_core::IoHandler::default();
-	let FullDeps { client, pool, select_chain, chain_spec, deny_unsafe, babe, grandpa, beefy } =
-		deps;
+	let FullDeps {
+		client,
+		pool,
+		select_chain,
+		chain_spec,
+		deny_unsafe,
+		babe,
+		grandpa,
+		beefy,
+		overseer_handle,
+	} = deps;
 	let BabeDeps { keystore, babe_config, shared_epoch_changes } = babe;
 	let GrandpaDeps {
 		shared_voter_state,
@@ -135,6 +157,7 @@ where
 	io.extend_with(SystemApi::to_delegate(FullSystem::new(client.clone(), pool, deny_unsafe)));
 	io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone())));
 	io.extend_with(MmrApi::to_delegate(Mmr::new(client.clone())));
+	io.extend_with(ParachainHostApi::to_delegate(ParachainHost::new(client.clone())));
 	io.extend_with(sc_consensus_babe_rpc::BabeApi::to_delegate(BabeRpcHandler::new(
 		client.clone(),
 		shared_epoch_changes.clone(),
@@ -165,5 +188,34 @@ where
 	)?;
 	io.extend_with(beefy_gadget_rpc::BeefyApi::to_delegate(handler));
 
+	if let Some(overseer_handle) = overseer_handle {
+		use approval_voting::{ApprovalVoting, ApprovalVotingApi};
+		use bitfield_distribution::{BitfieldDistribution, BitfieldDistributionApi};
+		use candidate_backing::{CandidateBacking, CandidateBackingApi};
+		use chain_selection::{ChainSelection, ChainSelectionApi};
+		use gossip_support::{GossipSupport, GossipSupportApi};
+
+		io.extend_with(ApprovalVotingApi::to_delegate(ApprovalVoting::new(
+			overseer_handle.clone(),
+			deny_unsafe,
+		)));
+		io.extend_with(BitfieldDistributionApi::to_delegate(BitfieldDistribution::new(
+			overseer_handle.clone(),
+			deny_unsafe,
+		)));
+		io.extend_with(CandidateBackingApi::to_delegate(CandidateBacking::new(
+			overseer_handle.clone(),
+			deny_unsafe,
+		)));
+		io.extend_with(ChainSelectionApi::to_delegate(ChainSelection::new(
+			overseer_handle.clone(),
+			deny_unsafe,
+		)));
+		io.extend_with(GossipSupportApi::to_delegate(GossipSupport::new(
+			overseer_handle,
+			deny_unsafe,
+		)));
+	}
+
 	Ok(io)
 }
@@ -0,0 +1,795 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC interface to the `ParachainHost` runtime API, with JSON-friendly responses.
+//!
+//! Signatures are left out of the responses, as they were checked by the runtime already.
+
+use std::sync::Arc;
+
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::generic::BlockId;
+
+use polkadot_primitives::{
+	v1::{
+		self, AssignmentId, AuthorityDiscoveryId, Block, BlockNumber, CollatorId, Hash,
+		Id as ParaId, SessionIndex, ValidatorId, ValidatorIndex,
+	},
+	v2::{self, ParachainHost as ParachainHostRuntimeApi},
+};
+
+/// `ParachainHost` runtime API RPC methods.
+///
+/// All methods query the state at the given block, or the best block if none is given.
+#[rpc]
+pub trait ParachainHostApi {
+	/// Returns the validators of the current session.
+	#[rpc(name = "parachainHost_validators")]
+	fn validators(&self, at: Option<Hash>) -> Result<Vec<ValidatorId>>;
+
+	/// Returns the index of the session a child of the block will be in.
+	#[rpc(name = "parachainHost_sessionIndexForChild")]
+	fn session_index_for_child(&self, at: Option<Hash>) -> Result<SessionIndex>;
+
+	/// Returns the state of all availability cores.
+	#[rpc(name = "parachainHost_availabilityCores")]
+	fn availability_cores(&self, at: Option<Hash>) -> Result<Vec<AvailabilityCore>>;
+
+	/// Returns the events of the candidates backed, included or timed out in the block.
+	#[rpc(name = "parachainHost_candidateEvents")]
+	fn candidate_events(&self, at: Option<Hash>) -> Result<Vec<CandidateEvent>>;
+
+	/// Returns the information about the given session, if it is still stored.
+	#[rpc(name = "parachainHost_sessionInfo")]
+	fn session_info(&self, index: SessionIndex, at: Option<Hash>) -> Result<Option<SessionInfo>>;
+
+	/// Returns the contents of the downward message queue of the given para.
+	#[rpc(name = "parachainHost_dmqContents")]
+	fn dmq_contents(
+		&self,
+		para_id: ParaId,
+		at: Option<Hash>,
+	) -> Result<Vec<InboundDownwardMessage>>;
+
+	/// Returns the contents of all inbound HRMP channels of the given para, by sender.
+	#[rpc(name = "parachainHost_inboundHrmpChannelsContents")]
+	fn inbound_hrmp_channels_contents(
+		&self,
+		recipient: ParaId,
+		at: Option<Hash>,
+	) -> Result<Vec<InboundHrmpChannel>>;
+
+	/// Returns the backing and dispute votes which were put on chain with the block.
+	#[rpc(name = "parachainHost_onChainVotes")]
+	fn on_chain_votes(&self, at: Option<Hash>) -> Result<Option<OnChainVotes>>;
+}
+
+/// The state of an availability core.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum AvailabilityCore {
+	/// The core is occupied by a candidate pending availability.
+	#[serde(rename_all = "camelCase")]
+	Occupied {
+		/// The para the candidate is for.
+		para_id: ParaId,
+		/// The hash of the candidate.
+		candidate_hash: Hash,
+		/// The relay chain block number the candidate was backed at.
+		occupied_since: BlockNumber,
+		/// The relay chain block number the candidate times out at.
+		time_out_at: BlockNumber,
+		/// The index of the group responsible for the candidate.
+		group_responsible: u32,
+		/// One character per validator, `1` if it attested the availability of its chunk and
+		/// `0` otherwise.
+		availability: String,
+		/// The para scheduled next if the candidate becomes available.
+		next_up_on_available: Option<ParaId>,
+		/// The para scheduled next if the candidate times out.
+		next_up_on_time_out: Option<ParaId>,
+	},
+	/// A para is scheduled on the core.
+	#[serde(rename_all = "camelCase")]
+	Scheduled {
+		/// The para scheduled.
+		para_id: ParaId,
+		/// The collator required to author the candidate, if any.
+		collator: Option<CollatorId>,
+	},
+	/// The core is unoccupied and nothing is scheduled on it.
+	Free,
+}
+
+impl From<v1::CoreState> for AvailabilityCore {
+	fn from(core: v1::CoreState) -> Self {
+		match core {
+			v1::CoreState::Occupied(occupied) => AvailabilityCore::Occupied {
+				para_id: occupied.para_id(),
+				candidate_hash: occupied.candidate_hash.0,
+				occupied_since: occupied.occupied_since,
+				time_out_at: occupied.time_out_at,
+				group_responsible: occupied.group_responsible.0,
+				availability: occupied
+					.availability
+					.iter()
+					.map(|bit| if *bit { '1' } else { '0' })
+					.collect(),
+				next_up_on_available: occupied.next_up_on_available.map(|s| s.para_id),
+				next_up_on_time_out: occupied.next_up_on_time_out.map(|s| s.para_id),
+			},
+			v1::CoreState::Scheduled(scheduled) => AvailabilityCore::Scheduled {
+				para_id: scheduled.para_id,
+				collator: scheduled.collator,
+			},
+			v1::CoreState::Free => AvailabilityCore::Free,
+		}
+	}
+}
+
+/// What happened to a candidate in a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CandidateEventKind {
+	/// The candidate was backed and now occupies the core.
+	Backed,
+	/// The candidate became available and was included.
+	Included,
+	/// The candidate was not made available in time.
+	TimedOut,
+}
+
+/// An event of a candidate in a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateEvent {
+	/// What happened to the candidate.
+	pub kind: CandidateEventKind,
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The para the candidate is for.
+	pub para_id: ParaId,
+	/// The relay parent of the candidate.
+	pub relay_parent: Hash,
+	/// The head data the candidate produces.
+	pub head_data: Bytes,
+	/// The core the candidate occupies or occupied.
+	pub core_index: u32,
+	/// The group responsible for backing the candidate, unless it timed out.
+	pub group_index: Option<u32>,
+}
+
+impl From<v1::CandidateEvent> for CandidateEvent {
+	fn from(event: v1::CandidateEvent) -> Self {
+		let (kind, receipt, head_data, core_index, group_index) = match event {
+			v1::CandidateEvent::CandidateBacked(receipt, head_data, core_index, group_index) =>
+				(CandidateEventKind::Backed, receipt, head_data, core_index, Some(group_index)),
+			v1::CandidateEvent::CandidateIncluded(receipt, head_data, core_index, group_index) =>
+				(CandidateEventKind::Included, receipt, head_data, core_index, Some(group_index)),
+			v1::CandidateEvent::CandidateTimedOut(receipt, head_data, core_index) =>
+				(CandidateEventKind::TimedOut, receipt, head_data, core_index, None),
+		};
+
+		CandidateEvent {
+			kind,
+			candidate_hash: receipt.hash().0,
+			para_id: receipt.descriptor.para_id,
+			relay_parent: receipt.descriptor.relay_parent,
+			head_data: head_data.0.into(),
+			core_index: core_index.0,
+			group_index: group_index.map(|g| g.0),
+		}
+	}
+}
+
+/// Information about the validators of a session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInfo {
+	/// The validators actively participating in parachain consensus, as indices into the
+	/// broader validator set.
+	pub active_validator_indices: Vec<ValidatorIndex>,
+	/// The random seed of the session, gathered from BABE.
+	pub random_seed: Hash,
+	/// The number of sessions disputes are kept for.
+	pub dispute_period: SessionIndex,
+	/// The parachain validators in canonical ordering.
+	pub validators: Vec<ValidatorId>,
+	/// The authority discovery keys of all authorities in canonical ordering.
+	pub discovery_keys: Vec<AuthorityDiscoveryId>,
+	/// The assignment keys of the parachain validators.
+	pub assignment_keys: Vec<AssignmentId>,
+	/// The validator groups, in the order of their group indices.
+	pub validator_groups: Vec<Vec<ValidatorIndex>>,
+	/// The number of availability cores.
+	pub n_cores: u32,
+	/// The width of the zeroth delay tranche of approval assignments.
+	pub zeroth_delay_tranche_width: u32,
+	/// The number of samples of `relay_vrf_modulo` assignments.
+	pub relay_vrf_modulo_samples: u32,
+	/// The number of delay tranches in total.
+	pub n_delay_tranches: u32,
+	/// The number of slots after which an assignment without approval is a no-show.
+	pub no_show_slots: u32,
+	/// The number of validators needed to approve a block.
+	pub needed_approvals: u32,
+}
+
+impl From<v2::SessionInfo> for SessionInfo {
+	fn from(info: v2::SessionInfo) -> Self {
+		SessionInfo {
+			active_validator_indices: info.active_validator_indices,
+			random_seed: info.random_seed.into(),
+			dispute_period: info.dispute_period,
+			validators: info.validators,
+			discovery_keys: info.discovery_keys,
+			assignment_keys: info.assignment_keys,
+			validator_groups: info.validator_groups,
+			n_cores: info.n_cores,
+			zeroth_delay_tranche_width: info.zeroth_delay_tranche_width,
+			relay_vrf_modulo_samples: info.relay_vrf_modulo_samples,
+			n_delay_tranches: info.n_delay_tranches,
+			no_show_slots: info.no_show_slots,
+			needed_approvals: info.needed_approvals,
+		}
+	}
+}
+
+/// A message in the downward message queue of a para.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundDownwardMessage {
+	/// The relay chain block number the message was sent at.
+	pub sent_at: BlockNumber,
+	/// The message.
+	pub msg: Bytes,
+}
+
+impl From<v1::InboundDownwardMessage> for InboundDownwardMessage {
+	fn from(message: v1::InboundDownwardMessage) -> Self {
+		InboundDownwardMessage { sent_at: message.sent_at, msg: message.msg.into() }
+	}
+}
+
+/// A message in an HRMP channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundHrmpMessage {
+	/// The relay chain block number the message was sent at.
+	pub sent_at: BlockNumber,
+	/// The message.
+	pub data: Bytes,
+}
+
+impl From<v1::InboundHrmpMessage> for InboundHrmpMessage {
+	fn from(message: v1::InboundHrmpMessage) -> Self {
+		InboundHrmpMessage { sent_at: message.sent_at, data: message.data.into() }
+	}
+}
+
+/// The messages in an inbound HRMP channel of a para.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundHrmpChannel {
+	/// The para sending the messages.
+	pub sender: ParaId,
+	/// The messages, oldest first.
+	pub messages: Vec<InboundHrmpMessage>,
+}
+
+/// How a backing validator attested the validity of a candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackingVoteKind {
+	/// By seconding the candidate.
+	Implicit,
+	/// By a validity statement.
+	Explicit,
+}
+
+/// A vote of a backing validator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackingVote {
+	/// The validator who voted.
+	pub validator_index: ValidatorIndex,
+	/// How it attested the validity of the candidate.
+	pub kind: BackingVoteKind,
+}
+
+/// The backing votes of a candidate put on chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackedCandidateVotes {
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The para the candidate is for.
+	pub para_id: ParaId,
+	/// The votes of the backing group.
+	pub votes: Vec<BackingVote>,
+}
+
+/// The dispute votes of a candidate put on chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeVotes {
+	/// The hash of the disputed candidate.
+	pub candidate_hash: Hash,
+	/// The session the candidate was backed in.
+	pub session: SessionIndex,
+	/// The validators voting for the validity of the candidate.
+	pub valid: Vec<ValidatorIndex>,
+	/// The validators voting against the validity of the candidate.
+	pub invalid: Vec<ValidatorIndex>,
+}
+
+/// The backing and dispute votes put on chain with a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnChainVotes {
+	/// The session the block was included in.
+	pub session: SessionIndex,
+	/// The votes of the backing groups of the candidates backed in the block.
+	pub backing: Vec<BackedCandidateVotes>,
+	/// The disputes votes imported with the block.
+	pub disputes: Vec<DisputeVotes>,
+}
+
+impl From<v1::ScrapedOnChainVotes> for OnChainVotes {
+	fn from(votes: v1::ScrapedOnChainVotes) -> Self {
+		let backing = votes
+			.backing_validators_per_candidate
+			.into_iter()
+			.map(|(receipt, validators)| BackedCandidateVotes {
+				candidate_hash: receipt.hash().0,
+				para_id: receipt.descriptor.para_id,
+				votes: validators
+					.into_iter()
+					.map(|(validator_index, attestation)| BackingVote {
+						validator_index,
+						kind: match attestation {
+							v1::ValidityAttestation::Implicit(_) => BackingVoteKind::Implicit,
+							v1::ValidityAttestation::Explicit(_) => BackingVoteKind::Explicit,
+						},
+					})
+					.collect(),
+			})
+			.collect();
+
+		let disputes = votes
+			.disputes
+			.into_iter()
+			.map(|set| {
+				let (valid, invalid): (Vec<_>, Vec<_>) =
+					set.statements.into_iter().partition(|(statement, _, _)| {
+						matches!(statement, v1::DisputeStatement::Valid(_))
+					});
+				DisputeVotes {
+					candidate_hash: set.candidate_hash.0,
+					session: set.session,
+					valid: valid.into_iter().map(|(_, validator, _)| validator).collect(),
+					invalid: invalid.into_iter().map(|(_, validator, _)| validator).collect(),
+				}
+			})
+			.collect();
+
+		OnChainVotes { session: votes.session, backing, disputes }
+	}
+}
+
+/// Implements the [`ParachainHostApi`] RPC trait by calling the runtime API of the client.
+pub struct ParachainHost<C> {
+	client: Arc<C>,
+}
+
+impl<C> ParachainHost<C> {
+	/// Create a new instance calling the runtime API of the given client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client }
+	}
+}
+
+impl<C> ParachainHost<C>
+where
+	C: HeaderBackend<Block>,
+{
+	fn block_id(&self, at: Option<Hash>) -> BlockId<Block> {
+		BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash))
+	}
+}
+
+impl<C> ParachainHostApi for ParachainHost<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: ParachainHostRuntimeApi<Block>,
+{
+	fn validators(&self, at: Option<Hash>) -> Result<Vec<ValidatorId>> {
+		self.client.runtime_api().validators(&self.block_id(at)).map_err(runtime_error)
+	}
+
+	fn session_index_for_child(&self, at: Option<Hash>) -> Result<SessionIndex> {
+		self.client
+			.runtime_api()
+			.session_index_for_child(&self.block_id(at))
+			.map_err(runtime_error)
+	}
+
+	fn availability_cores(&self, at: Option<Hash>) -> Result<Vec<AvailabilityCore>> {
+		let cores = self
+			.client
+			.runtime_api()
+			.availability_cores(&self.block_id(at))
+			.map_err(runtime_error)?;
+
+		Ok(cores.into_iter().map(Into::into).collect())
+	}
+
+	fn candidate_events(&self, at: Option<Hash>) -> Result<Vec<CandidateEvent>> {
+		let events = self
+			.client
+			.runtime_api()
+			.candidate_events(&self.block_id(at))
+			.map_err(runtime_error)?;
+
+		Ok(events.into_iter().map(Into::into).collect())
+	}
+
+	fn session_info(&self, index: SessionIndex, at: Option<Hash>) -> Result<Option<SessionInfo>> {
+		let api = self.client.runtime_api();
+		let at = self.block_id(at);
+
+		let api_version = api
+			.api_version::<dyn ParachainHostRuntimeApi<Block>>(&at)
+			.map_err(runtime_error)?
+			.unwrap_or_default();
+		let info = session_info_at_version(
+			api_version,
+			|| api.session_info(&at, index).map_err(runtime_error),
+			|| {
+				#[allow(deprecated)]
+				api.session_info_before_version_2(&at, index).map_err(runtime_error)
+			},
+		)?;
+
+		Ok(info.map(Into::into))
+	}
+
+	fn dmq_contents(
+		&self,
+		para_id: ParaId,
+		at: Option<Hash>,
+	) -> Result<Vec<InboundDownwardMessage>> {
+		let messages = self
+			.client
+			.runtime_api()
+			.dmq_contents(&self.block_id(at), para_id)
+			.map_err(runtime_error)?;
+
+		Ok(messages.into_iter().map(Into::into).collect())
+	}
+
+	fn inbound_hrmp_channels_contents(
+		&self,
+		recipient: ParaId,
+		at: Option<Hash>,
+	) -> Result<Vec<InboundHrmpChannel>> {
+		let channels = self
+			.client
+			.runtime_api()
+			.inbound_hrmp_channels_contents(&self.block_id(at), recipient)
+			.map_err(runtime_error)?;
+
+		Ok(channels
+			.into_iter()
+			.map(|(sender, messages)| InboundHrmpChannel {
+				sender,
+				messages: messages.into_iter().map(Into::into).collect(),
+			})
+			.collect())
+	}
+
+	fn on_chain_votes(&self, at: Option<Hash>) -> Result<Option<OnChainVotes>> {
+		let votes = self
+			.client
+			.runtime_api()
+			.on_chain_votes(&self.block_id(at))
+			.map_err(runtime_error)?;
+
+		Ok(votes.map(Into::into))
+	}
+}
+
+/// Fetch the session info with the runtime API call available at the given API version.
+///
+/// Runtimes before version 2 of the API return the old session info.
+fn session_info_at_version(
+	api_version: u32,
+	session_info: impl FnOnce() -> Result<Option<v2::SessionInfo>>,
+	session_info_before_version_2: impl FnOnce() -> Result<Option<v1::SessionInfo>>,
+) -> Result<Option<v2::SessionInfo>> {
+	if api_version >= 2 {
+		session_info()
+	} else {
+		session_info_before_version_2().map(|info| info.map(Into::into))
+	}
+}
+
+/// The error returned if the runtime API call failed, e.g. because the state of the block was
+/// pruned already.
+fn runtime_error(err: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(2),
+		message: "Runtime API call failed".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use bitvec::bitvec;
+	use sp_core::crypto::UncheckedFrom;
+	use test_helpers::{
+		dummy_candidate_descriptor, dummy_candidate_receipt, dummy_collator, dummy_hash,
+		dummy_validator,
+	};
+
+	fn dummy_signature() -> v1::ValidatorSignature {
+		UncheckedFrom::unchecked_from([1u8; 64])
+	}
+
+	fn candidate_receipt(para_id: u32) -> v1::CandidateReceipt {
+		let mut receipt = dummy_candidate_receipt(dummy_hash());
+		receipt.descriptor.para_id = para_id.into();
+		receipt
+	}
+
+	fn old_session_info() -> v1::SessionInfo {
+		v1::SessionInfo {
+			validators: vec![dummy_validator()],
+			discovery_keys: Vec::new(),
+			assignment_keys: Vec::new(),
+			validator_groups: vec![vec![ValidatorIndex(0)]],
+			n_cores: 1,
+			zeroth_delay_tranche_width: 2,
+			relay_vrf_modulo_samples: 3,
+			n_delay_tranches: 4,
+			no_show_slots: 5,
+			needed_approvals: 6,
+		}
+	}
+
+	fn session_info() -> v2::SessionInfo {
+		v2::SessionInfo {
+			active_validator_indices: vec![ValidatorIndex(0)],
+			random_seed: [1u8; 32],
+			dispute_period: 12,
+			..v2::SessionInfo::from(old_session_info())
+		}
+	}
+
+	#[test]
+	fn occupied_core_is_converted() {
+		let mut candidate_descriptor = dummy_candidate_descriptor(dummy_hash());
+		candidate_descriptor.para_id = 1.into();
+		let core = v1::CoreState::Occupied(v1::OccupiedCore {
+			next_up_on_available: Some(v1::ScheduledCore { para_id: 2.into(), collator: None }),
+			occupied_since: 10,
+			time_out_at: 20,
+			next_up_on_time_out: None,
+			availability: bitvec![bitvec::order::Lsb0, u8; 1, 0, 1],
+			group_responsible: v1::GroupIndex(3),
+			candidate_hash: v1::CandidateHash(Hash::repeat_byte(4)),
+			candidate_descriptor,
+		});
+
+		assert_eq!(
+			AvailabilityCore::from(core),
+			AvailabilityCore::Occupied {
+				para_id: 1.into(),
+				candidate_hash: Hash::repeat_byte(4),
+				occupied_since: 10,
+				time_out_at: 20,
+				group_responsible: 3,
+				availability: "101".into(),
+				next_up_on_available: Some(2.into()),
+				next_up_on_time_out: None,
+			},
+		);
+	}
+
+	#[test]
+	fn scheduled_and_free_cores_are_converted() {
+		let collator = dummy_collator();
+		let core = v1::CoreState::Scheduled(v1::ScheduledCore {
+			para_id: 1.into(),
+			collator: Some(collator.clone()),
+		});
+
+		assert_eq!(
+			AvailabilityCore::from(core),
+			AvailabilityCore::Scheduled { para_id: 1.into(), collator: Some(collator) },
+		);
+		assert_eq!(AvailabilityCore::from(v1::CoreState::Free), AvailabilityCore::Free);
+	}
+
+	#[test]
+	fn candidate_events_are_converted() {
+		let receipt = candidate_receipt(1);
+		let head_data = v1::HeadData(vec![1, 2, 3]);
+		let events = vec![
+			(
+				v1::CandidateEvent::CandidateBacked(
+					receipt.clone(),
+					head_data.clone(),
+					v1::CoreIndex(2),
+					v1::GroupIndex(3),
+				),
+				CandidateEventKind::Backed,
+				Some(3),
+			),
+			(
+				v1::CandidateEvent::CandidateIncluded(
+					receipt.clone(),
+					head_data.clone(),
+					v1::CoreIndex(2),
+					v1::GroupIndex(3),
+				),
+				CandidateEventKind::Included,
+				Some(3),
+			),
+			(
+				v1::CandidateEvent::CandidateTimedOut(receipt.clone(), head_data, v1::CoreIndex(2)),
+				CandidateEventKind::TimedOut,
+				None,
+			),
+		];
+
+		for (event, kind, group_index) in events {
+			assert_eq!(
+				CandidateEvent::from(event),
+				CandidateEvent {
+					kind,
+					candidate_hash: receipt.hash().0,
+					para_id: 1.into(),
+					relay_parent: receipt.descriptor.relay_parent,
+					head_data: Bytes(vec![1, 2, 3]),
+					core_index: 2,
+					group_index,
+				},
+			);
+		}
+	}
+
+	#[test]
+	fn on_chain_votes_are_partitioned() {
+		let receipt = candidate_receipt(1);
+		let votes = v1::ScrapedOnChainVotes {
+			session: 7,
+			backing_validators_per_candidate: vec![(
+				receipt.clone(),
+				vec![
+					(ValidatorIndex(0), v1::ValidityAttestation::Implicit(dummy_signature())),
+					(ValidatorIndex(1), v1::ValidityAttestation::Explicit(dummy_signature())),
+				],
+			)],
+			disputes: vec![v1::DisputeStatementSet {
+				candidate_hash: v1::CandidateHash(Hash::repeat_byte(2)),
+				session: 6,
+				statements: vec![
+					(
+						v1::DisputeStatement::Valid(v1::ValidDisputeStatementKind::Explicit),
+						ValidatorIndex(0),
+						dummy_signature(),
+					),
+					(
+						v1::DisputeStatement::Invalid(v1::InvalidDisputeStatementKind::Explicit),
+						ValidatorIndex(1),
+						dummy_signature(),
+					),
+					(
+						v1::DisputeStatement::Valid(
+							v1::ValidDisputeStatementKind::ApprovalChecking,
+						),
+						ValidatorIndex(2),
+						dummy_signature(),
+					),
+				],
+			}],
+		};
+
+		assert_eq!(
+			OnChainVotes::from(votes),
+			OnChainVotes {
+				session: 7,
+				backing: vec![BackedCandidateVotes {
+					candidate_hash: receipt.hash().0,
+					para_id: 1.into(),
+					votes: vec![
+						BackingVote {
+							validator_index: ValidatorIndex(0),
+							kind: BackingVoteKind::Implicit,
+						},
+						BackingVote {
+							validator_index: ValidatorIndex(1),
+							kind: BackingVoteKind::Explicit,
+						},
+					],
+				}],
+				disputes: vec![DisputeVotes {
+					candidate_hash: Hash::repeat_byte(2),
+					session: 6,
+					valid: vec![ValidatorIndex(0), ValidatorIndex(2)],
+					invalid: vec![ValidatorIndex(1)],
+				}],
+			},
+		);
+	}
+
+	#[test]
+	fn session_info_is_converted() {
+		assert_eq!(
+			SessionInfo::from(session_info()),
+			SessionInfo {
+				active_validator_indices: vec![ValidatorIndex(0)],
+				random_seed: Hash::repeat_byte(1),
+				dispute_period: 12,
+				validators: vec![dummy_validator()],
+				discovery_keys: Vec::new(),
+				assignment_keys: Vec::new(),
+				validator_groups: vec![vec![ValidatorIndex(0)]],
+				n_cores: 1,
+				zeroth_delay_tranche_width: 2,
+				relay_vrf_modulo_samples: 3,
+				n_delay_tranches: 4,
+				no_show_slots: 5,
+				needed_approvals: 6,
+			},
+		);
+	}
+
+	#[test]
+	fn session_info_falls_back_before_version_2() {
+		for api_version in [0, 1] {
+			let info = session_info_at_version(
+				api_version,
+				|| panic!("`session_info` is not available before version 2"),
+				|| Ok(Some(old_session_info())),
+			)
+			.unwrap();
+
+			assert_eq!(info, Some(old_session_info().into()));
+		}
+	}
+
+	#[test]
+	fn session_info_is_fetched_from_version_2() {
+		let info = session_info_at_version(
+			2,
+			|| Ok(Some(session_info())),
+			|| panic!("`session_info_before_version_2` is not called from version 2"),
+		)
+		.unwrap();
+
+		assert_eq!(info, Some(session_info()));
+	}
+}
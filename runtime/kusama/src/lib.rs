@@ -24,13 +24,13 @@ use pallet_transaction_payment::CurrencyAdapter;
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use primitives::{
 	v1::{
-		AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent, CommittedCandidateReceipt,
-		CoreState, GroupRotationInfo, Hash, Id as ParaId, InboundDownwardMessage,
-		InboundHrmpMessage, Moment, Nonce, OccupiedCoreAssumption, PersistedValidationData,
-		ScrapedOnChainVotes, Signature, ValidationCode, ValidationCodeHash, ValidatorId,
-		ValidatorIndex,
+		AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent, CandidateHash,
+		CommittedCandidateReceipt, CoreState, GroupRotationInfo, Hash, Id as ParaId,
+		InboundDownwardMessage, InboundHrmpMessage, Moment, Nonce, OccupiedCoreAssumption,
+		PersistedValidationData, ScrapedOnChainVotes, Signature, ValidationCode,
+		ValidationCodeHash, ValidatorId, ValidatorIndex,
 	},
-	v2::SessionInfo,
+	v2::{slashing, InherentWeightInfo, SessionInfo},
 };
 use runtime_common::{
 	auctions, claims, crowdloan, impls::DealWithFees, paras_registrar, prod_or_fast, slots,
@@ -1248,7 +1248,7 @@ impl parachains_initializer::Config for Runtime {
 impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
-	type PunishValidators = ();
+	type SlashingHandler = ();
 	type WeightInfo = weights::runtime_parachains_disputes::WeightInfo<Runtime>;
 }
 
@@ -3154,6 +3154,34 @@ sp_api::impl_runtime_apis! {
 		{
 			parachains_runtime_api_impl::validation_code_hash::<Runtime>(para_id, assumption)
 		}
+
+		fn unapplied_slashes() -> Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)> {
+			Vec::new()
+		}
+
+		fn key_ownership_proof(
+			_validator_id: ValidatorId,
+		) -> Option<slashing::OpaqueKeyOwnershipProof> {
+			None
+		}
+
+		fn submit_report_dispute_lost(
+			_dispute_proof: slashing::DisputeProof,
+			_key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			None
+		}
+
+		fn inherent_weight_info() -> InherentWeightInfo {
+			parachains_runtime_api_impl::inherent_weight_info::<Runtime>()
+		}
+
+		fn submit_report_backing_misbehavior(
+			_misbehavior_proof: slashing::BackingMisbehaviorProof,
+			_key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			None
+		}
 	}
 
 	impl beefy_primitives::BeefyApi<Block> for Runtime {
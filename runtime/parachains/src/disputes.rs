@@ -38,6 +38,8 @@ use sp_std::{cmp::Ordering, prelude::*};
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod slashing;
+
 /// Whether the dispute is local or remote.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 pub enum DisputeLocation {
@@ -66,35 +68,72 @@ impl RewardValidators for () {
 }
 
 /// Punishment hooks for disputes.
-pub trait PunishValidators {
+///
+/// See [`slashing::SlashValidatorsForDisputes`] for an implementation reporting offences.
+pub trait SlashingHandler<BlockNumber> {
 	/// Punish a series of validators who were for an invalid parablock. This is expected to be a major
 	/// punishment.
 	fn punish_for_invalid(
 		session: SessionIndex,
-		validators: impl IntoIterator<Item = ValidatorIndex>,
+		candidate_hash: CandidateHash,
+		losers: impl IntoIterator<Item = ValidatorIndex>,
 	);
 
 	/// Punish a series of validators who were against a valid parablock. This is expected to be a minor
 	/// punishment.
 	fn punish_against_valid(
 		session: SessionIndex,
-		validators: impl IntoIterator<Item = ValidatorIndex>,
+		candidate_hash: CandidateHash,
+		losers: impl IntoIterator<Item = ValidatorIndex>,
 	);
 
 	/// Punish a series of validators who were part of a dispute which never concluded. This is expected
 	/// to be a minor punishment.
 	fn punish_inconclusive(
 		session: SessionIndex,
+		candidate_hash: CandidateHash,
 		validators: impl IntoIterator<Item = ValidatorIndex>,
 	);
+
+	/// Called by the initializer to initialize the slashing pallet.
+	fn initializer_initialize(now: BlockNumber) -> Weight;
+
+	/// Called by the initializer to finalize the slashing pallet.
+	fn initializer_finalize();
+
+	/// Called by the initializer to note that a new session has started.
+	fn initializer_on_new_session(session_index: SessionIndex);
 }
 
-impl PunishValidators for () {
-	fn punish_for_invalid(_: SessionIndex, _: impl IntoIterator<Item = ValidatorIndex>) {}
+impl<BlockNumber> SlashingHandler<BlockNumber> for () {
+	fn punish_for_invalid(
+		_: SessionIndex,
+		_: CandidateHash,
+		_: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+	}
+
+	fn punish_against_valid(
+		_: SessionIndex,
+		_: CandidateHash,
+		_: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+	}
 
-	fn punish_against_valid(_: SessionIndex, _: impl IntoIterator<Item = ValidatorIndex>) {}
+	fn punish_inconclusive(
+		_: SessionIndex,
+		_: CandidateHash,
+		_: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+	}
 
-	fn punish_inconclusive(_: SessionIndex, _: impl IntoIterator<Item = ValidatorIndex>) {}
+	fn initializer_initialize(_now: BlockNumber) -> Weight {
+		0
+	}
+
+	fn initializer_finalize() {}
+
+	fn initializer_on_new_session(_: SessionIndex) {}
 }
 
 /// Binary discriminator to determine if the expensive signature
@@ -373,7 +412,7 @@ pub mod pallet {
 	pub trait Config: frame_system::Config + configuration::Config + session_info::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type RewardValidators: RewardValidators;
-		type PunishValidators: PunishValidators;
+		type SlashingHandler: SlashingHandler<Self::BlockNumber>;
 
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
@@ -786,8 +825,9 @@ impl<T: Config> Pallet<T> {
 
 					// Slight punishment as these validators have failed to make data available to
 					// others in a timely manner.
-					T::PunishValidators::punish_inconclusive(
+					T::SlashingHandler::punish_inconclusive(
 						session_index,
+						candidate_hash,
 						participating.iter_ones().map(|i| ValidatorIndex(i as _)),
 					);
 				});
@@ -796,11 +836,13 @@ impl<T: Config> Pallet<T> {
 			}
 		}
 
-		weight
+		weight + T::SlashingHandler::initializer_initialize(now)
 	}
 
 	/// Called by the initializer to finalize the disputes pallet.
-	pub(crate) fn initializer_finalize() {}
+	pub(crate) fn initializer_finalize() {
+		T::SlashingHandler::initializer_finalize()
+	}
 
 	/// Called by the initializer to note a new session in the disputes pallet.
 	pub(crate) fn initializer_on_new_session(
@@ -808,6 +850,8 @@ impl<T: Config> Pallet<T> {
 	) {
 		let config = <configuration::Pallet<T>>::config();
 
+		T::SlashingHandler::initializer_on_new_session(notification.session_index);
+
 		if notification.session_index <= config.dispute_period + 1 {
 			return
 		}
@@ -1135,10 +1179,14 @@ impl<T: Config> Pallet<T> {
 		// Slash participants on a losing side.
 		{
 			// a valid candidate, according to 2/3. Punish those on the 'against' side.
-			T::PunishValidators::punish_against_valid(session, summary.slash_against);
+			T::SlashingHandler::punish_against_valid(
+				session,
+				candidate_hash,
+				summary.slash_against,
+			);
 
 			// an invalid candidate, according to 2/3. Punish those on the 'for' side.
-			T::PunishValidators::punish_for_invalid(session, summary.slash_for);
+			T::SlashingHandler::punish_for_invalid(session, candidate_hash, summary.slash_for);
 		}
 
 		<Disputes<T>>::insert(&session, &candidate_hash, &summary.state);
@@ -0,0 +1,1347 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Dispute slashing pallet.
+//!
+//! Once a dispute is concluded, we want to slash validators who were on the wrong side of the
+//! dispute. The slashing amount depends on whether the candidate was valid (small) or invalid
+//! (big). In addition to that, we might want to kick out the validators from the active set.
+//!
+//! The `offences` pallet from Substrate provides us with a way to do both. Currently, the
+//! interface expects us to provide staking information including nominator exposure in order to
+//! slash the offenders. The `session` pallet does not store that information for past sessions,
+//! instead it only keeps track of a rolling window of past validator set hashes. Hence, we need
+//! a key ownership proof of the offending validators, which we can not produce from within the
+//! runtime, as disputes can conclude long after the session they were raised in.
+//!
+//! Losers of a dispute are therefore recorded as [`PendingSlashes`] and the actual offence is
+//! only reported, once some node submits a valid key ownership proof for a losing validator via
+//! an unsigned transaction, see [`Pallet::report_dispute_lost_unsigned`]. Nodes learn about
+//! pending slashes via the `unapplied_slashes` runtime API.
+//!
+//! Validators issuing conflicting backing statements are caught by the statement table of
+//! candidate backing. The two signed statements are self-contained evidence of the misbehavior,
+//! which any node can report along with a key ownership proof of the offender, see
+//! [`Pallet::report_backing_misbehavior_unsigned`].
+
+use crate::disputes;
+use frame_support::{
+	traits::{Get, KeyOwnerProofSystem},
+	weights::{
+		constants::{WEIGHT_PER_MICROS, WEIGHT_PER_NANOS},
+		Pays, Weight,
+	},
+};
+use primitives::{
+	v1::{CandidateHash, SessionIndex, ValidatorId, ValidatorIndex},
+	v2::slashing::{
+		BackingMisbehaviorProof, BackingTimeSlot, DisputeProof, DisputesTimeSlot, PendingSlashes,
+		SlashingOffenceKind,
+	},
+};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
+		TransactionValidityError, ValidTransaction,
+	},
+	DispatchResult, KeyTypeId, Perbill, RuntimeDebug,
+};
+use sp_session::{GetSessionNumber, GetValidatorCount};
+use sp_staking::offence::{Kind, Offence, OffenceError, ReportOffence};
+use sp_std::{
+	collections::{btree_map::Entry, btree_set::BTreeSet},
+	prelude::*,
+};
+
+const LOG_TARGET: &str = "runtime::parachains::slashing";
+
+/// The number of validators in the session an offence was committed in.
+pub type ValidatorSetCount = u32;
+
+/// Offence data shared by both kinds of dispute offences.
+#[derive(RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Clone, PartialEq, Eq))]
+pub struct SlashingOffence<KeyOwnerIdentification> {
+	/// The size of the validator set in that session.
+	pub validator_set_count: ValidatorSetCount,
+	/// Should be unique per dispute.
+	pub time_slot: DisputesTimeSlot,
+	/// Staking information about the validators that lost in a dispute
+	/// to be passed to the offences pallet.
+	pub offenders: Vec<KeyOwnerIdentification>,
+}
+
+impl<KeyOwnerIdentification> SlashingOffence<KeyOwnerIdentification> {
+	fn new(
+		session_index: SessionIndex,
+		candidate_hash: CandidateHash,
+		validator_set_count: ValidatorSetCount,
+		offenders: Vec<KeyOwnerIdentification>,
+	) -> Self {
+		let time_slot = DisputesTimeSlot::new(session_index, candidate_hash);
+		Self { time_slot, validator_set_count, offenders }
+	}
+}
+
+/// Offence of a validator who voted for an invalid candidate, e.g. by backing or approving it.
+#[derive(RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Clone, PartialEq, Eq))]
+pub struct ForInvalidOffence<KeyOwnerIdentification>(pub SlashingOffence<KeyOwnerIdentification>);
+
+/// Offence of a validator who disputed a valid candidate.
+#[derive(RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Clone, PartialEq, Eq))]
+pub struct AgainstValidOffence<KeyOwnerIdentification>(
+	pub SlashingOffence<KeyOwnerIdentification>,
+);
+
+macro_rules! impl_slashing_offence {
+	($offence:ident, $id:expr, $slash_fraction:expr) => {
+		impl<Offender> Offence<Offender> for $offence<Offender>
+		where
+			Offender: Clone,
+		{
+			const ID: Kind = $id;
+
+			type TimeSlot = DisputesTimeSlot;
+
+			fn offenders(&self) -> Vec<Offender> {
+				self.0.offenders.clone()
+			}
+
+			fn session_index(&self) -> SessionIndex {
+				self.0.time_slot.session_index
+			}
+
+			fn validator_set_count(&self) -> ValidatorSetCount {
+				self.0.validator_set_count
+			}
+
+			fn time_slot(&self) -> Self::TimeSlot {
+				self.0.time_slot.clone()
+			}
+
+			fn slash_fraction(_offenders: u32, _validator_set_count: u32) -> Perbill {
+				$slash_fraction
+			}
+		}
+	};
+}
+
+// Backing or approving an invalid candidate is as bad as it gets, slash the full stake.
+impl_slashing_offence!(ForInvalidOffence, *b"disputes:invalid", Perbill::from_percent(100));
+
+// Disputing a valid candidate wastes resources of the network, but does not put finality at risk.
+// The offence gets reported, but no stake is being slashed.
+impl_slashing_offence!(AgainstValidOffence, *b"disputes:against", Perbill::zero());
+
+/// Offence of a validator who issued conflicting backing statements.
+#[derive(RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Clone, PartialEq, Eq))]
+pub struct BackingMisbehaviorOffence<KeyOwnerIdentification> {
+	/// The size of the validator set in that session.
+	pub validator_set_count: ValidatorSetCount,
+	/// The signing context of the conflicting statements.
+	pub time_slot: BackingTimeSlot,
+	/// Staking information about the misbehaving validators to be passed to the offences pallet.
+	pub offenders: Vec<KeyOwnerIdentification>,
+}
+
+impl<Offender: Clone> Offence<Offender> for BackingMisbehaviorOffence<Offender> {
+	const ID: Kind = *b"backing:misbehav";
+
+	type TimeSlot = BackingTimeSlot;
+
+	fn offenders(&self) -> Vec<Offender> {
+		self.offenders.clone()
+	}
+
+	fn session_index(&self) -> SessionIndex {
+		self.time_slot.session_index
+	}
+
+	fn validator_set_count(&self) -> ValidatorSetCount {
+		self.validator_set_count
+	}
+
+	fn time_slot(&self) -> Self::TimeSlot {
+		self.time_slot.clone()
+	}
+
+	fn slash_fraction(_offenders: u32, _validator_set_count: u32) -> Perbill {
+		// Seconding multiple candidates is never accidental, so it is punished as hard as backing
+		// an invalid candidate.
+		Perbill::from_percent(100)
+	}
+}
+
+/// This type implements `SlashingHandler` by recording pending slashes for losers of a dispute,
+/// which are turned into offences once a key ownership proof got submitted.
+pub struct SlashValidatorsForDisputes<C> {
+	_phantom: sp_std::marker::PhantomData<C>,
+}
+
+impl<C> Default for SlashValidatorsForDisputes<C> {
+	fn default() -> Self {
+		Self { _phantom: Default::default() }
+	}
+}
+
+impl<T: Config> SlashValidatorsForDisputes<Pallet<T>> {
+	/// Record the losers of a dispute, so they can be reported once a key ownership proof has
+	/// been provided.
+	fn do_punish(
+		session_index: SessionIndex,
+		candidate_hash: CandidateHash,
+		kind: SlashingOffenceKind,
+		losers: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		let losers: BTreeSet<_> = losers.into_iter().collect();
+		if losers.is_empty() {
+			return
+		}
+
+		let session_info = match crate::session_info::Pallet::<T>::session_info(session_index) {
+			Some(info) => info,
+			None => {
+				log::warn!(
+					target: LOG_TARGET,
+					"Missing session info for session {}, can not punish dispute losers",
+					session_index,
+				);
+				return
+			},
+		};
+
+		let keys = losers
+			.into_iter()
+			.filter_map(|i| session_info.validators.get(i.0 as usize).cloned().map(|id| (i, id)));
+
+		<UnappliedSlashes<T>>::mutate(session_index, candidate_hash, |pending| match pending {
+			Some(pending) if pending.kind == kind => pending.keys.extend(keys),
+			Some(_) => {
+				// A dispute concluding in both directions requires more than a byzantine
+				// threshold of double votes, the `ForInvalid` offence takes precedence.
+				if kind == SlashingOffenceKind::ForInvalid {
+					*pending = Some(PendingSlashes { keys: keys.collect(), kind });
+				}
+			},
+			None => *pending = Some(PendingSlashes { keys: keys.collect(), kind }),
+		});
+	}
+}
+
+impl<T: Config> disputes::SlashingHandler<T::BlockNumber>
+	for SlashValidatorsForDisputes<Pallet<T>>
+{
+	fn punish_for_invalid(
+		session_index: SessionIndex,
+		candidate_hash: CandidateHash,
+		losers: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		Self::do_punish(session_index, candidate_hash, SlashingOffenceKind::ForInvalid, losers);
+	}
+
+	fn punish_against_valid(
+		session_index: SessionIndex,
+		candidate_hash: CandidateHash,
+		losers: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		Self::do_punish(session_index, candidate_hash, SlashingOffenceKind::AgainstValid, losers);
+	}
+
+	fn punish_inconclusive(
+		_session_index: SessionIndex,
+		_candidate_hash: CandidateHash,
+		_validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		// Inconclusive disputes are most likely spam or caused by unavailability, neither of
+		// which we can attribute to a single validator with certainty.
+	}
+
+	fn initializer_initialize(now: T::BlockNumber) -> Weight {
+		Pallet::<T>::initializer_initialize(now)
+	}
+
+	fn initializer_finalize() {
+		Pallet::<T>::initializer_finalize()
+	}
+
+	fn initializer_on_new_session(session_index: SessionIndex) {
+		Pallet::<T>::initializer_on_new_session(session_index)
+	}
+}
+
+/// A trait that defines methods to report an offence (after the slashing report has been
+/// validated) and for submitting a transaction to report a slash (from an offchain context).
+pub trait HandleReports<T: Config> {
+	/// The longevity, in blocks, that the offence report is valid for. When using the staking
+	/// pallet this should be equal to the bonding duration (in blocks, not eras).
+	type ReportLongevity: Get<u64>;
+
+	/// Report an offence.
+	fn report_offence(
+		kind: SlashingOffenceKind,
+		offence: SlashingOffence<T::KeyOwnerIdentification>,
+	) -> Result<(), OffenceError>;
+
+	/// Returns true if the offenders at the given time slot has already been reported.
+	fn is_known_offence(
+		kind: SlashingOffenceKind,
+		offenders: &[T::KeyOwnerIdentification],
+		time_slot: &DisputesTimeSlot,
+	) -> bool;
+
+	/// Create and dispatch a slashing report extrinsic.
+	/// This should be called offchain.
+	fn submit_unsigned_slashing_report(
+		dispute_proof: DisputeProof,
+		key_owner_proof: T::KeyOwnerProof,
+	) -> DispatchResult;
+
+	/// Report a backing misbehavior offence.
+	fn report_backing_offence(
+		offence: BackingMisbehaviorOffence<T::KeyOwnerIdentification>,
+	) -> Result<(), OffenceError>;
+
+	/// Returns true if the backing misbehavior of the offenders at the given time slot has
+	/// already been reported.
+	fn is_known_backing_offence(
+		offenders: &[T::KeyOwnerIdentification],
+		time_slot: &BackingTimeSlot,
+	) -> bool;
+
+	/// Create and dispatch a backing misbehavior report extrinsic.
+	/// This should be called offchain.
+	fn submit_unsigned_backing_report(
+		misbehavior_proof: BackingMisbehaviorProof,
+		key_owner_proof: T::KeyOwnerProof,
+	) -> DispatchResult;
+}
+
+impl<T: Config> HandleReports<T> for () {
+	type ReportLongevity = ();
+
+	fn report_offence(
+		_kind: SlashingOffenceKind,
+		_offence: SlashingOffence<T::KeyOwnerIdentification>,
+	) -> Result<(), OffenceError> {
+		Ok(())
+	}
+
+	fn is_known_offence(
+		_kind: SlashingOffenceKind,
+		_offenders: &[T::KeyOwnerIdentification],
+		_time_slot: &DisputesTimeSlot,
+	) -> bool {
+		true
+	}
+
+	fn submit_unsigned_slashing_report(
+		_dispute_proof: DisputeProof,
+		_key_owner_proof: T::KeyOwnerProof,
+	) -> DispatchResult {
+		Ok(())
+	}
+
+	fn report_backing_offence(
+		_offence: BackingMisbehaviorOffence<T::KeyOwnerIdentification>,
+	) -> Result<(), OffenceError> {
+		Ok(())
+	}
+
+	fn is_known_backing_offence(
+		_offenders: &[T::KeyOwnerIdentification],
+		_time_slot: &BackingTimeSlot,
+	) -> bool {
+		true
+	}
+
+	fn submit_unsigned_backing_report(
+		_misbehavior_proof: BackingMisbehaviorProof,
+		_key_owner_proof: T::KeyOwnerProof,
+	) -> DispatchResult {
+		Ok(())
+	}
+}
+
+/// An implementation of `HandleReports` backed by an offences reporting system like
+/// `pallet_offences`.
+///
+/// `R` is the offence reporter, `L` the report longevity.
+pub struct SlashingReportHandler<I, R, L> {
+	_phantom: sp_std::marker::PhantomData<(I, R, L)>,
+}
+
+impl<I, R, L> Default for SlashingReportHandler<I, R, L> {
+	fn default() -> Self {
+		Self { _phantom: Default::default() }
+	}
+}
+
+impl<T, R, L> HandleReports<T> for SlashingReportHandler<T::KeyOwnerIdentification, R, L>
+where
+	T: Config + frame_system::offchain::SendTransactionTypes<Call<T>>,
+	R: ReportOffence<
+			T::AccountId,
+			T::KeyOwnerIdentification,
+			ForInvalidOffence<T::KeyOwnerIdentification>,
+		> + ReportOffence<
+			T::AccountId,
+			T::KeyOwnerIdentification,
+			AgainstValidOffence<T::KeyOwnerIdentification>,
+		> + ReportOffence<
+			T::AccountId,
+			T::KeyOwnerIdentification,
+			BackingMisbehaviorOffence<T::KeyOwnerIdentification>,
+		>,
+	L: Get<u64>,
+{
+	type ReportLongevity = L;
+
+	fn report_offence(
+		kind: SlashingOffenceKind,
+		offence: SlashingOffence<T::KeyOwnerIdentification>,
+	) -> Result<(), OffenceError> {
+		let reporters = Vec::new();
+		match kind {
+			SlashingOffenceKind::ForInvalid =>
+				R::report_offence(reporters, ForInvalidOffence(offence)),
+			SlashingOffenceKind::AgainstValid =>
+				R::report_offence(reporters, AgainstValidOffence(offence)),
+		}
+	}
+
+	fn is_known_offence(
+		kind: SlashingOffenceKind,
+		offenders: &[T::KeyOwnerIdentification],
+		time_slot: &DisputesTimeSlot,
+	) -> bool {
+		match kind {
+			SlashingOffenceKind::ForInvalid => <R as ReportOffence<
+				T::AccountId,
+				T::KeyOwnerIdentification,
+				ForInvalidOffence<T::KeyOwnerIdentification>,
+			>>::is_known_offence(offenders, time_slot),
+			SlashingOffenceKind::AgainstValid => <R as ReportOffence<
+				T::AccountId,
+				T::KeyOwnerIdentification,
+				AgainstValidOffence<T::KeyOwnerIdentification>,
+			>>::is_known_offence(offenders, time_slot),
+		}
+	}
+
+	fn submit_unsigned_slashing_report(
+		dispute_proof: DisputeProof,
+		key_owner_proof: <T as Config>::KeyOwnerProof,
+	) -> DispatchResult {
+		use frame_system::offchain::SubmitTransaction;
+
+		let session_index = dispute_proof.time_slot.session_index;
+		let validator_index = dispute_proof.validator_index.0;
+		let kind = dispute_proof.kind;
+
+		let call = Call::report_dispute_lost_unsigned {
+			dispute_proof: Box::new(dispute_proof),
+			key_owner_proof,
+		};
+
+		match SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into()) {
+			Ok(()) => log::info!(
+				target: LOG_TARGET,
+				"Submitted dispute slashing report, session({}), index({}), kind({:?})",
+				session_index,
+				validator_index,
+				kind,
+			),
+			Err(()) => log::error!(
+				target: LOG_TARGET,
+				"Error submitting dispute slashing report, session({}), index({}), kind({:?})",
+				session_index,
+				validator_index,
+				kind,
+			),
+		}
+
+		Ok(())
+	}
+
+	fn report_backing_offence(
+		offence: BackingMisbehaviorOffence<T::KeyOwnerIdentification>,
+	) -> Result<(), OffenceError> {
+		R::report_offence(Vec::new(), offence)
+	}
+
+	fn is_known_backing_offence(
+		offenders: &[T::KeyOwnerIdentification],
+		time_slot: &BackingTimeSlot,
+	) -> bool {
+		<R as ReportOffence<
+			T::AccountId,
+			T::KeyOwnerIdentification,
+			BackingMisbehaviorOffence<T::KeyOwnerIdentification>,
+		>>::is_known_offence(offenders, time_slot)
+	}
+
+	fn submit_unsigned_backing_report(
+		misbehavior_proof: BackingMisbehaviorProof,
+		key_owner_proof: <T as Config>::KeyOwnerProof,
+	) -> DispatchResult {
+		use frame_system::offchain::SubmitTransaction;
+
+		let session_index = misbehavior_proof.time_slot.session_index;
+		let validator_index = misbehavior_proof.validator_index.0;
+
+		let call = Call::report_backing_misbehavior_unsigned {
+			misbehavior_proof: Box::new(misbehavior_proof),
+			key_owner_proof,
+		};
+
+		match SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into()) {
+			Ok(()) => log::info!(
+				target: LOG_TARGET,
+				"Submitted backing misbehavior report, session({}), index({})",
+				session_index,
+				validator_index,
+			),
+			Err(()) => log::error!(
+				target: LOG_TARGET,
+				"Error submitting backing misbehavior report, session({}), index({})",
+				session_index,
+				validator_index,
+			),
+		}
+
+		Ok(())
+	}
+}
+
+pub trait WeightInfo {
+	fn report_dispute_lost(validator_count: ValidatorSetCount) -> Weight;
+	fn report_backing_misbehavior(validator_count: ValidatorSetCount) -> Weight;
+}
+
+pub struct TestWeightInfo;
+impl WeightInfo for TestWeightInfo {
+	fn report_dispute_lost(_validator_count: ValidatorSetCount) -> Weight {
+		0
+	}
+	fn report_backing_misbehavior(_validator_count: ValidatorSetCount) -> Weight {
+		0
+	}
+}
+
+/// Weights of the slashing reports, estimated like those of the BABE and GRANDPA equivocation
+/// reports, which likewise check a key ownership proof and report an offence.
+pub struct EstimatedWeightInfo<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: frame_system::Config> EstimatedWeightInfo<T> {
+	fn check_proof_and_report_offence(validator_count: ValidatorSetCount) -> Weight {
+		// we take the validator set count from the membership proof to
+		// calculate the weight but we set a floor of 100 validators.
+		let validator_count = validator_count.max(100) as Weight;
+
+		// worst case we are considering is that the given offender
+		// is backed by 200 nominators
+		const MAX_NOMINATORS: Weight = 200;
+
+		// checking membership proof
+		(35 * WEIGHT_PER_MICROS)
+			.saturating_add((175 * WEIGHT_PER_NANOS).saturating_mul(validator_count))
+			.saturating_add(T::DbWeight::get().reads(5))
+			// report offence
+			.saturating_add(110 * WEIGHT_PER_MICROS)
+			.saturating_add(25 * WEIGHT_PER_MICROS * MAX_NOMINATORS)
+			.saturating_add(T::DbWeight::get().reads(14 + 3 * MAX_NOMINATORS))
+			.saturating_add(T::DbWeight::get().writes(10 + 3 * MAX_NOMINATORS))
+	}
+}
+
+impl<T: frame_system::Config> WeightInfo for EstimatedWeightInfo<T> {
+	fn report_dispute_lost(validator_count: ValidatorSetCount) -> Weight {
+		Self::check_proof_and_report_offence(validator_count)
+			// removing the pending slash
+			.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+	}
+	fn report_backing_misbehavior(validator_count: ValidatorSetCount) -> Weight {
+		Self::check_proof_and_report_offence(validator_count)
+			// reading the session info and checking both signatures
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(95 * WEIGHT_PER_MICROS)
+	}
+}
+
+pub use pallet::*;
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + crate::disputes::Config {
+		/// The proof of key ownership, used for validating slashing reports.
+		/// The proof must include the session index and validator count of the
+		/// session at which the offence occurred.
+		type KeyOwnerProof: Parameter + GetSessionNumber + GetValidatorCount;
+
+		/// The identification of a key owner, used when reporting slashes.
+		type KeyOwnerIdentification: Parameter;
+
+		/// A system for proving ownership of keys, i.e. that a given key was part
+		/// of a validator set, needed for validating slashing reports.
+		type KeyOwnerProofSystem: KeyOwnerProofSystem<
+			(KeyTypeId, ValidatorId),
+			Proof = Self::KeyOwnerProof,
+			IdentificationTuple = Self::KeyOwnerIdentification,
+		>;
+
+		/// The slashing report handling subsystem, defines methods to report an
+		/// offence (after the slashing report has been validated) and for submitting a
+		/// transaction to report a slash (from an offchain context).
+		/// NOTE: when enabling slashing report handling (i.e. this type isn't set to
+		/// `()`) you must use this pallet's `ValidateUnsigned` in the runtime
+		/// definition.
+		type HandleReports: HandleReports<Self>;
+
+		/// Priority of unsigned slashing reports in the transaction pool.
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	/// Validators pending dispute slashes.
+	#[pallet::storage]
+	pub(super) type UnappliedSlashes<T> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		SessionIndex,
+		Blake2_128Concat,
+		CandidateHash,
+		PendingSlashes,
+	>;
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The key ownership proof is invalid.
+		InvalidKeyOwnershipProof,
+		/// The session index is too old or invalid.
+		InvalidSessionIndex,
+		/// The candidate hash is invalid.
+		InvalidCandidateHash,
+		/// There is no pending slash for the given validator index and time
+		/// slot.
+		InvalidValidatorIndex,
+		/// The validator index does not match the validator id.
+		ValidatorIndexIdMismatch,
+		/// The given slashing report is valid but already previously reported.
+		DuplicateSlashingReport,
+		/// The backing statements do not conflict or are not signed by the validator.
+		InvalidBackingMisbehaviorProof,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Report a validator who lost a dispute, proven by `dispute_proof` and a proof that the
+		/// validator was part of the validator set in the session of the dispute.
+		///
+		/// Unsigned, as any node can submit reports for pending slashes.
+		#[pallet::weight(<T as Config>::WeightInfo::report_dispute_lost(
+			key_owner_proof.validator_count()
+		))]
+		pub fn report_dispute_lost_unsigned(
+			origin: OriginFor<T>,
+			// box to decrease the size of the call
+			dispute_proof: Box<DisputeProof>,
+			key_owner_proof: T::KeyOwnerProof,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+
+			let session_index = dispute_proof.time_slot.session_index;
+			let candidate_hash = dispute_proof.time_slot.candidate_hash;
+
+			// the validator set count must be taken from the session of the dispute
+			if key_owner_proof.session() != session_index {
+				return Err(Error::<T>::InvalidSessionIndex.into())
+			}
+			let validator_set_count = key_owner_proof.validator_count() as ValidatorSetCount;
+			// check the membership proof to extract the offender's id
+			let key = (primitives::v1::PARACHAIN_KEY_TYPE_ID, dispute_proof.validator_id.clone());
+			let offender = T::KeyOwnerProofSystem::check_proof(key, key_owner_proof)
+				.ok_or(Error::<T>::InvalidKeyOwnershipProof)?;
+
+			// check that there is a pending slash for the given
+			// validator index and candidate hash
+			let try_remove = |v: &mut Option<PendingSlashes>| -> DispatchResult {
+				let pending = v.as_mut().ok_or(Error::<T>::InvalidCandidateHash)?;
+				if pending.kind != dispute_proof.kind {
+					return Err(Error::<T>::InvalidCandidateHash.into())
+				}
+
+				match pending.keys.entry(dispute_proof.validator_index) {
+					Entry::Vacant(_) => return Err(Error::<T>::InvalidValidatorIndex.into()),
+					// check that `validator_index` matches `validator_id`
+					Entry::Occupied(e) if e.get() != &dispute_proof.validator_id =>
+						return Err(Error::<T>::ValidatorIndexIdMismatch.into()),
+					Entry::Occupied(e) => {
+						e.remove(); // the report is correct
+					},
+				}
+
+				// if the last validator is slashed for this dispute, clean up the storage
+				if pending.keys.is_empty() {
+					*v = None;
+				}
+
+				Ok(())
+			};
+
+			<UnappliedSlashes<T>>::try_mutate_exists(&session_index, &candidate_hash, try_remove)?;
+
+			let offence = SlashingOffence::new(
+				session_index,
+				candidate_hash,
+				validator_set_count,
+				vec![offender],
+			);
+
+			<T::HandleReports as HandleReports<T>>::report_offence(dispute_proof.kind, offence)
+				.map_err(|_| Error::<T>::DuplicateSlashingReport)?;
+
+			Ok(Pays::No.into())
+		}
+
+		/// Report a validator who issued conflicting backing statements, proven by
+		/// `misbehavior_proof` and a proof that the validator was part of the validator set in
+		/// the session the statements were signed in.
+		///
+		/// Unsigned, as any node can submit reports of misbehavior it witnessed.
+		#[pallet::weight(<T as Config>::WeightInfo::report_backing_misbehavior(
+			key_owner_proof.validator_count()
+		))]
+		pub fn report_backing_misbehavior_unsigned(
+			origin: OriginFor<T>,
+			// box to decrease the size of the call
+			misbehavior_proof: Box<BackingMisbehaviorProof>,
+			key_owner_proof: T::KeyOwnerProof,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+
+			let validator_id = Self::check_backing_misbehavior(&misbehavior_proof)?;
+
+			// the validator set count must be taken from the session of the statements
+			if key_owner_proof.session() != misbehavior_proof.time_slot.session_index {
+				return Err(Error::<T>::InvalidSessionIndex.into())
+			}
+			let validator_set_count = key_owner_proof.validator_count() as ValidatorSetCount;
+			// check the membership proof to extract the offender's id
+			let key = (primitives::v1::PARACHAIN_KEY_TYPE_ID, validator_id);
+			let offender = T::KeyOwnerProofSystem::check_proof(key, key_owner_proof)
+				.ok_or(Error::<T>::InvalidKeyOwnershipProof)?;
+
+			let offence = BackingMisbehaviorOffence {
+				validator_set_count,
+				time_slot: misbehavior_proof.time_slot,
+				offenders: vec![offender],
+			};
+
+			<T::HandleReports as HandleReports<T>>::report_backing_offence(offence)
+				.map_err(|_| Error::<T>::DuplicateSlashingReport)?;
+
+			Ok(Pays::No.into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+		fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			Self::validate_unsigned(source, call)
+		}
+
+		fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+			Self::pre_dispatch(call)
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Called by the initializer to initialize the slashing pallet.
+	fn initializer_initialize(_now: T::BlockNumber) -> Weight {
+		0
+	}
+
+	/// Called by the initializer to finalize the slashing pallet.
+	fn initializer_finalize() {}
+
+	/// Called by the initializer to note a new session in the slashing pallet.
+	fn initializer_on_new_session(session_index: SessionIndex) {
+		// This should be small, as disputes are limited by spam slots, so no limit is fine.
+		const REMOVE_LIMIT: Option<u32> = None;
+
+		let config = <crate::configuration::Pallet<T>>::config();
+		if session_index <= config.dispute_period + 1 {
+			return
+		}
+
+		let old_session = session_index - config.dispute_period - 1;
+		<UnappliedSlashes<T>>::remove_prefix(old_session, REMOVE_LIMIT);
+	}
+
+	/// All slashes still waiting for a key ownership proof.
+	pub(crate) fn unapplied_slashes() -> Vec<(SessionIndex, CandidateHash, PendingSlashes)> {
+		<UnappliedSlashes<T>>::iter().collect()
+	}
+
+	/// Submits a slashing report for a lost dispute as an unsigned transaction.
+	///
+	/// This function expects an offchain context and cannot be callable from the on-chain logic.
+	pub(crate) fn submit_unsigned_slashing_report(
+		dispute_proof: DisputeProof,
+		key_owner_proof: T::KeyOwnerProof,
+	) -> Option<()> {
+		<T::HandleReports as HandleReports<T>>::submit_unsigned_slashing_report(
+			dispute_proof,
+			key_owner_proof,
+		)
+		.ok()
+	}
+
+	/// Submits a backing misbehavior report as an unsigned transaction.
+	///
+	/// This function expects an offchain context and cannot be callable from the on-chain logic.
+	pub(crate) fn submit_unsigned_backing_report(
+		misbehavior_proof: BackingMisbehaviorProof,
+		key_owner_proof: T::KeyOwnerProof,
+	) -> Option<()> {
+		<T::HandleReports as HandleReports<T>>::submit_unsigned_backing_report(
+			misbehavior_proof,
+			key_owner_proof,
+		)
+		.ok()
+	}
+
+	/// Verify evidence of conflicting backing statements against the session info of the
+	/// session they were signed in, returning the key of the misbehaving validator.
+	fn check_backing_misbehavior(
+		misbehavior_proof: &BackingMisbehaviorProof,
+	) -> Result<ValidatorId, Error<T>> {
+		let session_index = misbehavior_proof.time_slot.session_index;
+		let session_info = crate::session_info::Pallet::<T>::session_info(session_index)
+			.ok_or(Error::<T>::InvalidSessionIndex)?;
+
+		let validator_id = session_info
+			.validators
+			.get(misbehavior_proof.validator_index.0 as usize)
+			.cloned()
+			.ok_or(Error::<T>::InvalidValidatorIndex)?;
+
+		if misbehavior_proof.check(&validator_id) {
+			Ok(validator_id)
+		} else {
+			Err(Error::<T>::InvalidBackingMisbehaviorProof)
+		}
+	}
+}
+
+/// Methods for the `ValidateUnsigned` implementation:
+///
+/// It restricts calls to `report_dispute_lost_unsigned` and `report_backing_misbehavior_unsigned`
+/// to local calls (i.e. extrinsics generated on this node) or that already in a block. This
+/// guarantees that only block authors can include unsigned slashing reports.
+impl<T: Config> Pallet<T> {
+	pub fn validate_unsigned(source: TransactionSource, call: &Call<T>) -> TransactionValidity {
+		// discard slashing report not coming from the local node
+		match source {
+			TransactionSource::Local | TransactionSource::InBlock => { /* allowed */ },
+			_ => {
+				log::warn!(
+					target: LOG_TARGET,
+					"rejecting unsigned transaction because it is not local/in-block."
+				);
+
+				return InvalidTransaction::Call.into()
+			},
+		}
+
+		let longevity = <T::HandleReports as HandleReports<T>>::ReportLongevity::get();
+
+		match call {
+			Call::report_dispute_lost_unsigned { dispute_proof, key_owner_proof } => {
+				// check report staleness
+				is_known_offence::<T>(dispute_proof, key_owner_proof)?;
+
+				let tag_prefix = match dispute_proof.kind {
+					SlashingOffenceKind::ForInvalid => "DisputeForInvalid",
+					SlashingOffenceKind::AgainstValid => "DisputeAgainstValid",
+				};
+
+				ValidTransaction::with_tag_prefix(tag_prefix)
+					.priority(T::UnsignedPriority::get())
+					// Only one report for the same offender at the same slot.
+					.and_provides((
+						dispute_proof.time_slot.clone(),
+						dispute_proof.validator_id.clone(),
+					))
+					.longevity(longevity)
+					// We don't propagate this. This can never be included on a remote node.
+					.propagate(false)
+					.build()
+			},
+			Call::report_backing_misbehavior_unsigned { misbehavior_proof, key_owner_proof } => {
+				// check report staleness
+				is_known_backing_offence::<T>(misbehavior_proof, key_owner_proof)?;
+
+				ValidTransaction::with_tag_prefix("BackingMisbehavior")
+					.priority(T::UnsignedPriority::get())
+					// Only one report for the same offender at the same slot.
+					.and_provides((
+						misbehavior_proof.time_slot.clone(),
+						misbehavior_proof.validator_index,
+					))
+					.longevity(longevity)
+					// We don't propagate this. This can never be included on a remote node.
+					.propagate(false)
+					.build()
+			},
+			_ => InvalidTransaction::Call.into(),
+		}
+	}
+
+	pub fn pre_dispatch(call: &Call<T>) -> Result<(), TransactionValidityError> {
+		match call {
+			Call::report_dispute_lost_unsigned { dispute_proof, key_owner_proof } =>
+				is_known_offence::<T>(dispute_proof, key_owner_proof),
+			Call::report_backing_misbehavior_unsigned { misbehavior_proof, key_owner_proof } =>
+				is_known_backing_offence::<T>(misbehavior_proof, key_owner_proof),
+			_ => Err(InvalidTransaction::Call.into()),
+		}
+	}
+}
+
+fn is_known_offence<T: Config>(
+	dispute_proof: &DisputeProof,
+	key_owner_proof: &T::KeyOwnerProof,
+) -> Result<(), TransactionValidityError> {
+	if key_owner_proof.session() != dispute_proof.time_slot.session_index {
+		return Err(InvalidTransaction::BadProof.into())
+	}
+
+	// check the membership proof to extract the offender's id
+	let key = (primitives::v1::PARACHAIN_KEY_TYPE_ID, dispute_proof.validator_id.clone());
+
+	let offender = T::KeyOwnerProofSystem::check_proof(key, key_owner_proof.clone())
+		.ok_or(InvalidTransaction::BadProof)?;
+
+	// check if the offence has already been reported,
+	// and if so then we can discard the report.
+	let is_known_offence = <T::HandleReports as HandleReports<T>>::is_known_offence(
+		dispute_proof.kind,
+		&[offender],
+		&dispute_proof.time_slot,
+	);
+
+	if is_known_offence {
+		Err(InvalidTransaction::Stale.into())
+	} else {
+		Ok(())
+	}
+}
+
+fn is_known_backing_offence<T: Config>(
+	misbehavior_proof: &BackingMisbehaviorProof,
+	key_owner_proof: &T::KeyOwnerProof,
+) -> Result<(), TransactionValidityError> {
+	if key_owner_proof.session() != misbehavior_proof.time_slot.session_index {
+		return Err(InvalidTransaction::BadProof.into())
+	}
+
+	let validator_id = Pallet::<T>::check_backing_misbehavior(misbehavior_proof)
+		.map_err(|_| InvalidTransaction::BadProof)?;
+
+	// check the membership proof to extract the offender's id
+	let key = (primitives::v1::PARACHAIN_KEY_TYPE_ID, validator_id);
+
+	let offender = T::KeyOwnerProofSystem::check_proof(key, key_owner_proof.clone())
+		.ok_or(InvalidTransaction::BadProof)?;
+
+	// check if the offence has already been reported,
+	// and if so then we can discard the report.
+	let is_known_offence = <T::HandleReports as HandleReports<T>>::is_known_backing_offence(
+		&[offender],
+		&misbehavior_proof.time_slot,
+	);
+
+	if is_known_offence {
+		Err(InvalidTransaction::Stale.into())
+	} else {
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		disputes::SlashingHandler,
+		mock::{new_test_ext, MockGenesisConfig, Origin, Test},
+		session_info,
+	};
+	use frame_support::{assert_noop, assert_ok};
+	use keyring::Sr25519Keyring;
+	use parity_scale_codec::Encode;
+	use primitives::v2::SessionInfo;
+	use sp_session::MembershipProof;
+
+	type Slash = SlashValidatorsForDisputes<Pallet<Test>>;
+
+	fn insert_session_info(session: SessionIndex, validators: &[Sr25519Keyring]) {
+		let validators: Vec<ValidatorId> = validators.iter().map(|v| v.public().into()).collect();
+		let info = SessionInfo {
+			active_validator_indices: (0..validators.len() as u32).map(ValidatorIndex).collect(),
+			random_seed: [0u8; 32],
+			dispute_period: 6,
+			validators,
+			discovery_keys: Vec::new(),
+			assignment_keys: Vec::new(),
+			validator_groups: Vec::new(),
+			n_cores: 0,
+			zeroth_delay_tranche_width: 0,
+			relay_vrf_modulo_samples: 0,
+			n_delay_tranches: 0,
+			no_show_slots: 0,
+			needed_approvals: 0,
+		};
+		session_info::Sessions::<Test>::insert(session, info);
+	}
+
+	#[test]
+	fn losers_are_recorded_as_pending_slashes() {
+		new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+			let validators = [Sr25519Keyring::Alice, Sr25519Keyring::Bob, Sr25519Keyring::Charlie];
+			insert_session_info(1, &validators);
+			let candidate_hash = CandidateHash(sp_core::H256::repeat_byte(1));
+
+			Slash::punish_against_valid(1, candidate_hash, vec![ValidatorIndex(2)]);
+			Slash::punish_for_invalid(
+				1,
+				candidate_hash,
+				vec![ValidatorIndex(0), ValidatorIndex(1)],
+			);
+			// `AgainstValid` never overrides `ForInvalid`.
+			Slash::punish_against_valid(1, candidate_hash, vec![ValidatorIndex(2)]);
+			// Unknown validators and sessions are ignored.
+			Slash::punish_for_invalid(1, candidate_hash, vec![ValidatorIndex(7)]);
+			Slash::punish_for_invalid(2, candidate_hash, vec![ValidatorIndex(0)]);
+
+			let pending = Pallet::<Test>::unapplied_slashes();
+			assert_eq!(pending.len(), 1);
+			let (session, hash, slashes) = &pending[0];
+			assert_eq!((*session, *hash), (1, candidate_hash));
+			assert_eq!(slashes.kind, SlashingOffenceKind::ForInvalid);
+			assert_eq!(
+				slashes.keys.keys().cloned().collect::<Vec<_>>(),
+				vec![ValidatorIndex(0), ValidatorIndex(1)],
+			);
+			assert_eq!(slashes.keys[&ValidatorIndex(1)], Sr25519Keyring::Bob.public().into());
+		});
+	}
+
+	#[test]
+	fn pending_slashes_are_pruned_after_dispute_period() {
+		new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+			let dispute_period = crate::configuration::Pallet::<Test>::config().dispute_period;
+			insert_session_info(1, &[Sr25519Keyring::Alice]);
+			let candidate_hash = CandidateHash(sp_core::H256::repeat_byte(1));
+
+			Slash::punish_for_invalid(1, candidate_hash, vec![ValidatorIndex(0)]);
+
+			Slash::initializer_on_new_session(dispute_period + 1);
+			assert_eq!(Pallet::<Test>::unapplied_slashes().len(), 1);
+
+			Slash::initializer_on_new_session(dispute_period + 2);
+			assert!(Pallet::<Test>::unapplied_slashes().is_empty());
+		});
+	}
+
+	#[test]
+	fn dispute_lost_reports_are_checked() {
+		new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+			use Sr25519Keyring::{Alice, Bob, Charlie};
+
+			insert_session_info(1, &[Alice, Bob, Charlie]);
+			let candidate_hash = CandidateHash(sp_core::H256::repeat_byte(1));
+			Slash::punish_for_invalid(
+				1,
+				candidate_hash,
+				vec![ValidatorIndex(0), ValidatorIndex(1)],
+			);
+
+			let dispute_proof = |kind, validator_index, validator: Sr25519Keyring| {
+				Box::new(DisputeProof {
+					time_slot: DisputesTimeSlot::new(1, candidate_hash),
+					kind,
+					validator_index: ValidatorIndex(validator_index),
+					validator_id: validator.public().into(),
+				})
+			};
+			let for_invalid = |validator_index, validator| {
+				dispute_proof(SlashingOffenceKind::ForInvalid, validator_index, validator)
+			};
+			let key_owner_proof = |session, validator: Sr25519Keyring| {
+				let validator_id: ValidatorId = validator.public().into();
+				MembershipProof {
+					session,
+					trie_nodes: vec![validator_id.encode()],
+					validator_count: 3,
+				}
+			};
+			let report = |dispute_proof, key_owner_proof| {
+				Pallet::<Test>::report_dispute_lost_unsigned(
+					Origin::none(),
+					dispute_proof,
+					key_owner_proof,
+				)
+			};
+
+			// Key ownership proven in another session.
+			assert_noop!(
+				report(for_invalid(0, Alice), key_owner_proof(2, Alice)),
+				Error::<Test>::InvalidSessionIndex,
+			);
+			assert_eq!(
+				Pallet::<Test>::pre_dispatch(&Call::report_dispute_lost_unsigned {
+					dispute_proof: for_invalid(0, Alice),
+					key_owner_proof: key_owner_proof(2, Alice),
+				}),
+				Err(InvalidTransaction::BadProof.into()),
+			);
+			// Key ownership proven for another key.
+			assert_noop!(
+				report(for_invalid(0, Alice), key_owner_proof(1, Bob)),
+				Error::<Test>::InvalidKeyOwnershipProof,
+			);
+			// Another kind of offence.
+			assert_noop!(
+				report(
+					dispute_proof(SlashingOffenceKind::AgainstValid, 0, Alice),
+					key_owner_proof(1, Alice),
+				),
+				Error::<Test>::InvalidCandidateHash,
+			);
+			// Not a loser of the dispute.
+			assert_noop!(
+				report(for_invalid(2, Charlie), key_owner_proof(1, Charlie)),
+				Error::<Test>::InvalidValidatorIndex,
+			);
+			// The index of another loser.
+			assert_noop!(
+				report(for_invalid(1, Alice), key_owner_proof(1, Alice)),
+				Error::<Test>::ValidatorIndexIdMismatch,
+			);
+
+			assert_ok!(report(for_invalid(0, Alice), key_owner_proof(1, Alice)));
+			// Each slash can only be reported once.
+			assert_noop!(
+				report(for_invalid(0, Alice), key_owner_proof(1, Alice)),
+				Error::<Test>::InvalidValidatorIndex,
+			);
+			let pending = Pallet::<Test>::unapplied_slashes();
+			assert_eq!(pending.len(), 1);
+			assert_eq!(
+				pending[0].2.keys.keys().cloned().collect::<Vec<_>>(),
+				vec![ValidatorIndex(1)],
+			);
+
+			// Pending slashes are removed once all losers were reported.
+			assert_ok!(report(for_invalid(1, Bob), key_owner_proof(1, Bob)));
+			assert!(Pallet::<Test>::unapplied_slashes().is_empty());
+			assert_noop!(
+				report(for_invalid(1, Bob), key_owner_proof(1, Bob)),
+				Error::<Test>::InvalidCandidateHash,
+			);
+		});
+	}
+
+	#[test]
+	fn backing_misbehavior_reports_are_checked() {
+		use crate::mock::BACKING_OFFENCES;
+		use primitives::{
+			v1::{CompactStatement, SigningContext},
+			v2::slashing::BackingMisbehavior,
+		};
+		use Sr25519Keyring::{Alice, Bob};
+
+		new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+			insert_session_info(1, &[Alice, Bob]);
+			let time_slot =
+				BackingTimeSlot { session_index: 1, relay_parent: sp_core::H256::repeat_byte(2) };
+			let context = SigningContext { session_index: 1, parent_hash: time_slot.relay_parent };
+			let seconded = |byte| {
+				let candidate_hash = CandidateHash(sp_core::H256::repeat_byte(byte));
+				let payload = CompactStatement::Seconded(candidate_hash).signing_payload(&context);
+				(candidate_hash, Bob.sign(&payload).into())
+			};
+
+			let misbehavior_proof = |validator_index| {
+				Box::new(BackingMisbehaviorProof {
+					time_slot: time_slot.clone(),
+					validator_index: ValidatorIndex(validator_index),
+					misbehavior: BackingMisbehavior::MultipleCandidates {
+						first: seconded(1),
+						second: seconded(3),
+					},
+				})
+			};
+			let key_owner_proof = |session, validator: Sr25519Keyring| {
+				let validator_id: ValidatorId = validator.public().into();
+				MembershipProof {
+					session,
+					trie_nodes: vec![validator_id.encode()],
+					validator_count: 2,
+				}
+			};
+			let call = |misbehavior_proof, key_owner_proof| {
+				Call::report_backing_misbehavior_unsigned { misbehavior_proof, key_owner_proof }
+			};
+			let report = |misbehavior_proof, key_owner_proof| {
+				Pallet::<Test>::report_backing_misbehavior_unsigned(
+					Origin::none(),
+					misbehavior_proof,
+					key_owner_proof,
+				)
+			};
+
+			// Key ownership proven in another session.
+			assert_noop!(
+				report(misbehavior_proof(1), key_owner_proof(2, Bob)),
+				Error::<Test>::InvalidSessionIndex,
+			);
+			assert_eq!(
+				Pallet::<Test>::pre_dispatch(&call(misbehavior_proof(1), key_owner_proof(2, Bob))),
+				Err(InvalidTransaction::BadProof.into()),
+			);
+			// Key ownership proven for another key.
+			assert_noop!(
+				report(misbehavior_proof(1), key_owner_proof(1, Alice)),
+				Error::<Test>::InvalidKeyOwnershipProof,
+			);
+			// Statements signed by another validator.
+			assert_noop!(
+				report(misbehavior_proof(0), key_owner_proof(1, Alice)),
+				Error::<Test>::InvalidBackingMisbehaviorProof,
+			);
+			assert_eq!(
+				Pallet::<Test>::pre_dispatch(&call(misbehavior_proof(0), key_owner_proof(1, Bob))),
+				Err(InvalidTransaction::BadProof.into()),
+			);
+
+			// Only local reports are accepted into the pool.
+			let valid_call = call(misbehavior_proof(1), key_owner_proof(1, Bob));
+			assert_eq!(
+				Pallet::<Test>::validate_unsigned(TransactionSource::External, &valid_call),
+				InvalidTransaction::Call.into(),
+			);
+			assert!(
+				Pallet::<Test>::validate_unsigned(TransactionSource::Local, &valid_call).is_ok()
+			);
+			assert_eq!(Pallet::<Test>::pre_dispatch(&valid_call), Ok(()));
+
+			assert_ok!(report(misbehavior_proof(1), key_owner_proof(1, Bob)));
+			BACKING_OFFENCES.with(|offences| {
+				let offences = offences.borrow();
+				assert_eq!(offences.len(), 1);
+				assert_eq!(offences[0].time_slot, time_slot);
+				assert_eq!(offences[0].offenders, vec![ValidatorId::from(Bob.public())]);
+				assert_eq!(offences[0].validator_set_count, 2);
+			});
+
+			// Each offence can only be reported once.
+			assert_noop!(
+				report(misbehavior_proof(1), key_owner_proof(1, Bob)),
+				Error::<Test>::DuplicateSlashingReport,
+			);
+			assert_eq!(
+				Pallet::<Test>::validate_unsigned(TransactionSource::Local, &valid_call),
+				InvalidTransaction::Stale.into(),
+			);
+			assert_eq!(
+				Pallet::<Test>::pre_dispatch(&valid_call),
+				Err(InvalidTransaction::Stale.into()),
+			);
+		});
+	}
+
+	#[test]
+	fn backing_misbehavior_proofs_are_checked() {
+		use primitives::{
+			v1::{CompactStatement, SigningContext},
+			v2::slashing::BackingMisbehavior,
+		};
+
+		new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+			insert_session_info(1, &[Sr25519Keyring::Alice, Sr25519Keyring::Bob]);
+			let relay_parent = sp_core::H256::repeat_byte(2);
+			let context = SigningContext { session_index: 1, parent_hash: relay_parent };
+			let seconded = |keyring: Sr25519Keyring, byte| {
+				let candidate_hash = CandidateHash(sp_core::H256::repeat_byte(byte));
+				let payload = CompactStatement::Seconded(candidate_hash).signing_payload(&context);
+				(candidate_hash, keyring.sign(&payload).into())
+			};
+
+			let proof = |validator_index, misbehavior| BackingMisbehaviorProof {
+				time_slot: BackingTimeSlot { session_index: 1, relay_parent },
+				validator_index: ValidatorIndex(validator_index),
+				misbehavior,
+			};
+			let multiple_candidates = BackingMisbehavior::MultipleCandidates {
+				first: seconded(Sr25519Keyring::Bob, 1),
+				second: seconded(Sr25519Keyring::Bob, 3),
+			};
+
+			let check = |p| Pallet::<Test>::check_backing_misbehavior(&p);
+
+			assert_eq!(
+				check(proof(1, multiple_candidates.clone())).ok(),
+				Some(Sr25519Keyring::Bob.public().into()),
+			);
+			// Signed by another validator.
+			assert!(matches!(
+				check(proof(0, multiple_candidates.clone())),
+				Err(Error::<Test>::InvalidBackingMisbehaviorProof),
+			));
+			assert!(matches!(
+				check(proof(2, multiple_candidates)),
+				Err(Error::<Test>::InvalidValidatorIndex),
+			));
+			// Seconding the same candidate twice is no misbehavior.
+			let same_candidate = BackingMisbehavior::MultipleCandidates {
+				first: seconded(Sr25519Keyring::Bob, 1),
+				second: seconded(Sr25519Keyring::Bob, 1),
+			};
+			assert!(matches!(
+				check(proof(1, same_candidate)),
+				Err(Error::<Test>::InvalidBackingMisbehaviorProof),
+			));
+		});
+	}
+}
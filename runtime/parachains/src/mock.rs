@@ -17,8 +17,10 @@
 //! Mocks for all the traits.
 
 use crate::{
-	configuration, disputes, dmp, hrmp, inclusion, initializer, origin, paras, paras_inherent,
-	scheduler, session_info, shared,
+	configuration,
+	disputes::{self, slashing::{BackingMisbehaviorOffence, HandleReports, SlashingOffence}},
+	dmp, hrmp, inclusion, initializer, origin, paras, paras_inherent, scheduler, session_info,
+	shared,
 	ump::{self, MessageId, UmpSink},
 	ParaId,
 };
@@ -29,18 +31,25 @@ use frame_support::{
 	weights::Weight,
 };
 use frame_support_test::TestRandomness;
-use parity_scale_codec::Decode;
-use primitives::v1::{
-	AuthorityDiscoveryId, Balance, BlockNumber, Header, Moment, SessionIndex, UpwardMessage,
-	ValidatorIndex,
+use parity_scale_codec::{Decode, Encode};
+use primitives::{
+	v1::{
+		AuthorityDiscoveryId, Balance, BlockNumber, CandidateHash, Header, Moment, SessionIndex,
+		UpwardMessage, ValidatorId, ValidatorIndex,
+	},
+	v2::slashing::{
+		BackingMisbehaviorProof, BackingTimeSlot, DisputeProof, DisputesTimeSlot,
+		SlashingOffenceKind,
+	},
 };
 use sp_core::H256;
 use sp_io::TestExternalities;
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup},
 	transaction_validity::TransactionPriority,
-	KeyTypeId, Permill,
+	DispatchResult, KeyTypeId, Permill,
 };
+use sp_staking::offence::OffenceError;
 use std::{cell::RefCell, collections::HashMap};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -67,6 +76,7 @@ frame_support::construct_runtime!(
 		ParachainsOrigin: origin,
 		SessionInfo: session_info,
 		Disputes: disputes,
+		ParasSlashing: disputes::slashing,
 		Babe: pallet_babe,
 	}
 );
@@ -242,10 +252,108 @@ impl crate::hrmp::Config for Test {
 impl crate::disputes::Config for Test {
 	type Event = Event;
 	type RewardValidators = Self;
-	type PunishValidators = Self;
+	type SlashingHandler = Self;
 	type WeightInfo = crate::disputes::TestWeightInfo;
 }
 
+parameter_types! {
+	pub const SlashingUnsignedPriority: TransactionPriority = TransactionPriority::max_value();
+}
+
+/// Proves ownership of a validator key by a membership proof containing just the encoded key.
+pub struct TestKeyOwnerProofSystem;
+
+impl KeyOwnerProofSystem<(KeyTypeId, ValidatorId)> for TestKeyOwnerProofSystem {
+	type Proof = sp_session::MembershipProof;
+	type IdentificationTuple = ValidatorId;
+
+	fn prove(_key: (KeyTypeId, ValidatorId)) -> Option<Self::Proof> {
+		None
+	}
+
+	fn check_proof(
+		(_, validator_id): (KeyTypeId, ValidatorId),
+		proof: Self::Proof,
+	) -> Option<Self::IdentificationTuple> {
+		(proof.trie_nodes == vec![validator_id.encode()]).then(|| validator_id)
+	}
+}
+
+thread_local! {
+	pub static BACKING_OFFENCES: RefCell<Vec<BackingMisbehaviorOffence<ValidatorId>>> =
+		RefCell::new(Vec::new());
+}
+
+/// Handles slashing reports like `()`, except for recording reported backing offences.
+pub struct TestHandleReports;
+
+impl HandleReports<Test> for TestHandleReports {
+	type ReportLongevity = ();
+
+	fn report_offence(
+		kind: SlashingOffenceKind,
+		offence: SlashingOffence<ValidatorId>,
+	) -> Result<(), OffenceError> {
+		<() as HandleReports<Test>>::report_offence(kind, offence)
+	}
+
+	fn is_known_offence(
+		kind: SlashingOffenceKind,
+		offenders: &[ValidatorId],
+		time_slot: &DisputesTimeSlot,
+	) -> bool {
+		<() as HandleReports<Test>>::is_known_offence(kind, offenders, time_slot)
+	}
+
+	fn submit_unsigned_slashing_report(
+		dispute_proof: DisputeProof,
+		key_owner_proof: sp_session::MembershipProof,
+	) -> DispatchResult {
+		<() as HandleReports<Test>>::submit_unsigned_slashing_report(dispute_proof, key_owner_proof)
+	}
+
+	fn report_backing_offence(
+		offence: BackingMisbehaviorOffence<ValidatorId>,
+	) -> Result<(), OffenceError> {
+		if Self::is_known_backing_offence(&offence.offenders, &offence.time_slot) {
+			return Err(OffenceError::DuplicateReport)
+		}
+		BACKING_OFFENCES.with(|o| o.borrow_mut().push(offence));
+		Ok(())
+	}
+
+	fn is_known_backing_offence(offenders: &[ValidatorId], time_slot: &BackingTimeSlot) -> bool {
+		BACKING_OFFENCES.with(|o| {
+			o.borrow()
+				.iter()
+				.any(|offence| &offence.time_slot == time_slot && offence.offenders == offenders)
+		})
+	}
+
+	fn submit_unsigned_backing_report(
+		misbehavior_proof: BackingMisbehaviorProof,
+		key_owner_proof: sp_session::MembershipProof,
+	) -> DispatchResult {
+		<() as HandleReports<Test>>::submit_unsigned_backing_report(
+			misbehavior_proof,
+			key_owner_proof,
+		)
+	}
+}
+
+impl crate::disputes::slashing::Config for Test {
+	type KeyOwnerProof =
+		<Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, ValidatorId)>>::Proof;
+	type KeyOwnerIdentification = <Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(
+		KeyTypeId,
+		ValidatorId,
+	)>>::IdentificationTuple;
+	type KeyOwnerProofSystem = TestKeyOwnerProofSystem;
+	type HandleReports = TestHandleReports;
+	type UnsignedPriority = SlashingUnsignedPriority;
+	type WeightInfo = crate::disputes::slashing::TestWeightInfo;
+}
+
 thread_local! {
 	pub static REWARD_VALIDATORS: RefCell<Vec<(SessionIndex, Vec<ValidatorIndex>)>> = RefCell::new(Vec::new());
 	pub static PUNISH_VALIDATORS_FOR: RefCell<Vec<(SessionIndex, Vec<ValidatorIndex>)>> = RefCell::new(Vec::new());
@@ -262,30 +370,40 @@ impl crate::disputes::RewardValidators for Test {
 	}
 }
 
-impl crate::disputes::PunishValidators for Test {
+impl crate::disputes::SlashingHandler<BlockNumber> for Test {
 	fn punish_for_invalid(
 		session: SessionIndex,
-		validators: impl IntoIterator<Item = ValidatorIndex>,
+		_: CandidateHash,
+		losers: impl IntoIterator<Item = ValidatorIndex>,
 	) {
-		PUNISH_VALIDATORS_FOR
-			.with(|r| r.borrow_mut().push((session, validators.into_iter().collect())))
+		PUNISH_VALIDATORS_FOR.with(|r| r.borrow_mut().push((session, losers.into_iter().collect())))
 	}
 
 	fn punish_against_valid(
 		session: SessionIndex,
-		validators: impl IntoIterator<Item = ValidatorIndex>,
+		_: CandidateHash,
+		losers: impl IntoIterator<Item = ValidatorIndex>,
 	) {
 		PUNISH_VALIDATORS_AGAINST
-			.with(|r| r.borrow_mut().push((session, validators.into_iter().collect())))
+			.with(|r| r.borrow_mut().push((session, losers.into_iter().collect())))
 	}
 
 	fn punish_inconclusive(
 		session: SessionIndex,
+		_: CandidateHash,
 		validators: impl IntoIterator<Item = ValidatorIndex>,
 	) {
 		PUNISH_VALIDATORS_INCONCLUSIVE
 			.with(|r| r.borrow_mut().push((session, validators.into_iter().collect())))
 	}
+
+	fn initializer_initialize(_now: BlockNumber) -> Weight {
+		0
+	}
+
+	fn initializer_finalize() {}
+
+	fn initializer_on_new_session(_: SessionIndex) {}
 }
 
 impl crate::scheduler::Config for Test {}
@@ -18,18 +18,18 @@
 //! functions.
 
 use crate::{
-	configuration, dmp, hrmp, inclusion, initializer, paras, paras_inherent, scheduler,
+	configuration, disputes, dmp, hrmp, inclusion, initializer, paras, paras_inherent, scheduler,
 	session_info, shared,
 };
 use primitives::{
 	v1::{
-		AuthorityDiscoveryId, CandidateEvent, CommittedCandidateReceipt, CoreIndex, CoreOccupied,
-		CoreState, GroupIndex, GroupRotationInfo, Hash, Id as ParaId, InboundDownwardMessage,
-		InboundHrmpMessage, OccupiedCore, OccupiedCoreAssumption, PersistedValidationData,
-		ScheduledCore, ScrapedOnChainVotes, SessionIndex, ValidationCode, ValidationCodeHash,
-		ValidatorId, ValidatorIndex, ValidatorSignature,
+		AuthorityDiscoveryId, CandidateEvent, CandidateHash, CommittedCandidateReceipt, CoreIndex,
+		CoreOccupied, CoreState, GroupIndex, GroupRotationInfo, Hash, Id as ParaId,
+		InboundDownwardMessage, InboundHrmpMessage, OccupiedCore, OccupiedCoreAssumption,
+		PersistedValidationData, ScheduledCore, ScrapedOnChainVotes, SessionIndex, ValidationCode,
+		ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 	},
-	v2::{PvfCheckStatement, SessionInfo},
+	v2::{slashing, InherentWeightInfo, PvfCheckStatement, SessionInfo},
 };
 use sp_runtime::traits::One;
 use sp_std::{collections::btree_map::BTreeMap, prelude::*};
@@ -402,3 +402,61 @@ where
 		<paras::Pallet<T>>::current_code_hash(&para_id)
 	})
 }
+
+/// Returns the pending slashes of lost disputes still waiting for a key ownership proof. See
+/// [`disputes::slashing::Pallet::unapplied_slashes`].
+pub fn unapplied_slashes<T: disputes::slashing::Config>(
+) -> Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)> {
+	<disputes::slashing::Pallet<T>>::unapplied_slashes()
+}
+
+/// Submits an unsigned extrinsic to report a lost dispute. See
+/// [`disputes::slashing::Pallet::submit_unsigned_slashing_report`].
+pub fn submit_unsigned_slashing_report<T: disputes::slashing::Config>(
+	dispute_proof: slashing::DisputeProof,
+	key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+) -> Option<()> {
+	let key_ownership_proof = key_ownership_proof.decode()?;
+
+	<disputes::slashing::Pallet<T>>::submit_unsigned_slashing_report(
+		dispute_proof,
+		key_ownership_proof,
+	)
+}
+
+/// Submits an unsigned extrinsic to report conflicting backing statements. See
+/// [`disputes::slashing::Pallet::submit_unsigned_backing_report`].
+pub fn submit_unsigned_backing_report<T: disputes::slashing::Config>(
+	misbehavior_proof: slashing::BackingMisbehaviorProof,
+	key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+) -> Option<()> {
+	let key_ownership_proof = key_ownership_proof.decode()?;
+
+	<disputes::slashing::Pallet<T>>::submit_unsigned_backing_report(
+		misbehavior_proof,
+		key_ownership_proof,
+	)
+}
+
+/// Returns the weights charged for the components of the parachains inherent, as given by the
+/// [`paras_inherent::WeightInfo`] of the runtime.
+pub fn inherent_weight_info<T: paras_inherent::Config>() -> InherentWeightInfo {
+	use frame_support::traits::Get;
+	use paras_inherent::WeightInfo;
+	type Weights<T> = <T as paras_inherent::Config>::WeightInfo;
+
+	let backed_candidate_base = Weights::<T>::enter_backed_candidates_variable(0);
+	let dispute_statement_set_base = Weights::<T>::enter_variable_disputes(0);
+
+	InherentWeightInfo {
+		max_block_weight: <T as frame_system::Config>::BlockWeights::get().max_block,
+		bitfield: Weights::<T>::enter_bitfields(),
+		backed_candidate_base,
+		backed_candidate_per_vote: Weights::<T>::enter_backed_candidates_variable(1)
+			.saturating_sub(backed_candidate_base),
+		backed_candidate_code_upgrade: Weights::<T>::enter_backed_candidate_code_upgrade(),
+		dispute_statement_set_base,
+		dispute_statement: Weights::<T>::enter_variable_disputes(1)
+			.saturating_sub(dispute_statement_set_base),
+	}
+}
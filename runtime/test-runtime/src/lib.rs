@@ -46,13 +46,13 @@ use pallet_transaction_payment::{FeeDetails, RuntimeDispatchInfo};
 use polkadot_runtime_parachains::reward_points::RewardValidatorsWithEraPoints;
 use primitives::{
 	v1::{
-		AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent, CommittedCandidateReceipt,
-		CoreState, GroupRotationInfo, Hash as HashT, Id as ParaId, InboundDownwardMessage,
-		InboundHrmpMessage, Moment, Nonce, OccupiedCoreAssumption, PersistedValidationData,
-		ScrapedOnChainVotes, Signature, ValidationCode, ValidationCodeHash, ValidatorId,
-		ValidatorIndex,
+		AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent, CandidateHash,
+		CommittedCandidateReceipt, CoreState, GroupRotationInfo, Hash as HashT, Id as ParaId,
+		InboundDownwardMessage, InboundHrmpMessage, Moment, Nonce, OccupiedCoreAssumption,
+		PersistedValidationData, ScrapedOnChainVotes, Signature, ValidationCode,
+		ValidationCodeHash, ValidatorId, ValidatorIndex,
 	},
-	v2::SessionInfo as SessionInfoData,
+	v2::{slashing, InherentWeightInfo, SessionInfo as SessionInfoData},
 };
 use runtime_common::{
 	claims, paras_sudo_wrapper, BlockHashCount, BlockLength, BlockWeights, SlowAdjustingFeeUpdate,
@@ -478,7 +478,7 @@ impl parachains_inclusion::Config for Runtime {
 impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
-	type PunishValidators = ();
+	type SlashingHandler = ();
 	type WeightInfo = parachains_disputes::TestWeightInfo;
 }
 
@@ -895,6 +895,34 @@ sp_api::impl_runtime_apis! {
 		{
 			runtime_impl::validation_code_hash::<Runtime>(para_id, assumption)
 		}
+
+		fn unapplied_slashes() -> Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)> {
+			Vec::new()
+		}
+
+		fn key_ownership_proof(
+			_validator_id: ValidatorId,
+		) -> Option<slashing::OpaqueKeyOwnershipProof> {
+			None
+		}
+
+		fn submit_report_dispute_lost(
+			_dispute_proof: slashing::DisputeProof,
+			_key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			None
+		}
+
+		fn inherent_weight_info() -> InherentWeightInfo {
+			runtime_impl::inherent_weight_info::<Runtime>()
+		}
+
+		fn submit_report_backing_misbehavior(
+			_misbehavior_proof: slashing::BackingMisbehaviorProof,
+			_key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			None
+		}
 	}
 
 	impl beefy_primitives::BeefyApi<Block> for Runtime {
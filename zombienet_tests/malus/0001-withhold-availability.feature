@@ -0,0 +1,23 @@
+Description: Withholding availability data
+Network: ./0001-withhold-availability.toml
+Creds: config
+
+
+alice: is up
+bob: is up
+charlie: is up
+dave: is up
+alice: reports node_roles is 4
+bob: reports node_roles is 4
+charlie: reports node_roles is 4
+alice: reports block height is at least 2 within 15 seconds
+alice: reports peers count is at least 3
+bob: reports peers count is at least 3
+charlie: reports peers count is at least 3
+alice: reports parachain_fetch_retries_total is at least 1 within 250 seconds
+bob: reports parachain_fetch_retries_total is at least 1 within 90 seconds
+charlie: reports parachain_fetch_retries_total is at least 1 within 90 seconds
+alice: reports parachain_peer_reputation_costs_total{reason="Validator did not serve a chunk of a candidate it backed"} is at least 1 within 250 seconds
+bob: reports parachain_peer_reputation_costs_total{reason="Validator did not serve a chunk of a candidate it backed"} is at least 1 within 90 seconds
+charlie: reports parachain_peer_reputation_costs_total{reason="Validator did not serve a chunk of a candidate it backed"} is at least 1 within 90 seconds
+alice: parachain 100 block height is at least 10 within 200 seconds
@@ -0,0 +1,20 @@
+Description: Spamming approvals with bad signatures
+Network: ./0004-spam-bad-approvals.toml
+Creds: config
+
+
+alice: is up
+bob: is up
+charlie: is up
+dave: is up
+alice: reports node_roles is 4
+bob: reports node_roles is 4
+charlie: reports node_roles is 4
+alice: reports block height is at least 2 within 15 seconds
+alice: reports peers count is at least 3
+bob: reports peers count is at least 3
+charlie: reports peers count is at least 3
+alice: reports parachain_peer_reputation_costs_total{reason="The vote was bad"} is at least 1 within 250 seconds
+bob: reports parachain_peer_reputation_costs_total{reason="The vote was bad"} is at least 1 within 90 seconds
+charlie: reports parachain_peer_reputation_costs_total{reason="The vote was bad"} is at least 1 within 90 seconds
+alice: reports parachain_approvals_no_shows_total is at least 1 within 250 seconds